@@ -1,31 +1,42 @@
 //! Benchmarking application
 
-#![cfg(unix)]
-
 use clap::Parser;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::env::consts::EXE_EXTENSION;
 use std::ffi::OsStr;
+use std::fmt::Write as _;
+use std::fs;
 use std::num::NonZero;
-use std::path::{Path, PathBuf};
+use std::path::{self, Path, PathBuf};
 use std::process::Command;
 use std::time::Instant;
-use std::{array, fs};
 use sts_lib::bitvec::BitVec;
 use sts_lib::test_runner::run_all_tests;
 use sts_lib::tests::approximate_entropy::ApproximateEntropyTestArg;
+use sts_lib::tests::autocorrelation::AutocorrelationTestArg;
+use sts_lib::tests::binary_matrix_rank::BinaryMatrixRankTestArg;
 use sts_lib::tests::frequency_block::FrequencyBlockTestArg;
 use sts_lib::tests::linear_complexity::LinearComplexityTestArg;
+use sts_lib::tests::longest_run_of_ones::LongestRunOfOnesTestArg;
+use sts_lib::tests::random_excursions::RandomExcursionsTestArg;
+use sts_lib::tests::random_excursions_variant::RandomExcursionsVariantTestArg;
 use sts_lib::tests::serial::SerialTestArg;
+use sts_lib::tests::spectral_dft::{PeakThreshold, SpectralDftTestArg};
 use sts_lib::tests::template_matching::non_overlapping::NonOverlappingTemplateTestArgs;
 use sts_lib::tests::template_matching::overlapping::OverlappingTemplateTestArgs;
 use sts_lib::{Test, TestArgs};
 
-// Count of test files
-const COUNT_TEST_FILES: usize = 5;
-// The amount of runs to do for each test file
-const COUNT_RUNS_PER_FILE: usize = 100;
+// The test files used from the test files directory, if no files are given.
+const DEFAULT_TEST_FILES: [&str; 5] = [
+    "e.1e6.bin",
+    "pi.1e6.bin",
+    "sha1.1e6.bin",
+    "sqrt2.1e6.bin",
+    "sqrt3.1e6.bin",
+];
 
+// The measured times in ms of each test: (rust, c)
 type StatisticStorage = HashMap<Test, (Vec<f64>, Vec<f64>)>;
 
 /// Command line arguments
@@ -35,10 +46,100 @@ struct CmdArgs {
     /// The path to the modified and built 'assess' binary.
     #[arg(short = 'b', long = "bin", value_name = "PATH_TO_BUILT_ASSESS_BINARY")]
     bin_path: Option<PathBuf>,
-    /// The path to the directory containing the test files.
-    /// From the repository root: 'sts-lib/test-files'.
-    #[arg(short = 'd', long = "dir", value_name = "PATH_TO_TEST_FILES_DIRECTORY")]
-    test_files_dir: PathBuf,
+    /// The path to the directory containing the test files e.1e6.bin, pi.1e6.bin, sha1.1e6.bin,
+    /// sqrt2.1e6.bin and sqrt3.1e6.bin. From the repository root: 'sts-lib/test-files'.
+    #[arg(
+        short = 'd',
+        long = "dir",
+        value_name = "PATH_TO_TEST_FILES_DIRECTORY",
+        required_unless_present = "files",
+        conflicts_with = "files"
+    )]
+    test_files_dir: Option<PathBuf>,
+    /// The binary test files to use instead of the files in the test files directory. Each file
+    /// is tested as a whole.
+    #[arg(long, num_args = 1..)]
+    files: Vec<PathBuf>,
+    /// The count of runs for each test file.
+    #[arg(short, long, default_value = "100")]
+    runs: NonZero<usize>,
+    /// Additionally save the statistics to the given path, for automated performance tracking.
+    /// Files ending with ".json" are written as JSON, all other files as CSV with ';' delimiters.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+/// The statistics of the measured times of one test, in ms.
+#[derive(Debug, Copy, Clone, Serialize)]
+struct Statistics {
+    runs: usize,
+    mean: f64,
+    std_dev: f64,
+    min: f64,
+    median: f64,
+    p90: f64,
+    p99: f64,
+    max: f64,
+}
+
+impl Statistics {
+    /// Calculates the statistics of the given times, `None` if there are none.
+    fn new(times: &[f64]) -> Option<Self> {
+        if times.is_empty() {
+            return None;
+        }
+
+        let mut sorted = times.to_vec();
+        sorted.sort_unstable_by(f64::total_cmp);
+
+        let runs = sorted.len();
+        let mean = sorted.iter().sum::<f64>() / (runs as f64);
+        // sample standard deviation, 0 for a single run
+        let std_dev = if runs > 1 {
+            let sum_squares = sorted.iter().map(|t| (t - mean).powi(2)).sum::<f64>();
+            (sum_squares / ((runs - 1) as f64)).sqrt()
+        } else {
+            0.0
+        };
+
+        // nearest-rank method
+        let percentile = |p: f64| sorted[((p / 100.0 * runs as f64).ceil() as usize).max(1) - 1];
+
+        Some(Self {
+            runs,
+            mean,
+            std_dev,
+            min: sorted[0],
+            median: percentile(50.0),
+            p90: percentile(90.0),
+            p99: percentile(99.0),
+            max: sorted[runs - 1],
+        })
+    }
+}
+
+/// The statistics of one test, for both implementations.
+#[derive(Debug, Clone, Serialize)]
+struct TestStatistics {
+    test: String,
+    rust: Option<Statistics>,
+    reference: Option<Statistics>,
+}
+
+/// The statistics of all tests for one test file, or over all files.
+#[derive(Debug, Clone, Serialize)]
+struct FileStatistics {
+    /// The path of the test file, `None` for the statistics over all files.
+    file: Option<PathBuf>,
+    tests: Vec<TestStatistics>,
+}
+
+/// The output file, see [CmdArgs::output].
+#[derive(Debug, Clone, Serialize)]
+struct BenchmarkOutput {
+    runs_per_file: usize,
+    files: Vec<FileStatistics>,
+    overall: FileStatistics,
 }
 
 /// To deserialize the output of the reference implementation.
@@ -76,28 +177,41 @@ fn map_c_name_to_test(name: String) -> Option<Test> {
     }
 }
 
-/// Get the average of the given list
-fn average(list: &[f64]) -> Option<f64> {
-    if !list.is_empty() {
-        Some(list.iter().sum::<f64>() / (list.len() as f64))
-    } else {
-        None
-    }
+/// Calculates the statistics of each test, sorted by the test.
+fn test_statistics(statistics: &StatisticStorage) -> Vec<TestStatistics> {
+    let mut statistics = statistics.iter().collect::<Vec<_>>();
+    statistics.sort_unstable_by_key(|(test, _)| **test as u8);
+
+    statistics
+        .into_iter()
+        .map(|(test, (rust, c))| TestStatistics {
+            test: test.to_string(),
+            rust: Statistics::new(rust),
+            reference: Statistics::new(c),
+        })
+        .collect()
 }
 
 /// Print the given statistics
-fn print_statistics(test: Test, rust_avg: Option<f64>, c_avg: Option<f64>) {
+fn print_statistics(statistics: &TestStatistics) {
+    let TestStatistics {
+        test,
+        rust,
+        reference,
+    } = statistics;
+
     println!("\tTest {test}");
     // print corresponding line only if necessary
-    if let Some(rust_avg) = rust_avg {
-        println!("\t\tAverage time of this implementation:          {rust_avg:.6} ms");
+    if let Some(rust) = rust {
+        print_times("this implementation:         ", rust);
     }
-    if let Some(c_avg) = c_avg {
-        println!("\t\tAverage time of the reference implementation: {c_avg:.6} ms");
+    if let Some(reference) = reference {
+        print_times("the reference implementation:", reference);
     }
 
     // print diff only if both averages are given
-    if let (Some(rust_avg), Some(c_avg)) = (rust_avg, c_avg) {
+    if let (Some(rust), Some(reference)) = (rust, reference) {
+        let (rust_avg, c_avg) = (rust.mean, reference.mean);
         let diff = 100.0 * rust_avg / c_avg;
         let faster_or_slower = if diff <= 100.0 { "faster" } else { "SLOWER" };
 
@@ -108,10 +222,46 @@ fn print_statistics(test: Test, rust_avg: Option<f64>, c_avg: Option<f64>) {
     }
 }
 
+/// Print the times of one implementation.
+fn print_times(implementation: &str, statistics: &Statistics) {
+    let Statistics {
+        mean,
+        std_dev,
+        median,
+        p90,
+        ..
+    } = statistics;
+    println!(
+        "\t\tAverage time of {implementation} {mean:.6} ms (std. dev.: {std_dev:.6} ms, \
+        median: {median:.6} ms, p90: {p90:.6} ms)"
+    );
+}
+
+/// Adds the platform-specific extension of executables (".exe" on Windows) to the given path,
+/// if the path doesn't exist as given. This allows to pass the same path on all platforms.
+fn resolve_executable(exe: PathBuf) -> PathBuf {
+    if exe.exists() || EXE_EXTENSION.is_empty() || exe.extension().is_some() {
+        return exe;
+    }
+
+    let with_extension = exe.with_extension(EXE_EXTENSION);
+    if with_extension.exists() {
+        with_extension
+    } else {
+        exe
+    }
+}
+
 /// Use the C implementation
-fn test_c_imp(test_file: &Path, executable: &Path, statistics: &mut StatisticStorage) {
+fn test_c_imp(
+    test_file: &Path,
+    len_bit: usize,
+    executable: &Path,
+    statistics: &mut StatisticStorage,
+) {
+    let len_bit = len_bit.to_string();
     let output = Command::new(executable)
-        .args([test_file.as_os_str(), OsStr::new("1000000")])
+        .args([test_file.as_os_str(), OsStr::new(&len_bit)])
         .current_dir(executable.parent().unwrap())
         .output()
         // just crash if there is a problem executing the reference implementation.
@@ -134,15 +284,7 @@ fn test_c_imp(test_file: &Path, executable: &Path, statistics: &mut StatisticSto
 }
 
 /// Use the Rust implementation
-fn test_rust_imp(test_file: &Path, test_args: TestArgs, statistics: &mut StatisticStorage) {
-    let raw_data = fs::read(test_file).unwrap();
-    let data = BitVec::from(raw_data);
-    if data.len_bit() != 1_000_000 {
-        panic!(
-            "Invalid test file length. Expected 1000000 bits. Got: {} bits. Aborting...",
-            data.len_bit()
-        );
-    }
+fn test_rust_imp(data: &BitVec, test_args: TestArgs, statistics: &mut StatisticStorage) {
     let mut results = run_all_tests(data, test_args).unwrap();
 
     loop {
@@ -164,16 +306,24 @@ fn main() {
     let args = CmdArgs::parse();
 
     // Build paths to the test files.
-    // If the path cannot be canonicalized, something went very wrong...
-    let test_files_dir = args.test_files_dir.canonicalize().unwrap();
-
-    let test_files: [PathBuf; COUNT_TEST_FILES] = [
-        test_files_dir.join("e.1e6.bin"),
-        test_files_dir.join("pi.1e6.bin"),
-        test_files_dir.join("sha1.1e6.bin"),
-        test_files_dir.join("sqrt2.1e6.bin"),
-        test_files_dir.join("sqrt3.1e6.bin"),
-    ];
+    // The paths must be absolute, since the reference implementation is run in its own directory.
+    // Not canonicalized, because Windows would return verbatim paths ("\\?\C:\..."), which are
+    // not understood by the C runtime of the reference implementation.
+    // If the path cannot be made absolute, something went very wrong...
+    let test_files = match args.test_files_dir {
+        Some(dir) => {
+            let dir = path::absolute(dir).unwrap();
+            DEFAULT_TEST_FILES
+                .iter()
+                .map(|file| dir.join(file))
+                .collect::<Vec<_>>()
+        }
+        None => args
+            .files
+            .into_iter()
+            .map(|file| path::absolute(file).unwrap())
+            .collect(),
+    };
 
     // check existence of files
     for file in &test_files {
@@ -190,7 +340,9 @@ fn main() {
     }
 
     // check existence of binary
-    let executable = args.bin_path;
+    let executable = args
+        .bin_path
+        .map(|exe| resolve_executable(path::absolute(exe).unwrap()));
     if let Some(exe) = &executable {
         if !exe.exists() {
             panic!("Executable {} does not exist! Aborting..", exe.display());
@@ -206,88 +358,118 @@ fn main() {
     // test arguments for the rust version
     let test_args = TestArgs {
         frequency_block: FrequencyBlockTestArg::Manual(NonZero::new(128).unwrap()),
+        longest_run_of_ones: LongestRunOfOnesTestArg::ChooseAutomatically,
+        binary_matrix_rank: BinaryMatrixRankTestArg::try_new(32, 32).unwrap(),
+        spectral_dft: SpectralDftTestArg::new(PeakThreshold::KimUmeno),
         non_overlapping_template: NonOverlappingTemplateTestArgs::new_const::<9, 8>(),
-        overlapping_template: OverlappingTemplateTestArgs::new_nist_behaviour(9).unwrap(),
+        overlapping_template: OverlappingTemplateTestArgs::try_new_nist_behaviour(9).unwrap(),
         linear_complexity: LinearComplexityTestArg::ManualBlockLength(NonZero::new(500).unwrap()),
-        serial: SerialTestArg::new(16).unwrap(),
-        approximate_entropy: ApproximateEntropyTestArg::new(10).unwrap(),
+        serial: SerialTestArg::try_new(16).unwrap(),
+        approximate_entropy: ApproximateEntropyTestArg::try_new(10).unwrap(),
+        random_excursions: RandomExcursionsTestArg::default(),
+        random_excursions_variant: RandomExcursionsVariantTestArg::default(),
+        autocorrelation: AutocorrelationTestArg::default(),
+        timeout: None,
     };
 
-    // data structures to store the statistics: (rust, c)
-    let mut statistics: [StatisticStorage; COUNT_TEST_FILES] =
-        array::from_fn(|_| Default::default());
-    // will contain all calculated per-file averages
-    let mut all_averages: StatisticStorage = HashMap::new();
+    let runs = args.runs.get();
+    // will contain the times of all files
+    let mut all_times: StatisticStorage = HashMap::new();
+    let mut file_statistics = Vec::with_capacity(test_files.len());
 
-    for (i, test_file) in test_files.iter().enumerate() {
+    for test_file in &test_files {
         eprintln!("Testing {}...", test_file.display());
 
-        let stats = &mut statistics[i];
+        let data = BitVec::from(fs::read(test_file).unwrap());
+        let mut times = StatisticStorage::new();
 
-        for j in 0..COUNT_RUNS_PER_FILE {
+        for j in 0..runs {
             // Rust attempt
-            eprintln!(
-                "\tAttempt {}/{COUNT_RUNS_PER_FILE} - This implementation",
-                j + 1
-            );
-            test_rust_imp(test_file, test_args, stats);
+            eprintln!("\tAttempt {}/{runs} - This implementation", j + 1);
+            test_rust_imp(&data, test_args, &mut times);
 
             if let Some(exe) = &executable {
                 // C attempt
-                eprintln!(
-                    "\tAttempt {}/{COUNT_RUNS_PER_FILE} - Reference implementation",
-                    j + 1
-                );
-                test_c_imp(test_file, exe, stats);
+                eprintln!("\tAttempt {}/{runs} - Reference implementation", j + 1);
+                test_c_imp(test_file, data.len_bit(), exe, &mut times);
             }
         }
 
         // Print the statistics to stderr for separation
         println!("Statistics for test file {}:", test_file.display());
 
-        // sort the results by the test
-        let mut statistics = stats.iter().collect::<Vec<_>>();
-        statistics.sort_unstable_by_key(|(test, _)| **test as u8);
-
-        let averages = statistics
-            .into_iter()
-            .map(|(test, (rust, c))| (test, average(rust), average(c)));
-
-        for (test, rust, c) in averages {
-            print_statistics(*test, rust, c);
-
-            all_averages
-                .entry(*test)
-                .and_modify(|(first, second)| {
-                    if let Some(rust) = rust {
-                        first.push(rust);
-                    }
-                    if let Some(c) = c {
-                        second.push(c);
-                    }
-                })
-                .or_insert_with(|| {
-                    let rust = rust.map(|v| vec![v]).unwrap_or_default();
-                    let c = c.map(|v| vec![v]).unwrap_or_default();
-
-                    (rust, c)
-                });
+        let statistics = test_statistics(&times);
+        for statistics in &statistics {
+            print_statistics(statistics);
         }
-
         println!();
+
+        for (test, (rust, c)) in times {
+            let (all_rust, all_c) = all_times.entry(test).or_default();
+            all_rust.extend(rust);
+            all_c.extend(c);
+        }
+        file_statistics.push(FileStatistics {
+            file: Some(test_file.clone()),
+            tests: statistics,
+        });
     }
 
-    // calculate overall averages - again, sort by tests
-    let mut all_averages = all_averages.into_iter().collect::<Vec<_>>();
-    all_averages.sort_unstable_by_key(|(test, _)| *test as u8);
-    let averages = all_averages.into_iter().map(|(test, (rust, c))| {
-        // this works because the same amount of tests is executed for every file
-        (test, average(&rust), average(&c))
-    });
+    // calculate the overall statistics over the times of all files
+    let overall = FileStatistics {
+        file: None,
+        tests: test_statistics(&all_times),
+    };
 
     println!("Overall statistics:");
 
-    for (test, rust, c) in averages {
-        print_statistics(test, rust, c);
+    for statistics in &overall.tests {
+        print_statistics(statistics);
     }
+
+    if let Some(path) = args.output {
+        let output = BenchmarkOutput {
+            runs_per_file: runs,
+            files: file_statistics,
+            overall,
+        };
+        write_output(&path, &output);
+    }
+}
+
+/// Write the statistics to the output file, as JSON or CSV depending on the extension.
+fn write_output(path: &Path, output: &BenchmarkOutput) {
+    let content = if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::to_string_pretty(output).unwrap()
+    } else {
+        let mut csv = String::from(
+            "file;test;implementation;runs;mean ms;std. dev. ms;min ms;median ms;p90 ms;p99 ms;\
+            max ms\n",
+        );
+        for file in output.files.iter().chain([&output.overall]) {
+            let name = match &file.file {
+                Some(file) => file.display().to_string(),
+                None => "overall".to_owned(),
+            };
+
+            for test in &file.tests {
+                let implementations = [("rust", test.rust), ("reference", test.reference)];
+                for (implementation, statistics) in implementations {
+                    let Some(s) = statistics else {
+                        continue;
+                    };
+                    writeln!(
+                        csv,
+                        "{name};{};{implementation};{};{};{};{};{};{};{};{}",
+                        test.test, s.runs, s.mean, s.std_dev, s.min, s.median, s.p90, s.p99, s.max
+                    )
+                    .unwrap();
+                }
+            }
+        }
+        csv
+    };
+
+    fs::write(path, content)
+        .unwrap_or_else(|e| panic!("Cannot write the output file {}: {e}", path.display()));
 }
@@ -0,0 +1,431 @@
+//! Differential testing of this implementation against the NIST reference implementation.
+//!
+//! The unmodified `assess` binary of the reference implementation is driven through its text
+//! interface, see [Reference]. The same input is tested with this implementation, using the
+//! equivalent [Parameters], and the P-values of both implementations are compared with [compare].
+//!
+//! See the README for how to build the reference implementation and run the tests.
+
+use std::collections::HashMap;
+use std::env::consts::EXE_EXTENSION;
+use std::fmt::{self, Display};
+use std::fs;
+use std::io::{self, Write};
+use std::num::NonZero;
+use std::path::{self, Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, PoisonError};
+use sts_lib::bitvec::BitVec;
+use sts_lib::test_runner::{run_tests, validate_tests};
+use sts_lib::tests::approximate_entropy::ApproximateEntropyTestArg;
+use sts_lib::tests::frequency_block::FrequencyBlockTestArg;
+use sts_lib::tests::linear_complexity::LinearComplexityTestArg;
+use sts_lib::tests::serial::SerialTestArg;
+use sts_lib::tests::spectral_dft::{PeakThreshold, SpectralDftTestArg};
+use sts_lib::tests::template_matching::non_overlapping::NonOverlappingTemplateTestArgs;
+use sts_lib::tests::template_matching::overlapping::OverlappingTemplateTestArgs;
+use sts_lib::{ArgError, Test, TestArgs};
+use thiserror::Error;
+
+/// The environment variable that contains the path to the `assess` binary, see
+/// [Reference::from_env].
+pub const REFERENCE_ENV: &str = "STS_REFERENCE_ASSESS";
+
+/// The default maximum absolute difference of two P-values, see [default_tolerance]. The reference
+/// implementation only writes 6 decimal places.
+pub const DEFAULT_TOLERANCE: f64 = 1e-4;
+
+/// The name of the input file, in the directory of the reference implementation. A relative path
+/// is used, since the reference implementation reads the path with `scanf("%s")`, which stops
+/// at whitespace.
+const INPUT_FILE: &str = "conformance-input.bin";
+
+/// The compared tests, with the directory of their results in `experiments/AlgorithmTesting`.
+/// The Lempel-Ziv test and the autocorrelation test are not part of the reference implementation.
+const REFERENCE_TESTS: [(Test, &str); 15] = [
+    (Test::Frequency, "Frequency"),
+    (Test::FrequencyWithinABlock, "BlockFrequency"),
+    (Test::Runs, "Runs"),
+    (Test::LongestRunOfOnes, "LongestRun"),
+    (Test::BinaryMatrixRank, "Rank"),
+    (Test::SpectralDft, "FFT"),
+    (
+        Test::NonOverlappingTemplateMatching,
+        "NonOverlappingTemplate",
+    ),
+    (Test::OverlappingTemplateMatching, "OverlappingTemplate"),
+    (Test::MaurersUniversalStatistical, "Universal"),
+    (Test::LinearComplexity, "LinearComplexity"),
+    (Test::Serial, "Serial"),
+    (Test::ApproximateEntropy, "ApproximateEntropy"),
+    (Test::CumulativeSums, "CumulativeSums"),
+    (Test::RandomExcursions, "RandomExcursions"),
+    (Test::RandomExcursionsVariant, "RandomExcursionsVariant"),
+];
+
+/// The P-values of each test, in the order they are written by the reference implementation.
+pub type PValues = HashMap<Test, Vec<f64>>;
+
+/// The errors of a differential test run.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Error running the reference implementation: {0}")]
+    Reference(#[from] io::Error),
+    #[error("Invalid parameters: {0}")]
+    InvalidParameters(#[from] ArgError),
+}
+
+/// The parameters of the reference implementation, which can be adjusted in its text interface.
+/// All other parameters of the reference implementation are fixed, see [Parameters::test_args].
+///
+/// The default values are the defaults of the reference implementation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Parameters {
+    /// The block length of the frequency test within a block.
+    pub block_frequency: usize,
+    /// The template length of the non-overlapping template matching test.
+    pub non_overlapping_template: usize,
+    /// The template length of the overlapping template matching test, 9 or 10.
+    pub overlapping_template: usize,
+    /// The block length of the approximate entropy test.
+    pub approximate_entropy: u8,
+    /// The block length of the serial test.
+    pub serial: u8,
+    /// The block length of the linear complexity test, between 500 and 5000.
+    pub linear_complexity: usize,
+}
+
+impl Default for Parameters {
+    fn default() -> Self {
+        Self {
+            block_frequency: 128,
+            non_overlapping_template: 9,
+            overlapping_template: 9,
+            approximate_entropy: 10,
+            serial: 16,
+            linear_complexity: 500,
+        }
+    }
+}
+
+impl Parameters {
+    /// The arguments of this implementation that are equivalent to the parameters: the
+    /// non-overlapping template matching test uses 8 blocks, the overlapping template matching
+    /// test uses the [behaviour of the reference implementation](OverlappingTemplateTestArgs::try_new_nist_behaviour),
+    /// and the spectral DFT test uses the corrected threshold.
+    pub fn test_args(&self) -> Result<TestArgs, ArgError> {
+        let block_frequency = NonZero::new(self.block_frequency).ok_or(ArgError::TooSmall {
+            name: "block length",
+            value: self.block_frequency,
+            min: 1,
+        })?;
+        let linear_complexity = NonZero::new(self.linear_complexity)
+            .filter(|block_length| (500..=5000).contains(&block_length.get()))
+            .ok_or(ArgError::OutOfRange {
+                name: "block length",
+                value: self.linear_complexity,
+                min: 500,
+                max: 5000,
+            })?;
+
+        Ok(TestArgs {
+            frequency_block: FrequencyBlockTestArg::Manual(block_frequency),
+            spectral_dft: SpectralDftTestArg::new(PeakThreshold::KimUmeno),
+            non_overlapping_template: NonOverlappingTemplateTestArgs::try_new(
+                self.non_overlapping_template,
+                8,
+            )?,
+            overlapping_template: OverlappingTemplateTestArgs::try_new_nist_behaviour(
+                self.overlapping_template,
+            )?,
+            linear_complexity: LinearComplexityTestArg::ManualBlockLength(linear_complexity),
+            serial: SerialTestArg::try_new(self.serial)?,
+            approximate_entropy: ApproximateEntropyTestArg::try_new(self.approximate_entropy)?,
+            ..Default::default()
+        })
+    }
+
+    /// The answers to the parameter adjustment menu of the reference implementation, ending the
+    /// menu. Only the parameters that differ from the defaults are adjusted.
+    fn menu_input(&self) -> String {
+        let default = Self::default();
+        let adjustments = [
+            (self.block_frequency, default.block_frequency),
+            (
+                self.non_overlapping_template,
+                default.non_overlapping_template,
+            ),
+            (self.overlapping_template, default.overlapping_template),
+            (
+                self.approximate_entropy.into(),
+                default.approximate_entropy.into(),
+            ),
+            (self.serial.into(), default.serial.into()),
+            (self.linear_complexity, default.linear_complexity),
+        ];
+
+        let mut input = String::new();
+        for (idx, (value, default)) in adjustments.into_iter().enumerate() {
+            if value != default {
+                // the menu entries start with 1
+                input.push_str(&format!("{}\n{value}\n", idx + 1));
+            }
+        }
+        input.push_str("0\n");
+        input
+    }
+}
+
+/// The unmodified `assess` binary of the reference implementation.
+///
+/// The reference implementation reads its templates and writes its results relative to its own
+/// directory, so it must not be moved after it was built. For the same reason, only one run at a
+/// time is possible per directory: runs of the same instance are serialized, but separate
+/// processes must not use the same directory concurrently.
+#[derive(Debug)]
+pub struct Reference {
+    executable: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl Reference {
+    /// Uses the given `assess` binary. On Windows, the extension `.exe` may be omitted.
+    pub fn new(executable: impl AsRef<Path>) -> io::Result<Self> {
+        let executable = resolve_executable(path::absolute(executable)?);
+        if !executable.is_file() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Executable {} does not exist", executable.display()),
+            ));
+        }
+
+        Ok(Self {
+            executable,
+            lock: Mutex::new(()),
+        })
+    }
+
+    /// Uses the `assess` binary given in the environment variable [REFERENCE_ENV], `None` if the
+    /// variable is not set.
+    pub fn from_env() -> Option<io::Result<Self>> {
+        std::env::var_os(REFERENCE_ENV).map(Self::new)
+    }
+
+    /// Runs all tests of the reference implementation on the given input, which is tested as a
+    /// whole, and returns the P-values of each test. Tests without results, e.g. because the input
+    /// is too short for them, are missing.
+    pub fn run(&self, data: &[u8], parameters: &Parameters) -> io::Result<PValues> {
+        // the directory is shared by all runs
+        let _guard = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        let dir = self.executable.parent().unwrap();
+        let input_file = dir.join(INPUT_FILE);
+        fs::write(&input_file, data)?;
+
+        let result = self.run_in_dir(dir, data.len() * 8, parameters);
+        let _ = fs::remove_file(&input_file);
+        result
+    }
+
+    fn run_in_dir(
+        &self,
+        dir: &Path,
+        len_bit: usize,
+        parameters: &Parameters,
+    ) -> io::Result<PValues> {
+        let mut child = Command::new(&self.executable)
+            .arg(len_bit.to_string())
+            .current_dir(dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        // generator: input file, tests: all, parameter adjustments, 1 bitstream, binary input
+        let input = format!("0\n{INPUT_FILE}\n1\n{}1\n1\n", parameters.menu_input());
+        let mut stdin = child.stdin.take().unwrap();
+        stdin.write_all(input.as_bytes())?;
+        // close stdin, so that the reference implementation cannot wait for further input
+        drop(stdin);
+
+        // the exit code of the reference implementation is meaningless, check the results instead
+        let output = child.wait_with_output()?;
+
+        let results_dir = dir.join("experiments").join("AlgorithmTesting");
+        let mut p_values = PValues::new();
+        for (test, name) in REFERENCE_TESTS {
+            let results_file = results_dir.join(name).join("results.txt");
+            let Ok(content) = fs::read_to_string(&results_file) else {
+                continue;
+            };
+            let values = content
+                .split_whitespace()
+                .map(|value| {
+                    value.parse::<f64>().map_err(|e| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("Invalid P-value {value} in {}: {e}", results_file.display()),
+                        )
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if !values.is_empty() {
+                p_values.insert(test, values);
+            }
+        }
+
+        // the frequency test is applicable to every input
+        if !p_values.contains_key(&Test::Frequency) {
+            let msg = String::from_utf8_lossy(&output.stderr);
+            return Err(io::Error::other(format!(
+                "The reference implementation produced no results ({}): {msg}",
+                output.status
+            )));
+        }
+
+        Ok(p_values)
+    }
+}
+
+/// Runs all tests of this implementation that are also part of the reference implementation on
+/// the given input, and returns the P-values of each test. Tests that are not applicable to the
+/// input length or the arguments, or that return an error, are missing.
+pub fn run_rust(data: &BitVec, args: TestArgs) -> PValues {
+    let tests = REFERENCE_TESTS
+        .into_iter()
+        .map(|(test, _)| test)
+        .filter(|&test| validate_tests(data.len_bit(), [test], args).is_ok());
+
+    // validated above, cannot fail
+    run_tests(data, tests, args)
+        .unwrap()
+        .filter_map(|(test, results)| {
+            let p_values = results
+                .ok()?
+                .into_iter()
+                // not applicable results are marked with NaN, and are skipped in the comparison
+                .map(|result| {
+                    if result.is_applicable() {
+                        result.p_value()
+                    } else {
+                        f64::NAN
+                    }
+                })
+                .collect();
+            Some((test, p_values))
+        })
+        .collect()
+}
+
+/// The default maximum absolute difference of two P-values of the given test.
+///
+/// Some tests are known to deviate from the reference implementation:
+/// * The spectral DFT test uses single precision, so a peak close to the threshold may be counted
+///   differently.
+/// * The linear complexity test uses exact probability constants instead of the rounded ones.
+pub fn default_tolerance(test: Test) -> f64 {
+    match test {
+        Test::SpectralDft | Test::LinearComplexity => 1e-2,
+        _ => DEFAULT_TOLERANCE,
+    }
+}
+
+/// A P-value of this implementation that does not match the reference implementation.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Mismatch {
+    pub test: Test,
+    /// The index of the result of the test.
+    pub index: usize,
+    /// The P-value of the reference implementation.
+    pub expected: f64,
+    /// The P-value of this implementation.
+    pub actual: f64,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            test,
+            index,
+            expected,
+            actual,
+        } = self;
+        write!(
+            f,
+            "{test}, result {index}: the reference implementation returned {expected:.6}, \
+             this implementation {actual:.6} (difference: {:.6})",
+            (expected - actual).abs()
+        )
+    }
+}
+
+/// The outcome of a comparison, see [compare].
+#[derive(Clone, Debug, Default)]
+pub struct Comparison {
+    /// The count of compared P-values.
+    pub compared: usize,
+    pub mismatches: Vec<Mismatch>,
+}
+
+/// Compares the P-values of both implementations with the given tolerance per test, see
+/// [default_tolerance].
+///
+/// Only tests with results of both implementations are compared, and only as many results as
+/// the reference implementation returned: it tests at most 148 templates in the non-overlapping
+/// template matching test. Not applicable results of this implementation are skipped.
+pub fn compare(reference: &PValues, rust: &PValues, tolerance: impl Fn(Test) -> f64) -> Comparison {
+    let mut comparison = Comparison::default();
+
+    for (test, _) in REFERENCE_TESTS {
+        let (Some(expected), Some(actual)) = (reference.get(&test), rust.get(&test)) else {
+            continue;
+        };
+        let tolerance = tolerance(test);
+
+        for (index, (&expected, &actual)) in expected.iter().zip(actual).enumerate() {
+            if actual.is_nan() {
+                continue;
+            }
+
+            comparison.compared += 1;
+            if (expected - actual).abs() > tolerance {
+                comparison.mismatches.push(Mismatch {
+                    test,
+                    index,
+                    expected,
+                    actual,
+                });
+            }
+        }
+    }
+
+    comparison
+}
+
+/// Tests the given input with both implementations and compares the P-values with the
+/// [default tolerance](default_tolerance).
+pub fn run_differential(
+    reference: &Reference,
+    data: &[u8],
+    parameters: &Parameters,
+) -> Result<Comparison, Error> {
+    let args = parameters.test_args()?;
+    let expected = reference.run(data, parameters)?;
+    let actual = run_rust(&BitVec::from(data.to_vec()), args);
+
+    Ok(compare(&expected, &actual, default_tolerance))
+}
+
+/// Adds the platform-specific extension of executables (".exe" on Windows) to the given path,
+/// if the path doesn't exist as given. This allows to pass the same path on all platforms.
+fn resolve_executable(exe: PathBuf) -> PathBuf {
+    if exe.exists() || EXE_EXTENSION.is_empty() || exe.extension().is_some() {
+        return exe;
+    }
+
+    let with_extension = exe.with_extension(EXE_EXTENSION);
+    if with_extension.exists() {
+        with_extension
+    } else {
+        exe
+    }
+}
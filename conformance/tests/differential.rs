@@ -0,0 +1,128 @@
+//! Differential tests against the reference implementation. All tests pass without doing anything
+//! if the environment variable `STS_REFERENCE_ASSESS` is not set, see the README.
+
+use conformance::{run_differential, Comparison, Parameters, Reference};
+use proptest::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// The reference implementation, `None` if it is not configured.
+fn reference() -> Option<&'static Reference> {
+    static REFERENCE: OnceLock<Option<Reference>> = OnceLock::new();
+
+    REFERENCE
+        .get_or_init(|| Reference::from_env().map(|reference| reference.unwrap()))
+        .as_ref()
+}
+
+/// Formats all mismatches, one per line.
+fn describe(comparison: &Comparison) -> String {
+    comparison
+        .mismatches
+        .iter()
+        .map(|mismatch| format!("\n\t{mismatch}"))
+        .collect()
+}
+
+/// Expands the seed to `len` pseudo-random bytes with SplitMix64. Generating the seed instead of
+/// the bytes keeps the shrinking of failing cases cheap.
+fn random_bytes(seed: u64, len: usize) -> Vec<u8> {
+    let mut state = seed;
+    let mut next = move || {
+        state = state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    };
+
+    let mut bytes = Vec::with_capacity(len + 8);
+    while bytes.len() < len {
+        bytes.extend_from_slice(&next().to_be_bytes());
+    }
+    bytes.truncate(len);
+    bytes
+}
+
+/// Parameters within the bounds of both implementations, for inputs of at least 10^5 bits.
+fn parameters() -> impl Strategy<Value = Parameters> {
+    (
+        20..=1000usize,
+        2..=10usize,
+        prop_oneof![Just(9usize), Just(10)],
+        2..=10u8,
+        2..=14u8,
+        500..=5000usize,
+    )
+        .prop_map(
+            |(
+                block_frequency,
+                non_overlapping_template,
+                overlapping_template,
+                approximate_entropy,
+                serial,
+                linear_complexity,
+            )| Parameters {
+                block_frequency,
+                non_overlapping_template,
+                overlapping_template,
+                approximate_entropy,
+                serial,
+                linear_complexity,
+            },
+        )
+}
+
+#[test]
+fn test_files_with_default_parameters() {
+    let Some(reference) = reference() else {
+        return;
+    };
+
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../sts-lib/test-files");
+    for file in [
+        "e.1e6.bin",
+        "pi.1e6.bin",
+        "sha1.1e6.bin",
+        "sqrt2.1e6.bin",
+        "sqrt3.1e6.bin",
+    ] {
+        let data = fs::read(dir.join(file)).unwrap();
+        let comparison = run_differential(reference, &data, &Parameters::default()).unwrap();
+
+        assert!(comparison.compared > 0, "{file}: nothing was compared");
+        assert!(
+            comparison.mismatches.is_empty(),
+            "{file}: P-values differ:{}",
+            describe(&comparison)
+        );
+    }
+}
+
+proptest! {
+    // each case runs the complete reference implementation
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn random_inputs_and_parameters(
+        seed in any::<u64>(),
+        // 10^6 bits are needed for all tests, shorter inputs skip some of them
+        len in prop_oneof![12_500..125_000usize, Just(125_000usize)],
+        parameters in parameters(),
+    ) {
+        let Some(reference) = reference() else {
+            return Ok(());
+        };
+
+        let data = random_bytes(seed, len);
+        let comparison = run_differential(reference, &data, &parameters).unwrap();
+
+        prop_assert!(comparison.compared > 0, "nothing was compared");
+        prop_assert!(
+            comparison.mismatches.is_empty(),
+            "P-values differ:{}",
+            describe(&comparison)
+        );
+    }
+}
@@ -119,16 +119,20 @@ int main(int argc, char **argv) {
 
     // Print the test results for each test.
     for (int i = 0; i < TEST_COUNT; i++) {
-        size_t length = 0;
-        TestResult **results = sts_TestRunner_get_result(runner, i, &length);
+        TestResultList *results = sts_TestRunner_get_result(runner, i);
+        if (results == NULL) {
+            print_last_error();
+            continue;
+        }
 
-        for (int j = 0; j < length; j++) {
-            printf("Test: %d: TestResult %d: P-Value: %lf", i + 1, j, sts_TestResult_get_p_value(results[j]));
+        for (size_t j = 0; j < sts_TestResultList_len(results); j++) {
+            const TestResult *result = sts_TestResultList_get(results, j);
+            printf("Test: %d: TestResult %zu: P-Value: %lf", i + 1, j, sts_TestResult_get_p_value(result));
 
             size_t comment_length = 0;
-            if (sts_TestResult_get_comment(results[j], NULL, &comment_length) == 0) {
+            if (sts_TestResult_get_comment(result, NULL, &comment_length) == 0) {
                 char* comment = malloc(sizeof(char) * comment_length);
-                sts_TestResult_get_comment(results[j], comment, &comment_length);
+                sts_TestResult_get_comment(result, comment, &comment_length);
                 printf("; Comment: %s\n", comment);
                 free(comment);
             } else {
@@ -136,7 +140,7 @@ int main(int argc, char **argv) {
             }
         }
 
-        sts_TestResult_list_destroy(results, length);
+        sts_TestResultList_destroy(results);
     }
 
     sts_TestRunner_destroy(runner);
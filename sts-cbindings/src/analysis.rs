@@ -0,0 +1,264 @@
+//! Analysis of the results of a test over multiple sequences.
+
+use crate::test_result::{TestResult, TestResultList};
+use crate::test_runner::test::{RawTest, Test};
+use crate::{ErrorSink, StsError};
+use std::ffi::c_int;
+use std::slice;
+use sts_lib::analysis::TestVerdict as InternalTestVerdict;
+
+/// The verdict for one test over multiple sequences, see [sts_analyse].
+///
+/// The proportion of sequences that passed each result of the test is compared to the acceptance
+/// interval `p ± 3 * sqrt(p * (1 - p) / m)`, with `p = 1 - threshold` and *m* being the count of
+/// sequences.
+pub struct TestVerdict(InternalTestVerdict);
+
+/// Analyses the results of the given test over multiple sequences.
+///
+/// `results` is a list of `count` result lists, one per sequence, as returned by the test
+/// functions or [sts_TestRunner_get_result]. The passed lists remain owned by the caller.
+/// Sequences with results that are not applicable (see [sts_TestResult_is_applicable]) are not
+/// part of the sample.
+///
+/// The resulting pointer must be freed via [sts_TestVerdict_destroy]. If an error happened,
+/// `NULL` is returned - use [sts_get_last_error].
+///
+/// ## Safety
+///
+/// * `results` must be a valid pointer to `count` elements, each being a valid, non-null
+///   [TestResultList].
+/// * `results` and all contained lists may not be mutated for the duration of this call.
+#[no_mangle]
+pub unsafe extern "C" fn sts_analyse(
+    test: RawTest,
+    results: *const &TestResultList,
+    count: usize,
+    threshold: f64,
+) -> Option<Box<TestVerdict>> {
+    let errors = ErrorSink::LastError;
+    // SAFETY: same considerations apply to the call as for this function.
+    unsafe { analyse(test, results, count, threshold, errors) }
+}
+
+/// Same as [sts_analyse], but an error is written to `error` instead of being stored as the last
+/// error, see [StsError].
+///
+/// ## Safety
+///
+/// * The same requirements as for [sts_analyse] apply.
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_analyse_e(
+    test: RawTest,
+    results: *const &TestResultList,
+    count: usize,
+    threshold: f64,
+    error: *mut *mut StsError,
+) -> Option<Box<TestVerdict>> {
+    // SAFETY: same considerations apply to the calls as for this function.
+    unsafe {
+        let errors = ErrorSink::out(error);
+        analyse(test, results, count, threshold, errors)
+    }
+}
+
+/// Implementation of [sts_analyse].
+///
+/// ## Safety
+///
+/// The same requirements as for [sts_analyse] apply.
+unsafe fn analyse(
+    test: RawTest,
+    results: *const &TestResultList,
+    count: usize,
+    threshold: f64,
+    errors: ErrorSink,
+) -> Option<Box<TestVerdict>> {
+    let Ok(test) = Test::try_from(test) else {
+        errors.report(StsError::invalid_test(test));
+        return None;
+    };
+
+    if count > 0 && results.is_null() {
+        errors.report(sts_lib::Error::InvalidParameter(
+            "results must not be NULL".to_owned(),
+        ));
+        return None;
+    }
+
+    let sequences = if count == 0 {
+        Vec::new()
+    } else {
+        // SAFETY: the caller has to ensure that the pointer is valid with count elements.
+        let results = unsafe { slice::from_raw_parts(results, count) };
+
+        results
+            .iter()
+            .map(|list| {
+                list.0
+                    .iter()
+                    .map(|result| result.0.clone())
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    };
+
+    let verdict = sts_lib::analysis::analyse([(test.into(), sequences)], threshold)
+        .pop()
+        .expect("one verdict per test");
+
+    Some(Box::new(TestVerdict(verdict)))
+}
+
+/// Checks the uniformity of the given P-values, e.g. the P-values of one test over multiple
+/// sequences, with the chi-square test of NIST SP 800-22r1a, section 4.2.2.
+///
+/// The P-value of the returned result is the P-value of the P-values. NIST considers the P-values
+/// to be uniformly distributed if it is at least 0.0001. If no P-values are given, the result is
+/// not applicable, see [sts_TestResult_is_applicable].
+///
+/// The resulting pointer must be freed via [sts_TestResult_destroy]. If an error happened, `NULL`
+/// is returned - use [sts_get_last_error].
+///
+/// ## Safety
+///
+/// * `p_values` must be a valid pointer to `count` elements.
+/// * `p_values` may not be mutated for the duration of this call.
+#[no_mangle]
+pub unsafe extern "C" fn sts_p_value_uniformity(
+    p_values: *const f64,
+    count: usize,
+) -> Option<Box<TestResult>> {
+    // SAFETY: same considerations apply to the call as for this function.
+    unsafe { p_value_uniformity(p_values, count, ErrorSink::LastError) }
+}
+
+/// Same as [sts_p_value_uniformity], but an error is written to `error` instead of being stored
+/// as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * The same requirements as for [sts_p_value_uniformity] apply.
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_p_value_uniformity_e(
+    p_values: *const f64,
+    count: usize,
+    error: *mut *mut StsError,
+) -> Option<Box<TestResult>> {
+    // SAFETY: same considerations apply to the calls as for this function.
+    unsafe { p_value_uniformity(p_values, count, ErrorSink::out(error)) }
+}
+
+/// Implementation of [sts_p_value_uniformity].
+///
+/// ## Safety
+///
+/// The same requirements as for [sts_p_value_uniformity] apply.
+unsafe fn p_value_uniformity(
+    p_values: *const f64,
+    count: usize,
+    errors: ErrorSink,
+) -> Option<Box<TestResult>> {
+    let p_values = if count == 0 {
+        &[]
+    } else if p_values.is_null() {
+        errors.report(sts_lib::Error::InvalidParameter(
+            "p_values must not be NULL".to_owned(),
+        ));
+        return None;
+    } else {
+        // SAFETY: the caller has to ensure that the pointer is valid with count elements.
+        unsafe { slice::from_raw_parts(p_values, count) }
+    };
+
+    let result = sts_lib::analysis::p_value_uniformity(p_values);
+    Some(Box::new(TestResult(result)))
+}
+
+/// Destroys the given test verdict.
+///
+/// ## Safety
+///
+/// * `verdict` must have been created by [sts_analyse].
+/// * `verdict` must be a valid, non-null pointer.
+/// * `verdict` will be invalid after this call, access will lead to undefined behaviour.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestVerdict_destroy(verdict: Option<Box<TestVerdict>>) {
+    _ = verdict;
+}
+
+/// Checks if the test passed: the proportion of every result lies within the acceptance interval.
+/// Returns `false` if there are no results.
+///
+/// ## Safety
+///
+/// * `verdict` must have been created by [sts_analyse].
+/// * `verdict` must be a valid pointer.
+/// * `verdict` may not be mutated for the duration of this call.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestVerdict_passed(verdict: &TestVerdict) -> bool {
+    verdict.0.passed()
+}
+
+/// Returns the count of results of the test, see [sts_TestVerdict_get_proportion].
+/// Returns 0 if no sequence returned an applicable result.
+///
+/// ## Safety
+///
+/// * `verdict` must have been created by [sts_analyse].
+/// * `verdict` must be a valid pointer.
+/// * `verdict` may not be mutated for the duration of this call.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestVerdict_get_result_count(verdict: &TestVerdict) -> usize {
+    verdict.0.results().len()
+}
+
+/// Returns the count of sequences the test was not applicable to. These are not part of the
+/// sample.
+///
+/// ## Safety
+///
+/// * `verdict` must have been created by [sts_analyse].
+/// * `verdict` must be a valid pointer.
+/// * `verdict` may not be mutated for the duration of this call.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestVerdict_get_not_applicable_count(verdict: &TestVerdict) -> usize {
+    verdict.0.not_applicable_count()
+}
+
+/// Extracts the proportion of passed sequences for the result with the given index: the count of
+/// passed sequences, the sample size and the acceptance interval (min, max). The count of results
+/// is returned by [sts_TestVerdict_get_result_count].
+///
+/// # Return values
+///
+/// - 0: everything's OK, the values are written to the given pointers.
+/// - 1: there is no result with the given index.
+///
+/// ## Safety
+///
+/// * `verdict` must have been created by [sts_analyse].
+/// * `verdict` must be a valid pointer.
+/// * `verdict` may not be mutated for the duration of this call.
+/// * `passed_count`, `sample_size`, `min` and `max` must be valid pointers.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestVerdict_get_proportion(
+    verdict: &TestVerdict,
+    idx: usize,
+    passed_count: &mut usize,
+    sample_size: &mut usize,
+    min: &mut f64,
+    max: &mut f64,
+) -> c_int {
+    match verdict.0.results().get(idx) {
+        Some(result) => {
+            *passed_count = result.passed_count();
+            *sample_size = result.sample_size();
+            (*min, *max) = result.acceptance_interval();
+            0
+        }
+        None => 1,
+    }
+}
@@ -1,14 +1,82 @@
 //! All exported functions related to a [BitVec].
 //! BitVec is a container for storing bit sequences for use in testing.
 
-use std::ffi::c_char;
+use std::ffi::{c_char, c_int, CStr};
+use std::fs::File;
 use std::ptr::slice_from_raw_parts;
 
+use crate::{ErrorSink, StsError};
+use sts_lib::bitvec::builder::ReaderFormat;
 use sts_lib::bitvec::BitVec as InternalBitVec;
 
+// Type of a raw input format, used for the FFI boundary (rust doesn't like it if a value is passed
+// for an enum that is not in the enum).
+pub type RawInputFormat = c_int;
+
 /// BitVec: a list of bits to run statistical tests on.
+// 'static: the words borrowed by sts_BitVec_borrow_words are kept alive by the caller, until the
+// BitVec and all its clones are destroyed.
 #[derive(Clone)]
-pub struct BitVec(pub(crate) InternalBitVec);
+pub struct BitVec(pub(crate) InternalBitVec<'static>);
+
+/// The format of an input file, see [sts_BitVec_from_file].
+/// cbindgen:prefix-with-name=true
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+pub enum InputFormat {
+    /// Binary input: each byte contains 8 bits, starting with the MSB.
+    Binary = 0,
+    /// ASCII input: the character "0" maps to 0 and "1" maps to 1. No other character is allowed.
+    Ascii = 1,
+    /// ASCII input: the character "0" maps to 0 and "1" maps to 1. Any other character is skipped.
+    AsciiLossy = 2,
+    /// Hexadecimal text input: each hex digit contains 4 bits, starting with the MSB. Whitespace is
+    /// skipped, no other character is allowed.
+    Hex = 3,
+    /// Hexadecimal text input: each hex digit contains 4 bits, starting with the MSB. Any other
+    /// character is skipped.
+    HexLossy = 4,
+    /// Base64 text input, decoded to bytes, each byte containing 8 bits, starting with the MSB.
+    /// Whitespace is skipped, no other character is allowed.
+    Base64 = 5,
+    /// ASCII input: the character "0" maps to 0 and "1" maps to 1. Whitespace is skipped, no other
+    /// character is allowed. This is the format of the data files of the NIST reference
+    /// implementation.
+    AsciiWhitespace = 6,
+}
+
+impl From<InputFormat> for ReaderFormat {
+    fn from(value: InputFormat) -> Self {
+        match value {
+            InputFormat::Binary => ReaderFormat::Binary,
+            InputFormat::Ascii => ReaderFormat::Ascii,
+            InputFormat::AsciiLossy => ReaderFormat::AsciiLossy,
+            InputFormat::Hex => ReaderFormat::Hex,
+            InputFormat::HexLossy => ReaderFormat::HexLossy,
+            InputFormat::Base64 => ReaderFormat::Base64,
+            InputFormat::AsciiWhitespace => ReaderFormat::AsciiWhitespace,
+        }
+    }
+}
+
+impl TryFrom<RawInputFormat> for InputFormat {
+    type Error = ();
+
+    fn try_from(value: RawInputFormat) -> Result<Self, Self::Error> {
+        let format = match value {
+            0 => InputFormat::Binary,
+            1 => InputFormat::Ascii,
+            2 => InputFormat::AsciiLossy,
+            3 => InputFormat::Hex,
+            4 => InputFormat::HexLossy,
+            5 => InputFormat::Base64,
+            6 => InputFormat::AsciiWhitespace,
+            _ => return Err(()),
+        };
+
+        Ok(format)
+    }
+}
 
 /// Creates a Bit Vector from a string, with the ASCII char "0" mapping to 0 and "1" mapping to 1.
 /// Any other character is ignored.
@@ -90,6 +158,277 @@ pub unsafe extern "C" fn sts_BitVec_from_bits(ptr: *const bool, len: usize) -> B
     Box::new(BitVec(bitvec))
 }
 
+/// Creates a BitVec from a buffer of packed words, e.g. of another library that already stores
+/// the bits packed, without converting them to bytes first. The words are copied, see
+/// [sts_BitVec_borrow_words] to avoid this.
+///
+/// Each word has the size of `size_t` (equal to `uintptr_t` on all supported platforms) and
+/// contains `8 * sizeof(size_t)` bits. The first bit is the most significant bit of `words[0]`,
+/// followed by the lower bits, then the bits of `words[1]` and so on. This refers to the numerical
+/// value of the words and is independent of the byte order of the platform: a byte buffer must
+/// not simply be cast to words on little-endian platforms, use [sts_BitVec_from_bytes] instead.
+///
+/// ## Parameters
+///
+/// * `words`: pointer to the word buffer
+/// * `n_words`: count of words (elements)
+/// * `bit_len`: count of bits to use, at most `n_words * 8 * sizeof(size_t)`. Bits and words
+///   after it are ignored.
+///
+/// ## Return value
+///
+/// * The created BitVec.
+/// * `NULL` if `bit_len` is greater than the count of bits in the words. The error message and
+///   code can be found out with [sts_get_last_error].
+///
+/// ## Safety
+///
+/// * `words` must be aligned for `size_t` and valid for reads of up to `n_words` elements.
+/// * The memory referenced by `words` must not be mutated for the duration of this method call.
+/// * `words`, particularly the de-allocation of it, remains in the responsibility of the caller.
+/// * The de-allocation of the returned [BitVec] must be done via [sts_BitVec_destroy].
+#[no_mangle]
+pub unsafe extern "C" fn sts_BitVec_from_words(
+    words: *const usize,
+    n_words: usize,
+    bit_len: usize,
+) -> Option<Box<BitVec>> {
+    // SAFETY: same considerations apply to the call as for this function.
+    unsafe { bitvec_from_words(words, n_words, bit_len, ErrorSink::LastError) }
+}
+
+/// Same as [sts_BitVec_from_words], but an error is written to `error` instead of being stored as
+/// the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * The same requirements as for [sts_BitVec_from_words] apply.
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_BitVec_from_words_e(
+    words: *const usize,
+    n_words: usize,
+    bit_len: usize,
+    error: *mut *mut StsError,
+) -> Option<Box<BitVec>> {
+    // SAFETY: same considerations apply to the calls as for this function.
+    unsafe { bitvec_from_words(words, n_words, bit_len, ErrorSink::out(error)) }
+}
+
+/// Implementation of [sts_BitVec_from_words].
+///
+/// ## Safety
+///
+/// The same requirements as for [sts_BitVec_from_words] apply.
+unsafe fn bitvec_from_words(
+    words: *const usize,
+    n_words: usize,
+    bit_len: usize,
+    errors: ErrorSink,
+) -> Option<Box<BitVec>> {
+    // SAFETY: caller has to ensure that words is valid for reads up to n_words elements.
+    let words = unsafe { &*slice_from_raw_parts(words, n_words) };
+
+    match InternalBitVec::from_words(words, bit_len) {
+        Some(bitvec) => Some(Box::new(BitVec(bitvec))),
+        None => {
+            errors.report(sts_lib::Error::InvalidParameter(format!(
+                "The bit length {bit_len} is greater than the {} bits of {n_words} words",
+                n_words.saturating_mul(usize::BITS as usize)
+            )));
+            None
+        }
+    }
+}
+
+/// Creates a BitVec that borrows a buffer of packed words instead of copying it, e.g. to test a
+/// large buffer without doubling the memory usage. The words have the same layout as for
+/// [sts_BitVec_from_words]: a byte buffer can only be borrowed on big-endian platforms, where its
+/// layout is the same as the one of words.
+///
+/// The BitVec is a read-only view: the words are never written to. [sts_BitVec_crop] copies the
+/// remaining words if it has to clear bits in the new last word. Clones created with
+/// [sts_BitVec_clone] borrow the same words.
+///
+/// ## Parameters
+///
+/// * `words`: pointer to the word buffer
+/// * `n_words`: count of words (elements)
+/// * `bit_len`: count of bits to use, at most `n_words * 8 * sizeof(size_t)`. Words after it are
+///   ignored, the bits after it in the last used word must be 0.
+///
+/// ## Return value
+///
+/// * The created BitVec.
+/// * `NULL` if `bit_len` is greater than the count of bits in the words, or if a bit after
+///   `bit_len` in the last used word is 1. The error message and code can be found out with
+///   [sts_get_last_error].
+///
+/// ## Safety
+///
+/// * `words` must be aligned for `size_t` and valid for reads of up to `n_words` elements.
+/// * The memory referenced by `words` must neither be mutated nor de-allocated until the returned
+///   BitVec and all clones of it have been destroyed with [sts_BitVec_destroy].
+/// * `words`, particularly the de-allocation of it, remains in the responsibility of the caller.
+#[no_mangle]
+pub unsafe extern "C" fn sts_BitVec_borrow_words(
+    words: *const usize,
+    n_words: usize,
+    bit_len: usize,
+) -> Option<Box<BitVec>> {
+    // SAFETY: same considerations apply to the call as for this function.
+    unsafe { bitvec_borrow_words(words, n_words, bit_len, ErrorSink::LastError) }
+}
+
+/// Same as [sts_BitVec_borrow_words], but an error is written to `error` instead of being stored
+/// as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * The same requirements as for [sts_BitVec_borrow_words] apply.
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_BitVec_borrow_words_e(
+    words: *const usize,
+    n_words: usize,
+    bit_len: usize,
+    error: *mut *mut StsError,
+) -> Option<Box<BitVec>> {
+    // SAFETY: same considerations apply to the calls as for this function.
+    unsafe { bitvec_borrow_words(words, n_words, bit_len, ErrorSink::out(error)) }
+}
+
+/// Implementation of [sts_BitVec_borrow_words].
+///
+/// ## Safety
+///
+/// The same requirements as for [sts_BitVec_borrow_words] apply.
+unsafe fn bitvec_borrow_words(
+    words: *const usize,
+    n_words: usize,
+    bit_len: usize,
+    errors: ErrorSink,
+) -> Option<Box<BitVec>> {
+    // SAFETY: caller has to ensure that words is valid for reads up to n_words elements, and that
+    // the words outlive the BitVec and all clones of it.
+    let words: &'static [usize] = unsafe { &*slice_from_raw_parts(words, n_words) };
+
+    if bit_len > n_words.saturating_mul(usize::BITS as usize) {
+        errors.report(sts_lib::Error::InvalidParameter(format!(
+            "The bit length {bit_len} is greater than the {} bits of {n_words} words",
+            n_words.saturating_mul(usize::BITS as usize)
+        )));
+        return None;
+    }
+
+    match InternalBitVec::from_words_borrowed(words, bit_len) {
+        Some(bitvec) => Some(Box::new(BitVec(bitvec))),
+        None => {
+            errors.report(sts_lib::Error::InvalidParameter(format!(
+                "The bits after the bit length {bit_len} in the last word must be 0"
+            )));
+            None
+        }
+    }
+}
+
+/// Creates a BitVec by reading the file at the given path, interpreting its content in the given
+/// [InputFormat]. The file is read in chunks, only the resulting BitVec is held in memory.
+///
+/// ## Parameters
+///
+/// * `path`: the nul-terminated, UTF-8 encoded path of the file
+/// * `format`: the format of the file content, see [InputFormat]
+/// * `max_bits`: the maximum count of bits to read, the rest of the file is ignored. `0` means
+///   that the whole file is read.
+///
+/// ## Return value
+///
+/// * The created BitVec, if the file was read successfully.
+/// * `NULL` if the path is not valid UTF-8, the format is invalid, the file could not be read or
+///   its content is invalid for the given format. The error message and code can be found out
+///   with [sts_get_last_error].
+///
+/// ## Safety
+///
+/// * The memory pointed to by `path` must contain a valid nul terminator at the end of the string.
+/// * `path` must be valid, as defined by the Rust module safety documentation of `std::ptr`, for
+///   reads of bytes up to and including the nul terminator.
+/// * The memory referenced by `path` must not be mutated for the duration of this method call.
+/// * `path`, particularly the de-allocation of it, remains in the responsibility of the caller.
+/// * The de-allocation of the returned [BitVec] must be done via [sts_BitVec_destroy].
+#[no_mangle]
+pub unsafe extern "C" fn sts_BitVec_from_file(
+    path: *const c_char,
+    format: RawInputFormat,
+    max_bits: usize,
+) -> Option<Box<BitVec>> {
+    // SAFETY: same considerations apply to the call as for this function.
+    unsafe { bitvec_from_file(path, format, max_bits, ErrorSink::LastError) }
+}
+
+/// Same as [sts_BitVec_from_file], but an error is written to `error` instead of being stored as
+/// the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * The same requirements as for [sts_BitVec_from_file] apply.
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_BitVec_from_file_e(
+    path: *const c_char,
+    format: RawInputFormat,
+    max_bits: usize,
+    error: *mut *mut StsError,
+) -> Option<Box<BitVec>> {
+    // SAFETY: same considerations apply to the calls as for this function.
+    unsafe { bitvec_from_file(path, format, max_bits, ErrorSink::out(error)) }
+}
+
+/// Implementation of [sts_BitVec_from_file].
+///
+/// ## Safety
+///
+/// The same requirements as for [sts_BitVec_from_file] apply.
+unsafe fn bitvec_from_file(
+    path: *const c_char,
+    format: RawInputFormat,
+    max_bits: usize,
+    errors: ErrorSink,
+) -> Option<Box<BitVec>> {
+    // SAFETY: caller has to ensure that path is a valid nul-terminated string.
+    let path = unsafe { CStr::from_ptr(path) };
+    let Ok(path) = path.to_str() else {
+        errors.report(sts_lib::Error::InvalidParameter(
+            "The path is not valid UTF-8".to_owned(),
+        ));
+        return None;
+    };
+
+    let Ok(format) = InputFormat::try_from(format) else {
+        errors.report(sts_lib::Error::InvalidParameter(format!(
+            "The numerical value {format} is not a valid input format!"
+        )));
+        return None;
+    };
+
+    let result = File::open(path).and_then(|file| {
+        if max_bits == 0 {
+            InternalBitVec::from_reader(file, format.into())
+        } else {
+            InternalBitVec::from_reader_with_max_length(file, format.into(), max_bits)
+        }
+    });
+
+    match result {
+        Ok(bitvec) => Some(Box::new(BitVec(bitvec))),
+        Err(e) => {
+            errors.report(StsError::from_io_error(path, e));
+            None
+        }
+    }
+}
+
 /// Destroys a created BitVec.
 ///
 /// ## Safety
@@ -108,7 +447,8 @@ pub unsafe extern "C" fn sts_BitVec_clone(bitvec: &BitVec) -> Box<BitVec> {
 /// ## Safety
 ///
 /// * `bitvec` must have been created by either [sts_BitVec_from_str], [sts_BitVec_from_str_with_max_length],
-///   [sts_BitVec_from_bytes], [sts_BitVec_from_bits] or [sts_BitVec_clone].
+///   [sts_BitVec_from_bytes], [sts_BitVec_from_bits], [sts_BitVec_from_words],
+///   [sts_BitVec_borrow_words], [sts_BitVec_from_file], [sts_BitVec_clone] or [sts_BitVec_slice].
 /// * `bitvec` may be null.
 /// * There must be no other references to `bitvec`.
 /// * After this call, the memory referenced by `bitvec` is freed. Trying to access this memory
@@ -124,7 +464,8 @@ pub unsafe extern "C" fn sts_BitVec_destroy(bitvec: Option<Box<BitVec>>) {
 /// ## Safety
 ///
 /// * `bitvec` must have been created by either [sts_BitVec_from_str], [sts_BitVec_from_str_with_max_length],
-///   [sts_BitVec_from_bytes], [sts_BitVec_from_bits] or [sts_BitVec_clone].
+///   [sts_BitVec_from_bytes], [sts_BitVec_from_bits], [sts_BitVec_from_words],
+///   [sts_BitVec_borrow_words], [sts_BitVec_from_file], [sts_BitVec_clone] or [sts_BitVec_slice].
 /// * `bitvec` must be a valid, non-null pointer.
 /// * `bitvec` may not be mutated for the duration of this call.
 #[no_mangle]
@@ -138,10 +479,76 @@ pub unsafe extern "C" fn sts_BitVec_len_bit(bitvec: &BitVec) -> usize {
 /// ## Safety
 ///
 /// * `bitvec` must have been created by either [sts_BitVec_from_str], [sts_BitVec_from_str_with_max_length],
-///   [sts_BitVec_from_bytes], [sts_BitVec_from_bits] or [sts_BitVec_clone].
+///   [sts_BitVec_from_bytes], [sts_BitVec_from_bits], [sts_BitVec_from_words],
+///   [sts_BitVec_borrow_words], [sts_BitVec_from_file], [sts_BitVec_clone] or [sts_BitVec_slice].
 /// * `bitvec` must be a valid, non-null pointer.
 /// * `bitvec` may not be mutated by other functions for the duration of this call.
 #[no_mangle]
 pub unsafe extern "C" fn sts_BitVec_crop(bitvec: &mut BitVec, new_bit_len: usize) {
     bitvec.0.crop(new_bit_len)
 }
+
+/// Returns a new BitVec that contains a copy of the bits from index `start` (inclusive) to `end`
+/// (exclusive) of the given BitVec, e.g. to test only a part of the sequence. The given BitVec is
+/// unchanged.
+///
+/// The resulting pointer must be freed via [sts_BitVec_destroy].
+///
+/// ## Return value
+///
+/// * The new BitVec.
+/// * `NULL` if `start > end` or `end` is greater than the bit length of `bitvec`. The error
+///   message and code can be found out with [sts_get_last_error].
+///
+/// ## Safety
+///
+/// * `bitvec` must have been created by either [sts_BitVec_from_str], [sts_BitVec_from_str_with_max_length],
+///   [sts_BitVec_from_bytes], [sts_BitVec_from_bits], [sts_BitVec_from_words],
+///   [sts_BitVec_borrow_words], [sts_BitVec_from_file], [sts_BitVec_clone] or [sts_BitVec_slice].
+/// * `bitvec` must be a valid, non-null pointer.
+/// * `bitvec` may not be mutated for the duration of this call.
+#[no_mangle]
+pub unsafe extern "C" fn sts_BitVec_slice(
+    bitvec: &BitVec,
+    start: usize,
+    end: usize,
+) -> Option<Box<BitVec>> {
+    bitvec_slice(bitvec, start, end, ErrorSink::LastError)
+}
+
+/// Same as [sts_BitVec_slice], but an error is written to `error` instead of being stored as the
+/// last error, see [StsError].
+///
+/// ## Safety
+///
+/// * The same requirements as for [sts_BitVec_slice] apply.
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_BitVec_slice_e(
+    bitvec: &BitVec,
+    start: usize,
+    end: usize,
+    error: *mut *mut StsError,
+) -> Option<Box<BitVec>> {
+    // SAFETY: the caller has to ensure that error is valid.
+    bitvec_slice(bitvec, start, end, unsafe { ErrorSink::out(error) })
+}
+
+/// Implementation of [sts_BitVec_slice].
+fn bitvec_slice(
+    bitvec: &BitVec,
+    start: usize,
+    end: usize,
+    errors: ErrorSink,
+) -> Option<Box<BitVec>> {
+    let len_bit = bitvec.0.len_bit();
+
+    if start > end || end > len_bit {
+        errors.report(sts_lib::Error::InvalidParameter(format!(
+            "The range {start}..{end} is not valid for a BitVec of length {len_bit}"
+        )));
+        return None;
+    }
+
+    Some(Box::new(BitVec(bitvec.0.slice(start..end))))
+}
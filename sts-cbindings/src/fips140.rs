@@ -0,0 +1,235 @@
+//! The statistical power-up tests of FIPS 140-2, as a quick check before running the tests of
+//! SP 800-22.
+
+use crate::bitvec::BitVec;
+use crate::{ErrorSink, StsError};
+use std::ffi::c_int;
+use std::slice;
+use sts_lib::fips140::{
+    Fips140Result as InternalFips140Result, Fips140Test as InternalFips140Test,
+};
+
+// Type of a raw FIPS 140-2 test, used for the FFI boundary (rust doesn't like it if a value is
+// passed for an enum that is not in the enum).
+pub type RawFips140Test = c_int;
+
+/// The single tests of FIPS 140-2, see [sts_fips140_2_tests].
+/// cbindgen:prefix-with-name=true
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+pub enum Fips140Test {
+    /// The count of ones must lie in (9725, 10275).
+    Monobit = 0,
+    /// The statistic of the 4-bit segments must lie in (2.16, 46.17).
+    Poker = 1,
+    /// The count of runs of each length must lie within the bounds of FIPS 140-2.
+    Runs = 2,
+    /// There may be no run of 26 or more equal bits.
+    LongRun = 3,
+}
+
+impl From<Fips140Test> for InternalFips140Test {
+    fn from(value: Fips140Test) -> Self {
+        match value {
+            Fips140Test::Monobit => InternalFips140Test::Monobit,
+            Fips140Test::Poker => InternalFips140Test::Poker,
+            Fips140Test::Runs => InternalFips140Test::Runs,
+            Fips140Test::LongRun => InternalFips140Test::LongRun,
+        }
+    }
+}
+
+impl TryFrom<RawFips140Test> for Fips140Test {
+    type Error = ();
+
+    fn try_from(value: RawFips140Test) -> Result<Self, Self::Error> {
+        let test = match value {
+            0 => Fips140Test::Monobit,
+            1 => Fips140Test::Poker,
+            2 => Fips140Test::Runs,
+            3 => Fips140Test::LongRun,
+            _ => return Err(()),
+        };
+
+        Ok(test)
+    }
+}
+
+/// The result of the FIPS 140-2 tests, see [sts_fips140_2_tests].
+pub struct Fips140Result(InternalFips140Result);
+
+/// Runs the 4 statistical power-up tests of FIPS 140-2 (monobit, poker, runs and long run) on the
+/// first 20000 bits of the given data. **These tests are not part of SP 800-22**, but they are much
+/// faster and can be used as a quick check before running the full tests.
+///
+/// The resulting pointer must be freed via [sts_Fips140Result_destroy]. If the data is shorter
+/// than 20000 bits, `NULL` is returned - use [sts_get_last_error].
+///
+/// ## Safety
+///
+/// * `data` must have been created by one of the construction methods provided by this library.
+/// * `data` must be valid for reads and non-null.
+/// * `data` may not be mutated for the duration of this call.
+#[no_mangle]
+pub unsafe extern "C" fn sts_fips140_2_tests(data: &BitVec) -> Option<Box<Fips140Result>> {
+    fips140_2_tests(data, ErrorSink::LastError)
+}
+
+/// Same as [sts_fips140_2_tests], but an error is written to `error` instead of being stored as
+/// the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * The same requirements as for [sts_fips140_2_tests] apply.
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_fips140_2_tests_e(
+    data: &BitVec,
+    error: *mut *mut StsError,
+) -> Option<Box<Fips140Result>> {
+    // SAFETY: the caller has to ensure that error is valid.
+    fips140_2_tests(data, unsafe { ErrorSink::out(error) })
+}
+
+/// Implementation of [sts_fips140_2_tests].
+fn fips140_2_tests(data: &BitVec, errors: ErrorSink) -> Option<Box<Fips140Result>> {
+    match sts_lib::fips140::fips140_2_tests(&data.0) {
+        Ok(result) => Some(Box::new(Fips140Result(result))),
+        Err(e) => {
+            errors.report(e);
+            None
+        }
+    }
+}
+
+/// Destroys the given FIPS 140-2 result.
+///
+/// ## Safety
+///
+/// * `result` must have been created by [sts_fips140_2_tests].
+/// * `result` must be a valid, non-null pointer.
+/// * `result` will be invalid after this call, access will lead to undefined behaviour.
+#[no_mangle]
+pub unsafe extern "C" fn sts_Fips140Result_destroy(result: Option<Box<Fips140Result>>) {
+    _ = result;
+}
+
+/// Checks if all FIPS 140-2 tests passed - the combined verdict.
+///
+/// ## Safety
+///
+/// * `result` must have been created by [sts_fips140_2_tests].
+/// * `result` must be a valid pointer.
+/// * `result` may not be mutated for the duration of this call.
+#[no_mangle]
+pub unsafe extern "C" fn sts_Fips140Result_all_passed(result: &Fips140Result) -> bool {
+    result.0.all_passed()
+}
+
+/// Checks if the given FIPS 140-2 test passed.
+///
+/// ## Return value
+///
+/// * `1`: the test passed.
+/// * `0`: the test did not pass.
+/// * `-1`: the test is invalid. The error message and code can be found out with
+///   [sts_get_last_error].
+///
+/// ## Safety
+///
+/// * `result` must have been created by [sts_fips140_2_tests].
+/// * `result` must be a valid pointer.
+/// * `result` may not be mutated for the duration of this call.
+#[no_mangle]
+pub unsafe extern "C" fn sts_Fips140Result_passed(
+    result: &Fips140Result,
+    test: RawFips140Test,
+) -> c_int {
+    passed(result, test, ErrorSink::LastError)
+}
+
+/// Same as [sts_Fips140Result_passed], but an error is written to `error` instead of being stored
+/// as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * The same requirements as for [sts_Fips140Result_passed] apply.
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_Fips140Result_passed_e(
+    result: &Fips140Result,
+    test: RawFips140Test,
+    error: *mut *mut StsError,
+) -> c_int {
+    // SAFETY: the caller has to ensure that error is valid.
+    passed(result, test, unsafe { ErrorSink::out(error) })
+}
+
+/// Implementation of [sts_Fips140Result_passed].
+fn passed(result: &Fips140Result, test: RawFips140Test, errors: ErrorSink) -> c_int {
+    match Fips140Test::try_from(test) {
+        Ok(test) => result.0.passed(test.into()) as c_int,
+        Err(()) => {
+            errors.report(sts_lib::Error::InvalidParameter(format!(
+                "The numerical value {test} is not a valid FIPS 140-2 test!"
+            )));
+            -1
+        }
+    }
+}
+
+/// Returns the count of ones in the tested sample, used by the monobit test.
+///
+/// ## Safety
+///
+/// * `result` must have been created by [sts_fips140_2_tests].
+/// * `result` must be a valid pointer.
+/// * `result` may not be mutated for the duration of this call.
+#[no_mangle]
+pub unsafe extern "C" fn sts_Fips140Result_get_ones(result: &Fips140Result) -> usize {
+    result.0.ones()
+}
+
+/// Returns the statistic of the poker test.
+///
+/// ## Safety
+///
+/// * `result` must have been created by [sts_fips140_2_tests].
+/// * `result` must be a valid pointer.
+/// * `result` may not be mutated for the duration of this call.
+#[no_mangle]
+pub unsafe extern "C" fn sts_Fips140Result_get_poker_statistic(result: &Fips140Result) -> f64 {
+    result.0.poker_statistic()
+}
+
+/// Writes the count of runs of the given bit with length 1, 2, 3, 4, 5 and 6 or longer, used by
+/// the runs test, to `runs`.
+///
+/// ## Safety
+///
+/// * `result` must have been created by [sts_fips140_2_tests].
+/// * `result` must be a valid pointer.
+/// * `result` may not be mutated for the duration of this call.
+/// * `runs` must be a valid, non-null pointer, writable for 6 elements.
+#[no_mangle]
+pub unsafe extern "C" fn sts_Fips140Result_get_runs(
+    result: &Fips140Result,
+    bit: bool,
+    runs: *mut usize,
+) {
+    // SAFETY: the caller has to ensure that the pointer is valid for 6 elements.
+    let runs = unsafe { slice::from_raw_parts_mut(runs, 6) };
+    runs.copy_from_slice(&result.0.runs(bit));
+}
+
+/// Returns the length of the longest run in the tested sample, used by the long run test.
+///
+/// ## Safety
+///
+/// * `result` must have been created by [sts_fips140_2_tests].
+/// * `result` must be a valid pointer.
+/// * `result` may not be mutated for the duration of this call.
+#[no_mangle]
+pub unsafe extern "C" fn sts_Fips140Result_get_longest_run(result: &Fips140Result) -> usize {
+    result.0.longest_run()
+}
@@ -1,7 +1,9 @@
 #![doc = include_str!("../README.md")]
 
+pub mod analysis;
 pub mod bitvec;
 pub mod constants;
+pub mod fips140;
 pub mod test_args;
 pub mod test_result;
 pub mod test_runner;
@@ -9,14 +11,15 @@ pub mod tests;
 
 use crate::test_runner::test::RawTest;
 use std::cell::RefCell;
-use std::ffi::{c_char, c_int};
+use std::ffi::{c_char, c_int, CString};
 use std::num::NonZero;
-use std::slice;
+use std::{ptr, slice};
 use sts_lib::test_runner::RunnerError;
+use sts_lib::ArgError;
 
 thread_local! {
-    /// This variable stores the Display impl of the last error.
-    static LAST_ERROR: RefCell<(ErrorCode, String)> = const { RefCell::new((ErrorCode::NoError, String::new())) };
+    /// This variable stores the last error of the calling thread.
+    static LAST_ERROR: RefCell<Option<StsError>> = const { RefCell::new(None) };
 }
 
 /// Returns the last error that happened in the calling thread. This function works in 2 steps:
@@ -26,6 +29,8 @@ thread_local! {
 ///    the buffer. If the length is enough to store the error message, it is written to the buffer.
 ///    The error message is written with a nul-terminating byte.
 ///
+/// Errors of the `_e` variants of the fallible functions are not stored here, see [StsError].
+///
 /// ## Return values
 ///
 /// - >0: the [ErrorCode] of the last error. Everything worked.
@@ -40,14 +45,13 @@ thread_local! {
 /// * All responsibility for `ptr` and `len`, especially for its de-allocation, remains with the caller.
 #[no_mangle]
 pub unsafe extern "C" fn sts_get_last_error(ptr: *mut c_char, len: &mut usize) -> c_int {
-    // check if there is an error
-    if LAST_ERROR.with_borrow(|(e, _)| matches!(e, ErrorCode::NoError)) {
+    // check if there is an error, the needed length includes the nul byte
+    let Some((error_code, needed_length)) = LAST_ERROR.with_borrow(|e| {
+        e.as_ref()
+            .map(|e| (e.code, e.message.as_bytes_with_nul().len()))
+    }) else {
         return 0;
-    }
-
-    // LAST_ERROR is guaranteed to contain an error, we just checked. + 1 for the nul byte
-    let (error_code, needed_length) =
-        LAST_ERROR.with_borrow(|(error_code, msg)| (*error_code, msg.as_bytes().len() + 1));
+    };
 
     if ptr.is_null() {
         // caller only asks for the length
@@ -60,33 +64,194 @@ pub unsafe extern "C" fn sts_get_last_error(ptr: *mut c_char, len: &mut usize) -
         if *len < needed_length {
             -1
         } else {
-            // length is OK, write the String
-            // again: LAST_ERROR is guaranteed to contain a valid error.
-            let error_msg = LAST_ERROR.with_borrow_mut(|e| {
-                let mut value = (ErrorCode::NoError, String::new());
-                std::mem::swap(e, &mut value);
-                value.1
-            });
+            // length is OK, write the message
+            // LAST_ERROR is guaranteed to contain an error, we just checked.
+            let error = LAST_ERROR.take().expect("LAST_ERROR contains an error");
 
             // convert the buffer into a suitable type
             // SAFETY: it is the responsibility of the caller to ensure that the pointer is valid for
             //  writes of up to len bytes.
             let slice = unsafe { slice::from_raw_parts_mut(ptr as *mut u8, *len) };
-            // set last NUL byte
-            slice[*len - 1] = 0;
-            // set message
-            error_msg
-                .as_bytes()
-                .iter()
-                // it doesn't hurt to have the max length set explicitly
-                .zip(&mut slice[..(*len - 1)])
-                .for_each(|(input, output)| *output = *input);
+            // set message, including the nul byte
+            slice[..needed_length].copy_from_slice(error.message.as_bytes_with_nul());
 
             error_code as c_int
         }
     }
 }
 
+/// An error returned by the `_e` variants of the fallible functions, e.g. [sts_BitVec_from_file_e].
+///
+/// Each fallible function that reports its error with [sts_get_last_error] has a variant with the
+/// suffix `_e`, which takes an additional out-parameter `StsError **error`: if an error happens,
+/// an owned error is written to it, otherwise it is set to `NULL`. In contrast to
+/// [sts_get_last_error], the error is not bound to the calling thread and is not overwritten by
+/// later calls. If `error` is `NULL`, the error is discarded.
+///
+/// The [ErrorCode] of the error is returned by [sts_error_code], the error message by
+/// [sts_error_message]. The error must be freed with [sts_error_free].
+pub struct StsError {
+    code: ErrorCode,
+    message: CString,
+}
+
+impl StsError {
+    /// Creates a new error with the given code and message.
+    fn new(code: ErrorCode, message: String) -> Self {
+        // the messages contain no nul bytes, but user input (e.g. a path) might.
+        let message =
+            CString::new(message.replace('\0', "\\0")).expect("all nul bytes were replaced");
+
+        Self { code, message }
+    }
+
+    /// Creates the error from an IO error that happened while reading the given input file.
+    pub(crate) fn from_io_error(path: &str, error: std::io::Error) -> Self {
+        let msg = format!("Failed to read the input file \"{path}\": {error}");
+        Self::new(ErrorCode::InputFile, msg)
+    }
+
+    /// Creates the error from the specified errors that happened when running tests with the test
+    /// runner infrastructure.
+    pub(crate) fn test_failed(errors: Box<[(sts_lib::Test, sts_lib::Error)]>) -> Self {
+        let msg = format!("Test runner: one or multiple tests, failed, {errors:?}");
+        Self::new(ErrorCode::TestFailed, msg)
+    }
+
+    /// Creates an error about an invalid test (the given value was passed from FFI).
+    pub(crate) fn invalid_test(test_no: c_int) -> Self {
+        let msg = format!("The numerical value {test_no} is not a valid test!");
+        Self::new(ErrorCode::InvalidTest, msg)
+    }
+
+    /// Creates an error about the fact that the specified test was not run.
+    pub(crate) fn test_was_not_run(test: sts_lib::Test) -> Self {
+        let msg = format!("The test {test} was not run!");
+        Self::new(ErrorCode::TestWasNotRun, msg)
+    }
+
+    /// Creates an error about the fact that the specified test was not run on the specified
+    /// partition.
+    pub(crate) fn partition_was_not_run(test: sts_lib::Test, partition: usize) -> Self {
+        let msg = format!("The test {test} was not run on partition {partition}!");
+        Self::new(ErrorCode::TestWasNotRun, msg)
+    }
+}
+
+impl From<sts_lib::Error> for StsError {
+    fn from(error: sts_lib::Error) -> Self {
+        let code = match error {
+            sts_lib::Error::Overflow(_) => ErrorCode::Overflow,
+            sts_lib::Error::NaN => ErrorCode::NaN,
+            sts_lib::Error::Infinite => ErrorCode::Infinite,
+            sts_lib::Error::GammaFunctionFailed(_) => ErrorCode::GammaFunctionFailed,
+            sts_lib::Error::InvalidParameter(_) => ErrorCode::InvalidParameter,
+            sts_lib::Error::Cancelled => ErrorCode::Cancelled,
+            sts_lib::Error::TimedOut(_) => ErrorCode::TimedOut,
+        };
+
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<ArgError> for StsError {
+    fn from(error: ArgError) -> Self {
+        Self::new(ErrorCode::InvalidArgument, error.to_string())
+    }
+}
+
+impl From<RunnerError> for StsError {
+    fn from(error: RunnerError) -> Self {
+        let only_duplicates = error
+            .problems()
+            .iter()
+            .all(|problem| matches!(problem, RunnerError::DuplicateTest(_)));
+        let code = if only_duplicates {
+            ErrorCode::DuplicateTest
+        } else {
+            ErrorCode::InvalidRunnerInput
+        };
+
+        Self::new(code, error.to_string())
+    }
+}
+
+/// Where a fallible function reports its error: the last error of the calling thread, see
+/// [sts_get_last_error], or the out-parameter of an `_e` variant, see [StsError].
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum ErrorSink {
+    /// The error is stored as the last error of the calling thread.
+    LastError,
+    /// The error is written to the out-parameter, if it is not `NULL`. Only created with
+    /// [ErrorSink::out].
+    Out(*mut *mut StsError),
+}
+
+impl ErrorSink {
+    /// Creates the sink for the out-parameter `error` of an `_e` variant, setting it to `NULL`.
+    ///
+    /// ## Safety
+    ///
+    /// * `error` must either be `NULL` or valid for writes for the duration of the calling
+    ///   function.
+    pub(crate) unsafe fn out(error: *mut *mut StsError) -> Self {
+        if !error.is_null() {
+            // SAFETY: the caller has to ensure that the pointer is valid for writes.
+            unsafe { error.write(ptr::null_mut()) };
+        }
+
+        Self::Out(error)
+    }
+
+    /// Reports the given error.
+    pub(crate) fn report(self, error: impl Into<StsError>) {
+        let error = error.into();
+
+        match self {
+            ErrorSink::LastError => LAST_ERROR.set(Some(error)),
+            ErrorSink::Out(out) if out.is_null() => {}
+            // SAFETY: the pointer was checked by ErrorSink::out.
+            ErrorSink::Out(out) => unsafe { out.write(Box::into_raw(Box::new(error))) },
+        }
+    }
+}
+
+/// Returns the [ErrorCode] of the given error.
+///
+/// ## Safety
+///
+/// * `error` must have been returned by one of the `_e` functions, see [StsError].
+/// * `error` must be a valid, non-null pointer.
+#[no_mangle]
+pub unsafe extern "C" fn sts_error_code(error: &StsError) -> ErrorCode {
+    error.code
+}
+
+/// Returns the nul-terminated, human-readable message of the given error. The message is owned by
+/// the error, it is valid until the error is freed with [sts_error_free].
+///
+/// ## Safety
+///
+/// * `error` must have been returned by one of the `_e` functions, see [StsError].
+/// * `error` must be a valid, non-null pointer.
+#[no_mangle]
+pub unsafe extern "C" fn sts_error_message(error: &StsError) -> *const c_char {
+    error.message.as_ptr()
+}
+
+/// Frees the given error.
+///
+/// ## Safety
+///
+/// * `error` must have been returned by one of the `_e` functions, see [StsError].
+/// * `error` may be null.
+/// * `error` and its message will be invalid after this call, access will lead to undefined
+///   behaviour.
+#[no_mangle]
+pub unsafe extern "C" fn sts_error_free(error: Option<Box<StsError>>) {
+    _ = error;
+}
+
 /// Sets the maximum of threads to be used by the tests. These method can only be called ONCE and only
 /// BEFORE any test is started. If not used, a sane default will be chosen.
 ///
@@ -98,15 +263,33 @@ pub unsafe extern "C" fn sts_get_last_error(ptr: *mut c_char, len: &mut usize) -
 /// * 1: an error happened - use [sts_get_last_error]
 #[no_mangle]
 pub extern "C" fn sts_set_max_threads(max_threads: usize) -> c_int {
+    set_max_threads(max_threads, ErrorSink::LastError)
+}
+
+/// Same as [sts_set_max_threads], but an error is written to `error` instead of being stored as
+/// the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_set_max_threads_e(
+    max_threads: usize,
+    error: *mut *mut StsError,
+) -> c_int {
+    // SAFETY: the caller has to ensure that error is valid.
+    set_max_threads(max_threads, unsafe { ErrorSink::out(error) })
+}
+
+/// Implementation of [sts_set_max_threads].
+fn set_max_threads(max_threads: usize, errors: ErrorSink) -> c_int {
     let max_threads = match NonZero::new(max_threads) {
         Some(max_threads) => max_threads,
         None => {
-            LAST_ERROR.with_borrow_mut(|err| {
-                *err = (
-                    ErrorCode::SetMaxThreads,
-                    "0 is not a valid thread count".to_owned(),
-                )
-            });
+            errors.report(StsError::new(
+                ErrorCode::SetMaxThreads,
+                "0 is not a valid thread count".to_owned(),
+            ));
             return 1;
         }
     };
@@ -114,14 +297,15 @@ pub extern "C" fn sts_set_max_threads(max_threads: usize) -> c_int {
     match sts_lib::set_max_threads(max_threads) {
         Ok(()) => 0,
         Err(e) => {
-            LAST_ERROR.with_borrow_mut(|err| *err = (ErrorCode::SetMaxThreads, e.to_string()));
+            errors.report(StsError::new(ErrorCode::SetMaxThreads, e.to_string()));
             1
         }
     }
 }
 
 /// The error codes that are returned by some fallible functions.
-/// A human-readable error message can be retrieved with [sts_get_last_error].
+/// A human-readable error message can be retrieved with [sts_get_last_error] or
+/// [sts_error_message].
 /// cbindgen:prefix-with-name=true
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
@@ -148,6 +332,19 @@ pub enum ErrorCode {
     TestFailed = 9,
     /// The test whose result was tried to be retrieved from the test runner was not run.
     TestWasNotRun = 10,
+    /// The test was cancelled with a cancellation token, see [sts_CancellationToken_cancel].
+    Cancelled = 11,
+    /// Reading an input file failed, or its content is invalid for the given format.
+    InputFile = 12,
+    /// The test took longer than the timeout of the test arguments, see
+    /// [sts_RunnerTestArgs_set_timeout].
+    TimedOut = 13,
+    /// The input passed to the test runner is too short for one of the tests, or doesn't work with
+    /// the arguments of one of the tests. All problems are listed in the error message.
+    InvalidRunnerInput = 14,
+    /// A test argument could not be created, because one of the given values is not allowed. The
+    /// error message states the value and the allowed values.
+    InvalidArgument = 15,
 }
 
 /// Returns the minimum input length, in bits, for the specified test.
@@ -158,13 +355,33 @@ pub enum ErrorCode {
 /// * 0: an error happened - use [sts_get_last_error]
 #[no_mangle]
 pub extern "C" fn sts_get_min_length_for_test(test: RawTest) -> usize {
+    get_min_length_for_test(test, ErrorSink::LastError)
+}
+
+/// Same as [sts_get_min_length_for_test], but an error is written to `error` instead of being
+/// stored as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_get_min_length_for_test_e(
+    test: RawTest,
+    error: *mut *mut StsError,
+) -> usize {
+    // SAFETY: the caller has to ensure that error is valid.
+    get_min_length_for_test(test, unsafe { ErrorSink::out(error) })
+}
+
+/// Implementation of [sts_get_min_length_for_test].
+fn get_min_length_for_test(test: RawTest, errors: ErrorSink) -> usize {
     let raw_test = test;
     let test = crate::test_runner::test::Test::try_from(raw_test);
 
     let test = match test {
         Ok(test) => test,
         Err(()) => {
-            set_last_invalid_test(raw_test);
+            errors.report(StsError::invalid_test(raw_test));
             return 0;
         }
     };
@@ -173,45 +390,46 @@ pub extern "C" fn sts_get_min_length_for_test(test: RawTest) -> usize {
     sts_lib::get_min_length_for_test(test.into()).get()
 }
 
-/// Sets the last error from the specified [sts_lib::Error].
-fn set_last_from_error(error: sts_lib::Error) {
-    let (code, msg) = match error {
-        e @ sts_lib::Error::Overflow(_) => (ErrorCode::Overflow, e.to_string()),
-        e @ sts_lib::Error::NaN => (ErrorCode::NaN, e.to_string()),
-        e @ sts_lib::Error::Infinite => (ErrorCode::Infinite, e.to_string()),
-        e @ sts_lib::Error::GammaFunctionFailed(_) => {
-            (ErrorCode::GammaFunctionFailed, e.to_string())
-        }
-        e @ sts_lib::Error::InvalidParameter(_) => (ErrorCode::InvalidParameter, e.to_string()),
-    };
-
-    LAST_ERROR.with_borrow_mut(|e| *e = (code, msg));
+/// Returns the input length, in bits, recommended by NIST for the specified test. Inputs that are
+/// at least as long as the minimum length (see [sts_get_min_length_for_test]), but shorter than the
+/// recommended length, can be tested, but the result is less reliable.
+///
+/// ## Return values
+///
+/// * >0: the call worked. Returned is the recommended input length
+/// * 0: an error happened - use [sts_get_last_error]
+#[no_mangle]
+pub extern "C" fn sts_get_recommended_length_for_test(test: RawTest) -> usize {
+    get_recommended_length_for_test(test, ErrorSink::LastError)
 }
 
-/// Sets the last error from the specified [RunnerError].
-fn set_last_from_runner_error(error: RunnerError) {
-    LAST_ERROR.with_borrow_mut(|e| *e = (ErrorCode::DuplicateTest, error.to_string()));
+/// Same as [sts_get_recommended_length_for_test], but an error is written to `error` instead of
+/// being stored as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_get_recommended_length_for_test_e(
+    test: RawTest,
+    error: *mut *mut StsError,
+) -> usize {
+    // SAFETY: the caller has to ensure that error is valid.
+    get_recommended_length_for_test(test, unsafe { ErrorSink::out(error) })
 }
 
-/// Sets the last error from the specified errors that happened when running test
-/// with the test runner infrastructure.
-fn set_last_from_test_failed(error: Box<[(sts_lib::Test, sts_lib::Error)]>) {
-    LAST_ERROR.with_borrow_mut(|e| {
-        *e = (
-            ErrorCode::TestFailed,
-            format!("Test runner: one or multiple tests, failed, {error:?}"),
-        )
-    })
-}
+/// Implementation of [sts_get_recommended_length_for_test].
+fn get_recommended_length_for_test(test: RawTest, errors: ErrorSink) -> usize {
+    let raw_test = test;
+    let test = crate::test_runner::test::Test::try_from(raw_test);
 
-/// Sets the last error to be about an invalid test (the given value was passed from FFI).
-fn set_last_invalid_test(test_no: c_int) {
-    let msg = format!("The numerical value {test_no} is not a valid test!");
-    LAST_ERROR.with_borrow_mut(|e| *e = (ErrorCode::InvalidTest, msg));
-}
+    let test = match test {
+        Ok(test) => test,
+        Err(()) => {
+            errors.report(StsError::invalid_test(raw_test));
+            return 0;
+        }
+    };
 
-/// Sets the last error to be about the fact that the specified test was not run.
-fn set_last_test_was_not_run(test: sts_lib::Test) {
-    let msg = format!("The test {test} was not run!");
-    LAST_ERROR.with_borrow_mut(|e| *e = (ErrorCode::TestWasNotRun, msg));
+    sts_lib::get_recommended_length_for_test(test.into()).get()
 }
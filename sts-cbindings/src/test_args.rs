@@ -1,10 +1,14 @@
 //! Wrapper types for the test arguments.
 
+use crate::{ErrorSink, StsError};
+use std::ffi::c_int;
 use std::num::NonZero;
 use sts_lib::tests::{
-    approximate_entropy, frequency_block, linear_complexity, serial,
+    approximate_entropy, autocorrelation, binary_matrix_rank, frequency_block, linear_complexity,
+    longest_run_of_ones, random_excursions, random_excursions_variant, serial, spectral_dft,
     template_matching::{non_overlapping, overlapping},
 };
+use sts_lib::ArgError;
 
 /// Macro for automatically creating the necessary struct for a test argument.
 macro_rules! test_arg {
@@ -52,6 +56,11 @@ macro_rules! test_arg {
     }
 }
 
+/// Boxes the created argument, or reports the error and returns `NULL`.
+fn boxed_arg<T>(result: Result<T, ArgError>, errors: ErrorSink) -> Option<Box<T>> {
+    result.map(Box::new).map_err(|e| errors.report(e)).ok()
+}
+
 // frequency test within a block
 test_arg! {
     /// The argument for the Frequency test within a block: the block length.
@@ -83,12 +92,236 @@ pub extern "C" fn sts_TestArgFrequencyBlock_new(
     })
 }
 
+// Type of a raw selection strategy, used for the FFI boundary (rust doesn't like it if a value is
+// passed for an enum that is not in the enum).
+pub type RawSelectionStrategy = c_int;
+
+/// The strategies to choose the block length of the Frequency test within a block automatically,
+/// see [sts_TestArgFrequencyBlock_new_with_strategy].
+///
+/// All strategies choose a block length of at least 20 bits, greater than 1% of the input length,
+/// so that there are fewer than 100 blocks - if the input is long enough for at least 2 blocks.
+/// cbindgen:prefix-with-name=true
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+pub enum SelectionStrategy {
+    /// The smallest recommended block length, rounded up to a multiple of the word size if there
+    /// are still at least 2 blocks. This is the strategy of [sts_TestArgFrequencyBlock_default].
+    NistRecommended = 0,
+    /// The smallest recommended block length, which gives the most blocks.
+    MaximizeBlocks = 1,
+    /// The smallest recommended block length that is a power of two, if there are still at least
+    /// 2 blocks.
+    PowerOfTwo = 2,
+}
+
+impl From<SelectionStrategy> for frequency_block::SelectionStrategy {
+    fn from(value: SelectionStrategy) -> Self {
+        match value {
+            SelectionStrategy::NistRecommended => {
+                frequency_block::SelectionStrategy::NistRecommended
+            }
+            SelectionStrategy::MaximizeBlocks => frequency_block::SelectionStrategy::MaximizeBlocks,
+            SelectionStrategy::PowerOfTwo => frequency_block::SelectionStrategy::PowerOfTwo,
+        }
+    }
+}
+
+impl TryFrom<RawSelectionStrategy> for SelectionStrategy {
+    type Error = ();
+
+    fn try_from(value: RawSelectionStrategy) -> Result<Self, Self::Error> {
+        let strategy = match value {
+            0 => SelectionStrategy::NistRecommended,
+            1 => SelectionStrategy::MaximizeBlocks,
+            2 => SelectionStrategy::PowerOfTwo,
+            _ => return Err(()),
+        };
+
+        Ok(strategy)
+    }
+}
+
+/// Creates a new argument for the Frequency test within a block that chooses the block length
+/// automatically with the given strategy.
+///
+/// ## Return values
+/// - if the given `strategy` is invalid, `NULL` is returned.
+/// - else, a pointer to the argument is returned.
+#[no_mangle]
+pub extern "C" fn sts_TestArgFrequencyBlock_new_with_strategy(
+    strategy: RawSelectionStrategy,
+) -> Option<Box<TestArgFrequencyBlock>> {
+    SelectionStrategy::try_from(strategy).ok().map(|strategy| {
+        let arg = frequency_block::FrequencyBlockTestArg::ChooseWithStrategy(strategy.into());
+        Box::new(TestArgFrequencyBlock(arg))
+    })
+}
+
+/// Returns the block length the Frequency test within a block uses for an input with the given
+/// length in bits.
+///
+/// ## Safety
+///
+/// * `arg` must have been created by one of the construction methods provided by this library.
+/// * `arg` must be a valid pointer.
+/// * `arg` may not be mutated for the duration of this call.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestArgFrequencyBlock_block_length(
+    arg: &TestArgFrequencyBlock,
+    length: usize,
+) -> usize {
+    arg.0.block_length(length)
+}
+
+// longest run of ones test
+test_arg! {
+    /// The argument for the Test for the Longest Run of Ones in a Block: the configuration of
+    /// SP 800-22, section 2.4.2, given by the block length *M*: 8, 128 or 10000 bits.
+    ///
+    /// Each configuration has a minimum input length: 128, 6272 and 750000 bits. This constraint is
+    /// checked on executing the test. If the constraint is violated, an error will be raised.
+    struct TestArgLongestRunOfOnes(longest_run_of_ones::LongestRunOfOnesTestArg);
+
+    /// Creates a default argument for the Test for the Longest Run of Ones in a Block that chooses
+    /// the configuration based on the input length, as recommended by SP 800-22.
+    fn sts_TestArgLongestRunOfOnes_default() -> Self;
+
+    /// Destroys the given argument for the Test for the Longest Run of Ones in a Block.
+    fn sts_TestArgLongestRunOfOnes_destroy(self);
+}
+
+/// Creates a new argument for the Test for the Longest Run of Ones in a Block, specifying the
+/// block length in bits, which selects the configuration.
+///
+/// ## Return values
+/// - if the given `block_length` is not 8, 128 or 10000, `NULL` is returned - use
+///   [sts_get_last_error].
+/// - otherwise, a pointer to the argument is returned.
+#[no_mangle]
+pub extern "C" fn sts_TestArgLongestRunOfOnes_new(
+    block_length: usize,
+) -> Option<Box<TestArgLongestRunOfOnes>> {
+    boxed_arg(
+        longest_run_of_ones::LongestRunOfOnesTestArg::try_new(block_length)
+            .map(TestArgLongestRunOfOnes),
+        ErrorSink::LastError,
+    )
+}
+
+/// Same as [sts_TestArgLongestRunOfOnes_new], but an error is written to `error` instead of being
+/// stored as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestArgLongestRunOfOnes_new_e(
+    block_length: usize,
+    error: *mut *mut StsError,
+) -> Option<Box<TestArgLongestRunOfOnes>> {
+    // SAFETY: the caller has to ensure that error is valid.
+    boxed_arg(
+        longest_run_of_ones::LongestRunOfOnesTestArg::try_new(block_length)
+            .map(TestArgLongestRunOfOnes),
+        unsafe { ErrorSink::out(error) },
+    )
+}
+
+// binary matrix rank test
+test_arg! {
+    /// The argument for the Binary Matrix Rank Test: the dimensions of the matrices, *M* rows and
+    /// *Q* columns.
+    ///
+    /// Argument constraints:
+    /// 1. the rows and columns must each be between 2 and 64.
+    /// 2. each of the 3 rank classes must be expected at least 5 times, for 32x32 matrices this
+    ///    means at least 38 matrices.
+    ///
+    /// Constraint 1 is checked when creating the argument. Constraint 2 is checked on executing
+    /// the test. If the constraint is violated, the result has a P-value of 0 and a comment.
+    struct TestArgBinaryMatrixRank(binary_matrix_rank::BinaryMatrixRankTestArg);
+
+    /// Creates a default argument for the Binary Matrix Rank Test: 32x32 matrices, as used by
+    /// SP 800-22.
+    fn sts_TestArgBinaryMatrixRank_default() -> Self;
+
+    /// Destroys the given argument for the Binary Matrix Rank Test.
+    fn sts_TestArgBinaryMatrixRank_destroy(self);
+}
+
+/// Creates a new argument for the Binary Matrix Rank Test with the given count of rows and
+/// columns of each matrix.
+///
+/// ## Return values
+/// - if `rows` or `cols` is not between 2 and 64, `NULL` is returned - use [sts_get_last_error].
+/// - otherwise, a pointer to the argument is returned.
+#[no_mangle]
+pub extern "C" fn sts_TestArgBinaryMatrixRank_new(
+    rows: usize,
+    cols: usize,
+) -> Option<Box<TestArgBinaryMatrixRank>> {
+    boxed_arg(
+        binary_matrix_rank::BinaryMatrixRankTestArg::try_new(rows, cols)
+            .map(TestArgBinaryMatrixRank),
+        ErrorSink::LastError,
+    )
+}
+
+/// Same as [sts_TestArgBinaryMatrixRank_new], but an error is written to `error` instead of being
+/// stored as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestArgBinaryMatrixRank_new_e(
+    rows: usize,
+    cols: usize,
+    error: *mut *mut StsError,
+) -> Option<Box<TestArgBinaryMatrixRank>> {
+    // SAFETY: the caller has to ensure that error is valid.
+    boxed_arg(
+        binary_matrix_rank::BinaryMatrixRankTestArg::try_new(rows, cols)
+            .map(TestArgBinaryMatrixRank),
+        unsafe { ErrorSink::out(error) },
+    )
+}
+
+// spectral dft test
+test_arg! {
+    /// The argument for the Spectral DFT Test: how the 95 % peak height threshold *T* is derived.
+    ///
+    /// The original SP 800-22 used the constant *T = sqrt(3n)*, which is known to be biased.
+    /// The current revision of SP 800-22 uses *T = sqrt(ln(1/0.05) n)*, as corrected by Kim,
+    /// Umeno and Hasegawa.
+    struct TestArgSpectralDft(spectral_dft::SpectralDftTestArg);
+
+    /// Creates a default argument for the Spectral DFT Test, using the corrected threshold of Kim,
+    /// Umeno and Hasegawa, as used by the current revision of SP 800-22.
+    fn sts_TestArgSpectralDft_default() -> Self;
+
+    /// Destroys the given argument for the Spectral DFT Test.
+    fn sts_TestArgSpectralDft_destroy(self);
+}
+
+/// Creates a new argument for the Spectral DFT Test, using the biased threshold constant of the
+/// original SP 800-22, *T = sqrt(3n)*. Only use this to compare results with old implementations.
+///
+/// This function never returns `NULL`.
+#[no_mangle]
+pub extern "C" fn sts_TestArgSpectralDft_new_original_nist() -> Box<TestArgSpectralDft> {
+    Box::new(TestArgSpectralDft(
+        spectral_dft::PeakThreshold::OriginalNist.into(),
+    ))
+}
+
 // non-overlapping template matching
 test_arg! {
     /// The arguments for the Non-overlapping Template Matching Test.
     ///
     /// 1. The templates length to use within a block: `m`.
-    ///    2 <= `m` <= 21 - recommended: 9.
+    ///    2 <= `m` <= 31 - recommended: 9.
     /// 2. The number of independent blocks to test in the sequence: `N`
     ///    1 <= `N` < 100 - recommended: 8
     ///
@@ -111,14 +344,37 @@ test_arg! {
 ///
 /// ## Return values.
 /// * If both arguments are within the bounds specified in [TestArgNonOverlappingTemplate]: the new argument.
-/// * Otherwise: `NULL`
+/// * Otherwise: `NULL` - use [sts_get_last_error]
 #[no_mangle]
 pub extern "C" fn sts_TestArgNonOverlappingTemplate_new(
     template_len: usize,
     count_blocks: usize,
 ) -> Option<Box<TestArgNonOverlappingTemplate>> {
-    non_overlapping::NonOverlappingTemplateTestArgs::new(template_len, count_blocks)
-        .map(|arg| Box::new(TestArgNonOverlappingTemplate(arg)))
+    boxed_arg(
+        non_overlapping::NonOverlappingTemplateTestArgs::try_new(template_len, count_blocks)
+            .map(TestArgNonOverlappingTemplate),
+        ErrorSink::LastError,
+    )
+}
+
+/// Same as [sts_TestArgNonOverlappingTemplate_new], but an error is written to `error` instead of
+/// being stored as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestArgNonOverlappingTemplate_new_e(
+    template_len: usize,
+    count_blocks: usize,
+    error: *mut *mut StsError,
+) -> Option<Box<TestArgNonOverlappingTemplate>> {
+    // SAFETY: the caller has to ensure that error is valid.
+    boxed_arg(
+        non_overlapping::NonOverlappingTemplateTestArgs::try_new(template_len, count_blocks)
+            .map(TestArgNonOverlappingTemplate),
+        unsafe { ErrorSink::out(error) },
+    )
 }
 
 // overlapping template matching
@@ -150,15 +406,39 @@ test_arg! {
 ///
 /// ## Return values.
 /// * If all arguments are within the bounds specified in [TestArgOverlappingTemplate]: the new argument.
-/// * Otherwise: `NULL`
+/// * Otherwise: `NULL` - use [sts_get_last_error]
 #[no_mangle]
 pub extern "C" fn sts_TestArgOverlappingTemplate_new(
     template_length: usize,
     block_length: usize,
     freedom: usize,
 ) -> Option<Box<TestArgOverlappingTemplate>> {
-    overlapping::OverlappingTemplateTestArgs::new(template_length, block_length, freedom)
-        .map(|arg| Box::new(TestArgOverlappingTemplate(arg)))
+    boxed_arg(
+        overlapping::OverlappingTemplateTestArgs::try_new(template_length, block_length, freedom)
+            .map(TestArgOverlappingTemplate),
+        ErrorSink::LastError,
+    )
+}
+
+/// Same as [sts_TestArgOverlappingTemplate_new], but an error is written to `error` instead of
+/// being stored as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestArgOverlappingTemplate_new_e(
+    template_length: usize,
+    block_length: usize,
+    freedom: usize,
+    error: *mut *mut StsError,
+) -> Option<Box<TestArgOverlappingTemplate>> {
+    // SAFETY: the caller has to ensure that error is valid.
+    boxed_arg(
+        overlapping::OverlappingTemplateTestArgs::try_new(template_length, block_length, freedom)
+            .map(TestArgOverlappingTemplate),
+        unsafe { ErrorSink::out(error) },
+    )
 }
 
 /// Creates a new Overlapping Template Matching Test argument with the specified template length,
@@ -168,13 +448,35 @@ pub extern "C" fn sts_TestArgOverlappingTemplate_new(
 ///
 /// ## Return values.
 /// * If the argument is within the specified bounds: the new argument.
-/// * Otherwise: `NULL`
+/// * Otherwise: `NULL` - use [sts_get_last_error]
 #[no_mangle]
 pub extern "C" fn sts_TestArgOverlappingTemplate_new_nist_behaviour(
     template_length: usize,
 ) -> Option<Box<TestArgOverlappingTemplate>> {
-    overlapping::OverlappingTemplateTestArgs::new_nist_behaviour(template_length)
-        .map(|arg| Box::new(TestArgOverlappingTemplate(arg)))
+    boxed_arg(
+        overlapping::OverlappingTemplateTestArgs::try_new_nist_behaviour(template_length)
+            .map(TestArgOverlappingTemplate),
+        ErrorSink::LastError,
+    )
+}
+
+/// Same as [sts_TestArgOverlappingTemplate_new_nist_behaviour], but an error is written to `error`
+/// instead of being stored as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestArgOverlappingTemplate_new_nist_behaviour_e(
+    template_length: usize,
+    error: *mut *mut StsError,
+) -> Option<Box<TestArgOverlappingTemplate>> {
+    // SAFETY: the caller has to ensure that error is valid.
+    boxed_arg(
+        overlapping::OverlappingTemplateTestArgs::try_new_nist_behaviour(template_length)
+            .map(TestArgOverlappingTemplate),
+        unsafe { ErrorSink::out(error) },
+    )
 }
 
 // linear complexity test
@@ -248,10 +550,31 @@ test_arg! {
 /// ## Return value
 ///
 /// * if the given block length satisfies the constraints: the new argument.
-/// * otherwise: `NULL`
+/// * otherwise: `NULL` - use [sts_get_last_error]
 #[no_mangle]
 pub extern "C" fn sts_TestArgSerial_new(block_length: u8) -> Option<Box<TestArgSerial>> {
-    serial::SerialTestArg::new(block_length).map(|arg| Box::new(TestArgSerial(arg)))
+    boxed_arg(
+        serial::SerialTestArg::try_new(block_length).map(TestArgSerial),
+        ErrorSink::LastError,
+    )
+}
+
+/// Same as [sts_TestArgSerial_new], but an error is written to `error` instead of being stored as
+/// the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestArgSerial_new_e(
+    block_length: u8,
+    error: *mut *mut StsError,
+) -> Option<Box<TestArgSerial>> {
+    // SAFETY: the caller has to ensure that error is valid.
+    boxed_arg(
+        serial::SerialTestArg::try_new(block_length).map(TestArgSerial),
+        unsafe { ErrorSink::out(error) },
+    )
 }
 
 // approximate entropy test
@@ -287,11 +610,195 @@ test_arg! {
 /// ## Return value
 ///
 /// * if the given block length satisfies the constraints: the new argument.
-/// * otherwise: `NULL`
+/// * otherwise: `NULL` - use [sts_get_last_error]
 #[no_mangle]
 pub extern "C" fn sts_TestArgApproximateEntropy_new(
     block_length: u8,
 ) -> Option<Box<TestArgApproximateEntropy>> {
-    approximate_entropy::ApproximateEntropyTestArg::new(block_length)
-        .map(|arg| Box::new(TestArgApproximateEntropy(arg)))
+    boxed_arg(
+        approximate_entropy::ApproximateEntropyTestArg::try_new(block_length)
+            .map(TestArgApproximateEntropy),
+        ErrorSink::LastError,
+    )
+}
+
+/// Same as [sts_TestArgApproximateEntropy_new], but an error is written to `error` instead of being
+/// stored as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestArgApproximateEntropy_new_e(
+    block_length: u8,
+    error: *mut *mut StsError,
+) -> Option<Box<TestArgApproximateEntropy>> {
+    // SAFETY: the caller has to ensure that error is valid.
+    boxed_arg(
+        approximate_entropy::ApproximateEntropyTestArg::try_new(block_length)
+            .map(TestArgApproximateEntropy),
+        unsafe { ErrorSink::out(error) },
+    )
+}
+
+// random excursions test
+test_arg! {
+    /// The argument for the Random Excursions Test: the maximum state *x* to test. All states
+    /// `-x..=-1` and `+1..=+x` are tested, with one result per state.
+    ///
+    /// The maximum state must be between 1 and 32, this is checked when creating the argument.
+    ///
+    /// The default value for this argument is 4, as used by NIST.
+    struct TestArgRandomExcursions(random_excursions::RandomExcursionsTestArg);
+
+    /// Creates a default argument for the Random Excursions Test, with the maximum state set to 4.
+    fn sts_TestArgRandomExcursions_default() -> Self;
+
+    /// Destroys the given argument for the Random Excursions Test.
+    fn sts_TestArgRandomExcursions_destroy(self);
+}
+
+/// Creates a new argument for the Random Excursions Test, specifying the maximum state.
+///
+/// ## Return value
+///
+/// * if the given maximum state is between 1 and 32: the new argument.
+/// * otherwise: `NULL` - use [sts_get_last_error]
+#[no_mangle]
+pub extern "C" fn sts_TestArgRandomExcursions_new(
+    max_state: u8,
+) -> Option<Box<TestArgRandomExcursions>> {
+    boxed_arg(
+        random_excursions::RandomExcursionsTestArg::try_new(max_state).map(TestArgRandomExcursions),
+        ErrorSink::LastError,
+    )
+}
+
+/// Same as [sts_TestArgRandomExcursions_new], but an error is written to `error` instead of being
+/// stored as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestArgRandomExcursions_new_e(
+    max_state: u8,
+    error: *mut *mut StsError,
+) -> Option<Box<TestArgRandomExcursions>> {
+    // SAFETY: the caller has to ensure that error is valid.
+    boxed_arg(
+        random_excursions::RandomExcursionsTestArg::try_new(max_state).map(TestArgRandomExcursions),
+        unsafe { ErrorSink::out(error) },
+    )
+}
+
+// random excursions variant test
+test_arg! {
+    /// The argument for the Random Excursions Variant Test: the maximum state *x* to test. All
+    /// states `-x..=-1` and `+1..=+x` are tested, with one result per state.
+    ///
+    /// The maximum state must be between 1 and 32, this is checked when creating the argument.
+    ///
+    /// The default value for this argument is 9, as used by NIST.
+    struct TestArgRandomExcursionsVariant(random_excursions_variant::RandomExcursionsVariantTestArg);
+
+    /// Creates a default argument for the Random Excursions Variant Test, with the maximum state
+    /// set to 9.
+    fn sts_TestArgRandomExcursionsVariant_default() -> Self;
+
+    /// Destroys the given argument for the Random Excursions Variant Test.
+    fn sts_TestArgRandomExcursionsVariant_destroy(self);
+}
+
+/// Creates a new argument for the Random Excursions Variant Test, specifying the maximum state.
+///
+/// ## Return value
+///
+/// * if the given maximum state is between 1 and 32: the new argument.
+/// * otherwise: `NULL` - use [sts_get_last_error]
+#[no_mangle]
+pub extern "C" fn sts_TestArgRandomExcursionsVariant_new(
+    max_state: u8,
+) -> Option<Box<TestArgRandomExcursionsVariant>> {
+    boxed_arg(
+        random_excursions_variant::RandomExcursionsVariantTestArg::try_new(max_state)
+            .map(TestArgRandomExcursionsVariant),
+        ErrorSink::LastError,
+    )
+}
+
+/// Same as [sts_TestArgRandomExcursionsVariant_new], but an error is written to `error` instead of
+/// being stored as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestArgRandomExcursionsVariant_new_e(
+    max_state: u8,
+    error: *mut *mut StsError,
+) -> Option<Box<TestArgRandomExcursionsVariant>> {
+    // SAFETY: the caller has to ensure that error is valid.
+    boxed_arg(
+        random_excursions_variant::RandomExcursionsVariantTestArg::try_new(max_state)
+            .map(TestArgRandomExcursionsVariant),
+        unsafe { ErrorSink::out(error) },
+    )
+}
+
+// autocorrelation test
+test_arg! {
+    /// The argument for the Autocorrelation Test: the shift *d* in bits, i.e. the distance of the
+    /// compared bits.
+    ///
+    /// Argument constraints:
+    /// 1. the shift must be >= 1.
+    /// 2. the shift must be <= bit_len / 2.
+    ///
+    /// Constraint 1 is checked when creating the argument.
+    ///
+    /// Constraint 2 is checked on executing the test. If the constraint is violated,
+    /// an error will be raised.
+    ///
+    /// The default value for this argument is 1, i.e. each bit is compared with its successor.
+    struct TestArgAutocorrelation(autocorrelation::AutocorrelationTestArg);
+
+    /// Creates a default argument for the Autocorrelation Test, with the shift set to 1.
+    fn sts_TestArgAutocorrelation_default() -> Self;
+
+    /// Destroys the given argument for the Autocorrelation Test.
+    fn sts_TestArgAutocorrelation_destroy(self);
+}
+
+/// Creates a new argument for the Autocorrelation Test, specifying the shift in bits.
+///
+/// ## Return values
+/// - if the given `shift == 0`, `NULL` is returned - use [sts_get_last_error].
+/// - if the given `shift != 0`, a pointer to the argument is returned.
+#[no_mangle]
+pub extern "C" fn sts_TestArgAutocorrelation_new(
+    shift: usize,
+) -> Option<Box<TestArgAutocorrelation>> {
+    boxed_arg(
+        autocorrelation::AutocorrelationTestArg::try_new(shift).map(TestArgAutocorrelation),
+        ErrorSink::LastError,
+    )
+}
+
+/// Same as [sts_TestArgAutocorrelation_new], but an error is written to `error` instead of being
+/// stored as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestArgAutocorrelation_new_e(
+    shift: usize,
+    error: *mut *mut StsError,
+) -> Option<Box<TestArgAutocorrelation>> {
+    // SAFETY: the caller has to ensure that error is valid.
+    boxed_arg(
+        autocorrelation::AutocorrelationTestArg::try_new(shift).map(TestArgAutocorrelation),
+        unsafe { ErrorSink::out(error) },
+    )
 }
@@ -5,11 +5,24 @@ use std::slice;
 use sts_lib::TestResult as InternalTestResult;
 
 /// The result of a statistical test.
-#[derive(Copy, Clone)]
+#[derive(Clone)]
 pub struct TestResult(pub(crate) InternalTestResult);
 
-/// Destroys the given test result. If you want to destroy a whole list, use [sts_TestResult_list_destroy].
-/// You cannot destroy only a part of a list with this function.
+/// A list of test results, as returned by the tests with multiple results and by
+/// [sts_TestRunner_get_result]. The length of the list is returned by [sts_TestResultList_len],
+/// the single results are accessed with [sts_TestResultList_get].
+pub struct TestResultList(pub(crate) Box<[TestResult]>);
+
+impl TestResultList {
+    /// Creates the list from the given results.
+    pub(crate) fn new(results: impl IntoIterator<Item = InternalTestResult>) -> Self {
+        Self(results.into_iter().map(TestResult).collect())
+    }
+}
+
+/// Destroys the given test result. If you want to destroy a whole list, use
+/// [sts_TestResultList_destroy]. Results contained in a list cannot be destroyed with this
+/// function.
 ///
 /// ## Safety
 ///
@@ -22,20 +35,51 @@ pub unsafe extern "C" fn sts_TestResult_destroy(ptr: Option<Box<TestResult>>) {
     _ = ptr;
 }
 
-/// Destroys the given list of test results. If you want to destroy only a single test result,
-/// use [sts_TestResult_destroy].
+/// Destroys the given list of test results, including all contained results. If you want to
+/// destroy only a single test result, use [sts_TestResult_destroy].
 ///
 /// ## Safety
 ///
-/// * `ptr` must have been created by one of the tests or by the test runner, and must have been
+/// * `list` must have been created by one of the tests or by the test runner, and must have been
 ///   returned by the creating function as a list.
-/// * `ptr` must be valid allocation with `count` elements.
-/// * `ptr` must not be mutated for the duration of this call.
-/// * `ptr` will be invalid after this call, access will lead to undefined behaviour.
+/// * `list` may be null.
+/// * `list` must not be mutated for the duration of this call.
+/// * `list` and all results returned by [sts_TestResultList_get] for it will be invalid after
+///   this call, access will lead to undefined behaviour.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestResultList_destroy(list: Option<Box<TestResultList>>) {
+    _ = list;
+}
+
+/// Returns the count of test results in the given list.
+///
+/// ## Safety
+///
+/// * `list` must have been created by one of the tests or by the test runner.
+/// * `list` must be a valid pointer.
+/// * `list` may not be mutated for the duration of this call.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestResultList_len(list: &TestResultList) -> usize {
+    list.0.len()
+}
+
+/// Returns the test result with the given index in the list, or `NULL` if the index is not
+/// smaller than the length of the list, see [sts_TestResultList_len].
+///
+/// The returned result is owned by the list: it is valid until the list is destroyed with
+/// [sts_TestResultList_destroy] and may not be destroyed with [sts_TestResult_destroy].
+///
+/// ## Safety
+///
+/// * `list` must have been created by one of the tests or by the test runner.
+/// * `list` must be a valid pointer.
+/// * `list` may not be mutated for the duration of this call.
 #[no_mangle]
-pub unsafe extern "C" fn sts_TestResult_list_destroy(ptr: *mut Box<TestResult>, count: usize) {
-    // SAFETY: caller has to ensure that the pointer is valid with count elements
-    _ = unsafe { Box::from_raw(slice::from_raw_parts_mut(ptr, count)) };
+pub unsafe extern "C" fn sts_TestResultList_get(
+    list: &TestResultList,
+    idx: usize,
+) -> Option<&TestResult> {
+    list.0.get(idx)
 }
 
 /// Returns the p_value of the test result.
@@ -62,6 +106,20 @@ pub unsafe extern "C" fn sts_TestResult_passed(result: &TestResult, threshold: f
     result.0.passed(threshold)
 }
 
+/// Checks if the test was applicable to the input. If not, the p_value is set to 0.0, but this
+/// does not mean that the input is not random. This is the case e.g. for the random excursions
+/// test, if the input has too few cycles.
+///
+/// ## Safety
+///
+/// * `result` must have been created by one of the tests.
+/// * `result` must be a valid pointer.
+/// * `result` may not be mutated for the duration of this call.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestResult_is_applicable(result: &TestResult) -> bool {
+    result.0.is_applicable()
+}
+
 /// Extracts the (maybe existing) comment contained in the test result.
 /// This function works in 2 steps:
 /// 1. the caller calls the function with `ptr` set to `NULL`. The necessary length is written to
@@ -70,6 +128,10 @@ pub unsafe extern "C" fn sts_TestResult_passed(result: &TestResult, threshold: f
 ///    the buffer. If the length is enough to store the error message, it is written to the buffer.
 ///    The error message is written with a nul-terminating byte.
 ///
+/// The comment may contain details of the test run, e.g. the length of the input, so its length
+/// differs between results: always query the necessary length instead of using a fixed-size buffer.
+/// The comment is copied to the buffer, so it stays valid after the result is destroyed.
+///
 /// # Return values
 ///
 /// - 0: everything's OK.
@@ -91,15 +153,148 @@ pub unsafe extern "C" fn sts_TestResult_get_comment(
     ptr: *mut c_char,
     len: &mut usize,
 ) -> c_int {
-    // check if there is an error
-    if result.0.comment().is_none() {
-        return 1;
+    match result.0.comment() {
+        // SAFETY: the caller has to ensure that ptr and len are valid.
+        Some(comment) => unsafe { write_str(comment, ptr, len) },
+        None => 1,
+    }
+}
+
+/// Extracts the (maybe existing) label of the test result, which identifies the result for tests
+/// with multiple results, e.g. "forward" and "backward" for the cumulative sums test, "delta1" and
+/// "delta2" for the serial test, "x = +4" for the random excursions tests or "template 000000001"
+/// for the non-overlapping template matching test. Tests with a single result have no label.
+///
+/// This function works in 2 steps, like [sts_TestResult_get_comment].
+///
+/// # Return values
+///
+/// - 0: everything's OK.
+/// - 1: there is no label to store.
+/// - 2: the passed string buffer is too small.
+///
+/// ## Safety
+///
+/// * `result` must have been created by one of the tests.
+/// * `result` must be a valid pointer.
+/// * `result` may not be mutated for the duration of this call.
+/// * `len` must not be `NULL`.
+/// * `ptr` must be valid for writes of up to `len` bytes.
+/// * `ptr` may not be mutated for the duration of this call.
+/// * All responsibility for `ptr` and `len`, especially for its de-allocation, remains with the caller.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestResult_get_label(
+    result: &TestResult,
+    ptr: *mut c_char,
+    len: &mut usize,
+) -> c_int {
+    match result.0.label() {
+        // SAFETY: the caller has to ensure that ptr and len are valid.
+        Some(label) => unsafe { write_str(&label.to_string(), ptr, len) },
+        None => 1,
+    }
+}
+
+/// Returns the count of statistics values contained in the test result, see
+/// [sts_TestResult_get_statistic_value] and [sts_TestResult_get_statistic_name].
+/// Returns 0 if the test result contains no statistics.
+///
+/// ## Safety
+///
+/// * `result` must have been created by one of the tests.
+/// * `result` must be a valid pointer.
+/// * `result` may not be mutated for the duration of this call.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestResult_get_statistics_count(result: &TestResult) -> usize {
+    result
+        .0
+        .statistics()
+        .map_or(0, |statistics| statistics.values().len())
+}
+
+/// Extracts the statistics value with the given index from the test result. Integer values are
+/// converted to `double`. The count of values is returned by [sts_TestResult_get_statistics_count].
+///
+/// # Return values
+///
+/// - 0: everything's OK, the value is written to `value`.
+/// - 1: there is no statistics value with the given index.
+///
+/// ## Safety
+///
+/// * `result` must have been created by one of the tests.
+/// * `result` must be a valid pointer.
+/// * `result` may not be mutated for the duration of this call.
+/// * `value` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestResult_get_statistic_value(
+    result: &TestResult,
+    idx: usize,
+    value: &mut f64,
+) -> c_int {
+    match statistic(result, idx) {
+        Some((_, statistic)) => {
+            *value = statistic;
+            0
+        }
+        None => 1,
+    }
+}
+
+/// Extracts the name of the statistics value with the given index from the test result.
+/// This function works in 2 steps, like [sts_TestResult_get_comment]:
+/// 1. the caller calls the function with `ptr` set to `NULL`. The necessary length is written to
+///    `len`.
+/// 2. the caller calls the function with `ptr` set to a valid buffer, and `len` set to the length of
+///    the buffer. If the length is enough to store the name, it is written to the buffer.
+///    The name is written with a nul-terminating byte.
+///
+/// # Return values
+///
+/// - 0: everything's OK.
+/// - 1: there is no statistics value with the given index.
+/// - 2: the passed string buffer is too small.
+///
+/// ## Safety
+///
+/// * `result` must have been created by one of the tests.
+/// * `result` must be a valid pointer.
+/// * `result` may not be mutated for the duration of this call.
+/// * `len` must not be `NULL`.
+/// * `ptr` must be valid for writes of up to `len` bytes.
+/// * `ptr` may not be mutated for the duration of this call.
+/// * All responsibility for `ptr` and `len`, especially for its de-allocation, remains with the caller.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestResult_get_statistic_name(
+    result: &TestResult,
+    idx: usize,
+    ptr: *mut c_char,
+    len: &mut usize,
+) -> c_int {
+    match statistic(result, idx) {
+        // SAFETY: the caller has to ensure that ptr and len are valid.
+        Some((name, _)) => unsafe { write_str(name, ptr, len) },
+        None => 1,
     }
+}
 
-    let comment = result.0.comment().unwrap();
+/// Returns the name and value of the statistics value with the given index, if it exists.
+fn statistic(result: &TestResult, idx: usize) -> Option<(&'static str, f64)> {
+    result
+        .0
+        .statistics()
+        .and_then(|statistics| statistics.values().get(idx).copied())
+}
 
-    // LAST_ERROR is guaranteed to be Some, we just checked. + 1 for the nul byte
-    let needed_length = comment.as_bytes().len() + 1;
+/// Writes the given string to the buffer, or only the needed length if `ptr` is `NULL`.
+/// Returns 0 if everything's OK, or 2 if the buffer is too small.
+///
+/// ## Safety
+///
+/// * `ptr` must be valid for writes of up to `len` bytes, or `NULL`.
+unsafe fn write_str(value: &str, ptr: *mut c_char, len: &mut usize) -> c_int {
+    // + 1 for the nul byte
+    let needed_length = value.len() + 1;
 
     if ptr.is_null() {
         // caller only asks for the length
@@ -107,7 +302,7 @@ pub unsafe extern "C" fn sts_TestResult_get_comment(
         *len = needed_length;
         0
     } else {
-        // caller wants the comment
+        // caller wants the string
 
         // check length
         if *len < needed_length {
@@ -121,7 +316,7 @@ pub unsafe extern "C" fn sts_TestResult_get_comment(
             // set last NUL byte
             slice[*len - 1] = 0;
             // set message
-            comment
+            value
                 .as_bytes()
                 .iter()
                 .zip(slice)
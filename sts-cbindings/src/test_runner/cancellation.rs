@@ -0,0 +1,59 @@
+//! Opaque struct for cancelling a running test runner.
+
+/// A token to cancel the tests of a *TestRunner* from another thread.
+///
+/// Set the token with [sts_TestRunner_set_cancellation_token], then call
+/// [sts_CancellationToken_cancel] from any thread to abort the running tests. Cancelled tests
+/// return the error code `ErrorCode_Cancelled`.
+#[derive(Clone, Debug)]
+pub struct CancellationToken(pub(super) sts_lib::test_runner::CancellationToken);
+
+/// Creates a new cancellation token that is not cancelled.
+///
+/// The resulting pointer must be freed via [sts_CancellationToken_destroy]. The resulting pointer
+/// will never be `NULL`.
+#[no_mangle]
+pub extern "C" fn sts_CancellationToken_new() -> Box<CancellationToken> {
+    Box::new(CancellationToken(Default::default()))
+}
+
+/// Destroys the given cancellation token. A test runner the token was set on keeps working, it
+/// can still be cancelled.
+///
+/// ## Safety
+///
+/// * `token` must have been created by [sts_CancellationToken_new()]
+/// * `token` must be valid for reads and writes and non-null.
+/// * `token` may not be used by another thread for the duration of this call.
+/// * `token` will be an invalid pointer after this call, trying to access its memory will lead to
+///   undefined behaviour.
+#[no_mangle]
+pub unsafe extern "C" fn sts_CancellationToken_destroy(token: Option<Box<CancellationToken>>) {
+    _ = token;
+}
+
+/// Cancels all tests using the token. Running tests abort as soon as possible, tests that were
+/// not started yet are not run. Cancellation cannot be undone.
+///
+/// This function may be called from any thread, even while a test runner using the token is
+/// running.
+///
+/// ## Safety
+///
+/// * `token` must have been created by [sts_CancellationToken_new()]
+/// * `token` must be valid for reads and non-null.
+#[no_mangle]
+pub unsafe extern "C" fn sts_CancellationToken_cancel(token: &CancellationToken) {
+    token.0.cancel();
+}
+
+/// Checks if the token was cancelled with [sts_CancellationToken_cancel].
+///
+/// ## Safety
+///
+/// * `token` must have been created by [sts_CancellationToken_new()]
+/// * `token` must be valid for reads and non-null.
+#[no_mangle]
+pub unsafe extern "C" fn sts_CancellationToken_is_cancelled(token: &CancellationToken) -> bool {
+    token.0.is_cancelled()
+}
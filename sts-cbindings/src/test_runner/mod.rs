@@ -1,26 +1,211 @@
 //! The test runner, for running multiple tests in one call.
 
 use crate::bitvec::BitVec;
-use crate::test_result::TestResult;
+use crate::test_result::{TestResult, TestResultList};
+use crate::test_runner::cancellation::CancellationToken;
+use crate::test_runner::profile::{try_get_profile, RawProfile};
 use crate::test_runner::test::{RawTest, Test};
 use crate::test_runner::test_args::RunnerTestArgs;
-use crate::{set_last_from_runner_error, set_last_from_test_failed, set_last_invalid_test};
+use crate::{ErrorSink, StsError};
 use std::collections::HashMap;
-use std::ffi::c_int;
-use std::slice;
+use std::ffi::{c_int, c_void};
+use std::num::NonZero;
+use std::{ptr, slice};
 use sts_lib::test_runner;
-use sts_lib::test_runner::RunnerError;
+use sts_lib::test_runner::{PartitionedResults, RunnerError, Subsample};
 
+pub mod cancellation;
+pub mod profile;
 pub mod test;
 pub mod test_args;
 
 /// This test runner can be used to run several / all tests on a sequence in one call.
-pub struct TestRunner(HashMap<sts_lib::Test, Box<[sts_lib::TestResult]>>);
+///
+/// The runner also stores the threshold used to decide if a test passed, see
+/// [sts_TestRunner_set_threshold], and an optional cancellation token, see
+/// [sts_TestRunner_set_cancellation_token].
+///
+/// With [sts_TestRunner_run_tests_partitioned] and [sts_TestRunner_run_tests_subsampled], the tests
+/// can also be run on each of multiple sub-sequences, see [sts_TestRunner_get_partition_result].
+pub struct TestRunner {
+    results: HashMap<sts_lib::Test, Box<[sts_lib::TestResult]>>,
+    partition_results: HashMap<(sts_lib::Test, usize), Box<[sts_lib::TestResult]>>,
+    threshold: f64,
+    cancellation_token: Option<sts_lib::test_runner::CancellationToken>,
+}
+
+/// A callback that is called by [sts_TestRunner_run_tests_cb] after each test, on the thread that
+/// called [sts_TestRunner_run_tests_cb].
+///
+/// * `test` is the test that completed.
+/// * `results` is an array of `results_len` pointers to the results of the test, or `NULL` (with
+///   `results_len` being `0`) if the test failed with an error. The results are owned by the
+///   runner and are only valid until the callback returns. To keep them, use
+///   [sts_TestRunner_get_result] after the run.
+/// * `user_data` is the pointer given to [sts_TestRunner_run_tests_cb], unchanged.
+pub type TestRunnerCallback = unsafe extern "C" fn(
+    test: RawTest,
+    results: *const *const TestResult,
+    results_len: usize,
+    user_data: *mut c_void,
+);
 
 impl TestRunner {
-    /// Convenience function, handles the iterators returned by the test runner functions.
+    /// Runs the given tests, using the stored cancellation token if there is one.
     ///
     /// Used by all `test_runner_run_*` functions.
+    fn run(
+        &mut self,
+        data: &BitVec,
+        tests: impl Iterator<Item = sts_lib::Test>,
+        args: sts_lib::TestArgs,
+        errors: ErrorSink,
+    ) -> c_int {
+        // SAFETY: no callback is called.
+        unsafe { self.run_with_callback(data, tests, args, None, ptr::null_mut(), errors) }
+    }
+
+    /// Runs the given tests like [TestRunner::run], calling `callback` (if given) with `user_data`
+    /// after each test.
+    ///
+    /// Used by [sts_TestRunner_run_tests_cb].
+    ///
+    /// ## Safety
+    ///
+    /// `callback` must be safe to call with `user_data` and the results of each test.
+    unsafe fn run_with_callback(
+        &mut self,
+        data: &BitVec,
+        tests: impl Iterator<Item = sts_lib::Test>,
+        args: sts_lib::TestArgs,
+        callback: Option<TestRunnerCallback>,
+        user_data: *mut c_void,
+        errors: ErrorSink,
+    ) -> c_int {
+        let notify = |(test, results): &(_, Result<Vec<_>, _>)| {
+            let Some(callback) = callback else {
+                return;
+            };
+            let raw_test = Test::from(*test) as RawTest;
+
+            match results {
+                Ok(results) => {
+                    let list = TestResultList::new(results.iter().cloned());
+                    let pointers = list.0.iter().map(ptr::from_ref).collect::<Vec<_>>();
+                    // SAFETY: the caller has to ensure that the callback is safe to call, the
+                    // pointers are valid until the callback returns.
+                    unsafe { callback(raw_test, pointers.as_ptr(), pointers.len(), user_data) }
+                }
+                // SAFETY: see above.
+                Err(_) => unsafe { callback(raw_test, ptr::null(), 0, user_data) },
+            }
+        };
+
+        match self.cancellation_token.clone() {
+            Some(token) => self.handle_results(
+                test_runner::run_tests_with_cancel(&data.0, tests, args, &token)
+                    .map(|iter| iter.inspect(notify)),
+                errors,
+            ),
+            None => self.handle_results(
+                test_runner::run_tests(&data.0, tests, args).map(|iter| iter.inspect(notify)),
+                errors,
+            ),
+        }
+    }
+
+    /// Runs the given tests on each partition, using the stored cancellation token if there is
+    /// one.
+    ///
+    /// Used by [sts_TestRunner_run_tests_partitioned].
+    fn run_partitioned(
+        &mut self,
+        data: &BitVec,
+        tests: impl Iterator<Item = sts_lib::Test>,
+        args: sts_lib::TestArgs,
+        partitions: NonZero<usize>,
+        errors: ErrorSink,
+    ) -> c_int {
+        match self.cancellation_token.clone() {
+            Some(token) => self.handle_partitioned_results(
+                test_runner::run_tests_partitioned_with_cancel(
+                    &data.0, tests, args, partitions, &token,
+                ),
+                errors,
+            ),
+            None => self.handle_partitioned_results(
+                test_runner::run_tests_partitioned(&data.0, tests, args, partitions),
+                errors,
+            ),
+        }
+    }
+
+    /// Runs the given tests on each window of the subsample, using the stored cancellation token
+    /// if there is one.
+    ///
+    /// Used by [sts_TestRunner_run_tests_subsampled].
+    fn run_subsampled(
+        &mut self,
+        data: &BitVec,
+        tests: impl Iterator<Item = sts_lib::Test>,
+        args: sts_lib::TestArgs,
+        subsample: &Subsample,
+        errors: ErrorSink,
+    ) -> c_int {
+        match self.cancellation_token.clone() {
+            Some(token) => self.handle_partitioned_results(
+                test_runner::run_tests_subsampled_with_cancel(
+                    &data.0, tests, args, subsample, &token,
+                ),
+                errors,
+            ),
+            None => self.handle_partitioned_results(
+                test_runner::run_tests_subsampled(&data.0, tests, args, subsample),
+                errors,
+            ),
+        }
+    }
+
+    /// Convenience function, handles the iterators returned by the partitioned and subsampled
+    /// test runner functions.
+    fn handle_partitioned_results(
+        &mut self,
+        results: Result<impl Iterator<Item = (sts_lib::Test, PartitionedResults)>, RunnerError>,
+        errors: ErrorSink,
+    ) -> c_int {
+        let iter = match results {
+            Ok(iter) => iter,
+            Err(e) => {
+                errors.report(e);
+                return 1;
+            }
+        };
+
+        let mut results = HashMap::new();
+        let mut errs = Vec::new();
+
+        for (test, partition_results) in iter {
+            for (partition, res) in partition_results.into_iter().enumerate() {
+                match res {
+                    Ok(res) => {
+                        results.insert((test, partition), res.into_boxed_slice());
+                    }
+                    Err(e) => errs.push((test, e)),
+                }
+            }
+        }
+
+        self.partition_results = results;
+
+        if errs.is_empty() {
+            0
+        } else {
+            errors.report(StsError::test_failed(errs.into_boxed_slice()));
+            2
+        }
+    }
+
+    /// Convenience function, handles the iterators returned by the test runner functions.
     fn handle_results(
         &mut self,
         results: Result<
@@ -32,6 +217,7 @@ impl TestRunner {
             >,
             RunnerError,
         >,
+        errors: ErrorSink,
     ) -> c_int {
         match results {
             Ok(iter) => {
@@ -52,17 +238,17 @@ impl TestRunner {
                     .filter_map(|(test, err)| err.map(|err| (test, err)))
                     .collect::<Box<_>>();
 
-                self.0 = results;
+                self.results = results;
 
                 if errs.is_empty() {
                     0
                 } else {
-                    set_last_from_test_failed(errs);
+                    errors.report(StsError::test_failed(errs));
                     2
                 }
             }
             Err(e) => {
-                set_last_from_runner_error(e);
+                errors.report(e);
                 1
             }
         }
@@ -76,7 +262,12 @@ impl TestRunner {
 /// be `NULL`.
 #[no_mangle]
 pub extern "C" fn sts_TestRunner_new() -> Box<TestRunner> {
-    Box::new(TestRunner(HashMap::new()))
+    Box::new(TestRunner {
+        results: HashMap::new(),
+        partition_results: HashMap::new(),
+        threshold: sts_lib::DEFAULT_THRESHOLD,
+        cancellation_token: None,
+    })
 }
 
 /// Destroys the given test runner.
@@ -95,50 +286,282 @@ pub unsafe extern "C" fn sts_TestRunner_destroy(runner: Option<Box<TestRunner>>)
 }
 
 /// Returns the result of the given test, if it was run. Since some tests return multiple results,
-/// the returned pointer is an array, the count of elements will be stored into `length`.
+/// a list is returned, its length is returned by [sts_TestResultList_len].
 ///
 /// After this call, the result is no longer stored inside the runner.
 ///
-/// The resulting list of test results must be destroyed with
-/// [sts_TestResult_list_destroy].
+/// The resulting list of test results must be destroyed with [sts_TestResultList_destroy]. If an
+/// error happened, `NULL` is returned - use [sts_get_last_error].
 ///
 /// ## Safety
 ///
 /// * `runner` must have been created by [sts_TestRunner_new()]
 /// * `runner` must be valid for reads and writes and non-null.
 /// * `runner` may not be mutated for the duration of this call.
-/// * `length` must be a non-null pointer valid for writes.
-/// * `length` may not be mutated for the duration of this call.
 #[no_mangle]
 pub unsafe extern "C" fn sts_TestRunner_get_result(
     runner: &mut TestRunner,
     test: RawTest,
-    length: &mut usize,
-) -> *mut Box<TestResult> {
+) -> Option<Box<TestResultList>> {
+    get_result(runner, test, ErrorSink::LastError)
+}
+
+/// Same as [sts_TestRunner_get_result], but an error is written to `error` instead of being
+/// stored as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * The same requirements as for [sts_TestRunner_get_result] apply.
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestRunner_get_result_e(
+    runner: &mut TestRunner,
+    test: RawTest,
+    error: *mut *mut StsError,
+) -> Option<Box<TestResultList>> {
+    // SAFETY: the caller has to ensure that error is valid.
+    get_result(runner, test, unsafe { ErrorSink::out(error) })
+}
+
+/// Implementation of [sts_TestRunner_get_result].
+fn get_result(
+    runner: &mut TestRunner,
+    test: RawTest,
+    errors: ErrorSink,
+) -> Option<Box<TestResultList>> {
     // parse the test
     let Ok(test) = Test::try_from(test) else {
-        set_last_invalid_test(test);
-        return std::ptr::null_mut();
+        errors.report(StsError::invalid_test(test));
+        return None;
     };
 
     let test = test.into();
 
-    match runner.0.remove(&test) {
+    match runner.results.remove(&test) {
         None => {
-            crate::set_last_test_was_not_run(test);
-            std::ptr::null_mut()
+            errors.report(StsError::test_was_not_run(test));
+            None
         }
-        Some(result) => {
-            let result: Box<[Box<TestResult>]> = Box::into_iter(result)
-                .map(|res| Box::new(TestResult(res)))
-                .collect();
-            *length = result.len();
-            Box::into_raw(result) as *mut Box<TestResult>
+        Some(result) => Some(Box::new(TestResultList::new(result))),
+    }
+}
+
+/// Returns the result of the given test on the sub-sequence with the index `partition`, if it was
+/// run with [sts_TestRunner_run_tests_partitioned] or [sts_TestRunner_run_tests_subsampled].
+/// Since some tests return multiple results, a list is returned, its length is returned by
+/// [sts_TestResultList_len].
+///
+/// After this call, the result is no longer stored inside the runner.
+///
+/// The resulting list of test results must be destroyed with [sts_TestResultList_destroy]. If an
+/// error happened, `NULL` is returned - use [sts_get_last_error].
+///
+/// ## Safety
+///
+/// * `runner` must have been created by [sts_TestRunner_new()]
+/// * `runner` must be valid for reads and writes and non-null.
+/// * `runner` may not be mutated for the duration of this call.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestRunner_get_partition_result(
+    runner: &mut TestRunner,
+    test: RawTest,
+    partition: usize,
+) -> Option<Box<TestResultList>> {
+    get_partition_result(runner, test, partition, ErrorSink::LastError)
+}
+
+/// Same as [sts_TestRunner_get_partition_result], but an error is written to `error` instead of
+/// being stored as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * The same requirements as for [sts_TestRunner_get_partition_result] apply.
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestRunner_get_partition_result_e(
+    runner: &mut TestRunner,
+    test: RawTest,
+    partition: usize,
+    error: *mut *mut StsError,
+) -> Option<Box<TestResultList>> {
+    // SAFETY: the caller has to ensure that error is valid.
+    get_partition_result(runner, test, partition, unsafe { ErrorSink::out(error) })
+}
+
+/// Implementation of [sts_TestRunner_get_partition_result].
+fn get_partition_result(
+    runner: &mut TestRunner,
+    test: RawTest,
+    partition: usize,
+    errors: ErrorSink,
+) -> Option<Box<TestResultList>> {
+    // parse the test
+    let Ok(test) = Test::try_from(test) else {
+        errors.report(StsError::invalid_test(test));
+        return None;
+    };
+
+    let test = test.into();
+
+    match runner.partition_results.remove(&(test, partition)) {
+        None => {
+            errors.report(StsError::partition_was_not_run(test, partition));
+            None
+        }
+        Some(result) => Some(Box::new(TestResultList::new(result))),
+    }
+}
+
+/// Sets the threshold used by [sts_TestRunner_test_passed] to decide if a test passed. The
+/// threshold must be between 0 and 1 (exclusive). The default is [DEFAULT_THRESHOLD].
+///
+/// ## Return value
+///
+/// * `0`: the threshold was set.
+/// * `1`: the threshold is invalid, the stored threshold was not changed. The error message and
+///   code can be found out with [sts_get_last_error].
+///
+/// ## Safety
+///
+/// * `runner` must have been created by [sts_TestRunner_new()]
+/// * `runner` must be valid for reads and writes and non-null.
+/// * `runner` may not be mutated for the duration of this call.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestRunner_set_threshold(
+    runner: &mut TestRunner,
+    threshold: f64,
+) -> c_int {
+    set_threshold(runner, threshold, ErrorSink::LastError)
+}
+
+/// Same as [sts_TestRunner_set_threshold], but an error is written to `error` instead of being
+/// stored as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * The same requirements as for [sts_TestRunner_set_threshold] apply.
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestRunner_set_threshold_e(
+    runner: &mut TestRunner,
+    threshold: f64,
+    error: *mut *mut StsError,
+) -> c_int {
+    // SAFETY: the caller has to ensure that error is valid.
+    set_threshold(runner, threshold, unsafe { ErrorSink::out(error) })
+}
+
+/// Implementation of [sts_TestRunner_set_threshold].
+fn set_threshold(runner: &mut TestRunner, threshold: f64, errors: ErrorSink) -> c_int {
+    if threshold > 0.0 && threshold < 1.0 {
+        runner.threshold = threshold;
+        0
+    } else {
+        let msg = format!("The threshold must be between 0 and 1 (exclusive), is: {threshold}");
+        errors.report(sts_lib::Error::InvalidParameter(msg));
+        1
+    }
+}
+
+/// Returns the threshold used by [sts_TestRunner_test_passed] to decide if a test passed.
+///
+/// ## Safety
+///
+/// * `runner` must have been created by [sts_TestRunner_new()]
+/// * `runner` must be valid for reads and non-null.
+/// * `runner` may not be mutated for the duration of this call.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestRunner_get_threshold(runner: &TestRunner) -> f64 {
+    runner.threshold
+}
+
+/// Checks if the given test passed, using the threshold stored in the runner, see
+/// [sts_TestRunner_set_threshold]. A test with multiple results only passed if all results
+/// passed.
+///
+/// The result stays in the runner and can still be retrieved with [sts_TestRunner_get_result].
+///
+/// ## Return value
+///
+/// * `1`: the test passed.
+/// * `0`: the test did not pass.
+/// * `2`: the test was not applicable to the sequence, see [sts_TestResult_is_applicable].
+/// * `-1`: the test is invalid or its result is not stored in the runner. The error message and
+///   code can be found out with [sts_get_last_error].
+///
+/// ## Safety
+///
+/// * `runner` must have been created by [sts_TestRunner_new()]
+/// * `runner` must be valid for reads and non-null.
+/// * `runner` may not be mutated for the duration of this call.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestRunner_test_passed(runner: &TestRunner, test: RawTest) -> c_int {
+    test_passed(runner, test, ErrorSink::LastError)
+}
+
+/// Same as [sts_TestRunner_test_passed], but an error is written to `error` instead of being
+/// stored as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * The same requirements as for [sts_TestRunner_test_passed] apply.
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestRunner_test_passed_e(
+    runner: &TestRunner,
+    test: RawTest,
+    error: *mut *mut StsError,
+) -> c_int {
+    // SAFETY: the caller has to ensure that error is valid.
+    test_passed(runner, test, unsafe { ErrorSink::out(error) })
+}
+
+/// Implementation of [sts_TestRunner_test_passed].
+fn test_passed(runner: &TestRunner, test: RawTest, errors: ErrorSink) -> c_int {
+    // parse the test
+    let Ok(test) = Test::try_from(test) else {
+        errors.report(StsError::invalid_test(test));
+        return -1;
+    };
+
+    let test = test.into();
+
+    match runner.results.get(&test) {
+        None => {
+            errors.report(StsError::test_was_not_run(test));
+            -1
         }
+        Some(results) if results.iter().any(|result| !result.is_applicable()) => 2,
+        Some(results) => results
+            .iter()
+            .all(|result| result.passed(runner.threshold))
+            .into(),
     }
 }
 
-/// Runs all tests on the given bit sequence with the default test arguments.
+/// Sets the cancellation token used by all following runs of the test runner. If the token is
+/// cancelled with [sts_CancellationToken_cancel], the running tests abort and return an error with
+/// the code `ErrorCode_Cancelled`. Pass `NULL` to remove the token.
+///
+/// The runner keeps its own reference to the token, `token` may be destroyed after this call.
+///
+/// ## Safety
+///
+/// * `runner` must have been created by [sts_TestRunner_new()]
+/// * `runner` must be valid for reads and writes and non-null.
+/// * `runner` may not be mutated for the duration of this call.
+/// * `token` must either be `NULL` or have been created by [sts_CancellationToken_new()] and be
+///   valid for reads.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestRunner_set_cancellation_token(
+    runner: &mut TestRunner,
+    token: Option<&CancellationToken>,
+) {
+    runner.cancellation_token = token.map(|token| token.0.clone());
+}
+
+/// Runs the default tests (all tests except for the Lempel-Ziv compression test) on the given bit
+/// sequence with the default test arguments.
 ///
 /// ## Return value
 ///
@@ -155,8 +578,11 @@ pub unsafe extern "C" fn sts_TestRunner_get_result(
 /// * `bitvec` must have been created by either [sts_BitVec_from_str],
 ///   [sts_BitVec_from_str_with_max_length],
 ///   [sts_BitVec_from_bytes],
-///   [sts_BitVec_from_bits] or
-///   [sts_BitVec_clone].
+///   [sts_BitVec_from_bits],
+///   [sts_BitVec_from_words],
+///   [sts_BitVec_borrow_words],
+///   [sts_BitVec_from_file],
+///   [sts_BitVec_clone] or [sts_BitVec_slice].
 /// * `bitvec` must be a non-null pointer valid for reads.
 /// * `bitvec` may not be mutated for the duration of this call.
 #[no_mangle]
@@ -164,7 +590,36 @@ pub unsafe extern "C" fn sts_TestRunner_run_all_automatic(
     runner: &mut TestRunner,
     data: &BitVec,
 ) -> c_int {
-    runner.handle_results(test_runner::run_all_tests_automatic(&data.0))
+    let errors = ErrorSink::LastError;
+    runner.run(
+        data,
+        sts_lib::Test::default_set().iter().copied(),
+        Default::default(),
+        errors,
+    )
+}
+
+/// Same as [sts_TestRunner_run_all_automatic], but an error is written to `error` instead of
+/// being stored as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * The same requirements as for [sts_TestRunner_run_all_automatic] apply.
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestRunner_run_all_automatic_e(
+    runner: &mut TestRunner,
+    data: &BitVec,
+    error: *mut *mut StsError,
+) -> c_int {
+    // SAFETY: the caller has to ensure that error is valid.
+    let errors = unsafe { ErrorSink::out(error) };
+    runner.run(
+        data,
+        sts_lib::Test::default_set().iter().copied(),
+        Default::default(),
+        errors,
+    )
 }
 
 /// Runs all chosen tests on the given bit sequence with the default test arguments.
@@ -173,6 +628,7 @@ pub unsafe extern "C" fn sts_TestRunner_run_all_automatic(
 ///
 /// * If all tests ran successfully, `0` is returned.
 /// * If one of the tests specified was a duplicate of a previous test, `1` is returned.
+/// * If the input is too short for one of the tests or its arguments, `1` is returned.
 /// * If one of the tests specified was not a valid test as per the enum [Test], `1` is returned.
 /// * If an error occurred while running the tests, `2` is returned. All other tests are still done.
 ///   The good test results can be retrieved with [sts_TestRunner_get_result], the exact error can
@@ -189,8 +645,11 @@ pub unsafe extern "C" fn sts_TestRunner_run_all_automatic(
 /// * `bitvec` must have been created by either [sts_BitVec_from_str],
 ///   [sts_BitVec_from_str_with_max_length],
 ///   [sts_BitVec_from_bytes],
-///   [sts_BitVec_from_bits] or
-///   [sts_BitVec_clone].
+///   [sts_BitVec_from_bits],
+///   [sts_BitVec_from_words],
+///   [sts_BitVec_borrow_words],
+///   [sts_BitVec_from_file],
+///   [sts_BitVec_clone] or [sts_BitVec_slice].
 /// * `bitvec` must be a non-null pointer valid for reads.
 /// * `bitvec` may not be mutated for the duration of this call.
 /// * `tests` must be a valid, non-null pointer readable for up to `tests_len` elements.
@@ -202,20 +661,31 @@ pub unsafe extern "C" fn sts_TestRunner_run_automatic(
     tests: *const RawTest,
     tests_len: usize,
 ) -> c_int {
-    // SAFETY: same considerations apply to the call as for this function, caller has to ensure
-    // that the requirements are met.
-    let tests = unsafe { try_get_tests(tests, tests_len) };
-
-    let tests = match tests {
-        Some(tests) => tests,
-        // Error message was already set
-        None => return 1,
-    };
+    // SAFETY: same considerations apply to the call as for this function.
+    unsafe { run_tests(runner, data, tests, tests_len, None, ErrorSink::LastError) }
+}
 
-    runner.handle_results(test_runner::run_tests_automatic(&data.0, tests.into_iter()))
+/// Same as [sts_TestRunner_run_automatic], but an error is written to `error` instead of being
+/// stored as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * The same requirements as for [sts_TestRunner_run_automatic] apply.
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestRunner_run_automatic_e(
+    runner: &mut TestRunner,
+    data: &BitVec,
+    tests: *const RawTest,
+    tests_len: usize,
+    error: *mut *mut StsError,
+) -> c_int {
+    // SAFETY: same considerations apply to the calls as for this function.
+    unsafe { run_tests(runner, data, tests, tests_len, None, ErrorSink::out(error)) }
 }
 
-/// Runs all tests on the given bit sequence with the given test arguments.
+/// Runs the default tests (all tests except for the Lempel-Ziv compression test) on the given bit
+/// sequence with the given test arguments.
 ///
 /// ## Return value
 ///
@@ -232,8 +702,11 @@ pub unsafe extern "C" fn sts_TestRunner_run_automatic(
 /// * `bitvec` must have been created by either [sts_BitVec_from_str],
 ///   [sts_BitVec_from_str_with_max_length],
 ///   [sts_BitVec_from_bytes],
-///   [sts_BitVec_from_bits] or
-///   [sts_BitVec_clone].
+///   [sts_BitVec_from_bits],
+///   [sts_BitVec_from_words],
+///   [sts_BitVec_borrow_words],
+///   [sts_BitVec_from_file],
+///   [sts_BitVec_clone] or [sts_BitVec_slice].
 /// * `bitvec` must be a non-null pointer valid for reads.
 /// * `bitvec` may not be mutated for the duration of this call.
 /// * `test_args` must have been created by [sts_RunnerTestArgs_new].
@@ -244,9 +717,37 @@ pub unsafe extern "C" fn sts_TestRunner_run_all_tests(
     data: &BitVec,
     test_args: &RunnerTestArgs,
 ) -> c_int {
-    let args = test_args.0;
+    let errors = ErrorSink::LastError;
+    runner.run(
+        data,
+        sts_lib::Test::default_set().iter().copied(),
+        test_args.0,
+        errors,
+    )
+}
 
-    runner.handle_results(test_runner::run_all_tests(&data.0, args))
+/// Same as [sts_TestRunner_run_all_tests], but an error is written to `error` instead of being
+/// stored as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * The same requirements as for [sts_TestRunner_run_all_tests] apply.
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestRunner_run_all_tests_e(
+    runner: &mut TestRunner,
+    data: &BitVec,
+    test_args: &RunnerTestArgs,
+    error: *mut *mut StsError,
+) -> c_int {
+    // SAFETY: the caller has to ensure that error is valid.
+    let errors = unsafe { ErrorSink::out(error) };
+    runner.run(
+        data,
+        sts_lib::Test::default_set().iter().copied(),
+        test_args.0,
+        errors,
+    )
 }
 
 /// Runs all chosen tests on the given bit sequence with the given test arguments.
@@ -255,6 +756,7 @@ pub unsafe extern "C" fn sts_TestRunner_run_all_tests(
 ///
 /// * If all tests ran successfully, `0` is returned.
 /// * If one of the tests specified was a duplicate of a previous test, `1` is returned.
+/// * If the input is too short for one of the tests or its arguments, `1` is returned.
 /// * If one of the tests specified was not a valid test as per the enum [Test], `1` is returned.
 /// * If an error occurred while running the tests, `2` is returned. All other tests are still done.
 ///   The good test results can be retrieved with [sts_TestRunner_get_result], the exact error can
@@ -271,8 +773,11 @@ pub unsafe extern "C" fn sts_TestRunner_run_all_tests(
 /// * `bitvec` must have been created by either [sts_BitVec_from_str],
 ///   [sts_BitVec_from_str_with_max_length],
 ///   [sts_BitVec_from_bytes],
-///   [sts_BitVec_from_bits] or
-///   [sts_BitVec_clone].
+///   [sts_BitVec_from_bits],
+///   [sts_BitVec_from_words],
+///   [sts_BitVec_borrow_words],
+///   [sts_BitVec_from_file],
+///   [sts_BitVec_clone] or [sts_BitVec_slice].
 /// * `bitvec` must be a non-null pointer valid for reads.
 /// * `bitvec` may not be mutated for the duration of this call.
 /// * `tests` must be a valid, non-null pointer readable for up to `tests_len` elements.
@@ -287,29 +792,507 @@ pub unsafe extern "C" fn sts_TestRunner_run_tests(
     tests_len: usize,
     test_args: &RunnerTestArgs,
 ) -> c_int {
+    let test_args = Some(test_args);
+    let errors = ErrorSink::LastError;
+    // SAFETY: same considerations apply to the call as for this function.
+    unsafe { run_tests(runner, data, tests, tests_len, test_args, errors) }
+}
+
+/// Same as [sts_TestRunner_run_tests], but an error is written to `error` instead of being
+/// stored as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * The same requirements as for [sts_TestRunner_run_tests] apply.
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestRunner_run_tests_e(
+    runner: &mut TestRunner,
+    data: &BitVec,
+    tests: *const RawTest,
+    tests_len: usize,
+    test_args: &RunnerTestArgs,
+    error: *mut *mut StsError,
+) -> c_int {
+    let test_args = Some(test_args);
+    // SAFETY: same considerations apply to the calls as for this function.
+    unsafe {
+        let errors = ErrorSink::out(error);
+        run_tests(runner, data, tests, tests_len, test_args, errors)
+    }
+}
+
+/// Implementation of [sts_TestRunner_run_automatic] (without `test_args`) and
+/// [sts_TestRunner_run_tests].
+///
+/// ## Safety
+///
+/// The same requirements as for [sts_TestRunner_run_tests] apply.
+unsafe fn run_tests(
+    runner: &mut TestRunner,
+    data: &BitVec,
+    tests: *const RawTest,
+    tests_len: usize,
+    test_args: Option<&RunnerTestArgs>,
+    errors: ErrorSink,
+) -> c_int {
+    // SAFETY: same considerations apply to the call as for this function, caller has to ensure
+    // that the requirements are met.
+    let tests = unsafe { try_get_tests(tests, tests_len, errors) };
+
+    let tests = match tests {
+        Some(tests) => tests,
+        // Error was already reported
+        None => return 1,
+    };
+
+    let test_args = test_args.map(|args| args.0).unwrap_or_default();
+    runner.run(data, tests.into_iter(), test_args, errors)
+}
+
+/// Runs all chosen tests on the given bit sequence with the given test arguments, like
+/// [sts_TestRunner_run_tests], but calls `callback` after each test with its results, e.g. to
+/// update a user interface while the remaining tests run. `user_data` is passed to each call of
+/// the callback unchanged, it may be `NULL`. If `callback` is `NULL`, no callback is called.
+///
+/// The results are stored in the runner as well, they can be retrieved with
+/// [sts_TestRunner_get_result] after this call. A test that failed with an error is passed to the
+/// callback without results, the error is reported after all tests ran.
+///
+/// ## Return value
+///
+/// The same as for [sts_TestRunner_run_tests]. If `1` is returned, no test was run and the
+/// callback was not called.
+///
+/// ## Safety
+///
+/// * The same requirements as for [sts_TestRunner_run_tests] apply.
+/// * `callback` must either be `NULL` or a function safe to call with `user_data`, see
+///   [TestRunnerCallback].
+/// * `callback` may not call any function with `runner`.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestRunner_run_tests_cb(
+    runner: &mut TestRunner,
+    data: &BitVec,
+    tests: *const RawTest,
+    tests_len: usize,
+    test_args: &RunnerTestArgs,
+    callback: Option<TestRunnerCallback>,
+    user_data: *mut c_void,
+) -> c_int {
+    let errors = ErrorSink::LastError;
+    // SAFETY: same considerations apply to the call as for this function.
+    unsafe {
+        run_tests_cb(
+            runner, data, tests, tests_len, test_args, callback, user_data, errors,
+        )
+    }
+}
+
+/// Same as [sts_TestRunner_run_tests_cb], but an error is written to `error` instead of being
+/// stored as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * The same requirements as for [sts_TestRunner_run_tests_cb] apply.
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn sts_TestRunner_run_tests_cb_e(
+    runner: &mut TestRunner,
+    data: &BitVec,
+    tests: *const RawTest,
+    tests_len: usize,
+    test_args: &RunnerTestArgs,
+    callback: Option<TestRunnerCallback>,
+    user_data: *mut c_void,
+    error: *mut *mut StsError,
+) -> c_int {
+    // SAFETY: same considerations apply to the calls as for this function.
+    unsafe {
+        let errors = ErrorSink::out(error);
+        run_tests_cb(
+            runner, data, tests, tests_len, test_args, callback, user_data, errors,
+        )
+    }
+}
+
+/// Implementation of [sts_TestRunner_run_tests_cb].
+///
+/// ## Safety
+///
+/// The same requirements as for [sts_TestRunner_run_tests_cb] apply.
+#[allow(clippy::too_many_arguments)]
+unsafe fn run_tests_cb(
+    runner: &mut TestRunner,
+    data: &BitVec,
+    tests: *const RawTest,
+    tests_len: usize,
+    test_args: &RunnerTestArgs,
+    callback: Option<TestRunnerCallback>,
+    user_data: *mut c_void,
+    errors: ErrorSink,
+) -> c_int {
+    // SAFETY: same considerations apply to the call as for this function, caller has to ensure
+    // that the requirements are met.
+    let Some(tests) = (unsafe { try_get_tests(tests, tests_len, errors) }) else {
+        // Error was already reported
+        return 1;
+    };
+
+    // SAFETY: the caller has to ensure that the callback is safe to call.
+    unsafe {
+        runner.run_with_callback(
+            data,
+            tests.into_iter(),
+            test_args.0,
+            callback,
+            user_data,
+            errors,
+        )
+    }
+}
+
+/// Runs all chosen tests on each of `partitions` disjoint sub-sequences of the given bit sequence,
+/// with the given test arguments. All sub-sequences have the same length: the length of the
+/// sequence divided by `partitions`, the remaining bits at the end are not tested.
+///
+/// The results are retrieved with [sts_TestRunner_get_partition_result], with the index of the
+/// sub-sequence in the sequence. The results of previous partitioned runs are discarded, the
+/// results of [sts_TestRunner_run_tests] etc. are kept.
+///
+/// ## Return value
+///
+/// * If all tests ran successfully on all sub-sequences, `0` is returned.
+/// * If one of the tests specified was a duplicate of a previous test, `1` is returned.
+/// * If the sub-sequences are too short for one of the tests or its arguments, `1` is returned.
+/// * If one of the tests specified was not a valid test as per the enum [Test], `1` is returned.
+/// * If `partitions` is `0`, `1` is returned.
+/// * If an error occurred while running the tests, `2` is returned. All other tests are still done.
+///   The good test results can be retrieved with [sts_TestRunner_get_partition_result], the exact
+///   error can be retrieved.
+///
+/// In each error case, the error message and code can be found out with
+/// [sts_get_last_error).
+///
+/// ## Safety
+///
+/// * The same requirements as for [sts_TestRunner_run_tests] apply.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestRunner_run_tests_partitioned(
+    runner: &mut TestRunner,
+    data: &BitVec,
+    tests: *const RawTest,
+    tests_len: usize,
+    test_args: &RunnerTestArgs,
+    partitions: usize,
+) -> c_int {
+    let errors = ErrorSink::LastError;
+    // SAFETY: same considerations apply to the call as for this function.
+    unsafe {
+        run_tests_partitioned(
+            runner, data, tests, tests_len, test_args, partitions, errors,
+        )
+    }
+}
+
+/// Same as [sts_TestRunner_run_tests_partitioned], but an error is written to `error` instead of
+/// being stored as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * The same requirements as for [sts_TestRunner_run_tests_partitioned] apply.
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestRunner_run_tests_partitioned_e(
+    runner: &mut TestRunner,
+    data: &BitVec,
+    tests: *const RawTest,
+    tests_len: usize,
+    test_args: &RunnerTestArgs,
+    partitions: usize,
+    error: *mut *mut StsError,
+) -> c_int {
+    // SAFETY: same considerations apply to the calls as for this function.
+    unsafe {
+        let errors = ErrorSink::out(error);
+        run_tests_partitioned(
+            runner, data, tests, tests_len, test_args, partitions, errors,
+        )
+    }
+}
+
+/// Implementation of [sts_TestRunner_run_tests_partitioned].
+///
+/// ## Safety
+///
+/// The same requirements as for [sts_TestRunner_run_tests] apply.
+unsafe fn run_tests_partitioned(
+    runner: &mut TestRunner,
+    data: &BitVec,
+    tests: *const RawTest,
+    tests_len: usize,
+    test_args: &RunnerTestArgs,
+    partitions: usize,
+    errors: ErrorSink,
+) -> c_int {
+    let Some(partitions) = NonZero::new(partitions) else {
+        errors.report(sts_lib::Error::InvalidParameter(
+            "The count of partitions must be at least 1, is: 0".to_owned(),
+        ));
+        return 1;
+    };
+
+    // SAFETY: same considerations apply to the call as for this function, caller has to ensure
+    // that the requirements are met.
+    let tests = unsafe { try_get_tests(tests, tests_len, errors) };
+
+    let tests = match tests {
+        Some(tests) => tests,
+        // Error was already reported
+        None => return 1,
+    };
+
+    runner.run_partitioned(data, tests.into_iter(), test_args.0, partitions, errors)
+}
+
+/// Runs all chosen tests on each of `count` windows of `window_length` bits of the given bit
+/// sequence, with the given test arguments. The windows don't overlap and are placed at random
+/// offsets generated from `seed`: running with the same seed, window length and count on a
+/// sequence of the same length tests the same windows. This allows testing extremely long
+/// sequences without only testing their start.
+///
+/// If `offsets` is not `NULL`, the offsets of the windows, in bits and sorted ascending, are
+/// written to it. The results are retrieved with [sts_TestRunner_get_partition_result], with the
+/// index of the window in `offsets`. The results of previous partitioned or subsampled runs are
+/// discarded, the results of [sts_TestRunner_run_tests] etc. are kept.
+///
+/// ## Return value
+///
+/// * If all tests ran successfully on all windows, `0` is returned.
+/// * If one of the tests specified was a duplicate of a previous test, `1` is returned.
+/// * If the windows are too short for one of the tests or its arguments, `1` is returned.
+/// * If one of the tests specified was not a valid test as per the enum [Test], `1` is returned.
+/// * If `window_length` or `count` is `0`, or the windows don't fit into the sequence, `1` is
+///   returned.
+/// * If an error occurred while running the tests, `2` is returned. All other tests are still done.
+///   The good test results can be retrieved with [sts_TestRunner_get_partition_result], the exact
+///   error can be retrieved.
+///
+/// In each error case, the error message and code can be found out with
+/// [sts_get_last_error).
+///
+/// ## Safety
+///
+/// * The same requirements as for [sts_TestRunner_run_tests] apply.
+/// * `offsets` must either be `NULL` or valid for writes of `count` elements.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn sts_TestRunner_run_tests_subsampled(
+    runner: &mut TestRunner,
+    data: &BitVec,
+    tests: *const RawTest,
+    tests_len: usize,
+    test_args: &RunnerTestArgs,
+    window_length: usize,
+    count: usize,
+    seed: u64,
+    offsets: *mut usize,
+) -> c_int {
+    let errors = ErrorSink::LastError;
+    // SAFETY: same considerations apply to the call as for this function.
+    unsafe {
+        run_tests_subsampled(
+            runner,
+            data,
+            tests,
+            tests_len,
+            test_args,
+            window_length,
+            count,
+            seed,
+            offsets,
+            errors,
+        )
+    }
+}
+
+/// Same as [sts_TestRunner_run_tests_subsampled], but an error is written to `error` instead of
+/// being stored as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * The same requirements as for [sts_TestRunner_run_tests_subsampled] apply.
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn sts_TestRunner_run_tests_subsampled_e(
+    runner: &mut TestRunner,
+    data: &BitVec,
+    tests: *const RawTest,
+    tests_len: usize,
+    test_args: &RunnerTestArgs,
+    window_length: usize,
+    count: usize,
+    seed: u64,
+    offsets: *mut usize,
+    error: *mut *mut StsError,
+) -> c_int {
+    // SAFETY: same considerations apply to the calls as for this function.
+    unsafe {
+        let errors = ErrorSink::out(error);
+        run_tests_subsampled(
+            runner,
+            data,
+            tests,
+            tests_len,
+            test_args,
+            window_length,
+            count,
+            seed,
+            offsets,
+            errors,
+        )
+    }
+}
+
+/// Implementation of [sts_TestRunner_run_tests_subsampled].
+///
+/// ## Safety
+///
+/// The same requirements as for [sts_TestRunner_run_tests_subsampled] apply.
+#[allow(clippy::too_many_arguments)]
+unsafe fn run_tests_subsampled(
+    runner: &mut TestRunner,
+    data: &BitVec,
+    tests: *const RawTest,
+    tests_len: usize,
+    test_args: &RunnerTestArgs,
+    window_length: usize,
+    count: usize,
+    seed: u64,
+    offsets: *mut usize,
+    errors: ErrorSink,
+) -> c_int {
+    let (Some(window_length), Some(count)) = (NonZero::new(window_length), NonZero::new(count))
+    else {
+        errors.report(sts_lib::Error::InvalidParameter(
+            "The window length and the count of windows must be at least 1".to_owned(),
+        ));
+        return 1;
+    };
+
+    let subsample = match Subsample::new(data.0.len_bit(), window_length, count, seed) {
+        Ok(subsample) => subsample,
+        Err(e) => {
+            errors.report(e);
+            return 1;
+        }
+    };
+
     // SAFETY: same considerations apply to the call as for this function, caller has to ensure
     // that the requirements are met.
-    let tests = unsafe { try_get_tests(tests, tests_len) };
+    let tests = unsafe { try_get_tests(tests, tests_len, errors) };
 
     let tests = match tests {
         Some(tests) => tests,
-        // Error message was already set
+        // Error was already reported
         None => return 1,
     };
 
-    let args = test_args.0;
+    if !offsets.is_null() {
+        // SAFETY: the caller has to ensure that offsets is valid for writes of count elements.
+        let offsets = unsafe { slice::from_raw_parts_mut(offsets, count.get()) };
+        offsets.copy_from_slice(subsample.offsets());
+    }
 
-    runner.handle_results(test_runner::run_tests(&data.0, tests.into_iter(), args))
+    runner.run_subsampled(data, tests.into_iter(), test_args.0, &subsample, errors)
+}
+
+/// Runs the tests of the given [Profile](profile::Profile) on the given bit sequence, with the
+/// test arguments of the profile.
+///
+/// ## Return value
+///
+/// * If all tests ran successfully, `0` is returned.
+/// * If the profile is invalid, `1` is returned.
+/// * If an error occurred while running the tests, `2` is returned. All other tests are still done.
+///   The good test results can be retrieved with [sts_TestRunner_get_result], the exact error can
+///   be retrieved.
+///
+/// In each error case, the error message and code can be found out with
+/// [sts_get_last_error).
+///
+/// ## Safety
+///
+/// * `runner` must have been created by [sts_TestRunner_new()]
+/// * `runner` must be valid for reads and writes and non-null.
+/// * `runner` may not be mutated for the duration of this call.
+/// * `bitvec` must have been created by either [sts_BitVec_from_str],
+///   [sts_BitVec_from_str_with_max_length],
+///   [sts_BitVec_from_bytes],
+///   [sts_BitVec_from_bits],
+///   [sts_BitVec_from_words],
+///   [sts_BitVec_borrow_words],
+///   [sts_BitVec_from_file],
+///   [sts_BitVec_clone] or [sts_BitVec_slice].
+/// * `bitvec` must be a non-null pointer valid for reads.
+/// * `bitvec` may not be mutated for the duration of this call.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestRunner_run_profile(
+    runner: &mut TestRunner,
+    data: &BitVec,
+    profile: RawProfile,
+) -> c_int {
+    run_profile(runner, data, profile, ErrorSink::LastError)
+}
+
+/// Same as [sts_TestRunner_run_profile], but an error is written to `error` instead of being
+/// stored as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * The same requirements as for [sts_TestRunner_run_profile] apply.
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_TestRunner_run_profile_e(
+    runner: &mut TestRunner,
+    data: &BitVec,
+    profile: RawProfile,
+    error: *mut *mut StsError,
+) -> c_int {
+    // SAFETY: the caller has to ensure that error is valid.
+    run_profile(runner, data, profile, unsafe { ErrorSink::out(error) })
+}
+
+/// Implementation of [sts_TestRunner_run_profile].
+fn run_profile(
+    runner: &mut TestRunner,
+    data: &BitVec,
+    profile: RawProfile,
+    errors: ErrorSink,
+) -> c_int {
+    let Some(profile) = try_get_profile(profile, errors) else {
+        // Error was already reported
+        return 1;
+    };
+
+    let tests = profile.tests().iter().copied();
+    runner.run(data, tests, profile.test_args(), errors)
 }
 
 /// Try to convert the pointer with offset to a list of tests.
-/// Returns None and sets an error if any of the tests was invalid.
+/// Returns None and reports an error if any of the tests was invalid.
 ///
 /// ## Safety
 ///
 /// * `tests` must be a valid, non-null pointer readable for up to `tests_len` elements.
 /// * `tests` may not be mutated for the duration of this call.
-unsafe fn try_get_tests(tests: *const RawTest, tests_len: usize) -> Option<Vec<sts_lib::Test>> {
+unsafe fn try_get_tests(
+    tests: *const RawTest,
+    tests_len: usize,
+    errors: ErrorSink,
+) -> Option<Vec<sts_lib::Test>> {
     // SAFETY: caller has to ensure that tests is valid for read of tests_len elements.
     let tests = unsafe { slice::from_raw_parts(tests, tests_len) };
 
@@ -318,7 +1301,7 @@ unsafe fn try_get_tests(tests: *const RawTest, tests_len: usize) -> Option<Vec<s
         .map(|&raw_test| match Test::try_from(raw_test) {
             Ok(test) => Some(sts_lib::Test::from(test)),
             Err(()) => {
-                set_last_invalid_test(raw_test);
+                errors.report(StsError::invalid_test(raw_test));
                 None
             }
         })
@@ -0,0 +1,134 @@
+//! Profiles: recommended test selections and test arguments.
+
+use crate::{ErrorSink, StsError};
+use std::ffi::{c_char, c_int, CStr};
+
+// Type of a raw profile, used for the FFI boundary (rust doesn't like it if a value is passed for
+// an enum that is not in the enum).
+pub type RawProfile = c_int;
+
+/// A profile bundles a selection of tests with the test arguments to use for them, see
+/// [sts_RunnerTestArgs_from_profile] and [sts_TestRunner_run_profile].
+///
+/// Each profile has a name, which can be converted with [sts_Profile_from_name].
+/// cbindgen:prefix-with-name=true
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+pub enum Profile {
+    /// Name: "nist-default". All tests of the current revision of SP 800-22, with its parameters
+    /// and the ones of its reference implementation.
+    NistDefault = 0,
+    /// Name: "bsi-ais31". The tests of SP 800-22 that correspond to the statistical tests of
+    /// BSI AIS 31, and its autocorrelation test. This does not replace an AIS 31 evaluation.
+    BsiAis31 = 1,
+    /// Name: "fast-screening". Only the fast tests with low input length requirements.
+    FastScreening = 2,
+}
+
+impl From<Profile> for sts_lib::profile::Profile {
+    fn from(value: Profile) -> Self {
+        match value {
+            Profile::NistDefault => sts_lib::profile::Profile::NistDefault,
+            Profile::BsiAis31 => sts_lib::profile::Profile::BsiAis31,
+            Profile::FastScreening => sts_lib::profile::Profile::FastScreening,
+        }
+    }
+}
+
+impl From<sts_lib::profile::Profile> for Profile {
+    fn from(value: sts_lib::profile::Profile) -> Self {
+        match value {
+            sts_lib::profile::Profile::NistDefault => Profile::NistDefault,
+            sts_lib::profile::Profile::BsiAis31 => Profile::BsiAis31,
+            sts_lib::profile::Profile::FastScreening => Profile::FastScreening,
+        }
+    }
+}
+
+impl TryFrom<RawProfile> for Profile {
+    type Error = ();
+
+    fn try_from(value: RawProfile) -> Result<Self, Self::Error> {
+        let profile = match value {
+            0 => Profile::NistDefault,
+            1 => Profile::BsiAis31,
+            2 => Profile::FastScreening,
+            _ => return Err(()),
+        };
+
+        Ok(profile)
+    }
+}
+
+/// Converts the raw profile, reporting an error if it is invalid.
+pub(crate) fn try_get_profile(
+    profile: RawProfile,
+    errors: ErrorSink,
+) -> Option<sts_lib::profile::Profile> {
+    match Profile::try_from(profile) {
+        Ok(profile) => Some(profile.into()),
+        Err(()) => {
+            errors.report(sts_lib::Error::InvalidParameter(format!(
+                "The numerical value {profile} is not a valid profile!"
+            )));
+            None
+        }
+    }
+}
+
+/// Returns the profile with the given name, e.g. "nist-default".
+///
+/// ## Return value
+///
+/// * The [Profile], if the name is valid.
+/// * `-1` if the name is not valid UTF-8 or no profile has this name. The error message and code
+///   can be found out with [sts_get_last_error].
+///
+/// ## Safety
+///
+/// * The memory pointed to by `name` must contain a valid nul terminator at the end of the string.
+/// * `name` must be valid, as defined by the Rust module safety documentation of `std::ptr`, for
+///   reads of bytes up to and including the nul terminator.
+/// * The memory referenced by `name` must not be mutated for the duration of this method call.
+/// * `name`, particularly the de-allocation of it, remains in the responsibility of the caller.
+#[no_mangle]
+pub unsafe extern "C" fn sts_Profile_from_name(name: *const c_char) -> RawProfile {
+    // SAFETY: same considerations apply to the call as for this function.
+    unsafe { profile_from_name(name, ErrorSink::LastError) }
+}
+
+/// Same as [sts_Profile_from_name], but an error is written to `error` instead of being stored as
+/// the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * The same requirements as for [sts_Profile_from_name] apply.
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_Profile_from_name_e(
+    name: *const c_char,
+    error: *mut *mut StsError,
+) -> RawProfile {
+    // SAFETY: same considerations apply to the calls as for this function.
+    unsafe { profile_from_name(name, ErrorSink::out(error)) }
+}
+
+/// Implementation of [sts_Profile_from_name].
+///
+/// ## Safety
+///
+/// The same requirements as for [sts_Profile_from_name] apply.
+unsafe fn profile_from_name(name: *const c_char, errors: ErrorSink) -> RawProfile {
+    // SAFETY: caller has to ensure that name is a valid nul-terminated string.
+    let name = unsafe { CStr::from_ptr(name) };
+
+    match name.to_str().map(str::parse::<sts_lib::profile::Profile>) {
+        Ok(Ok(profile)) => Profile::from(profile) as RawProfile,
+        _ => {
+            errors.report(sts_lib::Error::InvalidParameter(format!(
+                "{name:?} is not a valid profile name!"
+            )));
+            -1
+        }
+    }
+}
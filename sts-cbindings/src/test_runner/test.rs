@@ -41,6 +41,10 @@ pub enum Test {
     RandomExcursions = 13,
     /// See [sts_random_excursions_variant_test].
     RandomExcursionsVariant = 14,
+    /// See [sts_lempel_ziv_test]. Not part of the current revision of SP 800-22.
+    LempelZiv = 15,
+    /// See [sts_autocorrelation_test]. Not part of SP 800-22, but of BSI AIS 31.
+    Autocorrelation = 16,
 }
 
 // If any of these fails, you also need to adjust the TryFrom-Implementation
@@ -62,6 +66,8 @@ impl From<Test> for sts_lib::Test {
             Test::CumulativeSums => sts_lib::Test::CumulativeSums,
             Test::RandomExcursions => sts_lib::Test::RandomExcursions,
             Test::RandomExcursionsVariant => sts_lib::Test::RandomExcursionsVariant,
+            Test::LempelZiv => sts_lib::Test::LempelZiv,
+            Test::Autocorrelation => sts_lib::Test::Autocorrelation,
         }
     }
 }
@@ -84,6 +90,8 @@ impl From<sts_lib::Test> for Test {
             sts_lib::Test::CumulativeSums => Test::CumulativeSums,
             sts_lib::Test::RandomExcursions => Test::RandomExcursions,
             sts_lib::Test::RandomExcursionsVariant => Test::RandomExcursionsVariant,
+            sts_lib::Test::LempelZiv => Test::LempelZiv,
+            sts_lib::Test::Autocorrelation => Test::Autocorrelation,
         }
     }
 }
@@ -108,6 +116,8 @@ impl TryFrom<RawTest> for Test {
             12 => Test::CumulativeSums,
             13 => Test::RandomExcursions,
             14 => Test::RandomExcursionsVariant,
+            15 => Test::LempelZiv,
+            16 => Test::Autocorrelation,
             _ => return Err(()),
         };
 
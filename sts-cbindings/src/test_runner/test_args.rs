@@ -1,9 +1,14 @@
 //! Opaque struct for the test arguments.
 
 use crate::test_args::{
-    TestArgApproximateEntropy, TestArgFrequencyBlock, TestArgLinearComplexity,
-    TestArgNonOverlappingTemplate, TestArgOverlappingTemplate, TestArgSerial,
+    TestArgApproximateEntropy, TestArgAutocorrelation, TestArgBinaryMatrixRank,
+    TestArgFrequencyBlock, TestArgLinearComplexity, TestArgLongestRunOfOnes,
+    TestArgNonOverlappingTemplate, TestArgOverlappingTemplate, TestArgRandomExcursions,
+    TestArgRandomExcursionsVariant, TestArgSerial, TestArgSpectralDft,
 };
+use crate::test_runner::profile::{try_get_profile, RawProfile};
+use crate::{ErrorSink, StsError};
+use std::time::Duration;
 
 /// All test arguments for use in a *TestRunner*,
 /// prefilled with sane defaults.
@@ -23,11 +28,45 @@ pub extern "C" fn sts_RunnerTestArgs_new() -> Box<RunnerTestArgs> {
     Box::new(RunnerTestArgs(args))
 }
 
+/// Create new [RunnerTestArgs] with the test arguments of the given [Profile].
+///
+/// The arguments can be changed with the `runner_test_args_set_...` functions.
+///
+/// ## Return value
+///
+/// * The created arguments, which must be freed via [sts_RunnerTestArgs_destroy].
+/// * `NULL` if the profile is invalid. The error message and code can be found out with
+///   [sts_get_last_error].
+#[no_mangle]
+pub extern "C" fn sts_RunnerTestArgs_from_profile(
+    profile: RawProfile,
+) -> Option<Box<RunnerTestArgs>> {
+    try_get_profile(profile, ErrorSink::LastError)
+        .map(|profile| Box::new(RunnerTestArgs(profile.test_args())))
+}
+
+/// Same as [sts_RunnerTestArgs_from_profile], but an error is written to `error` instead of being
+/// stored as the last error, see [StsError].
+///
+/// ## Safety
+///
+/// * `error` must either be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sts_RunnerTestArgs_from_profile_e(
+    profile: RawProfile,
+    error: *mut *mut StsError,
+) -> Option<Box<RunnerTestArgs>> {
+    // SAFETY: the caller has to ensure that error is valid.
+    try_get_profile(profile, unsafe { ErrorSink::out(error) })
+        .map(|profile| Box::new(RunnerTestArgs(profile.test_args())))
+}
+
 /// Destroy the given [RunnerTestArgs].
 ///
 /// ## Safety
 ///
-/// * `args` must have been created by [sts_RunnerTestArgs_new()]
+/// * `args` must have been created by [sts_RunnerTestArgs_new()] or
+///   [sts_RunnerTestArgs_from_profile()]
 /// * `args` must be valid for reads and writes and non-null.
 /// * `args` may not be mutated for the duration of this call.
 /// * `args` will be an invalid pointer after this call, trying to access its memory will lead to
@@ -66,6 +105,21 @@ setter! {
     fn sts_RunnerTestArgs_set_frequency_block(frequency_block: TestArgFrequencyBlock);
 }
 
+setter! {
+    /// Set the argument for the Test for the Longest Run of Ones in a Block to the given value.
+    fn sts_RunnerTestArgs_set_longest_run_of_ones(longest_run_of_ones: TestArgLongestRunOfOnes);
+}
+
+setter! {
+    /// Set the argument for the Binary Matrix Rank Test to the given value.
+    fn sts_RunnerTestArgs_set_binary_matrix_rank(binary_matrix_rank: TestArgBinaryMatrixRank);
+}
+
+setter! {
+    /// Set the argument for the Spectral DFT Test to the given value.
+    fn sts_RunnerTestArgs_set_spectral_dft(spectral_dft: TestArgSpectralDft);
+}
+
 setter! {
     /// Set the argument for the Non-Overlapping Template Matching Test to the given value.
     fn sts_RunnerTestArgs_set_non_overlapping_template(non_overlapping_template: TestArgNonOverlappingTemplate);
@@ -90,3 +144,53 @@ setter! {
     /// Set the argument for the Approximate Entropy Test to the given value.
     fn sts_RunnerTestArgs_set_approximate_entropy(approximate_entropy: TestArgApproximateEntropy);
 }
+
+setter! {
+    /// Set the argument for the Random Excursions Test to the given value.
+    fn sts_RunnerTestArgs_set_random_excursions(random_excursions: TestArgRandomExcursions);
+}
+
+setter! {
+    /// Set the argument for the Random Excursions Variant Test to the given value.
+    fn sts_RunnerTestArgs_set_random_excursions_variant(random_excursions_variant: TestArgRandomExcursionsVariant);
+}
+
+setter! {
+    /// Set the argument for the Autocorrelation Test to the given value.
+    fn sts_RunnerTestArgs_set_autocorrelation(autocorrelation: TestArgAutocorrelation);
+}
+
+/// Set the timeout of each test run with these arguments, in seconds. A test that runs longer is
+/// aborted and returns an error with the code [ErrorCode::TimedOut]. The timeout is checked
+/// regularly by the slow tests, so a test may run a bit longer than the timeout.
+///
+/// A value of `0` or less removes the timeout (the default).
+///
+/// ## Return value
+///
+/// * `true`: the timeout was set.
+/// * `false`: the value is NaN or too large, the timeout is unchanged.
+///
+/// ## Safety
+///
+/// * `runner` must have been created by [runner_test_args_new()]
+/// * `runner` must be valid for reads and writes and non-null.
+/// * `runner` may not be mutated for the duration of this call.
+#[no_mangle]
+pub unsafe extern "C" fn sts_RunnerTestArgs_set_timeout(
+    runner: &mut RunnerTestArgs,
+    seconds: f64,
+) -> bool {
+    if seconds <= 0.0 {
+        runner.0.timeout = None;
+        return true;
+    }
+
+    match Duration::try_from_secs_f64(seconds) {
+        Ok(timeout) => {
+            runner.0.timeout = Some(timeout);
+            true
+        }
+        Err(_) => false,
+    }
+}
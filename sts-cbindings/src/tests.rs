@@ -2,17 +2,21 @@
 
 use crate::bitvec::BitVec;
 use crate::test_args::{
-    TestArgApproximateEntropy, TestArgFrequencyBlock, TestArgLinearComplexity,
-    TestArgNonOverlappingTemplate, TestArgOverlappingTemplate, TestArgSerial,
+    TestArgApproximateEntropy, TestArgAutocorrelation, TestArgBinaryMatrixRank,
+    TestArgFrequencyBlock, TestArgLinearComplexity, TestArgLongestRunOfOnes,
+    TestArgNonOverlappingTemplate, TestArgOverlappingTemplate, TestArgRandomExcursions,
+    TestArgRandomExcursionsVariant, TestArgSerial, TestArgSpectralDft,
 };
-use crate::test_result::TestResult;
+use crate::test_result::{TestResult, TestResultList};
+use crate::{ErrorSink, StsError};
 use sts_lib::tests;
 
-/// Macro for generating a valid C function that calls the rust test internally.
+/// Macro for generating a valid C function that calls the rust test internally, and its variant
+/// with the suffix `_e` that returns the error via an out-parameter.
 macro_rules! test_wrapper {
     (
         $(#[$comment: meta])*
-        fn $name: ident => $call: expr;
+        fn $name: ident, $name_e: ident => $call: expr;
     ) => {
         $(#[$comment])*
         #[doc = ""]
@@ -29,30 +33,31 @@ macro_rules! test_wrapper {
         #[doc = " * All responsibility for `data`, particularly for its destruction, remains with the caller."]
         #[no_mangle]
         pub unsafe extern "C" fn $name(data: &BitVec) -> Option<Box<TestResult>> {
-            let result = $call(&data.0);
+            single_result($call(&data.0), ErrorSink::LastError)
+        }
 
-            match result {
-                Ok(res) => {
-                    Some(Box::new(TestResult(res)))
-                },
-                Err(err) => {
-                    // Set error for calling later
-                    crate::set_last_from_error(err);
-                    None
-                }
-            }
+        #[doc = concat!(" Same as [", stringify!($name), "], but an error is written to `error` instead of being stored as the last error, see [StsError].")]
+        #[doc = ""]
+        #[doc = " ## Safety"]
+        #[doc = ""]
+        #[doc = concat!(" * The same requirements as for [", stringify!($name), "] apply.")]
+        #[doc = " * `error` must either be `NULL` or valid for writes."]
+        #[no_mangle]
+        pub unsafe extern "C" fn $name_e(data: &BitVec, error: *mut *mut StsError) -> Option<Box<TestResult>> {
+            // SAFETY: the caller has to ensure that error is valid.
+            single_result($call(&data.0), unsafe { ErrorSink::out(error) })
         }
     };
     (
         $(#[$comment: meta])*
-        fn $name: ident(() => fixed_array($length: literal)) => $call: expr;
+        fn $name: ident, $name_e: ident(() => fixed_array($length: literal)) => $call: expr;
     ) => {
         $(#[$comment])*
         #[doc = ""]
         #[doc = " ## Return value"]
         #[doc = ""]
-        #[doc = " If the test ran without errors, a list of `TestResult` is returned. This result can be deallocated with `test_result_list_destroy`."]
-        #[doc = concat!(" The returned array always has length ", stringify!($length), ".")]
+        #[doc = " If the test ran without errors, a `TestResultList` is returned. This list can be deallocated with `sts_TestResultList_destroy`."]
+        #[doc = concat!(" The returned list always has length ", stringify!($length), ".")]
         #[doc = " If an error occurred, `NULL` is returned, and the error code and message can be retrieved with `get_last_error`."]
         #[doc = ""]
         #[doc = " ## Safety"]
@@ -62,28 +67,25 @@ macro_rules! test_wrapper {
         #[doc = " * `data` may not be mutated for the duration of this call."]
         #[doc = " * All responsibility for `data`, particularly for its destruction, remains with the caller."]
         #[no_mangle]
-        pub unsafe extern "C" fn $name(data: &BitVec) -> *mut Box<TestResult> {
-            let result = $call(&data.0);
+        pub unsafe extern "C" fn $name(data: &BitVec) -> Option<Box<TestResultList>> {
+            result_list($call(&data.0), ErrorSink::LastError)
+        }
 
-            match result {
-                Ok(res) => {
-                    let vec: Box<[Box<TestResult>]> = Box::into_iter(Vec::from(res).into_boxed_slice())
-                        .map(|res| Box::new(TestResult(res)))
-                        .collect();
-                    // strip away the length information
-                    Box::into_raw(vec) as *mut Box<TestResult>
-                },
-                Err(err) => {
-                    // Set error for calling later
-                    crate::set_last_from_error(err);
-                    std::ptr::null_mut()
-                }
-            }
+        #[doc = concat!(" Same as [", stringify!($name), "], but an error is written to `error` instead of being stored as the last error, see [StsError].")]
+        #[doc = ""]
+        #[doc = " ## Safety"]
+        #[doc = ""]
+        #[doc = concat!(" * The same requirements as for [", stringify!($name), "] apply.")]
+        #[doc = " * `error` must either be `NULL` or valid for writes."]
+        #[no_mangle]
+        pub unsafe extern "C" fn $name_e(data: &BitVec, error: *mut *mut StsError) -> Option<Box<TestResultList>> {
+            // SAFETY: the caller has to ensure that error is valid.
+            result_list($call(&data.0), unsafe { ErrorSink::out(error) })
         }
     };
     (
         $(#[$comment: meta])*
-        fn $name: ident($argtype: ty) => $call: expr;
+        fn $name: ident, $name_e: ident($argtype: ty) => $call: expr;
     ) => {
         $(#[$comment])*
         #[doc = ""]
@@ -103,30 +105,31 @@ macro_rules! test_wrapper {
         #[doc = " * All responsibility for `data` and `test_arg`, particularly for their destruction, remains with the caller."]
         #[no_mangle]
         pub unsafe extern "C" fn $name(data: &BitVec, test_arg: &$argtype) -> Option<Box<TestResult>> {
-            let result = $call(&data.0, test_arg.into());
+            single_result($call(&data.0, test_arg.into()), ErrorSink::LastError)
+        }
 
-            match result {
-                Ok(res) => {
-                    Some(Box::new(TestResult(res)))
-                },
-                Err(err) => {
-                    // Set error for calling later
-                    crate::set_last_from_error(err);
-                    None
-                }
-            }
+        #[doc = concat!(" Same as [", stringify!($name), "], but an error is written to `error` instead of being stored as the last error, see [StsError].")]
+        #[doc = ""]
+        #[doc = " ## Safety"]
+        #[doc = ""]
+        #[doc = concat!(" * The same requirements as for [", stringify!($name), "] apply.")]
+        #[doc = " * `error` must either be `NULL` or valid for writes."]
+        #[no_mangle]
+        pub unsafe extern "C" fn $name_e(data: &BitVec, test_arg: &$argtype, error: *mut *mut StsError) -> Option<Box<TestResult>> {
+            // SAFETY: the caller has to ensure that error is valid.
+            single_result($call(&data.0, test_arg.into()), unsafe { ErrorSink::out(error) })
         }
     };
     (
         $(#[$comment: meta])*
-        fn $name: ident($argtype: ty => dynamic_array) => $call: expr;
+        fn $name: ident, $name_e: ident($argtype: ty => dynamic_array) => $call: expr;
     ) => {
         $(#[$comment])*
         #[doc = ""]
         #[doc = " ## Return value"]
         #[doc = ""]
-        #[doc = " If the test ran without errors, a list of `TestResult` is returned. This list can be deallocated with `test_result_list_destroy`."]
-        #[doc = " The length of the returned list will be stored into `length`."]
+        #[doc = " If the test ran without errors, a `TestResultList` is returned. This list can be deallocated with `sts_TestResultList_destroy`."]
+        #[doc = " The length of the returned list is returned by `sts_TestResultList_len`."]
         #[doc = " If an error occurred, `NULL` is returned, and the error code and message can be retrieved with `get_last_error`."]
         #[doc = ""]
         #[doc = " ## Safety"]
@@ -137,40 +140,34 @@ macro_rules! test_wrapper {
         #[doc = " * `test_arg` must have been created by one of the construction methods provided by this library."]
         #[doc = " * `test_arg` must be valid for reads and non-null."]
         #[doc = " * `test_arg` may not be mutated for the duration of this call."]
-        #[doc = " * `length` must be valid for writes and non-null."]
-        #[doc = " * `length` may not be mutated for the duration of this call."]
-        #[doc = " * All responsibility for `data`, `test_arg` and `length`, particularly for their destruction, remains with the caller."]
+        #[doc = " * All responsibility for `data` and `test_arg`, particularly for their destruction, remains with the caller."]
         #[no_mangle]
-        pub unsafe extern "C" fn $name(data: &BitVec, test_arg: &$argtype, length: &mut usize) -> *mut Box<TestResult> {
-            let result = $call(&data.0, test_arg.into());
+        pub unsafe extern "C" fn $name(data: &BitVec, test_arg: &$argtype) -> Option<Box<TestResultList>> {
+            result_list($call(&data.0, test_arg.into()), ErrorSink::LastError)
+        }
 
-            match result {
-                Ok(res) => {
-                    *length = res.len();
-                    let vec: Box<[Box<TestResult>]> = Box::into_iter(res.into_boxed_slice())
-                        .map(|res| Box::new(TestResult(res)))
-                        .collect();
-                    // strip away the length information
-                    Box::into_raw(vec) as *mut Box<TestResult>
-                },
-                Err(err) => {
-                    // Set error for calling later
-                    crate::set_last_from_error(err);
-                    std::ptr::null_mut()
-                }
-            }
+        #[doc = concat!(" Same as [", stringify!($name), "], but an error is written to `error` instead of being stored as the last error, see [StsError].")]
+        #[doc = ""]
+        #[doc = " ## Safety"]
+        #[doc = ""]
+        #[doc = concat!(" * The same requirements as for [", stringify!($name), "] apply.")]
+        #[doc = " * `error` must either be `NULL` or valid for writes."]
+        #[no_mangle]
+        pub unsafe extern "C" fn $name_e(data: &BitVec, test_arg: &$argtype, error: *mut *mut StsError) -> Option<Box<TestResultList>> {
+            // SAFETY: the caller has to ensure that error is valid.
+            result_list($call(&data.0, test_arg.into()), unsafe { ErrorSink::out(error) })
         }
     };
     (
         $(#[$comment: meta])*
-        fn $name: ident($argtype: ty => fixed_array($length: literal)) => $call: expr;
+        fn $name: ident, $name_e: ident($argtype: ty => fixed_array($length: literal)) => $call: expr;
     ) => {
         $(#[$comment])*
         #[doc = ""]
         #[doc = " ## Return value"]
         #[doc = ""]
-        #[doc = " If the test ran without errors, a list of `TestResult` is returned. This list can be deallocated with `test_result_list_destroy`."]
-        #[doc = concat!(" The returned array always has length ", stringify!($length), ".")]
+        #[doc = " If the test ran without errors, a `TestResultList` is returned. This list can be deallocated with `sts_TestResultList_destroy`."]
+        #[doc = concat!(" The returned list always has length ", stringify!($length), ".")]
         #[doc = " If an error occurred, `NULL` is returned, and the error code and message can be retrieved with `get_last_error`."]
         #[doc = ""]
         #[doc = " ## Safety"]
@@ -183,32 +180,60 @@ macro_rules! test_wrapper {
         #[doc = " * `test_arg` may not be mutated for the duration of this call."]
         #[doc = " * All responsibility for `data` and `test_arg`, particularly for their destruction, remains with the caller."]
         #[no_mangle]
-        pub unsafe extern "C" fn $name(data: &BitVec, test_arg: &$argtype) -> *mut Box<TestResult> {
-            let result = $call(&data.0, test_arg.into());
+        pub unsafe extern "C" fn $name(data: &BitVec, test_arg: &$argtype) -> Option<Box<TestResultList>> {
+            result_list($call(&data.0, test_arg.into()), ErrorSink::LastError)
+        }
 
-            match result {
-                Ok(res) => {
-                    let vec: Box<[Box<TestResult>]> = Box::into_iter(Vec::from(res).into_boxed_slice())
-                        .map(|res| Box::new(TestResult(res)))
-                        .collect();
-                    Box::into_raw(vec) as *mut Box<TestResult>
-                },
-                Err(err) => {
-                    // Set error for calling later
-                    crate::set_last_from_error(err);
-                    std::ptr::null_mut()
-                }
-            }
+        #[doc = concat!(" Same as [", stringify!($name), "], but an error is written to `error` instead of being stored as the last error, see [StsError].")]
+        #[doc = ""]
+        #[doc = " ## Safety"]
+        #[doc = ""]
+        #[doc = concat!(" * The same requirements as for [", stringify!($name), "] apply.")]
+        #[doc = " * `error` must either be `NULL` or valid for writes."]
+        #[no_mangle]
+        pub unsafe extern "C" fn $name_e(data: &BitVec, test_arg: &$argtype, error: *mut *mut StsError) -> Option<Box<TestResultList>> {
+            // SAFETY: the caller has to ensure that error is valid.
+            result_list($call(&data.0, test_arg.into()), unsafe { ErrorSink::out(error) })
         }
     };
 }
 
+/// Converts the result of a test with a single result for the FFI boundary, reporting the error
+/// if there is one.
+fn single_result(
+    result: Result<sts_lib::TestResult, sts_lib::Error>,
+    errors: ErrorSink,
+) -> Option<Box<TestResult>> {
+    match result {
+        Ok(res) => Some(Box::new(TestResult(res))),
+        Err(err) => {
+            errors.report(err);
+            None
+        }
+    }
+}
+
+/// Converts the result of a test with multiple results for the FFI boundary, reporting the error
+/// if there is one.
+fn result_list(
+    result: Result<impl IntoIterator<Item = sts_lib::TestResult>, sts_lib::Error>,
+    errors: ErrorSink,
+) -> Option<Box<TestResultList>> {
+    match result {
+        Ok(res) => Some(Box::new(TestResultList::new(res))),
+        Err(err) => {
+            errors.report(err);
+            None
+        }
+    }
+}
+
 test_wrapper! {
     /// Frequency (mono bit) test - No. 1
     ///
     /// This test focuses on the numbers of ones and zeros in the sequence - the proportion should
     /// be roughly 50:50.
-    fn sts_frequency_test => tests::frequency::frequency_test;
+    fn sts_frequency_test, sts_frequency_test_e => tests::frequency::frequency_test;
 }
 
 test_wrapper! {
@@ -216,7 +241,7 @@ test_wrapper! {
     ///
     /// This tests for the same property as [sts_frequency_test], but within M-bit blocks.
     /// It is recommended that each block has a length of at least 100 bits.
-    fn sts_frequency_block_test(TestArgFrequencyBlock) => tests::frequency_block::frequency_block_test;
+    fn sts_frequency_block_test, sts_frequency_block_test_e(TestArgFrequencyBlock) => tests::frequency_block::frequency_block_test;
 }
 
 test_wrapper! {
@@ -225,7 +250,7 @@ test_wrapper! {
     /// This tests focuses on the number of runs in the sequence. A run is an uninterrupted sequence of
     /// identical bits.
     /// Each tested [BitVec] should have at least 100 bits length.
-    fn sts_runs_test => tests::runs::runs_test;
+    fn sts_runs_test, sts_runs_test_e => tests::runs::runs_test;
 }
 
 test_wrapper! {
@@ -237,18 +262,21 @@ test_wrapper! {
     /// An irregularity in the length of longest run of ones also implies an irregularity in the length
     /// of the longest runs of zeroes, meaning that only this test is necessary. See the NIST publication.
     ///
-    /// The data has to be at least 128 bits in length.
-    fn sts_longest_run_of_ones_test => tests::longest_run_of_ones::longest_run_of_ones_test;
+    /// The data has to be at least 128 bits in length. The configuration of SP 800-22 (block length
+    /// 8, 128 or 10000 bits) is chosen based on the input length, unless it is set with
+    /// [TestArgLongestRunOfOnes].
+    fn sts_longest_run_of_ones_test, sts_longest_run_of_ones_test_e(TestArgLongestRunOfOnes) => tests::longest_run_of_ones::longest_run_of_ones_test;
 }
 
 test_wrapper! {
     /// Binary Matrix Rank Test -  No. 5
     ///
     /// This test checks for linear dependence among fixed length substrings of the sequence.
-    /// These substrings are interpreted as matrices of size 32x32.
+    /// These substrings are interpreted as matrices of size 32x32, other dimensions can be set
+    /// with [TestArgBinaryMatrixRank].
     ///
-    /// The sequence must consist of at least 38 912 bits = 4864 bytes.
-    fn sts_binary_matrix_rank_test => tests::binary_matrix_rank::binary_matrix_rank_test;
+    /// The sequence must consist of at least 38 912 bits = 4864 bytes for 32x32 matrices.
+    fn sts_binary_matrix_rank_test, sts_binary_matrix_rank_test_e(TestArgBinaryMatrixRank) => tests::binary_matrix_rank::binary_matrix_rank_test;
 }
 
 test_wrapper! {
@@ -258,7 +286,10 @@ test_wrapper! {
     /// periodic features that indicate a deviation from a random sequence.
     ///
     /// It is recommended (but not required) for the input to be of at least 1000 bits.
-    fn sts_spectral_dft_test => tests::spectral_dft::spectral_dft_test;
+    ///
+    /// This test allows to choose the derivation of the peak height threshold, see
+    /// [TestArgSpectralDft].
+    fn sts_spectral_dft_test, sts_spectral_dft_test_e(TestArgSpectralDft) => tests::spectral_dft::spectral_dft_test;
 }
 
 test_wrapper! {
@@ -268,7 +299,7 @@ test_wrapper! {
     /// This test uses an m-bit window to search for an m-bit pattern.
     ///
     /// This test allows for parameters, see [TestArgNonOverlappingTemplate].
-    fn sts_non_overlapping_template_matching_test(TestArgNonOverlappingTemplate => dynamic_array) => tests::template_matching::non_overlapping::non_overlapping_template_matching_test;
+    fn sts_non_overlapping_template_matching_test, sts_non_overlapping_template_matching_test_e(TestArgNonOverlappingTemplate => dynamic_array) => tests::template_matching::non_overlapping::non_overlapping_template_matching_test;
 }
 
 test_wrapper! {
@@ -297,10 +328,10 @@ test_wrapper! {
     ///
     /// # About performance
     ///
-    /// This test is quite slow in debug mode when using the more precise pi values (non-NIST behaviour),
-    /// taking several seconds - it runs good when using release mode.
+    /// The more precise pi values (non-NIST behaviour) take time quadratic in the block length, e.g.
+    /// 3 ms for a block length of 1032 - except for the default arguments, whose values are built in.
     /// For better performance, values that are calculated once are cached.
-    fn sts_overlapping_template_matching_test(TestArgOverlappingTemplate) => tests::template_matching::overlapping::overlapping_template_matching_test;
+    fn sts_overlapping_template_matching_test, sts_overlapping_template_matching_test_e(TestArgOverlappingTemplate) => tests::template_matching::overlapping::overlapping_template_matching_test;
 }
 
 test_wrapper! {
@@ -311,7 +342,7 @@ test_wrapper! {
     ///
     /// The recommended minimum length of the sequence is 387 840 bits. The absolute minimum length to
     /// be used is 2020 bits, smaller inputs will raise an error.
-    fn sts_maurers_universal_statistical_test => tests::maurers_universal_statistical::maurers_universal_statistical_test;
+    fn sts_maurers_universal_statistical_test, sts_maurers_universal_statistical_test_e => tests::maurers_universal_statistical::maurers_universal_statistical_test;
 }
 
 test_wrapper! {
@@ -322,7 +353,7 @@ test_wrapper! {
     ///
     /// This test needs a parameter, [TestArgLinearComplexity]. Additionally, the input sequence
     /// must have a minimum length of 10^6 bits. Smaller lengths will raise an error.
-    fn sts_linear_complexity_test(TestArgLinearComplexity) => tests::linear_complexity::linear_complexity_test;
+    fn sts_linear_complexity_test, sts_linear_complexity_test_e(TestArgLinearComplexity) => tests::linear_complexity::linear_complexity_test;
 }
 
 test_wrapper! {
@@ -344,7 +375,7 @@ test_wrapper! {
     ///
     /// If the combination of the given data ([BitVec]) and [TestArgSerial] is invalid,
     /// an error is raised. For the exact constraints, see [TestArgSerial].
-    fn sts_serial_test(TestArgSerial => fixed_array(2)) => tests::serial::serial_test;
+    fn sts_serial_test, sts_serial_test_e(TestArgSerial => fixed_array(2)) => tests::serial::serial_test;
 }
 
 test_wrapper! {
@@ -362,7 +393,7 @@ test_wrapper! {
     ///
     /// If the combination of the given data ([BitVec]) and [TestArgApproximateEntropy] is invalid,
     /// an error is raised. For the exact constraints, see [TestArgApproximateEntropy].
-    fn sts_approximate_entropy_test(TestArgApproximateEntropy) => tests::approximate_entropy::approximate_entropy_test;
+    fn sts_approximate_entropy_test, sts_approximate_entropy_test_e(TestArgApproximateEntropy) => tests::approximate_entropy::approximate_entropy_test;
 }
 
 test_wrapper! {
@@ -375,7 +406,7 @@ test_wrapper! {
     ///
     /// The input sequence should be at least 100 bits in length, smaller sequences will raise
     /// an error.
-    fn sts_cumulative_sums_test(() => fixed_array(2)) => tests::cumulative_sums::cumulative_sums_test;
+    fn sts_cumulative_sums_test, sts_cumulative_sums_test_e(() => fixed_array(2)) => tests::cumulative_sums::cumulative_sums_test;
 }
 
 test_wrapper! {
@@ -389,12 +420,13 @@ test_wrapper! {
     /// Since the test needs at least 500 cycles to occur, bit sequences with fewer cycles will lead to an
     /// `Ok()` result, but with the values filled with "0.0".
     ///
-    /// If the computation finishes successfully, 8 [TestResult] are returned: one for each tested state,
-    /// `x`. The results will contain a comment about the state they are calculated from (e.g. "x = 3"),
-    /// the order is: `[-4, -3, -2, -1, +1, +2, +3, +4]`.
+    /// The tested states *x* can be configured with [TestArgRandomExcursions], NIST uses the states
+    /// `-4..=+4`. If the computation finishes successfully, one [TestResult] is returned for each
+    /// tested state. The results will contain a comment about the state they are calculated from
+    /// (e.g. "x = +3"), the order is ascending: `[-4, -3, -2, -1, +1, +2, +3, +4]` with the defaults.
     ///
     /// The input length must be at least 10^6 bits, otherwise, an error is raised.
-    fn sts_random_excursions_test(() => fixed_array(8)) => tests::random_excursions::random_excursions_test;
+    fn sts_random_excursions_test, sts_random_excursions_test_e(TestArgRandomExcursions => dynamic_array) => tests::random_excursions::random_excursions_test;
 }
 
 test_wrapper! {
@@ -406,10 +438,42 @@ test_wrapper! {
     ///
     /// This test does not require a minimum number of cycles.
     ///
-    /// If the computation finishes successfully, 18 [TestResult] are returned: one for each tested state,
-    /// `x`. The results will contain a comment about the state they are calculated from (e.g. "x = 3"),
-    /// the order is: `[-9, -8, -7, -6, -5, -4, -3, -2, -1, +1, +2, +3, +4, +5, +6, +7, +8, +9]`.
+    /// The tested states *x* can be configured with [TestArgRandomExcursionsVariant], NIST uses the
+    /// states `-9..=+9`. If the computation finishes successfully, one [TestResult] is returned for
+    /// each tested state. The results will contain a comment about the state they are calculated
+    /// from (e.g. "x = +3"), the order is ascending: `[-9, -8, ..., -1, +1, ..., +8, +9]` with the
+    /// defaults.
     ///
     /// The input length must be at least 10^6 bits, otherwise, an error is returned.
-    fn sts_random_excursions_variant_test(() => fixed_array(18)) => tests::random_excursions_variant::random_excursions_variant_test;
+    fn sts_random_excursions_variant_test, sts_random_excursions_variant_test_e(TestArgRandomExcursionsVariant => dynamic_array) => tests::random_excursions_variant::random_excursions_variant_test;
+}
+
+test_wrapper! {
+    /// The Lempel-Ziv compression test - **not part of the current revision of SP 800-22**.
+    ///
+    /// This test was part of the original SP 800-22 (2001) and was removed in revision 1, because
+    /// the reference distribution of its test statistic is inaccurate. It is only provided for
+    /// comparative studies.
+    ///
+    /// This test counts the cumulatively distinct words of the Lempel-Ziv parsing of the sequence.
+    /// A sequence that can be compressed significantly is considered non-random.
+    ///
+    /// The input length must be at least 10^6 bits, otherwise, an error is returned. Only the first
+    /// 10^6 bits are tested.
+    fn sts_lempel_ziv_test, sts_lempel_ziv_test_e => tests::lempel_ziv::lempel_ziv_test;
+}
+
+test_wrapper! {
+    /// The autocorrelation test - **not part of SP 800-22**, but test T5 of BSI AIS 31.
+    ///
+    /// This test compares the sequence with a copy of itself, shifted by *d* bits: the count of
+    /// bits that differ from the bit *d* positions later should be about half of the compared bits.
+    ///
+    /// This test needs a parameter [TestArgAutocorrelation]. Check the described constraints there.
+    ///
+    /// The input length should be at least 10^4 bits, like in AIS 31, although this is not enforced.
+    ///
+    /// If the combination of the given data ([BitVec]) and [TestArgAutocorrelation] is invalid,
+    /// an error is raised. For the exact constraints, see [TestArgAutocorrelation].
+    fn sts_autocorrelation_test, sts_autocorrelation_test_e(TestArgAutocorrelation) => tests::autocorrelation::autocorrelation_test;
 }
@@ -44,7 +44,8 @@
 
 /**
  * The error codes that are returned by some fallible functions.
- * A human-readable error message can be retrieved with [sts_get_last_error].
+ * A human-readable error message can be retrieved with [sts_get_last_error] or
+ * [sts_error_message].
  */
 typedef enum {
   /**
@@ -91,8 +92,139 @@ typedef enum {
    * The test whose result was tried to be retrieved from the test runner was not run.
    */
   ErrorCode_TestWasNotRun = 10,
+  /**
+   * The test was cancelled with a cancellation token, see [sts_CancellationToken_cancel].
+   */
+  ErrorCode_Cancelled = 11,
+  /**
+   * Reading an input file failed, or its content is invalid for the given format.
+   */
+  ErrorCode_InputFile = 12,
+  /**
+   * The test took longer than the timeout of the test arguments, see
+   * [sts_RunnerTestArgs_set_timeout].
+   */
+  ErrorCode_TimedOut = 13,
+  /**
+   * The input passed to the test runner is too short for one of the tests, or doesn't work with
+   * the arguments of one of the tests. All problems are listed in the error message.
+   */
+  ErrorCode_InvalidRunnerInput = 14,
+  /**
+   * A test argument could not be created, because one of the given values is not allowed. The
+   * error message states the value and the allowed values.
+   */
+  ErrorCode_InvalidArgument = 15,
 } ErrorCode;
 
+/**
+ * The single tests of FIPS 140-2, see [sts_fips140_2_tests].
+ */
+typedef enum {
+  /**
+   * The count of ones must lie in (9725, 10275).
+   */
+  Fips140Test_Monobit = 0,
+  /**
+   * The statistic of the 4-bit segments must lie in (2.16, 46.17).
+   */
+  Fips140Test_Poker = 1,
+  /**
+   * The count of runs of each length must lie within the bounds of FIPS 140-2.
+   */
+  Fips140Test_Runs = 2,
+  /**
+   * There may be no run of 26 or more equal bits.
+   */
+  Fips140Test_LongRun = 3,
+} Fips140Test;
+
+/**
+ * The format of an input file, see [sts_BitVec_from_file].
+ */
+typedef enum {
+  /**
+   * Binary input: each byte contains 8 bits, starting with the MSB.
+   */
+  InputFormat_Binary = 0,
+  /**
+   * ASCII input: the character "0" maps to 0 and "1" maps to 1. No other character is allowed.
+   */
+  InputFormat_Ascii = 1,
+  /**
+   * ASCII input: the character "0" maps to 0 and "1" maps to 1. Any other character is skipped.
+   */
+  InputFormat_AsciiLossy = 2,
+  /**
+   * Hexadecimal text input: each hex digit contains 4 bits, starting with the MSB. Whitespace is
+   * skipped, no other character is allowed.
+   */
+  InputFormat_Hex = 3,
+  /**
+   * Hexadecimal text input: each hex digit contains 4 bits, starting with the MSB. Any other
+   * character is skipped.
+   */
+  InputFormat_HexLossy = 4,
+  /**
+   * Base64 text input, decoded to bytes, each byte containing 8 bits, starting with the MSB.
+   * Whitespace is skipped, no other character is allowed.
+   */
+  InputFormat_Base64 = 5,
+  /**
+   * ASCII input: the character "0" maps to 0 and "1" maps to 1. Whitespace is skipped, no other
+   * character is allowed. This is the format of the data files of the NIST reference
+   * implementation.
+   */
+  InputFormat_AsciiWhitespace = 6,
+} InputFormat;
+
+/**
+ * A profile bundles a selection of tests with the test arguments to use for them, see
+ * [sts_RunnerTestArgs_from_profile] and [sts_TestRunner_run_profile].
+ *
+ * Each profile has a name, which can be converted with [sts_Profile_from_name].
+ */
+typedef enum {
+  /**
+   * Name: "nist-default". All tests of the current revision of SP 800-22, with its parameters
+   * and the ones of its reference implementation.
+   */
+  Profile_NistDefault = 0,
+  /**
+   * Name: "bsi-ais31". The tests of SP 800-22 that correspond to the statistical tests of
+   * BSI AIS 31, and its autocorrelation test. This does not replace an AIS 31 evaluation.
+   */
+  Profile_BsiAis31 = 1,
+  /**
+   * Name: "fast-screening". Only the fast tests with low input length requirements.
+   */
+  Profile_FastScreening = 2,
+} Profile;
+
+/**
+ * The strategies to choose the block length of the Frequency test within a block automatically,
+ * see [sts_TestArgFrequencyBlock_new_with_strategy].
+ *
+ * All strategies choose a block length of at least 20 bits, greater than 1% of the input length,
+ * so that there are fewer than 100 blocks - if the input is long enough for at least 2 blocks.
+ */
+typedef enum {
+  /**
+   * The smallest recommended block length, rounded up to a multiple of the word size if there
+   * are still at least 2 blocks. This is the strategy of [sts_TestArgFrequencyBlock_default].
+   */
+  SelectionStrategy_NistRecommended = 0,
+  /**
+   * The smallest recommended block length, which gives the most blocks.
+   */
+  SelectionStrategy_MaximizeBlocks = 1,
+  /**
+   * The smallest recommended block length that is a power of two, if there are still at least
+   * 2 blocks.
+   */
+  SelectionStrategy_PowerOfTwo = 2,
+} SelectionStrategy;
+
 /**
  * List of all tests, used for automatic running.
  */
@@ -157,6 +289,14 @@ typedef enum {
    * See [sts_random_excursions_variant_test].
    */
   Test_RandomExcursionsVariant = 14,
+  /**
+   * See [sts_lempel_ziv_test]. Not part of the current revision of SP 800-22.
+   */
+  Test_LempelZiv = 15,
+  /**
+   * See [sts_autocorrelation_test]. Not part of SP 800-22, but of BSI AIS 31.
+   */
+  Test_Autocorrelation = 16,
 } Test;
 
 /**
@@ -164,6 +304,20 @@ typedef enum {
  */
 typedef struct BitVec BitVec;
 
+/**
+ * A token to cancel the tests of a *TestRunner* from another thread.
+ *
+ * Set the token with [sts_TestRunner_set_cancellation_token], then call
+ * [sts_CancellationToken_cancel] from any thread to abort the running tests. Cancelled tests
+ * return the error code `ErrorCode_Cancelled`.
+ */
+typedef struct CancellationToken CancellationToken;
+
+/**
+ * The result of the FIPS 140-2 tests, see [sts_fips140_2_tests].
+ */
+typedef struct Fips140Result Fips140Result;
+
 /**
  * All test arguments for use in a *TestRunner*,
  * prefilled with sane defaults.
@@ -172,6 +326,20 @@ typedef struct BitVec BitVec;
  */
 typedef struct RunnerTestArgs RunnerTestArgs;
 
+/**
+ * An error returned by the `_e` variants of the fallible functions, e.g. [sts_BitVec_from_file_e].
+ *
+ * Each fallible function that reports its error with [sts_get_last_error] has a variant with the
+ * suffix `_e`, which takes an additional out-parameter `StsError **error`: if an error happens,
+ * an owned error is written to it, otherwise it is set to `NULL`. In contrast to
+ * [sts_get_last_error], the error is not bound to the calling thread and is not overwritten by
+ * later calls. If `error` is `NULL`, the error is discarded.
+ *
+ * The [ErrorCode] of the error is returned by [sts_error_code], the error message by
+ * [sts_error_message]. The error must be freed with [sts_error_free].
+ */
+typedef struct StsError StsError;
+
 /**
  * The argument for the Approximate Entropy Test: the block length in bits to check.
  *
@@ -191,6 +359,37 @@ typedef struct RunnerTestArgs RunnerTestArgs;
  */
 typedef struct TestArgApproximateEntropy TestArgApproximateEntropy;
 
+/**
+ * The argument for the Autocorrelation Test: the shift *d* in bits, i.e. the distance of the
+ * compared bits.
+ *
+ * Argument constraints:
+ * 1. the shift must be >= 1.
+ * 2. the shift must be <= bit_len / 2.
+ *
+ * Constraint 1 is checked when creating the argument.
+ *
+ * Constraint 2 is checked on executing the test. If the constraint is violated,
+ * an error will be raised.
+ *
+ * The default value for this argument is 1, i.e. each bit is compared with its successor.
+ */
+typedef struct TestArgAutocorrelation TestArgAutocorrelation;
+
+/**
+ * The argument for the Binary Matrix Rank Test: the dimensions of the matrices, *M* rows and
+ * *Q* columns.
+ *
+ * Argument constraints:
+ * 1. the rows and columns must each be between 2 and 64.
+ * 2. each of the 3 rank classes must be expected at least 5 times, for 32x32 matrices this
+ *    means at least 38 matrices.
+ *
+ * Constraint 1 is checked when creating the argument. Constraint 2 is checked on executing
+ * the test. If the constraint is violated, the result has a P-value of 0 and a comment.
+ */
+typedef struct TestArgBinaryMatrixRank TestArgBinaryMatrixRank;
+
 /**
  * The argument for the Frequency test within a block: the block length.
  *
@@ -209,11 +408,20 @@ typedef struct TestArgFrequencyBlock TestArgFrequencyBlock;
  */
 typedef struct TestArgLinearComplexity TestArgLinearComplexity;
 
+/**
+ * The argument for the Test for the Longest Run of Ones in a Block: the configuration of
+ * SP 800-22, section 2.4.2, given by the block length *M*: 8, 128 or 10000 bits.
+ *
+ * Each configuration has a minimum input length: 128, 6272 and 750000 bits. This constraint is
+ * checked on executing the test. If the constraint is violated, an error will be raised.
+ */
+typedef struct TestArgLongestRunOfOnes TestArgLongestRunOfOnes;
+
 /**
  * The arguments for the Non-overlapping Template Matching Test.
  *
  * 1. The templates length to use within a block: `m`.
- *    2 <= `m` <= 21 - recommended: 9.
+ *    2 <= `m` <= 31 - recommended: 9.
  * 2. The number of independent blocks to test in the sequence: `N`
  *    1 <= `N` < 100 - recommended: 8
  *
@@ -239,6 +447,26 @@ typedef struct TestArgNonOverlappingTemplate TestArgNonOverlappingTemplate;
  */
 typedef struct TestArgOverlappingTemplate TestArgOverlappingTemplate;
 
+/**
+ * The argument for the Random Excursions Test: the maximum state *x* to test. All states
+ * `-x..=-1` and `+1..=+x` are tested, with one result per state.
+ *
+ * The maximum state must be between 1 and 32, this is checked when creating the argument.
+ *
+ * The default value for this argument is 4, as used by NIST.
+ */
+typedef struct TestArgRandomExcursions TestArgRandomExcursions;
+
+/**
+ * The argument for the Random Excursions Variant Test: the maximum state *x* to test. All
+ * states `-x..=-1` and `+1..=+x` are tested, with one result per state.
+ *
+ * The maximum state must be between 1 and 32, this is checked when creating the argument.
+ *
+ * The default value for this argument is 9, as used by NIST.
+ */
+typedef struct TestArgRandomExcursionsVariant TestArgRandomExcursionsVariant;
+
 /**
  * The argument for the serial test: the block length in bits to check.
  *
@@ -258,16 +486,64 @@ typedef struct TestArgOverlappingTemplate TestArgOverlappingTemplate;
  */
 typedef struct TestArgSerial TestArgSerial;
 
+/**
+ * The argument for the Spectral DFT Test: how the 95 % peak height threshold *T* is derived.
+ *
+ * The original SP 800-22 used the constant *T = sqrt(3n)*, which is known to be biased.
+ * The current revision of SP 800-22 uses *T = sqrt(ln(1/0.05) n)*, as corrected by Kim,
+ * Umeno and Hasegawa.
+ */
+typedef struct TestArgSpectralDft TestArgSpectralDft;
+
 /**
  * The result of a statistical test.
  */
 typedef struct TestResult TestResult;
 
+/**
+ * A list of test results, as returned by the tests with multiple results and by
+ * [sts_TestRunner_get_result]. The length of the list is returned by [sts_TestResultList_len],
+ * the single results are accessed with [sts_TestResultList_get].
+ */
+typedef struct TestResultList TestResultList;
+
 /**
  * This test runner can be used to run several / all tests on a sequence in one call.
+ *
+ * The runner also stores the threshold used to decide if a test passed, see
+ * [sts_TestRunner_set_threshold], and an optional cancellation token, see
+ * [sts_TestRunner_set_cancellation_token].
+ *
+ * With [sts_TestRunner_run_tests_partitioned] and [sts_TestRunner_run_tests_subsampled], the tests
+ * can also be run on each of multiple sub-sequences, see [sts_TestRunner_get_partition_result].
  */
 typedef struct TestRunner TestRunner;
 
+/**
+ * The verdict for one test over multiple sequences, see [sts_analyse].
+ *
+ * The proportion of sequences that passed each result of the test is compared to the acceptance
+ * interval `p ± 3 * sqrt(p * (1 - p) / m)`, with `p = 1 - threshold` and *m* being the count of
+ * sequences.
+ */
+typedef struct TestVerdict TestVerdict;
+
+/**
+ * A callback that is called by [sts_TestRunner_run_tests_cb] after each test, on the thread that
+ * called [sts_TestRunner_run_tests_cb].
+ *
+ * * `test` is the test that completed.
+ * * `results` is an array of `results_len` pointers to the results of the test, or `NULL` (with
+ *   `results_len` being `0`) if the test failed with an error. The results are owned by the
+ *   runner and are only valid until the callback returns. To keep them, use
+ *   [sts_TestRunner_get_result] after the run.
+ * * `user_data` is the pointer given to [sts_TestRunner_run_tests_cb], unchanged.
+ */
+typedef void (*TestRunnerCallback)(Test test,
+                                   const TestResult *const *results,
+                                   size_t results_len,
+                                   void *user_data);
+
 
 #ifdef __cplusplus
 extern "C" {
@@ -297,6 +573,39 @@ extern "C" {
 int sts_get_last_error(char *ptr,
                        size_t *len);
 
+/**
+ * Returns the [ErrorCode] of the given error.
+ *
+ * ## Safety
+ *
+ * * `error` must have been returned by one of the `_e` functions, see [StsError].
+ * * `error` must be a valid, non-null pointer.
+ */
+ErrorCode sts_error_code(const StsError *error);
+
+/**
+ * Returns the nul-terminated, human-readable message of the given error. The message is owned by
+ * the error, it is valid until the error is freed with [sts_error_free].
+ *
+ * ## Safety
+ *
+ * * `error` must have been returned by one of the `_e` functions, see [StsError].
+ * * `error` must be a valid, non-null pointer.
+ */
+const char *sts_error_message(const StsError *error);
+
+/**
+ * Frees the given error.
+ *
+ * ## Safety
+ *
+ * * `error` must have been returned by one of the `_e` functions, see [StsError].
+ * * `error` may be null.
+ * * `error` and its message will be invalid after this call, access will lead to undefined
+ *   behaviour.
+ */
+void sts_error_free(StsError *error);
+
 /**
  * Sets the maximum of threads to be used by the tests. These method can only be called ONCE and only
  * BEFORE any test is started. If not used, a sane default will be chosen.
@@ -310,6 +619,16 @@ int sts_get_last_error(char *ptr,
  */
 int sts_set_max_threads(size_t max_threads);
 
+/**
+ * Same as [sts_set_max_threads], but an error is written to `error` instead of being stored as
+ * the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * `error` must either be `NULL` or valid for writes.
+ */
+int sts_set_max_threads_e(size_t max_threads, StsError **error);
+
 /**
  * Returns the minimum input length, in bits, for the specified test.
  *
@@ -320,6 +639,175 @@ int sts_set_max_threads(size_t max_threads);
  */
 size_t sts_get_min_length_for_test(Test test);
 
+/**
+ * Same as [sts_get_min_length_for_test], but an error is written to `error` instead of being
+ * stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * `error` must either be `NULL` or valid for writes.
+ */
+size_t sts_get_min_length_for_test_e(Test test, StsError **error);
+
+/**
+ * Returns the input length, in bits, recommended by NIST for the specified test. Inputs that are
+ * at least as long as the minimum length (see [sts_get_min_length_for_test]), but shorter than the
+ * recommended length, can be tested, but the result is less reliable.
+ *
+ * ## Return values
+ *
+ * * >0: the call worked. Returned is the recommended input length
+ * * 0: an error happened - use [sts_get_last_error]
+ */
+size_t sts_get_recommended_length_for_test(Test test);
+
+/**
+ * Same as [sts_get_recommended_length_for_test], but an error is written to `error` instead of
+ * being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * `error` must either be `NULL` or valid for writes.
+ */
+size_t sts_get_recommended_length_for_test_e(Test test, StsError **error);
+
+/**
+ * Analyses the results of the given test over multiple sequences.
+ *
+ * `results` is a list of `count` result lists, one per sequence, as returned by the test
+ * functions or [sts_TestRunner_get_result]. The passed lists remain owned by the caller.
+ * Sequences with results that are not applicable (see [sts_TestResult_is_applicable]) are not
+ * part of the sample.
+ *
+ * The resulting pointer must be freed via [sts_TestVerdict_destroy]. If an error happened,
+ * `NULL` is returned - use [sts_get_last_error].
+ *
+ * ## Safety
+ *
+ * * `results` must be a valid pointer to `count` elements, each being a valid, non-null
+ *   [TestResultList].
+ * * `results` and all contained lists may not be mutated for the duration of this call.
+ */
+TestVerdict *sts_analyse(Test test,
+                         const TestResultList *const *results,
+                         size_t count,
+                         double threshold);
+
+/**
+ * Same as [sts_analyse], but an error is written to `error` instead of being stored as the last
+ * error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_analyse] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestVerdict *sts_analyse_e(Test test,
+                           const TestResultList *const *results,
+                           size_t count,
+                           double threshold,
+                           StsError **error);
+
+/**
+ * Checks the uniformity of the given P-values, e.g. the P-values of one test over multiple
+ * sequences, with the chi-square test of NIST SP 800-22r1a, section 4.2.2.
+ *
+ * The P-value of the returned result is the P-value of the P-values. NIST considers the P-values
+ * to be uniformly distributed if it is at least 0.0001. If no P-values are given, the result is
+ * not applicable, see [sts_TestResult_is_applicable].
+ *
+ * The resulting pointer must be freed via [sts_TestResult_destroy]. If an error happened, `NULL`
+ * is returned - use [sts_get_last_error].
+ *
+ * ## Safety
+ *
+ * * `p_values` must be a valid pointer to `count` elements.
+ * * `p_values` may not be mutated for the duration of this call.
+ */
+TestResult *sts_p_value_uniformity(const double *p_values, size_t count);
+
+/**
+ * Same as [sts_p_value_uniformity], but an error is written to `error` instead of being stored
+ * as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_p_value_uniformity] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestResult *sts_p_value_uniformity_e(const double *p_values, size_t count, StsError **error);
+
+/**
+ * Destroys the given test verdict.
+ *
+ * ## Safety
+ *
+ * * `verdict` must have been created by [sts_analyse].
+ * * `verdict` must be a valid, non-null pointer.
+ * * `verdict` will be invalid after this call, access will lead to undefined behaviour.
+ */
+void sts_TestVerdict_destroy(TestVerdict *verdict);
+
+/**
+ * Checks if the test passed: the proportion of every result lies within the acceptance interval.
+ * Returns `false` if there are no results.
+ *
+ * ## Safety
+ *
+ * * `verdict` must have been created by [sts_analyse].
+ * * `verdict` must be a valid pointer.
+ * * `verdict` may not be mutated for the duration of this call.
+ */
+bool sts_TestVerdict_passed(const TestVerdict *verdict);
+
+/**
+ * Returns the count of results of the test, see [sts_TestVerdict_get_proportion].
+ * Returns 0 if no sequence returned an applicable result.
+ *
+ * ## Safety
+ *
+ * * `verdict` must have been created by [sts_analyse].
+ * * `verdict` must be a valid pointer.
+ * * `verdict` may not be mutated for the duration of this call.
+ */
+size_t sts_TestVerdict_get_result_count(const TestVerdict *verdict);
+
+/**
+ * Returns the count of sequences the test was not applicable to. These are not part of the
+ * sample.
+ *
+ * ## Safety
+ *
+ * * `verdict` must have been created by [sts_analyse].
+ * * `verdict` must be a valid pointer.
+ * * `verdict` may not be mutated for the duration of this call.
+ */
+size_t sts_TestVerdict_get_not_applicable_count(const TestVerdict *verdict);
+
+/**
+ * Extracts the proportion of passed sequences for the result with the given index: the count of
+ * passed sequences, the sample size and the acceptance interval (min, max). The count of results
+ * is returned by [sts_TestVerdict_get_result_count].
+ *
+ * # Return values
+ *
+ * - 0: everything's OK, the values are written to the given pointers.
+ * - 1: there is no result with the given index.
+ *
+ * ## Safety
+ *
+ * * `verdict` must have been created by [sts_analyse].
+ * * `verdict` must be a valid pointer.
+ * * `verdict` may not be mutated for the duration of this call.
+ * * `passed_count`, `sample_size`, `min` and `max` must be valid pointers.
+ */
+int sts_TestVerdict_get_proportion(const TestVerdict *verdict,
+                                   size_t idx,
+                                   size_t *passed_count,
+                                   size_t *sample_size,
+                                   double *min,
+                                   double *max);
+
 /**
  * Creates a Bit Vector from a string, with the ASCII char "0" mapping to 0 and "1" mapping to 1.
  * Any other character is ignored.
@@ -382,42 +870,181 @@ BitVec *sts_BitVec_from_bytes(const uint8_t *ptr, size_t len);
 BitVec *sts_BitVec_from_bits(const bool *ptr, size_t len);
 
 /**
- * Destroys a created BitVec.
+ * Creates a BitVec from a buffer of packed words, e.g. of another library that already stores
+ * the bits packed, without converting them to bytes first. The words are copied, see
+ * [sts_BitVec_borrow_words] to avoid this.
  *
- * ## Safety
+ * Each word has the size of `size_t` (equal to `uintptr_t` on all supported platforms) and
+ * contains `8 * sizeof(size_t)` bits. The first bit is the most significant bit of `words[0]`,
+ * followed by the lower bits, then the bits of `words[1]` and so on. This refers to the numerical
+ * value of the words and is independent of the byte order of the platform: a byte buffer must
+ * not simply be cast to words on little-endian platforms, use [sts_BitVec_from_bytes] instead.
  *
- * * `bitvec` must have been created by either [sts_BitVec_from_str], [sts_BitVec_from_str_with_max_length],
- *   [sts_BitVec_from_bytes] or [sts_BitVec_from_bits].
- * * `bitvec` must be a valid pointer.
- * * `bitvec` may not be mutated for the duration of this call..
- */
-BitVec *sts_BitVec_clone(const BitVec *bitvec);
-
-/**
- * Destroys a created BitVec.
+ * ## Parameters
+ *
+ * * `words`: pointer to the word buffer
+ * * `n_words`: count of words (elements)
+ * * `bit_len`: count of bits to use, at most `n_words * 8 * sizeof(size_t)`. Bits and words
+ *   after it are ignored.
+ *
+ * ## Return value
+ *
+ * * The created BitVec.
+ * * `NULL` if `bit_len` is greater than the count of bits in the words. The error message and
+ *   code can be found out with [sts_get_last_error].
  *
  * ## Safety
  *
- * * `bitvec` must have been created by either [sts_BitVec_from_str], [sts_BitVec_from_str_with_max_length],
- *   [sts_BitVec_from_bytes], [sts_BitVec_from_bits] or [sts_BitVec_clone].
- * * `bitvec` may be null.
- * * There must be no other references to `bitvec`.
- * * After this call, the memory referenced by `bitvec` is freed. Trying to access this memory
- *   will lead to undefined behaviour.
+ * * `words` must be aligned for `size_t` and valid for reads of up to `n_words` elements.
+ * * The memory referenced by `words` must not be mutated for the duration of this method call.
+ * * `words`, particularly the de-allocation of it, remains in the responsibility of the caller.
+ * * The de-allocation of the returned [BitVec] must be done via [sts_BitVec_destroy].
  */
-void sts_BitVec_destroy(BitVec *bitvec);
+BitVec *sts_BitVec_from_words(const size_t *words, size_t n_words, size_t bit_len);
 
 /**
- * Returns the count of bits in the BitVec.
+ * Same as [sts_BitVec_from_words], but an error is written to `error` instead of being stored as
+ * the last error, see [StsError].
  *
  * ## Safety
  *
- * * `bitvec` must have been created by either [sts_BitVec_from_str], [sts_BitVec_from_str_with_max_length],
- *   [sts_BitVec_from_bytes], [sts_BitVec_from_bits] or [sts_BitVec_clone].
- * * `bitvec` must be a valid, non-null pointer.
- * * `bitvec` may not be mutated for the duration of this call.
+ * * The same requirements as for [sts_BitVec_from_words] apply.
+ * * `error` must either be `NULL` or valid for writes.
  */
-size_t sts_BitVec_len_bit(const BitVec *bitvec);
+BitVec *sts_BitVec_from_words_e(const size_t *words,
+                                size_t n_words,
+                                size_t bit_len,
+                                StsError **error);
+
+/**
+ * Creates a BitVec that borrows a buffer of packed words instead of copying it, e.g. to test a
+ * large buffer without doubling the memory usage. The words have the same layout as for
+ * [sts_BitVec_from_words]: a byte buffer can only be borrowed on big-endian platforms, where its
+ * layout is the same as the one of words.
+ *
+ * The BitVec is a read-only view: the words are never written to. [sts_BitVec_crop] copies the
+ * remaining words if it has to clear bits in the new last word. Clones created with
+ * [sts_BitVec_clone] borrow the same words.
+ *
+ * ## Parameters
+ *
+ * * `words`: pointer to the word buffer
+ * * `n_words`: count of words (elements)
+ * * `bit_len`: count of bits to use, at most `n_words * 8 * sizeof(size_t)`. Words after it are
+ *   ignored, the bits after it in the last used word must be 0.
+ *
+ * ## Return value
+ *
+ * * The created BitVec.
+ * * `NULL` if `bit_len` is greater than the count of bits in the words, or if a bit after
+ *   `bit_len` in the last used word is 1. The error message and code can be found out with
+ *   [sts_get_last_error].
+ *
+ * ## Safety
+ *
+ * * `words` must be aligned for `size_t` and valid for reads of up to `n_words` elements.
+ * * The memory referenced by `words` must neither be mutated nor de-allocated until the returned
+ *   BitVec and all clones of it have been destroyed with [sts_BitVec_destroy].
+ * * `words`, particularly the de-allocation of it, remains in the responsibility of the caller.
+ */
+BitVec *sts_BitVec_borrow_words(const size_t *words, size_t n_words, size_t bit_len);
+
+/**
+ * Same as [sts_BitVec_borrow_words], but an error is written to `error` instead of being stored
+ * as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_BitVec_borrow_words] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+BitVec *sts_BitVec_borrow_words_e(const size_t *words,
+                                  size_t n_words,
+                                  size_t bit_len,
+                                  StsError **error);
+
+/**
+ * Creates a BitVec by reading the file at the given path, interpreting its content in the given
+ * [InputFormat]. The file is read in chunks, only the resulting BitVec is held in memory.
+ *
+ * ## Parameters
+ *
+ * * `path`: the nul-terminated, UTF-8 encoded path of the file
+ * * `format`: the format of the file content, see [InputFormat]
+ * * `max_bits`: the maximum count of bits to read, the rest of the file is ignored. `0` means
+ *   that the whole file is read.
+ *
+ * ## Return value
+ *
+ * * The created BitVec, if the file was read successfully.
+ * * `NULL` if the path is not valid UTF-8, the format is invalid, the file could not be read or
+ *   its content is invalid for the given format. The error message and code can be found out
+ *   with [sts_get_last_error].
+ *
+ * ## Safety
+ *
+ * * The memory pointed to by `path` must contain a valid nul terminator at the end of the string.
+ * * `path` must be valid, as defined by the Rust module safety documentation of `std::ptr`, for
+ *   reads of bytes up to and including the nul terminator.
+ * * The memory referenced by `path` must not be mutated for the duration of this method call.
+ * * `path`, particularly the de-allocation of it, remains in the responsibility of the caller.
+ * * The de-allocation of the returned [BitVec] must be done via [sts_BitVec_destroy].
+ */
+BitVec *sts_BitVec_from_file(const char *path, InputFormat format, size_t max_bits);
+
+/**
+ * Same as [sts_BitVec_from_file], but an error is written to `error` instead of being stored as
+ * the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_BitVec_from_file] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+BitVec *sts_BitVec_from_file_e(const char *path,
+                               InputFormat format,
+                               size_t max_bits,
+                               StsError **error);
+
+/**
+ * Destroys a created BitVec.
+ *
+ * ## Safety
+ *
+ * * `bitvec` must have been created by either [sts_BitVec_from_str], [sts_BitVec_from_str_with_max_length],
+ *   [sts_BitVec_from_bytes] or [sts_BitVec_from_bits].
+ * * `bitvec` must be a valid pointer.
+ * * `bitvec` may not be mutated for the duration of this call..
+ */
+BitVec *sts_BitVec_clone(const BitVec *bitvec);
+
+/**
+ * Destroys a created BitVec.
+ *
+ * ## Safety
+ *
+ * * `bitvec` must have been created by either [sts_BitVec_from_str], [sts_BitVec_from_str_with_max_length],
+ *   [sts_BitVec_from_bytes], [sts_BitVec_from_bits], [sts_BitVec_from_words],
+ *   [sts_BitVec_borrow_words], [sts_BitVec_from_file], [sts_BitVec_clone] or [sts_BitVec_slice].
+ * * `bitvec` may be null.
+ * * There must be no other references to `bitvec`.
+ * * After this call, the memory referenced by `bitvec` is freed. Trying to access this memory
+ *   will lead to undefined behaviour.
+ */
+void sts_BitVec_destroy(BitVec *bitvec);
+
+/**
+ * Returns the count of bits in the BitVec.
+ *
+ * ## Safety
+ *
+ * * `bitvec` must have been created by either [sts_BitVec_from_str], [sts_BitVec_from_str_with_max_length],
+ *   [sts_BitVec_from_bytes], [sts_BitVec_from_bits], [sts_BitVec_from_words],
+ *   [sts_BitVec_borrow_words], [sts_BitVec_from_file], [sts_BitVec_clone] or [sts_BitVec_slice].
+ * * `bitvec` must be a valid, non-null pointer.
+ * * `bitvec` may not be mutated for the duration of this call.
+ */
+size_t sts_BitVec_len_bit(const BitVec *bitvec);
 
 /**
  * Crops the BitVec to the given count of bits. Values for `new_bit_len` that are larger than the
@@ -426,13 +1053,172 @@ size_t sts_BitVec_len_bit(const BitVec *bitvec);
  * ## Safety
  *
  * * `bitvec` must have been created by either [sts_BitVec_from_str], [sts_BitVec_from_str_with_max_length],
- *   [sts_BitVec_from_bytes], [sts_BitVec_from_bits] or [sts_BitVec_clone].
+ *   [sts_BitVec_from_bytes], [sts_BitVec_from_bits], [sts_BitVec_from_words],
+ *   [sts_BitVec_borrow_words], [sts_BitVec_from_file], [sts_BitVec_clone] or [sts_BitVec_slice].
  * * `bitvec` must be a valid, non-null pointer.
  * * `bitvec` may not be mutated by other functions for the duration of this call.
  */
 void sts_BitVec_crop(BitVec *bitvec,
                      size_t new_bit_len);
 
+/**
+ * Returns a new BitVec that contains a copy of the bits from index `start` (inclusive) to `end`
+ * (exclusive) of the given BitVec, e.g. to test only a part of the sequence. The given BitVec is
+ * unchanged.
+ *
+ * The resulting pointer must be freed via [sts_BitVec_destroy].
+ *
+ * ## Return value
+ *
+ * * The new BitVec.
+ * * `NULL` if `start > end` or `end` is greater than the bit length of `bitvec`. The error
+ *   message and code can be found out with [sts_get_last_error].
+ *
+ * ## Safety
+ *
+ * * `bitvec` must have been created by either [sts_BitVec_from_str], [sts_BitVec_from_str_with_max_length],
+ *   [sts_BitVec_from_bytes], [sts_BitVec_from_bits], [sts_BitVec_from_words],
+ *   [sts_BitVec_borrow_words], [sts_BitVec_from_file], [sts_BitVec_clone] or [sts_BitVec_slice].
+ * * `bitvec` must be a valid, non-null pointer.
+ * * `bitvec` may not be mutated for the duration of this call.
+ */
+BitVec *sts_BitVec_slice(const BitVec *bitvec, size_t start, size_t end);
+
+/**
+ * Same as [sts_BitVec_slice], but an error is written to `error` instead of being stored as the
+ * last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_BitVec_slice] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+BitVec *sts_BitVec_slice_e(const BitVec *bitvec, size_t start, size_t end, StsError **error);
+
+/**
+ * Runs the 4 statistical power-up tests of FIPS 140-2 (monobit, poker, runs and long run) on the
+ * first 20000 bits of the given data. **These tests are not part of SP 800-22**, but they are much
+ * faster and can be used as a quick check before running the full tests.
+ *
+ * The resulting pointer must be freed via [sts_Fips140Result_destroy]. If the data is shorter
+ * than 20000 bits, `NULL` is returned - use [sts_get_last_error].
+ *
+ * ## Safety
+ *
+ * * `data` must have been created by one of the construction methods provided by this library.
+ * * `data` must be valid for reads and non-null.
+ * * `data` may not be mutated for the duration of this call.
+ */
+Fips140Result *sts_fips140_2_tests(const BitVec *data);
+
+/**
+ * Same as [sts_fips140_2_tests], but an error is written to `error` instead of being stored as
+ * the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_fips140_2_tests] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+Fips140Result *sts_fips140_2_tests_e(const BitVec *data, StsError **error);
+
+/**
+ * Destroys the given FIPS 140-2 result.
+ *
+ * ## Safety
+ *
+ * * `result` must have been created by [sts_fips140_2_tests].
+ * * `result` must be a valid, non-null pointer.
+ * * `result` will be invalid after this call, access will lead to undefined behaviour.
+ */
+void sts_Fips140Result_destroy(Fips140Result *result);
+
+/**
+ * Checks if all FIPS 140-2 tests passed - the combined verdict.
+ *
+ * ## Safety
+ *
+ * * `result` must have been created by [sts_fips140_2_tests].
+ * * `result` must be a valid pointer.
+ * * `result` may not be mutated for the duration of this call.
+ */
+bool sts_Fips140Result_all_passed(const Fips140Result *result);
+
+/**
+ * Checks if the given FIPS 140-2 test passed.
+ *
+ * ## Return value
+ *
+ * * `1`: the test passed.
+ * * `0`: the test did not pass.
+ * * `-1`: the test is invalid. The error message and code can be found out with
+ *   [sts_get_last_error].
+ *
+ * ## Safety
+ *
+ * * `result` must have been created by [sts_fips140_2_tests].
+ * * `result` must be a valid pointer.
+ * * `result` may not be mutated for the duration of this call.
+ */
+int sts_Fips140Result_passed(const Fips140Result *result, Fips140Test test);
+
+/**
+ * Same as [sts_Fips140Result_passed], but an error is written to `error` instead of being stored
+ * as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_Fips140Result_passed] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+int sts_Fips140Result_passed_e(const Fips140Result *result, Fips140Test test, StsError **error);
+
+/**
+ * Returns the count of ones in the tested sample, used by the monobit test.
+ *
+ * ## Safety
+ *
+ * * `result` must have been created by [sts_fips140_2_tests].
+ * * `result` must be a valid pointer.
+ * * `result` may not be mutated for the duration of this call.
+ */
+size_t sts_Fips140Result_get_ones(const Fips140Result *result);
+
+/**
+ * Returns the statistic of the poker test.
+ *
+ * ## Safety
+ *
+ * * `result` must have been created by [sts_fips140_2_tests].
+ * * `result` must be a valid pointer.
+ * * `result` may not be mutated for the duration of this call.
+ */
+double sts_Fips140Result_get_poker_statistic(const Fips140Result *result);
+
+/**
+ * Writes the count of runs of the given bit with length 1, 2, 3, 4, 5 and 6 or longer, used by
+ * the runs test, to `runs`.
+ *
+ * ## Safety
+ *
+ * * `result` must have been created by [sts_fips140_2_tests].
+ * * `result` must be a valid pointer.
+ * * `result` may not be mutated for the duration of this call.
+ * * `runs` must be a valid, non-null pointer, writable for 6 elements.
+ */
+void sts_Fips140Result_get_runs(const Fips140Result *result, bool bit, size_t *runs);
+
+/**
+ * Returns the length of the longest run in the tested sample, used by the long run test.
+ *
+ * ## Safety
+ *
+ * * `result` must have been created by [sts_fips140_2_tests].
+ * * `result` must be a valid pointer.
+ * * `result` may not be mutated for the duration of this call.
+ */
+size_t sts_Fips140Result_get_longest_run(const Fips140Result *result);
+
 /**
  * Creates a default new argument for the Frequency test within a block that chooses a suitable
  * block length automatically.
@@ -462,45 +1248,36 @@ void sts_TestArgFrequencyBlock_destroy(TestArgFrequencyBlock *ptr);
 TestArgFrequencyBlock *sts_TestArgFrequencyBlock_new(size_t block_length);
 
 /**
- * Creates a default new non-overlapping template test argument that chooses its template length
- * and block count according to the values recommended by NIST.
- * This function never returns `NULL`.
+ * Creates a new argument for the Frequency test within a block that chooses the block length
+ * automatically with the given strategy.
+ *
+ * ## Return values
+ * - if the given `strategy` is invalid, `NULL` is returned.
+ * - else, a pointer to the argument is returned.
  */
-TestArgNonOverlappingTemplate *sts_TestArgNonOverlappingTemplate_default(void);
+TestArgFrequencyBlock *sts_TestArgFrequencyBlock_new_with_strategy(SelectionStrategy strategy);
 
 /**
- * Destroys the given argument for Non-overlapping Template Matching Test.
+ * Returns the block length the Frequency test within a block uses for an input with the given
+ * length in bits.
  *
  * ## Safety
  *
- * * `ptr` must have been created by one of the construction methods provided by this library.
- * * `ptr` must be valid for reads and writes and non-null.
- * * `ptr` will be invalid after this call, access will lead to undefined behaviour.
- * * `ptr` may not be mutated for the duration of this call.
- */
-void sts_TestArgNonOverlappingTemplate_destroy(TestArgNonOverlappingTemplate *ptr);
-
-/**
- * Creates a new non-overlapping template test argument with the specified template length and block
- * count.
- *
- * ## Return values.
- * * If both arguments are within the bounds specified in [TestArgNonOverlappingTemplate]: the new argument.
- * * Otherwise: `NULL`
+ * * `arg` must have been created by one of the construction methods provided by this library.
+ * * `arg` must be a valid pointer.
+ * * `arg` may not be mutated for the duration of this call.
  */
-TestArgNonOverlappingTemplate *sts_TestArgNonOverlappingTemplate_new(size_t template_len,
-                                                                     size_t count_blocks);
+size_t sts_TestArgFrequencyBlock_block_length(const TestArgFrequencyBlock *arg, size_t length);
 
 /**
- * Creates a new argument for the Overlapping Template Matching Test, using the default values
- * [OVERLAPPING_TEMPLATE_DEFAULT_TEMPLATE_LENGTH], [OVERLAPPING_TEMPLATE_DEFAULT_BLOCK_LENGTH]
- * and [OVERLAPPING_TEMPLATE_DEFAULT_FREEDOM].
+ * Creates a default argument for the Test for the Longest Run of Ones in a Block that chooses
+ * the configuration based on the input length, as recommended by SP 800-22.
  * This function never returns `NULL`.
  */
-TestArgOverlappingTemplate *sts_TestArgOverlappingTemplate_default(void);
+TestArgLongestRunOfOnes *sts_TestArgLongestRunOfOnes_default(void);
 
 /**
- * Destroys the given argument for the Overlapping Template Matching Test.
+ * Destroys the given argument for the Test for the Longest Run of Ones in a Block.
  *
  * ## Safety
  *
@@ -509,41 +1286,38 @@ TestArgOverlappingTemplate *sts_TestArgOverlappingTemplate_default(void);
  * * `ptr` will be invalid after this call, access will lead to undefined behaviour.
  * * `ptr` may not be mutated for the duration of this call.
  */
-void sts_TestArgOverlappingTemplate_destroy(TestArgOverlappingTemplate *ptr);
+void sts_TestArgLongestRunOfOnes_destroy(TestArgLongestRunOfOnes *ptr);
 
 /**
- * Creates a new Overlapping Template Matching Test argument with the specified template length, block
- * count and degrees of freedom.
+ * Creates a new argument for the Test for the Longest Run of Ones in a Block, specifying the
+ * block length in bits, which selects the configuration.
  *
- * ## Return values.
- * * If all arguments are within the bounds specified in [TestArgOverlappingTemplate]: the new argument.
- * * Otherwise: `NULL`
+ * ## Return values
+ * - if the given `block_length` is not 8, 128 or 10000, `NULL` is returned - use
+ *   [sts_get_last_error].
+ * - otherwise, a pointer to the argument is returned.
  */
-TestArgOverlappingTemplate *sts_TestArgOverlappingTemplate_new(size_t template_length,
-                                                               size_t block_length,
-                                                               size_t freedom);
+TestArgLongestRunOfOnes *sts_TestArgLongestRunOfOnes_new(size_t block_length);
 
 /**
- * Creates a new Overlapping Template Matching Test argument with the specified template length,
- * forcing the test to use the inaccurate behaviour of the NIST STS reference implementation.
+ * Same as [sts_TestArgLongestRunOfOnes_new], but an error is written to `error` instead of being
+ * stored as the last error, see [StsError].
  *
- * The template length may be either 9 or 10.
+ * ## Safety
  *
- * ## Return values.
- * * If the argument is within the specified bounds: the new argument.
- * * Otherwise: `NULL`
+ * * `error` must either be `NULL` or valid for writes.
  */
-TestArgOverlappingTemplate *sts_TestArgOverlappingTemplate_new_nist_behaviour(size_t template_length);
+TestArgLongestRunOfOnes *sts_TestArgLongestRunOfOnes_new_e(size_t block_length, StsError **error);
 
 /**
- * Creates a default argument for the Linear Complexity Test, choosing the block length
- * automatically on runtime.
+ * Creates a default argument for the Binary Matrix Rank Test: 32x32 matrices, as used by
+ * SP 800-22.
  * This function never returns `NULL`.
  */
-TestArgLinearComplexity *sts_TestArgLinearComplexity_default(void);
+TestArgBinaryMatrixRank *sts_TestArgBinaryMatrixRank_default(void);
 
 /**
- * Destroys the given argument for the Linear Complexity Test.
+ * Destroys the given argument for the Binary Matrix Rank Test.
  *
  * ## Safety
  *
@@ -552,27 +1326,39 @@ TestArgLinearComplexity *sts_TestArgLinearComplexity_default(void);
  * * `ptr` will be invalid after this call, access will lead to undefined behaviour.
  * * `ptr` may not be mutated for the duration of this call.
  */
-void sts_TestArgLinearComplexity_destroy(TestArgLinearComplexity *ptr);
+void sts_TestArgBinaryMatrixRank_destroy(TestArgBinaryMatrixRank *ptr);
 
 /**
- * Creates a new argument for the linear Complexity Test, choosing the block length manually.
+ * Creates a new argument for the Binary Matrix Rank Test with the given count of rows and
+ * columns of each matrix.
  *
  * ## Return values
+ * - if `rows` or `cols` is not between 2 and 64, `NULL` is returned - use [sts_get_last_error].
+ * - otherwise, a pointer to the argument is returned.
+ */
+TestArgBinaryMatrixRank *sts_TestArgBinaryMatrixRank_new(size_t rows, size_t cols);
+
+/**
+ * Same as [sts_TestArgBinaryMatrixRank_new], but an error is written to `error` instead of being
+ * stored as the last error, see [StsError].
  *
- * * If the block length is within 500 <= block_length <= 5000: the new argument.
- * * Otherwise: `NULL`
+ * ## Safety
+ *
+ * * `error` must either be `NULL` or valid for writes.
  */
-TestArgLinearComplexity *sts_TestArgLinearComplexity_new(size_t block_length);
+TestArgBinaryMatrixRank *sts_TestArgBinaryMatrixRank_new_e(size_t rows,
+                                                           size_t cols,
+                                                           StsError **error);
 
 /**
- * Creates a default argument for the Serial Test, with the block length set to the one
- * recommended by NIST.
+ * Creates a default argument for the Spectral DFT Test, using the corrected threshold of Kim,
+ * Umeno and Hasegawa, as used by the current revision of SP 800-22.
  * This function never returns `NULL`.
  */
-TestArgSerial *sts_TestArgSerial_default(void);
+TestArgSpectralDft *sts_TestArgSpectralDft_default(void);
 
 /**
- * Destroys the given argument for the Serial Test.
+ * Destroys the given argument for the Spectral DFT Test.
  *
  * ## Safety
  *
@@ -581,28 +1367,25 @@ TestArgSerial *sts_TestArgSerial_default(void);
  * * `ptr` will be invalid after this call, access will lead to undefined behaviour.
  * * `ptr` may not be mutated for the duration of this call.
  */
-void sts_TestArgSerial_destroy(TestArgSerial *ptr);
+void sts_TestArgSpectralDft_destroy(TestArgSpectralDft *ptr);
 
 /**
- * Creates a new argument for the Serial Test. The block length is checked to fulfill the constraints
- * defined in the struct declaration.
- *
- * ## Return value
+ * Creates a new argument for the Spectral DFT Test, using the biased threshold constant of the
+ * original SP 800-22, *T = sqrt(3n)*. Only use this to compare results with old implementations.
  *
- * * if the given block length satisfies the constraints: the new argument.
- * * otherwise: `NULL`
+ * This function never returns `NULL`.
  */
-TestArgSerial *sts_TestArgSerial_new(uint8_t block_length);
+TestArgSpectralDft *sts_TestArgSpectralDft_new_original_nist(void);
 
 /**
- * Creates a default argument for the Approximate Entropy Test, with the block length set to the one
- * recommended by NIST.
+ * Creates a default new non-overlapping template test argument that chooses its template length
+ * and block count according to the values recommended by NIST.
  * This function never returns `NULL`.
  */
-TestArgApproximateEntropy *sts_TestArgApproximateEntropy_default(void);
+TestArgNonOverlappingTemplate *sts_TestArgNonOverlappingTemplate_default(void);
 
 /**
- * Destroys the given argument for the Approximate Entropy Test.
+ * Destroys the given argument for Non-overlapping Template Matching Test.
  *
  * ## Safety
  *
@@ -611,7 +1394,186 @@ TestArgApproximateEntropy *sts_TestArgApproximateEntropy_default(void);
  * * `ptr` will be invalid after this call, access will lead to undefined behaviour.
  * * `ptr` may not be mutated for the duration of this call.
  */
-void sts_TestArgApproximateEntropy_destroy(TestArgApproximateEntropy *ptr);
+void sts_TestArgNonOverlappingTemplate_destroy(TestArgNonOverlappingTemplate *ptr);
+
+/**
+ * Creates a new non-overlapping template test argument with the specified template length and block
+ * count.
+ *
+ * ## Return values.
+ * * If both arguments are within the bounds specified in [TestArgNonOverlappingTemplate]: the new argument.
+ * * Otherwise: `NULL` - use [sts_get_last_error]
+ */
+TestArgNonOverlappingTemplate *sts_TestArgNonOverlappingTemplate_new(size_t template_len,
+                                                                     size_t count_blocks);
+
+/**
+ * Same as [sts_TestArgNonOverlappingTemplate_new], but an error is written to `error` instead of
+ * being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestArgNonOverlappingTemplate *sts_TestArgNonOverlappingTemplate_new_e(size_t template_len,
+                                                                       size_t count_blocks,
+                                                                       StsError **error);
+
+/**
+ * Creates a new argument for the Overlapping Template Matching Test, using the default values
+ * [OVERLAPPING_TEMPLATE_DEFAULT_TEMPLATE_LENGTH], [OVERLAPPING_TEMPLATE_DEFAULT_BLOCK_LENGTH]
+ * and [OVERLAPPING_TEMPLATE_DEFAULT_FREEDOM].
+ * This function never returns `NULL`.
+ */
+TestArgOverlappingTemplate *sts_TestArgOverlappingTemplate_default(void);
+
+/**
+ * Destroys the given argument for the Overlapping Template Matching Test.
+ *
+ * ## Safety
+ *
+ * * `ptr` must have been created by one of the construction methods provided by this library.
+ * * `ptr` must be valid for reads and writes and non-null.
+ * * `ptr` will be invalid after this call, access will lead to undefined behaviour.
+ * * `ptr` may not be mutated for the duration of this call.
+ */
+void sts_TestArgOverlappingTemplate_destroy(TestArgOverlappingTemplate *ptr);
+
+/**
+ * Creates a new Overlapping Template Matching Test argument with the specified template length, block
+ * count and degrees of freedom.
+ *
+ * ## Return values.
+ * * If all arguments are within the bounds specified in [TestArgOverlappingTemplate]: the new argument.
+ * * Otherwise: `NULL` - use [sts_get_last_error]
+ */
+TestArgOverlappingTemplate *sts_TestArgOverlappingTemplate_new(size_t template_length,
+                                                               size_t block_length,
+                                                               size_t freedom);
+
+/**
+ * Same as [sts_TestArgOverlappingTemplate_new], but an error is written to `error` instead of
+ * being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestArgOverlappingTemplate *sts_TestArgOverlappingTemplate_new_e(size_t template_length,
+                                                                 size_t block_length,
+                                                                 size_t freedom,
+                                                                 StsError **error);
+
+/**
+ * Creates a new Overlapping Template Matching Test argument with the specified template length,
+ * forcing the test to use the inaccurate behaviour of the NIST STS reference implementation.
+ *
+ * The template length may be either 9 or 10.
+ *
+ * ## Return values.
+ * * If the argument is within the specified bounds: the new argument.
+ * * Otherwise: `NULL` - use [sts_get_last_error]
+ */
+TestArgOverlappingTemplate *sts_TestArgOverlappingTemplate_new_nist_behaviour(size_t template_length);
+
+/**
+ * Same as [sts_TestArgOverlappingTemplate_new_nist_behaviour], but an error is written to `error`
+ * instead of being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestArgOverlappingTemplate *sts_TestArgOverlappingTemplate_new_nist_behaviour_e(size_t template_length,
+                                                                                StsError **error);
+
+/**
+ * Creates a default argument for the Linear Complexity Test, choosing the block length
+ * automatically on runtime.
+ * This function never returns `NULL`.
+ */
+TestArgLinearComplexity *sts_TestArgLinearComplexity_default(void);
+
+/**
+ * Destroys the given argument for the Linear Complexity Test.
+ *
+ * ## Safety
+ *
+ * * `ptr` must have been created by one of the construction methods provided by this library.
+ * * `ptr` must be valid for reads and writes and non-null.
+ * * `ptr` will be invalid after this call, access will lead to undefined behaviour.
+ * * `ptr` may not be mutated for the duration of this call.
+ */
+void sts_TestArgLinearComplexity_destroy(TestArgLinearComplexity *ptr);
+
+/**
+ * Creates a new argument for the linear Complexity Test, choosing the block length manually.
+ *
+ * ## Return values
+ *
+ * * If the block length is within 500 <= block_length <= 5000: the new argument.
+ * * Otherwise: `NULL`
+ */
+TestArgLinearComplexity *sts_TestArgLinearComplexity_new(size_t block_length);
+
+/**
+ * Creates a default argument for the Serial Test, with the block length set to the one
+ * recommended by NIST.
+ * This function never returns `NULL`.
+ */
+TestArgSerial *sts_TestArgSerial_default(void);
+
+/**
+ * Destroys the given argument for the Serial Test.
+ *
+ * ## Safety
+ *
+ * * `ptr` must have been created by one of the construction methods provided by this library.
+ * * `ptr` must be valid for reads and writes and non-null.
+ * * `ptr` will be invalid after this call, access will lead to undefined behaviour.
+ * * `ptr` may not be mutated for the duration of this call.
+ */
+void sts_TestArgSerial_destroy(TestArgSerial *ptr);
+
+/**
+ * Creates a new argument for the Serial Test. The block length is checked to fulfill the constraints
+ * defined in the struct declaration.
+ *
+ * ## Return value
+ *
+ * * if the given block length satisfies the constraints: the new argument.
+ * * otherwise: `NULL` - use [sts_get_last_error]
+ */
+TestArgSerial *sts_TestArgSerial_new(uint8_t block_length);
+
+/**
+ * Same as [sts_TestArgSerial_new], but an error is written to `error` instead of being stored as
+ * the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestArgSerial *sts_TestArgSerial_new_e(uint8_t block_length, StsError **error);
+
+/**
+ * Creates a default argument for the Approximate Entropy Test, with the block length set to the one
+ * recommended by NIST.
+ * This function never returns `NULL`.
+ */
+TestArgApproximateEntropy *sts_TestArgApproximateEntropy_default(void);
+
+/**
+ * Destroys the given argument for the Approximate Entropy Test.
+ *
+ * ## Safety
+ *
+ * * `ptr` must have been created by one of the construction methods provided by this library.
+ * * `ptr` must be valid for reads and writes and non-null.
+ * * `ptr` will be invalid after this call, access will lead to undefined behaviour.
+ * * `ptr` may not be mutated for the duration of this call.
+ */
+void sts_TestArgApproximateEntropy_destroy(TestArgApproximateEntropy *ptr);
 
 /**
  * Creates a new argument for the Approximate Entropy Test. The block length is checked to fulfill
@@ -620,13 +1582,140 @@ void sts_TestArgApproximateEntropy_destroy(TestArgApproximateEntropy *ptr);
  * ## Return value
  *
  * * if the given block length satisfies the constraints: the new argument.
- * * otherwise: `NULL`
+ * * otherwise: `NULL` - use [sts_get_last_error]
  */
 TestArgApproximateEntropy *sts_TestArgApproximateEntropy_new(uint8_t block_length);
 
 /**
- * Destroys the given test result. If you want to destroy a whole list, use [sts_TestResult_list_destroy].
- * You cannot destroy only a part of a list with this function.
+ * Same as [sts_TestArgApproximateEntropy_new], but an error is written to `error` instead of being
+ * stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestArgApproximateEntropy *sts_TestArgApproximateEntropy_new_e(uint8_t block_length,
+                                                               StsError **error);
+
+/**
+ * Creates a default argument for the Random Excursions Test, with the maximum state set to 4.
+ * This function never returns `NULL`.
+ */
+TestArgRandomExcursions *sts_TestArgRandomExcursions_default(void);
+
+/**
+ * Destroys the given argument for the Random Excursions Test.
+ *
+ * ## Safety
+ *
+ * * `ptr` must have been created by one of the construction methods provided by this library.
+ * * `ptr` must be valid for reads and writes and non-null.
+ * * `ptr` will be invalid after this call, access will lead to undefined behaviour.
+ * * `ptr` may not be mutated for the duration of this call.
+ */
+void sts_TestArgRandomExcursions_destroy(TestArgRandomExcursions *ptr);
+
+/**
+ * Creates a new argument for the Random Excursions Test, specifying the maximum state.
+ *
+ * ## Return value
+ *
+ * * if the given maximum state is between 1 and 32: the new argument.
+ * * otherwise: `NULL` - use [sts_get_last_error]
+ */
+TestArgRandomExcursions *sts_TestArgRandomExcursions_new(uint8_t max_state);
+
+/**
+ * Same as [sts_TestArgRandomExcursions_new], but an error is written to `error` instead of being
+ * stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestArgRandomExcursions *sts_TestArgRandomExcursions_new_e(uint8_t max_state, StsError **error);
+
+/**
+ * Creates a default argument for the Random Excursions Variant Test, with the maximum state
+ * set to 9.
+ * This function never returns `NULL`.
+ */
+TestArgRandomExcursionsVariant *sts_TestArgRandomExcursionsVariant_default(void);
+
+/**
+ * Destroys the given argument for the Random Excursions Variant Test.
+ *
+ * ## Safety
+ *
+ * * `ptr` must have been created by one of the construction methods provided by this library.
+ * * `ptr` must be valid for reads and writes and non-null.
+ * * `ptr` will be invalid after this call, access will lead to undefined behaviour.
+ * * `ptr` may not be mutated for the duration of this call.
+ */
+void sts_TestArgRandomExcursionsVariant_destroy(TestArgRandomExcursionsVariant *ptr);
+
+/**
+ * Creates a new argument for the Random Excursions Variant Test, specifying the maximum state.
+ *
+ * ## Return value
+ *
+ * * if the given maximum state is between 1 and 32: the new argument.
+ * * otherwise: `NULL` - use [sts_get_last_error]
+ */
+TestArgRandomExcursionsVariant *sts_TestArgRandomExcursionsVariant_new(uint8_t max_state);
+
+/**
+ * Same as [sts_TestArgRandomExcursionsVariant_new], but an error is written to `error` instead of
+ * being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestArgRandomExcursionsVariant *sts_TestArgRandomExcursionsVariant_new_e(uint8_t max_state,
+                                                                         StsError **error);
+
+/**
+ * Creates a default argument for the Autocorrelation Test, with the shift set to 1.
+ * This function never returns `NULL`.
+ */
+TestArgAutocorrelation *sts_TestArgAutocorrelation_default(void);
+
+/**
+ * Destroys the given argument for the Autocorrelation Test.
+ *
+ * ## Safety
+ *
+ * * `ptr` must have been created by one of the construction methods provided by this library.
+ * * `ptr` must be valid for reads and writes and non-null.
+ * * `ptr` will be invalid after this call, access will lead to undefined behaviour.
+ * * `ptr` may not be mutated for the duration of this call.
+ */
+void sts_TestArgAutocorrelation_destroy(TestArgAutocorrelation *ptr);
+
+/**
+ * Creates a new argument for the Autocorrelation Test, specifying the shift in bits.
+ *
+ * ## Return values
+ * - if the given `shift == 0`, `NULL` is returned - use [sts_get_last_error].
+ * - if the given `shift != 0`, a pointer to the argument is returned.
+ */
+TestArgAutocorrelation *sts_TestArgAutocorrelation_new(size_t shift);
+
+/**
+ * Same as [sts_TestArgAutocorrelation_new], but an error is written to `error` instead of being
+ * stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestArgAutocorrelation *sts_TestArgAutocorrelation_new_e(size_t shift, StsError **error);
+
+/**
+ * Destroys the given test result. If you want to destroy a whole list, use
+ * [sts_TestResultList_destroy]. Results contained in a list cannot be destroyed with this
+ * function.
  *
  * ## Safety
  *
@@ -638,113 +1727,384 @@ TestArgApproximateEntropy *sts_TestArgApproximateEntropy_new(uint8_t block_lengt
 void sts_TestResult_destroy(TestResult *ptr);
 
 /**
- * Destroys the given list of test results. If you want to destroy only a single test result,
- * use [sts_TestResult_destroy].
+ * Destroys the given list of test results, including all contained results. If you want to
+ * destroy only a single test result, use [sts_TestResult_destroy].
  *
  * ## Safety
  *
- * * `ptr` must have been created by one of the tests or by the test runner, and must have been
+ * * `list` must have been created by one of the tests or by the test runner, and must have been
  *   returned by the creating function as a list.
- * * `ptr` must be valid allocation with `count` elements.
- * * `ptr` must not be mutated for the duration of this call.
- * * `ptr` will be invalid after this call, access will lead to undefined behaviour.
+ * * `list` may be null.
+ * * `list` must not be mutated for the duration of this call.
+ * * `list` and all results returned by [sts_TestResultList_get] for it will be invalid after
+ *   this call, access will lead to undefined behaviour.
+ */
+void sts_TestResultList_destroy(TestResultList *list);
+
+/**
+ * Returns the count of test results in the given list.
+ *
+ * ## Safety
+ *
+ * * `list` must have been created by one of the tests or by the test runner.
+ * * `list` must be a valid pointer.
+ * * `list` may not be mutated for the duration of this call.
+ */
+size_t sts_TestResultList_len(const TestResultList *list);
+
+/**
+ * Returns the test result with the given index in the list, or `NULL` if the index is not
+ * smaller than the length of the list, see [sts_TestResultList_len].
+ *
+ * The returned result is owned by the list: it is valid until the list is destroyed with
+ * [sts_TestResultList_destroy] and may not be destroyed with [sts_TestResult_destroy].
+ *
+ * ## Safety
+ *
+ * * `list` must have been created by one of the tests or by the test runner.
+ * * `list` must be a valid pointer.
+ * * `list` may not be mutated for the duration of this call.
  */
-void sts_TestResult_list_destroy(TestResult **ptr, size_t count);
+const TestResult *sts_TestResultList_get(const TestResultList *list, size_t idx);
 
 /**
  * Returns the p_value of the test result.
  *
  * ## Safety
  *
- * * `result` must have been created by one of the tests.
- * * `result` must be a valid pointer.
- * * `result` may not be mutated for the duration of this call.
+ * * `result` must have been created by one of the tests.
+ * * `result` must be a valid pointer.
+ * * `result` may not be mutated for the duration of this call.
+ */
+double sts_TestResult_get_p_value(const TestResult *result);
+
+/**
+ * Checks if the contained p_value passed the given threshold (i.e. if test passed).
+ *
+ * ## Safety
+ *
+ * * `result` must have been created by one of the tests.
+ * * `result` must be a valid pointer.
+ * * `result` may not be mutated for the duration of this call.
+ */
+bool sts_TestResult_passed(const TestResult *result, double threshold);
+
+/**
+ * Checks if the test was applicable to the input. If not, the p_value is set to 0.0, but this
+ * does not mean that the input is not random. This is the case e.g. for the random excursions
+ * test, if the input has too few cycles.
+ *
+ * ## Safety
+ *
+ * * `result` must have been created by one of the tests.
+ * * `result` must be a valid pointer.
+ * * `result` may not be mutated for the duration of this call.
+ */
+bool sts_TestResult_is_applicable(const TestResult *result);
+
+/**
+ * Extracts the (maybe existing) comment contained in the test result.
+ * This function works in 2 steps:
+ * 1. the caller calls the function with `ptr` set to `NULL`. The necessary length is written to
+ *    `len`.
+ * 2. the caller calls the function with `ptr` set to a valid buffer, and `len` set to the length of
+ *    the buffer. If the length is enough to store the error message, it is written to the buffer.
+ *    The error message is written with a nul-terminating byte.
+ *
+ * The comment may contain details of the test run, e.g. the length of the input, so its length
+ * differs between results: always query the necessary length instead of using a fixed-size buffer.
+ * The comment is copied to the buffer, so it stays valid after the result is destroyed.
+ *
+ * # Return values
+ *
+ * - 0: everything's OK.
+ * - 1: there is no comment to store.
+ * - 2: the passed string buffer is too small.
+ *
+ * ## Safety
+ *
+ * * `result` must have been created by one of the tests.
+ * * `result` must be a valid pointer.
+ * * `result` may not be mutated for the duration of this call.
+ * * `len` must not be `NULL`.
+ * * `ptr` must be valid for writes of up to `len` bytes.
+ * * `ptr` may not be mutated for the duration of this call.
+ * * All responsibility for `ptr` and `len`, especially for its de-allocation, remains with the caller.
+ */
+int sts_TestResult_get_comment(const TestResult *result,
+                               char *ptr,
+                               size_t *len);
+
+/**
+ * Extracts the (maybe existing) label of the test result, which identifies the result for tests
+ * with multiple results, e.g. "forward" and "backward" for the cumulative sums test, "delta1" and
+ * "delta2" for the serial test, "x = +4" for the random excursions tests or "template 000000001"
+ * for the non-overlapping template matching test. Tests with a single result have no label.
+ *
+ * This function works in 2 steps, like [sts_TestResult_get_comment].
+ *
+ * # Return values
+ *
+ * - 0: everything's OK.
+ * - 1: there is no label to store.
+ * - 2: the passed string buffer is too small.
+ *
+ * ## Safety
+ *
+ * * `result` must have been created by one of the tests.
+ * * `result` must be a valid pointer.
+ * * `result` may not be mutated for the duration of this call.
+ * * `len` must not be `NULL`.
+ * * `ptr` must be valid for writes of up to `len` bytes.
+ * * `ptr` may not be mutated for the duration of this call.
+ * * All responsibility for `ptr` and `len`, especially for its de-allocation, remains with the caller.
+ */
+int sts_TestResult_get_label(const TestResult *result,
+                             char *ptr,
+                             size_t *len);
+
+/**
+ * Returns the count of statistics values contained in the test result, see
+ * [sts_TestResult_get_statistic_value] and [sts_TestResult_get_statistic_name].
+ * Returns 0 if the test result contains no statistics.
+ *
+ * ## Safety
+ *
+ * * `result` must have been created by one of the tests.
+ * * `result` must be a valid pointer.
+ * * `result` may not be mutated for the duration of this call.
+ */
+size_t sts_TestResult_get_statistics_count(const TestResult *result);
+
+/**
+ * Extracts the statistics value with the given index from the test result. Integer values are
+ * converted to `double`. The count of values is returned by [sts_TestResult_get_statistics_count].
+ *
+ * # Return values
+ *
+ * - 0: everything's OK, the value is written to `value`.
+ * - 1: there is no statistics value with the given index.
+ *
+ * ## Safety
+ *
+ * * `result` must have been created by one of the tests.
+ * * `result` must be a valid pointer.
+ * * `result` may not be mutated for the duration of this call.
+ * * `value` must be a valid pointer.
+ */
+int sts_TestResult_get_statistic_value(const TestResult *result, size_t idx, double *value);
+
+/**
+ * Extracts the name of the statistics value with the given index from the test result.
+ * This function works in 2 steps, like [sts_TestResult_get_comment]:
+ * 1. the caller calls the function with `ptr` set to `NULL`. The necessary length is written to
+ *    `len`.
+ * 2. the caller calls the function with `ptr` set to a valid buffer, and `len` set to the length of
+ *    the buffer. If the length is enough to store the name, it is written to the buffer.
+ *    The name is written with a nul-terminating byte.
+ *
+ * # Return values
+ *
+ * - 0: everything's OK.
+ * - 1: there is no statistics value with the given index.
+ * - 2: the passed string buffer is too small.
+ *
+ * ## Safety
+ *
+ * * `result` must have been created by one of the tests.
+ * * `result` must be a valid pointer.
+ * * `result` may not be mutated for the duration of this call.
+ * * `len` must not be `NULL`.
+ * * `ptr` must be valid for writes of up to `len` bytes.
+ * * `ptr` may not be mutated for the duration of this call.
+ * * All responsibility for `ptr` and `len`, especially for its de-allocation, remains with the caller.
+ */
+int sts_TestResult_get_statistic_name(const TestResult *result,
+                                      size_t idx,
+                                      char *ptr,
+                                      size_t *len);
+
+/**
+ * Creates a new test runner. This test runner can be used to run multiple tests on 1 sequence in
+ * 1 function call.
+ *
+ * The result pointer must be freed with [sts_TestRunner_destroy]. The resulting pointer will never
+ * be `NULL`.
+ */
+TestRunner *sts_TestRunner_new(void);
+
+/**
+ * Destroys the given test runner.
+ *
+ * ## Safety
+ *
+ * * `runner` must have been created by [sts_TestRunner_new()]
+ * * `runner` must be valid for reads and writes and non-null.
+ * * `runner` may not be mutated for the duration of this call.
+ * * `runner` will be an invalid pointer after this call, trying to access its memory will lead to
+ *   undefined behaviour.
+ */
+void sts_TestRunner_destroy(TestRunner *runner);
+
+/**
+ * Returns the result of the given test, if it was run. Since some tests return multiple results,
+ * a list is returned, its length is returned by [sts_TestResultList_len].
+ *
+ * After this call, the result is no longer stored inside the runner.
+ *
+ * The resulting list of test results must be destroyed with [sts_TestResultList_destroy]. If an
+ * error happened, `NULL` is returned - use [sts_get_last_error].
+ *
+ * ## Safety
+ *
+ * * `runner` must have been created by [sts_TestRunner_new()]
+ * * `runner` must be valid for reads and writes and non-null.
+ * * `runner` may not be mutated for the duration of this call.
+ */
+TestResultList *sts_TestRunner_get_result(TestRunner *runner, Test test);
+
+/**
+ * Same as [sts_TestRunner_get_result], but an error is written to `error` instead of being
+ * stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_TestRunner_get_result] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestResultList *sts_TestRunner_get_result_e(TestRunner *runner,
+                                            Test test,
+                                            StsError **error);
+
+/**
+ * Returns the result of the given test on the sub-sequence with the index `partition`, if it was
+ * run with [sts_TestRunner_run_tests_partitioned] or [sts_TestRunner_run_tests_subsampled].
+ * Since some tests return multiple results, a list is returned, its length is returned by
+ * [sts_TestResultList_len].
+ *
+ * After this call, the result is no longer stored inside the runner.
+ *
+ * The resulting list of test results must be destroyed with [sts_TestResultList_destroy]. If an
+ * error happened, `NULL` is returned - use [sts_get_last_error].
+ *
+ * ## Safety
+ *
+ * * `runner` must have been created by [sts_TestRunner_new()]
+ * * `runner` must be valid for reads and writes and non-null.
+ * * `runner` may not be mutated for the duration of this call.
+ */
+TestResultList *sts_TestRunner_get_partition_result(TestRunner *runner,
+                                                    Test test,
+                                                    size_t partition);
+
+/**
+ * Same as [sts_TestRunner_get_partition_result], but an error is written to `error` instead of
+ * being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_TestRunner_get_partition_result] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestResultList *sts_TestRunner_get_partition_result_e(TestRunner *runner,
+                                                      Test test,
+                                                      size_t partition,
+                                                      StsError **error);
+
+/**
+ * Sets the threshold used by [sts_TestRunner_test_passed] to decide if a test passed. The
+ * threshold must be between 0 and 1 (exclusive). The default is [DEFAULT_THRESHOLD].
+ *
+ * ## Return value
+ *
+ * * `0`: the threshold was set.
+ * * `1`: the threshold is invalid, the stored threshold was not changed. The error message and
+ *   code can be found out with [sts_get_last_error].
+ *
+ * ## Safety
+ *
+ * * `runner` must have been created by [sts_TestRunner_new()]
+ * * `runner` must be valid for reads and writes and non-null.
+ * * `runner` may not be mutated for the duration of this call.
+ */
+int sts_TestRunner_set_threshold(TestRunner *runner, double threshold);
+
+/**
+ * Same as [sts_TestRunner_set_threshold], but an error is written to `error` instead of being
+ * stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_TestRunner_set_threshold] apply.
+ * * `error` must either be `NULL` or valid for writes.
  */
-double sts_TestResult_get_p_value(const TestResult *result);
+int sts_TestRunner_set_threshold_e(TestRunner *runner, double threshold, StsError **error);
 
 /**
- * Checks if the contained p_value passed the given threshold (i.e. if test passed).
+ * Returns the threshold used by [sts_TestRunner_test_passed] to decide if a test passed.
  *
  * ## Safety
  *
- * * `result` must have been created by one of the tests.
- * * `result` must be a valid pointer.
- * * `result` may not be mutated for the duration of this call.
+ * * `runner` must have been created by [sts_TestRunner_new()]
+ * * `runner` must be valid for reads and non-null.
+ * * `runner` may not be mutated for the duration of this call.
  */
-bool sts_TestResult_passed(const TestResult *result, double threshold);
+double sts_TestRunner_get_threshold(const TestRunner *runner);
 
 /**
- * Extracts the (maybe existing) comment contained in the test result.
- * This function works in 2 steps:
- * 1. the caller calls the function with `ptr` set to `NULL`. The necessary length is written to
- *    `len`.
- * 2. the caller calls the function with `ptr` set to a valid buffer, and `len` set to the length of
- *    the buffer. If the length is enough to store the error message, it is written to the buffer.
- *    The error message is written with a nul-terminating byte.
+ * Checks if the given test passed, using the threshold stored in the runner, see
+ * [sts_TestRunner_set_threshold]. A test with multiple results only passed if all results
+ * passed.
  *
- * # Return values
+ * The result stays in the runner and can still be retrieved with [sts_TestRunner_get_result].
  *
- * - 0: everything's OK.
- * - 1: there is no comment to store.
- * - 2: the passed string buffer is too small.
+ * ## Return value
  *
- * ## Safety
+ * * `1`: the test passed.
+ * * `0`: the test did not pass.
+ * * `2`: the test was not applicable to the sequence, see [sts_TestResult_is_applicable].
+ * * `-1`: the test is invalid or its result is not stored in the runner. The error message and
+ *   code can be found out with [sts_get_last_error].
  *
- * * `result` must have been created by one of the tests.
- * * `result` must be a valid pointer.
- * * `result` may not be mutated for the duration of this call.
- * * `len` must not be `NULL`.
- * * `ptr` must be valid for writes of up to `len` bytes.
- * * `ptr` may not be mutated for the duration of this call.
- * * All responsibility for `ptr` and `len`, especially for its de-allocation, remains with the caller.
- */
-int sts_TestResult_get_comment(const TestResult *result,
-                               char *ptr,
-                               size_t *len);
-
-/**
- * Creates a new test runner. This test runner can be used to run multiple tests on 1 sequence in
- * 1 function call.
+ * ## Safety
  *
- * The result pointer must be freed with [sts_TestRunner_destroy]. The resulting pointer will never
- * be `NULL`.
+ * * `runner` must have been created by [sts_TestRunner_new()]
+ * * `runner` must be valid for reads and non-null.
+ * * `runner` may not be mutated for the duration of this call.
  */
-TestRunner *sts_TestRunner_new(void);
+int sts_TestRunner_test_passed(const TestRunner *runner, Test test);
 
 /**
- * Destroys the given test runner.
+ * Same as [sts_TestRunner_test_passed], but an error is written to `error` instead of being
+ * stored as the last error, see [StsError].
  *
  * ## Safety
  *
- * * `runner` must have been created by [sts_TestRunner_new()]
- * * `runner` must be valid for reads and writes and non-null.
- * * `runner` may not be mutated for the duration of this call.
- * * `runner` will be an invalid pointer after this call, trying to access its memory will lead to
- *   undefined behaviour.
+ * * The same requirements as for [sts_TestRunner_test_passed] apply.
+ * * `error` must either be `NULL` or valid for writes.
  */
-void sts_TestRunner_destroy(TestRunner *runner);
+int sts_TestRunner_test_passed_e(const TestRunner *runner, Test test, StsError **error);
 
 /**
- * Returns the result of the given test, if it was run. Since some tests return multiple results,
- * the returned pointer is an array, the count of elements will be stored into `length`.
- *
- * After this call, the result is no longer stored inside the runner.
+ * Sets the cancellation token used by all following runs of the test runner. If the token is
+ * cancelled with [sts_CancellationToken_cancel], the running tests abort and return an error with
+ * the code `ErrorCode_Cancelled`. Pass `NULL` to remove the token.
  *
- * The resulting list of test results must be destroyed with
- * [sts_TestResult_list_destroy].
+ * The runner keeps its own reference to the token, `token` may be destroyed after this call.
  *
  * ## Safety
  *
  * * `runner` must have been created by [sts_TestRunner_new()]
  * * `runner` must be valid for reads and writes and non-null.
  * * `runner` may not be mutated for the duration of this call.
- * * `length` must be a non-null pointer valid for writes.
- * * `length` may not be mutated for the duration of this call.
+ * * `token` must either be `NULL` or have been created by [sts_CancellationToken_new()] and be
+ *   valid for reads.
  */
-TestResult **sts_TestRunner_get_result(TestRunner *runner, Test test, size_t *length);
+void sts_TestRunner_set_cancellation_token(TestRunner *runner, const CancellationToken *token);
 
 /**
- * Runs all tests on the given bit sequence with the default test arguments.
+ * Runs the default tests (all tests except for the Lempel-Ziv compression test) on the given bit
+ * sequence with the default test arguments.
  *
  * ## Return value
  *
@@ -761,13 +2121,27 @@ TestResult **sts_TestRunner_get_result(TestRunner *runner, Test test, size_t *le
  * * `bitvec` must have been created by either [sts_BitVec_from_str],
  *   [sts_BitVec_from_str_with_max_length],
  *   [sts_BitVec_from_bytes],
- *   [sts_BitVec_from_bits] or
- *   [sts_BitVec_clone].
+ *   [sts_BitVec_from_bits],
+ *   [sts_BitVec_from_words],
+ *   [sts_BitVec_borrow_words],
+ *   [sts_BitVec_from_file],
+ *   [sts_BitVec_clone] or [sts_BitVec_slice].
  * * `bitvec` must be a non-null pointer valid for reads.
  * * `bitvec` may not be mutated for the duration of this call.
  */
 int sts_TestRunner_run_all_automatic(TestRunner *runner, const BitVec *data);
 
+/**
+ * Same as [sts_TestRunner_run_all_automatic], but an error is written to `error` instead of
+ * being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_TestRunner_run_all_automatic] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+int sts_TestRunner_run_all_automatic_e(TestRunner *runner, const BitVec *data, StsError **error);
+
 /**
  * Runs all chosen tests on the given bit sequence with the default test arguments.
  *
@@ -775,6 +2149,7 @@ int sts_TestRunner_run_all_automatic(TestRunner *runner, const BitVec *data);
  *
  * * If all tests ran successfully, `0` is returned.
  * * If one of the tests specified was a duplicate of a previous test, `1` is returned.
+ * * If the input is too short for one of the tests or its arguments, `1` is returned.
  * * If one of the tests specified was not a valid test as per the enum [Test], `1` is returned.
  * * If an error occurred while running the tests, `2` is returned. All other tests are still done.
  *   The good test results can be retrieved with [sts_TestRunner_get_result], the exact error can
@@ -791,8 +2166,11 @@ int sts_TestRunner_run_all_automatic(TestRunner *runner, const BitVec *data);
  * * `bitvec` must have been created by either [sts_BitVec_from_str],
  *   [sts_BitVec_from_str_with_max_length],
  *   [sts_BitVec_from_bytes],
- *   [sts_BitVec_from_bits] or
- *   [sts_BitVec_clone].
+ *   [sts_BitVec_from_bits],
+ *   [sts_BitVec_from_words],
+ *   [sts_BitVec_borrow_words],
+ *   [sts_BitVec_from_file],
+ *   [sts_BitVec_clone] or [sts_BitVec_slice].
  * * `bitvec` must be a non-null pointer valid for reads.
  * * `bitvec` may not be mutated for the duration of this call.
  * * `tests` must be a valid, non-null pointer readable for up to `tests_len` elements.
@@ -804,7 +2182,23 @@ int sts_TestRunner_run_automatic(TestRunner *runner,
                                  size_t tests_len);
 
 /**
- * Runs all tests on the given bit sequence with the given test arguments.
+ * Same as [sts_TestRunner_run_automatic], but an error is written to `error` instead of being
+ * stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_TestRunner_run_automatic] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+int sts_TestRunner_run_automatic_e(TestRunner *runner,
+                                   const BitVec *data,
+                                   const Test *tests,
+                                   size_t tests_len,
+                                   StsError **error);
+
+/**
+ * Runs the default tests (all tests except for the Lempel-Ziv compression test) on the given bit
+ * sequence with the given test arguments.
  *
  * ## Return value
  *
@@ -821,8 +2215,11 @@ int sts_TestRunner_run_automatic(TestRunner *runner,
  * * `bitvec` must have been created by either [sts_BitVec_from_str],
  *   [sts_BitVec_from_str_with_max_length],
  *   [sts_BitVec_from_bytes],
- *   [sts_BitVec_from_bits] or
- *   [sts_BitVec_clone].
+ *   [sts_BitVec_from_bits],
+ *   [sts_BitVec_from_words],
+ *   [sts_BitVec_borrow_words],
+ *   [sts_BitVec_from_file],
+ *   [sts_BitVec_clone] or [sts_BitVec_slice].
  * * `bitvec` must be a non-null pointer valid for reads.
  * * `bitvec` may not be mutated for the duration of this call.
  * * `test_args` must have been created by [sts_RunnerTestArgs_new].
@@ -832,6 +2229,20 @@ int sts_TestRunner_run_all_tests(TestRunner *runner,
                                  const BitVec *data,
                                  const RunnerTestArgs *test_args);
 
+/**
+ * Same as [sts_TestRunner_run_all_tests], but an error is written to `error` instead of being
+ * stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_TestRunner_run_all_tests] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+int sts_TestRunner_run_all_tests_e(TestRunner *runner,
+                                   const BitVec *data,
+                                   const RunnerTestArgs *test_args,
+                                   StsError **error);
+
 /**
  * Runs all chosen tests on the given bit sequence with the given test arguments.
  *
@@ -839,6 +2250,7 @@ int sts_TestRunner_run_all_tests(TestRunner *runner,
  *
  * * If all tests ran successfully, `0` is returned.
  * * If one of the tests specified was a duplicate of a previous test, `1` is returned.
+ * * If the input is too short for one of the tests or its arguments, `1` is returned.
  * * If one of the tests specified was not a valid test as per the enum [Test], `1` is returned.
  * * If an error occurred while running the tests, `2` is returned. All other tests are still done.
  *   The good test results can be retrieved with [sts_TestRunner_get_result], the exact error can
@@ -855,8 +2267,11 @@ int sts_TestRunner_run_all_tests(TestRunner *runner,
  * * `bitvec` must have been created by either [sts_BitVec_from_str],
  *   [sts_BitVec_from_str_with_max_length],
  *   [sts_BitVec_from_bytes],
- *   [sts_BitVec_from_bits] or
- *   [sts_BitVec_clone].
+ *   [sts_BitVec_from_bits],
+ *   [sts_BitVec_from_words],
+ *   [sts_BitVec_borrow_words],
+ *   [sts_BitVec_from_file],
+ *   [sts_BitVec_clone] or [sts_BitVec_slice].
  * * `bitvec` must be a non-null pointer valid for reads.
  * * `bitvec` may not be mutated for the duration of this call.
  * * `tests` must be a valid, non-null pointer readable for up to `tests_len` elements.
@@ -871,29 +2286,404 @@ int sts_TestRunner_run_tests(TestRunner *runner,
                              const RunnerTestArgs *test_args);
 
 /**
- * Create new [RunnerTestArgs], prefilled with sane defaults.
+ * Same as [sts_TestRunner_run_tests], but an error is written to `error` instead of being
+ * stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_TestRunner_run_tests] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+int sts_TestRunner_run_tests_e(TestRunner *runner,
+                               const BitVec *data,
+                               const Test *tests,
+                               size_t tests_len,
+                               const RunnerTestArgs *test_args,
+                               StsError **error);
+
+/**
+ * Runs all chosen tests on the given bit sequence with the given test arguments, like
+ * [sts_TestRunner_run_tests], but calls `callback` after each test with its results, e.g. to
+ * update a user interface while the remaining tests run. `user_data` is passed to each call of
+ * the callback unchanged, it may be `NULL`. If `callback` is `NULL`, no callback is called.
+ *
+ * The results are stored in the runner as well, they can be retrieved with
+ * [sts_TestRunner_get_result] after this call. A test that failed with an error is passed to the
+ * callback without results, the error is reported after all tests ran.
+ *
+ * ## Return value
+ *
+ * The same as for [sts_TestRunner_run_tests]. If `1` is returned, no test was run and the
+ * callback was not called.
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_TestRunner_run_tests] apply.
+ * * `callback` must either be `NULL` or a function safe to call with `user_data`, see
+ *   [TestRunnerCallback].
+ * * `callback` may not call any function with `runner`.
+ */
+int sts_TestRunner_run_tests_cb(TestRunner *runner,
+                                const BitVec *data,
+                                const Test *tests,
+                                size_t tests_len,
+                                const RunnerTestArgs *test_args,
+                                TestRunnerCallback callback,
+                                void *user_data);
+
+/**
+ * Same as [sts_TestRunner_run_tests_cb], but an error is written to `error` instead of being
+ * stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_TestRunner_run_tests_cb] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+int sts_TestRunner_run_tests_cb_e(TestRunner *runner,
+                                  const BitVec *data,
+                                  const Test *tests,
+                                  size_t tests_len,
+                                  const RunnerTestArgs *test_args,
+                                  TestRunnerCallback callback,
+                                  void *user_data,
+                                  StsError **error);
+
+/**
+ * Runs all chosen tests on each of `partitions` disjoint sub-sequences of the given bit sequence,
+ * with the given test arguments. All sub-sequences have the same length: the length of the
+ * sequence divided by `partitions`, the remaining bits at the end are not tested.
+ *
+ * The results are retrieved with [sts_TestRunner_get_partition_result], with the index of the
+ * sub-sequence in the sequence. The results of previous partitioned runs are discarded, the
+ * results of [sts_TestRunner_run_tests] etc. are kept.
+ *
+ * ## Return value
+ *
+ * * If all tests ran successfully on all sub-sequences, `0` is returned.
+ * * If one of the tests specified was a duplicate of a previous test, `1` is returned.
+ * * If the sub-sequences are too short for one of the tests or its arguments, `1` is returned.
+ * * If one of the tests specified was not a valid test as per the enum [Test], `1` is returned.
+ * * If `partitions` is `0`, `1` is returned.
+ * * If an error occurred while running the tests, `2` is returned. All other tests are still done.
+ *   The good test results can be retrieved with [sts_TestRunner_get_partition_result], the exact
+ *   error can be retrieved.
+ *
+ * In each error case, the error message and code can be found out with
+ * [sts_get_last_error).
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_TestRunner_run_tests] apply.
+ */
+int sts_TestRunner_run_tests_partitioned(TestRunner *runner,
+                                         const BitVec *data,
+                                         const Test *tests,
+                                         size_t tests_len,
+                                         const RunnerTestArgs *test_args,
+                                         size_t partitions);
+
+/**
+ * Same as [sts_TestRunner_run_tests_partitioned], but an error is written to `error` instead of
+ * being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_TestRunner_run_tests_partitioned] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+int sts_TestRunner_run_tests_partitioned_e(TestRunner *runner,
+                                           const BitVec *data,
+                                           const Test *tests,
+                                           size_t tests_len,
+                                           const RunnerTestArgs *test_args,
+                                           size_t partitions,
+                                           StsError **error);
+
+/**
+ * Runs all chosen tests on each of `count` windows of `window_length` bits of the given bit
+ * sequence, with the given test arguments. The windows don't overlap and are placed at random
+ * offsets generated from `seed`: running with the same seed, window length and count on a
+ * sequence of the same length tests the same windows. This allows testing extremely long
+ * sequences without only testing their start.
+ *
+ * If `offsets` is not `NULL`, the offsets of the windows, in bits and sorted ascending, are
+ * written to it. The results are retrieved with [sts_TestRunner_get_partition_result], with the
+ * index of the window in `offsets`. The results of previous partitioned or subsampled runs are
+ * discarded, the results of [sts_TestRunner_run_tests] etc. are kept.
+ *
+ * ## Return value
+ *
+ * * If all tests ran successfully on all windows, `0` is returned.
+ * * If one of the tests specified was a duplicate of a previous test, `1` is returned.
+ * * If the windows are too short for one of the tests or its arguments, `1` is returned.
+ * * If one of the tests specified was not a valid test as per the enum [Test], `1` is returned.
+ * * If `window_length` or `count` is `0`, or the windows don't fit into the sequence, `1` is
+ *   returned.
+ * * If an error occurred while running the tests, `2` is returned. All other tests are still done.
+ *   The good test results can be retrieved with [sts_TestRunner_get_partition_result], the exact
+ *   error can be retrieved.
+ *
+ * In each error case, the error message and code can be found out with
+ * [sts_get_last_error).
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_TestRunner_run_tests] apply.
+ * * `offsets` must either be `NULL` or valid for writes of `count` elements.
+ */
+int sts_TestRunner_run_tests_subsampled(TestRunner *runner,
+                                        const BitVec *data,
+                                        const Test *tests,
+                                        size_t tests_len,
+                                        const RunnerTestArgs *test_args,
+                                        size_t window_length,
+                                        size_t count,
+                                        uint64_t seed,
+                                        size_t *offsets);
+
+/**
+ * Same as [sts_TestRunner_run_tests_subsampled], but an error is written to `error` instead of
+ * being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_TestRunner_run_tests_subsampled] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+int sts_TestRunner_run_tests_subsampled_e(TestRunner *runner,
+                                          const BitVec *data,
+                                          const Test *tests,
+                                          size_t tests_len,
+                                          const RunnerTestArgs *test_args,
+                                          size_t window_length,
+                                          size_t count,
+                                          uint64_t seed,
+                                          size_t *offsets,
+                                          StsError **error);
+
+/**
+ * Runs the tests of the given [Profile](profile::Profile) on the given bit sequence, with the
+ * test arguments of the profile.
+ *
+ * ## Return value
+ *
+ * * If all tests ran successfully, `0` is returned.
+ * * If the profile is invalid, `1` is returned.
+ * * If an error occurred while running the tests, `2` is returned. All other tests are still done.
+ *   The good test results can be retrieved with [sts_TestRunner_get_result], the exact error can
+ *   be retrieved.
+ *
+ * In each error case, the error message and code can be found out with
+ * [sts_get_last_error).
+ *
+ * ## Safety
+ *
+ * * `runner` must have been created by [sts_TestRunner_new()]
+ * * `runner` must be valid for reads and writes and non-null.
+ * * `runner` may not be mutated for the duration of this call.
+ * * `bitvec` must have been created by either [sts_BitVec_from_str],
+ *   [sts_BitVec_from_str_with_max_length],
+ *   [sts_BitVec_from_bytes],
+ *   [sts_BitVec_from_bits],
+ *   [sts_BitVec_from_words],
+ *   [sts_BitVec_borrow_words],
+ *   [sts_BitVec_from_file],
+ *   [sts_BitVec_clone] or [sts_BitVec_slice].
+ * * `bitvec` must be a non-null pointer valid for reads.
+ * * `bitvec` may not be mutated for the duration of this call.
+ */
+int sts_TestRunner_run_profile(TestRunner *runner, const BitVec *data, Profile profile);
+
+/**
+ * Same as [sts_TestRunner_run_profile], but an error is written to `error` instead of being
+ * stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_TestRunner_run_profile] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+int sts_TestRunner_run_profile_e(TestRunner *runner,
+                                 const BitVec *data,
+                                 Profile profile,
+                                 StsError **error);
+
+/**
+ * Creates a new cancellation token that is not cancelled.
+ *
+ * The resulting pointer must be freed via [sts_CancellationToken_destroy]. The resulting pointer
+ * will never be `NULL`.
+ */
+CancellationToken *sts_CancellationToken_new(void);
+
+/**
+ * Destroys the given cancellation token. A test runner the token was set on keeps working, it
+ * can still be cancelled.
+ *
+ * ## Safety
+ *
+ * * `token` must have been created by [sts_CancellationToken_new()]
+ * * `token` must be valid for reads and writes and non-null.
+ * * `token` may not be used by another thread for the duration of this call.
+ * * `token` will be an invalid pointer after this call, trying to access its memory will lead to
+ *   undefined behaviour.
+ */
+void sts_CancellationToken_destroy(CancellationToken *token);
+
+/**
+ * Cancels all tests using the token. Running tests abort as soon as possible, tests that were
+ * not started yet are not run. Cancellation cannot be undone.
+ *
+ * This function may be called from any thread, even while a test runner using the token is
+ * running.
+ *
+ * ## Safety
+ *
+ * * `token` must have been created by [sts_CancellationToken_new()]
+ * * `token` must be valid for reads and non-null.
+ */
+void sts_CancellationToken_cancel(const CancellationToken *token);
+
+/**
+ * Checks if the token was cancelled with [sts_CancellationToken_cancel].
+ *
+ * ## Safety
+ *
+ * * `token` must have been created by [sts_CancellationToken_new()]
+ * * `token` must be valid for reads and non-null.
+ */
+bool sts_CancellationToken_is_cancelled(const CancellationToken *token);
+
+/**
+ * Returns the profile with the given name, e.g. "nist-default".
+ *
+ * ## Return value
+ *
+ * * The [Profile], if the name is valid.
+ * * `-1` if the name is not valid UTF-8 or no profile has this name. The error message and code
+ *   can be found out with [sts_get_last_error].
+ *
+ * ## Safety
+ *
+ * * The memory pointed to by `name` must contain a valid nul terminator at the end of the string.
+ * * `name` must be valid, as defined by the Rust module safety documentation of `std::ptr`, for
+ *   reads of bytes up to and including the nul terminator.
+ * * The memory referenced by `name` must not be mutated for the duration of this method call.
+ * * `name`, particularly the de-allocation of it, remains in the responsibility of the caller.
+ */
+Profile sts_Profile_from_name(const char *name);
+
+/**
+ * Same as [sts_Profile_from_name], but an error is written to `error` instead of being stored as
+ * the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_Profile_from_name] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+Profile sts_Profile_from_name_e(const char *name, StsError **error);
+
+/**
+ * Create new [RunnerTestArgs], prefilled with sane defaults.
+ *
+ * To set an argument, use the appropriate `runner_test_args_set_...` function.
+ *
+ * The resulting pointer must be freed via [sts_RunnerTestArgs_destroy].
+ */
+RunnerTestArgs *sts_RunnerTestArgs_new(void);
+
+/**
+ * Create new [RunnerTestArgs] with the test arguments of the given [Profile].
+ *
+ * The arguments can be changed with the `runner_test_args_set_...` functions.
+ *
+ * ## Return value
+ *
+ * * The created arguments, which must be freed via [sts_RunnerTestArgs_destroy].
+ * * `NULL` if the profile is invalid. The error message and code can be found out with
+ *   [sts_get_last_error].
+ */
+RunnerTestArgs *sts_RunnerTestArgs_from_profile(Profile profile);
+
+/**
+ * Same as [sts_RunnerTestArgs_from_profile], but an error is written to `error` instead of being
+ * stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * `error` must either be `NULL` or valid for writes.
+ */
+RunnerTestArgs *sts_RunnerTestArgs_from_profile_e(Profile profile, StsError **error);
+
+/**
+ * Destroy the given [RunnerTestArgs].
+ *
+ * ## Safety
+ *
+ * * `args` must have been created by [sts_RunnerTestArgs_new()] or
+ *   [sts_RunnerTestArgs_from_profile()]
+ * * `args` must be valid for reads and writes and non-null.
+ * * `args` may not be mutated for the duration of this call.
+ * * `args` will be an invalid pointer after this call, trying to access its memory will lead to
+ *   undefined behaviour.
+ */
+void sts_RunnerTestArgs_destroy(RunnerTestArgs *args);
+
+/**
+ * Set the argument for the Frequency Block Test to the given value.
+ *
+ * ## Safety
+ *
+ * * `runner` must have been created by [runner_test_args_new()]
+ * * `runner` must be valid for reads and writes and non-null.
+ * * `runner` may not be mutated for the duration of this call.
+ * * `arg` must have been created by one of the construction methods provided by this library.
+ * * `arg` must be valid for reads and non-null.
+ * * `arg` may not be mutated for the duration of this call.
+ * * All responsibility for `arg`, particularly its de-allocation, remains with the caller.
+ *   This function copies the content of `arg`.
+ */
+void sts_RunnerTestArgs_set_frequency_block(RunnerTestArgs *runner,
+                                            const TestArgFrequencyBlock *arg);
+
+/**
+ * Set the argument for the Test for the Longest Run of Ones in a Block to the given value.
  *
- * To set an argument, use the appropriate `runner_test_args_set_...` function.
+ * ## Safety
  *
- * The resulting pointer must be freed via [sts_RunnerTestArgs_destroy].
+ * * `runner` must have been created by [runner_test_args_new()]
+ * * `runner` must be valid for reads and writes and non-null.
+ * * `runner` may not be mutated for the duration of this call.
+ * * `arg` must have been created by one of the construction methods provided by this library.
+ * * `arg` must be valid for reads and non-null.
+ * * `arg` may not be mutated for the duration of this call.
+ * * All responsibility for `arg`, particularly its de-allocation, remains with the caller.
+ *   This function copies the content of `arg`.
  */
-RunnerTestArgs *sts_RunnerTestArgs_new(void);
+void sts_RunnerTestArgs_set_longest_run_of_ones(RunnerTestArgs *runner,
+                                                const TestArgLongestRunOfOnes *arg);
 
 /**
- * Destroy the given [RunnerTestArgs].
+ * Set the argument for the Binary Matrix Rank Test to the given value.
  *
  * ## Safety
  *
- * * `args` must have been created by [sts_RunnerTestArgs_new()]
- * * `args` must be valid for reads and writes and non-null.
- * * `args` may not be mutated for the duration of this call.
- * * `args` will be an invalid pointer after this call, trying to access its memory will lead to
- *   undefined behaviour.
+ * * `runner` must have been created by [runner_test_args_new()]
+ * * `runner` must be valid for reads and writes and non-null.
+ * * `runner` may not be mutated for the duration of this call.
+ * * `arg` must have been created by one of the construction methods provided by this library.
+ * * `arg` must be valid for reads and non-null.
+ * * `arg` may not be mutated for the duration of this call.
+ * * All responsibility for `arg`, particularly its de-allocation, remains with the caller.
+ *   This function copies the content of `arg`.
  */
-void sts_RunnerTestArgs_destroy(RunnerTestArgs *args);
+void sts_RunnerTestArgs_set_binary_matrix_rank(RunnerTestArgs *runner,
+                                               const TestArgBinaryMatrixRank *arg);
 
 /**
- * Set the argument for the Frequency Block Test to the given value.
+ * Set the argument for the Spectral DFT Test to the given value.
  *
  * ## Safety
  *
@@ -906,8 +2696,7 @@ void sts_RunnerTestArgs_destroy(RunnerTestArgs *args);
  * * All responsibility for `arg`, particularly its de-allocation, remains with the caller.
  *   This function copies the content of `arg`.
  */
-void sts_RunnerTestArgs_set_frequency_block(RunnerTestArgs *runner,
-                                            const TestArgFrequencyBlock *arg);
+void sts_RunnerTestArgs_set_spectral_dft(RunnerTestArgs *runner, const TestArgSpectralDft *arg);
 
 /**
  * Set the argument for the Non-Overlapping Template Matching Test to the given value.
@@ -993,6 +2782,77 @@ void sts_RunnerTestArgs_set_serial(RunnerTestArgs *runner, const TestArgSerial *
 void sts_RunnerTestArgs_set_approximate_entropy(RunnerTestArgs *runner,
                                                 const TestArgApproximateEntropy *arg);
 
+/**
+ * Set the argument for the Random Excursions Test to the given value.
+ *
+ * ## Safety
+ *
+ * * `runner` must have been created by [runner_test_args_new()]
+ * * `runner` must be valid for reads and writes and non-null.
+ * * `runner` may not be mutated for the duration of this call.
+ * * `arg` must have been created by one of the construction methods provided by this library.
+ * * `arg` must be valid for reads and non-null.
+ * * `arg` may not be mutated for the duration of this call.
+ * * All responsibility for `arg`, particularly its de-allocation, remains with the caller.
+ *   This function copies the content of `arg`.
+ */
+void sts_RunnerTestArgs_set_random_excursions(RunnerTestArgs *runner,
+                                              const TestArgRandomExcursions *arg);
+
+/**
+ * Set the argument for the Random Excursions Variant Test to the given value.
+ *
+ * ## Safety
+ *
+ * * `runner` must have been created by [runner_test_args_new()]
+ * * `runner` must be valid for reads and writes and non-null.
+ * * `runner` may not be mutated for the duration of this call.
+ * * `arg` must have been created by one of the construction methods provided by this library.
+ * * `arg` must be valid for reads and non-null.
+ * * `arg` may not be mutated for the duration of this call.
+ * * All responsibility for `arg`, particularly its de-allocation, remains with the caller.
+ *   This function copies the content of `arg`.
+ */
+void sts_RunnerTestArgs_set_random_excursions_variant(RunnerTestArgs *runner,
+                                                      const TestArgRandomExcursionsVariant *arg);
+
+/**
+ * Set the argument for the Autocorrelation Test to the given value.
+ *
+ * ## Safety
+ *
+ * * `runner` must have been created by [runner_test_args_new()]
+ * * `runner` must be valid for reads and writes and non-null.
+ * * `runner` may not be mutated for the duration of this call.
+ * * `arg` must have been created by one of the construction methods provided by this library.
+ * * `arg` must be valid for reads and non-null.
+ * * `arg` may not be mutated for the duration of this call.
+ * * All responsibility for `arg`, particularly its de-allocation, remains with the caller.
+ *   This function copies the content of `arg`.
+ */
+void sts_RunnerTestArgs_set_autocorrelation(RunnerTestArgs *runner,
+                                            const TestArgAutocorrelation *arg);
+
+/**
+ * Set the timeout of each test run with these arguments, in seconds. A test that runs longer is
+ * aborted and returns an error with the code [ErrorCode::TimedOut]. The timeout is checked
+ * regularly by the slow tests, so a test may run a bit longer than the timeout.
+ *
+ * A value of `0` or less removes the timeout (the default).
+ *
+ * ## Return value
+ *
+ * * `true`: the timeout was set.
+ * * `false`: the value is NaN or too large, the timeout is unchanged.
+ *
+ * ## Safety
+ *
+ * * `runner` must have been created by [runner_test_args_new()]
+ * * `runner` must be valid for reads and writes and non-null.
+ * * `runner` may not be mutated for the duration of this call.
+ */
+bool sts_RunnerTestArgs_set_timeout(RunnerTestArgs *runner, double seconds);
+
 /**
  * Frequency (mono bit) test - No. 1
  *
@@ -1013,6 +2873,16 @@ void sts_RunnerTestArgs_set_approximate_entropy(RunnerTestArgs *runner,
  */
 TestResult *sts_frequency_test(const BitVec *data);
 
+/**
+ * Same as [sts_frequency_test], but an error is written to `error` instead of being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_frequency_test] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestResult *sts_frequency_test_e(const BitVec *data, StsError **error);
+
 /**
  * Frequency Test within a block - No. 2
  *
@@ -1037,6 +2907,18 @@ TestResult *sts_frequency_test(const BitVec *data);
 TestResult *sts_frequency_block_test(const BitVec *data,
                                      const TestArgFrequencyBlock *test_arg);
 
+/**
+ * Same as [sts_frequency_block_test], but an error is written to `error` instead of being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_frequency_block_test] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestResult *sts_frequency_block_test_e(const BitVec *data,
+                                       const TestArgFrequencyBlock *test_arg,
+                                       StsError **error);
+
 /**
  * Runs test - No. 3
  *
@@ -1058,6 +2940,16 @@ TestResult *sts_frequency_block_test(const BitVec *data,
  */
 TestResult *sts_runs_test(const BitVec *data);
 
+/**
+ * Same as [sts_runs_test], but an error is written to `error` instead of being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_runs_test] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestResult *sts_runs_test_e(const BitVec *data, StsError **error);
+
 /**
  * Test for the Longest Run of Ones in a Block - No. 4
  *
@@ -1067,7 +2959,9 @@ TestResult *sts_runs_test(const BitVec *data);
  * An irregularity in the length of longest run of ones also implies an irregularity in the length
  * of the longest runs of zeroes, meaning that only this test is necessary. See the NIST publication.
  *
- * The data has to be at least 128 bits in length.
+ * The data has to be at least 128 bits in length. The configuration of SP 800-22 (block length
+ * 8, 128 or 10000 bits) is chosen based on the input length, unless it is set with
+ * [TestArgLongestRunOfOnes].
  *
  * ## Return value
  *
@@ -1079,17 +2973,34 @@ TestResult *sts_runs_test(const BitVec *data);
  * * `data` must have been created by one of the construction methods provided by this library.
  * * `data` must be valid for reads and non-null.
  * * `data` may not be mutated for the duration of this call.
- * * All responsibility for `data`, particularly for its destruction, remains with the caller.
+ * * `test_arg` must have been created by one of the construction methods provided by this library.
+ * * `test_arg` must be valid for reads and non-null.
+ * * `test_arg` may not be mutated for the duration of this call.
+ * * All responsibility for `data` and `test_arg`, particularly for their destruction, remains with the caller.
  */
-TestResult *sts_longest_run_of_ones_test(const BitVec *data);
+TestResult *sts_longest_run_of_ones_test(const BitVec *data,
+                                         const TestArgLongestRunOfOnes *test_arg);
+
+/**
+ * Same as [sts_longest_run_of_ones_test], but an error is written to `error` instead of being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_longest_run_of_ones_test] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestResult *sts_longest_run_of_ones_test_e(const BitVec *data,
+                                           const TestArgLongestRunOfOnes *test_arg,
+                                           StsError **error);
 
 /**
  * Binary Matrix Rank Test -  No. 5
  *
  * This test checks for linear dependence among fixed length substrings of the sequence.
- * These substrings are interpreted as matrices of size 32x32.
+ * These substrings are interpreted as matrices of size 32x32, other dimensions can be set
+ * with [TestArgBinaryMatrixRank].
  *
- * The sequence must consist of at least 38 912 bits = 4864 bytes.
+ * The sequence must consist of at least 38 912 bits = 4864 bytes for 32x32 matrices.
  *
  * ## Return value
  *
@@ -1101,9 +3012,25 @@ TestResult *sts_longest_run_of_ones_test(const BitVec *data);
  * * `data` must have been created by one of the construction methods provided by this library.
  * * `data` must be valid for reads and non-null.
  * * `data` may not be mutated for the duration of this call.
- * * All responsibility for `data`, particularly for its destruction, remains with the caller.
+ * * `test_arg` must have been created by one of the construction methods provided by this library.
+ * * `test_arg` must be valid for reads and non-null.
+ * * `test_arg` may not be mutated for the duration of this call.
+ * * All responsibility for `data` and `test_arg`, particularly for their destruction, remains with the caller.
+ */
+TestResult *sts_binary_matrix_rank_test(const BitVec *data,
+                                        const TestArgBinaryMatrixRank *test_arg);
+
+/**
+ * Same as [sts_binary_matrix_rank_test], but an error is written to `error` instead of being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_binary_matrix_rank_test] apply.
+ * * `error` must either be `NULL` or valid for writes.
  */
-TestResult *sts_binary_matrix_rank_test(const BitVec *data);
+TestResult *sts_binary_matrix_rank_test_e(const BitVec *data,
+                                          const TestArgBinaryMatrixRank *test_arg,
+                                          StsError **error);
 
 /**
  * The Spectral Discrete Fourier Transform test - No. 6
@@ -1113,6 +3040,9 @@ TestResult *sts_binary_matrix_rank_test(const BitVec *data);
  *
  * It is recommended (but not required) for the input to be of at least 1000 bits.
  *
+ * This test allows to choose the derivation of the peak height threshold, see
+ * [TestArgSpectralDft].
+ *
  * ## Return value
  *
  * If the test ran without errors, a single `TestResult` is returned. This result can be deallocated with `test_result_destroy`.
@@ -1123,9 +3053,25 @@ TestResult *sts_binary_matrix_rank_test(const BitVec *data);
  * * `data` must have been created by one of the construction methods provided by this library.
  * * `data` must be valid for reads and non-null.
  * * `data` may not be mutated for the duration of this call.
- * * All responsibility for `data`, particularly for its destruction, remains with the caller.
+ * * `test_arg` must have been created by one of the construction methods provided by this library.
+ * * `test_arg` must be valid for reads and non-null.
+ * * `test_arg` may not be mutated for the duration of this call.
+ * * All responsibility for `data` and `test_arg`, particularly for their destruction, remains with the caller.
+ */
+TestResult *sts_spectral_dft_test(const BitVec *data,
+                                  const TestArgSpectralDft *test_arg);
+
+/**
+ * Same as [sts_spectral_dft_test], but an error is written to `error` instead of being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_spectral_dft_test] apply.
+ * * `error` must either be `NULL` or valid for writes.
  */
-TestResult *sts_spectral_dft_test(const BitVec *data);
+TestResult *sts_spectral_dft_test_e(const BitVec *data,
+                                    const TestArgSpectralDft *test_arg,
+                                    StsError **error);
 
 /**
  * Non-overlapping Template Matching test - No. 7
@@ -1137,8 +3083,8 @@ TestResult *sts_spectral_dft_test(const BitVec *data);
  *
  * ## Return value
  *
- * If the test ran without errors, a list of `TestResult` is returned. This list can be deallocated with `test_result_list_destroy`.
- * The length of the returned list will be stored into `length`.
+ * If the test ran without errors, a `TestResultList` is returned. This list can be deallocated with `sts_TestResultList_destroy`.
+ * The length of the returned list is returned by `sts_TestResultList_len`.
  * If an error occurred, `NULL` is returned, and the error code and message can be retrieved with `get_last_error`.
  *
  * ## Safety
@@ -1149,13 +3095,22 @@ TestResult *sts_spectral_dft_test(const BitVec *data);
  * * `test_arg` must have been created by one of the construction methods provided by this library.
  * * `test_arg` must be valid for reads and non-null.
  * * `test_arg` may not be mutated for the duration of this call.
- * * `length` must be valid for writes and non-null.
- * * `length` may not be mutated for the duration of this call.
- * * All responsibility for `data`, `test_arg` and `length`, particularly for their destruction, remains with the caller.
+ * * All responsibility for `data` and `test_arg`, particularly for their destruction, remains with the caller.
+ */
+TestResultList *sts_non_overlapping_template_matching_test(const BitVec *data,
+                                                           const TestArgNonOverlappingTemplate *test_arg);
+
+/**
+ * Same as [sts_non_overlapping_template_matching_test], but an error is written to `error` instead of being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_non_overlapping_template_matching_test] apply.
+ * * `error` must either be `NULL` or valid for writes.
  */
-TestResult **sts_non_overlapping_template_matching_test(const BitVec *data,
-                                                        const TestArgNonOverlappingTemplate *test_arg,
-                                                        size_t *length);
+TestResultList *sts_non_overlapping_template_matching_test_e(const BitVec *data,
+                                                             const TestArgNonOverlappingTemplate *test_arg,
+                                                             StsError **error);
 
 /**
  * Overlapping Template Matching test - No. 8
@@ -1183,8 +3138,8 @@ TestResult **sts_non_overlapping_template_matching_test(const BitVec *data,
  *
  * # About performance
  *
- * This test is quite slow in debug mode when using the more precise pi values (non-NIST behaviour),
- * taking several seconds - it runs good when using release mode.
+ * The more precise pi values (non-NIST behaviour) take time quadratic in the block length, e.g.
+ * 3 ms for a block length of 1032 - except for the default arguments, whose values are built in.
  * For better performance, values that are calculated once are cached.
  *
  * ## Return value
@@ -1205,6 +3160,18 @@ TestResult **sts_non_overlapping_template_matching_test(const BitVec *data,
 TestResult *sts_overlapping_template_matching_test(const BitVec *data,
                                                    const TestArgOverlappingTemplate *test_arg);
 
+/**
+ * Same as [sts_overlapping_template_matching_test], but an error is written to `error` instead of being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_overlapping_template_matching_test] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestResult *sts_overlapping_template_matching_test_e(const BitVec *data,
+                                                     const TestArgOverlappingTemplate *test_arg,
+                                                     StsError **error);
+
 /**
  * Maurer's "Universal Statistical" Test - No. 9
  *
@@ -1228,6 +3195,16 @@ TestResult *sts_overlapping_template_matching_test(const BitVec *data,
  */
 TestResult *sts_maurers_universal_statistical_test(const BitVec *data);
 
+/**
+ * Same as [sts_maurers_universal_statistical_test], but an error is written to `error` instead of being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_maurers_universal_statistical_test] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestResult *sts_maurers_universal_statistical_test_e(const BitVec *data, StsError **error);
+
 /**
  * The linear complexity test - No. 10
  *
@@ -1255,6 +3232,18 @@ TestResult *sts_maurers_universal_statistical_test(const BitVec *data);
 TestResult *sts_linear_complexity_test(const BitVec *data,
                                        const TestArgLinearComplexity *test_arg);
 
+/**
+ * Same as [sts_linear_complexity_test], but an error is written to `error` instead of being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_linear_complexity_test] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestResult *sts_linear_complexity_test_e(const BitVec *data,
+                                         const TestArgLinearComplexity *test_arg,
+                                         StsError **error);
+
 /**
  * The serial test - No. 11
  *
@@ -1277,8 +3266,8 @@ TestResult *sts_linear_complexity_test(const BitVec *data,
  *
  * ## Return value
  *
- * If the test ran without errors, a list of `TestResult` is returned. This list can be deallocated with `test_result_list_destroy`.
- * The returned array always has length 2.
+ * If the test ran without errors, a `TestResultList` is returned. This list can be deallocated with `sts_TestResultList_destroy`.
+ * The returned list always has length 2.
  * If an error occurred, `NULL` is returned, and the error code and message can be retrieved with `get_last_error`.
  *
  * ## Safety
@@ -1291,8 +3280,20 @@ TestResult *sts_linear_complexity_test(const BitVec *data,
  * * `test_arg` may not be mutated for the duration of this call.
  * * All responsibility for `data` and `test_arg`, particularly for their destruction, remains with the caller.
  */
-TestResult **sts_serial_test(const BitVec *data,
-                             const TestArgSerial *test_arg);
+TestResultList *sts_serial_test(const BitVec *data,
+                                const TestArgSerial *test_arg);
+
+/**
+ * Same as [sts_serial_test], but an error is written to `error` instead of being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_serial_test] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestResultList *sts_serial_test_e(const BitVec *data,
+                                  const TestArgSerial *test_arg,
+                                  StsError **error);
 
 /**
  * The approximate entropy test - No. 12
@@ -1328,6 +3329,18 @@ TestResult **sts_serial_test(const BitVec *data,
 TestResult *sts_approximate_entropy_test(const BitVec *data,
                                          const TestArgApproximateEntropy *test_arg);
 
+/**
+ * Same as [sts_approximate_entropy_test], but an error is written to `error` instead of being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_approximate_entropy_test] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestResult *sts_approximate_entropy_test_e(const BitVec *data,
+                                           const TestArgApproximateEntropy *test_arg,
+                                           StsError **error);
+
 /**
  * The cumulative sums test - No. 13
  *
@@ -1341,8 +3354,8 @@ TestResult *sts_approximate_entropy_test(const BitVec *data,
  *
  * ## Return value
  *
- * If the test ran without errors, a list of `TestResult` is returned. This result can be deallocated with `test_result_list_destroy`.
- * The returned array always has length 2.
+ * If the test ran without errors, a `TestResultList` is returned. This list can be deallocated with `sts_TestResultList_destroy`.
+ * The returned list always has length 2.
  * If an error occurred, `NULL` is returned, and the error code and message can be retrieved with `get_last_error`.
  *
  * ## Safety
@@ -1352,7 +3365,17 @@ TestResult *sts_approximate_entropy_test(const BitVec *data,
  * * `data` may not be mutated for the duration of this call.
  * * All responsibility for `data`, particularly for its destruction, remains with the caller.
  */
-TestResult **sts_cumulative_sums_test(const BitVec *data);
+TestResultList *sts_cumulative_sums_test(const BitVec *data);
+
+/**
+ * Same as [sts_cumulative_sums_test], but an error is written to `error` instead of being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_cumulative_sums_test] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestResultList *sts_cumulative_sums_test_e(const BitVec *data, StsError **error);
 
 /**
  * The random excursions test - No. 14.
@@ -1365,16 +3388,17 @@ TestResult **sts_cumulative_sums_test(const BitVec *data);
  * Since the test needs at least 500 cycles to occur, bit sequences with fewer cycles will lead to an
  * `Ok()` result, but with the values filled with "0.0".
  *
- * If the computation finishes successfully, 8 [TestResult] are returned: one for each tested state,
- * `x`. The results will contain a comment about the state they are calculated from (e.g. "x = 3"),
- * the order is: `[-4, -3, -2, -1, +1, +2, +3, +4]`.
+ * The tested states *x* can be configured with [TestArgRandomExcursions], NIST uses the states
+ * `-4..=+4`. If the computation finishes successfully, one [TestResult] is returned for each
+ * tested state. The results will contain a comment about the state they are calculated from
+ * (e.g. "x = +3"), the order is ascending: `[-4, -3, -2, -1, +1, +2, +3, +4]` with the defaults.
  *
  * The input length must be at least 10^6 bits, otherwise, an error is raised.
  *
  * ## Return value
  *
- * If the test ran without errors, a list of `TestResult` is returned. This result can be deallocated with `test_result_list_destroy`.
- * The returned array always has length 8.
+ * If the test ran without errors, a `TestResultList` is returned. This list can be deallocated with `sts_TestResultList_destroy`.
+ * The length of the returned list is returned by `sts_TestResultList_len`.
  * If an error occurred, `NULL` is returned, and the error code and message can be retrieved with `get_last_error`.
  *
  * ## Safety
@@ -1382,9 +3406,25 @@ TestResult **sts_cumulative_sums_test(const BitVec *data);
  * * `data` must have been created by one of the construction methods provided by this library.
  * * `data` must be valid for reads and non-null.
  * * `data` may not be mutated for the duration of this call.
- * * All responsibility for `data`, particularly for its destruction, remains with the caller.
+ * * `test_arg` must have been created by one of the construction methods provided by this library.
+ * * `test_arg` must be valid for reads and non-null.
+ * * `test_arg` may not be mutated for the duration of this call.
+ * * All responsibility for `data` and `test_arg`, particularly for their destruction, remains with the caller.
+ */
+TestResultList *sts_random_excursions_test(const BitVec *data,
+                                           const TestArgRandomExcursions *test_arg);
+
+/**
+ * Same as [sts_random_excursions_test], but an error is written to `error` instead of being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_random_excursions_test] apply.
+ * * `error` must either be `NULL` or valid for writes.
  */
-TestResult **sts_random_excursions_test(const BitVec *data);
+TestResultList *sts_random_excursions_test_e(const BitVec *data,
+                                             const TestArgRandomExcursions *test_arg,
+                                             StsError **error);
 
 /**
  * The random excursions variant test.
@@ -1395,16 +3435,61 @@ TestResult **sts_random_excursions_test(const BitVec *data);
  *
  * This test does not require a minimum number of cycles.
  *
- * If the computation finishes successfully, 18 [TestResult] are returned: one for each tested state,
- * `x`. The results will contain a comment about the state they are calculated from (e.g. "x = 3"),
- * the order is: `[-9, -8, -7, -6, -5, -4, -3, -2, -1, +1, +2, +3, +4, +5, +6, +7, +8, +9]`.
+ * The tested states *x* can be configured with [TestArgRandomExcursionsVariant], NIST uses the
+ * states `-9..=+9`. If the computation finishes successfully, one [TestResult] is returned for
+ * each tested state. The results will contain a comment about the state they are calculated
+ * from (e.g. "x = +3"), the order is ascending: `[-9, -8, ..., -1, +1, ..., +8, +9]` with the
+ * defaults.
  *
  * The input length must be at least 10^6 bits, otherwise, an error is returned.
  *
  * ## Return value
  *
- * If the test ran without errors, a list of `TestResult` is returned. This result can be deallocated with `test_result_list_destroy`.
- * The returned array always has length 18.
+ * If the test ran without errors, a `TestResultList` is returned. This list can be deallocated with `sts_TestResultList_destroy`.
+ * The length of the returned list is returned by `sts_TestResultList_len`.
+ * If an error occurred, `NULL` is returned, and the error code and message can be retrieved with `get_last_error`.
+ *
+ * ## Safety
+ *
+ * * `data` must have been created by one of the construction methods provided by this library.
+ * * `data` must be valid for reads and non-null.
+ * * `data` may not be mutated for the duration of this call.
+ * * `test_arg` must have been created by one of the construction methods provided by this library.
+ * * `test_arg` must be valid for reads and non-null.
+ * * `test_arg` may not be mutated for the duration of this call.
+ * * All responsibility for `data` and `test_arg`, particularly for their destruction, remains with the caller.
+ */
+TestResultList *sts_random_excursions_variant_test(const BitVec *data,
+                                                   const TestArgRandomExcursionsVariant *test_arg);
+
+/**
+ * Same as [sts_random_excursions_variant_test], but an error is written to `error` instead of being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_random_excursions_variant_test] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestResultList *sts_random_excursions_variant_test_e(const BitVec *data,
+                                                     const TestArgRandomExcursionsVariant *test_arg,
+                                                     StsError **error);
+
+/**
+ * The Lempel-Ziv compression test - **not part of the current revision of SP 800-22**.
+ *
+ * This test was part of the original SP 800-22 (2001) and was removed in revision 1, because
+ * the reference distribution of its test statistic is inaccurate. It is only provided for
+ * comparative studies.
+ *
+ * This test counts the cumulatively distinct words of the Lempel-Ziv parsing of the sequence.
+ * A sequence that can be compressed significantly is considered non-random.
+ *
+ * The input length must be at least 10^6 bits, otherwise, an error is returned. Only the first
+ * 10^6 bits are tested.
+ *
+ * ## Return value
+ *
+ * If the test ran without errors, a single `TestResult` is returned. This result can be deallocated with `test_result_destroy`.
  * If an error occurred, `NULL` is returned, and the error code and message can be retrieved with `get_last_error`.
  *
  * ## Safety
@@ -1414,7 +3499,59 @@ TestResult **sts_random_excursions_test(const BitVec *data);
  * * `data` may not be mutated for the duration of this call.
  * * All responsibility for `data`, particularly for its destruction, remains with the caller.
  */
-TestResult **sts_random_excursions_variant_test(const BitVec *data);
+TestResult *sts_lempel_ziv_test(const BitVec *data);
+
+/**
+ * Same as [sts_lempel_ziv_test], but an error is written to `error` instead of being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_lempel_ziv_test] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestResult *sts_lempel_ziv_test_e(const BitVec *data, StsError **error);
+
+/**
+ * The autocorrelation test - **not part of SP 800-22**, but test T5 of BSI AIS 31.
+ *
+ * This test compares the sequence with a copy of itself, shifted by *d* bits: the count of
+ * bits that differ from the bit *d* positions later should be about half of the compared bits.
+ *
+ * This test needs a parameter [TestArgAutocorrelation]. Check the described constraints there.
+ *
+ * The input length should be at least 10^4 bits, like in AIS 31, although this is not enforced.
+ *
+ * If the combination of the given data ([BitVec]) and [TestArgAutocorrelation] is invalid,
+ * an error is raised. For the exact constraints, see [TestArgAutocorrelation].
+ *
+ * ## Return value
+ *
+ * If the test ran without errors, a single `TestResult` is returned. This result can be deallocated with `test_result_destroy`.
+ * If an error occurred, `NULL` is returned, and the error code and message can be retrieved with `get_last_error`.
+ *
+ * ## Safety
+ *
+ * * `data` must have been created by one of the construction methods provided by this library.
+ * * `data` must be valid for reads and non-null.
+ * * `data` may not be mutated for the duration of this call.
+ * * `test_arg` must have been created by one of the construction methods provided by this library.
+ * * `test_arg` must be valid for reads and non-null.
+ * * `test_arg` may not be mutated for the duration of this call.
+ * * All responsibility for `data` and `test_arg`, particularly for their destruction, remains with the caller.
+ */
+TestResult *sts_autocorrelation_test(const BitVec *data, const TestArgAutocorrelation *test_arg);
+
+/**
+ * Same as [sts_autocorrelation_test], but an error is written to `error` instead of being stored as the last error, see [StsError].
+ *
+ * ## Safety
+ *
+ * * The same requirements as for [sts_autocorrelation_test] apply.
+ * * `error` must either be `NULL` or valid for writes.
+ */
+TestResult *sts_autocorrelation_test_e(const BitVec *data,
+                                       const TestArgAutocorrelation *test_arg,
+                                       StsError **error);
 
 #ifdef __cplusplus
 }  // extern "C"
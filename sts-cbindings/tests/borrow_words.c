@@ -0,0 +1,88 @@
+/**
+ * Tests sts_BitVec_borrow_words(): a borrowed BitVec gives the same results as a copied one, and
+ * the borrowed words are never written to. Compiled and run by c_api.rs.
+ */
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include "../sts-lib.h"
+
+#define CHECK(condition)                                                                  \
+    do {                                                                                  \
+        if (!(condition)) {                                                               \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #condition); \
+            exit(1);                                                                      \
+        }                                                                                 \
+    } while (0)
+
+#define WORD_BITS (8 * sizeof(size_t))
+#define N_WORDS 2000
+
+int main(void) {
+    size_t *words = malloc(N_WORDS * sizeof(size_t));
+    size_t *original = malloc(N_WORDS * sizeof(size_t));
+    CHECK(words != NULL && original != NULL);
+
+    // xorshift64
+    uint64_t state = 88172645463325252u;
+    for (size_t i = 0; i < N_WORDS; i++) {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        words[i] = (size_t) state;
+    }
+    memcpy(original, words, N_WORDS * sizeof(size_t));
+
+    size_t bit_len = N_WORDS * WORD_BITS;
+    BitVec *copied = sts_BitVec_from_words(words, N_WORDS, bit_len);
+    BitVec *borrowed = sts_BitVec_borrow_words(words, N_WORDS, bit_len);
+    CHECK(copied != NULL && borrowed != NULL);
+    CHECK(sts_BitVec_len_bit(borrowed) == bit_len);
+
+    TestResult *expected = sts_runs_test(copied);
+    TestResult *actual = sts_runs_test(borrowed);
+    CHECK(expected != NULL && actual != NULL);
+    CHECK(sts_TestResult_get_p_value(expected) == sts_TestResult_get_p_value(actual));
+    sts_TestResult_destroy(expected);
+    sts_TestResult_destroy(actual);
+
+    // cropping a clone to full words keeps borrowing, cropping inside a word clears the bits after
+    // the new length in a copy: the borrowed words are unchanged.
+    BitVec *clone = sts_BitVec_clone(borrowed);
+    sts_BitVec_crop(clone, bit_len / 2);
+    sts_BitVec_crop(borrowed, bit_len / 2 + 1);
+    CHECK(sts_BitVec_len_bit(clone) == bit_len / 2);
+    CHECK(sts_BitVec_len_bit(borrowed) == bit_len / 2 + 1);
+    CHECK(memcmp(words, original, N_WORDS * sizeof(size_t)) == 0);
+
+    sts_BitVec_crop(copied, bit_len / 2 + 1);
+    expected = sts_runs_test(copied);
+    actual = sts_runs_test(borrowed);
+    CHECK(expected != NULL && actual != NULL);
+    CHECK(sts_TestResult_get_p_value(expected) == sts_TestResult_get_p_value(actual));
+    sts_TestResult_destroy(expected);
+    sts_TestResult_destroy(actual);
+
+    // the bit after the length is set
+    size_t last_bit_set[1] = { 1 };
+    StsError *error = NULL;
+    CHECK(sts_BitVec_borrow_words_e(last_bit_set, 1, WORD_BITS - 1, &error) == NULL);
+    CHECK(error != NULL);
+    sts_error_free(error);
+    BitVec *full = sts_BitVec_borrow_words_e(last_bit_set, 1, WORD_BITS, &error);
+    CHECK(full != NULL && error == NULL);
+
+    // more bits than words
+    CHECK(sts_BitVec_borrow_words(words, N_WORDS, bit_len + 1) == NULL);
+    CHECK(sts_get_last_error(NULL, &(size_t) { 0 }) != 0);
+
+    sts_BitVec_destroy(full);
+    sts_BitVec_destroy(clone);
+    sts_BitVec_destroy(borrowed);
+    sts_BitVec_destroy(copied);
+    free(original);
+    free(words);
+
+    return 0;
+}
@@ -0,0 +1,53 @@
+//! Compiles the C tests in this directory with the C compiler `cc` (or the one given in the
+//! environment variable `CC`), links them against the static library and runs them. `cargo test`
+//! does not build the static library, it is built by the tests with `cargo build`.
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The directory of the static library: the test executables are in `deps` below it.
+fn library_dir() -> PathBuf {
+    let exe = env::current_exe().unwrap();
+    exe.parent().unwrap().parent().unwrap().to_path_buf()
+}
+
+/// Compiles and runs `tests/<name>.c`, the test fails if it returns a non-zero exit code.
+fn run_c_test(name: &str) {
+    let mut build = Command::new(env!("CARGO"));
+    build.args(["build", "--lib", "-p", env!("CARGO_PKG_NAME")]);
+    if !cfg!(debug_assertions) {
+        build.arg("--release");
+    }
+    let status = build.status().unwrap();
+    assert!(status.success(), "Building the static library failed");
+
+    let source = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join(format!("{name}.c"));
+    let executable = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_owned());
+
+    let status = Command::new(&compiler)
+        .arg("-std=c11")
+        .arg(&source)
+        .arg(library_dir().join("libsts.a"))
+        .args(["-lm", "-lpthread", "-ldl", "-o"])
+        .arg(&executable)
+        .status()
+        .unwrap_or_else(|e| panic!("Could not run the C compiler {compiler}: {e}"));
+    assert!(status.success(), "Compiling {} failed", source.display());
+
+    let output = Command::new(&executable).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{name} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+#[cfg_attr(not(unix), ignore = "uses the unix name of the library")]
+fn test_borrow_words() {
+    run_c_test("borrow_words");
+}
@@ -0,0 +1,340 @@
+//! Checkpoints for split runs, see [Checkpoint].
+//!
+//! A checkpoint file records the results of each completed part of a run with `--split`, so that
+//! an aborted run can be resumed with the next part. The file consists of JSON lines: the first
+//! line is the header, identifying the input file (size and hash of the first bytes) and the
+//! configuration of the run, each further line contains the results of one part.
+
+use crate::json::test_parameters;
+use crate::test_from_name;
+use crate::valid_arg::{InputSource, MaxLengthOrSplit, ValidatedConfig};
+use crate::{ArgBitOrder, InputFormat};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use sts_lib::test_runner::multi_sequence::RecordedResult;
+use sts_lib::{Test, TestResult};
+
+/// The count of bytes at the start of the input file that are hashed to detect changes.
+const HASH_PREFIX_LEN: u64 = 1 << 20;
+
+/// Error type for [Checkpoint]
+#[derive(Debug)]
+pub enum CheckpointError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    /// The input file differs in size or content from the one the checkpoint was created for.
+    InputChanged,
+    /// The checkpoint was created with a different split, format, threshold or test selection.
+    ConfigChanged,
+    /// A saved part contains a test name that is not known.
+    UnknownTest(String),
+    /// The checkpoint file is not a valid checkpoint, e.g. the parts are not consecutive.
+    Invalid(&'static str),
+}
+
+impl Display for CheckpointError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckpointError::Io(e) => write!(f, "IO error: {e}"),
+            CheckpointError::Json(e) => write!(f, "JSON error: {e}"),
+            CheckpointError::InputChanged => write!(
+                f,
+                "The input file changed since the checkpoint was created (size or content differ)"
+            ),
+            CheckpointError::ConfigChanged => write!(
+                f,
+                "The checkpoint was created with a different configuration (split, stride, input \
+                 format, threshold, tests or test arguments)"
+            ),
+            CheckpointError::UnknownTest(test) => write!(f, "Unknown test \"{test}\""),
+            CheckpointError::Invalid(reason) => write!(f, "Invalid checkpoint: {reason}"),
+        }
+    }
+}
+
+impl Error for CheckpointError {}
+
+impl From<std::io::Error> for CheckpointError {
+    fn from(value: std::io::Error) -> Self {
+        CheckpointError::Io(value)
+    }
+}
+
+impl From<serde_json::Error> for CheckpointError {
+    fn from(value: serde_json::Error) -> Self {
+        CheckpointError::Json(value)
+    }
+}
+
+/// The first line of a checkpoint file: identifies the input and the configuration of the run.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct CheckpointHeader {
+    /// The size of the input file in bytes.
+    input_size: u64,
+    /// The FNV-1a hash of the first [HASH_PREFIX_LEN] bytes of the input file, as hex.
+    input_hash: String,
+    input_format: InputFormat,
+    /// Missing in checkpoints of older versions, which only read MSB first.
+    #[serde(default)]
+    bit_order: ArgBitOrder,
+    split_bytes: usize,
+    stride_bytes: usize,
+    threshold: f64,
+    /// The tests that are run on each part, with their arguments.
+    tests: Vec<Value>,
+}
+
+/// One line after the header: the results of one completed part.
+#[derive(Debug, Serialize, Deserialize)]
+struct PartLine {
+    part: u64,
+    passed: bool,
+    results: Vec<SavedResult>,
+}
+
+/// The result of one test in a [PartLine].
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct SavedResult {
+    test: String,
+    #[serde(flatten)]
+    result: SavedStatus,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", tag = "status")]
+enum SavedStatus {
+    PValues { p_values: Vec<f64> },
+    NotApplicable,
+    Error,
+}
+
+/// The results of one part, as recorded in the checkpoint.
+#[derive(Clone, Debug)]
+pub struct RecordedPart {
+    /// If all tests of the part passed (or were not applicable).
+    pub passed: bool,
+    /// The result of each test, in the order the tests were run.
+    pub results: Vec<(Test, RecordedResult)>,
+}
+
+/// A checkpoint file of a run with `--split`.
+///
+/// Opening an existing checkpoint reads all completed parts, after checking that the input file
+/// and the configuration did not change. After each further part, its results are appended with
+/// [Checkpoint::record_part]. A part that was only partially written, because the program was
+/// aborted, is discarded.
+#[derive(Debug)]
+pub struct Checkpoint {
+    file: File,
+    completed: Vec<RecordedPart>,
+}
+
+impl Checkpoint {
+    /// Opens the checkpoint at the given path, or creates it if it does not exist (or is empty).
+    ///
+    /// The config must split an input file. The input file, the split configuration, the threshold
+    /// and the tests to run on each part with their arguments must match those of an existing
+    /// checkpoint.
+    pub fn open<P: AsRef<Path>>(
+        path: P,
+        config: &ValidatedConfig,
+        tests: &[Test],
+    ) -> Result<Self, CheckpointError> {
+        let InputSource::File(input_path) = &config.input else {
+            return Err(CheckpointError::Invalid("the input must be a file"));
+        };
+        let MaxLengthOrSplit::Split {
+            split_bytes,
+            stride_bytes,
+        } = config.max_length_or_split
+        else {
+            return Err(CheckpointError::Invalid("the input must be split"));
+        };
+
+        let (input_size, input_hash) = identify_input(input_path)?;
+        let header = CheckpointHeader {
+            input_size,
+            input_hash,
+            input_format: config.input_format,
+            bit_order: config.bit_order,
+            split_bytes: split_bytes.get(),
+            stride_bytes: stride_bytes.get(),
+            threshold: config.threshold,
+            tests: tests
+                .iter()
+                .map(|&test| {
+                    json!({
+                        "test": test.to_string(),
+                        "parameters": test_parameters(test, &config.test_arguments),
+                    })
+                })
+                .collect(),
+        };
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+
+        let mut reader = BufReader::new(&mut file);
+        let mut line = String::new();
+        // the length of the file up to the last complete line
+        let mut valid_len = reader.read_line(&mut line)? as u64;
+
+        if !line.ends_with('\n') {
+            // new checkpoint (or the header was only partially written)
+            drop(reader);
+            file.set_len(0)?;
+            serde_json::to_writer(&mut file, &header)?;
+            writeln!(file)?;
+            file.sync_data()?;
+
+            return Ok(Self {
+                file,
+                completed: Vec::new(),
+            });
+        }
+
+        let saved_header: CheckpointHeader = serde_json::from_str(&line)?;
+        if saved_header.input_size != header.input_size
+            || saved_header.input_hash != header.input_hash
+        {
+            return Err(CheckpointError::InputChanged);
+        }
+        if saved_header != header {
+            return Err(CheckpointError::ConfigChanged);
+        }
+
+        let mut completed = Vec::new();
+        loop {
+            line.clear();
+            let len = reader.read_line(&mut line)?;
+            // the last line is incomplete if the program was aborted while writing it
+            if !line.ends_with('\n') {
+                break;
+            }
+            valid_len += len as u64;
+
+            let part: PartLine = serde_json::from_str(&line)?;
+            if part.part != completed.len() as u64 + 1 {
+                return Err(CheckpointError::Invalid("the parts are not consecutive"));
+            }
+
+            let results = part
+                .results
+                .into_iter()
+                .map(|saved| {
+                    let test = test_from_name(&saved.test)
+                        .ok_or(CheckpointError::UnknownTest(saved.test))?;
+                    let result = match saved.result {
+                        SavedStatus::PValues { p_values } => RecordedResult::PValues(p_values),
+                        SavedStatus::NotApplicable => RecordedResult::NotApplicable,
+                        SavedStatus::Error => RecordedResult::Error,
+                    };
+                    Ok((test, result))
+                })
+                .collect::<Result<Vec<_>, CheckpointError>>()?;
+
+            completed.push(RecordedPart {
+                passed: part.passed,
+                results,
+            });
+        }
+
+        // remove the incomplete line, if any
+        drop(reader);
+        file.set_len(valid_len)?;
+
+        Ok(Self { file, completed })
+    }
+
+    /// The parts that were completed in previous runs, in order.
+    pub fn completed_parts(&self) -> &[RecordedPart] {
+        &self.completed
+    }
+
+    /// Records the results of the given part, which must be the part after the last completed
+    /// one. The results are written to disk before this function returns.
+    pub fn record_part(
+        &mut self,
+        part: u64,
+        passed: bool,
+        results: &[(Test, RecordedResult)],
+    ) -> Result<(), CheckpointError> {
+        if part != self.completed.len() as u64 + 1 {
+            return Err(CheckpointError::Invalid("the parts are not consecutive"));
+        }
+
+        let line = PartLine {
+            part,
+            passed,
+            results: results
+                .iter()
+                .map(|(test, result)| SavedResult {
+                    test: test.to_string(),
+                    result: match result {
+                        RecordedResult::PValues(p_values) => SavedStatus::PValues {
+                            p_values: p_values.clone(),
+                        },
+                        RecordedResult::NotApplicable => SavedStatus::NotApplicable,
+                        RecordedResult::Error => SavedStatus::Error,
+                    },
+                })
+                .collect(),
+        };
+
+        let mut line = serde_json::to_vec(&line)?;
+        line.push(b'\n');
+        self.file.write_all(&line)?;
+        self.file.sync_data()?;
+
+        self.completed.push(RecordedPart {
+            passed,
+            results: results.to_vec(),
+        });
+        Ok(())
+    }
+}
+
+/// Converts the result of a test run on one sequence to a [RecordedResult], like
+/// [MultiSequenceRunner](sts_lib::test_runner::MultiSequenceRunner) collects it.
+pub fn record_result(result: &Result<Vec<TestResult>, sts_lib::Error>) -> RecordedResult {
+    match result {
+        Ok(results) if results.iter().any(|result| !result.is_applicable()) => {
+            RecordedResult::NotApplicable
+        }
+        Ok(results) => RecordedResult::PValues(results.iter().map(|r| r.p_value()).collect()),
+        Err(_) => RecordedResult::Error,
+    }
+}
+
+/// Returns the size of the input file and the hash of its first [HASH_PREFIX_LEN] bytes, as hex.
+fn identify_input(path: &Path) -> Result<(u64, String), CheckpointError> {
+    let file = File::open(path)?;
+    let size = file.metadata()?.len();
+
+    let mut prefix = Vec::new();
+    file.take(HASH_PREFIX_LEN).read_to_end(&mut prefix)?;
+
+    Ok((size, format!("{:016x}", fnv1a(&prefix))))
+}
+
+/// The 64-bit FNV-1a hash of the given bytes. Only used to detect changes of the input, not for
+/// security.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    })
+}
@@ -1,34 +1,286 @@
 //! Everything necessary for command line arguments.
 
-use crate::{ArgTest, InputFormat};
-use clap::{Args, Parser};
+use crate::{
+    ArgBitOrder, ArgGenerator, ArgProfile, ArgTestSelection, CsvNotation, GeneratedFormat,
+    InputFormat, LogFormat, OutputFormat,
+};
+use clap::{ArgAction, Args, Parser, Subcommand};
+use std::net::SocketAddr;
 use std::num::NonZero;
 use std::path::PathBuf;
 
 /// The command line arguments.
+///
+/// Without a subcommand, the arguments of the subcommand "run" are accepted directly, i.e.
+/// `sts-cmd --input ...` is the same as `sts-cmd run --input ...`.
 #[derive(Debug, Parser)]
 #[command(version, author, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 pub struct CmdArgs {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    #[command(flatten)]
+    pub run_args: RunArgs,
+    #[command(flatten)]
+    pub log_args: LogArgs,
+}
+
+/// The arguments for the console output, valid for all subcommands.
+#[derive(Debug, Clone, Args)]
+pub struct LogArgs {
+    /// Only print the summaries of test runs, warnings and errors.
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+    /// Increase the verbosity of the console output: '-v' additionally prints the duration of
+    /// each test, details about the input and all fields of each event, '-vv' prints all events,
+    /// including those of the library.
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    pub verbose: u8,
+    /// The format of the console output. With "json", every event is printed as one JSON object
+    /// per line, e.g. to be processed by other programs.
+    #[arg(long, global = true, default_value = "text")]
+    pub log_format: LogFormat,
+    /// Only print a single line per tested sequence, for scripted health checks: "PASS" or
+    /// "FAIL", followed by space-separated "key=value" pairs with the count of tests, passed tests,
+    /// failed tests and tests with errors, the lowest P-value and the test it belongs to, e.g.
+    /// "FAIL tests=15 passed=14 failed=1 errors=0 worst-p-value=0.000412 worst-test=Runs". With
+    /// '--split' or multiple input files, the pair "sequence=<IDX>" comes first.
+    ///
+    /// Errors and the alerts of the subcommand "monitor" are still printed.
+    #[arg(long, global = true, conflicts_with_all = ["quiet", "verbose"])]
+    pub summary_only: bool,
+}
+
+/// The subcommands.
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run the tests on the input. This is the default if no subcommand is given.
+    Run(RunArgs),
+    /// Generate a sequence with one of the reference generators of NIST SP 800-22r1a, appendix D.
+    ///
+    /// The generators use the fixed seeds of the NIST reference implementation, so the generated
+    /// sequences can be used to verify this implementation, or as calibration sequences.
+    Generate(GenerateArgs),
+    /// Create the final analysis report over previously saved results.
+    ///
+    /// Each given CSV or JSON output file of the subcommand "run" is one tested sequence, e.g. the
+    /// output files of a run with '--split'. Like after testing multiple parts, the proportion of
+    /// sequences passing each test and the uniformity of the P-values are printed.
+    Report(ReportArgs),
+    /// Continuously monitor a pipe, FIFO or character device, e.g. the output of a hardware RNG.
+    ///
+    /// The input is tested in a sliding window: every <INTERVAL_BITS> bits, the fast tests are run
+    /// on the last <WINDOW_BITS> bits. For every P-value below the threshold, an alert is printed
+    /// as a warning (with '--log-format json', as one JSON line with the target
+    /// "sts_cmd::alert"). If any alert was raised, the exit code is 2.
+    ///
+    /// Note that even for perfectly random data, the proportion of results below the threshold is
+    /// the threshold itself, so occasional alerts are expected.
+    Monitor(MonitorArgs),
+    /// Validate a config file without running any tests.
+    ///
+    /// All problems are printed at once, each with the path of the key in the config file. If the
+    /// config gives a max length, the test arguments are checked against it, e.g. if the block
+    /// length of the serial test is too large for the input length. The command line arguments of
+    /// the subcommand "run" can be given as well, they are applied on top of the config file.
+    CheckConfig(CheckConfigArgs),
+    /// Calculate the pi values of the overlapping template matching test in advance and save
+    /// them to the cache directory.
+    ///
+    /// The accurate pi values (according to Hamano and Kaneko) take time quadratic in the block
+    /// length, e.g. seconds for block lengths of several 10^4. Once cached, later runs with the
+    /// same block length, template length and degrees of freedom load them instead. The values are calculated for each
+    /// combination of the given lengths and degrees of freedom.
+    PrecomputePis(PrecomputePisArgs),
+}
+
+/// The arguments of the subcommand "run".
+#[derive(Debug, Clone, Args)]
+pub struct RunArgs {
     // If an input file is specified, a config file is not needed, but allowed.
     /// Path to an optional configuration file. Required if the input file is not specified.
     ///
     /// The configuration written in the config file can be supplemented by command line switches.
-    #[arg(short, long, required_unless_present = "input_file")]
+    #[arg(short, long, required_unless_present_any = ["input_files", "stdin"])]
     pub config_file: Option<PathBuf>,
     #[command(flatten)]
     pub regular_args: RegularArgs,
+    /// Only print the plan of the test run, without running any test: the tests that would run on
+    /// each sequence and the tests that would be skipped because the input is too short, the
+    /// effective test arguments, and rough estimates of the time and memory needed.
+    ///
+    /// Only the length of the input is determined, for binary input files without reading them.
+    /// With '--stdin', '--max-length' is required. No output files are created.
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Measure the speed of the selected tests on this machine before running them, with a short
+    /// run on generated data (usually less than a second). The estimated time of each sequence
+    /// (and of '--dry-run') is then based on the measured speed, including the parallelization,
+    /// instead of a rough model for a single thread.
+    #[arg(long)]
+    pub calibrate: bool,
+}
+
+/// The arguments of the subcommand "check-config".
+#[derive(Debug, Clone, Args)]
+pub struct CheckConfigArgs {
+    /// Path to the configuration file to validate.
+    pub config_file: PathBuf,
+    #[command(flatten)]
+    pub regular_args: RegularArgs,
+}
+
+/// The arguments of the subcommand "generate".
+#[derive(Debug, Clone, Args)]
+pub struct GenerateArgs {
+    /// The generator to use.
+    #[arg(short, long)]
+    pub generator: ArgGenerator,
+    /// The length of the sequence to generate, in bits.
+    #[arg(short, long)]
+    pub length: NonZero<usize>,
+    /// Path to save the sequence to. If not given or "-", the sequence is written to stdout.
+    #[arg(short, long = "output")]
+    pub output_path: Option<PathBuf>,
+    /// The format of the sequence.
+    #[arg(short, long, default_value = "binary")]
+    pub format: GeneratedFormat,
+}
+
+impl GenerateArgs {
+    /// If the sequence is written to stdout instead of a file.
+    pub fn writes_to_stdout(&self) -> bool {
+        !matches!(&self.output_path, Some(path) if path.as_os_str() != "-")
+    }
+}
+
+/// The arguments of the subcommand "report".
+#[derive(Debug, Clone, Args)]
+pub struct ReportArgs {
+    /// The saved results, one file per tested sequence, or files with multiple parts written with
+    /// '--single-output'.
+    #[arg(required = true)]
+    pub files: Vec<PathBuf>,
+    /// The format of the files. Default: detected by the file extension, files ending with
+    /// ".json" are read as JSON, all other files as CSV.
+    #[arg(short, long)]
+    pub format: Option<OutputFormat>,
+    /// The threshold (alpha) to decide if a test passed: a test passes if its P-Value is at least
+    /// the threshold. Must be between 0 and 1 (exclusive). Default: 0.01.
+    #[arg(long, visible_alias = "alpha")]
+    pub threshold: Option<f64>,
+    /// Additionally save the histograms of the P-values of the final analysis to the given path,
+    /// for plotting their uniformity, see '--histogram' of the test run.
+    #[arg(long)]
+    pub histogram: Option<PathBuf>,
+}
+
+/// The arguments of the subcommand "monitor".
+#[derive(Debug, Clone, Args)]
+pub struct MonitorArgs {
+    /// The pipe, FIFO or character device to read from, e.g. "/dev/hwrng". The path "-" reads from
+    /// stdin.
+    #[arg(long, visible_alias = "input")]
+    pub fifo: PathBuf,
+    /// The input format.
+    #[arg(short = 'f', long, visible_alias = "format", default_value = "binary")]
+    pub input_format: InputFormat,
+    /// The order of the bits in each byte, only valid for the input formats binary and base64.
+    /// Default: msb-first.
+    #[arg(long)]
+    pub bit_order: Option<ArgBitOrder>,
+    /// The length of the sliding window the tests are run on, in bits. Must be whole bytes
+    /// (divisible by 8).
+    #[arg(long)]
+    pub window_bits: NonZero<usize>,
+    /// Run the tests every <INTERVAL_BITS> new bits. Must be whole bytes (divisible by 8).
+    /// Default: window_bits, i.e. the windows do not overlap.
+    ///
+    /// The windows are tested one after another, so the input is not read while testing.
+    #[arg(long)]
+    pub interval_bits: Option<NonZero<usize>>,
+    /// The tests to run on each window. Default: the tests of the profile "fast-screening".
+    /// Tests that need more bits than the window contains are skipped.
+    ///
+    /// The test arguments of the profile "fast-screening" are used. Groups of tests like "fast"
+    /// can be given as well, see '--tests' of the subcommand "run".
+    #[arg(short, long, value_delimiter = ',')]
+    pub tests: Option<Vec<ArgTestSelection>>,
+    /// The threshold (alpha): an alert is raised for each P-Value below the threshold. Must be
+    /// between 0 and 1 (exclusive). Default: 0.01.
+    #[arg(long, visible_alias = "alpha")]
+    pub threshold: Option<f64>,
+    /// Exit with code 2 after the first window that raised an alert, instead of continuing.
+    #[arg(long)]
+    pub exit_on_alert: bool,
+    /// Stop after the given count of windows. Default: run until the input ends.
+    #[arg(long)]
+    pub max_windows: Option<NonZero<u64>>,
+    /// The time in seconds the tests may take on each window, e.g. the time the source needs for
+    /// <INTERVAL_BITS> new bits. The tests are added in the given order as long as their
+    /// estimated duration fits into the budget, all other tests are skipped.
+    #[arg(long)]
+    pub time_budget: Option<f64>,
+    /// Measure the speed of the tests on this machine before monitoring, with a short run on
+    /// generated data, to estimate the duration of the tests on each window more precisely.
+    #[arg(long)]
+    pub calibrate: bool,
+    /// Serve Prometheus metrics on the given address, e.g. "127.0.0.1:9464": the latest P-value
+    /// of each test, the count of failures and errors per test and the count of windows. The
+    /// metrics are available at the path "/metrics".
+    #[arg(long)]
+    pub metrics_addr: Option<SocketAddr>,
+}
+
+/// The arguments of the subcommand "precompute-pis".
+#[derive(Debug, Clone, Args)]
+pub struct PrecomputePisArgs {
+    /// The block lengths M, in bits.
+    #[arg(short, long, value_delimiter = ',', default_value = "1032")]
+    pub block_lengths: Vec<NonZero<usize>>,
+    /// The template lengths m, in bits: 2 <= m <= 21.
+    #[arg(short, long, value_delimiter = ',', default_value = "9")]
+    pub template_lengths: Vec<NonZero<usize>>,
+    /// The degrees of freedom K, at least 3.
+    #[arg(short, long, value_delimiter = ',', default_value = "6")]
+    pub freedoms: Vec<NonZero<usize>>,
+    /// The cache directory. Default: the environment variable "STS_PI_CACHE_DIR", else the
+    /// directory "nist-sts" in the cache directory of the platform, e.g. "~/.cache/nist-sts".
+    #[arg(long)]
+    pub cache_dir: Option<PathBuf>,
 }
 
 /// The "regular" command line arguments (everything except for config file)
 #[derive(Debug, Clone, Args)]
 #[group(required = false, multiple = true)]
 pub struct RegularArgs {
-    /// Path to the input file. Mandatory.
-    #[arg(short, long = "input", requires = "input_format")]
-    pub input_file: Option<PathBuf>,
+    /// Path to the input file. Mandatory, except if '--stdin' is used. The path "-" reads from
+    /// stdin.
+    ///
+    /// Multiple files (e.g. 'capture_*.bin', expanded by the shell) or a directory, whose files
+    /// are tested in alphabetical order, can be given as well. Each file is then tested as a
+    /// separate sequence, followed by a final analysis over all files, like with '--split'. If the
+    /// output path is set, one output file is created per input file, named like the parts of
+    /// '--split', with <IDX> the position of the input file. Cannot be combined with '--split' and
+    /// '--checkpoint'.
+    #[arg(short, long = "input", requires = "input_format", num_args = 1..)]
+    pub input_files: Vec<PathBuf>,
+    /// Read the input from stdin instead of a file, e.g. to test the output of a generator in a
+    /// pipeline: `my_rng | sts-cmd --stdin --format binary --max-length 1000000`.
+    ///
+    /// With '--split', the count of parts cannot be known in advance.
+    #[arg(long, conflicts_with = "input_files", requires = "input_format")]
+    pub stdin: bool,
     /// The input file format. Required if a input file is specified.
-    #[arg(short = 'f', long)]
+    #[arg(short = 'f', long, visible_alias = "format")]
     pub input_format: Option<InputFormat>,
+    /// The order of the bits in each byte, only valid for the input formats binary and base64.
+    /// Default: msb-first.
+    ///
+    /// Some hardware captures pack the bits starting with the LSB. Testing them with the wrong
+    /// bit order silently changes the results of some tests.
+    #[arg(long)]
+    pub bit_order: Option<ArgBitOrder>,
     /// The maximum length of the sequence to test, in bits.
     #[arg(short = 'l', long)]
     pub max_length: Option<NonZero<usize>>,
@@ -38,30 +290,189 @@ pub struct RegularArgs {
     /// If the output path is set, multiple output files with the names 
     /// "<FILE_NAME>_<IDX>.<EXTENSION>" will be created, with <FILE_NAME> denoting the user-provided 
     /// filename, <EXTENSION> the user-provided extension, and <IDX> the index of the tested part, 
-    /// supplied by the application.
+    /// supplied by the application. With '--single-output', all parts are written to one file.
+    /// After all parts are tested, a final analysis over all parts is printed: the proportion of
+    /// parts passing each test and the uniformity of the P-values, as done by the NIST reference
+    /// implementation.
     #[arg(long, requires = "max_length")]
     pub split: bool,
+    /// Use overlapping parts when splitting the input: a new part starts every <STRIDE> bits,
+    /// e.g. '--max-length 1000000 --split --stride 100000' tests windows of 10^6 bits, each
+    /// starting 10^5 bits after the previous one. This allows to find local non-randomness.
+    ///
+    /// Requires the stride to be whole bytes (divisible by 8). Default: max_length, i.e. the parts
+    /// do not overlap. With a stride, <IDX> in the output file names is replaced by the offset of the
+    /// part in the input, in bits. Note that overlapping parts are not independent of each other,
+    /// which the final analysis assumes.
+    #[arg(long, requires = "split")]
+    pub stride: Option<NonZero<usize>>,
+    /// Test <COUNT> windows of max_length bits each, placed at random offsets in the input file,
+    /// instead of only the start of the input. This allows to test extremely long captures with a
+    /// bounded effort.
+    ///
+    /// The windows do not overlap. Their offsets are generated from '--seed', so the same seed
+    /// always tests the same windows of an input of the same length. The offset of each window is
+    /// printed and, if the output path is set, replaces <IDX> in the output file names, like with
+    /// '--stride'. After all windows are tested, the final analysis over all windows is printed.
+    /// Cannot be used with stdin, multiple input files and '--split'.
+    #[arg(
+        long,
+        value_name = "COUNT",
+        requires = "max_length",
+        conflicts_with_all = ["split", "stdin"]
+    )]
+    pub subsample: Option<NonZero<usize>>,
+    /// The seed for the window offsets of '--subsample'. Default: 0.
+    #[arg(long, requires = "subsample")]
+    pub seed: Option<u64>,
+    /// Path to a checkpoint file for '--split', to resume an aborted run.
+    ///
+    /// The results of each tested part are appended to the checkpoint file. If the file already
+    /// exists, the parts recorded in it are not tested again: their results are only used for the
+    /// final analysis, and testing resumes with the next part. The size and the hash of the first
+    /// MiB of the input file, the split and stride, the threshold and the tests with their
+    /// arguments must be the same as when the checkpoint was created. Cannot be used with stdin.
+    #[arg(long, requires = "split", conflicts_with = "stdin")]
+    pub checkpoint: Option<PathBuf>,
     /// Optional path to save the results to. Optional.
     ///
-    /// If given, the results will be saved in CSV format with ';' delimiter and the following columns:
-    /// test name; time in ms; result no.; PASS/FAIL; P-Value; comment
+    /// If given, the results will be saved in CSV format with ';' delimiter (see '--csv-delimiter')
+    /// and the following columns: test name; time in ms; result no.; PASS/FAIL; P-Value; comment
     ///
     /// If a test returns multiple results, test name and time in ms will be the same for all of them.
     /// If a test returns an error, PASS/FAIL will read "ERROR", P-Value will be -1 and comment will
-    /// specify the exact error.
+    /// specify the exact error. If a test is not applicable to the input (e.g. the random excursions
+    /// test with too few cycles), PASS/FAIL will read "N/A". For the linear complexity test, the
+    /// comment contains the histogram of the deviations T_i of the blocks, for the cumulative sums
+    /// test the maximum excursion of the partial sums.
+    ///
+    /// For other output formats, see '--output-format'.
     #[arg(short, long = "output")]
     pub output_path: Option<PathBuf>,
+    /// The format of the output file. Default: csv.
+    ///
+    /// With "json", one JSON document is written per test run, containing the input length, the
+    /// threshold, if all tests passed and, for each test: test name, time in ms, the test arguments
+    /// used, PASS/FAIL/N/A/ERROR, all results (result no., passed, applicable, P-Value, comment,
+    /// test statistics like the maximum excursion of the cumulative sums test) and the error
+    /// message, if any.
+    #[arg(long)]
+    pub output_format: Option<OutputFormat>,
+    /// Write the results of all parts (with '--split', '--subsample' or multiple input files) to
+    /// the output path itself, instead of one file per part.
+    ///
+    /// CSV files get an additional first column "part", JSON files contain one document per line
+    /// (JSON Lines), each with an additional field "part". The part is <IDX> of the file names
+    /// without this option: the index of the part, or its offset in bits with '--stride' or
+    /// '--subsample'. The subcommand "report" reads each part of the file as one sequence.
+    #[arg(long)]
+    pub single_output: bool,
+    /// Append the results to the output file instead of overwriting it, e.g. to resume a run with
+    /// '--checkpoint' or to collect the results of multiple runs. Requires '--single-output'.
+    #[arg(long, requires = "single_output")]
+    pub append_output: bool,
+    /// The delimiter between the columns of the CSV files (the output and '--histogram'), a single
+    /// ASCII character, e.g. ',' for spreadsheets in English locales. Default: ';'.
+    #[arg(long, value_name = "CHAR")]
+    pub csv_delimiter: Option<char>,
+    /// The count of digits after the decimal point of the P-values and times in the CSV files, at
+    /// most 30. Default: as many as needed to read the values back exactly.
+    #[arg(long, value_name = "DIGITS")]
+    pub csv_precision: Option<usize>,
+    /// The notation of the P-values and times in the CSV files. Default: auto.
+    #[arg(long)]
+    pub csv_notation: Option<CsvNotation>,
+    /// Use ',' as the decimal separator in the CSV files instead of '.', e.g. for spreadsheets in
+    /// German or French locales. Requires a delimiter other than ','.
+    #[arg(long)]
+    pub csv_decimal_comma: bool,
+    /// Additionally save the results in the directory layout of the NIST reference implementation
+    /// (the tool "assess"), so that scripts processing its output can be used.
+    ///
+    /// The directory "experiments/AlgorithmTesting" is created inside the given directory, with
+    /// the P-values of each tested part in "<TEST>/results.txt", the verdict of each result in
+    /// "<TEST>/stats.txt" and the final analysis over all parts in "finalAnalysisReport.txt".
+    /// <TEST> is the name used by the reference implementation, e.g. "BlockFrequency" or "FFT".
+    /// Without '--split' or multiple input files, the input is the only tested sequence.
+    #[arg(long, value_name = "DIR")]
+    pub nist_compat: Option<PathBuf>,
+    /// Additionally save a human-readable report of the test run to the given path, as HTML or
+    /// Markdown, depending on the extension (".html", ".htm", ".md" or ".markdown").
+    ///
+    /// The report contains a summary table, the P-values of each test, the test arguments and
+    /// information about the environment. With '--split' or multiple input files, it contains the
+    /// status of each part and the final analysis over all parts, with the histograms of the
+    /// P-values, instead of the P-values.
+    #[arg(long)]
+    pub report: Option<PathBuf>,
+    /// Additionally save the histograms of the P-values of the final analysis over all parts to
+    /// the given path, for plotting their uniformity. Only valid with '--split' or multiple input
+    /// files.
+    ///
+    /// For each result of each test, the count of P-values in each of the 10 bins from 0 to 1 is
+    /// saved, together with the uniformity P-value. Files ending with ".json" are written as JSON,
+    /// all other files as CSV with ';' delimiters.
+    #[arg(long)]
+    pub histogram: Option<PathBuf>,
+    /// A profile with a recommended selection of tests and test arguments, e.g. "nist-default".
+    ///
+    /// The tests of the profile are run, unless '--tests' is given. '--exclude-tests' removes
+    /// tests from the profile. Test arguments from '--overrides' or the config file take
+    /// precedence over the arguments of the profile.
+    ///
+    /// The profile "fips140-quick" runs the FIPS 140-2 tests on the first 20000 bits instead, as a
+    /// quick check. It cannot be combined with '--split', '--subsample', '--tests',
+    /// '--exclude-tests', '--output-path', '--nist-compat', '--report', '--entropy-estimation'
+    /// and '--diagnostics'.
+    #[arg(long)]
+    pub profile: Option<ArgProfile>,
     /// The tests to run: either include specific tests or exclude specific tests, if neither is
     /// set: run all tests.
     #[command(flatten)]
     pub tests_to_run: TestsToRun,
+    /// The threshold (alpha) to decide if a test passed: a test passes if its P-Value is at least
+    /// the threshold. Must be between 0 and 1 (exclusive). Default: 0.01.
+    #[arg(long, visible_alias = "alpha")]
+    pub threshold: Option<f64>,
+    /// The maximum time in seconds a single test may run. A test that takes longer is aborted and
+    /// reported as an error, the other tests are still run. Default: no timeout.
+    #[arg(long)]
+    pub timeout: Option<f64>,
+    /// Additionally estimate the min-entropy per bit of each tested sequence, with the most
+    /// common value and the collision estimators of NIST SP 800-90B, and the Shannon entropy.
+    ///
+    /// The estimates are printed in a separate section after the test results. This is only a
+    /// screening: the full assessment of SP 800-90B uses more estimators and can only give a
+    /// lower estimate. SP 800-90B requires at least 1000000 bits.
+    #[arg(long)]
+    pub entropy_estimation: bool,
+    /// Additionally print diagnostics of each tested sequence, which are not part of NIST SP
+    /// 800-22 and give no P-value: the histogram of the run lengths of zeros and ones, compared
+    /// with the expected counts of a random sequence, and the ones frequency and chi-square
+    /// statistic of each bit position within the bytes, as a heatmap.
+    ///
+    /// The diagnostics are printed in a separate section after the test results, and are also
+    /// written to the output file with '--output-format json'.
+    #[arg(long)]
+    pub diagnostics: bool,
     /// Test argument overrides in TOML format.
     ///
     /// Use the same format as the config file, key 'arguments' is implied.
     /// e.g. 'serial.block-length = 3'.
     #[arg(long, value_delimiter = ',')]
     pub overrides: Option<Vec<String>>,
+    /// Path to a file with custom templates for the non-overlapping template matching test,
+    /// instead of all aperiodic templates of the template length.
+    ///
+    /// The file contains one template per line, as a string of '0' and '1' - whitespace is
+    /// ignored, so the template files of the NIST reference implementation can be used. Empty
+    /// lines and lines starting with '#' are skipped. All templates must be aperiodic and have the
+    /// template length, which is set with 'non-overlapping-template-matching.template-length'
+    /// (default: 9). Same as 'non-overlapping-template-matching.template-file' in the overrides.
+    #[arg(long)]
+    pub template_file: Option<PathBuf>,
     /// Reduce the console output to only test run summaries (either all tests passed or not).
+    /// Unlike '--quiet', the progress is still printed.
     #[arg(long)]
     pub no_console: bool,
 }
@@ -72,15 +483,22 @@ pub struct RegularArgs {
 pub struct TestsToRun {
     /// Run only the specified tests.
     ///
+    /// Instead of single tests, groups of tests can be given: "fast" (all tests whose runtime
+    /// grows linearly with the input length), "slow" (all other tests) and "requires-1e6-bits"
+    /// (all tests requiring at least 10^6 bits), e.g. '--tests fast,linear-complexity'.
+    ///
     /// If neither this option nor '--exclude-tests' is specified, all tests are run, except
-    /// for those whose input length requirements are not satisfied.
+    /// for those whose input length requirements are not satisfied. The Lempel-Ziv compression
+    /// test, which is not part of the current revision of SP 800-22, is only run if it is
+    /// specified here.
     #[arg(short, long, value_delimiter = ',')]
-    pub tests: Option<Vec<ArgTest>>,
-    /// Run all available tests except for the excluded tests.
-    /// Tests whose input length requirements are not satisfied, are skipped.
+    pub tests: Option<Vec<ArgTestSelection>>,
+    /// Run all available tests except for the excluded tests and the Lempel-Ziv compression test.
+    /// Tests whose input length requirements are not satisfied, are skipped. Groups of tests can
+    /// be given as well, see '--tests', e.g. '--exclude-tests slow'.
     ///
     /// If neither this option nor '--tests' is specified, all tests are run, except
     /// for those whose input length requirements are not satisfied.
     #[arg(short, long, value_delimiter = ',')]
-    pub exclude_tests: Option<Vec<ArgTest>>,
+    pub exclude_tests: Option<Vec<ArgTestSelection>>,
 }
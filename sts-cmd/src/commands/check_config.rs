@@ -0,0 +1,28 @@
+//! The subcommand "check-config", see [check_config].
+
+use super::parse_config;
+use crate::cmd_args::CheckConfigArgs;
+use crate::logging::SUMMARY;
+use tracing::{info, warn};
+
+/// Validates the config file (with the command line arguments) without running any tests.
+///
+/// If the length of the input is known from the config, the test arguments are checked against
+/// it as well.
+pub fn check_config(args: CheckConfigArgs) -> anyhow::Result<()> {
+    let config = parse_config(Some(&args.config_file), args.regular_args)?;
+
+    match config.declared_len_bit() {
+        Some(len_bit) => config.validate_against_input_len(len_bit)?,
+        None => warn!(
+            "No max-length is given, the test arguments cannot be checked against the input length."
+        ),
+    }
+
+    info!(
+        target: SUMMARY,
+        "The configuration \"{}\" is valid.",
+        args.config_file.display()
+    );
+    Ok(())
+}
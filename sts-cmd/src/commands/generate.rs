@@ -0,0 +1,56 @@
+//! The subcommand "generate", see [generate].
+
+use crate::cmd_args::GenerateArgs;
+use crate::GeneratedFormat;
+use anyhow::Context;
+use std::fs;
+use std::io;
+use std::io::Write;
+use sts_lib::generators::Generator;
+use tracing::info;
+
+/// Generates a sequence with a reference generator and writes it to the output.
+pub fn generate(args: GenerateArgs) -> anyhow::Result<()> {
+    let generator = Generator::from(args.generator);
+    let data = generator.generate(args.length.get());
+
+    let output = match args.format {
+        GeneratedFormat::Binary => {
+            let (mut bytes, rest) = data.to_bytes();
+            bytes.extend(rest);
+            bytes
+        }
+        GeneratedFormat::Ascii => {
+            let (bytes, rest) = data.to_bytes();
+            let mut output = bytes
+                .iter()
+                .map(|byte| format!("{byte:08b}"))
+                .collect::<String>();
+            if let Some(rest) = rest {
+                output.push_str(&format!("{rest:08b}")[..data.len_bit() % 8]);
+            }
+            output.push('\n');
+            output.into_bytes()
+        }
+    };
+
+    match args.output_path {
+        Some(path) if !args.writes_to_stdout() => {
+            fs::write(&path, output)
+                .with_context(|| format!("Failed to write to \"{}\"", path.display()))?;
+            info!(
+                %generator,
+                length = data.len_bit(),
+                path = %path.display(),
+                "Generated {} bits with the generator \"{generator}\" into \"{}\".",
+                data.len_bit(),
+                path.display()
+            );
+        }
+        _ => io::stdout()
+            .write_all(&output)
+            .context("Failed to write to stdout")?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,197 @@
+//! The subcommands of this program, each in its own module, with the helpers they share.
+
+use crate::cmd_args::RegularArgs;
+use crate::csv::CsvOptions;
+use crate::logging::SUMMARY;
+use crate::toml_config::TomlConfig;
+use crate::valid_arg::ValidatedConfig;
+use crate::OutputFormat;
+use anyhow::Context;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use sts_lib::test_runner::multi_sequence::FinalAnalysis;
+use sts_lib::test_runner::{Calibration, MultiSequenceRunner};
+use sts_lib::{test_runner, Test, TestArgs};
+use tracing::{debug, info};
+
+pub mod check_config;
+pub mod generate;
+pub mod monitor;
+mod part_reader;
+pub mod precompute_pis;
+pub mod report;
+pub mod run;
+
+/// Runs the self-test of the library before any tests are run: no result can be trusted if the
+/// library does not work correctly on this machine.
+fn self_test() -> anyhow::Result<()> {
+    sts_lib::self_test().context("The self-test of the library failed")
+}
+
+/// Measures the speed of the given tests before the tests are run, see [Calibration::run].
+fn calibrate_tests(tests: impl IntoIterator<Item = Test>, test_args: TestArgs) -> Calibration {
+    info!("Calibrating the tests...");
+    let begin = Instant::now();
+    let calibration = Calibration::run(tests, test_args);
+    let time = begin.elapsed();
+    debug!(
+        time_ms = time.as_secs_f64() * 1000.0,
+        "Calibration finished in {}",
+        format_duration(time)
+    );
+    calibration
+}
+
+/// The estimated duration of the test: measured on this machine if a calibration is given, else
+/// the rough estimate for a single thread, see [test_runner::estimate_duration].
+fn estimate_duration(
+    calibration: Option<&Calibration>,
+    test: Test,
+    len_bit: usize,
+    test_args: &TestArgs,
+) -> Duration {
+    match calibration {
+        Some(calibration) => calibration.estimate_duration(test, len_bit, test_args),
+        None => test_runner::estimate_duration(test, len_bit, test_args),
+    }
+}
+
+/// The note after an estimated duration without a calibration, which is only valid for a single
+/// thread.
+fn single_thread_note(calibration: Option<&Calibration>) -> &'static str {
+    match calibration {
+        Some(_) => "",
+        None => " on a single thread (more threads are usually faster, see '--calibrate')",
+    }
+}
+
+/// Formats a duration for the console output, in ms, s or min.
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs_f64();
+    if secs < 1.0 {
+        format!("{:.1} ms", secs * 1000.0)
+    } else if secs < 120.0 {
+        format!("{secs:.1} s")
+    } else {
+        format!("{:.1} min", secs / 60.0)
+    }
+}
+
+/// Parses and validates the configuration, from the config file (if given) and the command line
+/// arguments.
+fn parse_config(
+    config_file: Option<&Path>,
+    regular_args: RegularArgs,
+) -> anyhow::Result<ValidatedConfig> {
+    let config = if let Some(config_file) = config_file {
+        let toml = fs::read_to_string(config_file)
+            .with_context(|| format!("Failed to read config file \"{}\"", config_file.display()))?;
+
+        let toml_config: TomlConfig =
+            toml::from_str(&toml).context("Failed to parse the config file")?;
+        ValidatedConfig::try_from_toml(toml_config, regular_args)
+    } else {
+        ValidatedConfig::try_from_cmd_args(regular_args)
+    }?;
+
+    Ok(config)
+}
+
+/// Writes the histograms of the P-values of the final analysis in the given format, see
+/// [crate::csv::write_histograms] and [crate::json::write_histograms].
+fn write_histograms(
+    path: &Path,
+    format: OutputFormat,
+    csv_options: &CsvOptions,
+    analyses: &[FinalAnalysis],
+) -> anyhow::Result<()> {
+    match format {
+        OutputFormat::Csv => {
+            crate::csv::write_histograms(path, analyses, csv_options).map_err(anyhow::Error::from)
+        }
+        OutputFormat::Json => {
+            crate::json::write_histograms(path, analyses).map_err(anyhow::Error::from)
+        }
+    }
+    .with_context(|| format!("Failed to write the histograms to \"{}\"", path.display()))
+}
+
+/// Print the final analysis over all parts and the verdict per test, see [MultiSequenceRunner].
+fn print_final_analysis(multi_runner: &MultiSequenceRunner, threshold: f64) -> anyhow::Result<()> {
+    info!(target: SUMMARY, "\nFinal analysis over all parts: ");
+
+    for analysis in multi_runner.final_analysis(threshold)? {
+        let test = analysis.test();
+        let start_str = match analysis.comment() {
+            Some(comment) => format!("Test {test} - Result {} ({comment})", analysis.result_idx()),
+            None => format!("Test {test} - Result {}", analysis.result_idx()),
+        };
+
+        let (min, max) = analysis.proportion_range();
+        let proportion_passed = if analysis.proportion_passed() {
+            "PASSED"
+        } else {
+            "FAILED"
+        };
+        let uniformity = match analysis.uniformity_p_value() {
+            Some(p_value) => {
+                let passed = if analysis.uniformity_passed() {
+                    "PASSED"
+                } else {
+                    "FAILED"
+                };
+                format!("{passed}. P-Value: {p_value}")
+            }
+            None => "-".to_owned(),
+        };
+
+        let mut message = format!(
+            "\t{start_str}:\n\
+             \t\tProportion: {proportion_passed}. {} / {} passed (acceptable: {min:.6} - {max:.6})\n\
+             \t\tUniformity: {uniformity}. Histogram: {:?}",
+            analysis.passed_count(),
+            analysis.sample_size(),
+            analysis.histogram(),
+        );
+        if analysis.error_count() > 0 {
+            message.push_str(&format!("\n\t\tErrors: {}", analysis.error_count()));
+        }
+        if analysis.not_applicable_count() > 0 {
+            message.push_str(&format!(
+                "\n\t\tNot applicable: {}",
+                analysis.not_applicable_count()
+            ));
+        }
+
+        info!(
+            target: SUMMARY,
+            %test,
+            result_idx = analysis.result_idx(),
+            passed_count = analysis.passed_count(),
+            sample_size = analysis.sample_size(),
+            proportion_passed = analysis.proportion_passed(),
+            uniformity_p_value = analysis.uniformity_p_value(),
+            uniformity_passed = analysis.uniformity_passed(),
+            error_count = analysis.error_count(),
+            not_applicable_count = analysis.not_applicable_count(),
+            "{message}"
+        );
+    }
+
+    // the proportion of passed parts must lie within the acceptance interval for all results
+    info!(target: SUMMARY, "\nVerdict per test: ");
+
+    for verdict in multi_runner.verdicts(threshold) {
+        let test = verdict.test();
+        let passed = if verdict.passed() { "PASSED" } else { "FAILED" };
+        info!(
+            target: SUMMARY,
+            %test,
+            passed = verdict.passed(),
+            "\tTest {test}: {passed}"
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,207 @@
+//! The subcommand "monitor", see [monitor].
+
+use super::part_reader::PartReader;
+use super::{calibrate_tests, estimate_duration, format_duration, self_test, single_thread_note};
+use crate::cmd_args::MonitorArgs;
+use crate::logging::ALERT;
+use crate::metrics::Metrics;
+use crate::valid_arg::{handle_bit_order, handle_threshold, InputSource};
+use crate::ArgTestSelection;
+use anyhow::Context;
+use std::fs;
+use std::io;
+use std::io::{BufRead, BufReader};
+use std::num::NonZero;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+use sts_lib::profile::Profile;
+use sts_lib::test_runner;
+use tracing::{error, info, info_span, warn};
+
+/// The exit code if the subcommand "monitor" raised an alert.
+pub const ALERT_EXIT_CODE: u8 = 2;
+
+/// Monitors the input continuously: the tests are run on a sliding window, an alert is raised for
+/// each P-value below the threshold.
+///
+/// Returns [ALERT_EXIT_CODE] if any alert was raised.
+pub fn monitor(args: MonitorArgs) -> anyhow::Result<ExitCode> {
+    self_test()?;
+
+    let threshold = handle_threshold(args.threshold).map_err(|err| anyhow::anyhow!(err))?;
+    let bit_order =
+        handle_bit_order(args.bit_order, args.input_format).map_err(|err| anyhow::anyhow!(err))?;
+    let window_bits = args.window_bits.get();
+    let interval_bits = args.interval_bits.map_or(window_bits, NonZero::get);
+    anyhow::ensure!(
+        window_bits % 8 == 0 && interval_bits % 8 == 0,
+        "window_bits and interval_bits must denote full bytes (be divisible by 8)"
+    );
+    let time_budget = match args.time_budget {
+        Some(time_budget) => Some(
+            Duration::try_from_secs_f64(time_budget)
+                .ok()
+                .filter(|time_budget| !time_budget.is_zero())
+                .context("time_budget must be a positive count of seconds")?,
+        ),
+        None => None,
+    };
+
+    let profile = Profile::FastScreening;
+    let tests = match args.tests {
+        Some(tests) => ArgTestSelection::expand(tests),
+        None => profile.tests().to_vec(),
+    };
+    let mut tests = tests
+        .into_iter()
+        .filter(|&test| {
+            match test_runner::validate_tests(window_bits, [test], profile.test_args()) {
+                Ok(()) => true,
+                Err(e) => {
+                    warn!(%test, "Skipping test {test}: cannot be run on the window. {e}");
+                    false
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let calibration = args
+        .calibrate
+        .then(|| calibrate_tests(tests.iter().copied(), profile.test_args()));
+    // the tests are added in the given order, as long as they fit into the time budget
+    let mut estimated = Duration::ZERO;
+    tests.retain(|&test| {
+        let test_args = profile.test_args();
+        let estimate = estimate_duration(calibration.as_ref(), test, window_bits, &test_args);
+        if time_budget.is_some_and(|time_budget| estimated + estimate > time_budget) {
+            warn!(
+                %test,
+                estimated_ms = estimate.as_secs_f64() * 1000.0,
+                "Skipping test {test}: its estimated duration of {} exceeds the time budget.",
+                format_duration(estimate)
+            );
+            return false;
+        }
+        estimated += estimate;
+        true
+    });
+    anyhow::ensure!(!tests.is_empty(), "No test can be run on the window");
+
+    let source = InputSource::from(args.fifo);
+    let reader: Box<dyn BufRead> = match &source {
+        InputSource::File(path) => {
+            let file = fs::File::open(path).context("Failed to open input")?;
+            Box::new(BufReader::new(file))
+        }
+        InputSource::Stdin => Box::new(io::stdin().lock()),
+        InputSource::Files(_) => unreachable!("a single path is never multiple files"),
+    };
+    let mut part_reader = PartReader::new(
+        reader,
+        args.input_format.into(),
+        bit_order.into(),
+        window_bits / 8,
+        interval_bits / 8,
+    );
+
+    info!(
+        input = %source,
+        window_bits,
+        interval_bits,
+        "Monitoring {source}: testing {window_bits} bits every {interval_bits} bits.\n"
+    );
+    info!(
+        estimated_ms = estimated.as_secs_f64() * 1000.0,
+        "Estimated time per window: {}{}\n",
+        format_duration(estimated),
+        single_thread_note(calibration.as_ref())
+    );
+
+    let metrics = match args.metrics_addr {
+        Some(addr) => {
+            let metrics = Arc::new(Metrics::new(&tests, window_bits, interval_bits, threshold));
+            let addr = Arc::clone(&metrics)
+                .serve(addr)
+                .with_context(|| format!("Failed to serve the metrics on {addr}"))?;
+            info!(%addr, "Serving Prometheus metrics on http://{addr}/metrics\n");
+            Some(metrics)
+        }
+        None => None,
+    };
+
+    let mut alerted = false;
+    let mut window = 0_u64;
+
+    while let Some(input) = part_reader.next_part()? {
+        window += 1;
+        let offset = (window - 1) * interval_bits as u64;
+        let _span = info_span!("window", window, offset).entered();
+
+        let mut alerts = 0_usize;
+        let runner = test_runner::run_tests(&input, tests.iter().copied(), profile.test_args())?;
+        for (test, result) in runner {
+            let results = match result {
+                Ok(results) => results,
+                Err(e) => {
+                    error!(%test, error = %e, "Window {window}: test {test}: ERROR: {e}");
+                    if let Some(metrics) = &metrics {
+                        metrics.record_error(test);
+                    }
+                    continue;
+                }
+            };
+            if let Some(metrics) = &metrics {
+                metrics.record_results(test, &results);
+            }
+
+            for (result_idx, result) in results.iter().enumerate() {
+                if result.is_applicable() && !result.passed(threshold) {
+                    alerts += 1;
+                    warn!(
+                        target: ALERT,
+                        window,
+                        offset,
+                        %test,
+                        result_idx,
+                        p_value = result.p_value(),
+                        threshold,
+                        "ALERT: window {window} (offset: {offset} bits): test {test}, result \
+                         {result_idx}: P-Value {} is below the threshold {threshold}",
+                        result.p_value()
+                    );
+                }
+            }
+        }
+
+        if let Some(metrics) = &metrics {
+            metrics.record_window(alerts);
+        }
+        if alerts == 0 {
+            info!(
+                alerts,
+                "Window {window} (offset: {offset} bits): all tests passed"
+            );
+        } else {
+            alerted = true;
+            if args.exit_on_alert {
+                return Ok(ExitCode::from(ALERT_EXIT_CODE));
+            }
+        }
+
+        if args.max_windows.is_some_and(|max| window >= max.get()) {
+            break;
+        }
+    }
+
+    info!(
+        windows = window,
+        "Stopped monitoring after {window} windows."
+    );
+
+    if alerted {
+        Ok(ExitCode::from(ALERT_EXIT_CODE))
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
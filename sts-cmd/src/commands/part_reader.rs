@@ -0,0 +1,207 @@
+//! Reading the input in parts, see [PartReader].
+
+use anyhow::Context;
+use std::collections::VecDeque;
+use std::io;
+use std::io::{BufRead, Read};
+use sts_lib::bitvec::base64::Base64Reader;
+use sts_lib::bitvec::builder::{BitVecBuilder, ReaderFormat};
+use sts_lib::bitvec::{BitOrder, BitVec};
+
+/// Reads the input part by part for [MaxLengthOrSplit::Split]. If the stride is smaller than the
+/// part length, the parts overlap and the overlapping bytes are kept between the parts. For
+/// [MaxLengthOrSplit::Subsample], the windows are read with [PartReader::read_window].
+pub(super) struct PartReader<R> {
+    reader: R,
+    format: ReaderFormat,
+    bit_order: BitOrder,
+    split_bytes: usize,
+    stride_bytes: usize,
+    // the current part, only used if the stride differs from the part length.
+    window: VecDeque<u8>,
+    // if the first part was already read
+    started: bool,
+}
+
+/// The position in the input of [PartReader::read_window].
+#[derive(Debug, Default)]
+pub(super) struct WindowCursor {
+    /// The count of bytes already read.
+    position_bytes: u64,
+    /// The last byte read, the next window may start in it.
+    last_byte: u8,
+}
+
+impl<'a> PartReader<Box<dyn BufRead + 'a>> {
+    /// Creates a reader for parts of `split_bytes` bytes, starting every `stride_bytes` bytes.
+    /// Base64 is decoded continuously over all parts, so that no data is lost between them.
+    pub(super) fn new(
+        reader: impl BufRead + 'a,
+        format: ReaderFormat,
+        bit_order: BitOrder,
+        split_bytes: usize,
+        stride_bytes: usize,
+    ) -> Self {
+        let (reader, format): (Box<dyn BufRead + 'a>, _) = match format {
+            ReaderFormat::Base64 => (Box::new(Base64Reader::new(reader)), ReaderFormat::Binary),
+            format => (Box::new(reader), format),
+        };
+
+        Self {
+            reader,
+            format,
+            bit_order,
+            split_bytes,
+            stride_bytes,
+            window: VecDeque::new(),
+            started: false,
+        }
+    }
+}
+
+impl<R: BufRead> PartReader<R> {
+    /// Returns the next part, or `None` if the input has fewer than the needed bytes left.
+    pub(super) fn next_part(&mut self) -> anyhow::Result<Option<BitVec<'static>>> {
+        if self.stride_bytes == self.split_bytes {
+            // disjoint parts: no need to buffer anything
+            return self.read_bits(self.split_bytes);
+        }
+
+        if !self.started {
+            self.started = true;
+            let Some(part) = self.read_bytes(self.split_bytes)? else {
+                return Ok(None);
+            };
+            self.window.extend(part);
+        } else if self.stride_bytes < self.split_bytes {
+            // keep the overlapping bytes, read the rest
+            let Some(new_bytes) = self.read_bytes(self.stride_bytes)? else {
+                return Ok(None);
+            };
+            self.window.drain(..self.stride_bytes);
+            self.window.extend(new_bytes);
+        } else {
+            // skip the bytes between the parts
+            let skip_bytes = self.stride_bytes - self.split_bytes;
+            if self.read_bits(skip_bytes)?.is_none() {
+                return Ok(None);
+            }
+            let Some(part) = self.read_bytes(self.split_bytes)? else {
+                return Ok(None);
+            };
+            self.window.clear();
+            self.window.extend(part);
+        }
+
+        Ok(Some(BitVec::from(&*self.window.make_contiguous())))
+    }
+
+    /// Skips the given count of parts without testing them, e.g. because they were already tested
+    /// in a previous run. Returns an error if the input has fewer parts.
+    pub(super) fn skip_parts(&mut self, count: u64) -> anyhow::Result<()> {
+        if count == 0 {
+            return Ok(());
+        }
+
+        if self.format == ReaderFormat::Binary {
+            // no decoding necessary: skip the bytes directly, the next part starts after them.
+            let skip_bytes = (self.stride_bytes as u64)
+                .checked_mul(count)
+                .context("Split size is too large")?;
+            anyhow::ensure!(
+                self.skip_bytes(skip_bytes)?,
+                "The input has fewer parts than recorded in the checkpoint"
+            );
+            return Ok(());
+        }
+
+        for _ in 0..count {
+            anyhow::ensure!(
+                self.next_part()?.is_some(),
+                "The input has fewer parts than recorded in the checkpoint"
+            );
+        }
+        Ok(())
+    }
+
+    /// Skips `count_bytes` bytes (after decoding the format). Returns `false` if the input has
+    /// fewer bytes left.
+    fn skip_bytes(&mut self, count_bytes: u64) -> anyhow::Result<bool> {
+        if self.format == ReaderFormat::Binary {
+            let skipped = io::copy(&mut self.reader.by_ref().take(count_bytes), &mut io::sink())
+                .context("Failed to read input")?;
+            return Ok(skipped == count_bytes);
+        }
+
+        // decode the skipped bytes in chunks, to keep the memory usage bounded.
+        const CHUNK_BYTES: u64 = 1 << 20;
+        let mut remaining = count_bytes;
+        while remaining > 0 {
+            let chunk = remaining.min(CHUNK_BYTES);
+            if self.read_bits(chunk as usize)?.is_none() {
+                return Ok(false);
+            }
+            remaining -= chunk;
+        }
+        Ok(true)
+    }
+
+    /// Reads the window of `len_bits` bits starting at `offset_bits` in the input, for
+    /// [MaxLengthOrSplit::Subsample]. The windows must be read in ascending order and must not
+    /// overlap, the cursor keeps track of the position in the input. Returns `None` if the input
+    /// is too short.
+    pub(super) fn read_window(
+        &mut self,
+        cursor: &mut WindowCursor,
+        offset_bits: u64,
+        len_bits: usize,
+    ) -> anyhow::Result<Option<BitVec<'static>>> {
+        // only whole bytes can be read: read all bytes containing the window, then slice it.
+        let start_byte = offset_bits / 8;
+        let end_byte = (offset_bits + len_bits as u64).div_ceil(8);
+
+        let mut bytes = Vec::with_capacity((end_byte - start_byte) as usize);
+        if start_byte < cursor.position_bytes {
+            // the window starts in the last byte of the previous window
+            bytes.push(cursor.last_byte);
+        } else if !self.skip_bytes(start_byte - cursor.position_bytes)? {
+            return Ok(None);
+        }
+        let read_bytes = end_byte - start_byte - bytes.len() as u64;
+        let Some(new_bytes) = self.read_bytes(read_bytes as usize)? else {
+            return Ok(None);
+        };
+        bytes.extend(new_bytes);
+
+        cursor.position_bytes = end_byte;
+        cursor.last_byte = bytes.last().copied().unwrap_or_default();
+
+        let start_bit = (offset_bits % 8) as usize;
+        Ok(Some(
+            BitVec::from(bytes).slice(start_bit..start_bit + len_bits),
+        ))
+    }
+
+    /// Reads exactly `count_bytes` bytes (after decoding the format) into a [BitVec], or returns
+    /// `None` if the input has fewer bytes left.
+    fn read_bits(&mut self, count_bytes: usize) -> anyhow::Result<Option<BitVec<'static>>> {
+        let count_bits = count_bytes
+            .checked_mul(8)
+            .context("Split size is too large")?;
+
+        let mut builder = BitVecBuilder::with_max_length(count_bits);
+        builder.set_bit_order(self.bit_order);
+        builder
+            .extend_from_reader(&mut self.reader, self.format)
+            .context("Failed to read input")?;
+
+        Ok(builder.is_full().then(|| builder.build()))
+    }
+
+    /// Same as [Self::read_bits], but returns the raw bytes (in MSB first order).
+    fn read_bytes(&mut self, count_bytes: usize) -> anyhow::Result<Option<Vec<u8>>> {
+        let bits = self.read_bits(count_bytes)?;
+        // the BitVec contains only full bytes
+        Ok(bits.map(|bits| bits.to_bytes().0))
+    }
+}
@@ -0,0 +1,53 @@
+//! The subcommand "precompute-pis", see [precompute_pis].
+
+use crate::cmd_args::PrecomputePisArgs;
+use anyhow::Context;
+use std::time::Instant;
+use sts_lib::tests::template_matching::overlapping::OverlappingTemplateTestArgs;
+use sts_lib::tests::template_matching::pi_cache;
+use tracing::info;
+
+/// Calculates the pi values of the overlapping template matching test for each combination of the
+/// given arguments and saves them to the cache directory.
+pub fn precompute_pis(args: PrecomputePisArgs) -> anyhow::Result<()> {
+    if let Some(dir) = args.cache_dir {
+        pi_cache::set_pi_cache_dir(dir);
+    }
+    let dir = pi_cache::cache_dir().context("No cache directory is known, use --cache-dir")?;
+    info!(dir = %dir.display(), "Saving the pi values to \"{}\".", dir.display());
+
+    for &block_length in &args.block_lengths {
+        for &template_length in &args.template_lengths {
+            for &freedom in &args.freedoms {
+                let (block_length, template_length, freedom) =
+                    (block_length.get(), template_length.get(), freedom.get());
+                let arg =
+                    OverlappingTemplateTestArgs::try_new(template_length, block_length, freedom)?;
+
+                let start = Instant::now();
+                let calculated = pi_cache::precompute_pis(arg).with_context(|| {
+                    format!("Failed to precompute the pi values for M = {block_length}, m = {template_length}, K = {freedom}")
+                })?;
+
+                if calculated {
+                    info!(
+                        block_length,
+                        template_length,
+                        freedom,
+                        duration_ms = start.elapsed().as_millis(),
+                        "Calculated the pi values for M = {block_length}, m = {template_length}, K = {freedom}."
+                    );
+                } else {
+                    info!(
+                        block_length,
+                        template_length,
+                        freedom,
+                        "The pi values for M = {block_length}, m = {template_length}, K = {freedom} are already cached."
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
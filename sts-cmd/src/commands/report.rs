@@ -0,0 +1,58 @@
+//! The subcommand "report", see [report].
+
+use super::{print_final_analysis, write_histograms};
+use crate::cmd_args::ReportArgs;
+use crate::csv::CsvOptions;
+use crate::valid_arg::handle_threshold;
+use crate::OutputFormat;
+use anyhow::Context;
+use std::collections::HashSet;
+use sts_lib::test_runner::MultiSequenceRunner;
+use sts_lib::TestArgs;
+use tracing::info;
+
+/// Creates the final analysis report over the saved results, each file being one sequence (or
+/// multiple, if written with '--single-output').
+pub fn report(args: ReportArgs) -> anyhow::Result<()> {
+    let threshold = handle_threshold(args.threshold).map_err(|err| anyhow::anyhow!(err))?;
+
+    let mut sequences = Vec::with_capacity(args.files.len());
+    for path in &args.files {
+        let format = args.format.unwrap_or_else(|| OutputFormat::from_path(path));
+
+        let results = match format {
+            OutputFormat::Csv => crate::csv::read_sequences(path).map_err(anyhow::Error::from),
+            OutputFormat::Json => crate::json::read_sequences(path).map_err(anyhow::Error::from),
+        }
+        .with_context(|| format!("Failed to read the results in \"{}\"", path.display()))?;
+
+        sequences.extend(results);
+    }
+
+    let tests = sequences
+        .iter()
+        .flatten()
+        .map(|(test, _)| *test)
+        .collect::<HashSet<_>>();
+
+    // the test arguments are irrelevant, no tests are run
+    let mut multi_runner = MultiSequenceRunner::new(tests.into_iter(), TestArgs::default())?;
+    for (test, result) in sequences.iter().flatten() {
+        multi_runner.add_recorded(*test, result);
+    }
+
+    info!(
+        sequences = sequences.len(),
+        "Read the results of {} sequences.",
+        sequences.len()
+    );
+    print_final_analysis(&multi_runner, threshold)?;
+
+    if let Some(path) = &args.histogram {
+        let analyses = multi_runner.final_analysis(threshold)?;
+        let format = OutputFormat::from_path(path);
+        write_histograms(path, format, &CsvOptions::default(), &analyses)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,162 @@
+//! The optional diagnostic sections of the console output: entropy estimates, run length
+//! histogram and bit position bias.
+
+use crate::logging::SUMMARY;
+use sts_lib::bitvec::BitVec;
+use sts_lib::diagnostics::BitPositionBias;
+use sts_lib::entropy_estimation;
+use sts_lib::tests::run_length_histogram::RunLengthHistogram;
+use tracing::{info, warn};
+
+/// The heat levels of [print_bit_position_bias]: the character of a bit position whose chi-square
+/// statistic (1 degree of freedom) is at least the critical value, and the significance level of
+/// the critical value. Positions below all levels are shown as a space.
+const HEAT_LEVELS: [(f64, char, &str); 4] = [
+    (10.828, '#', "0.001"),
+    (6.635, '*', "0.01"),
+    (3.841, ':', "0.05"),
+    (2.706, '.', "0.1"),
+];
+
+/// Estimates the entropy of the input, see [entropy_estimation], and prints the estimates as a
+/// separate section.
+pub(super) fn print_entropy_estimates(input: &BitVec) {
+    if input.len_bit() < entropy_estimation::RECOMMENDED_LENGTH.get() {
+        warn!(
+            input_length = input.len_bit(),
+            "\tThe input has {} bits, the entropy estimation of SP 800-90B requires at least {} bits.",
+            input.len_bit(),
+            entropy_estimation::RECOMMENDED_LENGTH
+        );
+    }
+
+    let estimates = match entropy_estimation::estimate_entropy(input) {
+        Ok(estimates) => estimates,
+        Err(e) => {
+            warn!(error = %e, "\tEntropy estimation: ERROR: {e}");
+            return;
+        }
+    };
+
+    let mcv = estimates.most_common_value();
+    let collision = estimates.collision();
+    info!(
+        target: SUMMARY,
+        shannon_entropy = estimates.shannon_entropy(),
+        mcv_min_entropy = mcv.min_entropy(),
+        mcv_probability_bound = mcv.probability_bound(),
+        collision_min_entropy = collision.min_entropy(),
+        collision_probability_bound = collision.probability_bound(),
+        min_entropy = estimates.min_entropy(),
+        "\tEntropy estimation (SP 800-90B, bits per bit):\n\
+        \t- Shannon entropy: {:.6}\n\
+        \t- Most common value: min-entropy {:.6} (p <= {:.6})\n\
+        \t- Collision: min-entropy {:.6} (p <= {:.6})\n\
+        \t- Min-entropy: {:.6}",
+        estimates.shannon_entropy(),
+        mcv.min_entropy(),
+        mcv.probability_bound(),
+        collision.min_entropy(),
+        collision.probability_bound(),
+        estimates.min_entropy()
+    );
+}
+
+/// Prints the observed and expected counts of the run lengths of zeros and ones as a separate
+/// section, see [run_length_histogram].
+pub(super) fn print_run_length_histogram(histogram: &Result<RunLengthHistogram, sts_lib::Error>) {
+    let histogram = match histogram {
+        Ok(histogram) => histogram,
+        Err(e) => {
+            warn!(error = %e, "\tRun-length histogram: ERROR: {e}");
+            return;
+        }
+    };
+
+    let longest_run = usize::max(histogram.longest_run(false), histogram.longest_run(true));
+    let mut lines = String::new();
+    for length in 1..=longest_run {
+        lines.push_str(&format!(
+            "\n\t- {length:>5}: zeros {:>10}, ones {:>10}, expected {:>14.2}",
+            histogram.count(false, length),
+            histogram.count(true, length),
+            histogram.expected_count(length)
+        ));
+    }
+
+    let chi_square = |bit| match histogram.chi_square(bit) {
+        Ok(chi_square) => format!("{chi_square:.6}"),
+        Err(e) => format!("ERROR: {e}"),
+    };
+    info!(
+        target: SUMMARY,
+        zero_runs = histogram.run_count(false),
+        one_runs = histogram.run_count(true),
+        expected_runs = histogram.expected_run_count(),
+        longest_zero_run = histogram.longest_run(false),
+        longest_one_run = histogram.longest_run(true),
+        "\tDiagnostics: run-length histogram (not part of SP 800-22, no P-value):{lines}\n\
+        \t- Runs: zeros {}, ones {}, expected {:.2}\n\
+        \t- Chi-square over {} bins: zeros {}, ones {}",
+        histogram.run_count(false),
+        histogram.run_count(true),
+        histogram.expected_run_count(),
+        histogram.chi_square_bins(),
+        chi_square(false),
+        chi_square(true)
+    );
+}
+
+/// Prints the ones frequency and the chi-square statistic of each bit position within the bytes as
+/// a separate section, see [diagnostics::bit_position_bias], with a single line heatmap of the
+/// positions, see [HEAT_LEVELS].
+pub(super) fn print_bit_position_bias(bias: &Result<BitPositionBias, sts_lib::Error>) {
+    let bias = match bias {
+        Ok(bias) => bias,
+        Err(e) => {
+            warn!(error = %e, "\tBit position bias: ERROR: {e}");
+            return;
+        }
+    };
+
+    let heat = |chi_square: f64| {
+        HEAT_LEVELS
+            .iter()
+            .find(|(critical_value, _, _)| chi_square >= *critical_value)
+            .map_or(' ', |&(_, heat, _)| heat)
+    };
+
+    let mut lines = String::new();
+    for (position, ((ones, frequency), chi_square)) in bias
+        .ones()
+        .into_iter()
+        .zip(bias.ones_frequencies())
+        .zip(bias.chi_squares())
+        .enumerate()
+    {
+        lines.push_str(&format!(
+            "\n\t- Position {position}: ones {ones:>10} ({frequency:.6}), chi-square {chi_square:>12.4} [{}]",
+            heat(chi_square)
+        ));
+    }
+    let heatmap = bias.chi_squares().map(heat).iter().collect::<String>();
+    let legend = HEAT_LEVELS
+        .iter()
+        .rev()
+        .map(|(_, heat, significance)| format!("'{heat}' p < {significance}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    info!(
+        target: SUMMARY,
+        bytes = bias.byte_count(),
+        total_chi_square = bias.total_chi_square(),
+        most_biased_position = bias.most_biased_position(),
+        "\tDiagnostics: bit position bias within {} bytes (not part of SP 800-22, no P-value):{lines}\n\
+        \t- Heatmap of the positions 0 to 7: [{heatmap}] ({legend})\n\
+        \t- Total chi-square: {:.4}, most biased position: {}",
+        bias.byte_count(),
+        bias.total_chi_square(),
+        bias.most_biased_position()
+    );
+}
@@ -0,0 +1,738 @@
+//! The subcommand "run", which is also used without a subcommand, see [run].
+
+use self::output::{
+    create_nist_compat, write_nist_final_analysis, write_report, write_single_nist_compat,
+};
+use self::plan::print_plan;
+use self::sequence::{run_tests, Parts, RunOutcome, TestRunArgs};
+use super::part_reader::{PartReader, WindowCursor};
+use super::{calibrate_tests, parse_config, print_final_analysis, self_test, write_histograms};
+use crate::checkpoint::Checkpoint;
+use crate::cmd_args::RunArgs;
+use crate::logging::{SUMMARY, VERDICT};
+use crate::report::Report;
+use crate::valid_arg::{InputSource, MaxLengthOrSplit, ValidatedConfig};
+use crate::InputFormat;
+use anyhow::Context;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::io::{BufRead, BufReader, Seek};
+use std::num::NonZero;
+use std::path::PathBuf;
+use sts_lib::bitvec::builder::{BitVecBuilder, ReaderFormat};
+use sts_lib::bitvec::BitVec;
+use sts_lib::fips140::{self, Fips140Test};
+use sts_lib::test_runner::{MultiSequenceRunner, Subsample};
+use sts_lib::{test_runner, IntoEnumIterator, Test};
+use tracing::{info, warn};
+
+mod diagnostics;
+mod output;
+mod plan;
+mod sequence;
+
+/// Runs the tests on the input.
+pub fn run(args: RunArgs) -> anyhow::Result<()> {
+    let RunArgs {
+        config_file,
+        regular_args,
+        dry_run,
+        calibrate,
+    } = args;
+
+    let mut config = parse_config(config_file.as_deref(), regular_args)?;
+
+    // problems with the test arguments are only warnings, the affected tests fail with an error.
+    if let Some(len_bit) = config.declared_len_bit() {
+        if let Err(diagnostics) = config.validate_against_input_len(len_bit) {
+            for diagnostic in diagnostics.iter() {
+                warn!(
+                    path = diagnostic.path,
+                    "The test arguments do not fit the input length - {diagnostic}"
+                );
+            }
+        }
+    }
+
+    if calibrate && !config.fips140_quick {
+        let calibration = calibrate_tests(config.tests_to_run.candidates(), config.test_arguments);
+        config.calibration = Some(calibration);
+    }
+
+    if dry_run {
+        return print_plan(&config);
+    }
+
+    self_test()?;
+
+    info!(input = %config.input, "Reading input: {}\n", config.input);
+
+    handle_input(config)?;
+
+    info!("Finished testing.");
+
+    Ok(())
+}
+
+/// Handles the input. The input is read in chunks, so that only the currently tested
+/// [BitVec] is held in memory.
+fn handle_input(config: ValidatedConfig) -> anyhow::Result<()> {
+    // all parts are appended to a single output file, which starts empty unless appending
+    if let (Some(path), true, false) = (
+        &config.output_path,
+        config.single_output,
+        config.append_output,
+    ) {
+        fs::File::create(path)
+            .with_context(|| format!("Failed to create the output file \"{}\"", path.display()))?;
+    }
+
+    match &config.input {
+        InputSource::File(path) => {
+            let file = fs::File::open(path).context("Failed to open input file")?;
+            let mut reader = BufReader::new(file);
+
+            if let MaxLengthOrSplit::Subsample {
+                window_bits,
+                count,
+                seed,
+            } = config.max_length_or_split
+            {
+                // only whole bytes can be read, see PartReader::read_window.
+                let len_bits = count_input_bits(&mut reader, config.input_format)? / 8 * 8;
+                let subsample = Subsample::new(len_bits as usize, window_bits, count, seed)
+                    .context("Failed to place the windows in the input")?;
+                return handle_subsample(reader, &subsample, &config);
+            }
+
+            // for files, the count of parts can be determined in advance.
+            let count_parts = match config.max_length_or_split {
+                MaxLengthOrSplit::Split {
+                    split_bytes,
+                    stride_bytes,
+                } => Some(count_parts(
+                    &mut reader,
+                    config.input_format,
+                    split_bytes,
+                    stride_bytes,
+                )?),
+                _ => None,
+            };
+
+            // only valid for split input files, see ValidatedConfig
+            let checkpoint = match &config.checkpoint_path {
+                Some(path) => {
+                    let tests = config.tests_to_run.select(split_bits(&config));
+                    let checkpoint = Checkpoint::open(path, &config, &tests);
+                    Some(checkpoint.with_context(|| {
+                        format!("Failed to open the checkpoint \"{}\"", path.display())
+                    })?)
+                }
+                None => None,
+            };
+
+            handle_reader(reader, count_parts, checkpoint, &config)
+        }
+        InputSource::Stdin => handle_reader(io::stdin().lock(), None, None, &config),
+        InputSource::Files(paths) => handle_files(paths, &config),
+    }
+}
+
+/// Tests each of the input files as a separate sequence, followed by the final analysis over all
+/// files, like the parts of a split input. Each file is read completely (or up to the max length)
+/// before it is tested.
+fn handle_files(paths: &[PathBuf], config: &ValidatedConfig) -> anyhow::Result<()> {
+    let test_run_args = TestRunArgs::from_config(config);
+    // splitting is not allowed with multiple files, see ValidatedConfig
+    let max_length = match config.max_length_or_split {
+        MaxLengthOrSplit::MaxLength(max_length) => Some(max_length),
+        _ => None,
+    };
+
+    let mut report = config.report.as_ref().map(|(path, format)| {
+        Report::new(
+            path.clone(),
+            *format,
+            input_name(config),
+            config.test_arguments,
+            config.threshold,
+        )
+    });
+
+    // if all tests passed
+    let mut passed = true;
+    let mut outcomes = Vec::with_capacity(paths.len());
+
+    for (i, path) in paths.iter().enumerate() {
+        let file = fs::File::open(path)
+            .with_context(|| format!("Failed to open input file \"{}\"", path.display()))?;
+        let reader = BufReader::new(file);
+        info!(
+            file = %path.display(),
+            "Sequence {} / {}: \"{}\"",
+            i + 1,
+            paths.len(),
+            path.display()
+        );
+
+        if config.fips140_quick {
+            let max_length = max_length.map_or(fips140::SAMPLE_LENGTH, |max_length| {
+                max_length.min(fips140::SAMPLE_LENGTH)
+            });
+            let input = read_input(reader, config, Some(max_length.get()))?;
+            run_fips140_tests(&input, config.console_output)?;
+            continue;
+        }
+
+        let input = read_input(reader, config, max_length.map(NonZero::get))?;
+        let parts = Some(Parts {
+            current: i as u64 + 1,
+            count: Some(paths.len() as u64),
+            offset: None,
+            last_offset: None,
+        });
+        let outcome = run_tests(&input, test_run_args, parts, None, report.as_mut())?;
+        passed &= outcome.passed;
+        outcomes.push(outcome);
+    }
+
+    if config.fips140_quick {
+        return Ok(());
+    }
+
+    // the tests may differ between the files, if their lengths differ
+    let tests = outcomes
+        .iter()
+        .flat_map(|outcome| &outcome.results)
+        .map(|(test, _)| *test)
+        .collect::<HashSet<_>>();
+
+    let mut multi_runner = MultiSequenceRunner::new(tests.into_iter(), config.test_arguments)?;
+    for outcome in &outcomes {
+        for (test, result) in &outcome.results {
+            multi_runner.add_recorded(*test, result);
+        }
+    }
+
+    analyse_sequences(&multi_runner, &outcomes, passed, report.as_mut(), config)
+}
+
+/// Tests each window of the subsample of the input file as a separate sequence, followed by the
+/// final analysis over all windows, like the parts of a split input. The windows are read one
+/// after another, so that only the currently tested window is held in memory.
+fn handle_subsample(
+    reader: impl BufRead,
+    subsample: &Subsample,
+    config: &ValidatedConfig,
+) -> anyhow::Result<()> {
+    let test_run_args = TestRunArgs::from_config(config);
+    let window_bits = subsample.window_len();
+    let offsets = subsample.offsets();
+
+    let mut report = config.report.as_ref().map(|(path, format)| {
+        Report::new(
+            path.clone(),
+            *format,
+            input_name(config),
+            config.test_arguments,
+            config.threshold,
+        )
+    });
+
+    info!(
+        target: SUMMARY,
+        seed = subsample.seed(),
+        windows = offsets.len(),
+        window_length = window_bits,
+        "Testing {} windows of {window_bits} bits, placed with seed {}. Offsets (bits): {:?}\n",
+        offsets.len(),
+        subsample.seed(),
+        offsets
+    );
+
+    // the windows are read with read_window, the part length is not used.
+    let window_bytes = window_bits.div_ceil(8);
+    let mut part_reader = PartReader::new(
+        reader,
+        config.input_format.into(),
+        config.bit_order.into(),
+        window_bytes,
+        window_bytes,
+    );
+    let mut cursor = WindowCursor::default();
+    let last_offset = offsets.last().map(|&offset| offset as u64);
+
+    // the selected tests only depend on the input length, which is the same for all windows.
+    let tests = test_run_args.tests_to_run.select(window_bits);
+    warn_short_input(window_bits, &tests);
+    let mut multi_runner = MultiSequenceRunner::new(tests.into_iter(), test_run_args.test_args)?;
+
+    // if all tests passed
+    let mut passed = true;
+    let mut outcomes = Vec::with_capacity(offsets.len());
+
+    for (i, &offset) in offsets.iter().enumerate() {
+        let input = part_reader
+            .read_window(&mut cursor, offset as u64, window_bits)?
+            .context("The input is shorter than expected, it may have been modified")?;
+
+        let parts = Some(Parts {
+            current: i as u64 + 1,
+            count: Some(offsets.len() as u64),
+            offset: Some(offset as u64),
+            last_offset,
+        });
+        let outcome = run_tests(
+            &input,
+            test_run_args,
+            parts,
+            Some(&mut multi_runner),
+            report.as_mut(),
+        )?;
+        passed &= outcome.passed;
+        outcomes.push(outcome);
+    }
+
+    analyse_sequences(&multi_runner, &outcomes, passed, report.as_mut(), config)
+}
+
+/// Prints the summary and the final analysis over all tested sequences, whose results were
+/// collected by the runner, and writes the NIST compatible output, the histograms and the report,
+/// if configured. Used by [handle_files] and [handle_subsample].
+fn analyse_sequences(
+    multi_runner: &MultiSequenceRunner,
+    outcomes: &[RunOutcome],
+    passed: bool,
+    report: Option<&mut Report>,
+    config: &ValidatedConfig,
+) -> anyhow::Result<()> {
+    if passed {
+        info!(target: SUMMARY, passed, "All tests passed");
+    } else {
+        info!(target: SUMMARY, passed, "One or more tests failed / did not pass");
+    }
+
+    print_final_analysis(multi_runner, config.threshold)?;
+    if let Some(dir) = &config.nist_compat_dir {
+        let tests = multi_runner.tests().collect::<Vec<_>>();
+        let mut nist_compat = create_nist_compat(dir, &tests, config)?;
+        for outcome in outcomes {
+            nist_compat
+                .write_sequence(&outcome.results)
+                .context("Failed to write the NIST compatible output")?;
+        }
+        write_nist_final_analysis(&mut nist_compat, multi_runner, config)?;
+    }
+    if let Some((path, format)) = &config.histogram {
+        let analyses = multi_runner.final_analysis(config.threshold)?;
+        write_histograms(path, *format, &config.csv_options, &analyses)?;
+    }
+    if let Some(report) = report {
+        report.set_final_analysis(
+            &multi_runner.final_analysis(config.threshold)?,
+            &multi_runner.verdicts(config.threshold),
+        );
+        write_report(report)?;
+    }
+
+    Ok(())
+}
+
+/// Reads the whole input, or at most `max_length` bits, in the input format and bit order of the
+/// config.
+fn read_input(
+    mut reader: impl BufRead,
+    config: &ValidatedConfig,
+    max_length: Option<usize>,
+) -> anyhow::Result<BitVec<'static>> {
+    let mut builder = match max_length {
+        Some(max_length) => BitVecBuilder::with_max_length(max_length),
+        None => BitVecBuilder::new(),
+    };
+    builder.set_bit_order(config.bit_order.into());
+    builder
+        .extend_from_reader(&mut reader, config.input_format.into())
+        .context("Failed to read input")?;
+
+    Ok(builder.build())
+}
+
+/// Counts the parts of `split_bytes` bytes, starting every `stride_bytes` bytes, the input file
+/// will be split into. The reader is rewound afterwards.
+fn count_parts(
+    reader: &mut BufReader<fs::File>,
+    input_format: InputFormat,
+    split_bytes: NonZero<usize>,
+    stride_bytes: NonZero<usize>,
+) -> anyhow::Result<u64> {
+    let count_bits = count_input_bits(reader, input_format)?;
+    let split_bits = split_bytes.get() as u64 * 8;
+    let stride_bits = stride_bytes.get() as u64 * 8;
+
+    // the first part needs split_bits, each further part stride_bits more.
+    let count_parts = match count_bits.checked_sub(split_bits) {
+        Some(remaining_bits) => remaining_bits / stride_bits + 1,
+        None => 0,
+    };
+
+    Ok(count_parts)
+}
+
+/// Counts the bits contained in the input file, after decoding the format. The reader is rewound
+/// afterwards.
+fn count_input_bits(
+    reader: &mut BufReader<fs::File>,
+    input_format: InputFormat,
+) -> anyhow::Result<u64> {
+    let count_bits = match input_format {
+        InputFormat::Binary => reader.get_ref().metadata()?.len() * 8,
+        // 1 Byte per Bit
+        InputFormat::Ascii => reader.get_ref().metadata()?.len(),
+        // the count of valid characters can only be determined by reading the file once.
+        InputFormat::AsciiLossy
+        | InputFormat::AsciiWhitespace
+        | InputFormat::Hex
+        | InputFormat::HexLossy
+        | InputFormat::Base64 => {
+            let count_bits = count_text_bits(reader, input_format.into())?;
+            reader.rewind()?;
+            count_bits
+        }
+    };
+
+    Ok(count_bits)
+}
+
+/// The length of each part in bits, or 0 if the input is not split.
+fn split_bits(config: &ValidatedConfig) -> usize {
+    match config.max_length_or_split {
+        MaxLengthOrSplit::Split { split_bytes, .. } => split_bytes.get() * 8,
+        _ => 0,
+    }
+}
+
+/// Reads the input from the given reader and runs the tests, based on the config. If the input
+/// is split, the count of parts is shown if given.
+///
+/// If a checkpoint is given, the parts recorded in it are skipped and the results of all further
+/// parts are recorded.
+fn handle_reader(
+    mut reader: impl BufRead,
+    count_parts: Option<u64>,
+    mut checkpoint: Option<Checkpoint>,
+    config: &ValidatedConfig,
+) -> anyhow::Result<()> {
+    let test_run_args = TestRunArgs::from_config(config);
+    let format = ReaderFormat::from(config.input_format);
+
+    if config.fips140_quick {
+        // only the sample is tested, a smaller max length leads to an error of the tests.
+        let max_length = match config.max_length_or_split {
+            MaxLengthOrSplit::MaxLength(max_length) => max_length.min(fips140::SAMPLE_LENGTH),
+            _ => fips140::SAMPLE_LENGTH,
+        };
+        let input = read_input(reader, config, Some(max_length.get()))?;
+
+        return run_fips140_tests(&input, config.console_output);
+    }
+
+    let mut report = config.report.as_ref().map(|(path, format)| {
+        Report::new(
+            path.clone(),
+            *format,
+            input_name(config),
+            config.test_arguments,
+            config.threshold,
+        )
+    });
+
+    match config.max_length_or_split {
+        MaxLengthOrSplit::MaxLength(max_length) => {
+            // Read only the necessary amount of bits
+            let input = read_input(reader, config, Some(max_length.get()))?;
+
+            // call test
+            let outcome = run_tests(&input, test_run_args, None, None, report.as_mut())?;
+
+            if let Some(dir) = &config.nist_compat_dir {
+                write_single_nist_compat(dir, &outcome, config)?;
+            }
+            if let Some(report) = &report {
+                write_report(report)?;
+            }
+        }
+        MaxLengthOrSplit::Split {
+            split_bytes,
+            stride_bytes,
+        } => {
+            let mut part_reader = PartReader::new(
+                &mut reader,
+                format,
+                config.bit_order.into(),
+                split_bytes.get(),
+                stride_bytes.get(),
+            );
+            // for overlapping parts, the offset of each part is shown.
+            let overlap_stride =
+                (stride_bytes != split_bytes).then(|| stride_bytes.get() as u64 * 8);
+            let last_offset = overlap_stride
+                .zip(count_parts)
+                .map(|(stride, count)| count.saturating_sub(1) * stride);
+
+            let mut i = 1_u64;
+            // if all tests passed
+            let mut passed = true;
+            // collects the results of all parts - created with the first part, so that no final
+            // analysis is printed if the input is too short for a single part.
+            let mut multi_runner: Option<MultiSequenceRunner> = None;
+            // the selected tests only depend on the input length, which is the same for all parts.
+            let tests = test_run_args.tests_to_run.select(split_bits(config));
+            warn_short_input(split_bits(config), &tests);
+            let mut nist_compat = match &config.nist_compat_dir {
+                Some(dir) => Some(create_nist_compat(dir, &tests, config)?),
+                None => None,
+            };
+
+            if let Some(checkpoint) = &checkpoint {
+                let completed = checkpoint.completed_parts();
+                if !completed.is_empty() {
+                    info!(
+                        completed_parts = completed.len(),
+                        "Resuming from the checkpoint: {} parts were already tested.\n",
+                        completed.len()
+                    );
+
+                    let multi_runner = multi_runner.insert(MultiSequenceRunner::new(
+                        tests.iter().copied(),
+                        test_run_args.test_args,
+                    )?);
+                    for part in completed {
+                        for (test, result) in &part.results {
+                            multi_runner.add_recorded(*test, result);
+                        }
+                        passed &= part.passed;
+
+                        if let Some(nist_compat) = &mut nist_compat {
+                            nist_compat
+                                .write_sequence(&part.results)
+                                .context("Failed to write the NIST compatible output")?;
+                        }
+                    }
+
+                    part_reader.skip_parts(completed.len() as u64)?;
+                    i += completed.len() as u64;
+                }
+            }
+
+            loop {
+                let Some(input) = part_reader.next_part()? else {
+                    // the input has fewer than split_bytes bytes left --> regular exit
+                    if passed {
+                        info!(target: SUMMARY, passed, "All tests passed");
+                    } else {
+                        info!(target: SUMMARY, passed, "One or more tests failed / did not pass");
+                    }
+
+                    if let Some(multi_runner) = &multi_runner {
+                        print_final_analysis(multi_runner, test_run_args.threshold)?;
+
+                        if let Some(nist_compat) = &mut nist_compat {
+                            write_nist_final_analysis(nist_compat, multi_runner, config)?;
+                        }
+                        if let Some((path, format)) = &config.histogram {
+                            let analyses = multi_runner.final_analysis(config.threshold)?;
+                            write_histograms(path, *format, &config.csv_options, &analyses)?;
+                        }
+                    }
+
+                    if let Some(report) = &mut report {
+                        if let Some(multi_runner) = &multi_runner {
+                            let threshold = test_run_args.threshold;
+                            report.set_final_analysis(
+                                &multi_runner.final_analysis(threshold)?,
+                                &multi_runner.verdicts(threshold),
+                            );
+                        }
+                        write_report(report)?;
+                    }
+
+                    break;
+                };
+
+                let multi_runner = match &mut multi_runner {
+                    Some(multi_runner) => multi_runner,
+                    None => multi_runner.insert(MultiSequenceRunner::new(
+                        tests.iter().copied(),
+                        test_run_args.test_args,
+                    )?),
+                };
+
+                // call test
+                let parts = Some(Parts {
+                    current: i,
+                    count: count_parts,
+                    offset: overlap_stride.map(|stride| (i - 1) * stride),
+                    last_offset,
+                });
+                let outcome = run_tests(
+                    &input,
+                    test_run_args,
+                    parts,
+                    Some(multi_runner),
+                    report.as_mut(),
+                )?;
+                passed &= outcome.passed;
+
+                if let Some(nist_compat) = &mut nist_compat {
+                    nist_compat
+                        .write_sequence(&outcome.results)
+                        .context("Failed to write the NIST compatible output")?;
+                }
+
+                if let Some(checkpoint) = &mut checkpoint {
+                    checkpoint
+                        .record_part(i, outcome.passed, &outcome.results)
+                        .context("Failed to write the checkpoint")?;
+                }
+
+                // increment counter
+                i += 1;
+            }
+        }
+        MaxLengthOrSplit::Subsample { .. } => {
+            unreachable!("subsample is only valid with a single input file, see ValidatedConfig")
+        }
+        MaxLengthOrSplit::None => {
+            let input = read_input(reader, config, None)?;
+
+            // call test
+            let outcome = run_tests(&input, test_run_args, None, None, report.as_mut())?;
+
+            if let Some(dir) = &config.nist_compat_dir {
+                write_single_nist_compat(dir, &outcome, config)?;
+            }
+            if let Some(report) = &report {
+                write_report(report)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The name of the input in the NIST compatible output and the report: the path of the input
+/// file, or "stdin".
+fn input_name(config: &ValidatedConfig) -> String {
+    match &config.input {
+        InputSource::File(path) => path.display().to_string(),
+        InputSource::Stdin => "stdin".to_owned(),
+        InputSource::Files(paths) => paths
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+/// Counts the bits contained in a text format until the end of the reader: one bit per valid
+/// ASCII character ('0' or '1'), 4 bits per hex digit and 6 bits per base64 character (rounded
+/// down to whole bytes).
+fn count_text_bits(reader: &mut impl BufRead, format: ReaderFormat) -> anyhow::Result<u64> {
+    let is_valid = |c: &&u8| match format {
+        ReaderFormat::Hex | ReaderFormat::HexLossy => c.is_ascii_hexdigit(),
+        ReaderFormat::Base64 => c.is_ascii_alphanumeric() || **c == b'+' || **c == b'/',
+        _ => **c == b'0' || **c == b'1',
+    };
+    let mut count_chars = 0_u64;
+
+    loop {
+        let buffer = reader.fill_buf()?;
+        if buffer.is_empty() {
+            break;
+        }
+
+        count_chars += buffer.iter().filter(is_valid).count() as u64;
+
+        let len = buffer.len();
+        reader.consume(len);
+    }
+
+    let count_bits = match format {
+        ReaderFormat::Hex | ReaderFormat::HexLossy => 4 * count_chars,
+        ReaderFormat::Base64 => (6 * count_chars) / 8 * 8,
+        _ => count_chars,
+    };
+    Ok(count_bits)
+}
+
+/// Warns about each test that is run on an input shorter than recommended by NIST, see
+/// [test_runner::length_warnings]. With split input files, this is done once for all parts.
+fn warn_short_input(len_bit: usize, tests: &[Test]) {
+    for warning in test_runner::length_warnings(len_bit, tests.iter().copied()) {
+        warn!(
+            test = %warning.test(),
+            length = warning.length(),
+            recommended = warning.recommended().get(),
+            "{warning}"
+        );
+    }
+}
+
+/// Runs the FIPS 140-2 tests on the input, see [fips140], and prints the result of each test
+/// and the combined verdict.
+fn run_fips140_tests(input: &BitVec, console_output: bool) -> anyhow::Result<()> {
+    let result = fips140::fips140_2_tests(input).context("Failed to run the FIPS 140-2 tests")?;
+
+    if console_output {
+        for test in Fips140Test::iter() {
+            let status = if result.passed(test) {
+                "PASSED"
+            } else {
+                "FAILED"
+            };
+            let details = match test {
+                Fips140Test::Monobit => format!("Ones: {}", result.ones()),
+                Fips140Test::Poker => format!("Statistic: {}", result.poker_statistic()),
+                Fips140Test::Runs => format!(
+                    "Runs of zeros: {:?}, runs of ones: {:?}",
+                    result.runs(false),
+                    result.runs(true)
+                ),
+                Fips140Test::LongRun => format!("Longest run: {}", result.longest_run()),
+            };
+            info!(
+                test = %test,
+                status,
+                "\tFIPS 140-2 test {test}: {status}. {details}"
+            );
+        }
+    }
+
+    let passed = result.all_passed();
+    let tests = Fips140Test::iter().count();
+    let passed_count = Fips140Test::iter()
+        .filter(|test| result.passed(*test))
+        .count();
+    let status = if passed { "PASS" } else { "FAIL" };
+    info!(
+        target: VERDICT,
+        status,
+        tests,
+        passed = passed_count,
+        failed = tests - passed_count,
+        "{status} tests={tests} passed={passed_count} failed={} errors=0",
+        tests - passed_count
+    );
+
+    if passed {
+        info!(target: SUMMARY, passed, "\tSummary: all FIPS 140-2 tests passed");
+    } else {
+        info!(
+            target: SUMMARY,
+            passed, "\tSummary: one or more FIPS 140-2 tests failed"
+        );
+    }
+
+    Ok(())
+}
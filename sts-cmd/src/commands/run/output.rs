@@ -0,0 +1,211 @@
+//! The output files of a test run: the results, the report and the NIST compatible output.
+
+use super::input_name;
+use super::sequence::{Parts, RunOutcome, TestRunArgs};
+use crate::csv::CsvFile;
+use crate::json::JsonFile;
+use crate::nist_compat::NistCompatOutput;
+use crate::report::Report;
+use crate::valid_arg::ValidatedConfig;
+use crate::OutputFormat;
+use anyhow::Context;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use sts_lib::bitvec::BitVec;
+use sts_lib::diagnostics::BitPositionBias;
+use sts_lib::test_runner::MultiSequenceRunner;
+use sts_lib::tests::run_length_histogram::RunLengthHistogram;
+use sts_lib::{Test, TestResult};
+
+/// The output file for a test run, in the chosen [OutputFormat].
+#[derive(Debug)]
+pub(super) enum OutputFile {
+    Csv(Box<CsvFile>),
+    Json(Box<JsonFile>),
+}
+
+impl OutputFile {
+    /// Save the given test results to the output file.
+    pub(super) fn write_test(
+        &mut self,
+        test: Test,
+        time: Duration,
+        results: Result<&Vec<TestResult>, &sts_lib::Error>,
+    ) -> anyhow::Result<()> {
+        match self {
+            OutputFile::Csv(file) => file.write_test(test, time, results)?,
+            OutputFile::Json(file) => file.write_test(test, time, results)?,
+        }
+
+        Ok(())
+    }
+
+    /// Save the diagnostics of the sequence to the output file, if its format has a section for
+    /// them: only JSON has.
+    pub(super) fn write_diagnostics(
+        &mut self,
+        histogram: Option<&RunLengthHistogram>,
+        bias: Option<&BitPositionBias>,
+    ) -> anyhow::Result<()> {
+        match self {
+            OutputFile::Csv(_) => {}
+            OutputFile::Json(file) => file.write_diagnostics(histogram, bias)?,
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes the report, see [Report].
+pub(super) fn write_report(report: &Report) -> anyhow::Result<()> {
+    report.write().context("Failed to write the report")
+}
+
+/// Creates the NIST compatible output in the given directory for the given tests, see
+/// [NistCompatOutput].
+pub(super) fn create_nist_compat(
+    dir: &Path,
+    tests: &[Test],
+    config: &ValidatedConfig,
+) -> anyhow::Result<NistCompatOutput> {
+    NistCompatOutput::new(dir, tests, config.threshold).with_context(|| {
+        format!(
+            "Failed to create the NIST compatible output in \"{}\"",
+            dir.display()
+        )
+    })
+}
+
+/// Writes the NIST compatible output for an input that is not split, i.e. the input is the only
+/// tested sequence.
+pub(super) fn write_single_nist_compat(
+    dir: &Path,
+    outcome: &RunOutcome,
+    config: &ValidatedConfig,
+) -> anyhow::Result<()> {
+    let tests = outcome
+        .results
+        .iter()
+        .map(|(test, _)| *test)
+        .collect::<Vec<_>>();
+    let mut nist_compat = create_nist_compat(dir, &tests, config)?;
+
+    let mut multi_runner = MultiSequenceRunner::new(tests.into_iter(), config.test_arguments)?;
+    for (test, result) in &outcome.results {
+        multi_runner.add_recorded(*test, result);
+    }
+
+    nist_compat
+        .write_sequence(&outcome.results)
+        .context("Failed to write the NIST compatible output")?;
+    write_nist_final_analysis(&mut nist_compat, &multi_runner, config)
+}
+
+/// Writes the final analysis over all tested sequences to the NIST compatible output, with the
+/// input as generator.
+pub(super) fn write_nist_final_analysis(
+    nist_compat: &mut NistCompatOutput,
+    multi_runner: &MultiSequenceRunner,
+    config: &ValidatedConfig,
+) -> anyhow::Result<()> {
+    nist_compat
+        .write_final_analysis(
+            &input_name(config),
+            multi_runner.final_analysis(config.threshold)?,
+        )
+        .context("Failed to write the NIST compatible output")
+}
+
+/// Create the [OutputFile] instance for the test output, based on the path, the output format and
+/// the idx (if given). With a single output file, the results are appended to the output path,
+/// with the idx as the part.
+pub(super) fn create_output_file(
+    output_path: &Path,
+    input: &BitVec,
+    args: TestRunArgs,
+    parts: Option<Parts>,
+) -> anyhow::Result<OutputFile> {
+    if args.single_output {
+        // the same idx as in the file names without a single output file
+        let part = parts.map_or(1, |parts| parts.offset.unwrap_or(parts.current));
+
+        let file = match args.output_format {
+            OutputFormat::Csv => {
+                let file = CsvFile::append(output_path, part, args.threshold, args.csv_options)?;
+                OutputFile::Csv(Box::new(file))
+            }
+            OutputFormat::Json => {
+                let len_bit = input.len_bit();
+                let file =
+                    JsonFile::append(output_path, part, len_bit, args.test_args, args.threshold)?;
+                OutputFile::Json(Box::new(file))
+            }
+        };
+        return Ok(file);
+    }
+
+    let path = output_file_path(output_path, parts)?;
+
+    let file = match args.output_format {
+        OutputFormat::Csv => {
+            let file = CsvFile::new(path, args.threshold, args.csv_options)?;
+            OutputFile::Csv(Box::new(file))
+        }
+        OutputFormat::Json => {
+            let file = JsonFile::new(path, input.len_bit(), args.test_args, args.threshold)?;
+            OutputFile::Json(Box::new(file))
+        }
+    };
+
+    Ok(file)
+}
+
+/// Create the path of the output file, based on the path and the idx (if given).
+fn output_file_path(output_path: &Path, parts: Option<Parts>) -> anyhow::Result<PathBuf> {
+    let path = match parts {
+        Some(parts) => {
+            if output_path.file_name().is_none() {
+                // Very wrong
+                return Err(anyhow::anyhow!("Given output path contains no file name."));
+            }
+
+            if output_path.try_exists()? && !output_path.is_file() {
+                // path exists, but is no file (i.e. dir)
+                return Err(anyhow::anyhow!(
+                    "Given output path already exists, but is no file."
+                ));
+            }
+
+            // for overlapping or subsampled parts, the offset in bits is used instead of the index.
+            let (idx, max_idx) = match parts.offset {
+                Some(offset) => (offset, parts.last_offset),
+                None => (parts.current, parts.count),
+            };
+
+            // without a known count of parts, the index is not padded
+            let max_idx_len = max_idx.map_or(1, |max_idx| format!("{max_idx}").len());
+
+            // create one file per idx - filename_{idx}.extension
+            // create the filename with the _{idx} suffix and the extension
+            let file_name = {
+                let mut stem = output_path
+                    .file_stem()
+                    .map(OsStr::to_os_string)
+                    .unwrap_or_default();
+                stem.push(format!("_{:0>1$}", idx, max_idx_len));
+                if let Some(ext) = output_path.extension() {
+                    stem.push(".");
+                    stem.push(ext);
+                }
+                stem
+            };
+
+            // create the full path
+            output_path.with_file_name(file_name)
+        }
+        None => output_path.to_path_buf(),
+    };
+
+    Ok(path)
+}
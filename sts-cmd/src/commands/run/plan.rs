@@ -0,0 +1,210 @@
+//! The plan of a test run for '--dry-run', see [print_plan].
+
+use super::{count_input_bits, count_parts};
+use crate::commands::{estimate_duration, format_duration, single_thread_note};
+use crate::logging::SUMMARY;
+use crate::valid_arg::{InputSource, MaxLengthOrSplit, ValidatedConfig};
+use anyhow::Context;
+use std::fs;
+use std::io::BufReader;
+use std::path::Path;
+use std::time::Duration;
+use sts_lib::fips140;
+use sts_lib::test_runner;
+use sts_lib::test_runner::Subsample;
+use tracing::{info, warn};
+
+/// Prints the plan of the test run for '--dry-run', without running any test: for each length of
+/// the sequences to test, the tests that would run with their effective arguments and the tests
+/// that would be skipped, with rough estimates of the time and memory needed.
+pub(super) fn print_plan(config: &ValidatedConfig) -> anyhow::Result<()> {
+    if config.fips140_quick {
+        info!(
+            target: SUMMARY,
+            "Dry run: the FIPS 140-2 tests would run on the first {} bits of the input.",
+            fips140::SAMPLE_LENGTH
+        );
+        return Ok(());
+    }
+
+    let mut total = Duration::ZERO;
+    for (len_bit, count) in planned_sequences(config)? {
+        let sequences = match count {
+            Some(1) => "1 sequence".to_owned(),
+            Some(count) => format!("{count} sequences"),
+            None => "each sequence".to_owned(),
+        };
+        info!(
+            target: SUMMARY,
+            len_bit,
+            count,
+            "Dry run: {sequences} of {len_bit} bits"
+        );
+
+        let tests = config.tests_to_run.candidates();
+        let selected = config.tests_to_run.select(len_bit);
+        let plan = test_runner::plan_tests(len_bit, tests, config.test_arguments)?;
+
+        let mut duration = Duration::ZERO;
+        let mut memory = 0;
+        for planned in &plan {
+            let test = planned.test();
+            match planned.skip_reason() {
+                None => {
+                    let mut parameters =
+                        match crate::json::test_parameters(test, &config.test_arguments) {
+                            serde_json::Value::Object(parameters) => parameters
+                                .into_iter()
+                                .map(|(key, value)| format!("{key} = {value}"))
+                                .collect::<Vec<_>>(),
+                            _ => Vec::new(),
+                        };
+                    let block_length = planned
+                        .block_length()
+                        .filter(|_| !parameters.iter().any(|p| p.starts_with("block-length = ")));
+                    if let Some(block_length) = block_length {
+                        parameters.push(format!("effective block length = {block_length}"));
+                    }
+                    let parameters = match parameters.is_empty() {
+                        true => String::new(),
+                        false => format!(" ({})", parameters.join(", ")),
+                    };
+
+                    let estimated = estimate_duration(
+                        config.calibration.as_ref(),
+                        test,
+                        len_bit,
+                        &config.test_arguments,
+                    );
+                    info!(
+                        %test,
+                        block_length = planned.block_length(),
+                        estimated_ms = estimated.as_secs_f64() * 1000.0,
+                        estimated_memory = planned.estimated_memory(),
+                        "\tTest {test}: runs{parameters}, estimated: {}, {}",
+                        format_duration(estimated),
+                        format_bytes(planned.estimated_memory())
+                    );
+                    duration += estimated;
+                    memory = memory.max(planned.estimated_memory());
+                }
+                Some(reason) if selected.contains(&test) => {
+                    warn!(%test, "\tTest {test}: fails with an error - {reason}");
+                }
+                Some(reason) => info!(%test, "\tTest {test}: skipped - {reason}"),
+            }
+        }
+
+        // the tests run one after another, only the input is held in memory the whole time
+        let peak_memory = len_bit / 8 + memory;
+        info!(
+            target: SUMMARY,
+            estimated_ms = duration.as_secs_f64() * 1000.0,
+            peak_memory,
+            "\tEstimated per sequence: {}{}, peak memory {} (including the input)",
+            format_duration(duration),
+            single_thread_note(config.calibration.as_ref()),
+            format_bytes(peak_memory)
+        );
+        total += duration.mul_f64(count.unwrap_or(1) as f64);
+    }
+
+    info!(
+        target: SUMMARY,
+        estimated_ms = total.as_secs_f64() * 1000.0,
+        "Dry run: estimated total time: {}{}",
+        format_duration(total),
+        single_thread_note(config.calibration.as_ref())
+    );
+    Ok(())
+}
+
+/// The lengths of the sequences that would be tested with the config, in bits, with the count of
+/// sequences of each length, `None` if it cannot be known in advance (splitting stdin). Only the
+/// length of the input files is determined, binary files are not read.
+fn planned_sequences(config: &ValidatedConfig) -> anyhow::Result<Vec<(usize, Option<u64>)>> {
+    let max_length = match config.max_length_or_split {
+        MaxLengthOrSplit::MaxLength(max_length) => Some(max_length.get()),
+        _ => None,
+    };
+    let input_len = |path: &Path| -> anyhow::Result<usize> {
+        let file = fs::File::open(path)
+            .with_context(|| format!("Failed to open input file \"{}\"", path.display()))?;
+        let len_bit = count_input_bits(&mut BufReader::new(file), config.input_format)? as usize;
+        Ok(max_length.map_or(len_bit, |max_length| len_bit.min(max_length)))
+    };
+
+    let sequences = match (&config.input, config.max_length_or_split.clone()) {
+        (
+            input,
+            MaxLengthOrSplit::Split {
+                split_bytes,
+                stride_bytes,
+            },
+        ) => {
+            let count = match input {
+                InputSource::File(path) => {
+                    let file = fs::File::open(path).context("Failed to open input file")?;
+                    let mut reader = BufReader::new(file);
+                    let count_parts =
+                        count_parts(&mut reader, config.input_format, split_bytes, stride_bytes)?;
+                    Some(count_parts)
+                }
+                _ => None,
+            };
+            vec![(split_bytes.get() * 8, count)]
+        }
+        (
+            InputSource::File(path),
+            MaxLengthOrSplit::Subsample {
+                window_bits,
+                count,
+                seed,
+            },
+        ) => {
+            // only whole bytes can be read, see PartReader::read_window.
+            let len_bits = input_len(path)? / 8 * 8;
+            Subsample::new(len_bits, window_bits, count, seed)
+                .context("Failed to place the windows in the input")?;
+            vec![(window_bits.get(), Some(count.get() as u64))]
+        }
+        (InputSource::File(path), _) => vec![(input_len(path)?, Some(1))],
+        (InputSource::Files(paths), _) => {
+            // files of the same length share one plan
+            let mut sequences: Vec<(usize, Option<u64>)> = Vec::new();
+            for path in paths {
+                let len_bit = input_len(path)?;
+                match sequences.iter_mut().find(|(len, _)| *len == len_bit) {
+                    Some((_, count)) => *count = count.map(|count| count + 1),
+                    None => sequences.push((len_bit, Some(1))),
+                }
+            }
+            sequences
+        }
+        (InputSource::Stdin, _) => match max_length {
+            Some(max_length) => vec![(max_length, Some(1))],
+            None => anyhow::bail!(
+                "The length of the input from stdin is not known in advance, '--max-length' is \
+                 required for a dry run"
+            ),
+        },
+    };
+
+    Ok(sequences)
+}
+
+/// Formats a count of bytes for the console output, in B, KiB, MiB or GiB.
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 3] = ["KiB", "MiB", "GiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
@@ -0,0 +1,411 @@
+//! Running the tests on a single sequence, see [run_tests].
+
+use super::diagnostics::{
+    print_bit_position_bias, print_entropy_estimates, print_run_length_histogram,
+};
+use super::output::create_output_file;
+use super::warn_short_input;
+use crate::checkpoint::record_result;
+use crate::commands::{estimate_duration, format_duration, single_thread_note};
+use crate::csv::CsvOptions;
+use crate::logging::{SUMMARY, VERDICT};
+use crate::report::Report;
+use crate::valid_arg::{TestsToRun, ValidatedConfig};
+use crate::OutputFormat;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use sts_lib::bitvec::BitVec;
+use sts_lib::diagnostics;
+use sts_lib::test_runner::multi_sequence::RecordedResult;
+use sts_lib::test_runner::{Calibration, MultiSequenceRunner};
+use sts_lib::tests::run_length_histogram;
+use sts_lib::{test_runner, Test, TestArgs, TestResult};
+use tracing::{debug, error, field, info, info_span};
+
+/// A single item returned by the test runners.
+type TestResultItem = (Test, Result<Vec<TestResult>, sts_lib::Error>);
+
+/// Arguments for [run_tests] - borrowing from a [ValidatedConfig]
+#[derive(Debug, Copy, Clone)]
+pub(super) struct TestRunArgs<'a> {
+    pub(super) tests_to_run: &'a TestsToRun,
+    pub(super) test_args: TestArgs,
+    pub(super) threshold: f64,
+    pub(super) output_path: Option<&'a Path>,
+    pub(super) output_format: OutputFormat,
+    pub(super) single_output: bool,
+    pub(super) csv_options: CsvOptions,
+    pub(super) entropy_estimation: bool,
+    pub(super) diagnostics: bool,
+    pub(super) console_output: bool,
+    pub(super) calibration: Option<&'a Calibration>,
+}
+
+impl<'a> TestRunArgs<'a> {
+    /// Create an instance from a validated config
+    pub(super) fn from_config(config: &'a ValidatedConfig) -> Self {
+        Self {
+            tests_to_run: &config.tests_to_run,
+            test_args: config.test_arguments,
+            threshold: config.threshold,
+            output_path: config.output_path.as_deref(),
+            output_format: config.output_format,
+            single_output: config.single_output,
+            csv_options: config.csv_options,
+            entropy_estimation: config.entropy_estimation,
+            diagnostics: config.diagnostics,
+            console_output: config.console_output,
+            calibration: config.calibration.as_ref(),
+        }
+    }
+}
+
+/// The outcome of [run_tests] for one sequence.
+#[derive(Debug)]
+pub(super) struct RunOutcome {
+    /// If all tests passed (or were not applicable).
+    pub(super) passed: bool,
+    /// The result of each test, as collected for the final analysis.
+    pub(super) results: Vec<(Test, RecordedResult)>,
+}
+
+/// If multiple parts are tested in one execution
+#[derive(Debug, Copy, Clone)]
+pub(super) struct Parts {
+    /// The current part number
+    pub(super) current: u64,
+    /// How many parts there will be, if known in advance
+    pub(super) count: Option<u64>,
+    /// The offset of the current part in the input, in bits, if the parts overlap or are placed
+    /// at random offsets. Otherwise, the part is identified by its number.
+    pub(super) offset: Option<u64>,
+    /// The offset of the last part in the input, in bits, if `offset` is given and the count of
+    /// parts is known.
+    pub(super) last_offset: Option<u64>,
+}
+
+/// Run the specified tests on the specified BitVec, handle IO.
+/// If a test index is given, this function behaves as if a file is split into subfiles and tested in
+/// the same program execution.
+///
+/// If a [MultiSequenceRunner] is given, its tests are run and the results are collected for the
+/// final analysis.
+///
+/// If a [Report] is given, the results are added to it as a new sequence.
+///
+/// Returns if all tests passed and the result of each test.
+pub(super) fn run_tests(
+    input: &BitVec,
+    args: TestRunArgs,
+    parts: Option<Parts>,
+    multi_runner: Option<&mut MultiSequenceRunner>,
+    mut report: Option<&mut Report>,
+) -> anyhow::Result<RunOutcome> {
+    // calculate applicable tests
+    let selected_tests = match &multi_runner {
+        Some(multi_runner) => multi_runner.tests().collect(),
+        None => {
+            let tests = args.tests_to_run.select(input.len_bit());
+            warn_short_input(input.len_bit(), &tests);
+            tests
+        }
+    };
+
+    // Create output file, if necessary
+    let mut output_file = match args.output_path {
+        Some(path) => Some(create_output_file(path, input, args, parts)?),
+        None => None,
+    };
+    if let Some(report) = &mut report {
+        report.start_sequence(parts.map(|parts| parts.current), input.len_bit());
+    }
+
+    // Print the start info for this test runner.
+    let mut start_str = String::new();
+    if let Some(parts) = parts {
+        match parts.count {
+            Some(count) => start_str.push_str(&format!("{} / {count} ", parts.current)),
+            None => start_str.push_str(&format!("{} ", parts.current)),
+        }
+        if let Some(offset) = parts.offset {
+            start_str.push_str(&format!("(offset: {offset} bits) "));
+        }
+    }
+    info!(
+        target: SUMMARY,
+        part = parts.map(|parts| parts.current),
+        offset = parts.and_then(|parts| parts.offset),
+        "{start_str}Running the selected tests: "
+    );
+    debug!(
+        input_length = input.len_bit(),
+        "Input length: {} bits",
+        input.len_bit()
+    );
+
+    if args.console_output {
+        let tests = selected_tests
+            .iter()
+            .map(|test| format!("{test} "))
+            .collect::<String>();
+        info!(tests, "\t{tests}\n");
+
+        // tests that cannot run on the input fail immediately
+        let estimated = selected_tests
+            .iter()
+            .filter(|&&test| {
+                test_runner::validate_tests(input.len_bit(), [test], args.test_args).is_ok()
+            })
+            .map(|&test| {
+                estimate_duration(args.calibration, test, input.len_bit(), &args.test_args)
+            })
+            .sum::<Duration>();
+        let remaining = match parts {
+            Some(Parts {
+                current,
+                count: Some(count),
+                ..
+            }) if count > current => format!(
+                ", {} including the remaining {} parts",
+                format_duration(estimated.mul_f64((count - current + 1) as f64)),
+                count - current
+            ),
+            _ => String::new(),
+        };
+        info!(
+            estimated_ms = estimated.as_secs_f64() * 1000.0,
+            "\tEstimated time: {}{remaining}{}\n",
+            format_duration(estimated),
+            single_thread_note(args.calibration)
+        );
+    }
+
+    // Create runner - iterator is evaluated lazy - each test is only run, when .next() is called.
+    let mut iter: Box<dyn Iterator<Item = TestResultItem>> = match multi_runner {
+        Some(multi_runner) => Box::new(multi_runner.run_sequence(input)),
+        None => {
+            // the runner rejects all tests if one of them cannot be run on the input, the
+            // rejected tests are reported as errors instead, like the other failed tests.
+            let mut rejected = Vec::new();
+            let runnable = selected_tests
+                .iter()
+                .copied()
+                .filter(|&test| {
+                    match test_runner::validate_tests(input.len_bit(), [test], args.test_args) {
+                        Ok(()) => true,
+                        Err(e) => {
+                            let error = sts_lib::Error::InvalidParameter(e.to_string());
+                            rejected.push((test, Err(error)));
+                            false
+                        }
+                    }
+                })
+                .collect::<Vec<_>>();
+            let runner = test_runner::run_tests(input, runnable.into_iter(), args.test_args)?;
+
+            Box::new(runner.chain(rejected))
+        }
+    };
+
+    // if all tests passed
+    let mut passed = true;
+    let mut results = Vec::with_capacity(selected_tests.len());
+    let mut verdict = Verdict::default();
+
+    // use a manual loop to be able to time the test - the runner returns one item per test.
+    for _ in 0..selected_tests.len() {
+        // the test is only known after it ran
+        let span = info_span!("test", test = field::Empty, time_ms = field::Empty);
+        let _entered = span.enter();
+
+        let begin = Instant::now();
+        let Some((test, result)) = iter.next() else {
+            break;
+        };
+        let time = begin.elapsed();
+        span.record("test", field::display(test));
+        span.record("time_ms", (time.as_micros() as f64) / 1000.0);
+
+        // save to the output file
+        if let Some(output_file) = &mut output_file {
+            output_file.write_test(test, time, result.as_ref())?;
+        }
+        if let Some(report) = &mut report {
+            report.add_test(test, time, result.as_ref());
+        }
+        results.push((test, record_result(&result)));
+        verdict.add_test(test, result.as_ref(), args.threshold);
+
+        // Print test results
+        match result {
+            Ok(res) => {
+                // check if all tests passed - tests that were not applicable don't count as failed
+                if !res
+                    .iter()
+                    .all(|r| r.passed(args.threshold) || !r.is_applicable())
+                {
+                    passed = false;
+                }
+
+                if args.console_output {
+                    let time_as_ms = (time.as_micros() as f64) / 1000.0;
+
+                    if res.len() == 1 {
+                        print_test_result(
+                            format!("Test {test} ({}ms)", time_as_ms),
+                            0,
+                            &res[0],
+                            args.threshold,
+                        );
+                    } else {
+                        info!("\tTest: {test} ({}ms): multiple Results", time_as_ms);
+                        for (i, res) in res.iter().enumerate() {
+                            print_test_result(format!("- Result {i}"), i, res, args.threshold);
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                passed = false;
+                if args.console_output {
+                    error!(error = %e, "\tTest {test}: ERROR: {e}")
+                }
+            }
+        }
+    }
+
+    if args.entropy_estimation {
+        print_entropy_estimates(input);
+    }
+    if args.diagnostics {
+        let histogram = run_length_histogram::run_length_histogram(input);
+        let bias = diagnostics::bit_position_bias(input);
+        print_run_length_histogram(&histogram);
+        print_bit_position_bias(&bias);
+        if let Some(output_file) = &mut output_file {
+            output_file.write_diagnostics(histogram.as_ref().ok(), bias.as_ref().ok())?;
+        }
+    }
+
+    if passed {
+        info!(target: SUMMARY, passed, "\tSummary: all tests passed");
+    } else {
+        info!(
+            target: SUMMARY,
+            passed, "\tSummary: one or more tests failed / did not pass"
+        );
+    }
+    verdict.print(parts.map(|parts| parts.current));
+
+    Ok(RunOutcome { passed, results })
+}
+
+/// The counts of the single line verdict of a tested sequence, see [VERDICT].
+#[derive(Debug, Default)]
+struct Verdict {
+    tests: usize,
+    passed: usize,
+    failed: usize,
+    errors: usize,
+    // the lowest applicable P-value and its test
+    worst: Option<(f64, Test)>,
+}
+
+impl Verdict {
+    /// Adds the results of a test: a test failed if any applicable result did not pass.
+    fn add_test(
+        &mut self,
+        test: Test,
+        results: Result<&Vec<TestResult>, &sts_lib::Error>,
+        threshold: f64,
+    ) {
+        self.tests += 1;
+        let Ok(results) = results else {
+            self.errors += 1;
+            return;
+        };
+
+        if results
+            .iter()
+            .all(|r| r.passed(threshold) || !r.is_applicable())
+        {
+            self.passed += 1;
+        } else {
+            self.failed += 1;
+        }
+
+        for result in results.iter().filter(|r| r.is_applicable()) {
+            if self
+                .worst
+                .map_or(true, |(p_value, _)| result.p_value() < p_value)
+            {
+                self.worst = Some((result.p_value(), test));
+            }
+        }
+    }
+
+    /// Prints the verdict as a single line, with the index of the sequence, if given.
+    fn print(&self, sequence: Option<u64>) {
+        let status = if self.failed == 0 && self.errors == 0 {
+            "PASS"
+        } else {
+            "FAIL"
+        };
+
+        let mut line = status.to_owned();
+        if let Some(sequence) = sequence {
+            line.push_str(&format!(" sequence={sequence}"));
+        }
+        line.push_str(&format!(
+            " tests={} passed={} failed={} errors={}",
+            self.tests, self.passed, self.failed, self.errors
+        ));
+        if let Some((p_value, test)) = self.worst {
+            line.push_str(&format!(" worst-p-value={p_value:.6} worst-test={test}"));
+        }
+
+        info!(
+            target: VERDICT,
+            status,
+            sequence,
+            tests = self.tests,
+            passed = self.passed,
+            failed = self.failed,
+            errors = self.errors,
+            worst_p_value = self.worst.map(|(p_value, _)| p_value),
+            worst_test = self.worst.map(|(_, test)| field::display(test)),
+            "{line}"
+        );
+    }
+}
+
+/// Print a test result with a given start string and result index, using the given threshold
+fn print_test_result(start_str: String, result_idx: usize, result: &TestResult, threshold: f64) {
+    let passed = if !result.is_applicable() {
+        "NOT APPLICABLE"
+    } else if result.passed(threshold) {
+        "PASSED"
+    } else {
+        "FAILED"
+    };
+
+    if let Some(comment) = result.comment() {
+        info!(
+            result_idx,
+            status = passed,
+            p_value = result.p_value(),
+            comment,
+            "\t{start_str}: {passed}. P-Value: {}. Comment: {}",
+            result.p_value(),
+            comment
+        );
+    } else {
+        info!(
+            result_idx,
+            status = passed,
+            p_value = result.p_value(),
+            "\t{start_str}: {passed}. P-Value: {}",
+            result.p_value()
+        );
+    }
+}
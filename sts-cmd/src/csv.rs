@@ -1,19 +1,28 @@
-//! Everything needed to save CSV results.
+//! Everything needed to save CSV results and to read them back.
 
-use core::error::Error;
-use csv::WriterBuilder;
-use serde::Serialize;
+use crate::{test_from_name, CsvNotation};
+use csv::{ReaderBuilder, WriterBuilder};
+use serde::{Deserialize, Serialize, Serializer};
+use std::borrow::Cow;
+use std::error::Error;
 use std::fmt::{Display, Formatter};
-use std::fs::File;
+use std::fs::{File, OpenOptions};
+use std::io::Read;
 use std::path::Path;
 use std::time::Duration;
-use sts_lib::{Test, TestResult, DEFAULT_THRESHOLD};
+use sts_lib::statistics::TestStatistics;
+use sts_lib::test_runner::multi_sequence::{FinalAnalysis, RecordedResult, BIN_COUNT};
+use sts_lib::{Test, TestResult};
 
 /// Error type for [CsvFile]
 #[derive(Debug)]
 pub enum CsvFileError {
     Io(std::io::Error),
     Csv(csv::Error),
+    /// A saved file contains a test name that is not known.
+    UnknownTest(String),
+    /// A saved file contains a P-value that is not a number.
+    InvalidPValue(String),
 }
 
 impl Display for CsvFileError {
@@ -21,6 +30,8 @@ impl Display for CsvFileError {
         match self {
             CsvFileError::Io(e) => write!(f, "IO error: {e}"),
             CsvFileError::Csv(e) => write!(f, "CSV error: {e}"),
+            CsvFileError::UnknownTest(test) => write!(f, "Unknown test \"{test}\""),
+            CsvFileError::InvalidPValue(p_value) => write!(f, "Invalid P-value \"{p_value}\""),
         }
     }
 }
@@ -39,16 +50,64 @@ impl From<csv::Error> for CsvFileError {
     }
 }
 
-/// This struct represents a CSV file to write the test outputs.
-#[derive(Debug)]
-pub struct CsvFile(csv::Writer<File>);
+/// The formatting options of the CSV files: the delimiter and the formatting of the floating
+/// point values (P-values and times), e.g. for importing them into spreadsheets in locales that
+/// use a decimal comma.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// The delimiter between the columns, an ASCII character. Default: ';'.
+    pub delimiter: u8,
+    /// The count of digits after the decimal point. With [CsvNotation::Auto], a given precision
+    /// selects the fixed notation. Default: as many as needed to read the value back exactly.
+    pub precision: Option<usize>,
+    /// The notation of the floating point values. Default: [CsvNotation::Auto].
+    pub notation: CsvNotation,
+    /// Use ',' as the decimal separator instead of '.'. Requires a delimiter other than ','.
+    pub decimal_comma: bool,
+}
 
-impl CsvFile {
-    /// Create a new CSV File writer writing to the specified path.
-    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, CsvFileError> {
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b';',
+            precision: None,
+            notation: CsvNotation::Auto,
+            decimal_comma: false,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Formats a floating point value with these options.
+    pub fn format_float(&self, value: f64) -> String {
+        let formatted = match (self.notation, self.precision) {
+            // Debug is the shortest representation that is read back exactly
+            (CsvNotation::Auto, None) => format!("{value:?}"),
+            (CsvNotation::Auto | CsvNotation::Fixed, Some(precision)) => {
+                format!("{value:.precision$}")
+            }
+            (CsvNotation::Fixed, None) => format!("{value}"),
+            (CsvNotation::Scientific, Some(precision)) => format!("{value:.precision$e}"),
+            (CsvNotation::Scientific, None) => format!("{value:e}"),
+        };
+
+        if self.decimal_comma {
+            formatted.replace('.', ",")
+        } else {
+            formatted
+        }
+    }
+
+    /// A CSV writer with these options, writing to the given path.
+    fn writer<P: AsRef<Path>>(&self, path: P) -> Result<csv::Writer<File>, CsvFileError> {
+        Ok(self.builder(true).from_path(path)?)
+    }
+
+    /// A CSV writer builder with these options, writing the header if `has_headers` is set.
+    fn builder(&self, has_headers: bool) -> WriterBuilder {
         let mut builder = WriterBuilder::new();
 
-        builder.delimiter(b';').has_headers(true);
+        builder.delimiter(self.delimiter).has_headers(has_headers);
 
         // target specific: on windows, lines should end with CRLF, on all other platforms, the default
         // LF is enough.
@@ -59,7 +118,74 @@ impl CsvFile {
             builder.terminator(Terminator::CRLF);
         }
 
-        Ok(Self(builder.from_path(path)?))
+        builder
+    }
+}
+
+/// A floating point value in a CSV file, formatted with the [CsvOptions]. With the default
+/// options, the value is written by the CSV writer itself, as before the options existed.
+struct CsvFloat<'a>(f64, &'a CsvOptions);
+
+impl Serialize for CsvFloat<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let CsvFloat(value, options) = *self;
+
+        match options {
+            CsvOptions {
+                precision: None,
+                notation: CsvNotation::Auto,
+                decimal_comma: false,
+                ..
+            } => serializer.serialize_f64(value),
+            _ => serializer.serialize_str(&options.format_float(value)),
+        }
+    }
+}
+
+/// This struct represents a CSV file to write the test outputs.
+#[derive(Debug)]
+pub struct CsvFile {
+    writer: csv::Writer<File>,
+    threshold: f64,
+    options: CsvOptions,
+    /// With [CsvFile::append], the part written to the first column.
+    part: Option<u64>,
+}
+
+impl CsvFile {
+    /// Create a new CSV File writer writing to the specified path. The threshold is used to
+    /// determine if a result passed, the options to format the file.
+    pub fn new<P: AsRef<Path>>(
+        path: P,
+        threshold: f64,
+        options: CsvOptions,
+    ) -> Result<Self, CsvFileError> {
+        Ok(Self {
+            writer: options.writer(path)?,
+            threshold,
+            options,
+            part: None,
+        })
+    }
+
+    /// Create a new CSV File writer appending to the specified path, e.g. to write all parts of a
+    /// split input to the same file. Each row starts with an additional column "part", containing
+    /// the given part. The header is only written if the file is empty.
+    pub fn append<P: AsRef<Path>>(
+        path: P,
+        part: u64,
+        threshold: f64,
+        options: CsvOptions,
+    ) -> Result<Self, CsvFileError> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let is_empty = file.metadata()?.len() == 0;
+
+        Ok(Self {
+            writer: options.builder(is_empty).from_writer(file),
+            threshold,
+            options,
+            part: Some(part),
+        })
     }
 
     /// Append the given test results to the CSV file.
@@ -69,23 +195,27 @@ impl CsvFile {
         time: Duration,
         results: Result<S, &sts_lib::Error>,
     ) -> Result<(), CsvFileError> {
-        // CSV format: test name; time in ms; result no.; PASS/FAIL; P-Value; comment
+        // CSV format: (part;) test name; time in ms; result no.; label; PASS/FAIL/N/A; P-Value; comment
         let test = test.to_string();
         let time = (time.as_micros() as f64) / 1000.0;
 
         // struct to use for CSV
         #[derive(Serialize)]
         struct CsvFormat<'a> {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            part: Option<u64>,
             #[serde(rename = "test name")]
             test: &'a str,
             #[serde(rename = "time in ms")]
-            time: f64,
+            time: CsvFloat<'a>,
             #[serde(rename = "result no")]
             result_no: usize,
+            #[serde(rename = "label")]
+            label: String,
             #[serde(rename = "PASS/FAIL")]
             pass_fail: &'static str,
             #[serde(rename = "p-value")]
-            p_value: f64,
+            p_value: CsvFloat<'a>,
             #[serde(rename = "comment")]
             comment: &'a str,
         }
@@ -94,41 +224,201 @@ impl CsvFile {
             Ok(results) => {
                 // Serialization of successful results.
                 for (no, result) in results.as_ref().iter().enumerate() {
-                    let pass = if result.passed(DEFAULT_THRESHOLD) {
+                    let pass = if !result.is_applicable() {
+                        "N/A"
+                    } else if result.passed(self.threshold) {
                         "PASS"
                     } else {
                         "FAIL"
                     };
 
+                    let comment = csv_comment(result);
                     let row = CsvFormat {
+                        part: self.part,
                         test: &test,
-                        time,
+                        time: CsvFloat(time, &self.options),
                         result_no: no,
+                        label: result
+                            .label()
+                            .map(|label| label.to_string())
+                            .unwrap_or_default(),
                         pass_fail: pass,
-                        p_value: result.p_value(),
-                        comment: result.comment().unwrap_or(""),
+                        p_value: CsvFloat(result.p_value(), &self.options),
+                        comment: &comment,
                     };
 
-                    self.0.serialize(row)?;
+                    self.writer.serialize(row)?;
                 }
             }
             Err(e) => {
                 // Serialization of errors
                 let err = e.to_string();
                 let row = CsvFormat {
+                    part: self.part,
                     test: &test,
-                    time,
+                    time: CsvFloat(time, &self.options),
                     result_no: 0,
+                    label: String::new(),
                     pass_fail: "ERROR",
-                    p_value: -1.0,
+                    p_value: CsvFloat(-1.0, &self.options),
                     comment: &err,
                 };
 
-                self.0.serialize(row)?;
+                self.writer.serialize(row)?;
             }
         }
 
-        self.0.flush()?;
+        self.writer.flush()?;
         Ok(())
     }
 }
+
+/// The comment of a result in the CSV file. Results without a comment get the most important
+/// statistic instead, as diagnostic output: the histogram of the deviations *T_i* for the linear
+/// complexity test and the maximum excursion for the cumulative sums test.
+fn csv_comment(result: &TestResult) -> Cow<'_, str> {
+    match (result.comment(), result.statistics()) {
+        (Some(comment), _) => Cow::Borrowed(comment),
+        (None, Some(TestStatistics::LinearComplexity { histogram, .. })) => {
+            let histogram = histogram.map(|v| v.to_string()).join(", ");
+            Cow::Owned(format!("T_i histogram: [{histogram}]"))
+        }
+        (None, Some(TestStatistics::CumulativeSums { max_excursion })) => {
+            Cow::Owned(format!("max excursion: {max_excursion}"))
+        }
+        (None, _) => Cow::Borrowed(""),
+    }
+}
+
+/// Writes the histograms of the P-values of the final analysis to a CSV file, formatted with the
+/// given options, one row per result, for plotting the uniformity of the P-values.
+///
+/// The columns are: test name; result no.; label; comment; sample size; one column per bin with the count
+/// of P-values in it, named by its range, e.g. "0.0-0.1"; uniformity p-value; uniformity PASS/FAIL.
+/// The uniformity P-value is empty and the status "N/A" if it could not be computed, see
+/// [FinalAnalysis::uniformity_p_value].
+pub fn write_histograms<P: AsRef<Path>>(
+    path: P,
+    analyses: &[FinalAnalysis],
+    options: &CsvOptions,
+) -> Result<(), CsvFileError> {
+    let mut writer = options.writer(path)?;
+
+    let bins = (0..BIN_COUNT).map(|bin| {
+        let width = 1.0 / BIN_COUNT as f64;
+        format!("{:.1}-{:.1}", bin as f64 * width, (bin + 1) as f64 * width)
+    });
+    let header = ["test name", "result no", "label", "comment", "sample size"]
+        .into_iter()
+        .map(str::to_owned)
+        .chain(bins)
+        .chain(["uniformity p-value", "uniformity PASS/FAIL"].map(str::to_owned));
+    writer.write_record(header)?;
+
+    for analysis in analyses {
+        let (p_value, status) = match analysis.uniformity_p_value() {
+            Some(p_value) if analysis.uniformity_passed() => {
+                (options.format_float(p_value), "PASS")
+            }
+            Some(p_value) => (options.format_float(p_value), "FAIL"),
+            None => (String::new(), "N/A"),
+        };
+
+        let record = [
+            analysis.test().to_string(),
+            analysis.result_idx().to_string(),
+            analysis
+                .label()
+                .map(|label| label.to_string())
+                .unwrap_or_default(),
+            analysis.comment().unwrap_or_default().to_owned(),
+            analysis.sample_size().to_string(),
+        ]
+        .into_iter()
+        .chain(analysis.histogram().map(|count| count.to_string()))
+        .chain([p_value, status.to_owned()]);
+        writer.write_record(record)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// The results of all tests of one sequence, as read from a file.
+type SequenceResults = Vec<(Test, RecordedResult)>;
+
+/// Reads the results saved to a CSV file by [CsvFile], for each sequence in the file and each test
+/// in the order of the file. Tests with a result that is not applicable are read as
+/// [RecordedResult::NotApplicable].
+///
+/// A file contains one sequence, except for files written with [CsvFile::append]: each part in
+/// the column "part" is one sequence. The files can be written with any [CsvOptions]: the
+/// delimiter is detected from the header, and P-values with a decimal comma are accepted.
+pub fn read_sequences<P: AsRef<Path>>(path: P) -> Result<Vec<SequenceResults>, CsvFileError> {
+    // the columns needed for the results, all others are ignored
+    #[derive(Deserialize)]
+    struct CsvFormat {
+        #[serde(default)]
+        part: Option<String>,
+        #[serde(rename = "test name")]
+        test: String,
+        #[serde(rename = "PASS/FAIL")]
+        pass_fail: String,
+        #[serde(rename = "p-value")]
+        p_value: String,
+    }
+
+    // the header starts with the column "part" or "test name", followed by the delimiter
+    let mut header = [0; 10];
+    let delimiter = match File::open(path.as_ref())?.read_exact(&mut header) {
+        Ok(()) if header.starts_with(b"part") => header[4],
+        Ok(()) if header.starts_with(b"test name") => header[9],
+        _ => CsvOptions::default().delimiter,
+    };
+
+    let mut reader = ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(true)
+        .from_path(path)?;
+
+    // all rows of a part are written one after another, as are all rows of a test
+    let mut sequences: Vec<(Option<String>, SequenceResults)> = Vec::new();
+    for row in reader.deserialize() {
+        let row: CsvFormat = row?;
+        let test = test_from_name(&row.test).ok_or(CsvFileError::UnknownTest(row.test))?;
+
+        let result = match row.pass_fail.as_str() {
+            "ERROR" => RecordedResult::Error,
+            "N/A" => RecordedResult::NotApplicable,
+            _ => {
+                let p_value = row
+                    .p_value
+                    .replace(',', ".")
+                    .parse::<f64>()
+                    .map_err(|_| CsvFileError::InvalidPValue(row.p_value.clone()))?;
+                RecordedResult::PValues(vec![p_value])
+            }
+        };
+
+        let results = match sequences.last_mut() {
+            Some((part, results)) if *part == row.part => results,
+            _ => {
+                sequences.push((row.part, Vec::new()));
+                &mut sequences.last_mut().unwrap().1
+            }
+        };
+
+        match results.last_mut() {
+            Some((last_test, last_result)) if *last_test == test => match (last_result, result) {
+                (RecordedResult::PValues(p_values), RecordedResult::PValues(new)) => {
+                    p_values.extend(new)
+                }
+                (RecordedResult::NotApplicable | RecordedResult::Error, _) => {}
+                (last_result, result) => *last_result = result,
+            },
+            _ => results.push((test, result)),
+        }
+    }
+
+    Ok(sequences.into_iter().map(|(_, results)| results).collect())
+}
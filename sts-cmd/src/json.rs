@@ -0,0 +1,530 @@
+//! Everything needed to save JSON results and to read them back.
+
+use crate::test_from_name;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use sts_lib::diagnostics::{BitPositionBias, BYTE_BITS};
+use sts_lib::test_runner::multi_sequence::{FinalAnalysis, RecordedResult, BIN_COUNT};
+use sts_lib::tests::frequency_block::{FrequencyBlockTestArg, SelectionStrategy};
+use sts_lib::tests::linear_complexity::LinearComplexityTestArg;
+use sts_lib::tests::run_length_histogram::RunLengthHistogram;
+use sts_lib::tests::spectral_dft::PeakThreshold;
+use sts_lib::{Test, TestArgs, TestResult};
+
+/// Error type for [JsonFile]
+#[derive(Debug)]
+pub enum JsonFileError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    /// A saved file contains a test name that is not known.
+    UnknownTest(String),
+}
+
+impl Display for JsonFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JsonFileError::Io(e) => write!(f, "IO error: {e}"),
+            JsonFileError::Json(e) => write!(f, "JSON error: {e}"),
+            JsonFileError::UnknownTest(test) => write!(f, "Unknown test \"{test}\""),
+        }
+    }
+}
+
+impl Error for JsonFileError {}
+
+impl From<std::io::Error> for JsonFileError {
+    fn from(value: std::io::Error) -> Self {
+        JsonFileError::Io(value)
+    }
+}
+
+impl From<serde_json::Error> for JsonFileError {
+    fn from(value: serde_json::Error) -> Self {
+        JsonFileError::Json(value)
+    }
+}
+
+/// The JSON document written for one test run.
+#[derive(Debug, Serialize)]
+struct JsonDocument {
+    /// The tested part, only for documents written with [JsonFile::append].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part: Option<u64>,
+    /// The length of the tested sequence in bits.
+    input_length: usize,
+    /// The threshold used to determine if a result passed.
+    threshold: f64,
+    /// If all tests written so far passed (or were not applicable).
+    passed: bool,
+    tests: Vec<JsonTest>,
+    /// The diagnostics of the sequence, only if they were requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    diagnostics: Option<JsonDiagnostics>,
+}
+
+/// One test in the [JsonDocument].
+#[derive(Debug, Serialize)]
+struct JsonTest {
+    test: String,
+    time_ms: f64,
+    /// The test arguments that were used, empty for tests without arguments.
+    parameters: Value,
+    /// PASS, FAIL, N/A (not applicable) or ERROR
+    status: &'static str,
+    results: Vec<JsonTestResult>,
+    /// The error message, if the test returned an error.
+    error: Option<String>,
+}
+
+/// One result of a [JsonTest].
+#[derive(Debug, Serialize)]
+struct JsonTestResult {
+    result_no: usize,
+    /// Identifies the result for tests with multiple results, see [sts_lib::ResultLabel].
+    label: Option<String>,
+    passed: bool,
+    /// If the test was applicable to the input, see [sts_lib::ResultStatus].
+    applicable: bool,
+    p_value: f64,
+    comment: Option<String>,
+    /// The test statistics of the result by name, e.g. the maximum excursion of the cumulative
+    /// sums test, see [sts_lib::statistics::TestStatistics::values].
+    statistics: Option<Map<String, Value>>,
+}
+
+/// The diagnostics in the [JsonDocument], which are not part of NIST SP 800-22. Each diagnostic
+/// is missing if the sequence is too short for it.
+#[derive(Debug, Serialize)]
+struct JsonDiagnostics {
+    run_length_histogram: Option<JsonRunLengthHistogram>,
+    bit_position_bias: Option<JsonBitPositionBias>,
+}
+
+/// The counts of the run lengths, see [RunLengthHistogram]. The value at index `i` of each list
+/// belongs to the run length `i + 1`.
+#[derive(Debug, Serialize)]
+struct JsonRunLengthHistogram {
+    zeros: Vec<usize>,
+    ones: Vec<usize>,
+    /// The expected count of runs of zeros (or ones) of each length, up to the longest run.
+    expected: Vec<f64>,
+    chi_square_bins: usize,
+    /// The chi-square statistics of the runs of zeros and ones, missing if not finite.
+    chi_square_zeros: Option<f64>,
+    chi_square_ones: Option<f64>,
+}
+
+/// The bias of each bit position (0 to 7) within the bytes, see [BitPositionBias].
+#[derive(Debug, Serialize)]
+struct JsonBitPositionBias {
+    bytes: usize,
+    ones: [usize; BYTE_BITS],
+    ones_frequencies: [f64; BYTE_BITS],
+    chi_squares: [f64; BYTE_BITS],
+    total_chi_square: f64,
+}
+
+/// This struct represents a JSON file to write the test outputs.
+///
+/// The file contains one document for the whole test run. The document is rewritten after each
+/// test, so that the file always contains all results so far, even if the run is aborted.
+///
+/// With [JsonFile::append], the document is instead appended to the file as a single line, so
+/// that the file contains one document per line (JSON Lines), e.g. one per part of a split input.
+#[derive(Debug)]
+pub struct JsonFile {
+    path: PathBuf,
+    test_args: TestArgs,
+    document: JsonDocument,
+    /// With [JsonFile::append], the offset in the file where the line of the document starts.
+    line_start: Option<u64>,
+}
+
+impl JsonFile {
+    /// Create a new JSON file writer writing to the specified path. The input length and the
+    /// test arguments are used for the document contents, the threshold is used to determine if a
+    /// result passed.
+    pub fn new<P: AsRef<Path>>(
+        path: P,
+        input_length: usize,
+        test_args: TestArgs,
+        threshold: f64,
+    ) -> Result<Self, JsonFileError> {
+        let file = Self {
+            path: path.as_ref().to_path_buf(),
+            test_args,
+            document: JsonDocument {
+                part: None,
+                input_length,
+                threshold,
+                passed: true,
+                tests: Vec::new(),
+                diagnostics: None,
+            },
+            line_start: None,
+        };
+
+        // create the file right away, to report errors early.
+        file.write()?;
+        Ok(file)
+    }
+
+    /// Create a new JSON file writer appending one line to the specified path, e.g. to write all
+    /// parts of a split input to the same file. The document additionally contains the given
+    /// part. The line is rewritten after each test.
+    pub fn append<P: AsRef<Path>>(
+        path: P,
+        part: u64,
+        input_length: usize,
+        test_args: TestArgs,
+        threshold: f64,
+    ) -> Result<Self, JsonFileError> {
+        let line_start = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?
+            .metadata()?
+            .len();
+
+        let file = Self {
+            path: path.as_ref().to_path_buf(),
+            test_args,
+            document: JsonDocument {
+                part: Some(part),
+                input_length,
+                threshold,
+                passed: true,
+                tests: Vec::new(),
+                diagnostics: None,
+            },
+            line_start: Some(line_start),
+        };
+
+        file.write()?;
+        Ok(file)
+    }
+
+    /// Append the given test results to the JSON document.
+    pub fn write_test<S: AsRef<[TestResult]>>(
+        &mut self,
+        test: Test,
+        time: Duration,
+        results: Result<S, &sts_lib::Error>,
+    ) -> Result<(), JsonFileError> {
+        let time_ms = (time.as_micros() as f64) / 1000.0;
+        let parameters = test_parameters(test, &self.test_args);
+
+        let json_test = match results {
+            Ok(results) => {
+                let results = results
+                    .as_ref()
+                    .iter()
+                    .enumerate()
+                    .map(|(no, result)| JsonTestResult {
+                        result_no: no,
+                        label: result.label().map(|label| label.to_string()),
+                        passed: result.passed(self.document.threshold),
+                        applicable: result.is_applicable(),
+                        p_value: result.p_value(),
+                        comment: result.comment().map(str::to_owned),
+                        statistics: result.statistics().map(|statistics| {
+                            statistics
+                                .values()
+                                .into_iter()
+                                .map(|(name, value)| (name.to_string(), json!(value)))
+                                .collect()
+                        }),
+                    })
+                    .collect::<Vec<_>>();
+
+                let status = if results.iter().any(|result| !result.applicable) {
+                    "N/A"
+                } else if results.iter().all(|result| result.passed) {
+                    "PASS"
+                } else {
+                    "FAIL"
+                };
+
+                JsonTest {
+                    test: test.to_string(),
+                    time_ms,
+                    parameters,
+                    status,
+                    results,
+                    error: None,
+                }
+            }
+            Err(e) => JsonTest {
+                test: test.to_string(),
+                time_ms,
+                parameters,
+                status: "ERROR",
+                results: Vec::new(),
+                error: Some(e.to_string()),
+            },
+        };
+
+        if json_test.status == "FAIL" || json_test.status == "ERROR" {
+            self.document.passed = false;
+        }
+        self.document.tests.push(json_test);
+
+        self.write()
+    }
+
+    /// Add the diagnostics of the sequence to the JSON document, a missing diagnostic is written as
+    /// `null`.
+    pub fn write_diagnostics(
+        &mut self,
+        histogram: Option<&RunLengthHistogram>,
+        bias: Option<&BitPositionBias>,
+    ) -> Result<(), JsonFileError> {
+        let run_length_histogram = histogram.map(|histogram| {
+            let longest_run = usize::max(histogram.longest_run(false), histogram.longest_run(true));
+            JsonRunLengthHistogram {
+                zeros: histogram.counts(false).to_vec(),
+                ones: histogram.counts(true).to_vec(),
+                expected: (1..=longest_run)
+                    .map(|length| histogram.expected_count(length))
+                    .collect(),
+                chi_square_bins: histogram.chi_square_bins(),
+                chi_square_zeros: histogram.chi_square(false).ok(),
+                chi_square_ones: histogram.chi_square(true).ok(),
+            }
+        });
+        let bit_position_bias = bias.map(|bias| JsonBitPositionBias {
+            bytes: bias.byte_count(),
+            ones: bias.ones(),
+            ones_frequencies: bias.ones_frequencies(),
+            chi_squares: bias.chi_squares(),
+            total_chi_square: bias.total_chi_square(),
+        });
+
+        self.document.diagnostics = Some(JsonDiagnostics {
+            run_length_histogram,
+            bit_position_bias,
+        });
+        self.write()
+    }
+
+    /// (Re-)writes the whole document to the file, or only its line with [JsonFile::append].
+    fn write(&self) -> Result<(), JsonFileError> {
+        match self.line_start {
+            Some(line_start) => {
+                // everything before the line is kept
+                let file = OpenOptions::new().write(true).open(&self.path)?;
+                file.set_len(line_start)?;
+                let mut writer = BufWriter::new(file);
+                writer.seek(SeekFrom::Start(line_start))?;
+                serde_json::to_writer(&mut writer, &self.document)?;
+                writeln!(writer)?;
+                writer.flush()?;
+            }
+            None => {
+                let mut writer = BufWriter::new(File::create(&self.path)?);
+                serde_json::to_writer_pretty(&mut writer, &self.document)?;
+                writeln!(writer)?;
+                writer.flush()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The JSON document written by [write_histograms].
+#[derive(Debug, Serialize)]
+struct HistogramDocument {
+    /// The edges of the bins, from 0 to 1: bin i contains the P-values between edge i and i + 1.
+    bin_edges: Vec<f64>,
+    results: Vec<JsonHistogram>,
+}
+
+/// One result in the [HistogramDocument].
+#[derive(Debug, Serialize)]
+struct JsonHistogram {
+    test: String,
+    result_no: usize,
+    label: Option<String>,
+    comment: Option<String>,
+    sample_size: usize,
+    /// The count of P-values in each bin.
+    histogram: [usize; BIN_COUNT],
+    /// None if it could not be computed, see [FinalAnalysis::uniformity_p_value].
+    uniformity_p_value: Option<f64>,
+    /// PASS, FAIL or N/A (no uniformity P-value)
+    uniformity_status: &'static str,
+}
+
+/// Writes the histograms of the P-values of the final analysis to a JSON file, for plotting the
+/// uniformity of the P-values. The document contains the edges of the bins and, for each result:
+/// test name, result no., comment, sample size, the count of P-values in each bin, the uniformity
+/// P-value and PASS/FAIL/N/A.
+pub fn write_histograms<P: AsRef<Path>>(
+    path: P,
+    analyses: &[FinalAnalysis],
+) -> Result<(), JsonFileError> {
+    let document = HistogramDocument {
+        bin_edges: (0..=BIN_COUNT)
+            .map(|edge| edge as f64 / BIN_COUNT as f64)
+            .collect(),
+        results: analyses
+            .iter()
+            .map(|analysis| JsonHistogram {
+                test: analysis.test().to_string(),
+                result_no: analysis.result_idx(),
+                label: analysis.label().map(|label| label.to_string()),
+                comment: analysis.comment().map(str::to_owned),
+                sample_size: analysis.sample_size(),
+                histogram: analysis.histogram(),
+                uniformity_p_value: analysis.uniformity_p_value(),
+                uniformity_status: match analysis.uniformity_p_value() {
+                    Some(_) if analysis.uniformity_passed() => "PASS",
+                    Some(_) => "FAIL",
+                    None => "N/A",
+                },
+            })
+            .collect(),
+    };
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut writer, &document)?;
+    writeln!(writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads the results saved to a JSON file by [JsonFile], for each sequence in the file and each
+/// test in the order of the file. Tests with a result that is not applicable are read as
+/// [RecordedResult::NotApplicable].
+///
+/// A file contains one sequence, except for files written with [JsonFile::append]: each line is
+/// one sequence.
+pub fn read_sequences<P: AsRef<Path>>(
+    path: P,
+) -> Result<Vec<Vec<(Test, RecordedResult)>>, JsonFileError> {
+    // the fields needed for the results, all others are ignored
+    #[derive(Deserialize)]
+    struct SavedDocument {
+        tests: Vec<SavedTest>,
+    }
+
+    #[derive(Deserialize)]
+    struct SavedTest {
+        test: String,
+        status: String,
+        results: Vec<SavedTestResult>,
+    }
+
+    #[derive(Deserialize)]
+    struct SavedTestResult {
+        p_value: f64,
+    }
+
+    let reader = BufReader::new(File::open(path)?);
+
+    // a single document, or one document per line
+    serde_json::Deserializer::from_reader(reader)
+        .into_iter::<SavedDocument>()
+        .map(|document| {
+            document?
+                .tests
+                .into_iter()
+                .map(|saved| {
+                    let test = test_from_name(&saved.test)
+                        .ok_or(JsonFileError::UnknownTest(saved.test))?;
+
+                    let result = match saved.status.as_str() {
+                        "ERROR" => RecordedResult::Error,
+                        "N/A" => RecordedResult::NotApplicable,
+                        _ => RecordedResult::PValues(
+                            saved.results.iter().map(|result| result.p_value).collect(),
+                        ),
+                    };
+
+                    Ok((test, result))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The test arguments used by the given test, with the same names as in the TOML configuration.
+pub fn test_parameters(test: Test, args: &TestArgs) -> Value {
+    match test {
+        Test::FrequencyWithinABlock => match args.frequency_block {
+            FrequencyBlockTestArg::Manual(block_length) => json!({ "block-length": block_length }),
+            FrequencyBlockTestArg::ChooseAutomatically => json!({ "choose-automatically": true }),
+            FrequencyBlockTestArg::ChooseWithStrategy(strategy) => {
+                let strategy = match strategy {
+                    SelectionStrategy::NistRecommended => "nist-recommended",
+                    SelectionStrategy::MaximizeBlocks => "maximize-blocks",
+                    SelectionStrategy::PowerOfTwo => "power-of-two",
+                };
+                json!({ "choose-automatically": true, "strategy": strategy })
+            }
+        },
+        Test::SpectralDft => {
+            let threshold = match args.spectral_dft.peak_threshold() {
+                PeakThreshold::KimUmeno => "kim-umeno",
+                PeakThreshold::OriginalNist => "original-nist",
+            };
+            json!({ "threshold": threshold })
+        }
+        Test::NonOverlappingTemplateMatching => json!({
+            "template-length": args.non_overlapping_template.templates().template_len(),
+            "count-blocks": args.non_overlapping_template.count_blocks(),
+            "template-count": args.non_overlapping_template.templates().templates().len(),
+        }),
+        Test::OverlappingTemplateMatching => {
+            let arg = args.overlapping_template;
+            let mut parameters = json!({
+                "template-length": arg.template_length(),
+                "block-length": arg.block_length(),
+                "freedom": arg.freedom(),
+                "nist-behaviour": arg.nist_behaviour(),
+            });
+            if let Some(template) = arg.custom_template() {
+                let template = template >> (usize::BITS as usize - arg.template_length());
+                parameters["template"] = json!(format!(
+                    "{template:0width$b}",
+                    width = arg.template_length()
+                ));
+            }
+            parameters
+        }
+        Test::LinearComplexity => match args.linear_complexity {
+            LinearComplexityTestArg::ManualBlockLength(block_length) => {
+                json!({ "block-length": block_length })
+            }
+            LinearComplexityTestArg::ChooseAutomatically => json!({ "choose-automatically": true }),
+        },
+        Test::LongestRunOfOnes => match args.longest_run_of_ones.block_length() {
+            Some(block_length) => json!({ "block-length": block_length }),
+            None => json!({ "choose-automatically": true }),
+        },
+        Test::BinaryMatrixRank => json!({
+            "rows": args.binary_matrix_rank.rows(),
+            "cols": args.binary_matrix_rank.cols(),
+        }),
+        Test::Serial => json!({ "block-length": args.serial.block_length() }),
+        Test::ApproximateEntropy => {
+            json!({ "block-length": args.approximate_entropy.block_length() })
+        }
+        Test::RandomExcursions => json!({ "max-state": args.random_excursions.max_state() }),
+        Test::RandomExcursionsVariant => {
+            json!({ "max-state": args.random_excursions_variant.max_state() })
+        }
+        Test::Autocorrelation => json!({ "shift": args.autocorrelation.shift() }),
+        Test::Frequency
+        | Test::Runs
+        | Test::MaurersUniversalStatistical
+        | Test::CumulativeSums
+        | Test::LempelZiv => json!({}),
+    }
+}
@@ -1,11 +1,26 @@
 //! The command line arguments for this program.
 
+use clap::builder::PossibleValue;
 use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
-use sts_lib::Test;
+use std::ffi::OsStr;
+use std::path::Path;
+use std::sync::OnceLock;
+use sts_lib::bitvec::builder::ReaderFormat;
+use sts_lib::bitvec::BitOrder;
+use sts_lib::generators::Generator;
+use sts_lib::profile::Profile;
+use sts_lib::{IntoEnumIterator, Test};
 
+pub mod checkpoint;
 pub mod cmd_args;
+pub mod commands;
 pub mod csv;
+pub mod json;
+pub mod logging;
+pub mod metrics;
+pub mod nist_compat;
+pub mod report;
 pub mod toml_config;
 pub mod valid_arg;
 
@@ -43,6 +58,10 @@ pub enum ArgTest {
     RandomExcursions,
     /// Random Excursions Variant Test
     RandomExcursionsVariant,
+    /// Lempel-Ziv Compression Test (not part of the current revision of SP 800-22)
+    LempelZiv,
+    /// Autocorrelation Test (not part of SP 800-22, but of BSI AIS 31)
+    Autocorrelation,
 }
 
 // this implementation is only there to break if a test is added into sts_lib.
@@ -64,6 +83,8 @@ impl From<Test> for ArgTest {
             Test::CumulativeSums => ArgTest::CumulativeSums,
             Test::RandomExcursions => ArgTest::RandomExcursions,
             Test::RandomExcursionsVariant => ArgTest::RandomExcursionsVariant,
+            Test::LempelZiv => ArgTest::LempelZiv,
+            Test::Autocorrelation => ArgTest::Autocorrelation,
         }
     }
 }
@@ -86,10 +107,178 @@ impl From<ArgTest> for Test {
             ArgTest::CumulativeSums => Test::CumulativeSums,
             ArgTest::RandomExcursions => Test::RandomExcursions,
             ArgTest::RandomExcursionsVariant => Test::RandomExcursionsVariant,
+            ArgTest::LempelZiv => Test::LempelZiv,
+            ArgTest::Autocorrelation => Test::Autocorrelation,
         }
     }
 }
 
+/// The groups of tests that can be specified instead of single tests, see [ArgTestSelection].
+#[derive(Copy, Clone, Debug, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArgTestSet {
+    /// All tests whose runtime grows linearly with the input length.
+    Fast,
+    /// All other tests, which take the most time.
+    Slow,
+    /// All tests requiring at least 10^6 bits.
+    #[value(name = "requires-1e6-bits")]
+    #[serde(rename = "requires-1e6-bits")]
+    Requires1e6Bits,
+}
+
+impl ArgTestSet {
+    /// The tests of the group, see [Test::fast_set], [Test::slow_set] and
+    /// [Test::requires_1e6_bits].
+    pub fn tests(self) -> &'static [Test] {
+        match self {
+            ArgTestSet::Fast => Test::fast_set(),
+            ArgTestSet::Slow => Test::slow_set(),
+            ArgTestSet::Requires1e6Bits => Test::requires_1e6_bits(),
+        }
+    }
+}
+
+/// A single test or a group of tests, e.g. "runs" or "fast". Used both for command line arguments
+/// and TOML.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ArgTestSelection {
+    Test(ArgTest),
+    Set(ArgTestSet),
+}
+
+impl ArgTestSelection {
+    /// Expands the given selections into the selected tests, each test only once, in the order
+    /// they were first selected.
+    pub fn expand(selections: impl IntoIterator<Item = Self>) -> Vec<Test> {
+        let mut tests = Vec::new();
+        for selection in selections {
+            let selected = match selection {
+                ArgTestSelection::Test(test) => &[test.into()][..],
+                ArgTestSelection::Set(set) => set.tests(),
+            };
+            for &test in selected {
+                if !tests.contains(&test) {
+                    tests.push(test);
+                }
+            }
+        }
+        tests
+    }
+}
+
+// the possible values are those of ArgTest, followed by those of ArgTestSet.
+impl ValueEnum for ArgTestSelection {
+    fn value_variants<'a>() -> &'a [Self] {
+        static VARIANTS: OnceLock<Vec<ArgTestSelection>> = OnceLock::new();
+        VARIANTS.get_or_init(|| {
+            let tests = ArgTest::value_variants()
+                .iter()
+                .map(|&test| ArgTestSelection::Test(test));
+            let sets = ArgTestSet::value_variants()
+                .iter()
+                .map(|&set| ArgTestSelection::Set(set));
+            tests.chain(sets).collect()
+        })
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        match self {
+            ArgTestSelection::Test(test) => test.to_possible_value(),
+            ArgTestSelection::Set(set) => set.to_possible_value(),
+        }
+    }
+}
+
+/// Parses the name of a test, as written in the output files (e.g. "Frequency").
+pub(crate) fn test_from_name(name: &str) -> Option<Test> {
+    Test::iter().find(|test| test.to_string() == name)
+}
+
+/// The test profiles that can be specified. Used both for command line arguments and TOML.
+#[derive(Copy, Clone, Debug, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArgProfile {
+    /// All tests with the parameters of SP 800-22 and its reference implementation.
+    NistDefault,
+    /// The tests of SP 800-22 that correspond to the statistical tests of BSI AIS 31.
+    #[value(name = "bsi-ais31")]
+    #[serde(rename = "bsi-ais31")]
+    BsiAis31,
+    /// Only the fast tests with low input length requirements.
+    FastScreening,
+    /// Not the tests of SP 800-22, but the 4 power-up tests of FIPS 140-2 on the first 20000 bits,
+    /// as a quick check with a combined verdict. See [sts_lib::fips140].
+    #[value(name = "fips140-quick")]
+    #[serde(rename = "fips140-quick")]
+    Fips140Quick,
+}
+
+impl ArgProfile {
+    /// The corresponding profile of the library, `None` for [ArgProfile::Fips140Quick], which
+    /// runs the FIPS 140-2 tests instead of a selection of tests.
+    pub fn profile(self) -> Option<Profile> {
+        match self {
+            ArgProfile::NistDefault => Some(Profile::NistDefault),
+            ArgProfile::BsiAis31 => Some(Profile::BsiAis31),
+            ArgProfile::FastScreening => Some(Profile::FastScreening),
+            ArgProfile::Fips140Quick => None,
+        }
+    }
+}
+
+/// The reference generators that can be specified for the subcommand "generate".
+#[derive(Copy, Clone, Debug, PartialEq, ValueEnum)]
+pub enum ArgGenerator {
+    /// Linear congruential generator
+    LinearCongruential,
+    /// Quadratic congruential generator I
+    #[value(name = "quadratic-congruential-1")]
+    QuadraticCongruential1,
+    /// Quadratic congruential generator II
+    #[value(name = "quadratic-congruential-2")]
+    QuadraticCongruential2,
+    /// Cubic congruential generator
+    CubicCongruential,
+    /// Exclusive OR generator
+    Xor,
+    /// Modular exponentiation generator
+    ModularExponentiation,
+    /// Secure hash generator, based on SHA-1
+    #[value(name = "g-sha1")]
+    GSha1,
+    /// Blum-Blum-Shub generator
+    BlumBlumShub,
+    /// Micali-Schnorr generator
+    MicaliSchnorr,
+}
+
+impl From<ArgGenerator> for Generator {
+    fn from(value: ArgGenerator) -> Self {
+        match value {
+            ArgGenerator::LinearCongruential => Generator::LinearCongruential,
+            ArgGenerator::QuadraticCongruential1 => Generator::QuadraticCongruential1,
+            ArgGenerator::QuadraticCongruential2 => Generator::QuadraticCongruential2,
+            ArgGenerator::CubicCongruential => Generator::CubicCongruential,
+            ArgGenerator::Xor => Generator::Xor,
+            ArgGenerator::ModularExponentiation => Generator::ModularExponentiation,
+            ArgGenerator::GSha1 => Generator::GSha1,
+            ArgGenerator::BlumBlumShub => Generator::BlumBlumShub,
+            ArgGenerator::MicaliSchnorr => Generator::MicaliSchnorr,
+        }
+    }
+}
+
+/// The formats a generated sequence can be written in.
+#[derive(Copy, Clone, Debug, PartialEq, ValueEnum)]
+pub enum GeneratedFormat {
+    /// Binary output, 8 bits per byte. The last byte is padded with zeros.
+    Binary,
+    /// ASCII text consisting of only '0' or '1'.
+    Ascii,
+}
+
 /// The input file formats that can be specified. Used both for command line arguments and TOML.
 #[derive(Copy, Clone, Debug, PartialEq, ValueEnum, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -101,4 +290,99 @@ pub enum InputFormat {
     /// Input is an ASCII text file consisting of any character. Characters other than '0' or '1'
     /// are skipped.
     AsciiLossy,
+    /// Input is an ASCII text file consisting of '0' or '1', grouped with whitespace and newlines
+    /// like the data files of the NIST reference implementation. Whitespace and newlines are
+    /// skipped, no other character is allowed.
+    AsciiWhitespace,
+    /// Input is a text file of hexadecimal digits, each containing 4 bits. Whitespace and newlines
+    /// are skipped, no other character is allowed.
+    Hex,
+    /// Input is a text file of any character. Characters other than hexadecimal digits are
+    /// skipped.
+    HexLossy,
+    /// Input is a base64 encoded text file (standard alphabet, padding is optional). Whitespace
+    /// and newlines are skipped, no other character is allowed.
+    Base64,
+}
+
+impl From<InputFormat> for ReaderFormat {
+    fn from(value: InputFormat) -> Self {
+        match value {
+            InputFormat::Binary => ReaderFormat::Binary,
+            InputFormat::Ascii => ReaderFormat::Ascii,
+            InputFormat::AsciiLossy => ReaderFormat::AsciiLossy,
+            InputFormat::AsciiWhitespace => ReaderFormat::AsciiWhitespace,
+            InputFormat::Hex => ReaderFormat::Hex,
+            InputFormat::HexLossy => ReaderFormat::HexLossy,
+            InputFormat::Base64 => ReaderFormat::Base64,
+        }
+    }
+}
+
+/// The order of the bits in each byte of binary or base64 input. Used both for command line
+/// arguments and TOML.
+#[derive(Copy, Clone, Debug, Default, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArgBitOrder {
+    /// The first bit is the most significant bit of each byte.
+    #[default]
+    MsbFirst,
+    /// The first bit is the least significant bit of each byte, as in some hardware captures.
+    LsbFirst,
+}
+
+impl From<ArgBitOrder> for BitOrder {
+    fn from(value: ArgBitOrder) -> Self {
+        match value {
+            ArgBitOrder::MsbFirst => BitOrder::MsbFirst,
+            ArgBitOrder::LsbFirst => BitOrder::LsbFirst,
+        }
+    }
+}
+
+/// The output file formats that can be specified. Used both for command line arguments and TOML.
+#[derive(Copy, Clone, Debug, Default, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    /// CSV with ';' delimiter, one row per test result.
+    #[default]
+    Csv,
+    /// One JSON document per test run.
+    Json,
+}
+
+impl OutputFormat {
+    /// The format of a file, detected by its extension: files ending with ".json" are JSON, all
+    /// other files CSV.
+    pub fn from_path(path: &Path) -> Self {
+        if path.extension() == Some(OsStr::new("json")) {
+            OutputFormat::Json
+        } else {
+            OutputFormat::Csv
+        }
+    }
+}
+
+/// The notation of the floating point values in CSV files, see
+/// [CsvOptions](crate::csv::CsvOptions). Used both for command line arguments and TOML.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CsvNotation {
+    /// The shortest representation that is read back exactly, with an exponent only for very
+    /// small or large values, e.g. "0.0123" or "1.5e-20".
+    #[default]
+    Auto,
+    /// Always without an exponent, e.g. "0.0123" or "0.000000000000000000015".
+    Fixed,
+    /// Always with an exponent, e.g. "1.23e-2" or "1.5e-20".
+    Scientific,
+}
+
+/// The formats of the console output that can be specified.
+#[derive(Copy, Clone, Debug, PartialEq, ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable text.
+    Text,
+    /// One JSON object per line and event, including all fields of the event.
+    Json,
 }
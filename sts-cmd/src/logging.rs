@@ -0,0 +1,136 @@
+//! Everything needed for the console output, which is done with [tracing] events.
+//!
+//! By default, the events are printed as human-readable text, i.e. only the message of each event.
+//! With a higher verbosity, the level, the span (e.g. the currently running test) and all fields
+//! of each event are printed as well. With [LogFormat::Json], each event is printed as one JSON
+//! object per line, including all fields, to be parsed by other programs.
+//!
+//! The summaries of test runs (the summary of each run, the final analysis and the verdicts) use
+//! the target [SUMMARY], they are printed even in quiet mode. The same applies to the alerts of
+//! the subcommand "monitor", which use the target [ALERT].
+//!
+//! The single line verdict of each tested sequence uses the target [VERDICT], it is only printed
+//! in summary-only mode, which prints nothing else except for errors and alerts.
+
+use crate::LogFormat;
+use std::{fmt, io};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::filter::{LevelFilter, Targets};
+use tracing_subscriber::fmt::format::{FmtSpan, Writer};
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
+
+/// The target of the events that summarize test runs, printed even in quiet mode.
+pub const SUMMARY: &str = "sts_cmd::summary";
+/// The target of the alerts of the subcommand "monitor", which are warnings and thus printed even
+/// in quiet mode.
+pub const ALERT: &str = "sts_cmd::alert";
+/// The target of the single line verdict of each tested sequence, only printed in summary-only
+/// mode.
+pub const VERDICT: &str = "sts_cmd::verdict";
+
+/// Initializes the console output for the whole program. The output is written to stdout, or to
+/// stderr if `use_stderr` is set, e.g. because stdout is used for data.
+///
+/// The verbosity is:
+/// - quiet: only the summaries of test runs, warnings and errors.
+/// - 0 (default): additionally, the result of every test and the progress.
+/// - 1: additionally, the duration of each test (the test spans) and details about the input,
+///   with the level, span and all fields of each event.
+/// - 2 or more: all events, including those of the library.
+///
+/// In summary-only mode, only the verdicts (see [VERDICT]), alerts and errors are printed.
+pub fn init(quiet: bool, verbosity: u8, summary_only: bool, format: LogFormat, use_stderr: bool) {
+    let level = match (quiet, verbosity) {
+        (true, _) => Level::WARN,
+        (false, 0) => Level::INFO,
+        (false, 1) => Level::DEBUG,
+        (false, _) => Level::TRACE,
+    };
+
+    let filter = if summary_only {
+        Targets::new()
+            .with_target(VERDICT, Level::INFO)
+            .with_target(ALERT, Level::WARN)
+            .with_target("sts_cmd", Level::ERROR)
+            .with_target("sts_lib", Level::ERROR)
+    } else {
+        Targets::new()
+            .with_target(VERDICT, LevelFilter::OFF)
+            .with_target(SUMMARY, Level::INFO.max(level))
+            .with_target("sts_cmd", level)
+            .with_target("sts_lib", if verbosity >= 2 { level } else { Level::WARN })
+    };
+
+    let span_events = if verbosity >= 1 {
+        FmtSpan::CLOSE
+    } else {
+        FmtSpan::NONE
+    };
+
+    let writer = BoxMakeWriter::new(move || -> Box<dyn io::Write> {
+        if use_stderr {
+            Box::new(io::stderr())
+        } else {
+            Box::new(io::stdout())
+        }
+    });
+
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(writer)
+        .with_span_events(span_events);
+
+    let layer = match format {
+        LogFormat::Text if verbosity == 0 => layer.event_format(MessageOnly).boxed(),
+        LogFormat::Text => layer.without_time().boxed(),
+        LogFormat::Json => layer.json().with_current_span(true).boxed(),
+    };
+
+    tracing_subscriber::registry()
+        .with(layer.with_filter(filter))
+        .init();
+}
+
+/// Formats an event as its message only - the human-readable default output.
+struct MessageOnly;
+
+impl<S, N> FormatEvent<S, N> for MessageOnly
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        _ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        /// Extracts the message of the event.
+        struct MessageVisitor<'a, 'w> {
+            writer: &'a mut Writer<'w>,
+            result: fmt::Result,
+        }
+
+        impl Visit for MessageVisitor<'_, '_> {
+            fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+                if field.name() == "message" {
+                    self.result = write!(self.writer, "{value:?}");
+                }
+            }
+        }
+
+        let mut visitor = MessageVisitor {
+            writer: &mut writer,
+            result: Ok(()),
+        };
+        event.record(&mut visitor);
+        visitor.result?;
+
+        writeln!(writer)
+    }
+}
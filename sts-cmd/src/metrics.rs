@@ -0,0 +1,285 @@
+//! Prometheus metrics for the subcommand "monitor", see [Metrics].
+//!
+//! The metrics are served by a minimal HTTP server on a background thread, in the text-based
+//! exposition format of Prometheus, so that the health of a monitored RNG can be integrated into
+//! an existing alerting infrastructure. Only the path "/metrics" is served.
+
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sts_lib::{Test, TestResult};
+
+/// The time a client may take to send its request, before the connection is closed.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The metrics of a monitoring run, updated after each test and window and rendered on each
+/// request.
+#[derive(Debug)]
+pub struct Metrics {
+    window_bits: usize,
+    interval_bits: usize,
+    threshold: f64,
+    state: Mutex<MetricsState>,
+}
+
+#[derive(Debug, Default)]
+struct MetricsState {
+    windows: u64,
+    alert_windows: u64,
+    last_window: Option<SystemTime>,
+    tests: Vec<TestMetrics>,
+}
+
+/// The metrics of a single test.
+#[derive(Debug)]
+struct TestMetrics {
+    test: Test,
+    failures: u64,
+    errors: u64,
+    /// The index, label and P-value of each applicable result of the latest window.
+    p_values: Vec<(usize, String, f64)>,
+}
+
+impl Metrics {
+    /// Creates the metrics for monitoring the given tests. The counters of all tests start at 0,
+    /// so they are exported before the first failure.
+    pub fn new(tests: &[Test], window_bits: usize, interval_bits: usize, threshold: f64) -> Self {
+        let tests = tests
+            .iter()
+            .map(|&test| TestMetrics {
+                test,
+                failures: 0,
+                errors: 0,
+                p_values: Vec::new(),
+            })
+            .collect();
+
+        Self {
+            window_bits,
+            interval_bits,
+            threshold,
+            state: Mutex::new(MetricsState {
+                tests,
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Records the results of a test on the current window: the P-values replace the ones of the
+    /// previous window, each result below the threshold counts as a failure.
+    pub fn record_results(&self, test: Test, results: &[TestResult]) {
+        self.with_test(test, |metrics| {
+            metrics.p_values = results
+                .iter()
+                .enumerate()
+                .filter(|(_, result)| result.is_applicable())
+                .map(|(idx, result)| {
+                    let label = result.label().map(|l| l.to_string()).unwrap_or_default();
+                    (idx, label, result.p_value())
+                })
+                .collect();
+            metrics.failures += results
+                .iter()
+                .filter(|result| result.is_applicable() && !result.passed(self.threshold))
+                .count() as u64;
+        });
+    }
+
+    /// Records that a test returned an error on the current window. The P-values of the previous
+    /// window are removed.
+    pub fn record_error(&self, test: Test) {
+        self.with_test(test, |metrics| {
+            metrics.p_values.clear();
+            metrics.errors += 1;
+        });
+    }
+
+    /// Records a completed window with the given count of alerts.
+    pub fn record_window(&self, alerts: usize) {
+        let mut state = self.lock();
+        state.windows += 1;
+        if alerts > 0 {
+            state.alert_windows += 1;
+        }
+        state.last_window = Some(SystemTime::now());
+    }
+
+    /// Renders the metrics in the text-based exposition format of Prometheus.
+    pub fn render(&self) -> String {
+        let state = self.lock();
+        let mut out = String::new();
+
+        let mut metric = |name: &str, kind: &str, help: &str, samples: &[(String, f64)]| {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} {kind}");
+            for (labels, value) in samples {
+                let _ = writeln!(out, "{name}{labels} {value}");
+            }
+        };
+
+        metric(
+            "sts_monitor_windows_total",
+            "counter",
+            "The count of tested windows.",
+            &[(String::new(), state.windows as f64)],
+        );
+        metric(
+            "sts_monitor_alert_windows_total",
+            "counter",
+            "The count of tested windows with at least one alert.",
+            &[(String::new(), state.alert_windows as f64)],
+        );
+        let last_window = state
+            .last_window
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0.0, |time| time.as_secs_f64());
+        metric(
+            "sts_monitor_last_window_timestamp_seconds",
+            "gauge",
+            "The time the latest window was tested, in seconds since the Unix epoch. 0 before the \
+             first window.",
+            &[(String::new(), last_window)],
+        );
+        metric(
+            "sts_monitor_window_bits",
+            "gauge",
+            "The length of each tested window in bits.",
+            &[(String::new(), self.window_bits as f64)],
+        );
+        metric(
+            "sts_monitor_interval_bits",
+            "gauge",
+            "The count of new bits between two windows.",
+            &[(String::new(), self.interval_bits as f64)],
+        );
+        metric(
+            "sts_monitor_threshold",
+            "gauge",
+            "The threshold an alert is raised below.",
+            &[(String::new(), self.threshold)],
+        );
+
+        let p_values = state
+            .tests
+            .iter()
+            .flat_map(|metrics| {
+                metrics.p_values.iter().map(|(idx, label, p_value)| {
+                    let labels = format!(
+                        "{{test=\"{}\",result=\"{idx}\",label=\"{}\"}}",
+                        metrics.test,
+                        escape_label(label)
+                    );
+                    (labels, *p_value)
+                })
+            })
+            .collect::<Vec<_>>();
+        metric(
+            "sts_monitor_p_value",
+            "gauge",
+            "The P-value of each applicable result of the latest window.",
+            &p_values,
+        );
+
+        let per_test = |value: fn(&TestMetrics) -> u64| {
+            state
+                .tests
+                .iter()
+                .map(|metrics| {
+                    let labels = format!("{{test=\"{}\"}}", metrics.test);
+                    (labels, value(metrics) as f64)
+                })
+                .collect::<Vec<_>>()
+        };
+        metric(
+            "sts_monitor_test_failures_total",
+            "counter",
+            "The count of results below the threshold, per test.",
+            &per_test(|metrics| metrics.failures),
+        );
+        metric(
+            "sts_monitor_test_errors_total",
+            "counter",
+            "The count of windows the test returned an error on, per test.",
+            &per_test(|metrics| metrics.errors),
+        );
+
+        out
+    }
+
+    /// Starts serving the metrics on the given address, e.g. "127.0.0.1:9464", on a background
+    /// thread. Returns the bound address, which differs from the given one for port 0.
+    pub fn serve(self: Arc<Self>, addr: SocketAddr) -> io::Result<SocketAddr> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+
+        thread::Builder::new()
+            .name("metrics".to_owned())
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    let result = stream.and_then(|stream| self.handle_connection(stream));
+                    if let Err(e) = result {
+                        tracing::debug!(error = %e, "Metrics request failed: {e}");
+                    }
+                }
+            })?;
+
+        Ok(addr)
+    }
+
+    /// Answers a single HTTP request, the connection is closed afterward.
+    fn handle_connection(&self, stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+        let mut reader = BufReader::new(&stream);
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        // the headers are not needed, but are read so that the client receives the response
+        let mut header = String::new();
+        while reader.read_line(&mut header)? > 2 {
+            header.clear();
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let (status, body) = match (parts.next(), parts.next()) {
+            (Some("GET" | "HEAD"), Some("/metrics")) => ("200 OK", self.render()),
+            (Some("GET" | "HEAD"), Some(_)) => ("404 Not Found", "Not found\n".to_owned()),
+            _ => ("405 Method Not Allowed", "Method not allowed\n".to_owned()),
+        };
+
+        let mut stream = &stream;
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4; charset=utf-8\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n",
+            body.len()
+        )?;
+        // a response to HEAD has no body
+        if !request_line.starts_with("HEAD") {
+            stream.write_all(body.as_bytes())?;
+        }
+        stream.flush()
+    }
+
+    fn with_test(&self, test: Test, f: impl FnOnce(&mut TestMetrics)) {
+        let mut state = self.lock();
+        if let Some(metrics) = state.tests.iter_mut().find(|metrics| metrics.test == test) {
+            f(metrics);
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MetricsState> {
+        // the metrics stay consistent enough to be served even if an update panicked
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Escapes a label value for the exposition format.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,272 @@
+//! Output in the directory layout of the NIST reference implementation (the tool `assess`), so
+//! that scripts processing its output can be used with this application, see [NistCompatOutput].
+//!
+//! The layout is:
+//!
+//! - `experiments/AlgorithmTesting/<TEST>/results.txt`: the P-values of each tested sequence, one
+//!   per line, in the order of the sequences.
+//! - `experiments/AlgorithmTesting/<TEST>/stats.txt`: the verdict and P-value of each result of each
+//!   tested sequence. The reference implementation writes the intermediate values of the
+//!   computation here, which are not available.
+//! - `experiments/AlgorithmTesting/finalAnalysisReport.txt`: the final analysis over all tested
+//!   sequences, in the format of the reference implementation.
+//!
+//! `<TEST>` is the name of the test used by the reference implementation, see [test_dir_name].
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use sts_lib::test_runner::multi_sequence::{FinalAnalysis, RecordedResult};
+use sts_lib::Test;
+
+/// The directory of all tests of a sequence given as input file, as used by the reference
+/// implementation. The directories of its own generators are not used.
+pub const GENERATOR_DIR: &str = "AlgorithmTesting";
+
+/// The separator lines of the final analysis report.
+const SEPARATOR: &str =
+    "------------------------------------------------------------------------------";
+const DASHED_SEPARATOR: &str =
+    "- - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - -";
+
+/// The name of the test used by the reference implementation, as directory name and in the final
+/// analysis report. The tests that are not part of the reference implementation use their name
+/// in the same style.
+pub fn test_dir_name(test: Test) -> &'static str {
+    match test {
+        Test::Frequency => "Frequency",
+        Test::FrequencyWithinABlock => "BlockFrequency",
+        Test::Runs => "Runs",
+        Test::LongestRunOfOnes => "LongestRun",
+        Test::BinaryMatrixRank => "Rank",
+        Test::SpectralDft => "FFT",
+        Test::NonOverlappingTemplateMatching => "NonOverlappingTemplate",
+        Test::OverlappingTemplateMatching => "OverlappingTemplate",
+        Test::MaurersUniversalStatistical => "Universal",
+        Test::LinearComplexity => "LinearComplexity",
+        Test::Serial => "Serial",
+        Test::ApproximateEntropy => "ApproximateEntropy",
+        Test::CumulativeSums => "CumulativeSums",
+        Test::RandomExcursions => "RandomExcursions",
+        Test::RandomExcursionsVariant => "RandomExcursionsVariant",
+        Test::LempelZiv => "LempelZivCompression",
+        Test::Autocorrelation => "Autocorrelation",
+    }
+}
+
+/// The position of the test in the final analysis report of the reference implementation. The
+/// tests that are not part of the reference implementation come last.
+fn report_position(test: Test) -> u8 {
+    match test {
+        Test::Frequency => 0,
+        Test::FrequencyWithinABlock => 1,
+        Test::CumulativeSums => 2,
+        Test::Runs => 3,
+        Test::LongestRunOfOnes => 4,
+        Test::BinaryMatrixRank => 5,
+        Test::SpectralDft => 6,
+        Test::NonOverlappingTemplateMatching => 7,
+        Test::OverlappingTemplateMatching => 8,
+        Test::MaurersUniversalStatistical => 9,
+        Test::ApproximateEntropy => 10,
+        Test::RandomExcursions => 11,
+        Test::RandomExcursionsVariant => 12,
+        Test::Serial => 13,
+        Test::LinearComplexity => 14,
+        Test::LempelZiv => 15,
+        Test::Autocorrelation => 16,
+    }
+}
+
+/// The files `results.txt` and `stats.txt` of one test.
+#[derive(Debug)]
+struct TestFiles {
+    results: BufWriter<File>,
+    stats: BufWriter<File>,
+}
+
+/// Writes the results of the tested sequences in the directory layout of the reference
+/// implementation, see the [module documentation](self).
+#[derive(Debug)]
+pub struct NistCompatOutput {
+    /// The directory `experiments/AlgorithmTesting`.
+    dir: PathBuf,
+    files: HashMap<Test, TestFiles>,
+    threshold: f64,
+}
+
+impl NistCompatOutput {
+    /// Creates the directory `experiments/AlgorithmTesting` inside the given directory, with the
+    /// files `results.txt` and `stats.txt` for each of the given tests. Existing files are
+    /// overwritten. The threshold is used to determine if a result passed.
+    pub fn new(root: &Path, tests: &[Test], threshold: f64) -> io::Result<Self> {
+        let dir = root.join("experiments").join(GENERATOR_DIR);
+
+        let files = tests
+            .iter()
+            .map(|&test| {
+                let test_dir = dir.join(test_dir_name(test));
+                fs::create_dir_all(&test_dir)?;
+
+                let files = TestFiles {
+                    results: BufWriter::new(File::create(test_dir.join("results.txt"))?),
+                    stats: BufWriter::new(File::create(test_dir.join("stats.txt"))?),
+                };
+                Ok((test, files))
+            })
+            .collect::<io::Result<_>>()?;
+
+        Ok(Self {
+            dir,
+            files,
+            threshold,
+        })
+    }
+
+    /// Appends the results of one tested sequence. Results of tests that were not given to
+    /// [NistCompatOutput::new] are ignored.
+    ///
+    /// Like the reference implementation, only P-values are written to `results.txt`: a test that
+    /// was not applicable to the sequence or returned an error is only noted in `stats.txt`.
+    pub fn write_sequence(&mut self, results: &[(Test, RecordedResult)]) -> io::Result<()> {
+        for (test, result) in results {
+            let Some(files) = self.files.get_mut(test) else {
+                continue;
+            };
+
+            match result {
+                RecordedResult::PValues(p_values) => {
+                    for &p_value in p_values {
+                        let verdict = if p_value >= self.threshold {
+                            "SUCCESS"
+                        } else {
+                            "FAILURE"
+                        };
+
+                        writeln!(files.results, "{p_value:.6}")?;
+                        writeln!(files.stats, "{verdict}\t\tp_value = {p_value:.6}")?;
+                    }
+                }
+                RecordedResult::NotApplicable => {
+                    writeln!(files.stats, "WARNING:  TEST NOT APPLICABLE")?;
+                }
+                RecordedResult::Error => writeln!(files.stats, "ERROR:  TEST FAILED")?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes `finalAnalysisReport.txt`, with the name of the input given as generator, and
+    /// flushes all files.
+    pub fn write_final_analysis(
+        &mut self,
+        input_name: &str,
+        mut analyses: Vec<FinalAnalysis>,
+    ) -> io::Result<()> {
+        for files in self.files.values_mut() {
+            files.results.flush()?;
+            files.stats.flush()?;
+        }
+
+        analyses.sort_by_key(|analysis| (report_position(analysis.test()), analysis.result_idx()));
+
+        let mut report = BufWriter::new(File::create(self.dir.join("finalAnalysisReport.txt"))?);
+
+        writeln!(report, "{SEPARATOR}")?;
+        writeln!(
+            report,
+            "RESULTS FOR THE UNIFORMITY OF P-VALUES AND THE PROPORTION OF PASSING SEQUENCES"
+        )?;
+        writeln!(report, "{SEPARATOR}")?;
+        writeln!(report, "   generator is <{input_name}>")?;
+        writeln!(report, "{SEPARATOR}")?;
+        writeln!(
+            report,
+            " C1  C2  C3  C4  C5  C6  C7  C8  C9 C10  P-VALUE  PROPORTION  STATISTICAL TEST"
+        )?;
+        writeln!(report, "{SEPARATOR}")?;
+
+        for analysis in &analyses {
+            for count in analysis.histogram() {
+                write!(report, "{count:3} ")?;
+            }
+
+            match analysis.uniformity_p_value() {
+                Some(p_value) if p_value < 0.0001 => write!(report, " {p_value:8.6} * ")?,
+                Some(p_value) => write!(report, " {p_value:8.6}   ")?,
+                None => write!(report, "    ----    ")?,
+            }
+
+            let name = test_dir_name(analysis.test());
+            let sample_size = analysis.sample_size();
+            if sample_size == 0 {
+                writeln!(report, " ------     {name}")?;
+            } else {
+                let marker = if analysis.proportion_passed() {
+                    ' '
+                } else {
+                    '*'
+                };
+                let passed = analysis.passed_count();
+                writeln!(report, "{passed:4}/{sample_size:<4} {marker}  {name}")?;
+            }
+        }
+
+        writeln!(report, "\n")?;
+        writeln!(report, "{DASHED_SEPARATOR}")?;
+
+        let is_random_excursions = |analysis: &&FinalAnalysis| {
+            matches!(
+                analysis.test(),
+                Test::RandomExcursions | Test::RandomExcursionsVariant
+            )
+        };
+        if let Some(analysis) = analyses.iter().find(|a| !is_random_excursions(a)) {
+            writeln!(
+                report,
+                "The minimum pass rate for each statistical test with the exception of the"
+            )?;
+            writeln!(
+                report,
+                "random excursion (variant) test is approximately = {} for a",
+                min_pass_count(analysis)
+            )?;
+            writeln!(
+                report,
+                "sample size = {} binary sequences.\n",
+                analysis.sample_size()
+            )?;
+        }
+        if let Some(analysis) = analyses.iter().find(is_random_excursions) {
+            writeln!(
+                report,
+                "The minimum pass rate for the random excursion (variant) test"
+            )?;
+            writeln!(
+                report,
+                "is approximately = {} for a sample size = {} binary sequences.\n",
+                min_pass_count(analysis),
+                analysis.sample_size()
+            )?;
+        }
+        writeln!(
+            report,
+            "For further guidelines construct a probability table using the MAPLE program"
+        )?;
+        writeln!(
+            report,
+            "provided in the addendum section of the documentation."
+        )?;
+        writeln!(report, "{DASHED_SEPARATOR}")?;
+
+        report.flush()
+    }
+}
+
+/// The minimum count of passed sequences that lies in the acceptance interval of the analysis.
+fn min_pass_count(analysis: &FinalAnalysis) -> usize {
+    let (min, _) = analysis.proportion_range();
+    (min * analysis.sample_size() as f64).max(0.0) as usize
+}
@@ -0,0 +1,536 @@
+//! Human-readable reports of a test run as HTML or Markdown, see [Report].
+//!
+//! The reports are rendered with the templates in the directory `templates`: a summary table,
+//! the P-values of each test (for a single sequence) or the tested parts and the final analysis
+//! with the histograms of the P-values (for split runs), the test arguments and information about
+//! the environment.
+
+use crate::json::test_parameters;
+use serde::Serialize;
+use serde_json::Value;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sts_lib::analysis::TestVerdict;
+use sts_lib::test_runner::multi_sequence::FinalAnalysis;
+use sts_lib::{IntoEnumIterator, Test, TestArgs, TestResult};
+use tinytemplate::TinyTemplate;
+
+/// The template of HTML reports.
+const HTML_TEMPLATE: &str = include_str!("../templates/report.html");
+/// The template of Markdown reports.
+const MARKDOWN_TEMPLATE: &str = include_str!("../templates/report.md");
+
+/// The characters used for the histograms in Markdown reports, from the lowest to the highest bar.
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+/// The height of the highest bar of the histograms in HTML reports, in pixels.
+const MAX_BAR_HEIGHT: usize = 40;
+
+/// Error type for [Report]
+#[derive(Debug)]
+pub enum ReportError {
+    Io(std::io::Error),
+    Template(tinytemplate::error::Error),
+}
+
+impl Display for ReportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReportError::Io(e) => write!(f, "IO error: {e}"),
+            ReportError::Template(e) => write!(f, "Template error: {e}"),
+        }
+    }
+}
+
+impl Error for ReportError {}
+
+impl From<std::io::Error> for ReportError {
+    fn from(value: std::io::Error) -> Self {
+        ReportError::Io(value)
+    }
+}
+
+impl From<tinytemplate::error::Error> for ReportError {
+    fn from(value: tinytemplate::error::Error) -> Self {
+        ReportError::Template(value)
+    }
+}
+
+/// The format of a [Report].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ReportFormat {
+    Html,
+    Markdown,
+}
+
+impl ReportFormat {
+    /// The format for the extension of the given path: ".html" or ".htm" for HTML, ".md" or
+    /// ".markdown" for Markdown (case-insensitive). `None` for all other extensions.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+
+        match extension.as_str() {
+            "html" | "htm" => Some(ReportFormat::Html),
+            "md" | "markdown" => Some(ReportFormat::Markdown),
+            _ => None,
+        }
+    }
+}
+
+/// A human-readable report of a test run, collecting the results of each tested sequence until
+/// it is written with [Report::write].
+#[derive(Debug)]
+pub struct Report {
+    path: PathBuf,
+    format: ReportFormat,
+    input_name: String,
+    test_args: TestArgs,
+    threshold: f64,
+    sequences: Vec<SequenceContext>,
+    final_analysis: Vec<AnalysisContext>,
+    verdicts: Vec<TestVerdict>,
+    /// All tests that were run, for the test arguments in the report.
+    tests: Vec<Test>,
+}
+
+/// All values available in the templates.
+#[derive(Debug, Serialize)]
+struct ReportContext<'a> {
+    input: &'a str,
+    threshold: f64,
+    /// PASSED or FAILED
+    verdict: &'static str,
+    sequence_count: usize,
+    is_split: bool,
+    summary: Vec<SummaryRow>,
+    /// The tests of the only sequence, empty for split runs.
+    tests: &'a [TestContext],
+    /// The tested sequences of split runs, empty for a single sequence.
+    sequences: &'a [SequenceContext],
+    final_analysis: &'a [AnalysisContext],
+    parameters: Vec<ParameterRow>,
+    /// The timeout of each test, if any.
+    timeout: Option<String>,
+    environment: EnvironmentContext,
+}
+
+/// One row of the summary table: the result of a test on a single sequence, or its verdict over
+/// all sequences.
+#[derive(Debug, Serialize)]
+struct SummaryRow {
+    test: String,
+    status: &'static str,
+    details: String,
+}
+
+/// One tested sequence.
+#[derive(Debug, Serialize)]
+struct SequenceContext {
+    /// The index of the part, for split runs.
+    part: Option<u64>,
+    input_length: usize,
+    passed: bool,
+    /// The tests that failed or returned an error, comma-separated.
+    failed_tests: String,
+    tests: Vec<TestContext>,
+}
+
+/// The results of one test on one sequence.
+#[derive(Debug, Serialize)]
+struct TestContext {
+    test: String,
+    /// PASS, FAIL, N/A (not applicable) or ERROR
+    status: &'static str,
+    time_ms: f64,
+    results: Vec<ResultContext>,
+    /// The error message, if the test returned an error.
+    error: Option<String>,
+}
+
+/// One result of a [TestContext].
+#[derive(Debug, Serialize)]
+struct ResultContext {
+    result_no: usize,
+    /// PASS, FAIL or N/A (not applicable)
+    status: &'static str,
+    p_value: String,
+    comment: String,
+}
+
+/// The final analysis of one result of one test over all sequences of a split run.
+#[derive(Debug, Serialize)]
+struct AnalysisContext {
+    test: String,
+    result_no: usize,
+    comment: String,
+    passed_count: usize,
+    sample_size: usize,
+    proportion_passed: bool,
+    uniformity_p_value: Option<String>,
+    uniformity_passed: bool,
+    /// The histogram of the P-values as one character per bin, for text formats.
+    histogram: String,
+    bins: Vec<BinContext>,
+}
+
+/// One bin of the histogram of an [AnalysisContext].
+#[derive(Debug, Serialize)]
+struct BinContext {
+    count: usize,
+    /// The height of the bar in pixels.
+    height: usize,
+}
+
+/// The test arguments of one test.
+#[derive(Debug, Serialize)]
+struct ParameterRow {
+    test: String,
+    parameters: String,
+}
+
+/// Information about the environment the tests were run in.
+#[derive(Debug, Serialize)]
+struct EnvironmentContext {
+    version: &'static str,
+    os: &'static str,
+    arch: &'static str,
+    threads: usize,
+    created: String,
+}
+
+impl Report {
+    /// Creates a new report that is written to the given path, in the given format. The input
+    /// name, the test arguments and the threshold are shown in the report, the threshold is also
+    /// used to determine if a result passed.
+    pub fn new(
+        path: PathBuf,
+        format: ReportFormat,
+        input_name: String,
+        test_args: TestArgs,
+        threshold: f64,
+    ) -> Self {
+        Self {
+            path,
+            format,
+            input_name,
+            test_args,
+            threshold,
+            sequences: Vec::new(),
+            final_analysis: Vec::new(),
+            verdicts: Vec::new(),
+            tests: Vec::new(),
+        }
+    }
+
+    /// Starts a new tested sequence, the results of the tests are added with [Report::add_test].
+    /// The index of the part is given for split runs.
+    pub fn start_sequence(&mut self, part: Option<u64>, input_length: usize) {
+        self.sequences.push(SequenceContext {
+            part,
+            input_length,
+            passed: true,
+            failed_tests: String::new(),
+            tests: Vec::new(),
+        });
+    }
+
+    /// Adds the results of a test to the current sequence, see [Report::start_sequence].
+    pub fn add_test(
+        &mut self,
+        test: Test,
+        time: Duration,
+        results: Result<&Vec<TestResult>, &sts_lib::Error>,
+    ) {
+        let threshold = self.threshold;
+        let Some(sequence) = self.sequences.last_mut() else {
+            return;
+        };
+        if !self.tests.contains(&test) {
+            self.tests.push(test);
+        }
+        let time_ms = (time.as_micros() as f64) / 1000.0;
+
+        let test_context = match results {
+            Ok(results) => {
+                let results = results
+                    .iter()
+                    .enumerate()
+                    .map(|(no, result)| ResultContext {
+                        result_no: no,
+                        status: result_status(result, threshold),
+                        p_value: format!("{:.6}", result.p_value()),
+                        comment: result.comment().unwrap_or_default().to_owned(),
+                    })
+                    .collect::<Vec<_>>();
+
+                let status = if results.iter().any(|result| result.status == "N/A") {
+                    "N/A"
+                } else if results.iter().all(|result| result.status == "PASS") {
+                    "PASS"
+                } else {
+                    "FAIL"
+                };
+
+                TestContext {
+                    test: test.to_string(),
+                    status,
+                    time_ms,
+                    results,
+                    error: None,
+                }
+            }
+            Err(e) => TestContext {
+                test: test.to_string(),
+                status: "ERROR",
+                time_ms,
+                results: Vec::new(),
+                error: Some(e.to_string()),
+            },
+        };
+
+        if test_context.status == "FAIL" || test_context.status == "ERROR" {
+            sequence.passed = false;
+            if !sequence.failed_tests.is_empty() {
+                sequence.failed_tests.push_str(", ");
+            }
+            sequence.failed_tests.push_str(&test_context.test);
+        }
+        sequence.tests.push(test_context);
+    }
+
+    /// Sets the final analysis and the verdict of each test over all sequences of a split run.
+    /// Parts that were resumed from a checkpoint are only part of the final analysis.
+    pub fn set_final_analysis(&mut self, analyses: &[FinalAnalysis], verdicts: &[TestVerdict]) {
+        self.final_analysis = analyses
+            .iter()
+            .map(|analysis| {
+                let histogram = analysis.histogram();
+                let max = histogram.iter().copied().max().unwrap_or(0).max(1);
+
+                AnalysisContext {
+                    test: analysis.test().to_string(),
+                    result_no: analysis.result_idx(),
+                    comment: analysis.comment().unwrap_or_default().to_owned(),
+                    passed_count: analysis.passed_count(),
+                    sample_size: analysis.sample_size(),
+                    proportion_passed: analysis.proportion_passed(),
+                    uniformity_p_value: analysis
+                        .uniformity_p_value()
+                        .map(|p_value| format!("{p_value:.6}")),
+                    uniformity_passed: analysis.uniformity_passed(),
+                    histogram: histogram
+                        .iter()
+                        .map(|&count| BARS[(count * (BARS.len() - 1)).div_ceil(max)])
+                        .collect(),
+                    bins: histogram
+                        .iter()
+                        .map(|&count| BinContext {
+                            count,
+                            height: count * MAX_BAR_HEIGHT / max,
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        self.verdicts = verdicts.to_vec();
+        for verdict in verdicts {
+            if !self.tests.contains(&verdict.test()) {
+                self.tests.push(verdict.test());
+            }
+        }
+    }
+
+    /// Renders the report and writes it to its path.
+    pub fn write(&self) -> Result<(), ReportError> {
+        let is_split = self.sequences.len() != 1 || !self.final_analysis.is_empty();
+        let single_tests = match self.sequences.as_slice() {
+            [sequence] if !is_split => sequence.tests.as_slice(),
+            _ => &[],
+        };
+
+        let summary = if is_split {
+            verdict_summary(&self.verdicts)
+        } else {
+            single_summary(single_tests)
+        };
+        let passed = if is_split {
+            self.verdicts.iter().all(TestVerdict::passed)
+        } else {
+            self.sequences.iter().all(|sequence| sequence.passed)
+        };
+
+        let context = ReportContext {
+            input: &self.input_name,
+            threshold: self.threshold,
+            verdict: if passed { "PASSED" } else { "FAILED" },
+            sequence_count: self.sequences.len(),
+            is_split,
+            summary,
+            tests: single_tests,
+            sequences: if is_split { &self.sequences } else { &[] },
+            final_analysis: &self.final_analysis,
+            parameters: self.parameters(),
+            timeout: self
+                .test_args
+                .timeout
+                .map(|timeout| format!("{} s", timeout.as_secs_f64())),
+            environment: EnvironmentContext {
+                version: env!("CARGO_PKG_VERSION"),
+                os: std::env::consts::OS,
+                arch: std::env::consts::ARCH,
+                threads: std::thread::available_parallelism().map_or(1, |n| n.get()),
+                created: format_utc(SystemTime::now()),
+            },
+        };
+
+        let mut templates = TinyTemplate::new();
+        match self.format {
+            ReportFormat::Html => templates.add_template("report", HTML_TEMPLATE)?,
+            ReportFormat::Markdown => {
+                templates.add_template("report", MARKDOWN_TEMPLATE)?;
+                templates.set_default_formatter(&format_markdown);
+            }
+        }
+
+        let rendered = templates.render("report", &context)?;
+        fs::write(&self.path, rendered)?;
+        Ok(())
+    }
+
+    /// The test arguments of all tests that were run and have arguments.
+    fn parameters(&self) -> Vec<ParameterRow> {
+        Test::iter()
+            .filter(|test| self.tests.contains(test))
+            .filter_map(|test| {
+                let Value::Object(parameters) = test_parameters(test, &self.test_args) else {
+                    return None;
+                };
+                if parameters.is_empty() {
+                    return None;
+                }
+
+                let parameters = parameters
+                    .iter()
+                    .map(|(name, value)| format!("{name} = {value}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                Some(ParameterRow {
+                    test: test.to_string(),
+                    parameters,
+                })
+            })
+            .collect()
+    }
+}
+
+/// The summary table of a split run: the verdict of each test over all sequences, with the
+/// result with the lowest proportion of passed sequences.
+fn verdict_summary(verdicts: &[TestVerdict]) -> Vec<SummaryRow> {
+    verdicts
+        .iter()
+        .map(|verdict| {
+            let status = if verdict.passed() { "PASS" } else { "FAIL" };
+            let details = verdict
+                .results()
+                .iter()
+                .min_by(|a, b| a.proportion().total_cmp(&b.proportion()))
+                .map_or_else(
+                    || "no applicable results".to_owned(),
+                    |result| {
+                        format!(
+                            "{} / {} sequences passed",
+                            result.passed_count(),
+                            result.sample_size()
+                        )
+                    },
+                );
+
+            SummaryRow {
+                test: verdict.test().to_string(),
+                status,
+                details,
+            }
+        })
+        .collect()
+}
+
+/// The summary table of a single sequence: the status of each test and its P-value.
+fn single_summary(tests: &[TestContext]) -> Vec<SummaryRow> {
+    tests
+        .iter()
+        .map(|test| {
+            let details = match (&test.error, test.results.as_slice()) {
+                (Some(error), _) => error.clone(),
+                (None, [result]) => format!("P-value: {}", result.p_value),
+                (None, results) => {
+                    let failed = results
+                        .iter()
+                        .filter(|result| result.status == "FAIL")
+                        .count();
+                    format!("{} results, {failed} failed", results.len())
+                }
+            };
+
+            SummaryRow {
+                test: test.test.clone(),
+                status: test.status,
+                details,
+            }
+        })
+        .collect()
+}
+
+/// PASS, FAIL or N/A (not applicable), see [TestResult::passed].
+fn result_status(result: &TestResult, threshold: f64) -> &'static str {
+    if !result.is_applicable() {
+        "N/A"
+    } else if result.passed(threshold) {
+        "PASS"
+    } else {
+        "FAIL"
+    }
+}
+
+/// The default formatter for Markdown: like [tinytemplate::format_unescaped], but with '|'
+/// escaped, so that values can be used in tables.
+fn format_markdown(value: &Value, output: &mut String) -> tinytemplate::error::Result<()> {
+    match value {
+        Value::String(s) => {
+            output.push_str(&s.replace('|', "\\|"));
+            Ok(())
+        }
+        value => tinytemplate::format_unescaped(value, output),
+    }
+}
+
+/// Formats the given time as UTC, e.g. "2024-08-01 12:00:00 UTC".
+fn format_utc(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let (days, secs_of_day) = (secs / 86400, secs % 86400);
+
+    // conversion of the days since 1970-01-01 into the civil date, see
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z / 146097;
+    let day_of_era = z % 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60
+    )
+}
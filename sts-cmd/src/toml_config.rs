@@ -1,136 +1,314 @@
 //! TOML configuration file.
 
-use crate::{ArgTest, InputFormat};
+use crate::valid_arg::Diagnostics;
+use crate::{ArgBitOrder, ArgProfile, ArgTestSelection, CsvNotation, InputFormat, OutputFormat};
 use serde::{Deserialize, Serialize};
 use std::num::NonZero;
 use std::path::PathBuf;
 use sts_lib::tests::approximate_entropy::ApproximateEntropyTestArg;
-use sts_lib::tests::frequency_block::FrequencyBlockTestArg;
+use sts_lib::tests::autocorrelation::AutocorrelationTestArg;
+use sts_lib::tests::binary_matrix_rank::BinaryMatrixRankTestArg;
+use sts_lib::tests::frequency_block::{FrequencyBlockTestArg, SelectionStrategy};
 use sts_lib::tests::linear_complexity::LinearComplexityTestArg;
+use sts_lib::tests::longest_run_of_ones::LongestRunOfOnesTestArg;
+use sts_lib::tests::random_excursions::RandomExcursionsTestArg;
+use sts_lib::tests::random_excursions_variant::RandomExcursionsVariantTestArg;
 use sts_lib::tests::serial::SerialTestArg;
+use sts_lib::tests::spectral_dft::{PeakThreshold, SpectralDftTestArg};
 use sts_lib::tests::template_matching::non_overlapping::NonOverlappingTemplateTestArgs;
 use sts_lib::tests::template_matching::overlapping::OverlappingTemplateTestArgs;
+use sts_lib::tests::template_matching::TemplateArg;
 use sts_lib::TestArgs;
 
 /// Struct for the TOML configuration file, the constraints of CmdArgs are not validated here.
+///
+/// Unknown keys are rejected in all tables, so that a misspelled key does not silently fall back
+/// to the default.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case", default)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
 pub struct TomlConfig {
     // not really optional, must be supplemented from cmd args if missing.
     pub input: TomlInput,
     pub test: TomlTest,
     // really optional
     pub output: Option<TomlOutput>,
-    // each argument is optional
+    // each argument is optional, the table may also be called "test-args"
+    #[serde(alias = "test-args")]
     pub arguments: Option<TomlTestArguments>,
 }
 
 /// Input: file, format, max length
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case", default)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
 pub struct TomlInput {
     pub input_file: Option<PathBuf>,
+    // multiple files, each tested as a separate sequence. Cannot be given with input_file.
+    pub input_files: Option<Vec<PathBuf>>,
     pub input_format: Option<InputFormat>,
+    pub bit_order: Option<ArgBitOrder>,
     pub max_length: Option<NonZero<usize>>,
     pub split: bool,
+    pub stride: Option<NonZero<usize>>,
+    // the count of windows to test, with max-length bits each. Cannot be given with split.
+    pub subsample: Option<NonZero<usize>>,
+    pub seed: Option<u64>,
+    pub checkpoint: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case", default)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
 pub struct TomlOutput {
     pub path: Option<PathBuf>,
+    pub format: Option<OutputFormat>,
+    // all parts are written to the output path itself
+    pub single_file: bool,
+    // requires single-file
+    pub append: bool,
+    pub csv_delimiter: Option<char>,
+    pub csv_precision: Option<usize>,
+    pub csv_notation: Option<CsvNotation>,
+    pub csv_decimal_comma: bool,
+    pub nist_compat: Option<PathBuf>,
+    pub report: Option<PathBuf>,
+    pub histogram: Option<PathBuf>,
     pub no_console: bool,
 }
 
-/// Tests to run: allowlist or blocklist
+/// Tests to run: profile, allowlist or blocklist, the threshold to decide if a test passed and
+/// the timeout of each test.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case", default)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
 pub struct TomlTest {
-    // include tests overrides exclude tests
-    pub include: Option<Vec<ArgTest>>,
-    pub exclude: Option<Vec<ArgTest>>,
+    pub profile: Option<ArgProfile>,
+    // include and exclude cannot both be given
+    // single tests or groups of tests
+    pub include: Option<Vec<ArgTestSelection>>,
+    pub exclude: Option<Vec<ArgTestSelection>>,
+    pub threshold: Option<f64>,
+    // in seconds
+    pub timeout: Option<f64>,
+    pub entropy_estimation: bool,
+    pub diagnostics: bool,
 }
 
 /// Test arguments for the test runner. Also used in cmd line overrides.
-#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case", default)]
+///
+/// Unknown keys are rejected, so that a misspelled argument does not silently fall back to the
+/// library default.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
 pub struct TomlTestArguments {
-    pub frequency_block: Option<TomlFrequencyBlockLinearComplexity>,
+    pub frequency_block: Option<TomlFrequencyBlock>,
+    pub longest_run_of_ones: Option<TomlFrequencyBlockLinearComplexity>,
+    pub binary_matrix_rank: Option<TomlBinaryMatrixRank>,
+    pub spectral_dft: Option<TomlSpectralDft>,
     pub non_overlapping_template_matching: Option<TomlNonOverlapping>,
     pub overlapping_template_matching: Option<TomlOverlapping>,
     pub linear_complexity: Option<TomlFrequencyBlockLinearComplexity>,
     pub serial: Option<TomlSerialApproximateEntropy>,
     pub approximate_entropy: Option<TomlSerialApproximateEntropy>,
+    pub random_excursions: Option<TomlRandomExcursions>,
+    pub random_excursions_variant: Option<TomlRandomExcursions>,
+    pub autocorrelation: Option<TomlAutocorrelation>,
 }
 
 impl TryFrom<TomlTestArguments> for TestArgs {
-    type Error = &'static str;
+    type Error = Diagnostics;
 
+    /// Converts the arguments, using the library defaults for all missing arguments.
     fn try_from(value: TomlTestArguments) -> Result<Self, Self::Error> {
+        value.into_test_args(TestArgs::default())
+    }
+}
+
+impl TomlTestArguments {
+    /// Converts the arguments, missing arguments are taken from `base`, e.g. the arguments of a
+    /// [Profile](sts_lib::profile::Profile).
+    ///
+    /// All invalid arguments are collected, each with the path of its key in the config file.
+    pub fn into_test_args(self, base: TestArgs) -> Result<TestArgs, Diagnostics> {
+        let mut diagnostics = Diagnostics::default();
         let TomlTestArguments {
             frequency_block,
+            longest_run_of_ones,
+            binary_matrix_rank,
+            spectral_dft,
             non_overlapping_template_matching,
             overlapping_template_matching,
             linear_complexity,
             serial,
             approximate_entropy,
-        } = value;
+            random_excursions,
+            random_excursions_variant,
+            autocorrelation,
+        } = self;
 
+        // a strategy implies choosing automatically, unless that is explicitly disabled
         let frequency_block = frequency_block
-            .map(|arg| match (arg.choose_automatically, arg.block_length) {
-                (_, None) | (Some(true), _) => FrequencyBlockTestArg::ChooseAutomatically,
-                (Some(false), Some(block_length)) | (None, Some(block_length)) => {
-                    FrequencyBlockTestArg::Manual(block_length)
+            .map(|arg| {
+                let TomlFrequencyBlock {
+                    block_length,
+                    choose_automatically,
+                    strategy,
+                } = arg;
+                match (choose_automatically, block_length, strategy) {
+                    (Some(false), Some(block_length), _) | (None, Some(block_length), None) => {
+                        FrequencyBlockTestArg::Manual(block_length)
+                    }
+                    (_, _, Some(strategy)) => {
+                        FrequencyBlockTestArg::ChooseWithStrategy(strategy.into())
+                    }
+                    (_, _, None) => FrequencyBlockTestArg::ChooseAutomatically,
                 }
             })
-            .unwrap_or_default();
+            .unwrap_or(base.frequency_block);
+
+        let longest_run_of_ones = match longest_run_of_ones {
+            Some(arg) => match (arg.choose_automatically, arg.block_length) {
+                (_, None) | (Some(true), _) => Some(LongestRunOfOnesTestArg::ChooseAutomatically),
+                (Some(false), Some(block_length)) | (None, Some(block_length)) => diagnostics
+                    .check(
+                        "arguments.longest-run-of-ones.block-length",
+                        LongestRunOfOnesTestArg::try_new(block_length.get()),
+                    ),
+            },
+            None => Some(base.longest_run_of_ones),
+        };
+
+        let binary_matrix_rank = {
+            if let Some(arg) = binary_matrix_rank {
+                let base = base.binary_matrix_rank;
+
+                let rows = arg.rows.map(NonZero::get).unwrap_or(base.rows());
+                let cols = arg.cols.map(NonZero::get).unwrap_or(base.cols());
+
+                diagnostics.check(
+                    "arguments.binary-matrix-rank",
+                    BinaryMatrixRankTestArg::try_new(rows, cols),
+                )
+            } else {
+                Some(base.binary_matrix_rank)
+            }
+        };
+
+        let spectral_dft = spectral_dft
+            .and_then(|arg| arg.threshold)
+            .map(|threshold| SpectralDftTestArg::new(PeakThreshold::from(threshold)))
+            .unwrap_or(base.spectral_dft);
 
         let non_overlapping_template = {
             if let Some(arg) = non_overlapping_template_matching {
-                use sts_lib::tests::template_matching::non_overlapping::DEFAULT_BLOCK_COUNT;
-                use sts_lib::tests::template_matching::DEFAULT_TEMPLATE_LENGTH;
+                let base = base.non_overlapping_template;
 
                 let template_length = arg
                     .template_length
                     .map(NonZero::get)
-                    .unwrap_or(DEFAULT_TEMPLATE_LENGTH);
+                    .unwrap_or(base.templates().template_len());
                 let count_blocks = arg
                     .count_blocks
                     .map(NonZero::get)
-                    .unwrap_or(DEFAULT_BLOCK_COUNT);
+                    .unwrap_or(base.count_blocks());
 
-                NonOverlappingTemplateTestArgs::new(template_length, count_blocks)
-                    .ok_or("Config file: invalid value for non-overlapping-template-matching.")?
+                if let Some(path) = arg.template_file {
+                    match TemplateArg::from_template_file(&path, template_length) {
+                        Ok(templates) => diagnostics.check(
+                            "arguments.non-overlapping-template-matching.count-blocks",
+                            NonOverlappingTemplateTestArgs::try_new_with_custom_template(
+                                templates,
+                                count_blocks,
+                            ),
+                        ),
+                        Err(e) => {
+                            diagnostics.push(
+                                "arguments.non-overlapping-template-matching.template-file",
+                                format!("\"{}\": {e}", path.display()),
+                            );
+                            None
+                        }
+                    }
+                } else {
+                    diagnostics.check(
+                        "arguments.non-overlapping-template-matching",
+                        NonOverlappingTemplateTestArgs::try_new(template_length, count_blocks),
+                    )
+                }
             } else {
-                Default::default()
+                Some(base.non_overlapping_template)
             }
         };
 
         let overlapping_template = {
             if let Some(arg) = overlapping_template_matching {
-                use sts_lib::tests::template_matching::overlapping::{
-                    DEFAULT_BLOCK_LENGTH, DEFAULT_FREEDOM, DEFAULT_TEMPLATE_LENGTH,
-                };
+                let base = base.overlapping_template;
 
-                let nist_behaviour = arg.nist_behaviour.unwrap_or(false);
-                let template_length = arg
-                    .template_length
-                    .map(NonZero::get)
-                    .unwrap_or(DEFAULT_TEMPLATE_LENGTH);
+                let nist_behaviour = arg.nist_behaviour.unwrap_or(base.nist_behaviour());
+                let template = arg.template.as_deref().map(parse_template);
+                let template_length = match (&template, arg.template_length) {
+                    (Some(Ok((_, len))), None) => *len,
+                    (_, template_length) => template_length
+                        .map(NonZero::get)
+                        .unwrap_or(base.template_length()),
+                };
 
-                if nist_behaviour {
-                    OverlappingTemplateTestArgs::new_nist_behaviour(template_length)
+                if nist_behaviour && template.is_some() {
+                    diagnostics.push(
+                        "arguments.overlapping-template-matching.template",
+                        "cannot be combined with nist-behaviour",
+                    );
+                    None
+                } else if nist_behaviour {
+                    diagnostics.check(
+                        "arguments.overlapping-template-matching.template-length",
+                        OverlappingTemplateTestArgs::try_new_nist_behaviour(template_length)
+                            .map_err(|e| format!("{e} (with nist-behaviour)")),
+                    )
                 } else {
                     let block_length = arg
                         .block_length
                         .map(NonZero::get)
-                        .unwrap_or(DEFAULT_BLOCK_LENGTH);
-                    let freedom = arg.freedom.map(NonZero::get).unwrap_or(DEFAULT_FREEDOM);
-                    OverlappingTemplateTestArgs::new(template_length, block_length, freedom)
+                        .unwrap_or(base.block_length());
+                    let freedom = arg.freedom.map(NonZero::get).unwrap_or(base.freedom());
+
+                    if block_length < template_length {
+                        diagnostics.push(
+                            "arguments.overlapping-template-matching.block-length",
+                            format!("must not be smaller than template-length ({template_length})"),
+                        );
+                    }
+
+                    match template {
+                        Some(Ok((template, len))) if len == template_length => diagnostics.check(
+                            "arguments.overlapping-template-matching.template",
+                            OverlappingTemplateTestArgs::try_new_with_custom_template(
+                                template << (usize::BITS as usize - len),
+                                template_length,
+                                block_length,
+                                freedom,
+                            ),
+                        ),
+                        Some(Ok((_, len))) => {
+                            diagnostics.push(
+                                "arguments.overlapping-template-matching.template",
+                                format!("has {len} bits, but template-length is {template_length}"),
+                            );
+                            None
+                        }
+                        Some(Err(e)) => {
+                            diagnostics.push("arguments.overlapping-template-matching.template", e);
+                            None
+                        }
+                        None => diagnostics.check(
+                            "arguments.overlapping-template-matching.template-length",
+                            OverlappingTemplateTestArgs::try_new(
+                                template_length,
+                                block_length,
+                                freedom,
+                            ),
+                        ),
+                    }
                 }
-                .ok_or("Config file: invalid value for overlapping-template-matching.")?
             } else {
-                Default::default()
+                Some(base.overlapping_template)
             }
         };
 
@@ -141,17 +319,28 @@ impl TryFrom<TomlTestArguments> for TestArgs {
                     LinearComplexityTestArg::ManualBlockLength(block_length)
                 }
             })
-            .unwrap_or_default();
+            .unwrap_or(base.linear_complexity);
+
+        if let LinearComplexityTestArg::ManualBlockLength(block_length) = linear_complexity {
+            if !(500..=5000).contains(&block_length.get()) {
+                diagnostics.push(
+                    "arguments.linear-complexity.block-length",
+                    format!("must be between 500 and 5000, is: {block_length}"),
+                );
+            }
+        }
 
         let serial = {
             if let Some(TomlSerialApproximateEntropy {
                 block_length: Some(block_length),
             }) = serial
             {
-                SerialTestArg::new(block_length.get())
-                    .ok_or("Config file: invalid value for serial.block-length")?
+                diagnostics.check(
+                    "arguments.serial.block-length",
+                    SerialTestArg::try_new(block_length.get()),
+                )
             } else {
-                Default::default()
+                Some(base.serial)
             }
         };
 
@@ -160,53 +349,218 @@ impl TryFrom<TomlTestArguments> for TestArgs {
                 block_length: Some(block_length),
             }) = approximate_entropy
             {
-                ApproximateEntropyTestArg::new(block_length.get())
-                    .ok_or("Config file: invalid value for approximate-entropy.block-length")?
+                diagnostics.check(
+                    "arguments.approximate-entropy.block-length",
+                    ApproximateEntropyTestArg::try_new(block_length.get()),
+                )
             } else {
-                Default::default()
+                Some(base.approximate_entropy)
             }
         };
 
-        Ok(TestArgs {
-            frequency_block,
+        let random_excursions = {
+            if let Some(TomlRandomExcursions {
+                max_state: Some(max_state),
+            }) = random_excursions
+            {
+                diagnostics.check(
+                    "arguments.random-excursions.max-state",
+                    RandomExcursionsTestArg::try_new(max_state),
+                )
+            } else {
+                Some(base.random_excursions)
+            }
+        };
+
+        let random_excursions_variant = {
+            if let Some(TomlRandomExcursions {
+                max_state: Some(max_state),
+            }) = random_excursions_variant
+            {
+                diagnostics.check(
+                    "arguments.random-excursions-variant.max-state",
+                    RandomExcursionsVariantTestArg::try_new(max_state),
+                )
+            } else {
+                Some(base.random_excursions_variant)
+            }
+        };
+
+        let autocorrelation = autocorrelation
+            .and_then(|arg| arg.shift)
+            .map(|shift| AutocorrelationTestArg::try_new(shift.get()).expect("shift is non-zero"))
+            .unwrap_or(base.autocorrelation);
+
+        match (
+            longest_run_of_ones,
+            binary_matrix_rank,
             non_overlapping_template,
             overlapping_template,
-            linear_complexity,
             serial,
             approximate_entropy,
-        })
+            random_excursions,
+            random_excursions_variant,
+        ) {
+            (
+                Some(longest_run_of_ones),
+                Some(binary_matrix_rank),
+                Some(non_overlapping_template),
+                Some(overlapping_template),
+                Some(serial),
+                Some(approximate_entropy),
+                Some(random_excursions),
+                Some(random_excursions_variant),
+            ) if diagnostics.is_empty() => Ok(TestArgs {
+                frequency_block,
+                longest_run_of_ones,
+                binary_matrix_rank,
+                spectral_dft,
+                non_overlapping_template,
+                overlapping_template,
+                linear_complexity,
+                serial,
+                approximate_entropy,
+                random_excursions,
+                random_excursions_variant,
+                autocorrelation,
+                timeout: base.timeout,
+            }),
+            _ => Err(diagnostics),
+        }
+    }
+}
+
+/// Parses a template of the overlapping template matching test, given as '0' and '1' - whitespace
+/// is ignored. Returns the template, with the bits starting at the LSB, and its length.
+fn parse_template(text: &str) -> Result<(usize, usize), String> {
+    let bits = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+
+    if let Some(character) = bits.chars().find(|c| !matches!(c, '0' | '1')) {
+        return Err(format!(
+            "invalid character '{character}', only '0', '1' and whitespace are allowed"
+        ));
+    }
+    if !(2..=21).contains(&bits.len()) {
+        return Err(format!(
+            "must have between 2 and 21 bits, has: {}",
+            bits.len()
+        ));
+    }
+
+    let template = usize::from_str_radix(&bits, 2).expect("only '0' and '1' are left");
+    Ok((template, bits.len()))
+}
+
+/// Test argument for the Frequency test within a block.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct TomlFrequencyBlock {
+    pub block_length: Option<NonZero<usize>>,
+    pub choose_automatically: Option<bool>,
+    pub strategy: Option<TomlSelectionStrategy>,
+}
+
+/// The strategy for choosing the block length of the Frequency test within a block automatically.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TomlSelectionStrategy {
+    NistRecommended,
+    MaximizeBlocks,
+    PowerOfTwo,
+}
+
+impl From<TomlSelectionStrategy> for SelectionStrategy {
+    fn from(value: TomlSelectionStrategy) -> Self {
+        match value {
+            TomlSelectionStrategy::NistRecommended => SelectionStrategy::NistRecommended,
+            TomlSelectionStrategy::MaximizeBlocks => SelectionStrategy::MaximizeBlocks,
+            TomlSelectionStrategy::PowerOfTwo => SelectionStrategy::PowerOfTwo,
+        }
     }
 }
 
-/// Test argument for the Frequency test within a block and the linear complexity test.
+/// Test argument for the longest run of ones test and the linear complexity test.
 #[derive(Debug, Copy, Clone, Default, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case", default)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
 pub struct TomlFrequencyBlockLinearComplexity {
     pub block_length: Option<NonZero<usize>>,
     pub choose_automatically: Option<bool>,
 }
 
-/// Test argument for the non-overlapping template matching test.
+/// Test argument for the binary matrix rank test.
 #[derive(Debug, Copy, Clone, Default, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case", default)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct TomlBinaryMatrixRank {
+    pub rows: Option<NonZero<usize>>,
+    pub cols: Option<NonZero<usize>>,
+}
+
+/// Test argument for the spectral DFT test.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct TomlSpectralDft {
+    pub threshold: Option<TomlSpectralDftThreshold>,
+}
+
+/// The derivation of the peak height threshold of the spectral DFT test.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TomlSpectralDftThreshold {
+    KimUmeno,
+    OriginalNist,
+}
+
+impl From<TomlSpectralDftThreshold> for PeakThreshold {
+    fn from(value: TomlSpectralDftThreshold) -> Self {
+        match value {
+            TomlSpectralDftThreshold::KimUmeno => PeakThreshold::KimUmeno,
+            TomlSpectralDftThreshold::OriginalNist => PeakThreshold::OriginalNist,
+        }
+    }
+}
+
+/// Test argument for the non-overlapping template matching test.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
 pub struct TomlNonOverlapping {
     pub template_length: Option<NonZero<usize>>,
     pub count_blocks: Option<NonZero<usize>>,
+    // custom templates, instead of all aperiodic templates of the given length
+    pub template_file: Option<PathBuf>,
 }
 
 /// Test argument for the overlapping template matching test.
-#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case", default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
 pub struct TomlOverlapping {
     pub template_length: Option<NonZero<usize>>,
     pub block_length: Option<NonZero<usize>>,
     pub freedom: Option<NonZero<usize>>,
     pub nist_behaviour: Option<bool>,
+    // custom template as '0' and '1', instead of all ones
+    pub template: Option<String>,
 }
 
 /// Test argument for the serial test and the approximate entropy test.
 #[derive(Debug, Copy, Clone, Default, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case", default)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
 pub struct TomlSerialApproximateEntropy {
     pub block_length: Option<NonZero<u8>>,
 }
+
+/// Test argument for the random excursions test and the random excursions variant test.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct TomlRandomExcursions {
+    pub max_state: Option<u8>,
+}
+
+/// Test argument for the autocorrelation test.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct TomlAutocorrelation {
+    pub shift: Option<NonZero<usize>>,
+}
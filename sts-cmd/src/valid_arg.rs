@@ -1,14 +1,91 @@
 //! Struct and conversion method for a validated arg.
 
 use crate::cmd_args::RegularArgs;
+use crate::csv::CsvOptions;
+use crate::report::ReportFormat;
 use crate::toml_config::{
-    TomlConfig, TomlFrequencyBlockLinearComplexity, TomlInput, TomlNonOverlapping, TomlOutput,
-    TomlOverlapping, TomlSerialApproximateEntropy, TomlTest, TomlTestArguments,
+    TomlConfig, TomlFrequencyBlock, TomlFrequencyBlockLinearComplexity, TomlInput,
+    TomlNonOverlapping, TomlOutput, TomlOverlapping, TomlRandomExcursions,
+    TomlSerialApproximateEntropy, TomlTest, TomlTestArguments,
 };
-use crate::InputFormat;
+use crate::{ArgBitOrder, ArgProfile, ArgTestSelection, InputFormat, OutputFormat};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs;
 use std::num::NonZero;
 use std::path::PathBuf;
-use sts_lib::{Test, TestArgs};
+use std::time::Duration;
+use sts_lib::fips140;
+use sts_lib::profile::Profile;
+use sts_lib::test_runner::Calibration;
+use sts_lib::tests::frequency_block::FrequencyBlockTestArg;
+use sts_lib::tests::linear_complexity::LinearComplexityTestArg;
+use sts_lib::{IntoEnumIterator, Test, TestArgs, DEFAULT_THRESHOLD};
+
+/// A problem with one value of the configuration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    /// The path of the value, e.g. "arguments.serial.block-length" for the config file or
+    /// "--threshold" for a command line argument.
+    pub path: &'static str,
+    /// What is wrong with the value.
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// All problems found while validating a configuration, see [Diagnostic].
+#[derive(Clone, Debug, Default)]
+pub struct Diagnostics(Vec<Diagnostic>);
+
+impl Diagnostics {
+    /// Records a problem with the value at the given path.
+    pub fn push(&mut self, path: &'static str, message: impl Into<String>) {
+        self.0.push(Diagnostic {
+            path,
+            message: message.into(),
+        });
+    }
+
+    /// Records the error of `result` as a problem with the value at the given path. Returns the
+    /// value if there was no error.
+    pub fn check<T, E: Display>(&mut self, path: &'static str, result: Result<T, E>) -> Option<T> {
+        result
+            .map_err(|message| self.push(path, message.to_string()))
+            .ok()
+    }
+
+    /// Records all problems of `result`. Returns the value if there were no problems.
+    pub fn append<T>(&mut self, result: Result<T, Diagnostics>) -> Option<T> {
+        result.map_err(|other| self.0.extend(other.0)).ok()
+    }
+
+    /// If no problems were found.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The problems, in the order they were found.
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.0.iter()
+    }
+}
+
+impl Display for Diagnostics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The configuration is invalid:")?;
+        for diagnostic in &self.0 {
+            write!(f, "\n  - {diagnostic}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for Diagnostics {}
 
 /// Which tests are to be run (allowed or blocked)
 #[derive(Clone, Debug)]
@@ -18,14 +95,46 @@ pub enum TestsToRun {
     All,
 }
 
+impl TestsToRun {
+    /// Selects the tests to run on an input with the given length in bits: an allowlist is used
+    /// as is, otherwise all tests whose input length requirements are satisfied are selected.
+    pub fn select(&self, len_bit: usize) -> Vec<Test> {
+        match self {
+            TestsToRun::AllowList(tests) => tests.clone(),
+            TestsToRun::BlockList(_) | TestsToRun::All => {
+                // all tests that are applicable based on the length
+                self.candidates()
+                    .into_iter()
+                    .filter(|test| sts_lib::get_min_length_for_test(*test).get() <= len_bit)
+                    .collect()
+            }
+        }
+    }
+
+    /// The tests to run, regardless of the input length: the allowlist, or all tests except for
+    /// the blocklist.
+    ///
+    /// Without an allowlist, the tests are taken from [Test::default_set]: [Test::LempelZiv] is
+    /// only run if an allowlist names it.
+    pub fn candidates(&self) -> Vec<Test> {
+        match self {
+            TestsToRun::AllowList(tests) => tests.clone(),
+            TestsToRun::BlockList(block_list) => Test::default_set()
+                .iter()
+                .copied()
+                .filter(|test| !block_list.contains(test))
+                .collect(),
+            TestsToRun::All => Test::default_set().to_vec(),
+        }
+    }
+}
+
 impl From<crate::cmd_args::TestsToRun> for TestsToRun {
     fn from(value: crate::cmd_args::TestsToRun) -> Self {
         if let Some(tests) = value.tests {
-            let tests = tests.into_iter().map(From::from).collect();
-            TestsToRun::AllowList(tests)
+            TestsToRun::AllowList(ArgTestSelection::expand(tests))
         } else if let Some(tests) = value.exclude_tests {
-            let tests = tests.into_iter().map(From::from).collect();
-            TestsToRun::BlockList(tests)
+            TestsToRun::BlockList(ArgTestSelection::expand(tests))
         } else {
             TestsToRun::All
         }
@@ -35,97 +144,335 @@ impl From<crate::cmd_args::TestsToRun> for TestsToRun {
 impl From<TomlTest> for TestsToRun {
     fn from(value: TomlTest) -> Self {
         if let Some(tests) = value.include {
-            let tests = tests.into_iter().map(From::from).collect();
-            TestsToRun::AllowList(tests)
+            TestsToRun::AllowList(ArgTestSelection::expand(tests))
         } else if let Some(tests) = value.exclude {
-            let tests = tests.into_iter().map(From::from).collect();
-            TestsToRun::BlockList(tests)
+            TestsToRun::BlockList(ArgTestSelection::expand(tests))
         } else {
             TestsToRun::All
         }
     }
 }
 
-/// To represent the max_length value and split flag combination
+/// To represent the max_length value and split flag (or subsample count) combination
 #[derive(Debug, Clone)]
 pub enum MaxLengthOrSplit {
     /// A max length was given, unit is bits.
     MaxLength(NonZero<usize>),
-    /// A split length was given, unit is bytes.
-    Split(NonZero<usize>),
+    /// A split length was given. The parts start every `stride_bytes` bytes, if this is smaller
+    /// than `split_bytes`, the parts overlap.
+    Split {
+        /// The length of each part, unit is bytes.
+        split_bytes: NonZero<usize>,
+        /// The distance between the start of 2 consecutive parts, unit is bytes. Equal to
+        /// `split_bytes` if no stride was given.
+        stride_bytes: NonZero<usize>,
+    },
+    /// A count of windows with the max length was given, placed at offsets generated from the
+    /// seed, see [Subsample](sts_lib::test_runner::Subsample).
+    Subsample {
+        /// The length of each window, unit is bits.
+        window_bits: NonZero<usize>,
+        /// The count of windows to test.
+        count: NonZero<usize>,
+        /// The seed for the offsets of the windows.
+        seed: u64,
+    },
     /// Neither a max length nor a split length was given.
     None,
 }
 
+/// Where to read the input (random data) from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum InputSource {
+    /// Read the input from the file with the given path.
+    File(PathBuf),
+    /// Read the input from stdin.
+    Stdin,
+    /// Read each of the files with the given paths, testing each file as a separate sequence.
+    Files(Vec<PathBuf>),
+}
+
+impl From<PathBuf> for InputSource {
+    /// The path "-" maps to [InputSource::Stdin], all other paths to [InputSource::File].
+    fn from(value: PathBuf) -> Self {
+        if value.as_os_str() == "-" {
+            InputSource::Stdin
+        } else {
+            InputSource::File(value)
+        }
+    }
+}
+
+impl Display for InputSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputSource::File(path) => write!(f, "\"{}\"", path.display()),
+            InputSource::Stdin => write!(f, "stdin"),
+            InputSource::Files(paths) => write!(f, "{} files", paths.len()),
+        }
+    }
+}
+
 /// A validated config with a valid state that can be used to run tests.
 #[derive(Clone, Debug)]
 pub struct ValidatedConfig {
-    /// Path to the input file (random data)
-    pub input_file: PathBuf,
+    /// Where to read the input (random data) from.
+    pub input: InputSource,
     /// Input format
     pub input_format: InputFormat,
+    /// The order of the bits in each byte, for binary and base64 input.
+    pub bit_order: ArgBitOrder,
     /// See [MaxLengthOrSplit]
     pub max_length_or_split: MaxLengthOrSplit,
+    /// An optional checkpoint file to resume a split run, only valid with
+    /// [MaxLengthOrSplit::Split] and [InputSource::File]. [MaxLengthOrSplit::Split] is not valid
+    /// with [InputSource::Files].
+    pub checkpoint_path: Option<PathBuf>,
     /// The exact tests to be run.
     pub tests_to_run: TestsToRun,
     /// Finished test arguments
     pub test_arguments: TestArgs,
+    /// The threshold to decide if a test passed.
+    pub threshold: f64,
     /// An optional path to save the outputs to.
     pub output_path: Option<PathBuf>,
+    /// The format of the output file.
+    pub output_format: OutputFormat,
+    /// Write the results of all parts to the output path itself, see
+    /// [CsvFile::append](crate::csv::CsvFile::append) and
+    /// [JsonFile::append](crate::json::JsonFile::append).
+    pub single_output: bool,
+    /// Append to the output file instead of overwriting it, only valid with `single_output`.
+    pub append_output: bool,
+    /// The formatting of the CSV files: the output file and the histograms.
+    pub csv_options: CsvOptions,
+    /// An optional directory to additionally save the outputs to, in the layout of the NIST
+    /// reference implementation, see [nist_compat](crate::nist_compat).
+    pub nist_compat_dir: Option<PathBuf>,
+    /// An optional path to save a human-readable report to, with the format chosen by its
+    /// extension, see [Report](crate::report::Report).
+    pub report: Option<(PathBuf, ReportFormat)>,
+    /// An optional path to save the histograms of the P-values of the final analysis to, with the
+    /// format chosen by its extension, see [OutputFormat::from_path]. Only valid with
+    /// [MaxLengthOrSplit::Split], [MaxLengthOrSplit::Subsample] or [InputSource::Files].
+    pub histogram: Option<(PathBuf, OutputFormat)>,
+    /// Estimate the entropy of each tested sequence, see [sts_lib::entropy_estimation].
+    pub entropy_estimation: bool,
+    /// Print diagnostics of each tested sequence, see [sts_lib::tests::run_length_histogram]
+    /// and [sts_lib::diagnostics].
+    pub diagnostics: bool,
     /// Write console output about individual tests, else only summaries.
     pub console_output: bool,
+    /// Run the FIPS 140-2 tests instead of the configured tests, see [ArgProfile::Fips140Quick].
+    /// Only valid without splitting or subsampling, a checkpoint, an output path, a NIST compatible
+    /// output, a report and a selection of tests.
+    pub fips140_quick: bool,
+    /// The speed of the tests measured on this machine, to estimate the duration of the test run.
+    /// Not part of the config, set with '--calibrate' before the tests are run.
+    pub calibration: Option<Calibration>,
 }
 
 impl ValidatedConfig {
     /// Creates a valid config from the command line arguments.
     ///
     /// These function may only be called if `config_file` was unspecified. Otherwise, a panic will occur.
-    pub fn try_from_cmd_args(args: RegularArgs) -> Result<Self, &'static str> {
+    ///
+    /// All problems are collected, each with the name of the argument.
+    pub fn try_from_cmd_args(args: RegularArgs) -> Result<Self, Diagnostics> {
         let RegularArgs {
-            input_file,
+            input_files,
+            stdin,
             input_format,
+            bit_order,
             max_length,
             split,
+            stride,
+            subsample,
+            seed,
+            checkpoint,
             output_path,
+            output_format,
+            single_output,
+            append_output,
+            csv_delimiter,
+            csv_precision,
+            csv_notation,
+            csv_decimal_comma,
+            nist_compat,
+            report,
+            histogram,
+            profile,
             tests_to_run,
+            threshold,
+            timeout,
+            entropy_estimation,
+            diagnostics: args_diagnostics,
             overrides,
+            template_file,
             no_console,
         } = args;
 
-        let input_file =
-            input_file.expect("input_file should be Some() except if a config file was specified.");
-        let input_format =
-            input_format.expect("input_format should be Some() if input_file was given.");
+        let mut diagnostics = Diagnostics::default();
 
-        let test_arguments = if let Some(overrides) = parse_overrides(overrides) {
-            overrides?.try_into()?
+        let input = if stdin {
+            Some(InputSource::Stdin)
         } else {
-            Default::default()
+            assert!(
+                !input_files.is_empty(),
+                "input_files should not be empty except if a config file or stdin was specified."
+            );
+            diagnostics.check("--input", handle_input_files(input_files))
+        };
+        let input_format =
+            input_format.expect("input_format should be Some() if input_files was given.");
+
+        let fips140_quick = profile == Some(ArgProfile::Fips140Quick);
+        let profile = profile.and_then(ArgProfile::profile);
+        let base_arguments = profile.map(|p| p.test_args()).unwrap_or_default();
+
+        if fips140_quick {
+            check_fips140_quick(
+                &mut diagnostics,
+                [
+                    ("--split", split),
+                    ("--subsample", subsample.is_some()),
+                    ("--checkpoint", checkpoint.is_some()),
+                    ("--tests", tests_to_run.tests.is_some()),
+                    ("--exclude-tests", tests_to_run.exclude_tests.is_some()),
+                    ("--output-path", output_path.is_some()),
+                    ("--nist-compat", nist_compat.is_some()),
+                    ("--report", report.is_some()),
+                    ("--entropy-estimation", entropy_estimation),
+                    ("--diagnostics", args_diagnostics),
+                ],
+            );
+        }
+
+        let test_arguments = match parse_overrides(overrides, template_file) {
+            Some(overrides) => diagnostics
+                .check("--overrides", overrides)
+                .and_then(|overrides| diagnostics.append(overrides.into_test_args(base_arguments))),
+            None => Some(base_arguments),
         };
 
-        let max_length_or_split = handle_split(split, max_length)?;
+        let max_length_or_split = match &input {
+            Some(input) => diagnostics.check(
+                "--split",
+                handle_split(split, max_length, stride, subsample, seed)
+                    .and_then(|value| check_split_input(input, value)),
+            ),
+            None => None,
+        };
+        let checkpoint_path = match (&input, &max_length_or_split) {
+            (Some(input), Some(max_length_or_split)) => diagnostics.check(
+                "--checkpoint",
+                handle_checkpoint(checkpoint, input, max_length_or_split),
+            ),
+            _ => None,
+        };
+        let histogram = match (&input, &max_length_or_split) {
+            (Some(input), Some(max_length_or_split)) => diagnostics.check(
+                "--histogram",
+                handle_histogram(histogram, input, max_length_or_split),
+            ),
+            _ => None,
+        };
+        let bit_order = diagnostics.check("--bit-order", handle_bit_order(bit_order, input_format));
+        let threshold = diagnostics.check("--threshold", handle_threshold(threshold));
+        let timeout = diagnostics.check("--timeout", handle_timeout(timeout));
+        let report = diagnostics.check("--report", handle_report(report));
+        let csv_delimiter = diagnostics.check(
+            "--csv-delimiter",
+            handle_csv_delimiter(csv_delimiter, csv_decimal_comma),
+        );
+        let csv_precision =
+            diagnostics.check("--csv-precision", handle_csv_precision(csv_precision));
+
+        let (
+            Some(input),
+            Some(bit_order),
+            Some(test_arguments),
+            Some(max_length_or_split),
+            Some(checkpoint_path),
+            Some(threshold),
+            Some(timeout),
+            Some(report),
+            Some(histogram),
+            Some(csv_delimiter),
+            Some(csv_precision),
+        ) = (
+            input,
+            bit_order,
+            test_arguments,
+            max_length_or_split,
+            checkpoint_path,
+            threshold,
+            timeout,
+            report,
+            histogram,
+            csv_delimiter,
+            csv_precision,
+        )
+        else {
+            return Err(diagnostics);
+        };
+        // problems that do not prevent creating the config
+        if !diagnostics.is_empty() {
+            return Err(diagnostics);
+        }
 
         Ok(Self {
-            input_file,
+            input,
             input_format,
+            bit_order,
             max_length_or_split,
-            tests_to_run: tests_to_run.into(),
-            test_arguments,
+            checkpoint_path,
+            tests_to_run: apply_profile(profile, tests_to_run.into()),
+            test_arguments: TestArgs {
+                timeout,
+                ..test_arguments
+            },
+            threshold,
             output_path,
+            output_format: output_format.unwrap_or_default(),
+            single_output,
+            append_output,
+            csv_options: CsvOptions {
+                delimiter: csv_delimiter,
+                precision: csv_precision,
+                notation: csv_notation.unwrap_or_default(),
+                decimal_comma: csv_decimal_comma,
+            },
+            nist_compat_dir: nist_compat,
+            report,
+            histogram,
+            entropy_estimation,
+            diagnostics: args_diagnostics,
             console_output: !no_console,
+            fips140_quick,
+            calibration: None,
         })
     }
 
     /// Creates a valid config from the specified toml configuration, uses overrides from the
     /// command line.
-    pub fn try_from_toml(toml: TomlConfig, args: RegularArgs) -> Result<Self, &'static str> {
+    ///
+    /// All problems are collected, each with the path of the key in the config file (or the name
+    /// of the argument, for problems with the command line arguments).
+    pub fn try_from_toml(toml: TomlConfig, args: RegularArgs) -> Result<Self, Diagnostics> {
         let TomlConfig {
             input:
                 TomlInput {
                     input_file,
+                    input_files,
                     input_format,
+                    bit_order,
                     max_length,
                     split,
+                    stride,
+                    subsample,
+                    seed,
+                    checkpoint,
                 },
             test,
             output,
@@ -134,68 +481,214 @@ impl ValidatedConfig {
 
         let TomlOutput {
             path: output_path,
+            format: output_format,
+            single_file,
+            append,
+            csv_delimiter,
+            csv_precision,
+            csv_notation,
+            csv_decimal_comma,
+            nist_compat,
+            report,
+            histogram,
             no_console,
         } = output.unwrap_or_default();
 
         let RegularArgs {
-            input_file: args_input_file,
+            input_files: args_input_files,
+            stdin: args_stdin,
             input_format: args_input_format,
+            bit_order: args_bit_order,
             max_length: args_input_length,
             split: args_split,
+            stride: args_stride,
+            subsample: args_subsample,
+            seed: args_seed,
+            checkpoint: args_checkpoint,
+            profile: args_profile,
             tests_to_run,
+            threshold: args_threshold,
+            timeout: args_timeout,
+            entropy_estimation: args_entropy_estimation,
+            diagnostics: args_diagnostics,
             overrides,
+            template_file,
             output_path: args_output_path,
+            output_format: args_output_format,
+            single_output: args_single_output,
+            append_output: args_append_output,
+            csv_delimiter: args_csv_delimiter,
+            csv_precision: args_csv_precision,
+            csv_notation: args_csv_notation,
+            csv_decimal_comma: args_csv_decimal_comma,
+            nist_compat: args_nist_compat,
+            report: args_report,
+            histogram: args_histogram,
             no_console: args_no_console,
         } = args;
 
+        let mut diagnostics = Diagnostics::default();
+
+        if test.include.is_some() && test.exclude.is_some() {
+            diagnostics.push("test", "include and exclude cannot both be given");
+        }
+
+        if input_file.is_some() && input_files.is_some() {
+            diagnostics.push("input", "input-file and input-files cannot both be given");
+        }
+
         // cmd args overwrite everywhere
-        let input_file = args_input_file
-            .or(input_file)
-            .ok_or("The input file is unspecified in the config file and the cmd args!")?;
-        let input_format = args_input_format
-            .or(input_format)
-            .ok_or("The input format is unspecified in the config file and the cmd args!")?;
+        let input = if args_stdin {
+            Some(InputSource::Stdin)
+        } else if !args_input_files.is_empty() {
+            diagnostics.check("--input", handle_input_files(args_input_files))
+        } else if let Some(input_files) = input_files {
+            diagnostics.check("input.input-files", handle_input_files(input_files))
+        } else {
+            diagnostics
+                .check(
+                    "input.input-file",
+                    input_file.ok_or("missing, must be given in the config file or the cmd args"),
+                )
+                .map(InputSource::from)
+        };
+        let input_format = diagnostics.check(
+            "input.input-format",
+            args_input_format
+                .or(input_format)
+                .ok_or("missing, must be given in the config file or the cmd args"),
+        );
         let max_length = max_length.or(args_input_length);
         let split = args_split || split;
+        let stride = args_stride.or(stride);
+        let subsample = args_subsample.or(subsample);
+        let seed = args_seed.or(seed);
+        let checkpoint = args_checkpoint.or(checkpoint);
         let output_path = args_output_path.or(output_path);
+        let output_format = args_output_format.or(output_format).unwrap_or_default();
+        let single_output = args_single_output || single_file;
+        let append_output = args_append_output || append;
+        if append_output && !single_output {
+            diagnostics.push("output.append", "only valid together with single-file");
+        }
+        let csv_notation = args_csv_notation.or(csv_notation).unwrap_or_default();
+        let csv_decimal_comma = args_csv_decimal_comma || csv_decimal_comma;
+        let nist_compat_dir = args_nist_compat.or(nist_compat);
+        let report = args_report.or(report);
+        let histogram = args_histogram.or(histogram);
         let console_output = !(args_no_console || no_console);
+        let threshold = args_threshold.or(test.threshold);
+        let timeout = args_timeout.or(test.timeout);
+        let entropy_estimation = args_entropy_estimation || test.entropy_estimation;
+        let print_diagnostics = args_diagnostics || test.diagnostics;
+        let fips140_quick = args_profile.or(test.profile) == Some(ArgProfile::Fips140Quick);
+        let profile = args_profile.or(test.profile).and_then(ArgProfile::profile);
+        let base_arguments = profile.map(|p| p.test_args()).unwrap_or_default();
+
+        if fips140_quick {
+            check_fips140_quick(
+                &mut diagnostics,
+                [
+                    ("input.split", split),
+                    ("input.subsample", subsample.is_some()),
+                    ("input.checkpoint", checkpoint.is_some()),
+                    (
+                        "test.include",
+                        test.include.is_some() || tests_to_run.tests.is_some(),
+                    ),
+                    (
+                        "test.exclude",
+                        test.exclude.is_some() || tests_to_run.exclude_tests.is_some(),
+                    ),
+                    ("output.path", output_path.is_some()),
+                    ("output.nist-compat", nist_compat_dir.is_some()),
+                    ("output.report", report.is_some()),
+                    ("test.entropy-estimation", entropy_estimation),
+                    ("test.diagnostics", print_diagnostics),
+                ],
+            );
+        }
 
         let tests_to_run: TestsToRun = {
             let cmd_tests_to_run = tests_to_run.into();
 
-            if let TestsToRun::All = &cmd_tests_to_run {
+            let tests_to_run = if let TestsToRun::All = &cmd_tests_to_run {
                 // no command line switch was specified, use the toml file
                 test.into()
             } else {
                 cmd_tests_to_run
-            }
+            };
+
+            apply_profile(profile, tests_to_run)
         };
 
         let test_arguments = if let Some(mut toml_args) = arguments {
             // override if necessary
-            if let Some(overrides) = parse_overrides(overrides) {
+            let overrides = parse_overrides(overrides, template_file)
+                .and_then(|overrides| diagnostics.check("--overrides", overrides));
+            if let Some(overrides) = overrides {
                 let TomlTestArguments {
                     frequency_block,
+                    longest_run_of_ones,
+                    binary_matrix_rank,
+                    spectral_dft,
                     non_overlapping_template_matching,
                     overlapping_template_matching,
                     linear_complexity,
                     serial,
                     approximate_entropy,
-                } = overrides?;
+                    random_excursions,
+                    random_excursions_variant,
+                    autocorrelation,
+                } = overrides;
 
                 if let Some(arg) = frequency_block {
                     match toml_args.frequency_block.as_mut() {
-                        Some(outer) => override_frequency_linear(outer, arg),
+                        Some(outer) => override_frequency_block(outer, arg),
                         None => toml_args.frequency_block = Some(arg),
                     }
                 }
 
+                if let Some(arg) = longest_run_of_ones {
+                    match toml_args.longest_run_of_ones.as_mut() {
+                        Some(outer) => override_frequency_linear(outer, arg),
+                        None => toml_args.longest_run_of_ones = Some(arg),
+                    }
+                }
+
+                if let Some(arg) = binary_matrix_rank {
+                    match toml_args.binary_matrix_rank.as_mut() {
+                        Some(outer) => {
+                            if arg.rows.is_some() {
+                                outer.rows = arg.rows;
+                            }
+
+                            if arg.cols.is_some() {
+                                outer.cols = arg.cols;
+                            }
+                        }
+                        None => toml_args.binary_matrix_rank = Some(arg),
+                    }
+                }
+
+                if let Some(arg) = spectral_dft {
+                    match toml_args.spectral_dft.as_mut() {
+                        Some(outer) => {
+                            if arg.threshold.is_some() {
+                                outer.threshold = arg.threshold;
+                            }
+                        }
+                        None => toml_args.spectral_dft = Some(arg),
+                    }
+                }
+
                 if let Some(arg) = non_overlapping_template_matching {
                     match toml_args.non_overlapping_template_matching.as_mut() {
                         Some(outer) => {
                             let TomlNonOverlapping {
                                 template_length,
                                 count_blocks,
+                                template_file,
                             } = arg;
 
                             if template_length.is_some() {
@@ -205,6 +698,10 @@ impl ValidatedConfig {
                             if count_blocks.is_some() {
                                 outer.count_blocks = count_blocks;
                             }
+
+                            if template_file.is_some() {
+                                outer.template_file = template_file;
+                            }
                         }
                         None => toml_args.non_overlapping_template_matching = Some(arg),
                     }
@@ -218,6 +715,7 @@ impl ValidatedConfig {
                                 block_length,
                                 freedom,
                                 nist_behaviour,
+                                template,
                             } = arg;
 
                             if template_length.is_some() {
@@ -235,6 +733,10 @@ impl ValidatedConfig {
                             if nist_behaviour.is_some() {
                                 outer.nist_behaviour = nist_behaviour;
                             }
+
+                            if template.is_some() {
+                                outer.template = template;
+                            }
                         }
                         None => toml_args.overlapping_template_matching = Some(arg),
                     }
@@ -260,41 +762,407 @@ impl ValidatedConfig {
                         None => toml_args.approximate_entropy = Some(arg),
                     }
                 }
+
+                if let Some(arg) = random_excursions {
+                    match toml_args.random_excursions.as_mut() {
+                        Some(outer) => override_random_excursions(outer, arg),
+                        None => toml_args.random_excursions = Some(arg),
+                    }
+                }
+
+                if let Some(arg) = random_excursions_variant {
+                    match toml_args.random_excursions_variant.as_mut() {
+                        Some(outer) => override_random_excursions(outer, arg),
+                        None => toml_args.random_excursions_variant = Some(arg),
+                    }
+                }
+
+                if let Some(arg) = autocorrelation {
+                    match toml_args.autocorrelation.as_mut() {
+                        Some(outer) => {
+                            if arg.shift.is_some() {
+                                outer.shift = arg.shift;
+                            }
+                        }
+                        None => toml_args.autocorrelation = Some(arg),
+                    }
+                }
             }
 
-            toml_args.try_into()?
-        } else if let Some(overrides) = parse_overrides(overrides) {
+            diagnostics.append(toml_args.into_test_args(base_arguments))
+        } else if let Some(overrides) = parse_overrides(overrides, template_file) {
             // only overrides
-            overrides?.try_into()?
+            diagnostics
+                .check("--overrides", overrides)
+                .and_then(|overrides| diagnostics.append(overrides.into_test_args(base_arguments)))
         } else {
-            Default::default()
+            Some(base_arguments)
         };
 
-        let max_length_or_split = handle_split(split, max_length)?;
+        let max_length_or_split = match &input {
+            Some(input) => diagnostics.check(
+                "input",
+                handle_split(split, max_length, stride, subsample, seed)
+                    .and_then(|value| check_split_input(input, value)),
+            ),
+            None => None,
+        };
+        let checkpoint_path = match (&input, &max_length_or_split) {
+            (Some(input), Some(max_length_or_split)) => diagnostics.check(
+                "input.checkpoint",
+                handle_checkpoint(checkpoint, input, max_length_or_split),
+            ),
+            _ => None,
+        };
+        let histogram = match (&input, &max_length_or_split) {
+            (Some(input), Some(max_length_or_split)) => diagnostics.check(
+                "output.histogram",
+                handle_histogram(histogram, input, max_length_or_split),
+            ),
+            _ => None,
+        };
+        let bit_order = match input_format {
+            Some(input_format) => diagnostics.check(
+                "input.bit-order",
+                handle_bit_order(args_bit_order.or(bit_order), input_format),
+            ),
+            None => None,
+        };
+        let threshold = diagnostics.check("test.threshold", handle_threshold(threshold));
+        let timeout = diagnostics.check("test.timeout", handle_timeout(timeout));
+        let report = diagnostics.check("output.report", handle_report(report));
+        let csv_delimiter = diagnostics.check(
+            "output.csv-delimiter",
+            handle_csv_delimiter(args_csv_delimiter.or(csv_delimiter), csv_decimal_comma),
+        );
+        let csv_precision = diagnostics.check(
+            "output.csv-precision",
+            handle_csv_precision(args_csv_precision.or(csv_precision)),
+        );
+
+        let (
+            Some(input),
+            Some(input_format),
+            Some(bit_order),
+            Some(test_arguments),
+            Some(max_length_or_split),
+            Some(checkpoint_path),
+            Some(threshold),
+            Some(timeout),
+            Some(report),
+            Some(histogram),
+            Some(csv_delimiter),
+            Some(csv_precision),
+        ) = (
+            input,
+            input_format,
+            bit_order,
+            test_arguments,
+            max_length_or_split,
+            checkpoint_path,
+            threshold,
+            timeout,
+            report,
+            histogram,
+            csv_delimiter,
+            csv_precision,
+        )
+        else {
+            return Err(diagnostics);
+        };
+        // problems that do not prevent creating the config
+        if !diagnostics.is_empty() {
+            return Err(diagnostics);
+        }
 
         Ok(Self {
-            input_file,
+            input,
             input_format,
+            bit_order,
             max_length_or_split,
+            checkpoint_path,
             tests_to_run,
-            test_arguments,
+            test_arguments: TestArgs {
+                timeout,
+                ..test_arguments
+            },
+            threshold,
             output_path,
+            output_format,
+            single_output,
+            append_output,
+            csv_options: CsvOptions {
+                delimiter: csv_delimiter,
+                precision: csv_precision,
+                notation: csv_notation,
+                decimal_comma: csv_decimal_comma,
+            },
+            nist_compat_dir,
+            report,
+            histogram,
+            entropy_estimation,
+            diagnostics: print_diagnostics,
             console_output,
+            fips140_quick,
+            calibration: None,
         })
     }
+
+    /// The length of the sequences to test in bits, as far as it is known from the config: the
+    /// length of each part when splitting, of each window when subsampling, or the maximum length.
+    pub fn declared_len_bit(&self) -> Option<usize> {
+        match self.max_length_or_split {
+            MaxLengthOrSplit::MaxLength(max_length) => Some(max_length.get()),
+            MaxLengthOrSplit::Split { split_bytes, .. } => Some(split_bytes.get() * 8),
+            MaxLengthOrSplit::Subsample { window_bits, .. } => Some(window_bits.get()),
+            MaxLengthOrSplit::None => None,
+        }
+    }
+
+    /// Checks the test arguments of the tests that will be run against the length of the input,
+    /// in bits. Each problem means that a test will fail with an error when it is run. For the
+    /// profile fips140-quick, only the input length is checked.
+    ///
+    /// All problems are collected, each with the path of the key in the config file, which the
+    /// command line arguments and the argument overrides correspond to.
+    pub fn validate_against_input_len(&self, len_bit: usize) -> Result<(), Diagnostics> {
+        let mut diagnostics = Diagnostics::default();
+        let args = &self.test_arguments;
+
+        if self.fips140_quick {
+            let sample_length = fips140::SAMPLE_LENGTH.get();
+            if len_bit < sample_length {
+                diagnostics.push(
+                    "input.max-length",
+                    format!(
+                        "the profile fips140-quick requires at least {sample_length} bits, the \
+                         input has {len_bit}"
+                    ),
+                );
+            }
+
+            return if diagnostics.is_empty() {
+                Ok(())
+            } else {
+                Err(diagnostics)
+            };
+        }
+
+        for test in self.tests_to_run.select(len_bit) {
+            let min_length = match test {
+                // depends on the dimensions of the matrices
+                Test::BinaryMatrixRank => args.binary_matrix_rank.min_input_length().get(),
+                test => sts_lib::get_min_length_for_test(test).get(),
+            };
+            if len_bit < min_length {
+                diagnostics.push(
+                    "test.include",
+                    format!("{test} requires at least {min_length} bits, the input has {len_bit}"),
+                );
+                continue;
+            }
+
+            match test {
+                Test::FrequencyWithinABlock => {
+                    if let FrequencyBlockTestArg::Manual(block_length) = args.frequency_block {
+                        if block_length.get() > len_bit {
+                            diagnostics.push(
+                                "arguments.frequency-block.block-length",
+                                format!(
+                                    "must not be greater than the input length ({len_bit} bits), \
+                                     is: {block_length}"
+                                ),
+                            );
+                        }
+                    }
+                }
+                Test::LongestRunOfOnes => {
+                    let min_length = args.longest_run_of_ones.min_input_length().get();
+                    if len_bit < min_length {
+                        diagnostics.push(
+                            "arguments.longest-run-of-ones.block-length",
+                            format!(
+                                "requires an input of at least {min_length} bits, the input has \
+                                 {len_bit}"
+                            ),
+                        );
+                    }
+                }
+                Test::NonOverlappingTemplateMatching => {
+                    let arg = args.non_overlapping_template;
+                    let block_length = len_bit / arg.count_blocks();
+                    let template_length = arg.templates().template_len();
+                    if block_length < template_length {
+                        diagnostics.push(
+                            "arguments.non-overlapping-template-matching",
+                            format!(
+                                "the block length (input length / count-blocks = {block_length}) \
+                                 must not be smaller than template-length ({template_length})"
+                            ),
+                        );
+                    }
+                }
+                Test::OverlappingTemplateMatching => {
+                    let block_length = args.overlapping_template.block_length();
+                    if block_length > len_bit {
+                        diagnostics.push(
+                            "arguments.overlapping-template-matching.block-length",
+                            format!(
+                                "must not be greater than the input length ({len_bit} bits), \
+                                 is: {block_length}"
+                            ),
+                        );
+                    }
+                }
+                Test::LinearComplexity => {
+                    if let LinearComplexityTestArg::ManualBlockLength(block_length) =
+                        args.linear_complexity
+                    {
+                        let count_blocks = len_bit / block_length.get();
+                        if count_blocks < 200 {
+                            diagnostics.push(
+                                "arguments.linear-complexity.block-length",
+                                format!(
+                                    "leads to {count_blocks} blocks for an input of {len_bit} \
+                                     bits, at least 200 are needed"
+                                ),
+                            );
+                        }
+                    }
+                }
+                Test::Serial => {
+                    let block_length = args.serial.block_length() as usize;
+                    let max_block_length = (len_bit.ilog2() as usize).saturating_sub(2);
+                    if block_length >= max_block_length {
+                        diagnostics.push(
+                            "arguments.serial.block-length",
+                            format!(
+                                "must be less than log2(input length) - 2 (= {max_block_length} \
+                                 for {len_bit} bits), is: {block_length}"
+                            ),
+                        );
+                    }
+                }
+                Test::ApproximateEntropy => {
+                    let block_length = args.approximate_entropy.block_length() as usize;
+                    let max_block_length = (len_bit.ilog2() as usize).saturating_sub(5);
+                    if block_length >= max_block_length {
+                        diagnostics.push(
+                            "arguments.approximate-entropy.block-length",
+                            format!(
+                                "must be less than log2(input length) - 5 (= {max_block_length} \
+                                 for {len_bit} bits), is: {block_length}"
+                            ),
+                        );
+                    }
+                }
+                Test::Autocorrelation => {
+                    let shift = args.autocorrelation.shift();
+                    if shift > len_bit / 2 {
+                        diagnostics.push(
+                            "arguments.autocorrelation.shift",
+                            format!(
+                                "must not be greater than half the input length (= {} for \
+                                 {len_bit} bits), is: {shift}",
+                                len_bit / 2
+                            ),
+                        );
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        if diagnostics.is_empty() {
+            Ok(())
+        } else {
+            Err(diagnostics)
+        }
+    }
+}
+
+/// Records a problem for each given value that cannot be combined with the profile
+/// [ArgProfile::Fips140Quick], which only tests the start of the input and writes no output file.
+fn check_fips140_quick<const N: usize>(
+    diagnostics: &mut Diagnostics,
+    values: [(&'static str, bool); N],
+) {
+    for (path, given) in values {
+        if given {
+            diagnostics.push(path, "cannot be combined with the profile fips140-quick");
+        }
+    }
 }
 
-/// Parse the overrides given via command line
+/// Restricts the tests to run to the tests of the profile, if one is given. An allowlist is used
+/// as is, all tests not in the profile are added to the blocklist.
+fn apply_profile(profile: Option<Profile>, tests_to_run: TestsToRun) -> TestsToRun {
+    let Some(profile) = profile else {
+        return tests_to_run;
+    };
+
+    let mut block_list = match tests_to_run {
+        allow_list @ TestsToRun::AllowList(_) => return allow_list,
+        TestsToRun::BlockList(block_list) => block_list,
+        TestsToRun::All => Vec::new(),
+    };
+
+    block_list.extend(Test::iter().filter(|test| !profile.tests().contains(test)));
+    TestsToRun::BlockList(block_list)
+}
+
+/// Parse the overrides given via command line. A template file given via '--template-file' is
+/// treated like an override of the non-overlapping template matching test.
 fn parse_overrides(
     overrides: Option<Vec<String>>,
+    template_file: Option<PathBuf>,
 ) -> Option<Result<TomlTestArguments, &'static str>> {
-    let overrides =
-        overrides.and_then(|overrides| overrides.into_iter().reduce(|a, b| a + "\n" + &b))?;
+    let overrides = overrides
+        .and_then(|overrides| overrides.into_iter().reduce(|a, b| a + "\n" + &b))
+        .map(|overrides| {
+            toml::from_str(&overrides).map_err(|_| "argument overrides is not valid TOML")
+        });
+
+    let Some(template_file) = template_file else {
+        return overrides;
+    };
+
+    let mut overrides = match overrides {
+        Some(Ok(overrides)) => overrides,
+        Some(Err(e)) => return Some(Err(e)),
+        None => TomlTestArguments::default(),
+    };
+    overrides
+        .non_overlapping_template_matching
+        .get_or_insert_with(Default::default)
+        .template_file = Some(template_file);
+
+    Some(Ok(overrides))
+}
+
+/// Does the overrides for the frequency block test.
+fn override_frequency_block(outer: &mut TomlFrequencyBlock, new_data: TomlFrequencyBlock) {
+    let TomlFrequencyBlock {
+        block_length,
+        choose_automatically,
+        strategy,
+    } = new_data;
 
-    Some(toml::from_str(&overrides).map_err(|_| "argument overrides is not valid TOML"))
+    if block_length.is_some() {
+        outer.block_length = block_length;
+    }
+
+    if choose_automatically.is_some() {
+        outer.choose_automatically = choose_automatically;
+    }
+
+    if strategy.is_some() {
+        outer.strategy = strategy;
+    }
 }
 
-/// Does the overrides for frequency block test and linear complexity test: same TOML argument type
+/// Does the overrides for longest run of ones test and linear complexity test: same TOML argument
+/// type
 fn override_frequency_linear(
     outer: &mut TomlFrequencyBlockLinearComplexity,
     new_data: TomlFrequencyBlockLinearComplexity,
@@ -325,24 +1193,70 @@ fn override_serial_entropy(
     }
 }
 
-/// Handle the split flag, in combination with max_length
+/// Does the overrides for random excursions test and random excursions variant test: same TOML
+/// argument type
+fn override_random_excursions(outer: &mut TomlRandomExcursions, new_data: TomlRandomExcursions) {
+    let TomlRandomExcursions { max_state } = new_data;
+
+    if max_state.is_some() {
+        outer.max_state = max_state;
+    }
+}
+
+/// Handle the split flag, in combination with max_length and stride, or the subsample count with
+/// its seed
 fn handle_split(
     split: bool,
     max_length: Option<NonZero<usize>>,
+    stride: Option<NonZero<usize>>,
+    subsample: Option<NonZero<usize>>,
+    seed: Option<u64>,
 ) -> Result<MaxLengthOrSplit, &'static str> {
-    if split {
+    if seed.is_some() && subsample.is_none() {
+        return Err("seed can only be used together with subsample");
+    }
+
+    if let Some(count) = subsample {
+        if split {
+            return Err("split and subsample cannot both be given");
+        }
+        let Some(window_bits) = max_length else {
+            return Err("subsample requires max-length");
+        };
+
+        Ok(MaxLengthOrSplit::Subsample {
+            window_bits,
+            count,
+            seed: seed.unwrap_or_default(),
+        })
+    } else if split {
         let Some(max_length) = max_length else {
-            return Err("max_length should be Some() - split is set");
+            return Err("split requires max-length");
         };
 
         if max_length.get() % 8 != 0 {
-            return Err("max_length must denote full bytes (be divisible by 8)");
+            return Err("max-length must denote full bytes (be divisible by 8) with split");
         }
 
         // since max_length % 8 == 0 and max_length != 0 --> max_length >= 8 --> unwrap()
         // is unreachable.
         let split_bytes = NonZero::new(max_length.get() / 8).unwrap();
-        Ok(MaxLengthOrSplit::Split(split_bytes))
+
+        let stride_bytes = match stride {
+            None => split_bytes,
+            Some(stride) if stride.get() % 8 != 0 => {
+                return Err("stride must denote full bytes (be divisible by 8)")
+            }
+            // same as above, unwrap() is unreachable.
+            Some(stride) => NonZero::new(stride.get() / 8).unwrap(),
+        };
+
+        Ok(MaxLengthOrSplit::Split {
+            split_bytes,
+            stride_bytes,
+        })
+    } else if stride.is_some() {
+        Err("stride can only be used together with split")
     } else {
         match max_length {
             None => Ok(MaxLengthOrSplit::None),
@@ -350,3 +1264,178 @@ fn handle_split(
         }
     }
 }
+
+/// Handle the input paths: a single file (or "-" for stdin) is read as is, while each of multiple
+/// files is tested as a separate sequence. A directory is replaced by its files, sorted by name.
+pub fn handle_input_files(paths: Vec<PathBuf>) -> Result<InputSource, &'static str> {
+    let mut files = Vec::with_capacity(paths.len());
+    for path in paths {
+        if path.is_dir() {
+            let mut dir_files = fs::read_dir(&path)
+                .map_err(|_| "the input directory cannot be read")?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| "the input directory cannot be read")?;
+            dir_files.retain(|path| path.is_file());
+            if dir_files.is_empty() {
+                return Err("the input directory contains no files");
+            }
+            dir_files.sort();
+            files.extend(dir_files);
+        } else {
+            files.push(path);
+        }
+    }
+
+    match files.len() {
+        0 => Err("missing, at least one input file must be given"),
+        1 => Ok(files.pop().unwrap().into()),
+        _ if files.iter().any(|path| path.as_os_str() == "-") => {
+            Err("stdin cannot be combined with other input files")
+        }
+        _ => Ok(InputSource::Files(files)),
+    }
+}
+
+/// Check that the input can be split: multiple input files are each tested as a whole. Windows
+/// can only be placed in a single input file, whose length is known in advance.
+fn check_split_input(
+    input: &InputSource,
+    max_length_or_split: MaxLengthOrSplit,
+) -> Result<MaxLengthOrSplit, &'static str> {
+    match (input, &max_length_or_split) {
+        (InputSource::Files(_), MaxLengthOrSplit::Split { .. }) => {
+            Err("split cannot be used with multiple input files")
+        }
+        (InputSource::Files(_), MaxLengthOrSplit::Subsample { .. }) => {
+            Err("subsample cannot be used with multiple input files")
+        }
+        (InputSource::Stdin, MaxLengthOrSplit::Subsample { .. }) => {
+            Err("subsample cannot be used with stdin")
+        }
+        _ => Ok(max_length_or_split),
+    }
+}
+
+/// Handle the checkpoint path: a checkpoint can only be used when splitting an input file.
+fn handle_checkpoint(
+    checkpoint: Option<PathBuf>,
+    input: &InputSource,
+    max_length_or_split: &MaxLengthOrSplit,
+) -> Result<Option<PathBuf>, &'static str> {
+    match (checkpoint, input, max_length_or_split) {
+        (None, _, _) => Ok(None),
+        (
+            Some(_),
+            _,
+            MaxLengthOrSplit::MaxLength(_)
+            | MaxLengthOrSplit::Subsample { .. }
+            | MaxLengthOrSplit::None,
+        ) => Err("checkpoint can only be used together with split"),
+        (Some(_), InputSource::Stdin, _) => Err("checkpoint cannot be used with stdin"),
+        (Some(_), InputSource::Files(_), _) => {
+            Err("checkpoint cannot be used with multiple input files")
+        }
+        (Some(checkpoint), InputSource::File(_), MaxLengthOrSplit::Split { .. }) => {
+            Ok(Some(checkpoint))
+        }
+    }
+}
+
+/// Handle the histogram path: the final analysis is only done when splitting or subsampling the
+/// input or testing multiple input files. The format is chosen by the extension.
+fn handle_histogram(
+    histogram: Option<PathBuf>,
+    input: &InputSource,
+    max_length_or_split: &MaxLengthOrSplit,
+) -> Result<Option<(PathBuf, OutputFormat)>, &'static str> {
+    match (histogram, input, max_length_or_split) {
+        (None, _, _) => Ok(None),
+        (Some(histogram), InputSource::Files(_), _)
+        | (Some(histogram), _, MaxLengthOrSplit::Split { .. })
+        | (Some(histogram), _, MaxLengthOrSplit::Subsample { .. }) => {
+            let format = OutputFormat::from_path(&histogram);
+            Ok(Some((histogram, format)))
+        }
+        (Some(_), _, _) => {
+            Err("histogram can only be used together with split, subsample or multiple input files")
+        }
+    }
+}
+
+/// Validate the bit order, using the default if none was given: only the byte formats binary and
+/// base64 have a bit order.
+pub fn handle_bit_order(
+    bit_order: Option<ArgBitOrder>,
+    input_format: InputFormat,
+) -> Result<ArgBitOrder, &'static str> {
+    match (bit_order, input_format) {
+        (None, _) => Ok(ArgBitOrder::default()),
+        (Some(bit_order), InputFormat::Binary | InputFormat::Base64) => Ok(bit_order),
+        (Some(_), _) => Err("bit order can only be used with the input formats binary and base64"),
+    }
+}
+
+/// Validate the threshold, using the default if none was given.
+pub fn handle_threshold(threshold: Option<f64>) -> Result<f64, &'static str> {
+    match threshold {
+        None => Ok(DEFAULT_THRESHOLD),
+        Some(threshold) if threshold > 0.0 && threshold < 1.0 => Ok(threshold),
+        Some(_) => Err("threshold must be between 0 and 1 (exclusive)"),
+    }
+}
+
+/// Validate the path of the report, the format is chosen by its extension. No report if none
+/// was given.
+pub fn handle_report(
+    path: Option<PathBuf>,
+) -> Result<Option<(PathBuf, ReportFormat)>, &'static str> {
+    match path {
+        None => Ok(None),
+        Some(path) => match ReportFormat::from_path(&path) {
+            Some(format) => Ok(Some((path, format))),
+            None => Err("the extension must be .html, .htm, .md or .markdown"),
+        },
+    }
+}
+
+/// Validate the delimiter of the CSV files, using the default if none was given. It must not be
+/// part of the formatted values, and not ',' if that is the decimal separator.
+pub fn handle_csv_delimiter(
+    delimiter: Option<char>,
+    decimal_comma: bool,
+) -> Result<u8, &'static str> {
+    let Some(delimiter) = delimiter else {
+        return Ok(CsvOptions::default().delimiter);
+    };
+
+    match delimiter {
+        ',' if decimal_comma => Err("must not be ',' with a decimal comma"),
+        '"' | '.' | '-' | '+' | '\n' | '\r' => {
+            Err("must not be '\"', '.', '-', '+' or a line break")
+        }
+        delimiter if delimiter.is_ascii_alphanumeric() => Err("must not be a letter or a digit"),
+        delimiter if delimiter.is_ascii() => Ok(delimiter as u8),
+        _ => Err("must be an ASCII character"),
+    }
+}
+
+/// Validate the precision of the floating point values in the CSV files, see
+/// [CsvOptions::precision].
+pub fn handle_csv_precision(precision: Option<usize>) -> Result<Option<usize>, &'static str> {
+    match precision {
+        Some(precision) if precision > 30 => Err("must be at most 30"),
+        precision => Ok(precision),
+    }
+}
+
+/// Validate the per-test timeout in seconds, no timeout if none was given.
+pub fn handle_timeout(timeout: Option<f64>) -> Result<Option<Duration>, &'static str> {
+    match timeout {
+        None => Ok(None),
+        Some(timeout) if timeout > 0.0 => Duration::try_from_secs_f64(timeout)
+            .map(Some)
+            .map_err(|_| "timeout is too large"),
+        Some(_) => Err("timeout must be greater than 0 seconds"),
+    }
+}
@@ -0,0 +1,205 @@
+//! Tests of the checkpoints of split runs: resuming after completed parts, discarding a partially
+//! written part and detecting a changed input or configuration.
+
+use clap::Parser;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use sts_cmd::checkpoint::{Checkpoint, CheckpointError};
+use sts_cmd::cmd_args::CmdArgs;
+use sts_cmd::valid_arg::ValidatedConfig;
+use sts_lib::test_runner::multi_sequence::RecordedResult;
+use sts_lib::Test;
+
+/// The tests run on each part.
+const TESTS: [Test; 2] = [Test::Frequency, Test::Serial];
+
+/// Creates an empty directory for the files of the given test.
+fn test_dir(name: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"))
+        .join("checkpoint")
+        .join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Writes an input file of 4 parts with 1000 bits each.
+fn write_input(dir: &Path) -> PathBuf {
+    let path = dir.join("input.bin");
+    let bytes = (0..500_u32)
+        .map(|i| (i * 37 % 251) as u8)
+        .collect::<Vec<_>>();
+    fs::write(&path, bytes).unwrap();
+    path
+}
+
+/// The config for splitting the input into parts of 1000 bits, with the given further arguments.
+fn config(input: &Path, args: &[&str]) -> ValidatedConfig {
+    let input = input.to_str().unwrap();
+    let base = [
+        "sts-cmd",
+        "--input",
+        input,
+        "--input-format",
+        "binary",
+        "--max-length",
+        "1000",
+        "--split",
+    ];
+    let args = CmdArgs::parse_from(base.iter().chain(args));
+    ValidatedConfig::try_from_cmd_args(args.run_args.regular_args).unwrap()
+}
+
+/// The results recorded for the given part.
+fn results(part: u64) -> Vec<(Test, RecordedResult)> {
+    vec![
+        (
+            Test::Frequency,
+            RecordedResult::PValues(vec![part as f64 / 10.0]),
+        ),
+        (Test::Serial, RecordedResult::NotApplicable),
+    ]
+}
+
+/// A new checkpoint has no completed parts, a reopened one all parts recorded before, and
+/// recording continues with the next part.
+#[test]
+fn test_resume() {
+    let dir = test_dir("resume");
+    let input = write_input(&dir);
+    let config = config(&input, &[]);
+    let path = dir.join("checkpoint.jsonl");
+
+    let mut checkpoint = Checkpoint::open(&path, &config, &TESTS).unwrap();
+    assert!(checkpoint.completed_parts().is_empty());
+    checkpoint.record_part(1, true, &results(1)).unwrap();
+    checkpoint.record_part(2, false, &results(2)).unwrap();
+    drop(checkpoint);
+
+    let mut checkpoint = Checkpoint::open(&path, &config, &TESTS).unwrap();
+    let completed = checkpoint.completed_parts();
+    assert_eq!(completed.len(), 2);
+    assert!(completed[0].passed);
+    assert_eq!(completed[0].results, results(1));
+    assert!(!completed[1].passed);
+    assert_eq!(completed[1].results, results(2));
+
+    // only the next part can be recorded
+    assert!(matches!(
+        checkpoint.record_part(4, true, &results(4)),
+        Err(CheckpointError::Invalid(_))
+    ));
+    checkpoint.record_part(3, true, &results(3)).unwrap();
+    drop(checkpoint);
+
+    let checkpoint = Checkpoint::open(&path, &config, &TESTS).unwrap();
+    assert_eq!(checkpoint.completed_parts().len(), 3);
+    assert_eq!(checkpoint.completed_parts()[2].results, results(3));
+}
+
+/// A partially written last line, as left behind by an aborted run, is discarded and overwritten
+/// by the next recorded part.
+#[test]
+fn test_truncated_last_line() {
+    let dir = test_dir("truncated_last_line");
+    let input = write_input(&dir);
+    let config = config(&input, &[]);
+    let path = dir.join("checkpoint.jsonl");
+
+    let mut checkpoint = Checkpoint::open(&path, &config, &TESTS).unwrap();
+    checkpoint.record_part(1, true, &results(1)).unwrap();
+    checkpoint.record_part(2, true, &results(2)).unwrap();
+    drop(checkpoint);
+    let complete_len = fs::metadata(&path).unwrap().len();
+
+    let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+    file.write_all(br#"{"part":3,"passed":true,"results":[{"test":"Frequ"#)
+        .unwrap();
+    drop(file);
+
+    let mut checkpoint = Checkpoint::open(&path, &config, &TESTS).unwrap();
+    assert_eq!(checkpoint.completed_parts().len(), 2);
+    assert_eq!(fs::metadata(&path).unwrap().len(), complete_len);
+
+    checkpoint.record_part(3, false, &results(3)).unwrap();
+    drop(checkpoint);
+
+    let checkpoint = Checkpoint::open(&path, &config, &TESTS).unwrap();
+    let completed = checkpoint.completed_parts();
+    assert_eq!(completed.len(), 3);
+    assert!(!completed[2].passed);
+    assert_eq!(completed[2].results, results(3));
+}
+
+/// A checkpoint cannot be resumed if the size or the first bytes of the input file changed.
+#[test]
+fn test_input_changed() {
+    let dir = test_dir("input_changed");
+    let input = write_input(&dir);
+    let config = config(&input, &[]);
+    let path = dir.join("checkpoint.jsonl");
+
+    let mut checkpoint = Checkpoint::open(&path, &config, &TESTS).unwrap();
+    checkpoint.record_part(1, true, &results(1)).unwrap();
+    drop(checkpoint);
+
+    // same size, different content
+    let mut bytes = fs::read(&input).unwrap();
+    bytes[0] ^= 1;
+    fs::write(&input, &bytes).unwrap();
+    assert!(matches!(
+        Checkpoint::open(&path, &config, &TESTS),
+        Err(CheckpointError::InputChanged)
+    ));
+
+    // original content, different size
+    bytes[0] ^= 1;
+    bytes.push(0);
+    fs::write(&input, &bytes).unwrap();
+    assert!(matches!(
+        Checkpoint::open(&path, &config, &TESTS),
+        Err(CheckpointError::InputChanged)
+    ));
+
+    // the original input can still be resumed
+    bytes.pop();
+    fs::write(&input, &bytes).unwrap();
+    let checkpoint = Checkpoint::open(&path, &config, &TESTS).unwrap();
+    assert_eq!(checkpoint.completed_parts().len(), 1);
+}
+
+/// A checkpoint cannot be resumed with a different split, threshold, test selection or test
+/// arguments.
+#[test]
+fn test_config_changed() {
+    let dir = test_dir("config_changed");
+    let input = write_input(&dir);
+    let path = dir.join("checkpoint.jsonl");
+
+    let mut checkpoint = Checkpoint::open(&path, &config(&input, &[]), &TESTS).unwrap();
+    checkpoint.record_part(1, true, &results(1)).unwrap();
+    drop(checkpoint);
+
+    let changed = [
+        (config(&input, &["--stride", "800"]), &TESTS[..]),
+        (config(&input, &["--threshold", "0.001"]), &TESTS[..]),
+        (config(&input, &[]), &TESTS[..1]),
+        (
+            config(&input, &["--overrides", "serial.block-length=3"]),
+            &TESTS[..],
+        ),
+    ];
+    for (config, tests) in changed {
+        assert!(
+            matches!(
+                Checkpoint::open(&path, &config, tests),
+                Err(CheckpointError::ConfigChanged)
+            ),
+            "{config:?}"
+        );
+    }
+
+    let checkpoint = Checkpoint::open(&path, &config(&input, &[]), &TESTS).unwrap();
+    assert_eq!(checkpoint.completed_parts().len(), 1);
+}
@@ -0,0 +1,171 @@
+//! Tests of the CSV output: the formatting of the floating point values with [CsvOptions], the
+//! rows written by [CsvFile] and reading them back.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use sts_cmd::csv::{read_sequences, CsvFile, CsvOptions};
+use sts_cmd::CsvNotation;
+use sts_lib::bitvec::BitVec;
+use sts_lib::test_runner::multi_sequence::RecordedResult;
+use sts_lib::tests::frequency::frequency_test;
+use sts_lib::{Test, TestResult};
+
+/// The example input of the frequency test, NIST SP 800-22r1a, section 2.1.8, P-value 0.109599.
+const FREQUENCY_EXAMPLE: &str = "11001001000011111101101010100010001000010110100011\
+                                 00001000110100110001001100011001100010100010111000";
+
+/// Creates an empty directory for the files of the given test.
+fn test_dir(name: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"))
+        .join("csv")
+        .join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// The result of the frequency test on [FREQUENCY_EXAMPLE] (passed) and on 100 ones (failed).
+fn frequency_results() -> (TestResult, TestResult) {
+    let example = BitVec::from_ascii_str(FREQUENCY_EXAMPLE).unwrap();
+    let ones = BitVec::from(vec![true; 100]);
+    (
+        frequency_test(&example).unwrap(),
+        frequency_test(&ones).unwrap(),
+    )
+}
+
+/// The floating point values with each notation, with and without precision and decimal comma.
+#[test]
+fn test_format_float() {
+    let options = |notation, precision, decimal_comma| CsvOptions {
+        precision,
+        notation,
+        decimal_comma,
+        ..Default::default()
+    };
+
+    let auto = options(CsvNotation::Auto, None, false);
+    assert_eq!(auto.format_float(0.0123), "0.0123");
+    assert_eq!(auto.format_float(1.5e-20), "1.5e-20");
+    assert_eq!(auto.format_float(1.0), "1.0");
+
+    // a precision selects the fixed notation
+    let auto = options(CsvNotation::Auto, Some(3), false);
+    assert_eq!(auto.format_float(0.0123), "0.012");
+    assert_eq!(auto.format_float(1.5e-20), "0.000");
+
+    let fixed = options(CsvNotation::Fixed, None, false);
+    assert_eq!(fixed.format_float(0.0123), "0.0123");
+    assert_eq!(fixed.format_float(1.5e-20), "0.000000000000000000015");
+    let fixed = options(CsvNotation::Fixed, Some(4), false);
+    assert_eq!(fixed.format_float(0.5), "0.5000");
+
+    let scientific = options(CsvNotation::Scientific, None, false);
+    assert_eq!(scientific.format_float(0.0123), "1.23e-2");
+    assert_eq!(scientific.format_float(1.5e-20), "1.5e-20");
+    let scientific = options(CsvNotation::Scientific, Some(2), false);
+    assert_eq!(scientific.format_float(0.5), "5.00e-1");
+
+    let comma = options(CsvNotation::Fixed, Some(4), true);
+    assert_eq!(comma.format_float(0.5), "0,5000");
+    let comma = options(CsvNotation::Scientific, None, true);
+    assert_eq!(comma.format_float(0.0123), "1,23e-2");
+}
+
+/// The rows of a file written with the default options, and reading them back.
+#[test]
+fn test_write_default() {
+    let dir = test_dir("write_default");
+    let path = dir.join("results.csv");
+    let (passed, failed) = frequency_results();
+
+    let mut file = CsvFile::new(&path, 0.01, CsvOptions::default()).unwrap();
+    let time = Duration::from_micros(1500);
+    file.write_test(Test::Frequency, time, Ok(std::slice::from_ref(&passed)))
+        .unwrap();
+    file.write_test(Test::Runs, time, Ok(std::slice::from_ref(&failed)))
+        .unwrap();
+    let error = sts_lib::Error::InvalidParameter("example".to_owned());
+    file.write_test::<&[TestResult]>(Test::Serial, time, Err(&error))
+        .unwrap();
+    drop(file);
+
+    let content = fs::read_to_string(&path).unwrap();
+    let rows = content.lines().collect::<Vec<_>>();
+    assert_eq!(
+        rows[0],
+        "test name;time in ms;result no;label;PASS/FAIL;p-value;comment"
+    );
+    assert_eq!(
+        rows[1],
+        format!("Frequency;1.5;0;;PASS;{:?};", passed.p_value())
+    );
+    assert_eq!(rows[2], format!("Runs;1.5;0;;FAIL;{:?};", failed.p_value()));
+    assert!(
+        rows[3].starts_with("Serial;1.5;0;;ERROR;-1.0;"),
+        "{}",
+        rows[3]
+    );
+    assert_eq!(rows.len(), 4);
+
+    // the error is not part of the recorded results
+    let sequences = read_sequences(&path).unwrap();
+    assert_eq!(sequences.len(), 1);
+    assert_eq!(
+        sequences[0][..2],
+        [
+            (
+                Test::Frequency,
+                RecordedResult::PValues(vec![passed.p_value()])
+            ),
+            (Test::Runs, RecordedResult::PValues(vec![failed.p_value()])),
+        ]
+    );
+}
+
+/// Appending parts with a different delimiter, precision and decimal comma: the header is only
+/// written once, each row starts with the part, and the file can still be read back.
+#[test]
+fn test_append_with_options() {
+    let dir = test_dir("append_with_options");
+    let path = dir.join("results.csv");
+    let (passed, failed) = frequency_results();
+    let options = CsvOptions {
+        delimiter: b'\t',
+        precision: Some(6),
+        notation: CsvNotation::Fixed,
+        decimal_comma: true,
+    };
+
+    for (part, result) in [(1, &passed), (2, &failed)] {
+        let mut file = CsvFile::append(&path, part, 0.01, options).unwrap();
+        file.write_test(
+            Test::Frequency,
+            Duration::from_millis(2),
+            Ok(std::slice::from_ref(result)),
+        )
+        .unwrap();
+    }
+
+    let content = fs::read_to_string(&path).unwrap();
+    let rows = content.lines().collect::<Vec<_>>();
+    assert_eq!(
+        rows,
+        [
+            "part\ttest name\ttime in ms\tresult no\tlabel\tPASS/FAIL\tp-value\tcomment",
+            "1\tFrequency\t2,000000\t0\t\tPASS\t0,109599\t",
+            &format!(
+                "2\tFrequency\t2,000000\t0\t\tFAIL\t{}\t",
+                options.format_float(failed.p_value())
+            ),
+        ]
+    );
+
+    let sequences = read_sequences(&path).unwrap();
+    assert_eq!(sequences.len(), 2);
+    let RecordedResult::PValues(p_values) = &sequences[0][0].1 else {
+        panic!("{sequences:?}");
+    };
+    assert!((p_values[0] - passed.p_value()).abs() < 1e-6);
+}
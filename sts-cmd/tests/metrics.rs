@@ -0,0 +1,146 @@
+//! Tests of the Prometheus metrics of the subcommand "monitor": the rendered exposition format and
+//! the HTTP server.
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::sync::Arc;
+use sts_cmd::metrics::Metrics;
+use sts_lib::bitvec::BitVec;
+use sts_lib::tests::frequency::frequency_test;
+use sts_lib::tests::runs::runs_test;
+use sts_lib::Test;
+
+/// The tests that are monitored.
+const TESTS: [Test; 3] = [Test::Frequency, Test::Runs, Test::Serial];
+
+/// The value of the sample with the given name and labels, e.g. `sts_monitor_windows_total` or
+/// `sts_monitor_test_errors_total{test="Runs"}`.
+fn sample(rendered: &str, name: &str) -> Option<f64> {
+    rendered
+        .lines()
+        .filter_map(|line| line.strip_prefix(name)?.strip_prefix(' '))
+        .map(|value| value.parse().unwrap())
+        .next()
+}
+
+/// Sends a request to the server and returns the response.
+fn request(addr: SocketAddr, request_line: &str) -> String {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(stream, "{request_line}\r\nHost: localhost\r\n\r\n").unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+/// Before the first window, the configuration is exported and all counters are 0.
+#[test]
+fn test_render_initial() {
+    let metrics = Metrics::new(&TESTS, 1_000_000, 100_000, 0.01);
+    let rendered = metrics.render();
+
+    assert!(rendered.contains("# TYPE sts_monitor_windows_total counter\n"));
+    assert_eq!(sample(&rendered, "sts_monitor_windows_total"), Some(0.0));
+    assert_eq!(
+        sample(&rendered, "sts_monitor_alert_windows_total"),
+        Some(0.0)
+    );
+    assert_eq!(
+        sample(&rendered, "sts_monitor_last_window_timestamp_seconds"),
+        Some(0.0)
+    );
+    assert_eq!(sample(&rendered, "sts_monitor_window_bits"), Some(1e6));
+    assert_eq!(sample(&rendered, "sts_monitor_interval_bits"), Some(1e5));
+    assert_eq!(sample(&rendered, "sts_monitor_threshold"), Some(0.01));
+    for test in TESTS {
+        let failures = format!("sts_monitor_test_failures_total{{test=\"{test}\"}}");
+        let errors = format!("sts_monitor_test_errors_total{{test=\"{test}\"}}");
+        assert_eq!(sample(&rendered, &failures), Some(0.0));
+        assert_eq!(sample(&rendered, &errors), Some(0.0));
+    }
+    assert!(!rendered.contains("sts_monitor_p_value{"));
+}
+
+/// The P-values of the latest window replace the previous ones, failures and errors are counted
+/// over all windows.
+#[test]
+fn test_render_recorded() {
+    let metrics = Metrics::new(&TESTS, 100, 100, 0.01);
+    let random = BitVec::from_ascii_str(
+        "11001001000011111101101010100010001000010110100011\
+         00001000110100110001001100011001100010100010111000",
+    )
+    .unwrap();
+    let ones = BitVec::from(vec![true; 100]);
+
+    // first window: all tests fail or return an error
+    let failed = frequency_test(&ones).unwrap();
+    metrics.record_results(Test::Frequency, &[failed]);
+    metrics.record_error(Test::Runs);
+    metrics.record_error(Test::Serial);
+    metrics.record_window(1);
+
+    // second window: frequency and runs pass
+    let frequency = frequency_test(&random).unwrap();
+    let runs = runs_test(&random).unwrap();
+    metrics.record_results(Test::Frequency, std::slice::from_ref(&frequency));
+    metrics.record_results(Test::Runs, std::slice::from_ref(&runs));
+    metrics.record_error(Test::Serial);
+    metrics.record_window(0);
+
+    let rendered = metrics.render();
+    assert_eq!(sample(&rendered, "sts_monitor_windows_total"), Some(2.0));
+    assert_eq!(
+        sample(&rendered, "sts_monitor_alert_windows_total"),
+        Some(1.0)
+    );
+    assert!(sample(&rendered, "sts_monitor_last_window_timestamp_seconds").unwrap() > 0.0);
+
+    let p_value = |test: Test| {
+        let name = format!("sts_monitor_p_value{{test=\"{test}\",result=\"0\",label=\"\"}}");
+        sample(&rendered, &name)
+    };
+    assert_eq!(p_value(Test::Frequency), Some(frequency.p_value()));
+    assert_eq!(p_value(Test::Runs), Some(runs.p_value()));
+    assert_eq!(p_value(Test::Serial), None);
+
+    let counter =
+        |name: &str, test: Test| sample(&rendered, &format!("{name}{{test=\"{test}\"}}")).unwrap();
+    assert_eq!(
+        counter("sts_monitor_test_failures_total", Test::Frequency),
+        1.0
+    );
+    assert_eq!(
+        counter("sts_monitor_test_errors_total", Test::Frequency),
+        0.0
+    );
+    assert_eq!(counter("sts_monitor_test_failures_total", Test::Runs), 0.0);
+    assert_eq!(counter("sts_monitor_test_errors_total", Test::Runs), 1.0);
+    assert_eq!(counter("sts_monitor_test_errors_total", Test::Serial), 2.0);
+}
+
+/// The server answers "/metrics" with the rendered metrics, other paths with 404 and other
+/// methods with 405.
+#[test]
+fn test_serve() {
+    let metrics = Arc::new(Metrics::new(&TESTS, 1000, 1000, 0.01));
+    metrics.record_window(0);
+    let addr = Arc::clone(&metrics)
+        .serve("127.0.0.1:0".parse().unwrap())
+        .unwrap();
+
+    let response = request(addr, "GET /metrics HTTP/1.1");
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    assert!(head.starts_with("HTTP/1.1 200 OK\r\n"), "{head}");
+    assert!(head.contains(&format!("Content-Length: {}\r\n", body.len())));
+    assert_eq!(body, metrics.render());
+
+    let response = request(addr, "HEAD /metrics HTTP/1.1");
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.ends_with("\r\n\r\n"), "{response}");
+
+    let response = request(addr, "GET / HTTP/1.1");
+    assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+
+    let response = request(addr, "POST /metrics HTTP/1.1");
+    assert!(response.starts_with("HTTP/1.1 405 Method Not Allowed\r\n"));
+}
@@ -0,0 +1,51 @@
+//! Tests of the tests selected by the command line, with '--dry-run' so that no test is run.
+
+use std::path::Path;
+use std::process::Command;
+use sts_lib::Test;
+
+/// Runs sts-cmd with '--dry-run' on the reference file of e with 10^6 bits and returns the names of
+/// the tests that would run.
+fn selected_tests(args: &[&str]) -> Vec<String> {
+    let input = Path::new(env!("CARGO_MANIFEST_DIR")).join("../sts-lib/test-files/e.1e6.bin");
+    let output = Command::new(env!("CARGO_BIN_EXE_sts-cmd"))
+        .arg("--input")
+        .arg(input)
+        .args(["--input-format", "binary", "--dry-run"])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+
+    let output =
+        String::from_utf8(output.stdout).unwrap() + &String::from_utf8_lossy(&output.stderr);
+    output
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("Test "))
+        .filter_map(|line| line.split_once(": runs"))
+        .map(|(test, _)| test.to_owned())
+        .collect()
+}
+
+/// Without a selection, the default tests of the library run: all tests except for the Lempel-Ziv
+/// compression test.
+#[test]
+fn test_default_selection() {
+    assert!(!Test::default_set().contains(&Test::LempelZiv));
+    assert!(Test::default_set().contains(&Test::Autocorrelation));
+
+    let tests = selected_tests(&[]);
+    let default_tests = Test::default_set()
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    assert_eq!(tests, default_tests);
+
+    let tests = selected_tests(&["--exclude-tests", "frequency"]);
+    assert_eq!(tests.len(), 15, "{tests:?}");
+    assert!(!tests.contains(&"LempelZiv".to_owned()));
+
+    // it is only run if it is named
+    let tests = selected_tests(&["--tests", "frequency,lempel-ziv"]);
+    assert_eq!(tests, ["Frequency", "LempelZiv"]);
+}
@@ -0,0 +1,129 @@
+//! Benchmarks for the binary matrix rank test with the default 32x32 matrices, on the standard
+//! 10^6 bit test files in `test-files`.
+//!
+//! Besides the whole test (`test`), the row reduction of the test (`branchless`: the pivot row is
+//! XORed into each row below it, masked with the bit of the row in the pivot column) is compared
+//! with the previous one (`branching`: the bit of each row is tested before XORing), both on the
+//! matrices of the test files.
+//!
+//! Run with `cargo bench -p sts-lib --bench binary_matrix_rank`.
+
+mod common;
+
+use common::{test_file, TEST_FILES};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::hint::black_box;
+use sts_lib::bitvec::BitVec;
+use sts_lib::tests::binary_matrix_rank::{binary_matrix_rank_test, BinaryMatrixRankTestArg};
+
+/// The count of rows and columns of the matrices.
+const DIMENSION: usize = 32;
+
+/// A matrix, each row stored in the lowest bits of a word, the first column in the highest one.
+type Matrix = [u64; DIMENSION];
+
+/// Reads the matrices of the test from the data, each row from 4 bytes.
+fn matrices(data: &BitVec) -> Vec<Matrix> {
+    let (bytes, _) = data.to_bytes();
+
+    bytes
+        .chunks_exact(DIMENSION * 4)
+        .map(|matrix| {
+            let mut rows = [0; DIMENSION];
+            for (row, bytes) in rows.iter_mut().zip(matrix.chunks_exact(4)) {
+                *row = u32::from_be_bytes(bytes.try_into().unwrap()) as u64;
+            }
+            rows
+        })
+        .collect()
+}
+
+/// The rank of the matrix, with the rows below the pivot reduced by the given function, which
+/// gets the row, the pivot row and the shift of the pivot column.
+fn rank(mut rows: Matrix, reduce: impl Fn(&mut u64, u64, usize)) -> usize {
+    let mut rank = 0;
+
+    for col in 0..DIMENSION {
+        let shift = DIMENSION - col - 1;
+        let Some(found_row) = (rank..DIMENSION).find(|&row| (rows[row] >> shift) & 1 == 1) else {
+            continue;
+        };
+        rows.swap(rank, found_row);
+
+        let pivot = rows[rank];
+        for row in &mut rows[(rank + 1)..] {
+            reduce(row, pivot, shift);
+        }
+
+        rank += 1;
+    }
+
+    rank
+}
+
+/// The previous reduction: the pivot row is only XORed into rows with a 1 in the pivot column.
+fn rank_branching(rows: Matrix) -> usize {
+    rank(rows, |row, pivot, shift| {
+        if (*row >> shift) & 1 == 1 {
+            *row ^= pivot;
+        }
+    })
+}
+
+/// The reduction of the test: the mask is all ones if the row has a 1 in the pivot column, else 0.
+fn rank_branchless(rows: Matrix) -> usize {
+    rank(rows, |row, pivot, shift| {
+        let mask = 0_u64.wrapping_sub((*row >> shift) & 1);
+        *row ^= pivot & mask;
+    })
+}
+
+fn binary_matrix_rank(c: &mut Criterion) {
+    let mut group = c.benchmark_group("binary_matrix_rank");
+    let arg = BinaryMatrixRankTestArg::default();
+
+    for (name, file_name) in TEST_FILES {
+        let data = test_file(file_name);
+        let matrices = matrices(&data);
+        assert!(
+            matrices
+                .iter()
+                .all(|&matrix| rank_branching(matrix) == rank_branchless(matrix)),
+            "the reductions result in different ranks for {name}"
+        );
+        group.throughput(Throughput::Bytes(data.len_bit() as u64 / 8));
+
+        group.bench_with_input(BenchmarkId::new("test", name), &data, |b, data| {
+            b.iter(|| binary_matrix_rank_test(black_box(data), arg).unwrap())
+        });
+        group.bench_with_input(
+            BenchmarkId::new("branching", name),
+            &matrices,
+            |b, matrices| {
+                b.iter(|| {
+                    black_box(matrices)
+                        .iter()
+                        .map(|&matrix| rank_branching(matrix))
+                        .sum::<usize>()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("branchless", name),
+            &matrices,
+            |b, matrices| {
+                b.iter(|| {
+                    black_box(matrices)
+                        .iter()
+                        .map(|&matrix| rank_branchless(matrix))
+                        .sum::<usize>()
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, binary_matrix_rank);
+criterion_main!(benches);
@@ -0,0 +1,45 @@
+//! Helpers shared by the benchmarks.
+
+// each benchmark only uses some of the helpers
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::Path;
+use sts_lib::bitvec::BitVec;
+
+/// The directory containing the test files.
+const TEST_FILE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/test-files");
+
+/// The standard test files with 10^6 bits, with their names.
+pub const TEST_FILES: [(&str, &str); 5] = [
+    ("e", "e.1e6.bin"),
+    ("pi", "pi.1e6.bin"),
+    ("sha1", "sha1.1e6.bin"),
+    ("sqrt2", "sqrt2.1e6.bin"),
+    ("sqrt3", "sqrt3.1e6.bin"),
+];
+
+/// Creates deterministic pseudo-random input data of the given bit length (xorshift).
+pub fn input(len_bit: usize) -> BitVec<'static> {
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let bytes = (0..len_bit.div_ceil(8))
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect::<Vec<_>>();
+
+    let mut data = BitVec::from(bytes);
+    data.crop(len_bit);
+    data
+}
+
+/// Reads the given file of [TEST_FILES].
+pub fn test_file(file_name: &str) -> BitVec<'static> {
+    let path = Path::new(TEST_FILE_PATH).join(file_name);
+    let data = fs::read(&path)
+        .unwrap_or_else(|e| panic!("Cannot read the test file {}: {e}", path.display()));
+    BitVec::from(data)
+}
@@ -0,0 +1,38 @@
+//! Benchmarks for Maurer's universal statistical test, for inputs from 10^6 to 10^8 bits (block
+//! lengths 7, 9 and 12).
+//!
+//! Run with `cargo bench -p sts-lib --bench maurers_universal_statistical`.
+
+mod common;
+
+use common::input;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::hint::black_box;
+use sts_lib::tests::maurers_universal_statistical::maurers_universal_statistical_test;
+
+/// The input lengths in bits, with their names.
+const LENGTHS: [(&str, usize); 3] = [
+    ("1e6", 1_000_000),
+    ("1e7", 10_000_000),
+    ("1e8", 100_000_000),
+];
+
+fn maurers_universal_statistical(c: &mut Criterion) {
+    let mut group = c.benchmark_group("maurers_universal_statistical");
+    // the largest inputs take about a second for each iteration
+    group.sample_size(10);
+
+    for (name, len_bit) in LENGTHS {
+        let data = input(len_bit);
+        group.throughput(Throughput::Bytes(len_bit as u64 / 8));
+
+        group.bench_with_input(BenchmarkId::new("default", name), &data, |b, data| {
+            b.iter(|| maurers_universal_statistical_test(black_box(data)).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, maurers_universal_statistical);
+criterion_main!(benches);
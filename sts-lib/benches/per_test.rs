@@ -0,0 +1,45 @@
+//! Benchmarks for each test with the arguments of the NIST reference implementation, on the
+//! standard 10^6 bit test files in `test-files` (e, pi, sha1, sqrt2 and sqrt3), the same inputs
+//! as used by the benchmarking application. This allows to detect performance regressions
+//! without the modified `assess` binary of the reference implementation.
+//!
+//! Run with `cargo bench -p sts-lib --bench per_test`. A single test can be selected with a filter,
+//! e.g. `cargo bench -p sts-lib --bench per_test -- LinearComplexity`.
+
+mod common;
+
+use common::{test_file, TEST_FILES};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::hint::black_box;
+use sts_lib::test_runner::run_tests;
+use sts_lib::{IntoEnumIterator, Test, TestArgs};
+
+fn per_test(c: &mut Criterion) {
+    let mut group = c.benchmark_group("per_test");
+    // the slowest tests take several 100 ms for each iteration
+    group.sample_size(10);
+
+    let args = TestArgs::nist_sp800_22_default();
+    let files = TEST_FILES.map(|(name, file_name)| (name, test_file(file_name)));
+
+    for test in Test::iter() {
+        for (name, data) in &files {
+            group.throughput(Throughput::Bytes(data.len_bit() as u64 / 8));
+
+            group.bench_with_input(BenchmarkId::new(test.to_string(), name), data, |b, data| {
+                b.iter(|| {
+                    let (_, result) = run_tests(black_box(data), [test].into_iter(), args)
+                        .unwrap()
+                        .next()
+                        .unwrap();
+                    result.unwrap()
+                })
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, per_test);
+criterion_main!(benches);
@@ -0,0 +1,49 @@
+//! Benchmarks for the serial and approximate entropy tests with the default arguments (block
+//! lengths 16 and 10), for inputs from 10^6 to 10^8 bits.
+//!
+//! Run with `cargo bench -p sts-lib --bench serial_and_approximate_entropy`.
+
+mod common;
+
+use common::input;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::hint::black_box;
+use sts_lib::tests::approximate_entropy::{approximate_entropy_test, ApproximateEntropyTestArg};
+use sts_lib::tests::serial::{serial_test, SerialTestArg};
+
+/// The input lengths in bits, with their names.
+const LENGTHS: [(&str, usize); 3] = [
+    ("1e6", 1_000_000),
+    ("1e7", 10_000_000),
+    ("1e8", 100_000_000),
+];
+
+fn serial_and_approximate_entropy(c: &mut Criterion) {
+    let mut group = c.benchmark_group("serial_and_approximate_entropy");
+    // the largest inputs take several seconds for each iteration
+    group.sample_size(10);
+
+    for (name, len_bit) in LENGTHS {
+        let data = input(len_bit);
+        group.throughput(Throughput::Bytes(len_bit as u64 / 8));
+
+        group.bench_with_input(BenchmarkId::new("serial", name), &data, |b, data| {
+            b.iter(|| serial_test(black_box(data), SerialTestArg::default()).unwrap())
+        });
+        group.bench_with_input(
+            BenchmarkId::new("approximate_entropy", name),
+            &data,
+            |b, data| {
+                b.iter(|| {
+                    approximate_entropy_test(black_box(data), ApproximateEntropyTestArg::default())
+                        .unwrap()
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, serial_and_approximate_entropy);
+criterion_main!(benches);
@@ -0,0 +1,38 @@
+//! Benchmarks for the spectral DFT test with the default arguments, for inputs from 10^6 to 10^8
+//! bits.
+//!
+//! Run with `cargo bench -p sts-lib --bench spectral_dft`.
+
+mod common;
+
+use common::input;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::hint::black_box;
+use sts_lib::tests::spectral_dft::{spectral_dft_test, SpectralDftTestArg};
+
+/// The input lengths in bits, with their names.
+const LENGTHS: [(&str, usize); 3] = [
+    ("1e6", 1_000_000),
+    ("1e7", 10_000_000),
+    ("1e8", 100_000_000),
+];
+
+fn spectral_dft(c: &mut Criterion) {
+    let mut group = c.benchmark_group("spectral_dft");
+    // the largest inputs take several seconds for each iteration
+    group.sample_size(10);
+
+    for (name, len_bit) in LENGTHS {
+        let data = input(len_bit);
+        group.throughput(Throughput::Bytes(len_bit as u64 / 8));
+
+        group.bench_with_input(BenchmarkId::new("default", name), &data, |b, data| {
+            b.iter(|| spectral_dft_test(black_box(data), SpectralDftTestArg::default()).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, spectral_dft);
+criterion_main!(benches);
@@ -0,0 +1,46 @@
+//! Benchmarks for the non-overlapping template matching test with the default arguments (148
+//! templates of length 9, 8 blocks), for inputs from 10^6 to 10^8 bits.
+//!
+//! Run with `cargo bench -p sts-lib --bench template_matching`.
+
+mod common;
+
+use common::input;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::hint::black_box;
+use sts_lib::tests::template_matching::non_overlapping::{
+    non_overlapping_template_matching_test, NonOverlappingTemplateTestArgs,
+};
+
+/// The input lengths in bits, with their names.
+const LENGTHS: [(&str, usize); 3] = [
+    ("1e6", 1_000_000),
+    ("1e7", 10_000_000),
+    ("1e8", 100_000_000),
+];
+
+fn non_overlapping(c: &mut Criterion) {
+    let mut group = c.benchmark_group("template_matching");
+    // the largest inputs take long for each iteration
+    group.sample_size(10);
+
+    let arg = NonOverlappingTemplateTestArgs::default();
+
+    for (name, len_bit) in LENGTHS {
+        let data = input(len_bit);
+        group.throughput(Throughput::Bytes(len_bit as u64 / 8));
+
+        group.bench_with_input(
+            BenchmarkId::new("non_overlapping", name),
+            &data,
+            |b, data| {
+                b.iter(|| non_overlapping_template_matching_test(black_box(data), arg).unwrap())
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, non_overlapping);
+criterion_main!(benches);
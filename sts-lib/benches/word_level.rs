@@ -0,0 +1,49 @@
+//! Benchmarks for the tests that work on whole words instead of single bits: frequency, runs and
+//! cumulative sums, for inputs from 10^6 to 10^9 bits.
+//!
+//! Run with `cargo bench -p sts-lib --bench word_level`.
+
+mod common;
+
+use common::input;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::hint::black_box;
+use sts_lib::tests::cumulative_sums::cumulative_sums_test;
+use sts_lib::tests::frequency::frequency_test;
+use sts_lib::tests::runs::runs_test;
+
+/// The input lengths in bits, with their names.
+const LENGTHS: [(&str, usize); 4] = [
+    ("1e6", 1_000_000),
+    ("1e7", 10_000_000),
+    ("1e8", 100_000_000),
+    ("1e9", 1_000_000_000),
+];
+
+fn word_level(c: &mut Criterion) {
+    let mut group = c.benchmark_group("word_level");
+    // the largest inputs take long for each iteration
+    group.sample_size(10);
+
+    for (name, len_bit) in LENGTHS {
+        let data = input(len_bit);
+        group.throughput(Throughput::Bytes(len_bit as u64 / 8));
+
+        group.bench_with_input(BenchmarkId::new("frequency", name), &data, |b, data| {
+            b.iter(|| frequency_test(black_box(data)).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("runs", name), &data, |b, data| {
+            b.iter(|| runs_test(black_box(data)).unwrap())
+        });
+        group.bench_with_input(
+            BenchmarkId::new("cumulative_sums", name),
+            &data,
+            |b, data| b.iter(|| cumulative_sums_test(black_box(data)).unwrap()),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, word_level);
+criterion_main!(benches);
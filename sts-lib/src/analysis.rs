@@ -0,0 +1,293 @@
+//! Analysis of the results of tests over multiple sequences, see [analyse].
+//!
+//! As described in section 4.2.1 of NIST SP 800-22r1a, the proportion of sequences passing a test
+//! is compared to the acceptance interval `p̂ ± 3 * sqrt(p̂ * (1 - p̂) / m)`, with `p̂ = 1 - α` for
+//! the threshold *α* and *m* being the sample size (see [acceptance_interval]). If the proportion
+//! lies outside of this interval, there is evidence that the data is not random.
+//!
+//! Complementing the proportion, the uniformity of the P-values of a test over all sequences can
+//! be checked with [p_value_uniformity], as described in section 4.2.2.
+//!
+//! The [MultiSequenceRunner](crate::test_runner::MultiSequenceRunner) does both analyses while
+//! running the tests.
+
+use crate::internals::{check_f64, igamc};
+use crate::statistics::TestStatistics;
+use crate::test_runner::multi_sequence::BIN_COUNT;
+use crate::{Error, NotApplicableReason, Test, TestResult};
+
+/// Calculates the acceptance interval (min, max) for the proportion of passed sequences, for the
+/// given threshold and sample size. If the sample size is 0, the interval is empty (min and max
+/// are NaN).
+///
+/// ```
+/// use sts_lib::analysis::acceptance_interval;
+///
+/// // the example of SP 800-22r1a, section 4.2.1
+/// let (min, max) = acceptance_interval(0.01, 1000);
+/// assert!((min - 0.9805607).abs() < 1e-6);
+/// assert!((max - 0.9994393).abs() < 1e-6);
+/// ```
+pub fn acceptance_interval(threshold: f64, sample_size: usize) -> (f64, f64) {
+    let p_hat = 1.0 - threshold;
+    let delta = 3.0 * f64::sqrt(p_hat * (1.0 - p_hat) / sample_size as f64);
+    (p_hat - delta, p_hat + delta)
+}
+
+/// Checks the uniformity of the given P-values, e.g. the P-values of one test over multiple
+/// sequences, with the chi-square test of NIST SP 800-22r1a, section 4.2.2: the P-values are sorted
+/// into [BIN_COUNT] bins `[0.0, 0.1)`, ..., `[0.9, 1.0]` and the bin frequencies are compared to
+/// the frequencies expected for uniformly distributed P-values.
+///
+/// The P-value of the result is the P-value of the P-values. NIST considers the P-values to be
+/// uniformly distributed if it is at least
+/// [UNIFORMITY_THRESHOLD](crate::test_runner::multi_sequence::UNIFORMITY_THRESHOLD), see
+/// [TestResult::passed].
+/// NIST recommends at least 55 P-values for this test.
+///
+/// If no P-values are given, the result is [not applicable](crate::ResultStatus::NotApplicable).
+///
+/// ```
+/// use sts_lib::analysis::p_value_uniformity;
+/// use sts_lib::test_runner::multi_sequence::UNIFORMITY_THRESHOLD;
+///
+/// let p_values = (0..100).map(|i| i as f64 / 100.0).collect::<Vec<_>>();
+/// let result = p_value_uniformity(&p_values);
+/// assert_eq!(result.p_value(), 1.0);
+/// assert!(result.passed(UNIFORMITY_THRESHOLD));
+/// ```
+pub fn p_value_uniformity(p_values: &[f64]) -> TestResult {
+    if p_values.is_empty() {
+        return TestResult::not_applicable(
+            "No P-values were given.",
+            NotApplicableReason::EmptySample,
+        );
+    }
+
+    let histogram = p_value_histogram(p_values);
+    // the chi-square is always finite and non-negative for a sample size > 0.
+    let (chi_square, p_value) = uniformity_chi_square(&histogram, p_values.len())
+        .expect("the chi-square test cannot fail for a non-empty sample");
+
+    TestResult::new(p_value).with_statistics(TestStatistics::Uniformity {
+        sample_size: p_values.len(),
+        chi_square,
+    })
+}
+
+/// Sorts the P-values into the [BIN_COUNT] bins `[0.0, 0.1)`, ..., `[0.9, 1.0]`.
+pub(crate) fn p_value_histogram(p_values: &[f64]) -> [usize; BIN_COUNT] {
+    let mut histogram = [0_usize; BIN_COUNT];
+    for &p_value in p_values {
+        // P-value 1.0 belongs in the last bin
+        let bin = usize::min((p_value * BIN_COUNT as f64) as usize, BIN_COUNT - 1);
+        histogram[bin] += 1;
+    }
+    histogram
+}
+
+/// Calculates the chi-square statistic and the P-value of the uniformity of the P-values in the
+/// given histogram.
+pub(crate) fn uniformity_chi_square(
+    histogram: &[usize; BIN_COUNT],
+    sample_size: usize,
+) -> Result<(f64, f64), Error> {
+    let expected = sample_size as f64 / BIN_COUNT as f64;
+
+    let chi_square = histogram
+        .iter()
+        .map(|&frequency| (frequency as f64 - expected).powi(2) / expected)
+        .sum::<f64>();
+    check_f64(chi_square)?;
+
+    // the incomplete gamma function is not defined for x = 0: the frequencies match exactly.
+    let p_value = if chi_square == 0.0 {
+        1.0
+    } else {
+        igamc((BIN_COUNT - 1) as f64 / 2.0, chi_square / 2.0)?
+    };
+    check_f64(p_value)?;
+
+    Ok((chi_square, p_value))
+}
+
+/// Analyses the results of tests over multiple sequences: for each test, the results of all
+/// sequences are given, each being the list of results the test returned for one sequence
+/// (e.g. from [run_tests](crate::test_runner::run_tests)).
+///
+/// Returns one [TestVerdict] per given test, in the given order. The tests are usually identified
+/// by [Test], [custom tests](crate::test_runner::custom::CustomTest) by their name.
+///
+/// The given `threshold` is used to determine if a single result passed, see
+/// [TestResult::passed]. Sequences with results that are not
+/// [applicable](TestResult::is_applicable) are not part of the sample.
+///
+/// ```
+/// use sts_lib::analysis::analyse;
+/// use sts_lib::bitvec::BitVec;
+/// use sts_lib::tests::frequency::frequency_test;
+/// use sts_lib::{Test, DEFAULT_THRESHOLD};
+///
+/// let sequences = ["1011010101", "1100100100", "0110101101"];
+/// let results = sequences
+///     .iter()
+///     .map(|sequence| {
+///         let data = BitVec::from_ascii_str(sequence).unwrap();
+///         vec![frequency_test(&data).unwrap()]
+///     })
+///     .collect::<Vec<_>>();
+///
+/// let verdicts = analyse([(Test::Frequency, results)], DEFAULT_THRESHOLD);
+/// assert_eq!(verdicts.len(), 1);
+/// assert_eq!(verdicts[0].results()[0].sample_size(), 3);
+/// assert!(verdicts[0].passed());
+/// ```
+pub fn analyse<T, S, R>(
+    results: impl IntoIterator<Item = (T, S)>,
+    threshold: f64,
+) -> Vec<TestVerdict<T>>
+where
+    T: Copy,
+    S: IntoIterator<Item = R>,
+    R: AsRef<[TestResult]>,
+{
+    results
+        .into_iter()
+        .map(|(test, sequences)| {
+            // the P-values for each result index
+            let mut p_values: Vec<Vec<f64>> = Vec::new();
+            let mut not_applicable_count = 0;
+
+            for results in sequences {
+                let results = results.as_ref();
+
+                if results.iter().any(|result| !result.is_applicable()) {
+                    not_applicable_count += 1;
+                    continue;
+                }
+
+                for (idx, result) in results.iter().enumerate() {
+                    if idx == p_values.len() {
+                        p_values.push(Vec::new());
+                    }
+
+                    p_values[idx].push(result.p_value());
+                }
+            }
+
+            TestVerdict::from_p_values(test, &p_values, not_applicable_count, threshold)
+        })
+        .collect()
+}
+
+/// The verdict for one test over multiple sequences, see [analyse]. The test is identified by
+/// `T`: [Test], or the name of a [custom test](crate::test_runner::custom::CustomTest).
+#[derive(Clone, Debug)]
+pub struct TestVerdict<T = Test> {
+    test: T,
+    results: Vec<ResultProportion>,
+    not_applicable_count: usize,
+}
+
+impl<T: Copy> TestVerdict<T> {
+    /// Creates the verdict from the P-values of all sequences, for each result index.
+    pub(crate) fn from_p_values(
+        test: T,
+        p_values: &[Vec<f64>],
+        not_applicable_count: usize,
+        threshold: f64,
+    ) -> Self {
+        let results = p_values
+            .iter()
+            .enumerate()
+            .map(|(result_idx, p_values)| {
+                ResultProportion::from_p_values(result_idx, p_values, threshold)
+            })
+            .collect();
+
+        Self {
+            test,
+            results,
+            not_applicable_count,
+        }
+    }
+
+    /// The test this verdict is about.
+    pub fn test(&self) -> T {
+        self.test
+    }
+
+    /// The proportion of passed sequences, for each result index (for tests that return multiple
+    /// results). Is empty if no sequence returned an applicable result.
+    pub fn results(&self) -> &[ResultProportion] {
+        &self.results
+    }
+
+    /// The count of sequences the test was [not applicable](crate::ResultStatus::NotApplicable)
+    /// to. These are not part of the sample.
+    pub fn not_applicable_count(&self) -> usize {
+        self.not_applicable_count
+    }
+
+    /// If the test passed: the proportion of every result lies within the acceptance interval.
+    /// Is `false` if there are no results.
+    pub fn passed(&self) -> bool {
+        !self.results.is_empty() && self.results.iter().all(ResultProportion::passed)
+    }
+}
+
+/// The proportion of sequences that passed one result of a test, see [TestVerdict::results].
+#[derive(Copy, Clone, Debug)]
+pub struct ResultProportion {
+    result_idx: usize,
+    sample_size: usize,
+    passed_count: usize,
+    threshold: f64,
+}
+
+impl ResultProportion {
+    /// Creates the proportion of the given P-values that are at least `threshold`.
+    pub(crate) fn from_p_values(result_idx: usize, p_values: &[f64], threshold: f64) -> Self {
+        Self {
+            result_idx,
+            sample_size: p_values.len(),
+            passed_count: p_values.iter().filter(|&&p| p >= threshold).count(),
+            threshold,
+        }
+    }
+
+    /// The index of the result, for tests that return multiple results.
+    pub fn result_idx(&self) -> usize {
+        self.result_idx
+    }
+
+    /// The count of sequences that returned an applicable result.
+    pub fn sample_size(&self) -> usize {
+        self.sample_size
+    }
+
+    /// The count of sequences that passed the test.
+    pub fn passed_count(&self) -> usize {
+        self.passed_count
+    }
+
+    /// The proportion of sequences that passed the test. Is 0 if the sample size is 0.
+    pub fn proportion(&self) -> f64 {
+        if self.sample_size == 0 {
+            0.0
+        } else {
+            self.passed_count as f64 / self.sample_size as f64
+        }
+    }
+
+    /// The acceptance interval (min, max) for the proportion, see [acceptance_interval].
+    pub fn acceptance_interval(&self) -> (f64, f64) {
+        acceptance_interval(self.threshold, self.sample_size)
+    }
+
+    /// If the proportion lies within the [acceptance interval](Self::acceptance_interval).
+    pub fn passed(&self) -> bool {
+        let (min, max) = self.acceptance_interval();
+        self.sample_size > 0 && (min..=max).contains(&self.proportion())
+    }
+}
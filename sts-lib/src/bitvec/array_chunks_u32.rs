@@ -4,7 +4,9 @@
 // 32 and 64-bit platforms.
 
 use crate::bitvec::BitVec;
+#[cfg(feature = "std")]
 use rayon::iter::plumbing::{bridge, Consumer, Producer, ProducerCallback, UnindexedConsumer};
+#[cfg(feature = "std")]
 use rayon::prelude::*;
 
 /// Supports iteration over N u32 at a time. N must be even.
@@ -15,6 +17,7 @@ impl<const N: usize> BitVecU32Chunks<'_, N> {
     ///
     /// Panics if the length is greater than the iterator length.
     //noinspection RsAssertEqual
+    #[cfg(feature = "std")]
     fn split(self, len: usize) -> (Self, Self) {
         const { assert!(N % 2 == 0, "N must be even") };
 
@@ -47,7 +50,7 @@ impl<const N: usize> Iterator for BitVecU32Chunks<'_, N> {
     //noinspection RsAssertEqual
     #[cfg(target_pointer_width = "64")]
     fn next(&mut self) -> Option<Self::Item> {
-        use std::array;
+        use core::array;
         const { assert!(N % 2 == 0, "N must be even") };
 
         let count_usize = N / 2;
@@ -68,7 +71,7 @@ impl<const N: usize> Iterator for BitVecU32Chunks<'_, N> {
 
     #[cfg(target_pointer_width = "32")]
     fn next(&mut self) -> Option<Self::Item> {
-        use std::mem;
+        use core::mem;
 
         let (data, last) = self.0.split_first_chunk::<N>()?;
         self.0 = last;
@@ -96,7 +99,7 @@ impl<const N: usize> ExactSizeIterator for BitVecU32Chunks<'_, N> {
             }
             #[cfg(target_pointer_width = "32")]
             {
-                self.data.len()
+                self.0.len()
             }
         };
 
@@ -107,7 +110,7 @@ impl<const N: usize> ExactSizeIterator for BitVecU32Chunks<'_, N> {
 impl<const N: usize> DoubleEndedIterator for BitVecU32Chunks<'_, N> {
     #[cfg(target_pointer_width = "64")]
     fn next_back(&mut self) -> Option<Self::Item> {
-        use std::array;
+        use core::array;
 
         let count_usize = N / 2;
         let (first, data) = self.0.split_at_checked(self.0.len() - count_usize)?;
@@ -127,7 +130,7 @@ impl<const N: usize> DoubleEndedIterator for BitVecU32Chunks<'_, N> {
 
     #[cfg(target_pointer_width = "32")]
     fn next_back(&mut self) -> Option<Self::Item> {
-        use std::mem;
+        use core::mem;
 
         let (first, data) = self.0.split_last_chunk::<N>()?;
         self.0 = first;
@@ -139,8 +142,10 @@ impl<const N: usize> DoubleEndedIterator for BitVecU32Chunks<'_, N> {
 }
 
 /// Supports iteration over N u32 at a time. N must be even. Parallel.
+#[cfg(feature = "std")]
 pub struct BitVecU32ParChunks<'a, const N: usize>(BitVecU32Chunks<'a, N>);
 
+#[cfg(feature = "std")]
 impl<const N: usize> IndexedParallelIterator for BitVecU32ParChunks<'_, N> {
     fn len(&self) -> usize {
         self.0.len()
@@ -172,6 +177,7 @@ impl<const N: usize> IndexedParallelIterator for BitVecU32ParChunks<'_, N> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<'a, const N: usize> ParallelIterator for BitVecU32ParChunks<'a, N> {
     type Item = <BitVecU32Chunks<'a, N> as Iterator>::Item;
 
@@ -184,11 +190,11 @@ impl<'a, const N: usize> ParallelIterator for BitVecU32ParChunks<'a, N> {
 }
 
 // constructors on BitVec
-impl BitVec {
+impl BitVec<'_> {
     /// Returns an iterator that yields N u32 values at a time. N must be even.
     // const context does not support assert_eq!()
     //noinspection RsAssertEqual
-    pub fn array_chunks_u32<const N: usize>(&self) -> BitVecU32Chunks<N> {
+    pub fn array_chunks_u32<const N: usize>(&self) -> BitVecU32Chunks<'_, N> {
         const { assert!(N % 2 == 0, "N must be even") };
 
         let (data, _) = self.as_full_slice();
@@ -197,7 +203,8 @@ impl BitVec {
 
     /// Returns a parallel iterator that yields N u32 values at a time. N must be even.
     //noinspection RsAssertEqual
-    pub fn par_array_chunks_u32<const N: usize>(&self) -> BitVecU32ParChunks<N> {
+    #[cfg(feature = "std")]
+    pub fn par_array_chunks_u32<const N: usize>(&self) -> BitVecU32ParChunks<'_, N> {
         const { assert!(N % 2 == 0, "N must be even") };
         BitVecU32ParChunks(self.array_chunks_u32())
     }
@@ -0,0 +1,170 @@
+//! Streaming decoding of base64 input, see [Base64Reader].
+
+use std::io;
+use std::io::{BufRead, ErrorKind, Read};
+
+/// A reader adapter that decodes base64 text (standard alphabet, as defined in RFC 4648) from the
+/// wrapped reader into raw bytes.
+///
+/// ASCII whitespace (including newlines) is skipped, padding with "=" is optional. Padding may also
+/// appear in the middle of the input, e.g. for multiple concatenated base64 strings. Any other
+/// character leads to an error of kind [ErrorKind::InvalidData].
+///
+/// This adapter is used for [ReaderFormat::Base64](super::builder::ReaderFormat::Base64). To read
+/// base64 input in parts (e.g. into multiple [BitVecBuilder](super::builder::BitVecBuilder)),
+/// wrap the reader once and use [ReaderFormat::Binary](super::builder::ReaderFormat::Binary), so
+/// that no decoded data is lost between the parts.
+#[derive(Debug)]
+pub struct Base64Reader<R> {
+    inner: R,
+    // decoded bytes
+    buffer: Vec<u8>,
+    // the position of the first unread byte in the buffer
+    pos: usize,
+    // the current incomplete group of 4 characters
+    group: Group,
+}
+
+impl<R: BufRead> Base64Reader<R> {
+    /// Creates a new adapter, decoding the base64 text of the given reader.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            buffer: Vec::new(),
+            pos: 0,
+            group: Group::default(),
+        }
+    }
+
+    /// Returns the wrapped reader. Any decoded but unread data is lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+/// A group of up to 4 base64 characters, already decoded to 6 bits each.
+#[derive(Copy, Clone, Debug, Default)]
+struct Group {
+    values: [u8; 4],
+    len: usize,
+}
+
+impl Group {
+    /// Appends the decoded value of one character. If the group is complete, its bytes are
+    /// written to the buffer.
+    fn push(&mut self, value: u8, buffer: &mut Vec<u8>) {
+        self.values[self.len] = value;
+        self.len += 1;
+
+        if self.len == self.values.len() {
+            // cannot fail for a complete group
+            _ = self.finish(buffer);
+        }
+    }
+
+    /// Writes the bytes of the (possibly incomplete) group to the buffer and resets it. A group of
+    /// only one character contains no full byte and is invalid, in this case, the group is kept.
+    fn finish(&mut self, buffer: &mut Vec<u8>) -> io::Result<()> {
+        let [a, b, c, d] = self.values;
+        let len = self.len;
+
+        match len {
+            0 => Ok(()),
+            1 => Err(invalid_data("Incomplete base64 group")),
+            len => {
+                *self = Self::default();
+                let bytes = [(a << 2) | (b >> 4), (b << 4) | (c >> 2), (c << 6) | d];
+                // 2 characters contain 1 byte, 3 characters 2 bytes and 4 characters 3 bytes.
+                buffer.extend_from_slice(&bytes[..len - 1]);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<R: BufRead> BufRead for Base64Reader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        while self.pos >= self.buffer.len() {
+            self.buffer.clear();
+            self.pos = 0;
+
+            let input = match self.inner.fill_buf() {
+                Ok(input) => input,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+
+            if input.is_empty() {
+                // EOF: the last group may be unpadded
+                self.group.finish(&mut self.buffer)?;
+                break;
+            }
+
+            let mut consumed = 0;
+            let mut error = None;
+
+            for &char in input {
+                if char == b'=' {
+                    // padding ends the current group
+                    if let Err(e) = self.group.finish(&mut self.buffer) {
+                        error = Some(e);
+                        break;
+                    }
+                } else if !char.is_ascii_whitespace() {
+                    let Some(value) = decode_char(char) else {
+                        error = Some(invalid_data(
+                            "Input contains characters other than base64 or whitespace",
+                        ));
+                        break;
+                    };
+                    self.group.push(value, &mut self.buffer);
+                }
+
+                consumed += 1;
+            }
+
+            self.inner.consume(consumed);
+
+            if let Some(error) = error {
+                // return the data decoded so far first, the error occurs again on the next call.
+                if self.buffer.is_empty() {
+                    return Err(error);
+                }
+                break;
+            }
+        }
+
+        Ok(&self.buffer[self.pos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = usize::min(self.pos + amt, self.buffer.len());
+    }
+}
+
+impl<R: BufRead> Read for Base64Reader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let len = usize::min(available.len(), buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+/// Decodes one character of the standard base64 alphabet to its 6-bit value.
+fn decode_char(char: u8) -> Option<u8> {
+    match char {
+        b'A'..=b'Z' => Some(char - b'A'),
+        b'a'..=b'z' => Some(char - b'a' + 26),
+        b'0'..=b'9' => Some(char - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    }
+}
+
+/// Creates an IO error of kind [ErrorKind::InvalidData].
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg)
+}
@@ -0,0 +1,178 @@
+//! Iterators over the single bits of a BitVec, see [Bits] and [ParBits].
+
+use crate::bitvec::word::{self, Word};
+use crate::bitvec::BitVec;
+use core::iter::FusedIterator;
+#[cfg(feature = "std")]
+use rayon::iter::plumbing::{bridge, Consumer, Producer, ProducerCallback, UnindexedConsumer};
+#[cfg(feature = "std")]
+use rayon::prelude::*;
+
+/// Iterator over the bits of a [BitVec], yielding each bit as a [bool], starting with the first
+/// bit. Created with [BitVec::iter_bits].
+#[derive(Clone, Debug)]
+pub struct Bits<'a> {
+    words: &'a [Word],
+    // index of the next bit to yield from the front
+    start: usize,
+    // index after the next bit to yield from the back
+    end: usize,
+}
+
+impl Bits<'_> {
+    /// Split the iterator into 2, with the first one having the specified length.
+    ///
+    /// Panics if the length is greater than the iterator length.
+    #[cfg(feature = "std")]
+    fn split(self, len: usize) -> (Self, Self) {
+        assert!(len <= self.len(), "split index out of bounds");
+
+        let mid = self.start + len;
+        let part1 = Self {
+            words: self.words,
+            start: self.start,
+            end: mid,
+        };
+        let part2 = Self {
+            words: self.words,
+            start: mid,
+            end: self.end,
+        };
+        (part1, part2)
+    }
+
+    /// Returns the bit at the given absolute index.
+    fn bit(&self, idx: usize) -> bool {
+        word::get_bit(self.words[word::word_index(idx)], word::bit_in_word(idx))
+    }
+}
+
+impl Iterator for Bits<'_> {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.start >= self.end {
+            return None;
+        }
+
+        let bit = self.bit(self.start);
+        self.start += 1;
+        Some(bit)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.start = self.start.saturating_add(n).min(self.end);
+        self.next()
+    }
+}
+
+impl DoubleEndedIterator for Bits<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.start >= self.end {
+            return None;
+        }
+
+        self.end -= 1;
+        Some(self.bit(self.end))
+    }
+}
+
+impl ExactSizeIterator for Bits<'_> {}
+
+impl FusedIterator for Bits<'_> {}
+
+/// Parallel iterator over the bits of a [BitVec], see [Bits]. Created with
+/// [BitVec::par_iter_bits].
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct ParBits<'a>(Bits<'a>);
+
+#[cfg(feature = "std")]
+impl IndexedParallelIterator for ParBits<'_> {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> C::Result {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> CB::Output {
+        struct CurrentProducer<'b>(Bits<'b>);
+
+        impl<'b> Producer for CurrentProducer<'b> {
+            type Item = bool;
+
+            type IntoIter = Bits<'b>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.0
+            }
+
+            fn split_at(self, index: usize) -> (Self, Self) {
+                let (i1, i2) = self.0.split(index);
+                (Self(i1), Self(i2))
+            }
+        }
+
+        callback.callback(CurrentProducer(self.0))
+    }
+}
+
+#[cfg(feature = "std")]
+impl ParallelIterator for ParBits<'_> {
+    type Item = bool;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+impl<'a> IntoIterator for &'a BitVec<'_> {
+    type Item = bool;
+    type IntoIter = Bits<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_bits()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> IntoParallelIterator for &'a BitVec<'_> {
+    type Iter = ParBits<'a>;
+    type Item = bool;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.par_iter_bits()
+    }
+}
+
+// constructors on BitVec
+impl BitVec<'_> {
+    /// Returns an iterator that yields each bit as a [bool], starting with the first bit.
+    pub fn iter_bits(&self) -> Bits<'_> {
+        Bits {
+            words: &self.words,
+            start: 0,
+            end: self.len_bit(),
+        }
+    }
+
+    /// Returns a parallel iterator that yields each bit as a [bool]. See [Self::iter_bits].
+    #[cfg(feature = "std")]
+    pub fn par_iter_bits(&self) -> ParBits<'_> {
+        ParBits(self.iter_bits())
+    }
+}
@@ -0,0 +1,420 @@
+//! Incremental construction of a [BitVec], e.g. from a [Read] source.
+//!
+//! This allows to convert very large inputs without having to load the raw input into memory first:
+//! only the resulting [BitVec] is stored.
+
+#[cfg(feature = "std")]
+use crate::bitvec::base64::Base64Reader;
+use crate::bitvec::word::{Word, WORD_BITS};
+use crate::bitvec::{BitOrder, BitVec};
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "std")]
+use std::io::{BufRead, BufReader, ErrorKind, Read};
+
+/// The format of raw input data, used when reading a [BitVec] with [BitVec::from_reader] or
+/// [BitVecBuilder::extend_from_reader].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ReaderFormat {
+    /// Binary input: each byte contains 8 bits, starting with the MSB (unless another
+    /// [bit order](BitVecBuilder::set_bit_order) is set).
+    Binary,
+    /// ASCII input: the character "0" maps to 0 and "1" maps to 1. No other character is allowed.
+    Ascii,
+    /// ASCII input: the character "0" maps to 0 and "1" maps to 1. Any other character is skipped.
+    AsciiLossy,
+    /// ASCII input: the character "0" maps to 0 and "1" maps to 1. ASCII whitespace (including
+    /// newlines) is skipped, no other character is allowed. This is the format of the data files
+    /// of the NIST reference implementation, which group the digits with spaces and newlines.
+    AsciiWhitespace,
+    /// Hexadecimal text input: each hex digit ("0" - "9", "a" - "f", "A" - "F") contains 4 bits,
+    /// starting with the MSB. ASCII whitespace (including newlines) is skipped, no other character
+    /// is allowed.
+    Hex,
+    /// Hexadecimal text input: each hex digit contains 4 bits, starting with the MSB. Any other
+    /// character is skipped.
+    HexLossy,
+    /// Base64 text input (standard alphabet), decoded to bytes, each byte containing 8 bits,
+    /// starting with the MSB (unless another [bit order](BitVecBuilder::set_bit_order) is set).
+    /// ASCII whitespace (including newlines) is skipped, see [Base64Reader].
+    Base64,
+}
+
+impl ReaderFormat {
+    /// Decodes a single character of a text format.
+    fn decode_char(self, char: u8) -> DecodedChar {
+        match self {
+            // base64 is already decoded to bytes by the Base64Reader
+            ReaderFormat::Binary | ReaderFormat::Base64 => {
+                DecodedChar::Bits(char as usize, u8::BITS as u8)
+            }
+            ReaderFormat::Ascii | ReaderFormat::AsciiLossy | ReaderFormat::AsciiWhitespace => {
+                match char {
+                    b'0' | b'1' => DecodedChar::Bits((char == b'1') as usize, 1),
+                    _ if self == ReaderFormat::AsciiLossy => DecodedChar::Skip,
+                    _ if self == ReaderFormat::AsciiWhitespace && char.is_ascii_whitespace() => {
+                        DecodedChar::Skip
+                    }
+                    _ => DecodedChar::Invalid,
+                }
+            }
+            ReaderFormat::Hex | ReaderFormat::HexLossy => match (char as char).to_digit(16) {
+                Some(digit) => DecodedChar::Bits(digit as usize, 4),
+                None if self == ReaderFormat::HexLossy || char.is_ascii_whitespace() => {
+                    DecodedChar::Skip
+                }
+                None => DecodedChar::Invalid,
+            },
+        }
+    }
+}
+
+/// The result of [ReaderFormat::decode_char].
+#[derive(Copy, Clone, Debug)]
+enum DecodedChar {
+    /// The character contains the given count of bits.
+    Bits(usize, u8),
+    /// The character contains no bits and is skipped.
+    Skip,
+    /// The character is not allowed in the format.
+    Invalid,
+}
+
+/// A builder to create a [BitVec] incrementally. Bits can be appended step by step, the
+/// finished [BitVec] is created with [Self::build].
+///
+/// Optionally, a maximum length can be given. All bits appended after this length was reached are
+/// silently discarded.
+#[derive(Clone, Debug, Default)]
+pub struct BitVecBuilder {
+    // data storage
+    bitvec: BitVec<'static>,
+    // the maximum count of bits to store.
+    max_length: Option<usize>,
+    // the order of the bits in each appended byte.
+    bit_order: BitOrder,
+}
+
+impl BitVecBuilder {
+    /// Creates a new, empty builder without a maximum length.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new, empty builder that stores a maximum of `max_length` bits.
+    pub fn with_max_length(max_length: usize) -> Self {
+        Self {
+            max_length: Some(max_length),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the order of the bits in each byte appended with [Self::extend_from_bytes] and
+    /// [Self::extend_from_reader] with [ReaderFormat::Binary] or [ReaderFormat::Base64].
+    /// Default: [BitOrder::MsbFirst]. Text formats are not affected.
+    pub fn set_bit_order(&mut self, bit_order: BitOrder) {
+        self.bit_order = bit_order;
+    }
+
+    /// How many bits are currently stored in the builder.
+    pub fn len_bit(&self) -> usize {
+        self.bitvec.len_bit()
+    }
+
+    /// If the builder has reached its maximum length. Always false if no maximum length was
+    /// given.
+    pub fn is_full(&self) -> bool {
+        self.max_length
+            .is_some_and(|max_length| self.len_bit() >= max_length)
+    }
+
+    /// Appends a single bit.
+    pub fn push_bit(&mut self, bit: bool) {
+        self.push_bits(bit as usize, 1);
+    }
+
+    /// Appends all bits of the given bytes, each byte containing 8 bits, in the
+    /// [bit order](Self::set_bit_order) of the builder (by default starting with the MSB).
+    pub fn extend_from_bytes(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            if self.is_full() {
+                break;
+            }
+            let byte = self.bit_order.to_msb_first(byte);
+            self.push_bits(byte as usize, u8::BITS as u8);
+        }
+    }
+
+    /// Appends the bits of the given ASCII characters, with "0" mapping to 0 and "1" mapping to 1.
+    /// No other character is allowed.
+    ///
+    /// Returns `false` if the input contains an invalid character, in this case, nothing is
+    /// appended.
+    pub fn extend_from_ascii(&mut self, chars: &[u8]) -> bool {
+        self.extend_from_text_checked(chars, ReaderFormat::Ascii)
+    }
+
+    /// Appends the bits of the given ASCII characters, with "0" mapping to 0 and "1" mapping to 1.
+    /// Any other character is skipped.
+    pub fn extend_from_ascii_lossy(&mut self, chars: &[u8]) {
+        self.extend_from_text_internal(chars, ReaderFormat::AsciiLossy);
+    }
+
+    /// Appends the bits of the given ASCII characters, with "0" mapping to 0 and "1" mapping to 1.
+    /// ASCII whitespace (including newlines) is skipped, no other character is allowed.
+    ///
+    /// Returns `false` if the input contains an invalid character, in this case, nothing is
+    /// appended.
+    pub fn extend_from_ascii_whitespace(&mut self, chars: &[u8]) -> bool {
+        self.extend_from_text_checked(chars, ReaderFormat::AsciiWhitespace)
+    }
+
+    /// Appends the bits of the given hexadecimal characters, each hex digit containing 4 bits,
+    /// starting with the MSB. ASCII whitespace (including newlines) is skipped, no other character
+    /// is allowed.
+    ///
+    /// Returns `false` if the input contains an invalid character, in this case, nothing is
+    /// appended.
+    pub fn extend_from_hex(&mut self, chars: &[u8]) -> bool {
+        self.extend_from_text_checked(chars, ReaderFormat::Hex)
+    }
+
+    /// Appends the bits of the given hexadecimal characters, each hex digit containing 4 bits,
+    /// starting with the MSB. Any other character is skipped.
+    pub fn extend_from_hex_lossy(&mut self, chars: &[u8]) {
+        self.extend_from_text_internal(chars, ReaderFormat::HexLossy);
+    }
+
+    /// Reads the given source until it is exhausted or the maximum length is reached, appending
+    /// the bits in the given format.
+    ///
+    /// Only the bytes that are needed are consumed from the reader, meaning that the same reader
+    /// can be used to fill multiple builders one after another. For [ReaderFormat::Binary], a
+    /// partially used byte counts as consumed. For [ReaderFormat::Base64], the reader is only
+    /// wrapped in a [Base64Reader] for the duration of this call, meaning that decoded but unused
+    /// data is lost - to fill multiple builders, wrap the reader in a [Base64Reader] once and use
+    /// [ReaderFormat::Binary] instead.
+    ///
+    /// ## Errors
+    ///
+    /// Any error of the reader is returned. If the input contains an invalid character for
+    /// [ReaderFormat::Ascii], [ReaderFormat::AsciiWhitespace], [ReaderFormat::Hex] or
+    /// [ReaderFormat::Base64], an error of kind [ErrorKind::InvalidData] is returned, all data
+    /// before the invalid character is appended.
+    #[cfg(feature = "std")]
+    pub fn extend_from_reader(
+        &mut self,
+        reader: &mut impl BufRead,
+        format: ReaderFormat,
+    ) -> io::Result<()> {
+        if format == ReaderFormat::Base64 {
+            self.extend_from_reader_internal(&mut Base64Reader::new(reader), ReaderFormat::Binary)
+        } else {
+            self.extend_from_reader_internal(reader, format)
+        }
+    }
+
+    /// Creates the [BitVec] from all bits appended so far.
+    pub fn build(self) -> BitVec<'static> {
+        self.bitvec
+    }
+}
+
+// private functions
+impl BitVecBuilder {
+    /// Implementation of [Self::extend_from_reader] for all formats, except
+    /// [ReaderFormat::Base64].
+    #[cfg(feature = "std")]
+    fn extend_from_reader_internal(
+        &mut self,
+        reader: &mut impl BufRead,
+        format: ReaderFormat,
+    ) -> io::Result<()> {
+        while !self.is_full() {
+            let buffer = match reader.fill_buf() {
+                Ok(buffer) => buffer,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+
+            if buffer.is_empty() {
+                // EOF
+                break;
+            }
+
+            let (consumed, valid) = self.extend_from_format(buffer, format);
+            reader.consume(consumed);
+
+            if !valid {
+                let msg = match format {
+                    ReaderFormat::Hex => {
+                        "Input contains characters other than hex digits or whitespace"
+                    }
+                    ReaderFormat::AsciiWhitespace => {
+                        "Input contains characters other than '0', '1' or whitespace"
+                    }
+                    _ => "Input contains characters other than '0' or '1'",
+                };
+                return Err(io::Error::new(ErrorKind::InvalidData, msg));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Appends the lowest `count` bits of `value`, starting with the most significant one of them.
+    /// If the maximum length would be exceeded, only the first bits that fit are appended.
+    fn push_bits(&mut self, mut value: Word, mut count: u8) {
+        if let Some(max_length) = self.max_length {
+            let remaining = max_length.saturating_sub(self.len_bit());
+            if remaining == 0 {
+                // already full, a shift by the whole count would overflow
+                return;
+            } else if remaining < (count as usize) {
+                // only keep the first bits
+                value >>= count as usize - remaining;
+                count = remaining as u8;
+            }
+        }
+
+        self.bitvec.push_bits(value, count);
+    }
+
+    /// Checks that the given input contains no invalid characters for the text format and
+    /// appends it. Returns `false` and appends nothing if an invalid character is found.
+    fn extend_from_text_checked(&mut self, chars: &[u8], format: ReaderFormat) -> bool {
+        if chars
+            .iter()
+            .any(|&c| matches!(format.decode_char(c), DecodedChar::Invalid))
+        {
+            return false;
+        }
+
+        self.extend_from_text_internal(chars, format);
+        true
+    }
+
+    /// Appends all valid characters of the text format from the given input, ignoring all other
+    /// characters. Characters are collected into whole words before being appended.
+    fn extend_from_text_internal(&mut self, chars: &[u8], format: ReaderFormat) {
+        if self.is_full() {
+            return;
+        }
+
+        let mut word: Word = 0;
+        let mut bit_count = 0_u8;
+
+        for &char in chars {
+            if let DecodedChar::Bits(value, count) = format.decode_char(char) {
+                // the bit count of all text formats divides the word size, so a word is always
+                // filled exactly.
+                word = (word << count) | value;
+                bit_count += count;
+
+                if bit_count == WORD_BITS as u8 {
+                    self.push_bits(word, bit_count);
+                    word = 0;
+                    bit_count = 0;
+
+                    if self.is_full() {
+                        return;
+                    }
+                }
+            }
+        }
+
+        self.push_bits(word, bit_count);
+    }
+
+    /// Appends the given buffer in the given format, stopping once the builder is full.
+    ///
+    /// Returns the count of bytes consumed from the buffer, and if the consumed part was valid.
+    #[cfg(feature = "std")]
+    fn extend_from_format(&mut self, buffer: &[u8], format: ReaderFormat) -> (usize, bool) {
+        match format {
+            // base64 is already decoded to bytes by the Base64Reader
+            ReaderFormat::Binary | ReaderFormat::Base64 => {
+                let needed_bytes = match self.max_length {
+                    Some(max_length) => max_length
+                        .saturating_sub(self.len_bit())
+                        .div_ceil(u8::BITS as usize),
+                    None => buffer.len(),
+                };
+                let buffer = &buffer[..usize::min(needed_bytes, buffer.len())];
+
+                self.extend_from_bytes(buffer);
+                (buffer.len(), true)
+            }
+            ReaderFormat::Ascii
+            | ReaderFormat::AsciiLossy
+            | ReaderFormat::AsciiWhitespace
+            | ReaderFormat::Hex
+            | ReaderFormat::HexLossy => {
+                // in text formats, each valid character is a fixed count of bits - find how many
+                // characters are needed.
+                let mut needed_bits = self
+                    .max_length
+                    .map(|max_length| max_length.saturating_sub(self.len_bit()));
+                let mut valid = true;
+
+                let end = buffer
+                    .iter()
+                    .position(|&c| {
+                        if needed_bits == Some(0) {
+                            // builder is full, stop here
+                            return true;
+                        }
+
+                        match format.decode_char(c) {
+                            DecodedChar::Bits(_, count) => {
+                                if let Some(needed_bits) = &mut needed_bits {
+                                    *needed_bits = needed_bits.saturating_sub(count as usize);
+                                }
+                                false
+                            }
+                            DecodedChar::Skip => false,
+                            DecodedChar::Invalid => {
+                                valid = false;
+                                true
+                            }
+                        }
+                    })
+                    .unwrap_or(buffer.len());
+
+                self.extend_from_text_internal(&buffer[..end], format);
+                (end, valid)
+            }
+        }
+    }
+}
+
+// constructors on BitVec
+impl BitVec<'_> {
+    /// Creates a [BitVec] by reading the given source until it is exhausted, interpreting the
+    /// data in the given format. The source is read in chunks, meaning that only the resulting
+    /// [BitVec] is held in memory.
+    ///
+    /// ## Errors
+    ///
+    /// Any error of the reader is returned. If the input contains an invalid character for
+    /// [ReaderFormat::Ascii], [ReaderFormat::AsciiWhitespace], [ReaderFormat::Hex] or
+    /// [ReaderFormat::Base64], an error of kind [ErrorKind::InvalidData] is returned.
+    #[cfg(feature = "std")]
+    pub fn from_reader(reader: impl Read, format: ReaderFormat) -> io::Result<Self> {
+        let mut builder = BitVecBuilder::new();
+        builder.extend_from_reader(&mut BufReader::new(reader), format)?;
+        Ok(builder.build())
+    }
+
+    /// Same as [Self::from_reader], but stops reading once `max_length` bits were read.
+    #[cfg(feature = "std")]
+    pub fn from_reader_with_max_length(
+        reader: impl Read,
+        format: ReaderFormat,
+        max_length: usize,
+    ) -> io::Result<Self> {
+        let mut builder = BitVecBuilder::with_max_length(max_length);
+        builder.extend_from_reader(&mut BufReader::new(reader), format)?;
+        Ok(builder.build())
+    }
+}
@@ -1,40 +1,40 @@
 //! Chunk iterators - chunks are counted in bytes. The chunk type is not optimized for usage,
 //! but rather for performance when using it in tests, see [Chunk].
 
+use crate::bitvec::word::{self, Word, WORD_BYTES};
 use crate::bitvec::BitVec;
+use core::mem;
+#[cfg(feature = "std")]
 use rayon::iter::plumbing::{bridge, Consumer, Producer, ProducerCallback, UnindexedConsumer};
+#[cfg(feature = "std")]
 use rayon::prelude::*;
-use std::mem;
 use tinyvec::ArrayVec;
 
 /// Length of the start / end ArrayVecs
-pub const PART_ARRAY_LEN: usize = BYTES_PER_USIZE - 1;
-
-/// How many bytes fit into 1 usize value
-const BYTES_PER_USIZE: usize = size_of::<usize>() / size_of::<u8>();
+pub const PART_ARRAY_LEN: usize = WORD_BYTES - 1;
 
 /// A generic chunk: contains full bytes, but only split as necessary.
 /// This allows for a large part of each chunk to be given as a reference.
 pub struct Chunk<'a> {
-    /// Start: not a complete usize value
+    /// Start: not a complete word
     pub start: ArrayVec<[u8; PART_ARRAY_LEN]>,
-    /// Middle: complete usize values
+    /// Middle: complete words, the first byte of each word is its most significant byte
     pub middle: &'a [usize],
-    /// End: not a complete usize value
+    /// End: not a complete word
     pub end: ArrayVec<[u8; PART_ARRAY_LEN]>,
 }
 
 impl<'a> Chunk<'a> {
     /// The length of the chunk, measured in bytes.
     pub fn len_byte(&self) -> usize {
-        self.start.len() + self.middle.len() * BYTES_PER_USIZE + self.end.len()
+        self.start.len() + self.middle.len() * WORD_BYTES + self.end.len()
     }
 
     /// create a new instance
     #[inline]
     fn new(
         start: ArrayVec<[u8; PART_ARRAY_LEN]>,
-        middle: &'a [usize],
+        middle: &'a [Word],
         end: ArrayVec<[u8; PART_ARRAY_LEN]>,
     ) -> Self {
         Self { start, middle, end }
@@ -81,10 +81,10 @@ impl ChunksExact<'_> {
         // always have to take the full self.0.start()
         let len = len - self.data.start.len();
 
-        let (p1, p2) = if len < self.data.middle.len() * BYTES_PER_USIZE {
+        let (p1, p2) = if len < self.data.middle.len() * WORD_BYTES {
             // need to split self.middle
-            let split_idx = len / BYTES_PER_USIZE;
-            let split_byte_idx = len % BYTES_PER_USIZE;
+            let split_idx = len / WORD_BYTES;
+            let split_byte_idx = len % WORD_BYTES;
 
             if split_byte_idx == 0 {
                 // clean split is possible
@@ -103,7 +103,7 @@ impl ChunksExact<'_> {
                 };
 
                 // split the middle usize
-                let middle = middle.to_be_bytes();
+                let middle = word::to_bytes(middle);
                 let end = ArrayVec::from_iter(middle[0..split_byte_idx].iter().copied());
                 let start = ArrayVec::from_iter(middle[split_byte_idx..].iter().copied());
 
@@ -114,7 +114,7 @@ impl ChunksExact<'_> {
             }
         } else {
             // have to take the full self.0.middle(), maybe some part of self.end()
-            let len = len - self.data.middle.len() * BYTES_PER_USIZE;
+            let len = len - self.data.middle.len() * WORD_BYTES;
 
             let Chunk {
                 start,
@@ -205,8 +205,10 @@ impl DoubleEndedIterator for ChunksExact<'_> {
 }
 
 /// Parallel Chunks Iterator
+#[cfg(feature = "std")]
 pub struct ParChunksExact<'a>(ChunksExact<'a>);
 
+#[cfg(feature = "std")]
 impl IndexedParallelIterator for ParChunksExact<'_> {
     fn len(&self) -> usize {
         self.0.len()
@@ -238,6 +240,7 @@ impl IndexedParallelIterator for ParChunksExact<'_> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<'a> ParallelIterator for ParChunksExact<'a> {
     type Item = Chunk<'a>;
 
@@ -250,15 +253,15 @@ impl<'a> ParallelIterator for ParChunksExact<'a> {
 }
 
 // constructors on BitVec
-impl BitVec {
+impl BitVec<'_> {
     /// Returns an iterator that yields chunks of size_in_bytes bytes at a time.
     /// The chunk datatype is [Chunk].
-    pub fn chunks_exact(&self, size_in_bytes: usize) -> ChunksExact {
+    pub fn chunks_exact(&self, size_in_bytes: usize) -> ChunksExact<'_> {
         let (data, rest) = self.as_full_slice();
         
         // get all full bytes from the rest, if there are any
         let end = if let Some(rest) = rest {
-            let full_elements = rest.to_be_bytes()
+            let full_elements = word::to_bytes(rest)
                 .into_iter()
                 .take((self.bit_count_last_word as usize) / (u8::BITS as usize));
             ArrayVec::from_iter(full_elements)
@@ -278,7 +281,8 @@ impl BitVec {
 
     /// Returns a parallel iterator that yields chunks of size_in_bytes bytes at a time.
     /// The chunk datatype is [Chunk].
-    pub fn par_chunks_exact(&self, size_in_bytes: usize) -> ParChunksExact {
+    #[cfg(feature = "std")]
+    pub fn par_chunks_exact(&self, size_in_bytes: usize) -> ParChunksExact<'_> {
         ParChunksExact(self.chunks_exact(size_in_bytes))
     }
 }
@@ -1,53 +1,406 @@
 //! Everything needed to store the data to test.
 
-use std::ffi::c_char;
-use std::mem;
-use std::ops::Deref;
+use crate::bitvec::builder::BitVecBuilder;
+#[cfg(feature = "std")]
+use crate::bitvec::builder::ReaderFormat;
+use crate::bitvec::slice::BitSlice;
+use crate::bitvec::word::{Word, WORD_BITS, WORD_BYTES};
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ffi::c_char;
+use core::ops::{Deref, Range};
 use sts_lib_derive::use_thread_pool;
-use tinyvec::ArrayVec;
 
 pub mod array_chunks_u32;
+#[cfg(feature = "std")]
+pub mod base64;
+pub mod bits;
+pub mod builder;
 pub mod chunks;
+pub mod slice;
+pub mod windows;
+pub(crate) mod word;
+
+/// Each byte with its bits spread to the even positions of 16 bits, bit 0 to bit 0, bit 1 to bit 2
+/// and so on - used to interleave bits.
+const INTERLEAVE_TABLE: [u16; 256] = {
+    let mut table = [0; 256];
+    let mut byte = 0;
+    while byte < 256 {
+        let mut bit = 0;
+        while bit < 8 {
+            table[byte] |= (((byte >> bit) & 1) as u16) << (2 * bit);
+            bit += 1;
+        }
+        byte += 1;
+    }
+    table
+};
+
+/// The order of the bits in each byte, when creating a [BitVec] from bytes.
+///
+/// Some hardware captures pack the bits starting with the LSB of each byte. Testing them with the
+/// wrong bit order does not change the results of some tests, but changes the results of others,
+/// e.g. the template matching tests.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum BitOrder {
+    /// The first bit is the most significant bit of each byte.
+    #[default]
+    MsbFirst,
+    /// The first bit is the least significant bit of each byte.
+    LsbFirst,
+}
+
+impl BitOrder {
+    /// Returns the byte with the bits in MSB first order.
+    pub(crate) fn to_msb_first(self, byte: u8) -> u8 {
+        match self {
+            BitOrder::MsbFirst => byte,
+            BitOrder::LsbFirst => byte.reverse_bits(),
+        }
+    }
+}
 
 /// A list of bits, tightly packed - used in all tests
-#[derive(Clone, Debug)]
-pub struct BitVec {
-    // data storage
-    pub(crate) words: Box<[usize]>,
-    // count of bits in the last word - maximum of usize::BITS - 1.
+///
+/// The bits are owned, except for a [BitVec] created with [BitVec::from_words_borrowed], that
+/// borrows them for the lifetime `'a`. All other constructors return a [BitVec] that can have any
+/// lifetime, e.g. `BitVec<'static>`.
+///
+/// Two [BitVec]s are equal if they contain the same bits.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BitVec<'a> {
+    // data storage, only borrowed if created with from_words_borrowed - the unused bits of the
+    // last word are always 0.
+    pub(crate) words: Cow<'a, [Word]>,
+    // count of bits in the last word - maximum of WORD_BITS - 1.
     pub(crate) bit_count_last_word: u8,
 }
 
-impl BitVec {
+impl<'a> BitVec<'a> {
+    /// Creates a new, empty [BitVec]. Bits can be appended with [Self::push_bit],
+    /// [Self::extend_from_bits] and [Self::concat].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
     /// How many bits the Vec contains
     pub fn len_bit(&self) -> usize {
         if self.bit_count_last_word == 0 {
-            self.words.len() * (usize::BITS as usize)
+            self.words.len() * WORD_BITS
         } else {
-            (self.words.len() - 1) * (usize::BITS as usize) + (self.bit_count_last_word as usize)
+            (self.words.len() - 1) * WORD_BITS + (self.bit_count_last_word as usize)
         }
     }
 
+    /// Returns the bit at the given index, or [None] if the index is out of bounds.
+    pub fn get(&self, idx: usize) -> Option<bool> {
+        if idx >= self.len_bit() {
+            return None;
+        }
+
+        let word = self.words[word::word_index(idx)];
+        Some(word::get_bit(word, word::bit_in_word(idx)))
+    }
+
+    /// The count of bits with value 1 (the Hamming weight of the sequence).
+    ///
+    /// This function runs in parallel.
+    #[use_thread_pool]
+    pub fn count_ones(&self) -> usize {
+        use crate::internals::par::*;
+
+        // the empty bits in the last word are always zero.
+        self.words
+            .par_iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// The count of bits with value 0.
+    ///
+    /// This function runs in parallel.
+    pub fn count_zeros(&self) -> usize {
+        self.len_bit() - self.count_ones()
+    }
+
+    /// The count of bits with value 1 in the given range of bits.
+    /// Equivalent to `self.bit_slice(range).count_ones()`.
+    ///
+    /// This function runs in parallel.
+    ///
+    /// ## Panics
+    ///
+    /// If the start of the range is greater than its end, or the end is greater than
+    /// [Self::len_bit].
+    pub fn hamming_weight_in(&self, range: Range<usize>) -> usize {
+        self.bit_slice(range).count_ones()
+    }
+
     /// Crop the BitVec to the passed bit length. This operation does nothing
     /// if the previous length is greater than the new length.
     pub fn crop(&mut self, new_bit_len: usize) {
         if new_bit_len < self.len_bit() {
-            let mut new_len = new_bit_len / (usize::BITS as usize);
-            let additional_bits = (new_bit_len % (usize::BITS as usize)) as u8;
+            let additional_bits = word::bit_in_word(new_bit_len);
+            let word_count = new_bit_len.div_ceil(WORD_BITS);
 
-            if additional_bits > 0 {
-                new_len += 1
+            match &mut self.words {
+                Cow::Borrowed(words) => *words = &words[..word_count],
+                Cow::Owned(words) => words.truncate(word_count),
             }
-
-            let mut data = mem::take(&mut self.words).into_vec();
-            data.truncate(new_len);
             if additional_bits > 0 {
-                let mask = !((1 << (usize::BITS as u8 - additional_bits)) - 1);
-                *data.last_mut().unwrap() &= mask;
+                let mask = word::leading_mask(additional_bits);
+                // borrowed words are only copied if bits have to be cleared
+                if self.words.last().is_some_and(|&last| last & !mask != 0) {
+                    *self.words.to_mut().last_mut().unwrap() &= mask;
+                }
             }
-            self.words = data.into_boxed_slice();
 
-            self.bit_count_last_word = additional_bits;
+            self.bit_count_last_word = additional_bits as u8;
+        }
+    }
+
+    /// Returns a new [BitVec] containing a copy of the given range of bits, e.g. to test only a
+    /// part of the sequence. To avoid copying, use [Self::bit_slice].
+    ///
+    /// ## Panics
+    ///
+    /// If the start of the range is greater than its end, or the end is greater than
+    /// [Self::len_bit].
+    pub fn slice(&self, range: Range<usize>) -> BitVec<'static> {
+        self.bit_slice(range).to_bit_vec()
+    }
+
+    /// Returns a view into the given range of bits, without copying them. See [BitSlice].
+    ///
+    /// ## Panics
+    ///
+    /// If the start of the range is greater than its end, or the end is greater than
+    /// [Self::len_bit].
+    pub fn bit_slice(&self, range: Range<usize>) -> BitSlice<'_> {
+        BitSlice::new(&self.words, 0, self.len_bit(), range)
+    }
+
+    /// Appends a single bit to the end of the [BitVec].
+    pub fn push_bit(&mut self, bit: bool) {
+        self.push_bits(bit as usize, 1);
+    }
+
+    /// Appends the given bits to the end of the [BitVec], each boolean representing one bit.
+    pub fn extend_from_bits(&mut self, bits: &[bool]) {
+        self.words.to_mut().reserve(bits.len().div_ceil(WORD_BITS));
+
+        for chunk in bits.chunks(WORD_BITS) {
+            // [0] = MSB
+            let word = chunk
+                .iter()
+                .fold(0 as Word, |word, &bit| (word << 1) | (bit as Word));
+            self.push_bits(word, chunk.len() as u8);
+        }
+    }
+
+    /// Appends all bits of `other` to the end of this [BitVec].
+    pub fn concat(&mut self, other: &BitVec) {
+        if self.bit_count_last_word == 0 {
+            // all words are full - the words of other can be copied as they are.
+            self.words.to_mut().extend_from_slice(&other.words);
+            self.bit_count_last_word = other.bit_count_last_word;
+            return;
+        }
+
+        self.words.to_mut().reserve(other.words.len());
+
+        let (full_words, last_word) = other.as_full_slice();
+        for &word in full_words {
+            self.push_bits(word, WORD_BITS as u8);
+        }
+
+        if let Some(last_word) = last_word {
+            let count = other.bit_count_last_word;
+            self.push_bits(last_word >> (WORD_BITS as u8 - count), count);
+        }
+    }
+
+    /// Returns the bitwise XOR of this [BitVec] and `other`, e.g. to combine 2 sources. If the
+    /// lengths differ, the result has the length of the shorter one.
+    ///
+    /// This function runs in parallel.
+    #[use_thread_pool]
+    pub fn xor(&self, other: &BitVec) -> BitVec<'static> {
+        use crate::internals::par::*;
+
+        let words = self
+            .words
+            .par_iter()
+            .zip(other.words.par_iter())
+            .map(|(a, b)| a ^ b)
+            .collect();
+
+        BitVec::from_words_cropped(words, usize::min(self.len_bit(), other.len_bit()))
+    }
+
+    /// Returns the bitwise complement of this [BitVec]: each bit is inverted.
+    ///
+    /// This function runs in parallel.
+    #[use_thread_pool]
+    pub fn not(&self) -> BitVec<'static> {
+        use crate::internals::par::*;
+
+        let words = self.words.par_iter().map(|word| !word).collect();
+
+        BitVec::from_words_cropped(words, self.len_bit())
+    }
+
+    /// Returns the bits of this [BitVec] and `other` interleaved, starting with the first bit of
+    /// this one: `a[0], b[0], a[1], b[1], ...`. If the lengths differ, only as many bits as the
+    /// shorter one has are taken from each, the result has twice its length.
+    ///
+    /// This function runs in parallel.
+    #[use_thread_pool]
+    pub fn interleave(&self, other: &BitVec) -> BitVec<'static> {
+        use crate::internals::par::*;
+
+        // each pair of words results in 2 words, each byte pair in 16 bits.
+        let words = self
+            .words
+            .par_iter()
+            .zip(other.words.par_iter())
+            .flat_map_iter(|(a, b)| {
+                let (a, b) = (word::to_bytes(*a), word::to_bytes(*b));
+                let half = a.len() / 2;
+
+                [0, half].map(|start| {
+                    (start..start + half).fold(0, |word, i| {
+                        let pair = ((INTERLEAVE_TABLE[a[i] as usize] as Word) << 1)
+                            | INTERLEAVE_TABLE[b[i] as usize] as Word;
+                        (word << 16) | pair
+                    })
+                })
+            })
+            .collect();
+
+        BitVec::from_words_cropped(words, 2 * usize::min(self.len_bit(), other.len_bit()))
+    }
+
+    /// Returns the bits after the von Neumann extractor: the bits are taken in non-overlapping
+    /// pairs, the pair `10` results in 1, the pair `01` in 0, and the pairs `00` and `11` are
+    /// discarded. This removes the bias of independent bits, at the cost of at least 3/4 of the
+    /// length. A last single bit is discarded.
+    ///
+    /// This function runs in parallel.
+    #[use_thread_pool]
+    pub fn von_neumann_extract(&self) -> BitVec<'static> {
+        use crate::internals::par::*;
+
+        // the first bit of each pair, [0] = MSB
+        const FIRST_BITS: Word = (Word::MAX / 3) << 1;
+
+        let pair_count = self.len_bit() / 2;
+        let pairs_per_word = WORD_BITS / 2;
+
+        // the extracted bits of each word, with their count.
+        let extracted = self
+            .words
+            .par_iter()
+            .enumerate()
+            .map(|(idx, &word)| {
+                let mut differing = (word ^ (word << 1)) & FIRST_BITS;
+
+                // only complete pairs are used
+                let remaining_pairs = pair_count.saturating_sub(idx * pairs_per_word);
+                if remaining_pairs < pairs_per_word {
+                    differing &= word::leading_mask(2 * remaining_pairs);
+                }
+
+                let mut value = 0;
+                let mut count = 0;
+                while differing != 0 {
+                    let bit = differing.leading_zeros() as usize;
+                    value = (value << 1) | (word::get_bit(word, bit) as Word);
+                    count += 1;
+                    differing &= !word::bit_mask(bit);
+                }
+
+                (value, count)
+            })
+            .collect::<Vec<_>>();
+
+        let mut output = BitVec::new();
+        output.words.to_mut().reserve(self.words.len() / 4);
+        for (value, count) in extracted {
+            output.push_bits(value, count);
+        }
+
+        output
+    }
+
+    /// Creates a [BitVec] of `bit_len` bits from packed words, e.g. from a buffer of another
+    /// library. The first bit is the most significant bit of the first word, independent of the
+    /// byte order of the platform. Words after `bit_len` are ignored, as are the bits after it in the
+    /// last used word.
+    ///
+    /// Returns [None] if `bit_len` is greater than the count of bits in the words.
+    pub fn from_words(words: &[usize], bit_len: usize) -> Option<Self> {
+        let word_count = bit_len.div_ceil(WORD_BITS);
+        let words = words.get(..word_count)?;
+
+        Some(Self::from_words_cropped(words.to_vec(), bit_len))
+    }
+
+    /// Creates a [BitVec] of `bit_len` bits that borrows the given words instead of copying them,
+    /// e.g. to test a large buffer of another library without doubling the memory usage. The words
+    /// have the same layout as for [Self::from_words], words after `bit_len` are ignored.
+    ///
+    /// The words are never written to: they are only copied if the [BitVec] is modified, e.g. with
+    /// [Self::push_bit] or [Self::concat]. Clones of the [BitVec] borrow the same words.
+    ///
+    /// Returns [None] if `bit_len` is greater than the count of bits in the words, or if the bits
+    /// after `bit_len` in the last used word are not 0.
+    pub fn from_words_borrowed(words: &'a [usize], bit_len: usize) -> Option<Self> {
+        let word_count = bit_len.div_ceil(WORD_BITS);
+        let words = words.get(..word_count)?;
+
+        let bit_count_last_word = word::bit_in_word(bit_len);
+        if bit_count_last_word != 0
+            && words
+                .last()
+                .is_some_and(|&last| last & !word::leading_mask(bit_count_last_word) != 0)
+        {
+            return None;
+        }
+
+        Some(Self {
+            words: Cow::Borrowed(words),
+            bit_count_last_word: bit_count_last_word as u8,
+        })
+    }
+
+    /// Creates a [BitVec] from a slice of bytes, each containing 8 values in the given
+    /// [order](BitOrder). [From<&[u8]>](From) uses [BitOrder::MsbFirst].
+    #[use_thread_pool]
+    pub fn from_bytes(value: &[u8], bit_order: BitOrder) -> Self {
+        use crate::internals::par::*;
+
+        // multiplication in the first step would be unwise (overflow potential)
+        let byte_count_last_word = (value.len() % WORD_BYTES) as u8;
+        let bit_count_last_word = byte_count_last_word * (u8::BITS as u8);
+
+        // copy, converting to the right data type - a missing part of the last word is 0
+        let words = value
+            .par_chunks(WORD_BYTES)
+            .map(|chunk| {
+                let mut bytes = [0; WORD_BYTES];
+                for (word_byte, &byte) in bytes.iter_mut().zip(chunk) {
+                    *word_byte = bit_order.to_msb_first(byte);
+                }
+                word::from_bytes(bytes)
+            })
+            .collect();
+
+        Self {
+            words,
+            bit_count_last_word,
         }
     }
 
@@ -57,20 +410,18 @@ impl BitVec {
     /// This function runs in parallel.
     #[use_thread_pool]
     pub fn from_ascii_str(value: &str) -> Option<Self> {
-        use rayon::iter::ParallelIterator;
-        use rayon::slice::ParallelSlice;
+        use crate::internals::par::*;
 
         let words = value
             .as_bytes()
-            .par_chunks(usize::BITS as usize)
+            .par_chunks(WORD_BITS)
             .map(|chunk| {
-                // [0] = MSB
                 chunk
                     .iter()
                     .enumerate()
-                    .try_fold(0usize, |word, (i, char)| {
+                    .try_fold(0 as Word, |word, (i, char)| {
                         if *char == b'1' {
-                            Some(word | (1 << ((usize::BITS as usize) - i - 1)))
+                            Some(word | word::bit_mask(i))
                         } else if *char == b'0' {
                             // no need to change the value itself
                             Some(word)
@@ -81,7 +432,7 @@ impl BitVec {
             })
             .collect::<Option<_>>()?;
 
-        let bit_count_last_word = (value.len() % (usize::BITS as usize)) as u8;
+        let bit_count_last_word = word::bit_in_word(value.len()) as u8;
 
         Some(Self {
             words,
@@ -106,6 +457,39 @@ impl BitVec {
         Self::from_ascii_str_lossy_internal(value, Some(max_length))
     }
 
+    /// Creates a [BitVec] from a string of hexadecimal digits, each digit containing 4 bits,
+    /// starting with the MSB. Upper- and lowercase digits are allowed. ASCII whitespace
+    /// (including newlines) between the digits is skipped, any other character is not allowed.
+    ///
+    /// This function runs sequential.
+    pub fn from_hex_str(value: &str) -> Option<Self> {
+        let mut builder = BitVecBuilder::new();
+        builder
+            .extend_from_hex(value.as_bytes())
+            .then(|| builder.build())
+    }
+
+    /// Creates a [BitVec] from a string of hexadecimal digits, each digit containing 4 bits,
+    /// starting with the MSB. Upper- and lowercase digits are allowed. Any other character is
+    /// skipped.
+    ///
+    /// This function runs sequential.
+    pub fn from_hex_str_lossy(value: &str) -> Self {
+        let mut builder = BitVecBuilder::new();
+        builder.extend_from_hex_lossy(value.as_bytes());
+        builder.build()
+    }
+
+    /// Creates a [BitVec] from a base64 string (standard alphabet, padding is optional), each
+    /// decoded byte containing 8 bits, starting with the MSB. ASCII whitespace (including newlines)
+    /// is skipped, any other character is not allowed.
+    ///
+    /// This function runs sequential.
+    #[cfg(feature = "std")]
+    pub fn from_base64(value: &str) -> Option<Self> {
+        Self::from_reader(value.as_bytes(), ReaderFormat::Base64).ok()
+    }
+
     /// Creates a [BitVec] from a string, with the ASCII char "0" mapping to 0 and "1" mapping to 1.
     /// Any other character is ignored.
     ///
@@ -159,43 +543,34 @@ impl BitVec {
     /// This operation is expensive.
     #[use_thread_pool]
     pub fn to_bytes(&self) -> (Vec<u8>, Option<u8>) {
-        use rayon::prelude::*;
+        use crate::internals::par::*;
 
         let (slice, value) = self.as_full_slice();
 
-        let (rest_for_iter, rest) = if let Some(value) = value {
-            let mut values = ArrayVec::from(value.to_be_bytes());
-
-            let full_elements = values
-                .drain(..)
-                .take((self.bit_count_last_word as usize) / (u8::BITS as usize));
-            let rest_for_iter: ArrayVec<[u8; size_of::<usize>() / size_of::<u8>() - 1]> =
-                ArrayVec::from_iter(full_elements);
-
-            let rest = ((self.bit_count_last_word as usize) % (u8::BITS as usize) != 0)
-                .then_some(values[0]);
-
-            (rest_for_iter, rest)
-        } else {
-            (ArrayVec::new(), None)
-        };
-
-        let bytes = slice
+        let mut bytes = slice
             .par_iter()
-            .flat_map(|v| v.to_be_bytes())
-            .chain(rest_for_iter.into_iter().par_bridge())
+            .flat_map(|&v| word::to_bytes(v))
             .collect::<Vec<u8>>();
 
+        let rest = value.and_then(|value| {
+            let value = word::to_bytes(value);
+            let full_bytes = (self.bit_count_last_word as usize) / (u8::BITS as usize);
+            bytes.extend_from_slice(&value[..full_bytes]);
+
+            ((self.bit_count_last_word as usize) % (u8::BITS as usize) != 0)
+                .then_some(value[full_bytes])
+        });
+
         (bytes, rest)
     }
 }
 
 // crate internals
-impl BitVec {
+impl BitVec<'_> {
     /// Returns the bits, stored in words. The MSB of each value has the lowest index.
     /// Each value is filled - returns an optional additional value, that may not be full - 
     /// check `self.bit_count_last_word` for the number of bits in the last word.
-    pub(crate) fn as_full_slice(&self) -> (&[usize], Option<usize>) {
+    pub(crate) fn as_full_slice(&self) -> (&[Word], Option<Word>) {
         if self.bit_count_last_word == 0 {
             (&self.words, None)
         } else {
@@ -205,10 +580,55 @@ impl BitVec {
                 .unwrap_or((&[], None))
         }
     }
+
+    /// Creates a [BitVec] of `bit_len` bits from the given words, the bits after it are cleared.
+    /// `bit_len` may be at most the count of bits in the words.
+    pub(crate) fn from_words_cropped(words: Vec<Word>, bit_len: usize) -> Self {
+        let mut bitvec = Self {
+            words: Cow::Owned(words),
+            bit_count_last_word: 0,
+        };
+        bitvec.crop(bit_len);
+        bitvec
+    }
+
+    /// Appends the lowest `count` bits of `value`, starting with the most significant one of them.
+    /// `count` may be at most [WORD_BITS].
+    pub(crate) fn push_bits(&mut self, mut value: Word, count: u8) {
+        if count == 0 {
+            return;
+        }
+
+        if count < WORD_BITS as u8 {
+            value &= (1 << count) - 1;
+        }
+
+        let words = self.words.to_mut();
+
+        // bit_count_last_word == 0 means that the last word (if any) is full
+        if self.bit_count_last_word == 0 {
+            words.push(0);
+        }
+
+        let free = WORD_BITS as u8 - self.bit_count_last_word;
+        // there is always at least 1 word in the vec
+        let last = words.last_mut().unwrap();
+
+        if count <= free {
+            *last |= value << (free - count);
+            self.bit_count_last_word = (self.bit_count_last_word + count) % (WORD_BITS as u8);
+        } else {
+            // split the value into 2 words
+            let rest = count - free;
+            *last |= value >> rest;
+            words.push(value << (WORD_BITS as u8 - rest));
+            self.bit_count_last_word = rest;
+        }
+    }
 }
 
 // private functions
-impl BitVec {
+impl BitVec<'_> {
     /// Creates a [BitVec] from a string, with the ASCII char "0" mapping to 0 and "1" mapping to 1.
     /// Any other character is ignored. [usize::MAX] bits can be stored.
     /// If a max length is given, a maximum of `max_length` valid bits are read
@@ -217,8 +637,8 @@ impl BitVec {
     /// This function runs sequential. (In contrast to [Self::from_ascii_str]).
     fn from_ascii_str_lossy_internal(value: &str, max_length: Option<usize>) -> Self {
         let mut full_words = Vec::new();
-        let mut current_bit_idx = (usize::BITS as u8) - 1; // start with a wrap around
-                                                           // we only need to increment if the length is relevant.
+        let mut current_bit_idx = (WORD_BITS as u8) - 1; // start with a wrap around
+                                                         // we only need to increment if the length is relevant.
         let mut found_bit_len = max_length.map(|_| 0_usize);
 
         for char in value.bytes() {
@@ -227,7 +647,7 @@ impl BitVec {
                 current_bit_idx += 1;
                 found_bit_len = found_bit_len.map(|i| i + 1);
 
-                if current_bit_idx == (usize::BITS as u8) {
+                if current_bit_idx == (WORD_BITS as u8) {
                     // allocate an additional byte and reset index
                     current_bit_idx = 0;
                     full_words.push(0);
@@ -236,7 +656,7 @@ impl BitVec {
                 if char == b'1' {
                     // there is always at least 1 byte in the vec
                     if let Some(b) = full_words.last_mut() {
-                        *b |= 1 << ((usize::BITS as usize) - (current_bit_idx as usize) - 1)
+                        *b |= word::bit_mask(current_bit_idx as usize)
                     }
                 }
 
@@ -248,8 +668,8 @@ impl BitVec {
         }
 
         Self {
-            words: full_words.into_boxed_slice(),
-            bit_count_last_word: (current_bit_idx + 1) % (usize::BITS as u8),
+            words: Cow::Owned(full_words),
+            bit_count_last_word: (current_bit_idx + 1) % (WORD_BITS as u8),
         }
     }
 
@@ -276,7 +696,7 @@ impl BitVec {
         const CHAR_1: c_char = b'1' as c_char;
 
         let mut full_words = Vec::new();
-        let mut current_bit_idx = (usize::BITS as u8) - 1; // start with a wrap around
+        let mut current_bit_idx = (WORD_BITS as u8) - 1; // start with a wrap around
         let mut found_bit_len = max_length.map(|_| 0_usize);
 
         // SAFETY: caller has provided a pointer to a valid C String.
@@ -287,7 +707,7 @@ impl BitVec {
                 current_bit_idx += 1;
                 found_bit_len = found_bit_len.map(|i| i + 1);
 
-                if current_bit_idx == (usize::BITS as u8) {
+                if current_bit_idx == (WORD_BITS as u8) {
                     // allocate an additional byte and reset index
                     current_bit_idx = 0;
                     full_words.push(0);
@@ -296,7 +716,7 @@ impl BitVec {
                 if current_value == CHAR_1 {
                     // there is always at least 1 byte in the vec
                     if let Some(b) = full_words.last_mut() {
-                        *b |= 1 << ((usize::BITS as usize) - (current_bit_idx as usize) - 1)
+                        *b |= word::bit_mask(current_bit_idx as usize)
                     }
                 }
 
@@ -316,83 +736,62 @@ impl BitVec {
         }
 
         Self {
-            words: full_words.into_boxed_slice(),
-            bit_count_last_word: (current_bit_idx + 1) % (usize::BITS as u8),
+            words: Cow::Owned(full_words),
+            bit_count_last_word: (current_bit_idx + 1) % (WORD_BITS as u8),
         }
     }
 }
 
 // conversion functions
-impl From<Vec<u8>> for BitVec {
+impl From<Vec<u8>> for BitVec<'_> {
     /// Creates a [BitVec] from a [Vec] of bytes, each containing 8 values.
     fn from(value: Vec<u8>) -> Self {
         Self::from(value.into_boxed_slice())
     }
 }
 
-impl<'a> From<&'a [u8]> for BitVec {
-    /// Creates a [BitVec] from a slice of bytes, each containing 8 values.
-    #[use_thread_pool]
+impl<'a> From<&'a [u8]> for BitVec<'_> {
+    /// Creates a [BitVec] from a slice of bytes, each containing 8 values, starting with the MSB.
+    /// For other bit orders, see [BitVec::from_bytes].
     fn from(value: &'a [u8]) -> Self {
-        use rayon::iter::ParallelIterator;
-        use rayon::slice::ParallelSlice;
-
-        const BYTES_PER_WORD: usize = (usize::BITS / u8::BITS) as usize;
-
-        // multiplication in the first step would be unwise (overflow potential)
-        let byte_count_last_word = (value.len() % BYTES_PER_WORD) as u8;
-        let bit_count_last_word = byte_count_last_word * (u8::BITS as u8);
-
-        // copy, converting to the right data type
-        let words = value
-            .par_chunks(BYTES_PER_WORD)
-            .map(|chunk| {
-                chunk.iter().enumerate().fold(0usize, |word, (i, byte)| {
-                    let shift = (usize::BITS as usize) - ((u8::BITS as usize) * (i + 1));
-                    word | (*byte as usize) << shift
-                })
-            })
-            .collect();
-
-        Self {
-            words,
-            bit_count_last_word,
-        }
+        Self::from_bytes(value, BitOrder::MsbFirst)
     }
 }
 
-impl From<Box<[u8]>> for BitVec {
+impl From<Box<[u8]>> for BitVec<'_> {
     /// Creates a [BitVec] from a boxed slice of bytes, each containing 8 values.
     fn from(value: Box<[u8]>) -> Self {
         Self::from(value.deref())
     }
 }
 
-impl From<Vec<bool>> for BitVec {
+impl From<Vec<bool>> for BitVec<'_> {
     /// Creates a [BitVec] from a [Vec] of booleans, each boolean representing one bit.
     fn from(value: Vec<bool>) -> Self {
         Self::from(value.as_slice())
     }
 }
 
-impl<'a> From<&'a [bool]> for BitVec {
+impl<'a> From<&'a [bool]> for BitVec<'_> {
     /// Creates a [BitVec] from a slice of booleans, each boolean representing one bit.
     #[use_thread_pool]
     fn from(value: &'a [bool]) -> Self {
-        use rayon::iter::ParallelIterator;
-        use rayon::slice::ParallelSlice;
+        use crate::internals::par::*;
 
         let words = value
-            .par_chunks(usize::BITS as usize)
+            .par_chunks(WORD_BITS)
             .map(|chunk| {
-                // [0] = MSB
-                chunk.iter().enumerate().fold(0usize, |word, (i, &bit)| {
-                    word | ((bit as usize) << ((usize::BITS as usize) - i - 1))
+                chunk.iter().enumerate().fold(0 as Word, |word, (i, &bit)| {
+                    if bit {
+                        word | word::bit_mask(i)
+                    } else {
+                        word
+                    }
                 })
             })
             .collect();
 
-        let bit_count_last_word = (value.len() % (usize::BITS as usize)) as u8;
+        let bit_count_last_word = word::bit_in_word(value.len()) as u8;
 
         Self {
             words,
@@ -401,8 +800,8 @@ impl<'a> From<&'a [bool]> for BitVec {
     }
 }
 
-impl AsRef<BitVec> for BitVec {
-    fn as_ref(&self) -> &BitVec {
+impl<'a> AsRef<BitVec<'a>> for BitVec<'a> {
+    fn as_ref(&self) -> &BitVec<'a> {
         self
     }
 }
@@ -0,0 +1,152 @@
+//! Zero-copy views into a [BitVec], see [BitSlice].
+
+use crate::bitvec::word::{self, Word, WORD_BITS};
+use crate::bitvec::BitVec;
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::ops::Range;
+use sts_lib_derive::use_thread_pool;
+
+/// A view into a range of bits of a [BitVec], created with [BitVec::bit_slice].
+///
+/// No bits are copied when creating a [BitSlice] (or a slice of it). Since the tests operate on
+/// [BitVec]s, use [BitSlice::to_bit_vec] to get a copy of the bits that can be tested.
+#[derive(Copy, Clone, Debug)]
+pub struct BitSlice<'a> {
+    // the words containing the bits, the first bit is in words[0].
+    words: &'a [Word],
+    // the index of the first bit in words[0], always < WORD_BITS.
+    offset: u32,
+    // the count of bits in the slice.
+    len_bit: usize,
+}
+
+impl<'a> BitSlice<'a> {
+    /// Creates a slice of the given range of bits of `words`, that contain `len_bit` bits.
+    ///
+    /// Panics if the range is out of bounds.
+    pub(crate) fn new(words: &'a [Word], offset: u32, len_bit: usize, range: Range<usize>) -> Self {
+        check_range(&range, len_bit);
+
+        let start = offset as usize + range.start;
+        let end = offset as usize + range.end;
+
+        Self {
+            words: &words[word::word_index(start)..end.div_ceil(WORD_BITS)],
+            offset: word::bit_in_word(start) as u32,
+            len_bit: range.len(),
+        }
+    }
+
+    /// How many bits the slice contains.
+    pub fn len_bit(&self) -> usize {
+        self.len_bit
+    }
+
+    /// Creates a view into the given range of bits of this slice. The range is relative to the
+    /// start of this slice.
+    ///
+    /// ## Panics
+    ///
+    /// If the start of the range is greater than its end, or the end is greater than
+    /// [Self::len_bit].
+    pub fn slice(&self, range: Range<usize>) -> BitSlice<'a> {
+        Self::new(self.words, self.offset, self.len_bit, range)
+    }
+
+    /// The count of bits with value 1 in the slice.
+    ///
+    /// This function runs in parallel.
+    #[use_thread_pool]
+    pub fn count_ones(&self) -> usize {
+        use crate::internals::par::*;
+
+        if self.len_bit == 0 {
+            return 0;
+        }
+
+        let last_idx = self.words.len() - 1;
+        // the count of bits of the slice in the last word, 0 means all bits.
+        let bits_last_word = word::bit_in_word(self.offset as usize + self.len_bit);
+
+        self.words
+            .par_iter()
+            .enumerate()
+            .map(|(i, &word)| {
+                let mut word = word;
+                if i == 0 {
+                    word &= !word::leading_mask(self.offset as usize);
+                }
+                if i == last_idx && bits_last_word != 0 {
+                    word &= word::leading_mask(bits_last_word);
+                }
+                word.count_ones() as usize
+            })
+            .sum()
+    }
+
+    /// Copies the bits of this slice into a new [BitVec].
+    ///
+    /// This function runs in parallel.
+    #[use_thread_pool]
+    pub fn to_bit_vec(&self) -> BitVec<'static> {
+        use crate::internals::par::*;
+
+        let word_count = self.len_bit.div_ceil(WORD_BITS);
+
+        let mut words: Vec<Word> = if self.offset == 0 {
+            self.words[..word_count].to_vec()
+        } else {
+            // each word is assembled from 2 neighbouring words
+            (0..word_count)
+                .into_par_iter()
+                .map(|i| {
+                    let high = self.words[i] << self.offset;
+                    let low = self
+                        .words
+                        .get(i + 1)
+                        .map_or(0, |&word| word >> (WORD_BITS as u32 - self.offset));
+                    high | low
+                })
+                .collect()
+        };
+
+        // the bits after the end of the slice must be 0.
+        let bit_count_last_word = word::bit_in_word(self.len_bit) as u8;
+        if bit_count_last_word != 0 {
+            if let Some(last) = words.last_mut() {
+                *last &= word::leading_mask(bit_count_last_word as usize);
+            }
+        }
+
+        BitVec {
+            words: Cow::Owned(words),
+            bit_count_last_word,
+        }
+    }
+}
+
+impl From<BitSlice<'_>> for BitVec<'_> {
+    /// Copies the bits of the slice, see [BitSlice::to_bit_vec].
+    fn from(value: BitSlice<'_>) -> Self {
+        value.to_bit_vec()
+    }
+}
+
+/// Panics if the given range is not valid for a sequence of `len_bit` bits, with a message
+/// similar to the one for slices.
+fn check_range(range: &Range<usize>, len_bit: usize) {
+    if range.start > range.end {
+        panic!(
+            "slice index starts at {} but ends at {}",
+            range.start, range.end
+        );
+    }
+
+    if range.end > len_bit {
+        panic!(
+            "range end index {} out of range for sequence of length {len_bit}",
+            range.end
+        );
+    }
+}
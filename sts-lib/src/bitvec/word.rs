@@ -0,0 +1,69 @@
+//! The words that store the bits of a [BitVec](super::BitVec), see [Word].
+//!
+//! The bits are stored in sequence order, starting with the most significant bit of each word.
+//! All conversions between words and bytes use this numeric order ([to_bytes] and [from_bytes]),
+//! never the layout of the word in memory. The words of a sequence are therefore the same on
+//! little- and big-endian targets, e.g. s390x, and no code may reinterpret the memory of words as
+//! bytes.
+
+/// The type of the words.
+pub(crate) type Word = usize;
+
+/// The count of bits in each word.
+pub(crate) const WORD_BITS: usize = Word::BITS as usize;
+
+/// The count of bytes in each word.
+pub(crate) const WORD_BYTES: usize = WORD_BITS / (u8::BITS as usize);
+
+/// The index of the word containing the bit with the given index of the sequence.
+#[inline]
+pub(crate) const fn word_index(bit_idx: usize) -> usize {
+    bit_idx / WORD_BITS
+}
+
+/// The index of the bit with the given index of the sequence within its word.
+#[inline]
+pub(crate) const fn bit_in_word(bit_idx: usize) -> usize {
+    bit_idx % WORD_BITS
+}
+
+/// The mask of the bit with the given index within a word, index 0 being the most significant
+/// bit.
+#[inline]
+pub(crate) const fn bit_mask(bit_in_word: usize) -> Word {
+    1 << (WORD_BITS - 1 - bit_in_word)
+}
+
+/// Returns the bit with the given index within the word, index 0 being the most significant bit.
+#[inline]
+pub(crate) const fn get_bit(word: Word, bit_in_word: usize) -> bool {
+    word & bit_mask(bit_in_word) != 0
+}
+
+/// Returns the bit with the given index of the sequence stored in the words.
+#[inline]
+pub(crate) fn get_bit_at(words: &[Word], bit_idx: usize) -> bool {
+    get_bit(words[word_index(bit_idx)], bit_in_word(bit_idx))
+}
+
+/// The mask of the first `count` bits of a word. `count` may be at most [WORD_BITS].
+#[inline]
+pub(crate) const fn leading_mask(count: usize) -> Word {
+    if count == 0 {
+        0
+    } else {
+        Word::MAX << (WORD_BITS - count)
+    }
+}
+
+/// The bytes of the word in sequence order: the first byte contains the first 8 bits.
+#[inline]
+pub(crate) const fn to_bytes(word: Word) -> [u8; WORD_BYTES] {
+    word.to_be_bytes()
+}
+
+/// Creates a word from bytes in sequence order, see [to_bytes].
+#[inline]
+pub(crate) const fn from_bytes(bytes: [u8; WORD_BYTES]) -> Word {
+    Word::from_be_bytes(bytes)
+}
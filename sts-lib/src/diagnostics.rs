@@ -0,0 +1,128 @@
+//! Diagnostics of byte-oriented inputs, see [bit_position_bias].
+//!
+//! **These diagnostics are not part of NIST SP 800-22 and give no P-value.** The tests of SP
+//! 800-22 treat the input as a single sequence of bits, but many inputs are produced byte by byte,
+//! e.g. by hardware random number generators, and often fail in a single bit position: a stuck or
+//! biased bit of the sampling circuit only shows up as a slight bias over the whole sequence, but
+//! as a strong bias at its position within each byte.
+//!
+//! For the distribution of the run lengths of zeros and ones, see
+//! [run_length_histogram](crate::tests::run_length_histogram).
+
+use crate::bitvec::word::{self, Word, WORD_BITS, WORD_BYTES};
+use crate::bitvec::BitVec;
+use crate::internals::trace_span;
+use crate::Error;
+
+/// The count of bit positions within a byte.
+pub const BYTE_BITS: usize = u8::BITS as usize;
+
+/// The ones frequency and the bias of each bit position within the bytes of a sequence, see
+/// [bit_position_bias].
+///
+/// The position 0 is the first bit of each byte in sequence order, i.e. the most significant bit
+/// for inputs read with [BitOrder::MsbFirst](crate::bitvec::BitOrder::MsbFirst).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BitPositionBias {
+    byte_count: usize,
+    ones: [usize; BYTE_BITS],
+}
+
+impl BitPositionBias {
+    /// The count of (full) bytes that were analysed.
+    pub fn byte_count(&self) -> usize {
+        self.byte_count
+    }
+
+    /// The count of ones at each bit position.
+    pub fn ones(&self) -> [usize; BYTE_BITS] {
+        self.ones
+    }
+
+    /// The frequency of ones at each bit position, 0.5 being expected for random sequences.
+    pub fn ones_frequencies(&self) -> [f64; BYTE_BITS] {
+        self.ones.map(|ones| ones as f64 / self.byte_count as f64)
+    }
+
+    /// The chi-square statistic of the counts of zeros and ones at each bit position,
+    /// `(2 * ones - n)^2 / n` with *n* being the count of bytes. For random sequences, it follows
+    /// the chi-square distribution with 1 degree of freedom: values above 6.635 occur with a
+    /// probability of 1 %.
+    pub fn chi_squares(&self) -> [f64; BYTE_BITS] {
+        self.ones.map(|ones| {
+            let diff = (2 * ones).abs_diff(self.byte_count) as f64;
+            diff * diff / self.byte_count as f64
+        })
+    }
+
+    /// The sum of the [chi-square statistics](Self::chi_squares) over all bit positions, with
+    /// 8 degrees of freedom for random sequences.
+    pub fn total_chi_square(&self) -> f64 {
+        self.chi_squares().into_iter().sum()
+    }
+
+    /// The bit position with the largest [chi-square statistic](Self::chi_squares), the first one
+    /// if several positions have the same statistic.
+    pub fn most_biased_position(&self) -> usize {
+        let chi_squares = self.chi_squares();
+        (0..BYTE_BITS).fold(0, |max, position| {
+            if chi_squares[position] > chi_squares[max] {
+                position
+            } else {
+                max
+            }
+        })
+    }
+}
+
+/// Counts the ones at each bit position (0 to 7) within the bytes of the sequence, see the
+/// [module docs](self). Trailing bits that do not fill a byte are ignored.
+///
+/// If the data has less than 8 bits, [Error::InvalidParameter] is raised.
+///
+/// ```
+/// use sts_lib::bitvec::BitVec;
+/// use sts_lib::diagnostics::bit_position_bias;
+///
+/// // the last bit of each byte is stuck at 1
+/// let data = BitVec::from([0b1010_1011_u8, 0b0101_0101, 0b1100_0011, 0b0011_1101].as_slice());
+/// let bias = bit_position_bias(&data).unwrap();
+/// assert_eq!(bias.ones(), [2, 2, 2, 2, 2, 2, 2, 4]);
+/// assert_eq!(bias.most_biased_position(), 7);
+/// assert_eq!(bias.chi_squares()[7], 4.0);
+/// ```
+pub fn bit_position_bias(data: &BitVec) -> Result<BitPositionBias, Error> {
+    trace_span!(INFO, "bit_position_bias", len_bit = data.len_bit());
+
+    let byte_count = data.len_bit() / BYTE_BITS;
+    if byte_count == 0 {
+        return Err(Error::InvalidParameter(format!(
+            "The bit position bias needs at least {BYTE_BITS} bits, got {}.",
+            data.len_bit()
+        )));
+    }
+
+    // the mask of the bit position in each byte of a word
+    let masks: [Word; BYTE_BITS] =
+        std::array::from_fn(|position| Word::MAX / 0xFF * (0x80 >> position));
+
+    let mut ones = [0; BYTE_BITS];
+    let mut count_word = |word: Word| {
+        for (ones, mask) in ones.iter_mut().zip(masks) {
+            *ones += (word & mask).count_ones() as usize;
+        }
+    };
+
+    let full_words = byte_count / WORD_BYTES;
+    for &word in &data.words[..full_words] {
+        count_word(word);
+    }
+    // the full bytes of the last word
+    let remaining_bits = (byte_count % WORD_BYTES) * BYTE_BITS;
+    if remaining_bits != 0 {
+        debug_assert!(remaining_bits < WORD_BITS);
+        count_word(data.words[full_words] & word::leading_mask(remaining_bits));
+    }
+
+    Ok(BitPositionBias { byte_count, ones })
+}
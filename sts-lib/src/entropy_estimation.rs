@@ -0,0 +1,222 @@
+//! Estimation of the entropy per bit of a noise source, see [estimate_entropy].
+//!
+//! **These estimators are not part of NIST SP 800-22.** While the tests of SP 800-22 check if a
+//! sequence is random, NIST SP 800-90B (section 6.3) specifies estimators for the min-entropy of
+//! the samples of a noise source. This module implements two of them for binary samples (one bit
+//! per sample), each giving an upper bound of the probability *p* of the most likely bit value with
+//! a confidence of 99 %, and the min-entropy `-log2(p)` in bits per bit:
+//!
+//! 1. [Most common value](most_common_value_estimate) (section 6.3.1): the proportion of the
+//!    more common bit value.
+//! 2. [Collision](collision_estimate) (section 6.3.2): the mean distance until a bit value is
+//!    repeated.
+//!
+//! Additionally, the [Shannon entropy](shannon_entropy) of the bit frequencies is given, which is
+//! always at least the min-entropy.
+//!
+//! SP 800-90B takes the minimum of all of its (10) estimators as the entropy of a non-IID source,
+//! so the estimate of this module is only a screening: it is never lower than the full
+//! assessment would be. SP 800-90B requires at least [RECOMMENDED_LENGTH] samples.
+
+use crate::bitvec::BitVec;
+use crate::internals::{check_f64, trace_span};
+use crate::Error;
+use std::num::NonZero;
+
+/// The minimum input length, in bits, for all estimators: the collision estimator needs at least
+/// 2 collisions.
+pub const MIN_LENGTH: NonZero<usize> = const {
+    match NonZero::new(6) {
+        Some(v) => v,
+        None => panic!("Literal should be non-zero!"),
+    }
+};
+
+/// The count of samples required by SP 800-90B, in bits.
+pub const RECOMMENDED_LENGTH: NonZero<usize> = const {
+    match NonZero::new(1_000_000) {
+        Some(v) => v,
+        None => panic!("Literal should be non-zero!"),
+    }
+};
+
+/// The quantile of the standard normal distribution for the 99 % confidence intervals.
+const Z_ALPHA: f64 = 2.576;
+
+/// The result of one entropy estimator, see the [module docs](self).
+#[derive(Copy, Clone, Debug)]
+pub struct EntropyEstimate {
+    probability_bound: f64,
+}
+
+impl EntropyEstimate {
+    /// The upper bound of the probability of the most likely bit value, between 0.5 and 1.
+    pub fn probability_bound(&self) -> f64 {
+        self.probability_bound
+    }
+
+    /// The estimated min-entropy, in bits per bit: `-log2(p)` with *p* being the
+    /// [probability bound](Self::probability_bound). Between 0 and 1.
+    pub fn min_entropy(&self) -> f64 {
+        // -0.0 for p = 1
+        (-self.probability_bound.log2()).max(0.0)
+    }
+}
+
+/// The results of all estimators, see [estimate_entropy].
+#[derive(Copy, Clone, Debug)]
+pub struct EntropyEstimates {
+    shannon_entropy: f64,
+    most_common_value: EntropyEstimate,
+    collision: EntropyEstimate,
+}
+
+impl EntropyEstimates {
+    /// The Shannon entropy of the bit frequencies, in bits per bit, see [shannon_entropy].
+    pub fn shannon_entropy(&self) -> f64 {
+        self.shannon_entropy
+    }
+
+    /// The estimate of the most common value estimator, see [most_common_value_estimate].
+    pub fn most_common_value(&self) -> EntropyEstimate {
+        self.most_common_value
+    }
+
+    /// The estimate of the collision estimator, see [collision_estimate].
+    pub fn collision(&self) -> EntropyEstimate {
+        self.collision
+    }
+
+    /// The minimum of the min-entropy of all estimators, in bits per bit.
+    pub fn min_entropy(&self) -> f64 {
+        self.most_common_value
+            .min_entropy()
+            .min(self.collision.min_entropy())
+    }
+}
+
+/// Runs all estimators on the given data, see the [module docs](self).
+///
+/// If the data is shorter than [MIN_LENGTH], [Error::InvalidParameter] is raised.
+///
+/// ```
+/// use sts_lib::bitvec::BitVec;
+/// use sts_lib::entropy_estimation::estimate_entropy;
+///
+/// // a constant sequence has no entropy
+/// let data = BitVec::from_ascii_str("1111111111111111").unwrap();
+/// let estimates = estimate_entropy(&data).unwrap();
+/// assert_eq!(estimates.min_entropy(), 0.0);
+/// assert_eq!(estimates.shannon_entropy(), 0.0);
+/// ```
+pub fn estimate_entropy(data: &BitVec) -> Result<EntropyEstimates, Error> {
+    trace_span!(INFO, "estimate_entropy", len_bit = data.len_bit());
+
+    Ok(EntropyEstimates {
+        shannon_entropy: shannon_entropy(data)?,
+        most_common_value: most_common_value_estimate(data)?,
+        collision: collision_estimate(data)?,
+    })
+}
+
+/// The Shannon entropy of the bit frequencies, in bits per bit: `-p * log2(p) - q * log2(q)`, with
+/// *p* and *q* being the proportions of ones and zeros. This ignores any dependencies between the
+/// bits.
+///
+/// If the data is shorter than [MIN_LENGTH], [Error::InvalidParameter] is raised.
+pub fn shannon_entropy(data: &BitVec) -> Result<f64, Error> {
+    check_length(data)?;
+
+    let p = data.count_ones() as f64 / data.len_bit() as f64;
+    let entropy = [p, 1.0 - p]
+        .into_iter()
+        .filter(|&p| p > 0.0)
+        .map(|p| -p * p.log2())
+        .sum::<f64>();
+    check_f64(entropy)?;
+
+    Ok(entropy)
+}
+
+/// The most common value estimate of SP 800-90B, section 6.3.1: with *p̂* being the proportion of
+/// the more common bit value in the *L* bits of the data, the probability bound is
+/// `min(1, p̂ + 2.576 * sqrt(p̂ * (1 - p̂) / (L - 1)))`.
+///
+/// If the data is shorter than [MIN_LENGTH], [Error::InvalidParameter] is raised.
+pub fn most_common_value_estimate(data: &BitVec) -> Result<EntropyEstimate, Error> {
+    check_length(data)?;
+
+    let len = data.len_bit() as f64;
+    let ones = data.count_ones();
+    let p_hat = ones.max(data.len_bit() - ones) as f64 / len;
+    let bound = p_hat + Z_ALPHA * f64::sqrt(p_hat * (1.0 - p_hat) / (len - 1.0));
+    check_f64(bound)?;
+
+    Ok(EntropyEstimate {
+        probability_bound: bound.min(1.0),
+    })
+}
+
+/// The collision estimate of SP 800-90B, section 6.3.2: the data is divided into consecutive
+/// segments that end with the first repetition of a bit value, for bits each segment has a length
+/// *t* of 2 or 3. With the mean *X̄* and the standard deviation *σ* of the *v* lengths, the lower
+/// bound of the mean is `X̄' = X̄ - 2.576 * σ / sqrt(v)`.
+///
+/// The probability bound is the probability *p* of the most likely bit value for which the
+/// expected length equals `X̄'`. For bits, the expected length is `2 + 2 * p * (1 - p)`, so *p* is
+/// calculated directly instead of with the binary search of SP 800-90B. If `X̄' >= 2.5`, there is
+/// no solution and the bound is 0.5 (1 bit of entropy), as specified. If `X̄' <= 2`, the bound is
+/// 1.
+///
+/// If the data is shorter than [MIN_LENGTH], [Error::InvalidParameter] is raised.
+pub fn collision_estimate(data: &BitVec) -> Result<EntropyEstimate, Error> {
+    check_length(data)?;
+
+    // the lengths of the segments until the first collision: 2 if the first 2 bits are equal,
+    // else 3 (the third bit equals one of them). A last incomplete segment is not counted.
+    let mut lengths = Vec::with_capacity(data.len_bit() / 2);
+    let mut bits = data.iter_bits();
+    while let (Some(first), Some(second)) = (bits.next(), bits.next()) {
+        if first == second {
+            lengths.push(2_u8);
+        } else if bits.next().is_some() {
+            lengths.push(3);
+        } else {
+            break;
+        }
+    }
+
+    let count = lengths.len() as f64;
+    let mean = lengths.iter().map(|&t| t as f64).sum::<f64>() / count;
+    let variance = lengths
+        .iter()
+        .map(|&t| (t as f64 - mean).powi(2))
+        .sum::<f64>()
+        / (count - 1.0);
+    let mean_bound = mean - Z_ALPHA * variance.sqrt() / count.sqrt();
+    check_f64(mean_bound)?;
+
+    let probability_bound = if mean_bound >= 2.5 {
+        0.5
+    } else if mean_bound <= 2.0 {
+        1.0
+    } else {
+        // solve 2 + 2 * p * (1 - p) = mean_bound for p >= 0.5
+        (1.0 + f64::sqrt(5.0 - 2.0 * mean_bound)) / 2.0
+    };
+
+    Ok(EntropyEstimate { probability_bound })
+}
+
+/// Checks that the data has at least [MIN_LENGTH] bits.
+fn check_length(data: &BitVec) -> Result<(), Error> {
+    if data.len_bit() < MIN_LENGTH.get() {
+        Err(Error::InvalidParameter(format!(
+            "The entropy estimation needs at least {} bits. Has: {}",
+            MIN_LENGTH,
+            data.len_bit()
+        )))
+    } else {
+        Ok(())
+    }
+}
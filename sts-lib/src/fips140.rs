@@ -0,0 +1,192 @@
+//! The statistical power-up tests of FIPS 140-2, see [fips140_2_tests].
+//!
+//! **These tests are not part of NIST SP 800-22.** FIPS 140-2 (section 4.9.1, as originally
+//! published) specifies 4 quick tests on a single sample of [SAMPLE_LENGTH] bits, each with fixed
+//! acceptance bounds instead of a P-value:
+//!
+//! 1. [Monobit](Fips140Test::Monobit): the count of ones must lie in `(9725, 10275)`.
+//! 2. [Poker](Fips140Test::Poker): the sample is divided into 5000 4-bit segments. With *f(i)*
+//!    being the count of each of the 16 possible values, the statistic
+//!    `X = 16 / 5000 * sum(f(i)^2) - 5000` must lie in `(2.16, 46.17)`.
+//! 3. [Runs](Fips140Test::Runs): the count of runs of each length (1 to 5, and 6 or longer) must
+//!    lie within [RUNS_INTERVALS], separately for runs of zeros and ones.
+//! 4. [Long run](Fips140Test::LongRun): there may be no run of [LONG_RUN_LENGTH] or more equal
+//!    bits.
+//!
+//! These tests are much faster than the tests of SP 800-22 and are meant as a screening before
+//! running the full test suite: a sequence failing them is very likely not random, but passing
+//! them says little about the quality of the sequence.
+
+use crate::bitvec::BitVec;
+use crate::internals::trace_span;
+use crate::Error;
+use std::num::NonZero;
+use strum::{Display, EnumIter};
+
+/// The length of the sample, in bits, that is tested. Longer inputs are cropped to this length.
+pub const SAMPLE_LENGTH: NonZero<usize> = const {
+    match NonZero::new(20_000) {
+        Some(v) => v,
+        None => panic!("Literal should be non-zero!"),
+    }
+};
+
+/// The (inclusive) intervals for the count of runs of length 1, 2, 3, 4, 5 and 6 or longer,
+/// applied to runs of zeros and runs of ones separately.
+pub const RUNS_INTERVALS: [(usize, usize); 6] = [
+    (2343, 2657),
+    (1135, 1365),
+    (542, 708),
+    (251, 373),
+    (111, 201),
+    (111, 201),
+];
+
+/// The length from which on a run fails the [long run](Fips140Test::LongRun) test.
+pub const LONG_RUN_LENGTH: usize = 26;
+
+/// The (exclusive) interval for the count of ones in the [monobit](Fips140Test::Monobit) test.
+const MONOBIT_INTERVAL: (usize, usize) = (9725, 10275);
+
+/// The (exclusive) interval for the statistic of the [poker](Fips140Test::Poker) test.
+const POKER_INTERVAL: (f64, f64) = (2.16, 46.17);
+
+/// The single tests of FIPS 140-2, see the [module docs](self).
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, EnumIter, Display)]
+#[repr(u8)]
+pub enum Fips140Test {
+    /// The count of ones.
+    Monobit = 0,
+    /// The distribution of 4-bit segments.
+    Poker = 1,
+    /// The count of runs of each length.
+    Runs = 2,
+    /// The absence of long runs.
+    LongRun = 3,
+}
+
+/// The result of the FIPS 140-2 tests, see [fips140_2_tests].
+#[derive(Copy, Clone, Debug)]
+pub struct Fips140Result {
+    ones: usize,
+    poker_statistic: f64,
+    runs: [[usize; 6]; 2],
+    longest_run: usize,
+}
+
+impl Fips140Result {
+    /// The count of ones in the sample, used by the [monobit](Fips140Test::Monobit) test.
+    pub fn ones(&self) -> usize {
+        self.ones
+    }
+
+    /// The statistic of the [poker](Fips140Test::Poker) test.
+    pub fn poker_statistic(&self) -> f64 {
+        self.poker_statistic
+    }
+
+    /// The count of runs of the given bit with length 1, 2, 3, 4, 5 and 6 or longer, used by the
+    /// [runs](Fips140Test::Runs) test.
+    pub fn runs(&self, bit: bool) -> [usize; 6] {
+        self.runs[bit as usize]
+    }
+
+    /// The length of the longest run in the sample, used by the [long run](Fips140Test::LongRun)
+    /// test.
+    pub fn longest_run(&self) -> usize {
+        self.longest_run
+    }
+
+    /// If the given test passed.
+    pub fn passed(&self, test: Fips140Test) -> bool {
+        match test {
+            Fips140Test::Monobit => {
+                MONOBIT_INTERVAL.0 < self.ones && self.ones < MONOBIT_INTERVAL.1
+            }
+            Fips140Test::Poker => {
+                POKER_INTERVAL.0 < self.poker_statistic && self.poker_statistic < POKER_INTERVAL.1
+            }
+            Fips140Test::Runs => self.runs.iter().all(|runs| {
+                runs.iter()
+                    .zip(RUNS_INTERVALS)
+                    .all(|(count, (min, max))| (min..=max).contains(count))
+            }),
+            Fips140Test::LongRun => self.longest_run < LONG_RUN_LENGTH,
+        }
+    }
+
+    /// If all tests passed - the combined verdict.
+    pub fn all_passed(&self) -> bool {
+        self.passed(Fips140Test::Monobit)
+            && self.passed(Fips140Test::Poker)
+            && self.passed(Fips140Test::Runs)
+            && self.passed(Fips140Test::LongRun)
+    }
+}
+
+/// Runs the FIPS 140-2 tests on the first [SAMPLE_LENGTH] bits of the given data. See the
+/// [module docs](self).
+///
+/// If the data is shorter than [SAMPLE_LENGTH], [Error::InvalidParameter] is raised.
+pub fn fips140_2_tests(data: &BitVec) -> Result<Fips140Result, Error> {
+    trace_span!(INFO, "fips140_2_tests", len_bit = data.len_bit());
+
+    if data.len_bit() < SAMPLE_LENGTH.get() {
+        return Err(Error::InvalidParameter(format!(
+            "The FIPS 140-2 tests need at least {} bits. Has: {}",
+            SAMPLE_LENGTH,
+            data.len_bit()
+        )));
+    }
+
+    let sample = data.bit_slice(0..SAMPLE_LENGTH.get());
+
+    // Monobit: count of ones
+    let ones = sample.count_ones();
+
+    // Poker: frequency of each 4-bit segment. The word size is a multiple of 4, so each segment
+    // lies within one word - the segments in the padding of the last word are skipped.
+    let sample = sample.to_bit_vec();
+    let segment_count = SAMPLE_LENGTH.get() / 4;
+    let mut frequencies = [0_usize; 16];
+    sample
+        .words
+        .iter()
+        .flat_map(|word| {
+            (0..usize::BITS)
+                .step_by(4)
+                .rev()
+                .map(move |shift| (word >> shift) & 0xF)
+        })
+        .take(segment_count)
+        .for_each(|segment| frequencies[segment] += 1);
+    let square_sum = frequencies.iter().map(|&f| (f * f) as f64).sum::<f64>();
+    let poker_statistic = 16.0 / segment_count as f64 * square_sum - segment_count as f64;
+
+    // Runs and long run: count of runs of each length, and the longest run
+    let mut runs = [[0_usize; 6]; 2];
+    let mut longest_run = 0;
+    let mut bits = sample.iter_bits();
+    let mut current_bit = bits.next().expect("sample is not empty");
+    let mut current_length = 1;
+    for bit in bits {
+        if bit == current_bit {
+            current_length += 1;
+        } else {
+            runs[current_bit as usize][current_length.min(6) - 1] += 1;
+            longest_run = longest_run.max(current_length);
+            current_bit = bit;
+            current_length = 1;
+        }
+    }
+    // the last run
+    runs[current_bit as usize][current_length.min(6) - 1] += 1;
+    longest_run = longest_run.max(current_length);
+
+    Ok(Fips140Result {
+        ones,
+        poker_statistic,
+        runs,
+        longest_run,
+    })
+}
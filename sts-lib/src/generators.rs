@@ -0,0 +1,304 @@
+//! The reference generators of NIST SP 800-22r1a, appendix D, see [Generator].
+//!
+//! The reference implementation of the STS ships these generators to produce sequences for the
+//! validation of the tests. All generators use the fixed seeds of the reference implementation,
+//! meaning each generator always produces the same sequence. The sequences can be used to verify
+//! this implementation, or as calibration sequences for other test suites.
+//!
+//! This module is only available with the feature `generators`.
+
+use crate::bitvec::builder::BitVecBuilder;
+use crate::bitvec::BitVec;
+use num_bigint::BigUint;
+use strum::{Display, EnumIter, EnumString, IntoStaticStr};
+
+/// The 512-bit prime modulus of the quadratic congruential generator I and the modular
+/// exponentiation generator.
+const PRIME_512: &str = "987b6a6bf2c56a97291c445409920032499f9ee7ad128301b5d0254aa1a9633fdbd378d40149f1e23a13849f3d45992f5c4c6b7104099bc301f6005f9d8115e1";
+/// The seed of the quadratic congruential generator I and the base of the modular exponentiation
+/// generator.
+const SEED_512: &str = "3844506a9456c564b8b8538e0cc15aff46c95e69600f084f0657c2401b3c244734b62ea9bb95be4923b9b7e84eeaf1a224894ef0328d44bc3eb3e983644da3f5";
+/// The seed of the quadratic congruential generator II and the cubic congruential generator.
+const SEED_512_2: &str = "7844506a9456c564b8b8538e0cc15aff46c95e69600f084f0657c2401b3c244734b62ea9bb95be4923b9b7e84eeaf1a224894ef0328d44bc3eb3e983644da3f5";
+/// The initial exponent of the modular exponentiation generator.
+const MODEXP_SEED: &str = "7ab36982ce1adf832019cdfeb2393cabdf0214ec";
+/// The primes of the Blum-Blum-Shub generator, both are congruent to 3 mod 4.
+const BBS_P: &str = "e65097baec92e70478caf4ed0ed94e1c94b154466bfb9ec9be37b2b0ff8526c222b76e0e915017535ae8b9207250257d0a0c87c0dacef78e17d1ef9dc44fd91f";
+const BBS_Q: &str = "e029aefcf8ea2c29d99cb53dd5fa9bc1d0176f5df8d9110fd16ee21f32e37ba86ff42f00531ad5b8a43073182cc2e15f5c86e8da059e346777c9a985f7d8a867";
+/// The seed of the Blum-Blum-Shub generator.
+const BBS_SEED: &str = "10d6333cfac8e30e808d2192f7c0439480da79db9bbca1667d73be9a677ed31311f3b830937763837cb7b1b1dc75f14eea417f84d9625628750de99e7ef1e976";
+/// The seed of the Micali-Schnorr generator. Only the lowest [MS_R] bits are used.
+const MS_SEED: &str =
+    "237c5f791c2cfe47bfb16d2d54a0d60665b20904ec822a619d6ed5d9492218a7a4c5b15d57c61601";
+/// The parameters of the Micali-Schnorr generator: the exponent, the bit length of the modulus
+/// (the product of the Blum-Blum-Shub primes), the bits output per step and the bits kept as the
+/// next state.
+const MS_E: u32 = 11;
+const MS_K: u64 = 837;
+const MS_R: u64 = 187;
+/// The initial key of the G-SHA1 generator.
+const SHA1_XKEY: [u8; 20] = [
+    0xec, 0x82, 0x2a, 0x61, 0x9d, 0x6e, 0xd5, 0xd9, 0x49, 0x22, 0x18, 0xa7, 0xa4, 0xc5, 0xb1, 0x5d,
+    0x57, 0xc6, 0x16, 0x01,
+];
+/// The initial hash value of SHA-1.
+const SHA1_INITIAL_STATE: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+/// The seed of the XOR generator, 127 bits.
+const XOR_SEED: &str = "0001011011011001000101111001001010011011101101000100000010101111111010100100001010110110000000000100110000101110011111111100111";
+
+/// The reference generators of NIST SP 800-22r1a, appendix D.
+///
+/// The name of each generator (as used by [Display] and [FromStr](std::str::FromStr)) is given in
+/// the documentation of the variant.
+///
+/// ```
+/// use sts_lib::generators::Generator;
+/// use sts_lib::tests::frequency::frequency_test;
+/// use sts_lib::DEFAULT_THRESHOLD;
+///
+/// let generator: Generator = "blum-blum-shub".parse().unwrap();
+/// assert_eq!(generator, Generator::BlumBlumShub);
+///
+/// let data = generator.generate(10_000);
+/// assert_eq!(data.len_bit(), 10_000);
+/// assert!(frequency_test(&data).unwrap().passed(DEFAULT_THRESHOLD));
+/// ```
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, EnumIter, EnumString, Display, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Generator {
+    /// Name: `linear-congruential`. The linear congruential generator of appendix D.1:
+    /// `z_i = 950706376 * z_{i-1} mod (2^31 - 1)`, each step outputs one bit: 1 if
+    /// `z_i / (2^31 - 1) >= 0.5`.
+    LinearCongruential,
+    /// Name: `quadratic-congruential-1`. The quadratic congruential generator I of appendix D.2:
+    /// `x_i = x_{i-1}^2 mod p` for a 512-bit prime *p*, each step outputs all 512 bits.
+    #[strum(serialize = "quadratic-congruential-1")]
+    QuadraticCongruential1,
+    /// Name: `quadratic-congruential-2`. The quadratic congruential generator II of appendix D.3:
+    /// `x_i = 2 * x_{i-1}^2 + 3 * x_{i-1} + 1 mod 2^512`, each step outputs all 512 bits.
+    #[strum(serialize = "quadratic-congruential-2")]
+    QuadraticCongruential2,
+    /// Name: `cubic-congruential`. The cubic congruential generator of appendix D.4:
+    /// `x_i = x_{i-1}^3 mod 2^512`, each step outputs all 512 bits.
+    CubicCongruential,
+    /// Name: `xor`. The exclusive OR generator of appendix D.5:
+    /// `x_i = x_{i-1} ⊕ x_{i-127}`, starting with a 127-bit seed. The seed itself is not output.
+    Xor,
+    /// Name: `modular-exponentiation`. The modular exponentiation generator of appendix D.6:
+    /// `x_i = g^{y_i} mod p` for a 512-bit prime *p*, each step outputs all 512 bits. The next
+    /// exponent `y_{i+1}` are the lowest 160 bits of `x_i`.
+    ModularExponentiation,
+    /// Name: `g-sha1`. The secure hash generator of appendix D.7, based on the SHA-1 function
+    /// *G* of FIPS 186-2, each step outputs 160 bits.
+    #[strum(serialize = "g-sha1")]
+    GSha1,
+    /// Name: `blum-blum-shub`. The Blum-Blum-Shub generator of appendix D.8:
+    /// `x_i = x_{i-1}^2 mod pq` for two 512-bit primes *p* and *q*, each step outputs the least
+    /// significant bit of `x_i`.
+    BlumBlumShub,
+    /// Name: `micali-schnorr`. The Micali-Schnorr generator of appendix D.9:
+    /// `y_i = x_{i-1}^11 mod pq` with the primes of [Self::BlumBlumShub], each step outputs the
+    /// lowest 837 bits of `y_i` and keeps the highest 187 bits as `x_i`. The seed of the reference
+    /// implementation is reduced to its lowest 187 bits.
+    MicaliSchnorr,
+}
+
+impl Generator {
+    /// The name of the generator, e.g. `blum-blum-shub`.
+    pub fn name(&self) -> &'static str {
+        self.into()
+    }
+
+    /// Generates the first `len_bit` bits of the sequence of this generator.
+    ///
+    /// The sequence is always the same, since the seeds of the reference implementation are used.
+    /// Note that the generators based on big integer arithmetic are slow, especially
+    /// [Self::BlumBlumShub], which only outputs one bit per modular squaring.
+    pub fn generate(&self, len_bit: usize) -> BitVec<'static> {
+        let mut builder = BitVecBuilder::with_max_length(len_bit);
+
+        match self {
+            Generator::LinearCongruential => linear_congruential(&mut builder),
+            Generator::QuadraticCongruential1 => quadratic_congruential_1(&mut builder),
+            Generator::QuadraticCongruential2 => quadratic_congruential_2(&mut builder),
+            Generator::CubicCongruential => cubic_congruential(&mut builder),
+            Generator::Xor => xor(&mut builder),
+            Generator::ModularExponentiation => modular_exponentiation(&mut builder),
+            Generator::GSha1 => g_sha1(&mut builder),
+            Generator::BlumBlumShub => blum_blum_shub(&mut builder),
+            Generator::MicaliSchnorr => micali_schnorr(&mut builder),
+        }
+
+        builder.build()
+    }
+}
+
+/// Parses the given hexadecimal constant.
+fn hex(value: &str) -> BigUint {
+    BigUint::parse_bytes(value.as_bytes(), 16).expect("constants are valid hexadecimal numbers")
+}
+
+/// Appends the given number as a big-endian number with the given count of bytes.
+fn push_number(builder: &mut BitVecBuilder, value: &BigUint, len_bytes: usize) {
+    let bytes = value.to_bytes_be();
+    builder.extend_from_bytes(&vec![0; len_bytes.saturating_sub(bytes.len())]);
+    builder.extend_from_bytes(&bytes);
+}
+
+fn linear_congruential(builder: &mut BitVecBuilder) {
+    const MODULUS: u64 = (1 << 31) - 1;
+    const MULTIPLIER: u64 = 950706376;
+
+    let mut z: u64 = 23482349;
+    while !builder.is_full() {
+        // cannot overflow: z < 2^31 and MULTIPLIER < 2^30
+        z = z * MULTIPLIER % MODULUS;
+        // equivalent to z / MODULUS >= 0.5
+        builder.push_bit(2 * z >= MODULUS);
+    }
+}
+
+fn quadratic_congruential_1(builder: &mut BitVecBuilder) {
+    let p = hex(PRIME_512);
+    let mut x = hex(SEED_512);
+
+    while !builder.is_full() {
+        x = &x * &x % &p;
+        push_number(builder, &x, 64);
+    }
+}
+
+fn quadratic_congruential_2(builder: &mut BitVecBuilder) {
+    let modulus = BigUint::from(1_u8) << 512;
+    let mut x = hex(SEED_512_2);
+
+    while !builder.is_full() {
+        x = (2_u8 * &x * &x + 3_u8 * &x + 1_u8) % &modulus;
+        push_number(builder, &x, 64);
+    }
+}
+
+fn cubic_congruential(builder: &mut BitVecBuilder) {
+    let modulus = BigUint::from(1_u8) << 512;
+    let mut x = hex(SEED_512_2);
+
+    while !builder.is_full() {
+        x = x.modpow(&BigUint::from(3_u8), &modulus);
+        push_number(builder, &x, 64);
+    }
+}
+
+fn xor(builder: &mut BitVecBuilder) {
+    // ring buffer of the last 127 bits, idx points to the oldest bit x_{i-127}
+    let mut bits = XOR_SEED.bytes().map(|c| c == b'1').collect::<Vec<_>>();
+    let mut idx = 0;
+
+    while !builder.is_full() {
+        let previous = bits[(idx + bits.len() - 1) % bits.len()];
+        let bit = previous ^ bits[idx];
+        bits[idx] = bit;
+        idx = (idx + 1) % bits.len();
+
+        builder.push_bit(bit);
+    }
+}
+
+fn modular_exponentiation(builder: &mut BitVecBuilder) {
+    let p = hex(PRIME_512);
+    let g = hex(SEED_512);
+    let mask = (BigUint::from(1_u8) << 160) - 1_u8;
+    let mut y = hex(MODEXP_SEED);
+
+    while !builder.is_full() {
+        let x = g.modpow(&y, &p);
+        push_number(builder, &x, 64);
+        y = x & &mask;
+    }
+}
+
+fn g_sha1(builder: &mut BitVecBuilder) {
+    let modulus = BigUint::from(1_u8) << 160;
+    let mut xkey = BigUint::from_bytes_be(&SHA1_XKEY);
+
+    while !builder.is_full() {
+        let mut block = [0; 64];
+        let xkey_bytes = xkey.to_bytes_be();
+        block[20 - xkey_bytes.len()..20].copy_from_slice(&xkey_bytes);
+
+        let state = sha1_compress(SHA1_INITIAL_STATE, &block);
+        let output = state
+            .iter()
+            .flat_map(|word| word.to_be_bytes())
+            .collect::<Vec<_>>();
+        builder.extend_from_bytes(&output);
+
+        xkey = (1_u8 + xkey + BigUint::from_bytes_be(&output)) % &modulus;
+    }
+}
+
+fn blum_blum_shub(builder: &mut BitVecBuilder) {
+    let n = hex(BBS_P) * hex(BBS_Q);
+    let seed = hex(BBS_SEED);
+    let mut x = &seed * &seed % &n;
+
+    while !builder.is_full() {
+        x = &x * &x % &n;
+        builder.push_bit(x.bit(0));
+    }
+}
+
+fn micali_schnorr(builder: &mut BitVecBuilder) {
+    let n = hex(BBS_P) * hex(BBS_Q);
+    let e = BigUint::from(MS_E);
+    let mut x = hex(MS_SEED) & ((BigUint::from(1_u8) << MS_R) - 1_u8);
+
+    while !builder.is_full() {
+        let y = x.modpow(&e, &n);
+        for bit in (0..MS_K).rev() {
+            builder.push_bit(y.bit(bit));
+        }
+        x = y >> MS_K;
+    }
+}
+
+/// The SHA-1 compression function of FIPS 180: processes one 512-bit block, starting with the
+/// given state. Returns the new state, including the final addition of the previous state.
+pub(crate) fn sha1_compress(state: [u32; 5], block: &[u8; 64]) -> [u32; 5] {
+    let mut w = [0_u32; 80];
+    for (word, chunk) in w.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_be_bytes(chunk.try_into().expect("chunks have 4 bytes"));
+    }
+    for t in 16..80 {
+        w[t] = (w[t - 3] ^ w[t - 8] ^ w[t - 14] ^ w[t - 16]).rotate_left(1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e] = state;
+    for (t, &word) in w.iter().enumerate() {
+        let (f, k) = match t {
+            0..20 => ((b & c) | (!b & d), 0x5a827999),
+            20..40 => (b ^ c ^ d, 0x6ed9eba1),
+            40..60 => ((b & c) | (b & d) | (c & d), 0x8f1bbcdc),
+            _ => (b ^ c ^ d, 0xca62c1d6),
+        };
+
+        let temp = a
+            .rotate_left(5)
+            .wrapping_add(f)
+            .wrapping_add(e)
+            .wrapping_add(k)
+            .wrapping_add(word);
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = temp;
+    }
+
+    [
+        state[0].wrapping_add(a),
+        state[1].wrapping_add(b),
+        state[2].wrapping_add(c),
+        state[3].wrapping_add(d),
+        state[4].wrapping_add(e),
+    ]
+}
@@ -1,18 +1,43 @@
 //! Internal functions that are used by tests - can be changed anytime
 
+use core::fmt::Debug;
+#[cfg(feature = "std")]
 use rayon::ThreadPoolBuilder;
-use std::fmt::Debug;
+#[cfg(feature = "std")]
 use std::sync::{LazyLock, OnceLock};
+#[cfg(feature = "std")]
 use sts_lib_derive::register_thread_pool;
 
 use crate::Error;
 
+#[cfg(not(feature = "std"))]
+mod no_std;
+#[cfg(not(feature = "std"))]
+pub(crate) use no_std::{CancelCheck, F64Ext};
+
+/// The parallel iterators used by the tests: the ones of [rayon] with the feature `std`, a
+/// sequential replacement with the same methods without it.
+pub(crate) mod par {
+    #[cfg(not(feature = "std"))]
+    pub(crate) use super::no_std::par::*;
+    #[cfg(feature = "std")]
+    pub(crate) use rayon::prelude::*;
+}
+
 /// The [complementary error function](https://en.wikipedia.org/wiki/Error_function)
+#[cfg(feature = "std")]
 pub(crate) use statrs::function::erf::erfc;
 
+#[cfg(not(feature = "std"))]
+pub(crate) use crate::math::erfc;
+
 /// igamc, the upper regularized incomplete gamma function.
+#[cfg(feature = "std")]
 pub(crate) use statrs::function::gamma::checked_gamma_ur as igamc;
 
+#[cfg(not(feature = "std"))]
+pub(crate) use crate::math::igamc;
+
 /// Checks the f64 value for NaN and Infinite, returns an error if this is the case.
 /// This function should be used as a guard.
 pub(crate) fn check_f64(value: f64) -> Result<(), Error> {
@@ -25,20 +50,94 @@ pub(crate) fn check_f64(value: f64) -> Result<(), Error> {
     }
 }
 
+/// Whether the feature `high-precision` is enabled: the statistics are calculated without the
+/// rounding errors that grow with the input length, see the README.
+pub(crate) const HIGH_PRECISION: bool = cfg!(feature = "high-precision");
+
+/// A sum of [f64] values. With the feature `high-precision`, the rounding error of each addition
+/// is kept and added at the end (Kahan-Babuška-Neumaier summation), so that the error of the sum
+/// does not grow with the count of values. Without the feature, this is the plain sum, with the
+/// same result as adding the values in the same order.
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct F64Sum {
+    sum: f64,
+    compensation: f64,
+}
+
+impl F64Sum {
+    /// Adds the value to the sum.
+    #[inline]
+    pub(crate) fn add(&mut self, value: f64) {
+        let sum = self.sum + value;
+        if HIGH_PRECISION {
+            // the low-order bits lost in the addition, taken from the smaller summand
+            self.compensation += if f64::abs(self.sum) >= f64::abs(value) {
+                (self.sum - sum) + value
+            } else {
+                (value - sum) + self.sum
+            };
+        }
+        self.sum = sum;
+    }
+
+    /// The value of the sum.
+    pub(crate) fn value(self) -> f64 {
+        self.sum + self.compensation
+    }
+}
+
+impl core::ops::Add for F64Sum {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        F64Sum::add(&mut self, rhs.sum);
+        self.compensation += rhs.compensation;
+        self
+    }
+}
+
+impl core::iter::Sum<f64> for F64Sum {
+    fn sum<I: Iterator<Item = f64>>(iter: I) -> Self {
+        iter.fold(Self::default(), |mut sum, value| {
+            sum.add(value);
+            sum
+        })
+    }
+}
+
+impl core::iter::Sum for F64Sum {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), core::ops::Add::add)
+    }
+}
+
 /// The number of threads to use in multithreading. Defaults to the number of physical CPUs, which
 /// is better for CPU-bound tasks. Note: use [crate::set_max_threads] to set this variable.
+#[cfg(feature = "std")]
 pub(crate) static RAYON_THREAD_COUNT: OnceLock<usize> = OnceLock::new();
 
+#[cfg(feature = "std")]
 register_thread_pool! {
     /// The threadpool itself, lazily initialized on first use.
     static THREAD_POOL = LazyLock::new(|| {
         let num_threads = *RAYON_THREAD_COUNT.get_or_init(num_cpus::get_physical);
 
-        ThreadPoolBuilder::new()
+        let pool = ThreadPoolBuilder::new()
             .num_threads(num_threads)
             .thread_name(|idx| format!("sts-{idx}"))
-            .build()
-            .expect("Could not build STS library thread pool. This should never happen!")
+            .build();
+
+        // WebAssembly targets without thread support cannot spawn threads: fall back to running
+        // the tests on the current thread alone, as rayon does for its global thread pool.
+        #[cfg(target_family = "wasm")]
+        let pool = pool.or_else(|_| {
+            ThreadPoolBuilder::new()
+                .num_threads(1)
+                .use_current_thread()
+                .build()
+        });
+
+        pool.expect("Could not build STS library thread pool. This should never happen!")
     });
 }
 
@@ -81,15 +180,6 @@ macro_rules! impl_bit_primitive {
 
 impl_bit_primitive!(u8, u32, usize);
 
-/// Returns the bit on the given idx from the sequence
-#[inline]
-pub(crate) fn get_bit_from_sequence<T: BitPrimitive>(seq: &[T], bit_idx: u32) -> bool {
-    let word_idx = bit_idx / T::BITS;
-    let bit_idx = bit_idx % T::BITS;
-
-    seq[word_idx as usize].get_bit(bit_idx)
-}
-
 /// Generate a macro for checked arithmetic that returns a good error message
 macro_rules! gen_checked_arithmetic {
     ($method: ident => $op: literal) => {
@@ -97,7 +187,7 @@ macro_rules! gen_checked_arithmetic {
             ($p1: expr, $p2: expr) => {
                 $p1.$method($p2)
                     .ok_or_else(|| 
-                        $crate::Error::Overflow(format!(
+                        $crate::Error::Overflow(::alloc::format!(
                             "{}, line {}: {} ({}) {} {} ({})", 
                             file!(), // filename of macro call
                             line!(), // line number of macro call
@@ -126,3 +216,16 @@ gen_checked_arithmetic! {
 
 #[allow(clippy::single_component_path_imports)]
 pub(super) use {checked_add, checked_add_unsigned, checked_mul, checked_sub_unsigned};
+
+/// Enters a [tracing](https://docs.rs/tracing) span with the given level, name and fields until the
+/// end of the current block. Does nothing if the feature `trace` is disabled.
+///
+/// Usage: `trace_span!(DEBUG, "name", field = value);`
+macro_rules! trace_span {
+    ($level: ident, $($args: tt)+) => {
+        #[cfg(feature = "trace")]
+        let _span = ::tracing::span!(::tracing::Level::$level, $($args)+).entered();
+    };
+}
+
+pub(crate) use trace_span;
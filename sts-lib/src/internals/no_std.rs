@@ -0,0 +1,212 @@
+//! Replacements for the parts of `std` and rayon that the tests available without the feature
+//! `std` use. They have the same names and arguments, so that the tests don't need to distinguish
+//! both cases.
+
+use crate::Error;
+
+/// The methods of [f64] that are only available with `std`, calculated with [libm]. Import this
+/// trait only without the feature `std`, with it the inherent methods are used.
+pub(crate) trait F64Ext {
+    fn abs(self) -> f64;
+    fn sqrt(self) -> f64;
+    fn ln(self) -> f64;
+    fn ln_1p(self) -> f64;
+    fn log2(self) -> f64;
+    fn powi(self, n: i32) -> f64;
+}
+
+impl F64Ext for f64 {
+    #[inline]
+    fn abs(self) -> f64 {
+        libm::fabs(self)
+    }
+
+    #[inline]
+    fn sqrt(self) -> f64 {
+        libm::sqrt(self)
+    }
+
+    #[inline]
+    fn ln(self) -> f64 {
+        libm::log(self)
+    }
+
+    #[inline]
+    fn ln_1p(self) -> f64 {
+        libm::log1p(self)
+    }
+
+    #[inline]
+    fn log2(self) -> f64 {
+        libm::log2(self)
+    }
+
+    #[inline]
+    fn powi(self, n: i32) -> f64 {
+        libm::pow(self, n as f64)
+    }
+}
+
+/// Without the test runner, tests cannot be cancelled: the check always passes.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct CancelCheck;
+
+impl CancelCheck {
+    /// The check of the current test.
+    pub(crate) fn current() -> Self {
+        Self
+    }
+
+    /// Always `Ok`, see [CancelCheck].
+    #[inline]
+    pub(crate) fn check(&self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Sequential replacements for the parallel iterators of rayon: the methods used by the tests,
+/// running on the current thread. The results are the same as with rayon, the identity values of
+/// [Iter::reduce], [Iter::try_fold] and [Iter::try_reduce] are used exactly once.
+pub(crate) mod par {
+    use core::iter;
+
+    /// A sequential iterator with the interface of a rayon parallel iterator.
+    #[derive(Clone, Debug)]
+    pub(crate) struct Iter<I>(I);
+
+    impl<I: Iterator> Iter<I> {
+        pub(crate) fn map<B, F: FnMut(I::Item) -> B>(self, f: F) -> Iter<iter::Map<I, F>> {
+            Iter(self.0.map(f))
+        }
+
+        pub(crate) fn zip<J: IntoIterator>(self, other: J) -> Iter<iter::Zip<I, J::IntoIter>> {
+            Iter(self.0.zip(other))
+        }
+
+        pub(crate) fn enumerate(self) -> Iter<iter::Enumerate<I>> {
+            Iter(self.0.enumerate())
+        }
+
+        pub(crate) fn rev(self) -> Iter<iter::Rev<I>>
+        where
+            I: DoubleEndedIterator,
+        {
+            Iter(self.0.rev())
+        }
+
+        pub(crate) fn flat_map<U: IntoIterator, F: FnMut(I::Item) -> U>(
+            self,
+            f: F,
+        ) -> Iter<iter::FlatMap<I, U, F>> {
+            Iter(self.0.flat_map(f))
+        }
+
+        pub(crate) fn flat_map_iter<U: IntoIterator, F: FnMut(I::Item) -> U>(
+            self,
+            f: F,
+        ) -> Iter<iter::FlatMap<I, U, F>> {
+            Iter(self.0.flat_map(f))
+        }
+
+        pub(crate) fn for_each<F: FnMut(I::Item)>(self, f: F) {
+            self.0.for_each(f)
+        }
+
+        pub(crate) fn try_for_each<E, F: FnMut(I::Item) -> Result<(), E>>(
+            mut self,
+            f: F,
+        ) -> Result<(), E> {
+            self.0.try_for_each(f)
+        }
+
+        pub(crate) fn sum<S: iter::Sum<I::Item>>(self) -> S {
+            self.0.sum()
+        }
+
+        pub(crate) fn collect<C: FromIterator<I::Item>>(self) -> C {
+            self.0.collect()
+        }
+
+        pub(crate) fn reduce<ID, OP>(self, identity: ID, op: OP) -> I::Item
+        where
+            ID: Fn() -> I::Item,
+            OP: FnMut(I::Item, I::Item) -> I::Item,
+        {
+            self.0.fold(identity(), op)
+        }
+
+        pub(crate) fn try_fold<T, E, ID, F>(
+            mut self,
+            identity: ID,
+            fold_op: F,
+        ) -> Iter<iter::Once<Result<T, E>>>
+        where
+            ID: Fn() -> T,
+            F: FnMut(T, I::Item) -> Result<T, E>,
+        {
+            Iter(iter::once(self.0.try_fold(identity(), fold_op)))
+        }
+
+        pub(crate) fn try_reduce<T, E, ID, OP>(mut self, identity: ID, mut op: OP) -> Result<T, E>
+        where
+            I: Iterator<Item = Result<T, E>>,
+            ID: Fn() -> T,
+            OP: FnMut(T, T) -> Result<T, E>,
+        {
+            self.0.try_fold(identity(), |acc, item| op(acc, item?))
+        }
+    }
+
+    impl<I: Iterator> IntoIterator for Iter<I> {
+        type Item = I::Item;
+        type IntoIter = I;
+
+        fn into_iter(self) -> I {
+            self.0
+        }
+    }
+
+    /// See [rayon::iter::IntoParallelIterator].
+    pub(crate) trait IntoParallelIterator: IntoIterator + Sized {
+        fn into_par_iter(self) -> Iter<Self::IntoIter> {
+            Iter(self.into_iter())
+        }
+    }
+
+    impl<T: IntoIterator> IntoParallelIterator for T {}
+
+    /// See [rayon::iter::IntoParallelRefIterator].
+    pub(crate) trait IntoParallelRefIterator<'a> {
+        type Iter: Iterator;
+
+        fn par_iter(&'a self) -> Iter<Self::Iter>;
+    }
+
+    impl<'a, T: ?Sized + 'a> IntoParallelRefIterator<'a> for T
+    where
+        &'a T: IntoIterator,
+    {
+        type Iter = <&'a T as IntoIterator>::IntoIter;
+
+        fn par_iter(&'a self) -> Iter<Self::Iter> {
+            Iter(self.into_iter())
+        }
+    }
+
+    /// See [rayon::slice::ParallelSlice].
+    pub(crate) trait ParallelSlice<T> {
+        fn par_chunks(&self, chunk_size: usize) -> Iter<core::slice::Chunks<'_, T>>;
+
+        fn par_chunks_exact(&self, chunk_size: usize) -> Iter<core::slice::ChunksExact<'_, T>>;
+    }
+
+    impl<T> ParallelSlice<T> for [T] {
+        fn par_chunks(&self, chunk_size: usize) -> Iter<core::slice::Chunks<'_, T>> {
+            Iter(self.chunks(chunk_size))
+        }
+
+        fn par_chunks_exact(&self, chunk_size: usize) -> Iter<core::slice::ChunksExact<'_, T>> {
+            Iter(self.chunks_exact(chunk_size))
+        }
+    }
+}
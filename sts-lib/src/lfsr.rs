@@ -0,0 +1,94 @@
+//! The linear complexity of bit sequences, see [linear_complexity] and [shortest_lfsr].
+//!
+//! The linear complexity of a sequence is the length of the shortest linear feedback shift
+//! register (LFSR) that generates the sequence. It is calculated with the Berlekamp-Massey
+//! algorithm, according to the Handbook of Applied Cryptography, p. 201, 6.30 - the same
+//! implementation as used by the [linear complexity test](crate::tests::linear_complexity).
+//!
+//! The Berlekamp-Massey algorithm needs *O(n²)* time for a sequence of *n* bits, so it is meant
+//! for sequences of up to a few 100000 bits.
+
+use crate::bitvec::word::{self, WORD_BITS};
+use crate::bitvec::BitVec;
+use crate::tests::linear_complexity::berlekamp_massey_with_polynomial;
+use std::ops::Range;
+
+/// The shortest LFSR that generates a sequence, see [shortest_lfsr].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lfsr {
+    connection_polynomial: Box<[bool]>,
+}
+
+impl Lfsr {
+    /// The length *L* of the LFSR, i.e. the linear complexity of the sequence.
+    pub fn length(&self) -> usize {
+        self.connection_polynomial.len() - 1
+    }
+
+    /// The coefficients *c_0* to *c_L* of the connection polynomial
+    /// `C(D) = 1 + c_1 * D + ... + c_L * D^L`, with *c_0* always being 1.
+    ///
+    /// The LFSR generates the sequence *s* with `s_j = c_1 * s_(j-1) + ... + c_L * s_(j-L)`
+    /// (modulo 2) for all *j >= L*, the first *L* bits are the initial state.
+    pub fn connection_polynomial(&self) -> &[bool] {
+        &self.connection_polynomial
+    }
+}
+
+/// Calculates the linear complexity of the given range of bits of the sequence.
+///
+/// ## Panics
+///
+/// If the start of the range is greater than its end, the end is greater than
+/// [BitVec::len_bit], or the range is longer than `u32::MAX - 64` bits.
+///
+/// ## Example
+///
+/// ```
+/// use sts_lib::bitvec::BitVec;
+/// use sts_lib::lfsr::linear_complexity;
+///
+/// let data = BitVec::from_ascii_str("1101011110001").unwrap();
+/// assert_eq!(linear_complexity(&data, 0..data.len_bit()), 4);
+/// ```
+pub fn linear_complexity(data: &BitVec, range: Range<usize>) -> usize {
+    shortest_lfsr(data, range).length()
+}
+
+/// Calculates the shortest LFSR that generates the given range of bits of the sequence, with its
+/// connection polynomial. The length of the LFSR is the linear complexity of the sequence.
+///
+/// ## Panics
+///
+/// If the start of the range is greater than its end, the end is greater than
+/// [BitVec::len_bit], or the range is longer than `u32::MAX - 64` bits.
+pub fn shortest_lfsr(data: &BitVec, range: Range<usize>) -> Lfsr {
+    assert!(
+        range.start <= range.end && range.end <= data.len_bit(),
+        "range {range:?} is out of bounds for a sequence with {} bits",
+        data.len_bit()
+    );
+    assert!(
+        range.len() <= u32::MAX as usize - WORD_BITS,
+        "range {range:?} is too long"
+    );
+
+    if range.is_empty() {
+        // the empty sequence is generated by the LFSR of length 0
+        return Lfsr {
+            connection_polynomial: Box::new([true]),
+        };
+    }
+
+    let words = &data.words[word::word_index(range.start)..=word::word_index(range.end - 1)];
+    let (length, polynomial) =
+        berlekamp_massey_with_polynomial(words, range.len(), word::bit_in_word(range.start));
+
+    let connection_polynomial = (0..=length)
+        .map(|i| word::get_bit_at(&polynomial, i))
+        .collect();
+
+    Lfsr {
+        connection_polynomial,
+    }
+}
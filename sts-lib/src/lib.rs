@@ -2,19 +2,54 @@
 // Explicitly only support sane CPU architectures - target_pointer_width = 16 would be unwise for this
 // kind of application.
 #![cfg(any(target_pointer_width = "64", target_pointer_width = "32"))]
+#![cfg_attr(not(feature = "std"), no_std)]
+// Without std, only a subset of the tests is available, which does not use all internal helpers.
+#![cfg_attr(not(feature = "std"), allow(dead_code, unused_imports, unused_macros))]
 
+extern crate alloc;
+// The unit tests run on a host with std, also without the feature `std`.
+#[cfg(all(test, not(feature = "std")))]
+#[macro_use]
+extern crate std;
+
+#[cfg(feature = "std")]
 use crate::internals::RAYON_THREAD_COUNT;
+use crate::statistics::TestStatistics;
 use crate::tests::approximate_entropy::ApproximateEntropyTestArg;
+#[cfg(feature = "std")]
+use crate::tests::autocorrelation::AutocorrelationTestArg;
+#[cfg(feature = "std")]
+use crate::tests::binary_matrix_rank::BinaryMatrixRankTestArg;
+use crate::tests::cumulative_sums::CusumDirection;
 use crate::tests::frequency_block::FrequencyBlockTestArg;
+#[cfg(feature = "std")]
 use crate::tests::linear_complexity::LinearComplexityTestArg;
-use crate::tests::serial::SerialTestArg;
+#[cfg(feature = "std")]
+use crate::tests::longest_run_of_ones::LongestRunOfOnesTestArg;
+#[cfg(feature = "std")]
+use crate::tests::random_excursions::RandomExcursionsTestArg;
+#[cfg(feature = "std")]
+use crate::tests::random_excursions_variant::RandomExcursionsVariantTestArg;
+use crate::tests::serial::{SerialKind, SerialTestArg};
+#[cfg(feature = "std")]
+use crate::tests::spectral_dft::SpectralDftTestArg;
+#[cfg(feature = "std")]
 use crate::tests::template_matching::non_overlapping::NonOverlappingTemplateTestArgs;
+#[cfg(feature = "std")]
 use crate::tests::template_matching::overlapping::OverlappingTemplateTestArgs;
-use std::num::NonZero;
-use strum::{Display, EnumIter};
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use core::num::NonZero;
+use core::time::Duration;
+use strum::EnumIter;
 use thiserror::Error;
 
 // Trait must be public for enum iter to work.
+#[cfg(feature = "std")]
+pub use self_test::self_test;
 pub use strum::EnumCount;
 pub use strum::IntoEnumIterator;
 
@@ -24,7 +59,26 @@ pub(crate) mod internals;
 mod unit_tests;
 
 // public exports
+#[cfg(feature = "std")]
+pub mod analysis;
 pub mod bitvec;
+#[cfg(feature = "std")]
+pub mod diagnostics;
+#[cfg(feature = "std")]
+pub mod entropy_estimation;
+#[cfg(feature = "std")]
+pub mod fips140;
+#[cfg(feature = "generators")]
+pub mod generators;
+#[cfg(feature = "std")]
+pub mod lfsr;
+pub mod math;
+#[cfg(feature = "std")]
+pub mod profile;
+#[cfg(feature = "std")]
+pub mod self_test;
+pub mod statistics;
+#[cfg(feature = "std")]
 pub mod test_runner;
 pub mod tests;
 
@@ -34,7 +88,7 @@ pub mod tests;
 pub const DEFAULT_THRESHOLD: f64 = 0.01;
 
 /// List of all tests, used e.g. for automatic running.
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, EnumIter, Display, EnumCount)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, EnumIter, strum::Display, EnumCount)]
 #[repr(u8)]
 pub enum Test {
     /// See [tests::frequency].
@@ -67,6 +121,95 @@ pub enum Test {
     RandomExcursions = 13,
     /// See [tests::random_excursions_variant]
     RandomExcursionsVariant = 14,
+    /// See [tests::lempel_ziv]. Not part of the current revision of SP 800-22.
+    LempelZiv = 15,
+    /// See [tests::autocorrelation]. Not part of SP 800-22, but of BSI AIS 31.
+    Autocorrelation = 16,
+}
+
+impl Test {
+    /// The tests that are run if no tests are selected, e.g. by `test_runner::run_all_tests`: all
+    /// tests except for [Test::LempelZiv], which was removed from SP 800-22 and should not be used
+    /// to assess the randomness of a sequence. It is only run if it is selected explicitly.
+    ///
+    /// ```
+    /// use sts_lib::Test;
+    ///
+    /// assert!(Test::default_set().contains(&Test::Autocorrelation));
+    /// assert!(!Test::default_set().contains(&Test::LempelZiv));
+    /// ```
+    pub fn default_set() -> &'static [Test] {
+        &[
+            Test::Frequency,
+            Test::FrequencyWithinABlock,
+            Test::Runs,
+            Test::LongestRunOfOnes,
+            Test::BinaryMatrixRank,
+            Test::SpectralDft,
+            Test::NonOverlappingTemplateMatching,
+            Test::OverlappingTemplateMatching,
+            Test::MaurersUniversalStatistical,
+            Test::LinearComplexity,
+            Test::Serial,
+            Test::ApproximateEntropy,
+            Test::CumulativeSums,
+            Test::RandomExcursions,
+            Test::RandomExcursionsVariant,
+            Test::Autocorrelation,
+        ]
+    }
+
+    /// The tests that are cheap to run: their runtime grows linearly with the input length, with a
+    /// small constant factor. Together with [Test::slow_set], these are all tests.
+    ///
+    /// ```
+    /// use sts_lib::Test;
+    ///
+    /// assert!(Test::fast_set().contains(&Test::Frequency));
+    /// assert!(!Test::fast_set().contains(&Test::LinearComplexity));
+    /// ```
+    pub fn fast_set() -> &'static [Test] {
+        &[
+            Test::Frequency,
+            Test::FrequencyWithinABlock,
+            Test::Runs,
+            Test::LongestRunOfOnes,
+            Test::BinaryMatrixRank,
+            Test::OverlappingTemplateMatching,
+            Test::MaurersUniversalStatistical,
+            Test::Serial,
+            Test::ApproximateEntropy,
+            Test::CumulativeSums,
+            Test::RandomExcursions,
+            Test::RandomExcursionsVariant,
+            Test::Autocorrelation,
+        ]
+    }
+
+    /// The tests that take the most time: the spectral DFT test (the DFT of the whole input), the
+    /// non-overlapping template matching test (one pass per template), the linear complexity test
+    /// (the Berlekamp-Massey algorithm for each block) and the Lempel-Ziv compression test.
+    pub fn slow_set() -> &'static [Test] {
+        &[
+            Test::SpectralDft,
+            Test::NonOverlappingTemplateMatching,
+            Test::LinearComplexity,
+            Test::LempelZiv,
+        ]
+    }
+
+    /// The tests that require at least 10^6 bits, either as their
+    /// [minimum](get_min_length_for_test) or as their
+    /// [recommended input length](get_recommended_length_for_test).
+    pub fn requires_1e6_bits() -> &'static [Test] {
+        &[
+            Test::OverlappingTemplateMatching,
+            Test::LinearComplexity,
+            Test::RandomExcursions,
+            Test::RandomExcursionsVariant,
+            Test::LempelZiv,
+        ]
+    }
 }
 
 /// All test arguments for use in a [TestRunner](test_runner::TestRunner),
@@ -82,21 +225,116 @@ pub enum Test {
 ///     ..Default::default()
 /// };
 /// ```
+#[cfg(feature = "std")]
 #[derive(Copy, Clone, Debug, Default)]
 pub struct TestArgs {
     pub frequency_block: FrequencyBlockTestArg,
+    pub longest_run_of_ones: LongestRunOfOnesTestArg,
+    pub binary_matrix_rank: BinaryMatrixRankTestArg,
+    pub spectral_dft: SpectralDftTestArg,
     pub non_overlapping_template: NonOverlappingTemplateTestArgs<'static>,
     pub overlapping_template: OverlappingTemplateTestArgs,
     pub linear_complexity: LinearComplexityTestArg,
     pub serial: SerialTestArg,
     pub approximate_entropy: ApproximateEntropyTestArg,
+    pub random_excursions: RandomExcursionsTestArg,
+    pub random_excursions_variant: RandomExcursionsVariantTestArg,
+    pub autocorrelation: AutocorrelationTestArg,
+    /// The maximum run time of each test run by the [test runner](test_runner), so that a
+    /// pathological input or argument cannot hang an automated pipeline. A test that exceeds the
+    /// timeout aborts with [Error::TimedOut], the other tests are still run. Default: no timeout.
+    ///
+    /// Like cancellation, the timeout is cooperative: only the long-running tests check it
+    /// periodically, fast tests always run to completion. The timeout is not applied when the test
+    /// functions are called directly.
+    pub timeout: Option<Duration>,
 }
 
 /// The common test result type, as used by all tests.
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct TestResult {
     p_value: f64,
-    comment: Option<&'static str>,
+    comment: Option<Cow<'static, str>>,
+    label: Option<ResultLabel>,
+    statistics: Option<TestStatistics>,
+    status: ResultStatus,
+}
+
+/// Identifies one result of a test that returns multiple results, so that consumers don't need to
+/// know the order of the results. See [TestResult::label].
+///
+/// The [Display] implementation gives a short name, e.g. "forward", "delta1" or "x = +4".
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ResultLabel {
+    /// The mode of the [Cumulative Sums Test](tests::cumulative_sums).
+    CumulativeSums(CusumDirection),
+    /// The test statistic of the [Serial Test](tests::serial).
+    Serial(SerialKind),
+    /// The state *x* of the [Random Excursions Test](tests::random_excursions) or the
+    /// [Random Excursions Variant Test](tests::random_excursions_variant).
+    ExcursionState(i8),
+    /// One template of the
+    /// [Non-overlapping Template Matching Test](tests::template_matching::non_overlapping).
+    Template {
+        /// The index of the template in the
+        /// [templates of the test argument](tests::template_matching::TemplateArg::templates).
+        index: usize,
+        /// The template, with the bits stored in the highest bits, as in
+        /// [TemplateArg](tests::template_matching::TemplateArg).
+        template: usize,
+        /// The length of the template in bits.
+        template_len: usize,
+    },
+}
+
+impl Display for ResultLabel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            ResultLabel::CumulativeSums(CusumDirection::Forward) => write!(f, "forward"),
+            ResultLabel::CumulativeSums(CusumDirection::Backward) => write!(f, "backward"),
+            ResultLabel::Serial(SerialKind::Delta1) => write!(f, "delta1"),
+            ResultLabel::Serial(SerialKind::Delta2) => write!(f, "delta2"),
+            ResultLabel::ExcursionState(state) => write!(f, "x = {state:+}"),
+            ResultLabel::Template {
+                template,
+                template_len,
+                ..
+            } => {
+                let bits = template >> (usize::BITS as usize - template_len);
+                write!(f, "template {bits:0template_len$b}")
+            }
+        }
+    }
+}
+
+/// The status of a [TestResult]: if the test could actually be applied to the input.
+///
+/// A test that is not applicable has a [p_value](TestResult::p_value) of 0.0 and does not
+/// [pass](TestResult::passed), but this is no evidence against the randomness of the input.
+/// Use [TestResult::status] to distinguish the two cases.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ResultStatus {
+    /// The test was applied, the P-value is meaningful.
+    Applicable,
+    /// The test could not be applied to the input, the P-value is meaningless.
+    NotApplicable(NotApplicableReason),
+}
+
+/// The reason why a test was [not applicable](ResultStatus::NotApplicable).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NotApplicableReason {
+    /// The random walk of the input has too few cycles, see
+    /// [tests::random_excursions] and [tests::random_excursions_variant].
+    TooFewCycles {
+        /// The count of cycles in the input.
+        cycles: usize,
+        /// The minimum count of cycles needed to apply the test.
+        required: usize,
+    },
+    /// No values were given to analyse, see [analysis::p_value_uniformity].
+    EmptySample,
 }
 
 // private methods
@@ -106,20 +344,77 @@ impl TestResult {
         Self {
             p_value,
             comment: None,
+            label: None,
+            statistics: None,
+            status: ResultStatus::Applicable,
         }
     }
 
     /// A new test result with a comment.
-    fn new_with_comment(p_value: f64, comment: &'static str) -> Self {
+    fn new_with_comment(p_value: f64, comment: impl Into<Cow<'static, str>>) -> Self {
         Self {
             p_value,
-            comment: Some(comment),
+            comment: Some(comment.into()),
+            label: None,
+            statistics: None,
+            status: ResultStatus::Applicable,
+        }
+    }
+
+    /// A new test result for a test that was not applicable, with the P-value set to 0.0.
+    fn not_applicable(comment: impl Into<Cow<'static, str>>, reason: NotApplicableReason) -> Self {
+        Self {
+            p_value: 0.0,
+            comment: Some(comment.into()),
+            label: None,
+            statistics: None,
+            status: ResultStatus::NotApplicable(reason),
         }
     }
+
+    /// Adds the given statistics to the test result.
+    fn with_statistics(mut self, statistics: TestStatistics) -> Self {
+        self.statistics = Some(statistics);
+        self
+    }
+
+    /// Adds the given label to the test result.
+    fn with_label(mut self, label: ResultLabel) -> Self {
+        self.label = Some(label);
+        self
+    }
 }
 
 // public methods
 impl TestResult {
+    /// Creates a result with the given P-value and an optional comment, for a
+    /// [custom test](test_runner::CustomTest). The result is always applicable.
+    pub fn from_p_value(p_value: f64, comment: Option<&'static str>) -> Self {
+        Self {
+            p_value,
+            comment: comment.map(Cow::Borrowed),
+            label: None,
+            statistics: None,
+            status: ResultStatus::Applicable,
+        }
+    }
+
+    /// Replaces the comment of the result, e.g. to report details of a
+    /// [custom test](test_runner::CustomTest) that are only known at run time.
+    ///
+    /// ```
+    /// use sts_lib::TestResult;
+    ///
+    /// let discarded = 123;
+    /// let result = TestResult::from_p_value(0.5, None)
+    ///     .with_comment(format!("discarded {discarded} bits"));
+    /// assert_eq!(result.comment(), Some("discarded 123 bits"));
+    /// ```
+    pub fn with_comment(mut self, comment: impl Into<Cow<'static, str>>) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+
     /// The p_value (result of the test)
     pub fn p_value(&self) -> f64 {
         self.p_value
@@ -132,9 +427,32 @@ impl TestResult {
         self.p_value >= threshold
     }
 
-    /// Some tests leave a comment about the outcome.
-    pub fn comment(&self) -> Option<&'static str> {
-        self.comment
+    /// Some tests leave a comment about the outcome, e.g. the state of the random excursions
+    /// tests or the parameters chosen for the input.
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    /// Identifies the result for tests that return multiple results, see [ResultLabel]. Is `None`
+    /// for tests that return a single result.
+    pub fn label(&self) -> Option<ResultLabel> {
+        self.label
+    }
+
+    /// The intermediate values the test calculated to get to the [p_value](Self::p_value).
+    /// Is `None` if the test did not get to calculate them, e.g. because the input was too short.
+    pub fn statistics(&self) -> Option<TestStatistics> {
+        self.statistics
+    }
+
+    /// If the test could be applied to the input, see [ResultStatus].
+    pub fn status(&self) -> ResultStatus {
+        self.status
+    }
+
+    /// Shorthand to check if the [status](Self::status) is [ResultStatus::Applicable].
+    pub fn is_applicable(&self) -> bool {
+        self.status == ResultStatus::Applicable
     }
 }
 
@@ -148,10 +466,90 @@ pub enum Error {
     NaN,
     #[error("Result is infinite.")]
     Infinite,
+    /// Only with the feature `std`: without it, the errors of the gamma function are reported as
+    /// [Error::InvalidParameter].
+    #[cfg(feature = "std")]
     #[error(transparent)]
     GammaFunctionFailed(#[from] statrs::function::gamma::GammaFuncError),
     #[error("Invalid Parameter: {0}")]
     InvalidParameter(String),
+    /// The test was cancelled with a [CancellationToken](test_runner::CancellationToken).
+    #[error("The test was cancelled.")]
+    Cancelled,
+    /// The test exceeded the given [timeout](TestArgs::timeout).
+    #[error("The test timed out after {0:?}.")]
+    TimedOut(Duration),
+}
+
+/// The error type for the creation of the test arguments, e.g.
+/// [RandomExcursionsTestArg::try_new](tests::random_excursions::RandomExcursionsTestArg::try_new).
+/// Each variant contains the invalid value and the allowed values, so that the message can be
+/// shown to the user directly.
+///
+#[cfg_attr(feature = "std", doc = "```")]
+#[cfg_attr(not(feature = "std"), doc = "```ignore")]
+/// use sts_lib::tests::random_excursions::RandomExcursionsTestArg;
+///
+/// let error = RandomExcursionsTestArg::try_new(0).unwrap_err();
+/// assert_eq!(error.to_string(), "The maximum state must be between 1 and 32, is: 0");
+/// ```
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ArgError {
+    /// The value is not in the inclusive range `min..=max`.
+    #[error("The {name} must be between {min} and {max}, is: {value}")]
+    OutOfRange {
+        name: &'static str,
+        value: usize,
+        min: usize,
+        max: usize,
+    },
+    /// The value is smaller than `min`, there is no upper bound.
+    #[error("The {name} must be at least {min}, is: {value}")]
+    TooSmall {
+        name: &'static str,
+        value: usize,
+        min: usize,
+    },
+    /// The value is not one of the allowed values.
+    #[error("The {name} must be one of {}, is: {value}", join_values(allowed))]
+    NotAllowed {
+        name: &'static str,
+        value: usize,
+        allowed: &'static [usize],
+    },
+}
+
+impl ArgError {
+    /// Checks that `value` is in `min..=max`.
+    pub(crate) fn check_range(
+        name: &'static str,
+        value: usize,
+        min: usize,
+        max: usize,
+    ) -> Result<(), Self> {
+        if (min..=max).contains(&value) {
+            Ok(())
+        } else {
+            Err(Self::OutOfRange {
+                name,
+                value,
+                min,
+                max,
+            })
+        }
+    }
+}
+
+/// Formats the allowed values of [ArgError::NotAllowed], e.g. "8, 128 or 10000".
+fn join_values(values: &[usize]) -> String {
+    match values {
+        [] => String::new(),
+        [value] => value.to_string(),
+        [init @ .., last] => {
+            let init = init.iter().map(usize::to_string).collect::<Vec<_>>();
+            format!("{} or {last}", init.join(", "))
+        }
+    }
 }
 
 /// Sets the maximum of threads to be used by the tests. These method can only be called ONCE and only
@@ -159,6 +557,11 @@ pub enum Error {
 ///
 /// If this is called multiple times or after the thread pool was already used (i.e. a test was run),
 /// an error will be returned.
+///
+/// This limit applies to the global thread pool, that is used by default. To use different thread
+/// limits in one process, run the tests with a [StsContext](test_runner::StsContext), which owns
+/// its own thread pool.
+#[cfg(feature = "std")]
 pub fn set_max_threads(max_threads: NonZero<usize>) -> Result<(), MaxThreadsSetError> {
     RAYON_THREAD_COUNT
         .set(max_threads.get())
@@ -166,15 +569,17 @@ pub fn set_max_threads(max_threads: NonZero<usize>) -> Result<(), MaxThreadsSetE
 }
 
 /// Error type for [set_max_threads]
+#[cfg(feature = "std")]
 #[derive(Debug, Error)]
 #[error("Could not set the maximum count of threads. Reason: multiple calls to fn / threadpool already used.")]
 pub struct MaxThreadsSetError;
 
 /// Returns the minimum input length, in bits, for the specified test.
+#[cfg(feature = "std")]
 pub fn get_min_length_for_test(test: Test) -> NonZero<usize> {
     use crate::tests;
 
-    const MIN_LENGTHS: [NonZero<usize>; 15] = [
+    const MIN_LENGTHS: [NonZero<usize>; 17] = [
         tests::frequency::MIN_INPUT_LENGTH,
         tests::frequency_block::MIN_INPUT_LENGTH,
         tests::runs::MIN_INPUT_LENGTH,
@@ -190,8 +595,33 @@ pub fn get_min_length_for_test(test: Test) -> NonZero<usize> {
         tests::cumulative_sums::MIN_INPUT_LENGTH,
         tests::random_excursions::MIN_INPUT_LENGTH,
         tests::random_excursions_variant::MIN_INPUT_LENGTH,
+        tests::lempel_ziv::MIN_INPUT_LENGTH,
+        tests::autocorrelation::MIN_INPUT_LENGTH,
     ];
 
     // use the assigned test primitive value as an index
     MIN_LENGTHS[(test as u8) as usize]
 }
+
+/// Returns the input length, in bits, recommended by NIST SP 800-22r1a for the specified test
+/// (see "Input Size Recommendation" in the description of each test). Inputs that are at least
+/// [as long as the minimum](get_min_length_for_test), but shorter than the recommended length,
+/// can be tested, but the result is less reliable. See
+/// [length_warnings](test_runner::length_warnings).
+///
+/// For most tests, the recommended length is the minimum length. It is only longer for Maurer's
+/// universal statistical test and the overlapping template matching test.
+#[cfg(feature = "std")]
+pub fn get_recommended_length_for_test(test: Test) -> NonZero<usize> {
+    use crate::tests;
+
+    match test {
+        Test::MaurersUniversalStatistical => {
+            tests::maurers_universal_statistical::RECOMMENDED_INPUT_LENGTH
+        }
+        Test::OverlappingTemplateMatching => {
+            tests::template_matching::overlapping::RECOMMENDED_INPUT_LENGTH
+        }
+        test => get_min_length_for_test(test),
+    }
+}
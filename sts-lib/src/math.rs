@@ -0,0 +1,83 @@
+//! The special functions used by the tests to calculate the P-values, for custom tests that
+//! should give results consistent with this library.
+//!
+//! These are the same implementations as used by the tests (from the crate
+//! [statrs](https://docs.rs/statrs)), not re-implementations. Without the feature `std`, [igamc] is
+//! the algorithm of statrs with the elementary functions of [libm], and [erfc] is the one of
+//! [libm]:
+//!
+//! - [igamc], the upper regularized incomplete gamma function, used for the P-values of all tests
+//!   based on a chi-square statistic.
+//! - [erfc], the complementary error function, used for the P-values of all tests based on a
+//!   normally distributed statistic.
+//! - [normal_cdf], the cumulative distribution function of the standard normal distribution, used
+//!   by the cumulative sums test.
+//!
+//! ## Precision
+//!
+//! Compared to the exact value, the relative error is below `1e-12` for [igamc] (below `1e-10` for
+//! `a` above 1000, e.g. for the serial test with block length 16) and below `1e-9` for [erfc] and
+//! [normal_cdf], which is checked against reference values calculated with arbitrary precision.
+//! This is much more than necessary to compare P-values against a threshold like `0.01`.
+
+use crate::Error;
+use core::f64::consts::SQRT_2;
+
+#[cfg(any(test, not(feature = "std")))]
+pub(crate) mod gamma;
+
+/// The [complementary error function](https://en.wikipedia.org/wiki/Error_function)
+/// `erfc(x) = 1 - erf(x)`.
+///
+/// ```
+/// use sts_lib::math::erfc;
+///
+/// assert_eq!(erfc(0.0), 1.0);
+/// assert!((erfc(1.0) - 0.157299207050285).abs() < 1e-9);
+/// ```
+pub fn erfc(x: f64) -> f64 {
+    #[cfg(feature = "std")]
+    let erfc = statrs::function::erf::erfc;
+    #[cfg(not(feature = "std"))]
+    let erfc = libm::erfc;
+
+    erfc(x)
+}
+
+/// The upper regularized incomplete gamma function `Q(a, x) = Γ(a, x) / Γ(a)`, called *igamc* in
+/// NIST SP 800-22r1a. For a chi-square statistic *χ²* with *k* degrees of freedom, the P-value is
+/// `igamc(k / 2, χ² / 2)`.
+///
+/// If `a` or `x` is not positive or one of the arguments is NaN, [Error::GammaFunctionFailed] is
+/// returned ([Error::InvalidParameter] without the feature `std`).
+///
+/// ```
+/// use sts_lib::math::igamc;
+///
+/// // chi-square statistic 1.0 with 1 degree of freedom
+/// let p_value = igamc(0.5, 0.5).unwrap();
+/// assert!((p_value - 0.317310507862914).abs() < 1e-12);
+///
+/// assert!(igamc(0.0, 1.0).is_err());
+/// ```
+pub fn igamc(a: f64, x: f64) -> Result<f64, Error> {
+    #[cfg(feature = "std")]
+    let result = Ok(statrs::function::gamma::checked_gamma_ur(a, x)?);
+    #[cfg(not(feature = "std"))]
+    let result = gamma::gamma_ur(a, x);
+
+    result
+}
+
+/// The cumulative distribution function of the standard normal distribution
+/// `Φ(x) = erfc(-x / sqrt(2)) / 2`.
+///
+/// ```
+/// use sts_lib::math::normal_cdf;
+///
+/// assert_eq!(normal_cdf(0.0), 0.5);
+/// assert!((normal_cdf(1.96) - 0.975002104851780).abs() < 1e-9);
+/// ```
+pub fn normal_cdf(x: f64) -> f64 {
+    0.5 * erfc(-x / SQRT_2)
+}
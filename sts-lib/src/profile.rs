@@ -0,0 +1,158 @@
+//! Profiles: recommended test selections and test arguments for common use cases, see [Profile].
+
+use crate::tests::approximate_entropy::ApproximateEntropyTestArg;
+use crate::tests::frequency_block::FrequencyBlockTestArg;
+use crate::tests::linear_complexity::LinearComplexityTestArg;
+use crate::tests::serial::SerialTestArg;
+use crate::{Test, TestArgs};
+use std::num::NonZero;
+use strum::{Display, EnumIter, EnumString, IntoStaticStr};
+
+/// A profile bundles a selection of tests with the test arguments to use for them, so that the
+/// exact parameters of a common use case can be selected by name.
+///
+/// The name of each profile (as used by [Display] and [FromStr](std::str::FromStr)) is given in
+/// the documentation of the variant.
+///
+/// ```
+/// use sts_lib::profile::Profile;
+///
+/// let profile: Profile = "nist-default".parse().unwrap();
+/// assert_eq!(profile, Profile::NistDefault);
+/// assert_eq!(profile.tests().len(), 15);
+/// ```
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, EnumIter, EnumString, Display, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Profile {
+    /// Name: `nist-default`. All tests of the current revision of SP 800-22 (i.e. without
+    /// [Test::LempelZiv] and [Test::Autocorrelation]), with the parameters of SP 800-22 and its reference implementation, see
+    /// [TestArgs::nist_sp800_22_default].
+    NistDefault,
+    /// Name: `bsi-ais31`. The tests of SP 800-22 that correspond to the statistical tests of
+    /// BSI AIS 31, and the autocorrelation test of AIS 31, see [TestArgs::bsi_ais31].
+    BsiAis31,
+    /// Name: `fast-screening`. Only the fast tests with low input length requirements, e.g. for a
+    /// first check of a generator, see [TestArgs::fast_screening].
+    FastScreening,
+}
+
+impl Profile {
+    /// The name of the profile, e.g. `nist-default`.
+    pub fn name(&self) -> &'static str {
+        self.into()
+    }
+
+    /// The tests that are run in this profile.
+    pub fn tests(&self) -> &'static [Test] {
+        match self {
+            Profile::NistDefault => &[
+                Test::Frequency,
+                Test::FrequencyWithinABlock,
+                Test::Runs,
+                Test::LongestRunOfOnes,
+                Test::BinaryMatrixRank,
+                Test::SpectralDft,
+                Test::NonOverlappingTemplateMatching,
+                Test::OverlappingTemplateMatching,
+                Test::MaurersUniversalStatistical,
+                Test::LinearComplexity,
+                Test::Serial,
+                Test::ApproximateEntropy,
+                Test::CumulativeSums,
+                Test::RandomExcursions,
+                Test::RandomExcursionsVariant,
+            ],
+            Profile::BsiAis31 => &[
+                Test::Frequency,
+                Test::Serial,
+                Test::Runs,
+                Test::LongestRunOfOnes,
+                Test::MaurersUniversalStatistical,
+                Test::Autocorrelation,
+            ],
+            Profile::FastScreening => &[
+                Test::Frequency,
+                Test::FrequencyWithinABlock,
+                Test::Runs,
+                Test::LongestRunOfOnes,
+                Test::Serial,
+                Test::ApproximateEntropy,
+                Test::CumulativeSums,
+            ],
+        }
+    }
+
+    /// The test arguments used in this profile.
+    pub fn test_args(&self) -> TestArgs {
+        match self {
+            Profile::NistDefault => TestArgs::nist_sp800_22_default(),
+            Profile::BsiAis31 => TestArgs::bsi_ais31(),
+            Profile::FastScreening => TestArgs::fast_screening(),
+        }
+    }
+}
+
+impl TestArgs {
+    /// The parameters recommended by SP 800-22 and used by its reference implementation:
+    ///
+    /// * Frequency test within a block: block length 128.
+    /// * Longest run of ones: the configuration for the input length, as specified in section
+    ///   2.4.2.
+    /// * Spectral DFT: the threshold of the current revision, see
+    ///   [PeakThreshold::KimUmeno](crate::tests::spectral_dft::PeakThreshold::KimUmeno).
+    /// * Non-overlapping and overlapping template matching: the defaults, template length 9.
+    /// * Linear complexity: block length 500.
+    /// * Serial: block length 16.
+    /// * Approximate entropy: block length 10.
+    ///
+    /// In contrast to [TestArgs::default], no other block lengths are chosen automatically, so that
+    /// apart from the configuration fixed by SP 800-22, the parameters do not depend on the input
+    /// length.
+    pub fn nist_sp800_22_default() -> Self {
+        Self {
+            frequency_block: FrequencyBlockTestArg::Manual(
+                const {
+                    match NonZero::new(128) {
+                        Some(v) => v,
+                        None => panic!("Literal should be non-zero!"),
+                    }
+                },
+            ),
+            linear_complexity: LinearComplexityTestArg::ManualBlockLength(
+                const {
+                    match NonZero::new(500) {
+                        Some(v) => v,
+                        None => panic!("Literal should be non-zero!"),
+                    }
+                },
+            ),
+            ..Default::default()
+        }
+    }
+
+    /// The parameters for the tests of SP 800-22 that correspond to the statistical tests of
+    /// BSI AIS 31 (procedure A): the serial test uses a block length of 4, like the poker test
+    /// (T2), all other arguments are the defaults.
+    ///
+    /// Except for the [autocorrelation test](crate::tests::autocorrelation) (T5), the tests of
+    /// AIS 31 are not implemented by this library, the profile only approximates them with the
+    /// related tests of SP 800-22 - it does not replace an AIS 31 evaluation.
+    pub fn bsi_ais31() -> Self {
+        Self {
+            serial: SerialTestArg::try_new(4).expect("4 is a valid block length"),
+            ..Default::default()
+        }
+    }
+
+    /// Parameters for a quick screening of short inputs: the serial test uses a block length of 8
+    /// and the approximate entropy test a block length of 6, which allows inputs from 2^12 bits.
+    /// All other arguments are the defaults.
+    pub fn fast_screening() -> Self {
+        Self {
+            serial: SerialTestArg::try_new(8).expect("8 is a valid block length"),
+            approximate_entropy: ApproximateEntropyTestArg::try_new(6)
+                .expect("6 is a valid block length"),
+            ..Default::default()
+        }
+    }
+}
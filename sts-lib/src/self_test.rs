@@ -0,0 +1,186 @@
+//! Runtime self-tests, see [self_test].
+//!
+//! The self-tests verify the library on the machine it actually runs on, independent of any CI:
+//! the storage of the bits (which must not depend on the byte order of the target, e.g. on
+//! big-endian s390x) and a selection of tests against the known answers of the examples in
+//! SP 800-22. An application can run them once at startup, they take well under a millisecond.
+
+use crate::bitvec::{BitOrder, BitVec};
+use crate::tests::cumulative_sums::cumulative_sums_test;
+use crate::tests::frequency::frequency_test;
+use crate::tests::frequency_block::{frequency_block_test, FrequencyBlockTestArg};
+use crate::tests::longest_run_of_ones::{longest_run_of_ones_test, LongestRunOfOnesTestArg};
+use crate::tests::runs::runs_test;
+use crate::tests::spectral_dft::{spectral_dft_test, SpectralDftTestArg};
+use crate::{Error, Test, TestResult};
+use std::num::NonZero;
+
+/// The example input of SP 800-22, e.g. 2.1.8: the first 100 bits of the binary expansion of pi.
+const PI_100: &str = "1100100100001111110110101010001000100001011010001100001000110100110001001100011001100010100010111000";
+
+/// The example input of SP 800-22, 2.4.8.
+const LONGEST_RUN_128: &str = "11001100000101010110110001001100111000000000001001001101010100010001001111010110100000001101011111001100111001101101100010110010";
+
+/// The bytes used to verify the storage of the bits: each byte differs, and the count of bytes is
+/// no multiple of the word size.
+const STORAGE_BYTES: [u8; 13] = [
+    0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0xfe, 0xdc, 0xba, 0x98, 0x0f,
+];
+
+/// The known P-values are given with 6 decimal places.
+const TOLERANCE: f64 = 5e-7;
+
+/// The error type of [self_test].
+#[derive(Error, Debug)]
+pub enum SelfTestError {
+    /// The bits are not stored as expected. The string describes the failed check.
+    #[error("The bit storage self-test failed: {0}")]
+    Storage(&'static str),
+    /// A test returned a P-value that differs from the known answer.
+    #[error("The self-test of the {test} test failed: expected P-value {expected}, got {actual}")]
+    WrongPValue {
+        test: Test,
+        expected: f64,
+        actual: f64,
+    },
+    /// A test returned an error on its known-answer input.
+    #[error("The self-test of the {test} test failed: {error}")]
+    TestError { test: Test, error: Error },
+}
+
+/// Runs the self-tests of the library, see the [module docs](self). Returns the first failed
+/// check.
+///
+/// ```
+/// sts_lib::self_test().expect("sts-lib does not work correctly on this machine");
+/// ```
+pub fn self_test() -> Result<(), SelfTestError> {
+    check_storage()?;
+    check_known_answers()
+}
+
+/// Checks that the bits are stored in sequence order, for all ways to create and read a [BitVec].
+fn check_storage() -> Result<(), SelfTestError> {
+    let ascii = STORAGE_BYTES
+        .iter()
+        .map(|byte| format!("{byte:08b}"))
+        .collect::<String>();
+    let bools = ascii.bytes().map(|c| c == b'1').collect::<Vec<_>>();
+
+    let from_bytes = BitVec::from(STORAGE_BYTES.as_slice());
+    let Some(from_ascii) = BitVec::from_ascii_str(&ascii) else {
+        return Err(SelfTestError::Storage("the ASCII input was not accepted"));
+    };
+
+    let checks = [
+        (
+            from_bytes.len_bit() == bools.len(),
+            "the length of the bytes is wrong",
+        ),
+        (
+            (0..bools.len()).all(|i| from_bytes.get(i) == Some(bools[i])),
+            "single bits are read in the wrong order",
+        ),
+        (
+            from_bytes.iter_bits().eq(bools.iter().copied()),
+            "the bit iterator yields the wrong bits",
+        ),
+        (
+            from_bytes == from_ascii,
+            "bytes and ASCII input are stored differently",
+        ),
+        (
+            from_bytes == BitVec::from(bools.as_slice()),
+            "bytes and booleans are stored differently",
+        ),
+        (
+            from_bytes.to_bytes() == (STORAGE_BYTES.to_vec(), None),
+            "the bytes are returned in the wrong order",
+        ),
+        (
+            from_bytes.count_ones() == bools.iter().filter(|&&bit| bit).count(),
+            "the count of ones is wrong",
+        ),
+        (
+            BitVec::from_bytes(&STORAGE_BYTES.map(u8::reverse_bits), BitOrder::LsbFirst)
+                == from_bytes,
+            "the bit order of the bytes is not applied",
+        ),
+        (
+            // the slice starts and ends within a byte and spans words on all targets
+            from_bytes.slice(3..99) == BitVec::from(&bools[3..99]),
+            "a slice contains the wrong bits",
+        ),
+    ];
+
+    match checks.into_iter().find(|(passed, _)| !passed) {
+        Some((_, description)) => Err(SelfTestError::Storage(description)),
+        None => Ok(()),
+    }
+}
+
+/// Checks a selection of tests against the examples of SP 800-22. The examples of the serial and
+/// the approximate entropy test are too short for the input length checks of these tests.
+fn check_known_answers() -> Result<(), SelfTestError> {
+    let pi_100 = BitVec::from_ascii_str(PI_100).unwrap();
+    let longest_run_128 = BitVec::from_ascii_str(LONGEST_RUN_128).unwrap();
+
+    check_p_values(
+        Test::Frequency,
+        frequency_test(&pi_100).map(|result| [result]),
+        &[0.109599],
+    )?;
+    check_p_values(
+        Test::FrequencyWithinABlock,
+        frequency_block_test(
+            &pi_100,
+            FrequencyBlockTestArg::Manual(NonZero::new(10).unwrap()),
+        )
+        .map(|result| [result]),
+        &[0.706438],
+    )?;
+    check_p_values(
+        Test::Runs,
+        runs_test(&pi_100).map(|result| [result]),
+        &[0.500798],
+    )?;
+    check_p_values(
+        Test::LongestRunOfOnes,
+        longest_run_of_ones_test(&longest_run_128, LongestRunOfOnesTestArg::default())
+            .map(|result| [result]),
+        &[0.180609],
+    )?;
+    check_p_values(
+        Test::SpectralDft,
+        spectral_dft_test(&pi_100, SpectralDftTestArg::default()).map(|result| [result]),
+        &[0.646355],
+    )?;
+    check_p_values(
+        Test::CumulativeSums,
+        cumulative_sums_test(&pi_100),
+        &[0.219194, 0.114866],
+    )
+}
+
+/// Compares the P-values of the results with the expected ones.
+fn check_p_values<R: AsRef<[TestResult]>>(
+    test: Test,
+    results: Result<R, Error>,
+    expected: &[f64],
+) -> Result<(), SelfTestError> {
+    let results = results.map_err(|error| SelfTestError::TestError { test, error })?;
+
+    for (result, &expected) in results.as_ref().iter().zip(expected) {
+        let actual = result.p_value();
+        // NaN must fail as well
+        if actual.is_nan() || f64::abs(actual - expected) >= TOLERANCE {
+            return Err(SelfTestError::WrongPValue {
+                test,
+                expected,
+                actual,
+            });
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,353 @@
+//! The test statistics that are calculated by each test, see [TestStatistics].
+//!
+//! The statistics are the intermediate values that lead to the P-value of a test. They are
+//! meant for debugging why a sequence (or a generator) fails a test, without having to
+//! re-implement the math of the test.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// The test statistics of one test result, see [TestResult::statistics](crate::TestResult::statistics).
+///
+/// Each variant corresponds to one test and contains the most important intermediate values of
+/// the test, named as in NIST SP 800-22r1a where possible.
+/// To access the values generically (e.g. for printing), use [Self::values].
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum TestStatistics {
+    /// Statistics of the [Frequency Test](crate::tests::frequency).
+    Frequency {
+        /// The sum of all bits, after converting each 0 to -1 and each 1 to +1 (*S_n*).
+        sum: isize,
+        /// The test statistic *s_obs*.
+        s_obs: f64,
+    },
+    /// Statistics of the [Frequency Test within a Block](crate::tests::frequency_block).
+    FrequencyWithinABlock {
+        /// The used block length *M*.
+        block_length: usize,
+        /// The count of tested blocks *N*.
+        block_count: usize,
+        /// The test statistic *χ²(obs)*.
+        chi_square: f64,
+    },
+    /// Statistics of the [Runs Test](crate::tests::runs).
+    Runs {
+        /// The proportion of ones in the sequence *π*.
+        proportion: f64,
+        /// The total count of runs *V_n(obs)*. Is `None` if the prerequisite frequency test did not
+        /// pass, meaning that the runs were never counted.
+        runs: Option<usize>,
+    },
+    /// Statistics of the [Test for the Longest Run of Ones in a Block](crate::tests::longest_run_of_ones).
+    LongestRunOfOnes {
+        /// The block length *M* of the used configuration.
+        block_length: usize,
+        /// The count of tested blocks *N*.
+        block_count: usize,
+        /// The test statistic *χ²(obs)*.
+        chi_square: f64,
+    },
+    /// Statistics of the [Binary Matrix Rank Test](crate::tests::binary_matrix_rank).
+    BinaryMatrixRank {
+        /// The count of rows *M* of each matrix.
+        rows: usize,
+        /// The count of columns *Q* of each matrix.
+        cols: usize,
+        /// The count of matrices with full rank *F_M*.
+        full_rank_count: usize,
+        /// The count of matrices with full rank - 1 *F_M-1*.
+        full_rank_minus_one_count: usize,
+        /// The count of the remaining matrices *N - F_M - F_M-1*.
+        remaining_count: usize,
+        /// The test statistic *χ²(obs)*.
+        chi_square: f64,
+    },
+    /// Statistics of the [Discrete Fourier Transform (Spectral) Test](crate::tests::spectral_dft).
+    SpectralDft {
+        /// The peak height threshold *T*.
+        threshold: f64,
+        /// The expected count of peaks below the threshold *N_0*.
+        expected_peaks: f64,
+        /// The observed count of peaks below the threshold *N_1*.
+        observed_peaks: usize,
+        /// The test statistic *d*.
+        d: f64,
+    },
+    /// Statistics of the [Non-overlapping Template Matching Test](crate::tests::template_matching::non_overlapping),
+    /// for one template.
+    NonOverlappingTemplateMatching {
+        /// The theoretical mean of matches per block *μ*.
+        mean: f64,
+        /// The theoretical variance of matches per block *σ²*.
+        variance: f64,
+        /// The test statistic *χ²(obs)*.
+        chi_square: f64,
+    },
+    /// Statistics of the [Overlapping Template Matching Test](crate::tests::template_matching::overlapping).
+    OverlappingTemplateMatching {
+        /// The count of tested blocks *N*.
+        block_count: usize,
+        /// The test statistic *χ²(obs)*.
+        chi_square: f64,
+    },
+    /// Statistics of [Maurer's "Universal Statistical" Test](crate::tests::maurers_universal_statistical).
+    MaurersUniversalStatistical {
+        /// The used block length *L*.
+        block_length: usize,
+        /// The count of initialization blocks *Q*.
+        init_block_count: usize,
+        /// The count of test blocks *K*.
+        test_block_count: usize,
+        /// The test statistic *f_n*.
+        f_n: f64,
+        /// The expected value of *f_n*.
+        expected_value: f64,
+        /// The standard deviation *σ*.
+        sigma: f64,
+    },
+    /// Statistics of the [Linear Complexity Test](crate::tests::linear_complexity).
+    LinearComplexity {
+        /// The used block length *M*.
+        block_length: usize,
+        /// The count of tested blocks *N*.
+        block_count: usize,
+        /// The histogram of the deviations *T_i* from the theoretical mean: the count of blocks
+        /// *ν_0* to *ν_6* in each class.
+        histogram: [usize; 7],
+        /// The test statistic *χ²(obs)*.
+        chi_square: f64,
+    },
+    /// Statistics of the [Serial Test](crate::tests::serial). Both results contain the same
+    /// statistics.
+    Serial {
+        /// The values *ψ²_m*, *ψ²_m-1* and *ψ²_m-2*.
+        psi_squared: [f64; 3],
+        /// The first test statistic *∇ψ²_m*.
+        delta: f64,
+        /// The second test statistic *∇²ψ²_m*.
+        delta_squared: f64,
+    },
+    /// Statistics of the [Approximate Entropy Test](crate::tests::approximate_entropy).
+    ApproximateEntropy {
+        /// The approximate entropy *ApEn(m)*.
+        ap_en: f64,
+        /// The test statistic *χ²(obs)*.
+        chi_square: f64,
+    },
+    /// Statistics of the [Cumulative Sums Test](crate::tests::cumulative_sums), for one mode: the
+    /// first result is the forward mode, the second the backward mode.
+    CumulativeSums {
+        /// The largest absolute value of the partial sums *z = max |S_k|*.
+        max_excursion: usize,
+    },
+    /// Statistics of the [Random Excursions Test](crate::tests::random_excursions), for one
+    /// state.
+    RandomExcursions {
+        /// The state *x* of this result.
+        state: i8,
+        /// The count of cycles *J*.
+        cycles: usize,
+        /// The test statistic *χ²(obs)*. Is `None` if there were too few cycles to run the test.
+        chi_square: Option<f64>,
+    },
+    /// Statistics of the [Random Excursions Variant Test](crate::tests::random_excursions_variant),
+    /// for one state.
+    RandomExcursionsVariant {
+        /// The state *x* of this result.
+        state: i8,
+        /// The count of cycles *J*.
+        cycles: usize,
+        /// The total count of visits to the state *ξ(x)*.
+        visits: usize,
+    },
+    /// Statistics of the [Lempel-Ziv Compression Test](crate::tests::lempel_ziv).
+    LempelZiv {
+        /// The count of cumulatively distinct words *W_obs*.
+        words: usize,
+    },
+    /// Statistics of the [Autocorrelation Test](crate::tests::autocorrelation).
+    Autocorrelation {
+        /// The used shift *d*.
+        shift: usize,
+        /// The count of bits that differ from the bit *d* positions later *A(d)*.
+        differences: usize,
+        /// The test statistic *X*.
+        statistic: f64,
+    },
+    /// Statistics of the [uniformity of P-values](crate::analysis::p_value_uniformity).
+    Uniformity {
+        /// The count of analysed P-values.
+        sample_size: usize,
+        /// The test statistic *χ²*.
+        chi_square: f64,
+    },
+}
+
+impl TestStatistics {
+    /// Returns all contained values with their name, in the order they are declared. Integer
+    /// values are converted to [f64], values that are `None` are skipped.
+    pub fn values(&self) -> Vec<(&'static str, f64)> {
+        match *self {
+            TestStatistics::Frequency { sum, s_obs } => {
+                vec![("sum", sum as f64), ("s_obs", s_obs)]
+            }
+            TestStatistics::FrequencyWithinABlock {
+                block_length,
+                block_count,
+                chi_square,
+            } => vec![
+                ("block_length", block_length as f64),
+                ("block_count", block_count as f64),
+                ("chi_square", chi_square),
+            ],
+            TestStatistics::LinearComplexity {
+                block_length,
+                block_count,
+                histogram,
+                chi_square,
+            } => vec![
+                ("block_length", block_length as f64),
+                ("block_count", block_count as f64),
+                ("v_0", histogram[0] as f64),
+                ("v_1", histogram[1] as f64),
+                ("v_2", histogram[2] as f64),
+                ("v_3", histogram[3] as f64),
+                ("v_4", histogram[4] as f64),
+                ("v_5", histogram[5] as f64),
+                ("v_6", histogram[6] as f64),
+                ("chi_square", chi_square),
+            ],
+            TestStatistics::Runs { proportion, runs } => {
+                let mut values = vec![("proportion", proportion)];
+                if let Some(runs) = runs {
+                    values.push(("runs", runs as f64));
+                }
+                values
+            }
+            TestStatistics::LongestRunOfOnes {
+                block_length,
+                block_count,
+                chi_square,
+            } => vec![
+                ("block_length", block_length as f64),
+                ("block_count", block_count as f64),
+                ("chi_square", chi_square),
+            ],
+            TestStatistics::OverlappingTemplateMatching {
+                block_count,
+                chi_square,
+            } => vec![
+                ("block_count", block_count as f64),
+                ("chi_square", chi_square),
+            ],
+            TestStatistics::BinaryMatrixRank {
+                rows,
+                cols,
+                full_rank_count,
+                full_rank_minus_one_count,
+                remaining_count,
+                chi_square,
+            } => vec![
+                ("rows", rows as f64),
+                ("cols", cols as f64),
+                ("full_rank_count", full_rank_count as f64),
+                (
+                    "full_rank_minus_one_count",
+                    full_rank_minus_one_count as f64,
+                ),
+                ("remaining_count", remaining_count as f64),
+                ("chi_square", chi_square),
+            ],
+            TestStatistics::SpectralDft {
+                threshold,
+                expected_peaks,
+                observed_peaks,
+                d,
+            } => vec![
+                ("threshold", threshold),
+                ("expected_peaks", expected_peaks),
+                ("observed_peaks", observed_peaks as f64),
+                ("d", d),
+            ],
+            TestStatistics::NonOverlappingTemplateMatching {
+                mean,
+                variance,
+                chi_square,
+            } => vec![
+                ("mean", mean),
+                ("variance", variance),
+                ("chi_square", chi_square),
+            ],
+            TestStatistics::MaurersUniversalStatistical {
+                block_length,
+                init_block_count,
+                test_block_count,
+                f_n,
+                expected_value,
+                sigma,
+            } => vec![
+                ("block_length", block_length as f64),
+                ("init_block_count", init_block_count as f64),
+                ("test_block_count", test_block_count as f64),
+                ("f_n", f_n),
+                ("expected_value", expected_value),
+                ("sigma", sigma),
+            ],
+            TestStatistics::Serial {
+                psi_squared,
+                delta,
+                delta_squared,
+            } => vec![
+                ("psi_squared_m", psi_squared[0]),
+                ("psi_squared_m_1", psi_squared[1]),
+                ("psi_squared_m_2", psi_squared[2]),
+                ("delta", delta),
+                ("delta_squared", delta_squared),
+            ],
+            TestStatistics::ApproximateEntropy { ap_en, chi_square } => {
+                vec![("ap_en", ap_en), ("chi_square", chi_square)]
+            }
+            TestStatistics::CumulativeSums { max_excursion } => {
+                vec![("max_excursion", max_excursion as f64)]
+            }
+            TestStatistics::RandomExcursions {
+                state,
+                cycles,
+                chi_square,
+            } => {
+                let mut values = vec![("state", state as f64), ("cycles", cycles as f64)];
+                if let Some(chi_square) = chi_square {
+                    values.push(("chi_square", chi_square));
+                }
+                values
+            }
+            TestStatistics::RandomExcursionsVariant {
+                state,
+                cycles,
+                visits,
+            } => vec![
+                ("state", state as f64),
+                ("cycles", cycles as f64),
+                ("visits", visits as f64),
+            ],
+            TestStatistics::LempelZiv { words } => vec![("words", words as f64)],
+            TestStatistics::Autocorrelation {
+                shift,
+                differences,
+                statistic,
+            } => vec![
+                ("shift", shift as f64),
+                ("differences", differences as f64),
+                ("statistic", statistic),
+            ],
+            TestStatistics::Uniformity {
+                sample_size,
+                chi_square,
+            } => vec![
+                ("sample_size", sample_size as f64),
+                ("chi_square", chi_square),
+            ],
+        }
+    }
+}
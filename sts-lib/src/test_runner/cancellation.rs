@@ -0,0 +1,123 @@
+//! Cooperative cancellation of running tests, see [CancellationToken], and the timeout of
+//! running tests, see [TestArgs::timeout](crate::TestArgs::timeout).
+
+use crate::Error;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+thread_local! {
+    /// The check of the test that is currently run on this thread, set by [with_check].
+    static CURRENT_CHECK: RefCell<CancelCheck> = const {
+        RefCell::new(CancelCheck {
+            token: None,
+            deadline: None,
+        })
+    };
+}
+
+/// A token to cooperatively cancel a long-running analysis, see
+/// [run_tests_with_cancel](super::run_tests_with_cancel).
+///
+/// The token can be cloned cheaply, all clones share the same state. To cancel the analysis from
+/// another thread, keep a clone of the token and call [Self::cancel]. Cancellation cannot be
+/// undone - to run tests again, use a new token.
+///
+/// Cancellation is cooperative: the tests check the token periodically and abort with
+/// [Error::Cancelled], all tests that were not started yet are not run at all.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Creates a new token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests the cancellation of all tests using this token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Checks if [Self::cancel] was called on this token or any of its clones.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Checks if the test currently run by the test runner on this thread was cancelled or exceeded
+/// its [timeout](crate::TestArgs::timeout), for long-running
+/// [custom tests](super::custom::CustomTest).
+///
+/// Returns [Error::Cancelled] or [Error::TimedOut], which the test should return as soon as
+/// possible. Must be called on the thread that called
+/// [CustomTest::run](super::custom::CustomTest::run), not inside closures that run on other
+/// threads. Outside of the test runner, this always returns `Ok`.
+pub fn check_current() -> Result<(), Error> {
+    CURRENT_CHECK.with_borrow(CancelCheck::check)
+}
+
+/// Used by the tests to check if they were cancelled or exceeded their timeout.
+///
+/// Must be created with [Self::current] on the thread that runs the test function itself, and can
+/// then be moved into closures that are executed on other threads of the thread pool.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct CancelCheck {
+    token: Option<CancellationToken>,
+    /// The point in time the test times out, with the timeout itself for the error.
+    deadline: Option<(Instant, Duration)>,
+}
+
+impl CancelCheck {
+    /// Returns the check for the test currently running on this thread.
+    pub(crate) fn current() -> Self {
+        CURRENT_CHECK.with_borrow(Clone::clone)
+    }
+
+    /// Returns [Error::Cancelled] if the test was cancelled, or [Error::TimedOut] if the test
+    /// exceeded its timeout.
+    #[inline]
+    pub(crate) fn check(&self) -> Result<(), Error> {
+        match (&self.token, self.deadline) {
+            (Some(token), _) if token.is_cancelled() => Err(Error::Cancelled),
+            (_, Some((deadline, timeout))) if Instant::now() >= deadline => {
+                Err(Error::TimedOut(timeout))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Runs the given function with the given token and timeout available to [CancelCheck::current].
+/// The timeout starts with this call.
+///
+/// The function must be called on the thread that runs the test function, i.e. inside the thread
+/// pool, because the test functions themselves are always executed inside the thread pool.
+pub(crate) fn with_check<T>(
+    token: Option<&CancellationToken>,
+    timeout: Option<Duration>,
+    f: impl FnOnce() -> T,
+) -> T {
+    /// Restores the previous check, even if `f` panics.
+    struct Guard(CancelCheck);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            CURRENT_CHECK.set(std::mem::take(&mut self.0));
+        }
+    }
+
+    let check = CancelCheck {
+        token: token.cloned(),
+        // a timeout too large to be represented is the same as no timeout
+        deadline: timeout.and_then(|timeout| {
+            Instant::now()
+                .checked_add(timeout)
+                .map(|deadline| (deadline, timeout))
+        }),
+    };
+
+    let _guard = Guard(CURRENT_CHECK.replace(check));
+    f()
+}
@@ -0,0 +1,166 @@
+//! Contexts with their own thread pool, see [StsContext].
+
+use crate::bitvec::BitVec;
+use crate::internals::IN_CUSTOM_THREAD_POOL;
+use crate::test_runner::{
+    checked_tests, run_test, run_test_with_token, CancellationToken, RunnerError,
+};
+use crate::{Error, Test, TestArgs, TestResult};
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+use std::num::NonZero;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// A context that owns its own thread pool, with its own thread limit.
+///
+/// By default, all tests run in the global thread pool of the library, whose thread count can
+/// only be set once per process with [set_max_threads](crate::set_max_threads). Tests run with a
+/// context use the thread pool of the context instead, so e.g. each service or notebook can use
+/// its own thread limit. The context can be cloned cheaply, all clones share the same thread pool.
+/// The thread pool is shut down once the last clone is dropped.
+///
+/// Use [StsContext::shared] to select a thread limit per call, e.g. in bindings: the contexts are
+/// cached, so each thread limit only creates one thread pool per process.
+///
+/// ```
+/// use std::num::NonZero;
+/// use sts_lib::bitvec::BitVec;
+/// use sts_lib::test_runner::StsContext;
+/// use sts_lib::tests::frequency::frequency_test;
+/// use sts_lib::{Test, TestArgs};
+///
+/// let context = StsContext::new(NonZero::new(2).unwrap()).unwrap();
+/// let data = BitVec::from(vec![0b1011_0101_u8; 16]);
+///
+/// // run tests with the test runner
+/// let results = context
+///     .run_tests(&data, [Test::Frequency, Test::Runs].into_iter(), TestArgs::default())
+///     .unwrap();
+/// assert_eq!(results.count(), 2);
+///
+/// // or run any function of the library
+/// let result = context.install(|| frequency_test(&data));
+/// assert!(result.is_ok());
+/// ```
+#[derive(Clone, Debug)]
+pub struct StsContext {
+    pool: Arc<ThreadPool>,
+}
+
+/// The contexts created by [StsContext::shared], at most one for each count of threads.
+static SHARED_CONTEXTS: Mutex<Vec<StsContext>> = Mutex::new(Vec::new());
+
+/// Two contexts are equal if they share the same thread pool, i.e. one is a clone of the other.
+impl PartialEq for StsContext {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.pool, &other.pool)
+    }
+}
+
+impl Eq for StsContext {}
+
+/// Error type for [StsContext::new] and [StsContext::shared]: the thread pool could not be created.
+#[derive(Debug, Error)]
+#[error("Could not create the thread pool of the context: {0}")]
+pub struct ContextCreationError(#[from] ThreadPoolBuildError);
+
+impl StsContext {
+    /// Creates a new context, with a thread pool of the given count of threads.
+    pub fn new(max_threads: NonZero<usize>) -> Result<Self, ContextCreationError> {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(max_threads.get())
+            .thread_name(|idx| format!("sts-context-{idx}"))
+            .start_handler(|_| IN_CUSTOM_THREAD_POOL.set(true))
+            .build()?;
+
+        Ok(Self {
+            pool: Arc::new(pool),
+        })
+    }
+
+    /// Returns a context with the given count of threads, that is shared by all callers with the
+    /// same count: its thread pool is created on the first call and then kept until the end of
+    /// the process. This allows choosing the thread limit for each call, without creating a new
+    /// thread pool each time.
+    pub fn shared(max_threads: NonZero<usize>) -> Result<Self, ContextCreationError> {
+        let mut contexts = SHARED_CONTEXTS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(context) = contexts
+            .iter()
+            .find(|context| context.max_threads() == max_threads.get())
+        {
+            return Ok(context.clone());
+        }
+
+        let context = Self::new(max_threads)?;
+        contexts.push(context.clone());
+        Ok(context)
+    }
+
+    /// The count of threads in the thread pool of this context.
+    pub fn max_threads(&self) -> usize {
+        self.pool.current_num_threads()
+    }
+
+    /// Runs the given function in the thread pool of this context: all functions of this library
+    /// called by `f` (e.g. the tests) use this thread pool instead of the global one.
+    pub fn install<R: Send>(&self, f: impl FnOnce() -> R + Send) -> R {
+        self.pool.install(f)
+    }
+
+    /// Runs all given tests in the thread pool of this context, like
+    /// [run_tests](super::run_tests).
+    ///
+    /// Only unique tests may be passed, and the input must be long enough for each test: the
+    /// tests are [validated](super::validate_tests) before any test is run, returning all problems
+    /// at once.
+    ///
+    /// Returns all test results. Like in [run_tests](super::run_tests), each test is only run when
+    /// `.next()` is called on the returned iterator.
+    pub fn run_tests<'a>(
+        &self,
+        data: impl AsRef<BitVec<'a>> + Sync,
+        tests: impl Iterator<Item = Test>,
+        args: TestArgs,
+    ) -> Result<impl Iterator<Item = (Test, Result<Vec<TestResult>, Error>)>, RunnerError> {
+        let context = self.clone();
+
+        let output = checked_tests(tests, data.as_ref().len_bit(), args)?
+            .into_iter()
+            .map(move |test| context.install(|| run_test(test, data.as_ref(), args)));
+
+        Ok(output)
+    }
+
+    /// Runs all given tests in the thread pool of this context, like
+    /// [run_tests_with_cancel](super::run_tests_with_cancel).
+    ///
+    /// Only unique tests may be passed, and the input must be long enough for each test: the
+    /// tests are [validated](super::validate_tests) before any test is run, returning all problems
+    /// at once.
+    ///
+    /// Returns all test results.
+    pub fn run_tests_with_cancel<'a>(
+        &self,
+        data: impl AsRef<BitVec<'a>> + Sync,
+        tests: impl Iterator<Item = Test>,
+        args: TestArgs,
+        token: &CancellationToken,
+    ) -> Result<impl Iterator<Item = (Test, Result<Vec<TestResult>, Error>)>, RunnerError> {
+        let context = self.clone();
+        let token = token.clone();
+
+        let output = checked_tests(tests, data.as_ref().len_bit(), args)?
+            .into_iter()
+            .map(move |test| {
+                if token.is_cancelled() {
+                    (test, Err(Error::Cancelled))
+                } else {
+                    context.install(|| run_test_with_token(test, data.as_ref(), args, Some(&token)))
+                }
+            });
+
+        Ok(output)
+    }
+}
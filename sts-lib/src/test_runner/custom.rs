@@ -0,0 +1,181 @@
+//! Tests defined outside of this library, run by the test runner, see [CustomTest].
+
+use crate::bitvec::BitVec;
+use crate::test_runner::cancellation::with_check;
+use crate::test_runner::CancellationToken;
+use crate::{Error, TestArgs, TestResult};
+use std::collections::HashSet;
+use std::fmt::{Debug, Formatter};
+use std::num::NonZero;
+use sts_lib_derive::use_thread_pool;
+
+/// A test that is not part of this library, e.g. a statistic of a downstream crate, that is run
+/// by the test runner like the tests of this library.
+///
+/// Custom tests are identified by their [name](Self::name) instead of [Test](crate::Test). They
+/// can be run with [run_custom_tests] and over multiple sequences with
+/// [MultiSequenceRunner::with_custom_tests](super::MultiSequenceRunner::with_custom_tests), whose
+/// results can be analysed like the results of the tests of this library.
+///
+/// The results are created with [TestResult::from_p_value]. The test runs in the thread pool of
+/// the library (or of the [context](super::StsContext)), so it can use rayon. A long-running test
+/// should call [check_current](super::cancellation::check_current) periodically, to support
+/// cancellation and the [timeout](TestArgs::timeout).
+///
+/// ```
+/// use std::num::NonZero;
+/// use sts_lib::bitvec::BitVec;
+/// use sts_lib::test_runner::custom::{run_custom_tests, CustomTest};
+/// use sts_lib::{Error, TestArgs, TestResult};
+///
+/// /// Passes if the first bit is set, with a meaningless P-value.
+/// struct FirstBit;
+///
+/// impl CustomTest for FirstBit {
+///     fn name(&self) -> &'static str {
+///         "First Bit"
+///     }
+///
+///     fn min_length(&self) -> NonZero<usize> {
+///         NonZero::<usize>::MIN
+///     }
+///
+///     fn run(&self, data: &BitVec, _: &TestArgs) -> Result<Vec<TestResult>, Error> {
+///         let p_value = if data.get(0) == Some(true) { 1.0 } else { 0.0 };
+///         Ok(vec![TestResult::from_p_value(p_value, None)])
+///     }
+/// }
+///
+/// let data = BitVec::from_ascii_str("1011010101").unwrap();
+/// let results = run_custom_tests(&data, [&FirstBit as &dyn CustomTest], TestArgs::default())
+///     .unwrap()
+///     .collect::<Vec<_>>();
+///
+/// let (name, result) = &results[0];
+/// assert_eq!(*name, "First Bit");
+/// assert_eq!(result.as_ref().unwrap()[0].p_value(), 1.0);
+/// ```
+pub trait CustomTest: Send + Sync {
+    /// The name of the test, which identifies it in the results. Must be unique among the custom
+    /// tests that are run together.
+    fn name(&self) -> &'static str;
+
+    /// The minimum input length in bits, like
+    /// [get_min_length_for_test](crate::get_min_length_for_test). The test is not run on shorter
+    /// inputs, the runner returns [Error::InvalidParameter] instead.
+    fn min_length(&self) -> NonZero<usize>;
+
+    /// Runs the test on the given data, with the arguments given to the runner. The arguments
+    /// of the tests of this library can be ignored, unless the test is based on one of them.
+    ///
+    /// Returns one or more results, tests with multiple results must always return the same count
+    /// of results, in the same order.
+    fn run(&self, data: &BitVec, args: &TestArgs) -> Result<Vec<TestResult>, Error>;
+}
+
+impl Debug for dyn CustomTest + '_ {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomTest")
+            .field("name", &self.name())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Error type when running custom tests: two of the custom tests to run have the same name.
+#[derive(Debug, Error)]
+#[error("Custom test {0} is a duplicate!")]
+pub struct CustomRunnerError(pub &'static str);
+
+/// Runs all given custom tests with the passed [args](TestArgs), like
+/// [run_tests](super::run_tests). The [timeout](TestArgs::timeout) is applied, if there is one.
+///
+/// Only tests with unique names may be passed.
+///
+/// Returns the results of all tests by name, in the given order. Each test is only run when
+/// `.next()` is called on the returned iterator.
+pub fn run_custom_tests<'a>(
+    data: impl AsRef<BitVec<'a>> + 'a,
+    tests: impl IntoIterator<Item = &'a dyn CustomTest>,
+    args: TestArgs,
+) -> Result<
+    impl Iterator<Item = (&'static str, Result<Vec<TestResult>, Error>)> + 'a,
+    CustomRunnerError,
+> {
+    let tests = tests.into_iter().collect::<Vec<_>>();
+    check_unique_names(tests.iter().map(|test| test.name()))?;
+
+    let output = tests.into_iter().map(move |test| {
+        let result = run_custom_test(test, data.as_ref(), args, None);
+        (test.name(), result)
+    });
+
+    Ok(output)
+}
+
+/// Runs all given custom tests like [run_custom_tests], but allows to cancel the running tests
+/// with the given [token](CancellationToken), like
+/// [run_tests_with_cancel](super::run_tests_with_cancel).
+///
+/// Only tests with unique names may be passed.
+///
+/// Once the token is cancelled, all remaining tests are skipped and return [Error::Cancelled].
+/// The currently running test only aborts early if it checks for the cancellation, see
+/// [check_current](super::cancellation::check_current).
+pub fn run_custom_tests_with_cancel<'a>(
+    data: impl AsRef<BitVec<'a>> + 'a,
+    tests: impl IntoIterator<Item = &'a dyn CustomTest>,
+    args: TestArgs,
+    token: &CancellationToken,
+) -> Result<
+    impl Iterator<Item = (&'static str, Result<Vec<TestResult>, Error>)> + 'a,
+    CustomRunnerError,
+> {
+    let tests = tests.into_iter().collect::<Vec<_>>();
+    check_unique_names(tests.iter().map(|test| test.name()))?;
+    let token = token.clone();
+
+    let output = tests.into_iter().map(move |test| {
+        let result = if token.is_cancelled() {
+            Err(Error::Cancelled)
+        } else {
+            run_custom_test(test, data.as_ref(), args, Some(&token))
+        };
+        (test.name(), result)
+    });
+
+    Ok(output)
+}
+
+/// Checks the names of the custom tests for duplicates.
+pub(crate) fn check_unique_names(
+    mut names: impl Iterator<Item = &'static str>,
+) -> Result<(), CustomRunnerError> {
+    let mut unique_names = HashSet::with_capacity(names.size_hint().0);
+
+    match names.find(|&name| !unique_names.insert(name)) {
+        Some(name) => Err(CustomRunnerError(name)),
+        None => Ok(()),
+    }
+}
+
+/// Runs the custom test inside the thread pool, if the input is long enough, with the token and
+/// the [timeout](TestArgs::timeout) available for the test to check.
+#[use_thread_pool]
+pub(crate) fn run_custom_test(
+    test: &dyn CustomTest,
+    data: &BitVec,
+    args: TestArgs,
+    token: Option<&CancellationToken>,
+) -> Result<Vec<TestResult>, Error> {
+    let min_length = test.min_length();
+    if data.len_bit() < min_length.get() {
+        return Err(Error::InvalidParameter(format!(
+            "The input length of {} bits is shorter than the minimum length of {min_length} bits \
+            of the custom test {}.",
+            data.len_bit(),
+            test.name()
+        )));
+    }
+
+    with_check(token, args.timeout, || test.run(data, &args))
+}
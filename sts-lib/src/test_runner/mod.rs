@@ -0,0 +1,621 @@
+//! Test runner to run several tests in a batch.
+
+use crate::bitvec::BitVec;
+use crate::{get_min_length_for_test, get_recommended_length_for_test};
+use crate::{tests, Error, Test, TestArgs, TestResult};
+use cancellation::with_check;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::num::NonZero;
+use sts_lib_derive::use_thread_pool;
+use tests::frequency_block::FrequencyBlockTestArg;
+use tests::linear_complexity::LinearComplexityTestArg;
+use tests::template_matching::non_overlapping;
+use tests::template_matching::overlapping;
+use tests::*;
+
+pub mod cancellation;
+pub mod context;
+pub mod custom;
+pub mod multi_sequence;
+pub mod plan;
+pub mod subsample;
+pub use cancellation::CancellationToken;
+pub use context::StsContext;
+pub use custom::CustomTest;
+pub use multi_sequence::MultiSequenceRunner;
+pub use plan::{estimate_duration, plan_tests, Calibration, PlannedTest};
+pub use subsample::{run_tests_subsampled, run_tests_subsampled_with_cancel, Subsample};
+
+/// Error type when using the test runner: the tests to run were rejected before any of them was
+/// executed. See [validate_tests] for the checks.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+#[non_exhaustive]
+pub enum RunnerError {
+    /// In the iterator with the tests to run, the test is contained more than 1 time.
+    #[error("Test {0} is a duplicate!")]
+    DuplicateTest(Test),
+    /// The input is shorter than the test requires with the given arguments.
+    #[error(
+        "Test {test} requires an input of at least {required} bits, the input has {actual} bits!"
+    )]
+    InputTooShort {
+        test: Test,
+        required: usize,
+        actual: usize,
+    },
+    /// The arguments of the test cannot be used with the length of the input.
+    #[error("Test {test}: {reason}")]
+    InvalidArgCombination { test: Test, reason: String },
+    /// Multiple of the other errors, in the order of the given tests.
+    #[error("{}", display_problems(.0))]
+    Multiple(Vec<RunnerError>),
+}
+
+impl RunnerError {
+    /// All problems contained in the error: the contained errors for [RunnerError::Multiple],
+    /// otherwise just the error itself.
+    pub fn problems(&self) -> &[RunnerError] {
+        match self {
+            RunnerError::Multiple(problems) => problems,
+            problem => std::slice::from_ref(problem),
+        }
+    }
+
+    /// Combines the found problems into an error, `None` if there are none.
+    fn from_problems(mut problems: Vec<RunnerError>) -> Option<Self> {
+        match problems.len() {
+            0 => None,
+            1 => problems.pop(),
+            _ => Some(RunnerError::Multiple(problems)),
+        }
+    }
+}
+
+/// The message of [RunnerError::Multiple]: the message of each problem.
+fn display_problems(problems: &[RunnerError]) -> String {
+    problems
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The results of one test on each sub-sequence, see [run_tests_partitioned] and
+/// [run_tests_subsampled].
+pub type PartitionedResults = Vec<Result<Vec<TestResult>, Error>>;
+
+/// Runs the [default tests](Test::default_set) automatically, with necessary arguments
+/// automatically chosen.
+///
+/// Returns all test results.
+pub fn run_all_tests_automatic<'a>(
+    data: impl AsRef<BitVec<'a>>,
+) -> Result<impl Iterator<Item = (Test, Result<Vec<TestResult>, Error>)>, RunnerError> {
+    run_tests_automatic(data, Test::default_set().iter().copied())
+}
+
+/// Runs all given tests automatically, with necessary arguments automatically chosen.
+///
+/// Only unique tests may be passed, and the input must be long enough for each test: the
+/// tests are [validated](validate_tests) before any test is run, returning all problems at once.
+///
+/// Returns all test results.
+pub fn run_tests_automatic<'a>(
+    data: impl AsRef<BitVec<'a>>,
+    tests: impl Iterator<Item = Test>,
+) -> Result<impl Iterator<Item = (Test, Result<Vec<TestResult>, Error>)>, RunnerError> {
+    run_tests(data, tests, TestArgs::default())
+}
+
+/// Runs the [default tests](Test::default_set) with the used arguments taken from the passed
+/// [args](TestArgs).
+///
+/// Returns all test results.
+pub fn run_all_tests<'a>(
+    data: impl AsRef<BitVec<'a>>,
+    args: TestArgs,
+) -> Result<impl Iterator<Item = (Test, Result<Vec<TestResult>, Error>)>, RunnerError> {
+    run_tests(data, Test::default_set().iter().copied(), args)
+}
+
+/// Runs all given tests with the used arguments taken from the passed [args](TestArgs).
+///
+/// Only unique tests may be passed, and the input must be long enough for each test: the
+/// tests are [validated](validate_tests) before any test is run, returning all problems at once.
+///
+/// Returns all test results.
+pub fn run_tests<'a>(
+    data: impl AsRef<BitVec<'a>>,
+    tests: impl Iterator<Item = Test>,
+    args: TestArgs,
+) -> Result<impl Iterator<Item = (Test, Result<Vec<TestResult>, Error>)>, RunnerError> {
+    let output = checked_tests(tests, data.as_ref().len_bit(), args)?
+        .into_iter()
+        .map(move |test| run_test(test, data.as_ref(), args));
+
+    Ok(output)
+}
+
+/// Runs all given tests with the used arguments taken from the passed [args](TestArgs), like
+/// [run_tests], but allows to cancel the running tests with the given [token](CancellationToken).
+///
+/// Only unique tests may be passed, and the input must be long enough for each test: the
+/// tests are [validated](validate_tests) before any test is run, returning all problems at once.
+///
+/// Once the token is cancelled, the currently running test aborts as soon as possible and all
+/// remaining tests are skipped. All of these tests return [Error::Cancelled].
+///
+/// Returns all test results.
+pub fn run_tests_with_cancel<'a>(
+    data: impl AsRef<BitVec<'a>>,
+    tests: impl Iterator<Item = Test>,
+    args: TestArgs,
+    token: &CancellationToken,
+) -> Result<impl Iterator<Item = (Test, Result<Vec<TestResult>, Error>)>, RunnerError> {
+    let token = token.clone();
+
+    let output = checked_tests(tests, data.as_ref().len_bit(), args)?
+        .into_iter()
+        .map(move |test| {
+            if token.is_cancelled() {
+                (test, Err(Error::Cancelled))
+            } else {
+                run_test_with_token(test, data.as_ref(), args, Some(&token))
+            }
+        });
+
+    Ok(output)
+}
+
+/// Runs all given tests on each of `partitions` disjoint sub-sequences of the data, with the used
+/// arguments taken from the passed [args](TestArgs). All sub-sequences have the same length: the
+/// length of the data divided by `partitions`, the remaining bits at the end are not tested.
+///
+/// Only unique tests may be passed, and the sub-sequences must be long enough for each test: the
+/// tests are [validated](validate_tests) before any test is run, returning all problems at once.
+///
+/// Returns the results of each test, with one entry per sub-sequence, in the order of the
+/// sub-sequences in the data. These can be analysed as a whole with
+/// [analyse](crate::analysis::analyse). Each test is only run (on all sub-sequences) when
+/// `.next()` is called on the returned iterator.
+///
+/// ```
+/// use std::num::NonZero;
+/// use sts_lib::bitvec::BitVec;
+/// use sts_lib::test_runner::run_tests_partitioned;
+/// use sts_lib::{Test, TestArgs};
+///
+/// let data = BitVec::from(vec![0b1011_0101_u8; 38]);
+/// let partitions = NonZero::new(3).unwrap();
+///
+/// let tests = [Test::Frequency].into_iter();
+/// let results = run_tests_partitioned(&data, tests, TestArgs::default(), partitions)
+///     .unwrap()
+///     .collect::<Vec<_>>();
+///
+/// // one result per sub-sequence of 101 bits, the last bit is not tested
+/// let (test, results) = &results[0];
+/// assert_eq!(*test, Test::Frequency);
+/// assert_eq!(results.len(), 3);
+/// ```
+pub fn run_tests_partitioned<'a>(
+    data: impl AsRef<BitVec<'a>>,
+    tests: impl Iterator<Item = Test>,
+    args: TestArgs,
+    partitions: NonZero<usize>,
+) -> Result<impl Iterator<Item = (Test, PartitionedResults)>, RunnerError> {
+    let partitions = partition(data.as_ref(), partitions);
+
+    let tests = checked_tests(tests, partition_length(&partitions), args)?;
+
+    let output = tests.into_iter().map(move |test| {
+        let results = partitions
+            .iter()
+            .map(|partition| run_test(test, partition, args).1)
+            .collect();
+
+        (test, results)
+    });
+
+    Ok(output)
+}
+
+/// Runs all given tests on each of `partitions` disjoint sub-sequences of the data, like
+/// [run_tests_partitioned], but allows to cancel the running tests with the given
+/// [token](CancellationToken).
+///
+/// Only unique tests may be passed, and the sub-sequences must be long enough for each test: the
+/// tests are [validated](validate_tests) before any test is run, returning all problems at once.
+///
+/// Once the token is cancelled, the currently running test aborts as soon as possible and all
+/// remaining tests and sub-sequences are skipped. All of these return [Error::Cancelled].
+pub fn run_tests_partitioned_with_cancel<'a>(
+    data: impl AsRef<BitVec<'a>>,
+    tests: impl Iterator<Item = Test>,
+    args: TestArgs,
+    partitions: NonZero<usize>,
+    token: &CancellationToken,
+) -> Result<impl Iterator<Item = (Test, PartitionedResults)>, RunnerError> {
+    let partitions = partition(data.as_ref(), partitions);
+    let token = token.clone();
+
+    let tests = checked_tests(tests, partition_length(&partitions), args)?;
+
+    let output = tests.into_iter().map(move |test| {
+        let results = partitions
+            .iter()
+            .map(|partition| {
+                if token.is_cancelled() {
+                    Err(Error::Cancelled)
+                } else {
+                    run_test_with_token(test, partition, args, Some(&token)).1
+                }
+            })
+            .collect();
+
+        (test, results)
+    });
+
+    Ok(output)
+}
+
+/// The length of each sub-sequence created by [partition].
+fn partition_length(partitions: &[BitVec]) -> usize {
+    partitions.first().map_or(0, BitVec::len_bit)
+}
+
+/// Splits the data into `count` disjoint sub-sequences of equal length, the remaining bits at the
+/// end are discarded.
+fn partition(data: &BitVec, count: NonZero<usize>) -> Vec<BitVec<'static>> {
+    let len = data.len_bit() / count.get();
+
+    (0..count.get())
+        .map(|idx| data.slice(idx * len..(idx + 1) * len))
+        .collect()
+}
+
+/// A warning that a test is run on an input that is long enough to run the test, but shorter than
+/// [recommended by NIST](get_recommended_length_for_test). The result of the test is valid, but
+/// less reliable. See [length_warnings].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LengthWarning {
+    test: Test,
+    length: usize,
+    recommended: NonZero<usize>,
+}
+
+impl LengthWarning {
+    /// The test that is run on the short input.
+    pub fn test(&self) -> Test {
+        self.test
+    }
+
+    /// The length of the input, in bits.
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// The input length recommended for the test, in bits.
+    pub fn recommended(&self) -> NonZero<usize> {
+        self.recommended
+    }
+}
+
+impl Display for LengthWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The input length of {} bits is shorter than the {} bits recommended for the test {}, \
+            the result is less reliable.",
+            self.length, self.recommended, self.test
+        )
+    }
+}
+
+/// Checks the given tests against the length of the input, in bits, before running them with one
+/// of the runner functions: returns a [warning](LengthWarning) for each test that can be run on
+/// the input, but with less than the [recommended length](get_recommended_length_for_test).
+///
+/// Tests for which the input is shorter than the [minimum length](get_min_length_for_test) are
+/// not part of the warnings, since the runner functions reject them, see [validate_tests].
+///
+/// ```
+/// use sts_lib::test_runner::length_warnings;
+/// use sts_lib::Test;
+///
+/// let warnings = length_warnings(100_000, [Test::Frequency, Test::MaurersUniversalStatistical]);
+/// assert_eq!(warnings.len(), 1);
+/// assert_eq!(warnings[0].test(), Test::MaurersUniversalStatistical);
+/// assert_eq!(warnings[0].recommended().get(), 387_840);
+/// ```
+pub fn length_warnings(
+    len_bit: usize,
+    tests: impl IntoIterator<Item = Test>,
+) -> Vec<LengthWarning> {
+    tests
+        .into_iter()
+        .filter_map(|test| {
+            let min = get_min_length_for_test(test);
+            let recommended = get_recommended_length_for_test(test);
+
+            (min.get() <= len_bit && len_bit < recommended.get()).then_some(LengthWarning {
+                test,
+                length: len_bit,
+                recommended,
+            })
+        })
+        .collect()
+}
+
+/// Checks the given tests before running them on an input with the given length in bits, with
+/// the same checks the runner functions do before executing anything: each test may only be
+/// given once, the input must be at least as long as the [minimum length](get_min_length_for_test)
+/// (or the minimum length of the chosen configuration for the longest run of ones and the binary
+/// matrix rank test), and the [arguments](TestArgs) must work with the input length.
+///
+/// For the runner functions working on sub-sequences, the checks are done with the length of
+/// each sub-sequence. All problems are returned at once, see [RunnerError::problems].
+///
+/// ```
+/// use sts_lib::test_runner::{validate_tests, RunnerError};
+/// use sts_lib::{Test, TestArgs};
+///
+/// let tests = [Test::Frequency, Test::LinearComplexity, Test::Frequency];
+/// let error = validate_tests(1000, tests, TestArgs::default()).unwrap_err();
+///
+/// assert_eq!(
+///     error.problems(),
+///     [
+///         RunnerError::InputTooShort {
+///             test: Test::LinearComplexity,
+///             required: 1_000_000,
+///             actual: 1000
+///         },
+///         RunnerError::DuplicateTest(Test::Frequency),
+///     ]
+/// );
+/// ```
+pub fn validate_tests(
+    len_bit: usize,
+    tests: impl IntoIterator<Item = Test>,
+    args: TestArgs,
+) -> Result<(), RunnerError> {
+    checked_tests(tests.into_iter(), len_bit, args).map(|_| ())
+}
+
+/// Checks the given tests for duplicates, returns the unique tests. All duplicates are returned at
+/// once.
+fn unique_tests(tests: impl Iterator<Item = Test>) -> Result<HashSet<Test>, RunnerError> {
+    let mut unique_tests = HashSet::with_capacity(tests.size_hint().0);
+
+    let duplicates = tests
+        .filter(|&test| !unique_tests.insert(test))
+        .map(RunnerError::DuplicateTest)
+        .collect();
+
+    match RunnerError::from_problems(duplicates) {
+        Some(error) => Err(error),
+        None => Ok(unique_tests),
+    }
+}
+
+/// Checks the given tests for duplicates and against the input length, see [validate_tests].
+/// Returns the unique tests.
+fn checked_tests(
+    tests: impl Iterator<Item = Test>,
+    len_bit: usize,
+    args: TestArgs,
+) -> Result<HashSet<Test>, RunnerError> {
+    let mut unique_tests = HashSet::with_capacity(tests.size_hint().0);
+    let mut problems = Vec::new();
+
+    for test in tests {
+        if unique_tests.insert(test) {
+            problems.extend(check_test(test, len_bit, args));
+        } else {
+            problems.push(RunnerError::DuplicateTest(test));
+        }
+    }
+
+    match RunnerError::from_problems(problems) {
+        Some(error) => Err(error),
+        None => Ok(unique_tests),
+    }
+}
+
+/// Checks the input length and the arguments of one test, mirroring the checks the test does
+/// when it is run.
+fn check_test(test: Test, len_bit: usize, args: TestArgs) -> Option<RunnerError> {
+    let required = match test {
+        // depend on the chosen configuration
+        Test::LongestRunOfOnes => args.longest_run_of_ones.min_input_length(),
+        Test::BinaryMatrixRank => args.binary_matrix_rank.min_input_length(),
+        test => get_min_length_for_test(test),
+    }
+    .get();
+
+    if len_bit < required {
+        return Some(RunnerError::InputTooShort {
+            test,
+            required,
+            actual: len_bit,
+        });
+    }
+
+    let reason = match test {
+        Test::FrequencyWithinABlock => match args.frequency_block {
+            FrequencyBlockTestArg::Manual(block_length) if block_length.get() > len_bit => Some(
+                format!("the block length {block_length} is greater than the input length"),
+            ),
+            _ => None,
+        },
+        Test::NonOverlappingTemplateMatching => {
+            let arg = args.non_overlapping_template;
+            let block_length = len_bit / arg.count_blocks();
+            let template_len = arg.templates().template_len();
+            (block_length < template_len).then(|| {
+                format!(
+                    "the calculated block length {block_length} is smaller than the template \
+                     length {template_len}"
+                )
+            })
+        }
+        Test::OverlappingTemplateMatching => {
+            let arg = args.overlapping_template;
+            if arg.block_length() < arg.template_length() {
+                Some(format!(
+                    "the block length {} is smaller than the template length {}",
+                    arg.block_length(),
+                    arg.template_length()
+                ))
+            } else if arg.block_length() > len_bit {
+                Some(format!(
+                    "the block length {} is greater than the input length",
+                    arg.block_length()
+                ))
+            } else {
+                None
+            }
+        }
+        Test::LinearComplexity => match args.linear_complexity {
+            LinearComplexityTestArg::ManualBlockLength(block_length) => {
+                let block_length = block_length.get();
+                if !(500..=5000).contains(&block_length) {
+                    Some(format!(
+                        "the block length must be between 500 and 5000, is: {block_length}"
+                    ))
+                } else if len_bit / block_length < 200 {
+                    Some(format!(
+                        "the block length {block_length} leads to fewer than 200 blocks"
+                    ))
+                } else {
+                    None
+                }
+            }
+            LinearComplexityTestArg::ChooseAutomatically => None,
+        },
+        Test::Serial => {
+            let block_length = args.serial.block_length() as usize;
+            let max_block_length = (len_bit.ilog2() as usize).saturating_sub(2);
+            (block_length >= max_block_length).then(|| {
+                format!(
+                    "the block length must be less than log2(len_bit) - 2 (={max_block_length}), \
+                     is: {block_length}"
+                )
+            })
+        }
+        Test::ApproximateEntropy => {
+            let block_length = args.approximate_entropy.block_length() as usize;
+            let max_block_length = (len_bit.ilog2() as usize).saturating_sub(5);
+            (block_length >= max_block_length).then(|| {
+                format!(
+                    "the block length must be less than log2(len_bit) - 5 (={max_block_length}), \
+                     is: {block_length}"
+                )
+            })
+        }
+        Test::Autocorrelation => {
+            let shift = args.autocorrelation.shift();
+            (shift > len_bit / 2).then(|| {
+                format!(
+                    "the shift must be <= len_bit / 2 (={}), is: {shift}",
+                    len_bit / 2
+                )
+            })
+        }
+        _ => None,
+    };
+
+    reason.map(|reason| RunnerError::InvalidArgCombination { test, reason })
+}
+
+/// Runs the test inside the thread pool, with the token and the [timeout](TestArgs::timeout)
+/// available for the test to check.
+#[use_thread_pool]
+fn run_test_with_token(
+    test: Test,
+    data: &BitVec,
+    args: TestArgs,
+    token: Option<&CancellationToken>,
+) -> (Test, Result<Vec<TestResult>, Error>) {
+    with_check(token, args.timeout, || execute_test(test, data, args))
+}
+
+/// Runs the test, applying the [timeout](TestArgs::timeout) if there is one.
+fn run_test(test: Test, data: &BitVec, args: TestArgs) -> (Test, Result<Vec<TestResult>, Error>) {
+    match args.timeout {
+        Some(_) => run_test_with_token(test, data, args, None),
+        None => execute_test(test, data, args),
+    }
+}
+
+/// internally used function to run the test and store the result.
+fn execute_test(
+    test: Test,
+    data: &BitVec,
+    args: TestArgs,
+) -> (Test, Result<Vec<TestResult>, Error>) {
+    let result = match test {
+        Test::Frequency => frequency::frequency_test(data),
+        Test::FrequencyWithinABlock => {
+            frequency_block::frequency_block_test(data, args.frequency_block)
+        }
+        Test::Runs => runs::runs_test(data),
+        Test::LongestRunOfOnes => {
+            longest_run_of_ones::longest_run_of_ones_test(data, args.longest_run_of_ones)
+        }
+        Test::BinaryMatrixRank => {
+            binary_matrix_rank::binary_matrix_rank_test(data, args.binary_matrix_rank)
+        }
+        Test::SpectralDft => spectral_dft::spectral_dft_test(data, args.spectral_dft),
+        // early return for the few tests that give multiple results
+        Test::NonOverlappingTemplateMatching => {
+            return (
+                test,
+                non_overlapping::non_overlapping_template_matching_test(
+                    data,
+                    args.non_overlapping_template,
+                ),
+            );
+        }
+        Test::OverlappingTemplateMatching => {
+            overlapping::overlapping_template_matching_test(data, args.overlapping_template)
+        }
+        Test::MaurersUniversalStatistical => {
+            maurers_universal_statistical::maurers_universal_statistical_test(data)
+        }
+        Test::LinearComplexity => {
+            linear_complexity::linear_complexity_test(data, args.linear_complexity)
+        }
+        Test::Serial => return (test, serial::serial_test(data, args.serial).map(From::from)),
+        Test::ApproximateEntropy => {
+            approximate_entropy::approximate_entropy_test(data, args.approximate_entropy)
+        }
+        Test::CumulativeSums => {
+            return (
+                test,
+                cumulative_sums::cumulative_sums_test(data).map(From::from),
+            )
+        }
+        Test::RandomExcursions => {
+            return (
+                test,
+                random_excursions::random_excursions_test(data, args.random_excursions),
+            )
+        }
+        Test::RandomExcursionsVariant => {
+            return (
+                test,
+                random_excursions_variant::random_excursions_variant_test(
+                    data,
+                    args.random_excursions_variant,
+                ),
+            )
+        }
+        Test::LempelZiv => lempel_ziv::lempel_ziv_test(data),
+        Test::Autocorrelation => autocorrelation::autocorrelation_test(data, args.autocorrelation),
+    };
+
+    (test, result.map(|res| vec![res]))
+}
@@ -0,0 +1,500 @@
+//! Running tests over multiple sequences and analysing the results as a whole, like the final
+//! analysis report of the NIST reference implementation (`finalAnalysisReport.txt`).
+//!
+//! For each test (and each result of tests with multiple results), two statistics are calculated
+//! over all sequences, as described in section 4.2 of NIST SP 800-22r1a:
+//! 1. The proportion of sequences passing the test. With the threshold *α*, the proportion should
+//!    lie within `p ± 3 * sqrt(p * (1 - p) / m)`, with `p = 1 - α` and *m* being the sample size.
+//! 2. The uniformity of the distribution of the P-values. The P-values are sorted into 10 bins
+//!    and a chi-square test is done on the bin frequencies, resulting in a P-value of the P-values.
+//!    The distribution is considered uniform if this P-value is at least [UNIFORMITY_THRESHOLD].
+//!    NIST recommends a sample size of at least 55 sequences for this statistic.
+
+use crate::analysis::{p_value_histogram, uniformity_chi_square, ResultProportion, TestVerdict};
+use crate::bitvec::BitVec;
+use crate::test_runner::custom::{
+    check_unique_names, run_custom_test, CustomRunnerError, CustomTest,
+};
+use crate::test_runner::{run_test, unique_tests, RunnerError, StsContext};
+use crate::{Error, ResultLabel, Test, TestArgs, TestResult};
+use std::borrow::Cow;
+use std::sync::Arc;
+
+/// The count of bins the P-values are sorted into for the uniformity statistic.
+pub const BIN_COUNT: usize = 10;
+
+/// The threshold for the P-value of the uniformity statistic, as recommended by NIST.
+pub const UNIFORMITY_THRESHOLD: f64 = 0.0001;
+
+/// A test runner that runs the same tests over multiple sequences and collects the results, to
+/// create a [final analysis](FinalAnalysis) over all sequences.
+///
+/// Sequences are added one after another, with [Self::run_sequence] or [Self::run_sequences],
+/// meaning that the sequences don't have to be held in memory at the same time.
+///
+/// The tests run in the global thread pool of the library, or in the thread pool of a
+/// [context](StsContext), see [Self::with_context].
+///
+/// [Custom tests](CustomTest) can be added with [Self::with_custom_tests], they are run with
+/// [Self::run_custom_sequence] and analysed separately, identified by their name.
+#[derive(Clone, Debug)]
+pub struct MultiSequenceRunner {
+    args: TestArgs,
+    collected: Vec<CollectedResults>,
+    custom_tests: Vec<Arc<dyn CustomTest>>,
+    /// The results of the custom tests, in the same order as `custom_tests`.
+    custom_collected: Vec<CollectedResults<&'static str>>,
+    context: Option<StsContext>,
+}
+
+/// The result of one test on one sequence that was recorded earlier, e.g. read back from the
+/// output of a previous run. See [MultiSequenceRunner::add_recorded].
+#[derive(Clone, Debug, PartialEq)]
+pub enum RecordedResult {
+    /// The test returned results with the given P-values, one for each result index.
+    PValues(Vec<f64>),
+    /// The test was [not applicable](crate::ResultStatus::NotApplicable) to the sequence.
+    NotApplicable,
+    /// The test returned an error.
+    Error,
+}
+
+/// All results of one test over all sequences.
+#[derive(Clone, Debug)]
+struct CollectedResults<T = Test> {
+    test: T,
+    /// The P-values, for each result index: all P-values of all sequences.
+    p_values: Vec<Vec<f64>>,
+    /// The comment for each result index, taken from the first sequence.
+    comments: Vec<Option<Cow<'static, str>>>,
+    /// The label for each result index, taken from the first sequence.
+    labels: Vec<Option<ResultLabel>>,
+    /// How many sequences returned an error for the test.
+    error_count: usize,
+    /// How many sequences the test was not applicable to.
+    not_applicable_count: usize,
+}
+
+impl<T: Copy> CollectedResults<T> {
+    /// No results of the given test yet.
+    fn new(test: T) -> Self {
+        Self {
+            test,
+            p_values: Vec::new(),
+            comments: Vec::new(),
+            labels: Vec::new(),
+            error_count: 0,
+            not_applicable_count: 0,
+        }
+    }
+
+    /// Adds the result of one sequence.
+    fn add(&mut self, result: &Result<Vec<TestResult>, Error>) {
+        match result {
+            Ok(results) if results.iter().any(|result| !result.is_applicable()) => {
+                self.not_applicable_count += 1
+            }
+            Ok(results) => {
+                for (idx, result) in results.iter().enumerate() {
+                    if idx == self.p_values.len() {
+                        self.p_values.push(Vec::new());
+                        self.comments.push(result.comment.clone());
+                        self.labels.push(result.label());
+                    }
+
+                    self.p_values[idx].push(result.p_value());
+                }
+            }
+            Err(_) => self.error_count += 1,
+        }
+    }
+
+    /// Adds the recorded result of one sequence. The comments and labels are not recorded.
+    fn add_recorded(&mut self, result: &RecordedResult) {
+        match result {
+            RecordedResult::PValues(p_values) => {
+                for (idx, &p_value) in p_values.iter().enumerate() {
+                    if idx == self.p_values.len() {
+                        self.p_values.push(Vec::new());
+                        self.comments.push(None);
+                        self.labels.push(None);
+                    }
+
+                    self.p_values[idx].push(p_value);
+                }
+            }
+            RecordedResult::NotApplicable => self.not_applicable_count += 1,
+            RecordedResult::Error => self.error_count += 1,
+        }
+    }
+
+    /// Appends the final analysis of each result index to `analysis`, see
+    /// [MultiSequenceRunner::final_analysis]. The proportions are taken from the
+    /// [verdict](Self::verdict).
+    fn final_analysis(
+        &self,
+        threshold: f64,
+        analysis: &mut Vec<FinalAnalysis<T>>,
+    ) -> Result<(), Error> {
+        if self.p_values.is_empty() && (self.error_count > 0 || self.not_applicable_count > 0) {
+            analysis.push(FinalAnalysis {
+                test: self.test,
+                comment: None,
+                label: None,
+                histogram: [0; BIN_COUNT],
+                proportion: ResultProportion::from_p_values(0, &[], threshold),
+                error_count: self.error_count,
+                not_applicable_count: self.not_applicable_count,
+                uniformity_p_value: None,
+            });
+            return Ok(());
+        }
+
+        let verdict = self.verdict(threshold);
+        for (((p_values, comment), &label), &proportion) in self
+            .p_values
+            .iter()
+            .zip(&self.comments)
+            .zip(&self.labels)
+            .zip(verdict.results())
+        {
+            let histogram = p_value_histogram(p_values);
+            let (_, uniformity_p_value) = uniformity_chi_square(&histogram, p_values.len())?;
+
+            analysis.push(FinalAnalysis {
+                test: self.test,
+                comment: comment.clone(),
+                label,
+                histogram,
+                proportion,
+                error_count: self.error_count,
+                not_applicable_count: self.not_applicable_count,
+                uniformity_p_value: Some(uniformity_p_value),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// The verdict over all sequences, see [MultiSequenceRunner::verdicts].
+    fn verdict(&self, threshold: f64) -> TestVerdict<T> {
+        TestVerdict::from_p_values(
+            self.test,
+            &self.p_values,
+            self.not_applicable_count,
+            threshold,
+        )
+    }
+}
+
+impl MultiSequenceRunner {
+    /// Creates a new runner for the given tests, with the used arguments taken from the passed
+    /// [args](TestArgs).
+    ///
+    /// Only unique tests may be passed.
+    pub fn new(tests: impl Iterator<Item = Test>, args: TestArgs) -> Result<Self, RunnerError> {
+        // check for duplicate tests, the length of the sequences is not known yet.
+        let unique_tests = unique_tests(tests)?;
+
+        // use a stable order: the test number
+        let mut tests = unique_tests.into_iter().collect::<Vec<_>>();
+        tests.sort_unstable_by_key(|&test| test as u8);
+
+        let collected = tests.into_iter().map(CollectedResults::new).collect();
+
+        Ok(Self {
+            args,
+            collected,
+            custom_tests: Vec::new(),
+            custom_collected: Vec::new(),
+            context: None,
+        })
+    }
+
+    /// Adds the given [custom tests](CustomTest), which are run with the same arguments by
+    /// [Self::run_custom_sequence].
+    ///
+    /// Only tests with unique names may be passed, including the custom tests added before.
+    pub fn with_custom_tests(
+        mut self,
+        tests: impl IntoIterator<Item = Arc<dyn CustomTest>>,
+    ) -> Result<Self, CustomRunnerError> {
+        let tests = tests.into_iter().collect::<Vec<_>>();
+        check_unique_names(
+            self.custom_tests
+                .iter()
+                .chain(&tests)
+                .map(|test| test.name()),
+        )?;
+
+        self.custom_collected
+            .extend(tests.iter().map(|test| CollectedResults::new(test.name())));
+        self.custom_tests.extend(tests);
+
+        Ok(self)
+    }
+
+    /// Runs all tests in the thread pool of the given [context](StsContext) instead of the
+    /// global thread pool.
+    pub fn with_context(mut self, context: StsContext) -> Self {
+        self.context = Some(context);
+        self
+    }
+
+    /// The tests this runner runs on each sequence, in the order they are run.
+    pub fn tests(&self) -> impl Iterator<Item = Test> + '_ {
+        self.collected.iter().map(|collected| collected.test)
+    }
+
+    /// The names of the [custom tests](CustomTest) this runner runs on each sequence, in the order
+    /// they are run.
+    pub fn custom_tests(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.custom_tests.iter().map(|test| test.name())
+    }
+
+    /// Runs all tests on the given sequence. The returned iterator is evaluated lazily: each test
+    /// is only run when `.next()` is called. Only results that were returned by the iterator are
+    /// collected for the final analysis.
+    pub fn run_sequence<'a>(
+        &'a mut self,
+        data: &'a BitVec,
+    ) -> impl Iterator<Item = (Test, Result<Vec<TestResult>, Error>)> + 'a {
+        let args = self.args;
+        let context = self.context.as_ref();
+
+        self.collected.iter_mut().map(move |collected| {
+            let (test, result) = match context {
+                Some(context) => context.install(|| run_test(collected.test, data, args)),
+                None => run_test(collected.test, data, args),
+            };
+            collected.add(&result);
+            (test, result)
+        })
+    }
+
+    /// Runs all [custom tests](CustomTest) on the given sequence, like [Self::run_sequence]. The
+    /// results are returned by the name of the test.
+    pub fn run_custom_sequence<'a>(
+        &'a mut self,
+        data: &'a BitVec,
+    ) -> impl Iterator<Item = (&'static str, Result<Vec<TestResult>, Error>)> + 'a {
+        let args = self.args;
+        let context = self.context.as_ref();
+
+        self.custom_tests
+            .iter()
+            .zip(&mut self.custom_collected)
+            .map(move |(test, collected)| {
+                let test = test.as_ref();
+                let result = match context {
+                    Some(context) => context.install(|| run_custom_test(test, data, args, None)),
+                    None => run_custom_test(test, data, args, None),
+                };
+                collected.add(&result);
+                (test.name(), result)
+            })
+    }
+
+    /// Runs all tests and [custom tests](CustomTest) on all given sequences, only collecting the
+    /// results for the final analysis.
+    pub fn run_sequences<'a>(
+        &mut self,
+        sequences: impl IntoIterator<Item = impl AsRef<BitVec<'a>>>,
+    ) {
+        for sequence in sequences {
+            self.run_sequence(sequence.as_ref()).for_each(drop);
+            self.run_custom_sequence(sequence.as_ref()).for_each(drop);
+        }
+    }
+
+    /// Adds a previously recorded result of the given test on one sequence, e.g. read back from the
+    /// output of an earlier run, so that the final analysis can be created without running the
+    /// tests again. Recorded results and results of [Self::run_sequence] can be mixed.
+    ///
+    /// Returns `false` if the given test is not run by this runner, the result is ignored in this
+    /// case.
+    pub fn add_recorded(&mut self, test: Test, result: &RecordedResult) -> bool {
+        match self
+            .collected
+            .iter_mut()
+            .find(|collected| collected.test == test)
+        {
+            Some(collected) => {
+                collected.add_recorded(result);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Adds a previously recorded result of the [custom test](CustomTest) with the given name on
+    /// one sequence, like [Self::add_recorded].
+    ///
+    /// Returns `false` if the given custom test is not run by this runner, the result is ignored
+    /// in this case.
+    pub fn add_recorded_custom(&mut self, name: &str, result: &RecordedResult) -> bool {
+        match self
+            .custom_collected
+            .iter_mut()
+            .find(|collected| collected.test == name)
+        {
+            Some(collected) => {
+                collected.add_recorded(result);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Creates the final analysis over all sequences run so far. One entry is created for each
+    /// result of each test, ordered by the test number and the result index. If a test only
+    /// returned errors or was not applicable to any sequence, one entry with a sample size of 0 is
+    /// created for it.
+    ///
+    /// The given `threshold` is used to determine if a single result passed, see
+    /// [TestResult::passed].
+    pub fn final_analysis(&self, threshold: f64) -> Result<Vec<FinalAnalysis>, Error> {
+        let mut analysis = Vec::new();
+        for collected in &self.collected {
+            collected.final_analysis(threshold, &mut analysis)?;
+        }
+
+        Ok(analysis)
+    }
+
+    /// Creates the final analysis of the [custom tests](CustomTest) over all sequences run so far,
+    /// like [Self::final_analysis], ordered by the order the custom tests were added.
+    pub fn custom_final_analysis(
+        &self,
+        threshold: f64,
+    ) -> Result<Vec<FinalAnalysis<&'static str>>, Error> {
+        let mut analysis = Vec::new();
+        for collected in &self.custom_collected {
+            collected.final_analysis(threshold, &mut analysis)?;
+        }
+
+        Ok(analysis)
+    }
+
+    /// Creates the [verdict](TestVerdict) of each test over all sequences run so far, ordered by
+    /// the test number. Sequences where the test returned an error are not part of the sample.
+    ///
+    /// The given `threshold` is used to determine if a single result passed, see
+    /// [TestResult::passed].
+    pub fn verdicts(&self, threshold: f64) -> Vec<TestVerdict> {
+        self.collected
+            .iter()
+            .map(|collected| collected.verdict(threshold))
+            .collect()
+    }
+
+    /// Creates the [verdict](TestVerdict) of each [custom test](CustomTest) over all sequences run
+    /// so far, like [Self::verdicts], ordered by the order the custom tests were added.
+    pub fn custom_verdicts(&self, threshold: f64) -> Vec<TestVerdict<&'static str>> {
+        self.custom_collected
+            .iter()
+            .map(|collected| collected.verdict(threshold))
+            .collect()
+    }
+}
+
+/// The final analysis of one result of one test over multiple sequences. The test is identified
+/// by `T`: [Test], or the name of a [custom test](CustomTest).
+/// See the [module docs](crate::test_runner::multi_sequence).
+///
+/// The proportion of passed sequences is the [ResultProportion] of the [verdict](TestVerdict) of
+/// the test, completed by the uniformity of the P-values.
+#[derive(Clone, Debug)]
+pub struct FinalAnalysis<T = Test> {
+    test: T,
+    comment: Option<Cow<'static, str>>,
+    label: Option<ResultLabel>,
+    histogram: [usize; BIN_COUNT],
+    proportion: ResultProportion,
+    error_count: usize,
+    not_applicable_count: usize,
+    uniformity_p_value: Option<f64>,
+}
+
+impl<T: Copy> FinalAnalysis<T> {
+    /// The test this analysis is about.
+    pub fn test(&self) -> T {
+        self.test
+    }
+
+    /// The index of the result, for tests that return multiple results.
+    pub fn result_idx(&self) -> usize {
+        self.proportion.result_idx()
+    }
+
+    /// The proportion of sequences that passed this result, as in the [verdict](TestVerdict) of
+    /// the test.
+    pub fn result_proportion(&self) -> &ResultProportion {
+        &self.proportion
+    }
+
+    /// The comment of the analysed result, see [TestResult::comment].
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    /// The label of the analysed result, see [TestResult::label].
+    pub fn label(&self) -> Option<ResultLabel> {
+        self.label
+    }
+
+    /// The frequencies of the P-values in each of the 10 bins `[0.0, 0.1)`, ..., `[0.9, 1.0]`.
+    pub fn histogram(&self) -> [usize; BIN_COUNT] {
+        self.histogram
+    }
+
+    /// The count of sequences that returned a result.
+    pub fn sample_size(&self) -> usize {
+        self.proportion.sample_size()
+    }
+
+    /// The count of sequences that passed the test.
+    pub fn passed_count(&self) -> usize {
+        self.proportion.passed_count()
+    }
+
+    /// The count of sequences where the test returned an error. These are not part of the
+    /// sample.
+    pub fn error_count(&self) -> usize {
+        self.error_count
+    }
+
+    /// The count of sequences the test was [not applicable](crate::ResultStatus::NotApplicable)
+    /// to. These are not part of the sample.
+    pub fn not_applicable_count(&self) -> usize {
+        self.not_applicable_count
+    }
+
+    /// The proportion of sequences that passed the test. Is 0 if the sample size is 0.
+    pub fn proportion(&self) -> f64 {
+        self.proportion.proportion()
+    }
+
+    /// The range of acceptable proportions (min, max), based on the threshold and the sample size,
+    /// see [ResultProportion::acceptance_interval].
+    pub fn proportion_range(&self) -> (f64, f64) {
+        self.proportion.acceptance_interval()
+    }
+
+    /// If the proportion of passed sequences lies within the [acceptable range](Self::proportion_range).
+    pub fn proportion_passed(&self) -> bool {
+        self.proportion.passed()
+    }
+
+    /// The P-value of the uniformity of the P-values. Is `None` if the sample size is 0.
+    pub fn uniformity_p_value(&self) -> Option<f64> {
+        self.uniformity_p_value
+    }
+
+    /// If the P-values are uniformly distributed, i.e. if the
+    /// [uniformity P-value](Self::uniformity_p_value) is at least [UNIFORMITY_THRESHOLD].
+    pub fn uniformity_passed(&self) -> bool {
+        self.uniformity_p_value
+            .is_some_and(|p_value| p_value >= UNIFORMITY_THRESHOLD)
+    }
+}
@@ -0,0 +1,354 @@
+//! Planning of a test run: which tests would run on an input of a given length, with which
+//! parameters and roughly how many resources they need - without running any test.
+//!
+//! The durations are estimated from simple models of the complexity of each test, see
+//! [estimate_duration]. To adapt them to the current machine, a short [Calibration] run can be
+//! done first.
+
+use crate::bitvec::BitVec;
+use crate::test_runner::subsample::SplitMix64;
+use crate::test_runner::{check_test, run_tests, unique_tests, RunnerError};
+use crate::tests::linear_complexity::LinearComplexityTestArg;
+use crate::tests::maurers_universal_statistical;
+use crate::tests::template_matching::non_overlapping;
+use crate::{Test, TestArgs};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// The input lengths a test is calibrated on, the first one the test can run on is used.
+const CALIBRATION_LENGTHS: [usize; 2] = [100_000, 1_000_000];
+
+/// The minimum time each test is repeated for during the calibration, to reduce the influence of
+/// the timer resolution and of the setup of the runner.
+const MIN_CALIBRATION_TIME: Duration = Duration::from_millis(20);
+
+/// The maximum count of repetitions of each test during the calibration.
+const MAX_CALIBRATION_RUNS: u32 = 100;
+
+/// The plan for one test on an input of a given length, see [plan_tests].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlannedTest {
+    test: Test,
+    skip_reason: Option<RunnerError>,
+    block_length: Option<usize>,
+    estimated_memory: usize,
+    estimated_duration: Duration,
+}
+
+impl PlannedTest {
+    /// The planned test.
+    pub fn test(&self) -> Test {
+        self.test
+    }
+
+    /// If the test would run, i.e. if the input is long enough and works with the test arguments.
+    pub fn will_run(&self) -> bool {
+        self.skip_reason.is_none()
+    }
+
+    /// Why the test would be skipped: either [RunnerError::InputTooShort] or
+    /// [RunnerError::InvalidArgCombination]. `None` if the test would run.
+    pub fn skip_reason(&self) -> Option<&RunnerError> {
+        self.skip_reason.as_ref()
+    }
+
+    /// The block length, in bits, the test would use for the input: the block length given in the
+    /// test arguments, or the block length chosen for the input length if it is chosen
+    /// automatically. `None` for tests without blocks.
+    pub fn block_length(&self) -> Option<usize> {
+        self.block_length
+    }
+
+    /// The estimated memory the test needs in addition to the input, in bytes. Only the large
+    /// buffers are counted, e.g. the FFT buffer of the spectral DFT test.
+    pub fn estimated_memory(&self) -> usize {
+        self.estimated_memory
+    }
+
+    /// A rough estimate of the time the test takes on a single thread, see [estimate_duration].
+    /// Most tests are parallelized, so with multiple threads they usually finish sooner. Zero if
+    /// the test would be skipped.
+    pub fn estimated_duration(&self) -> Duration {
+        self.estimated_duration
+    }
+}
+
+/// Plans running the given tests on an input with the given length in bits, with the used
+/// arguments taken from the passed [args](TestArgs), without running anything.
+///
+/// Returns one [PlannedTest] per test, in the given order: if the test would run or why it would
+/// be skipped (the input is too short or doesn't work with the test arguments, see
+/// [validate_tests](super::validate_tests)), the effective block length and rough estimates of
+/// the memory and time needed. Only duplicate tests are an error.
+///
+/// ```
+/// use sts_lib::test_runner::plan_tests;
+/// use sts_lib::{Test, TestArgs};
+///
+/// let plan = plan_tests(
+///     100_000,
+///     [Test::FrequencyWithinABlock, Test::LinearComplexity],
+///     TestArgs::default(),
+/// )
+/// .unwrap();
+///
+/// assert!(plan[0].will_run());
+/// assert_eq!(plan[0].block_length(), Some(1024));
+/// // the linear complexity test requires 10^6 bits
+/// assert!(!plan[1].will_run());
+/// ```
+pub fn plan_tests(
+    len_bit: usize,
+    tests: impl IntoIterator<Item = Test>,
+    args: TestArgs,
+) -> Result<Vec<PlannedTest>, RunnerError> {
+    let tests = tests.into_iter().collect::<Vec<_>>();
+    unique_tests(tests.iter().copied())?;
+
+    let plan = tests
+        .into_iter()
+        .map(|test| match check_test(test, len_bit, args) {
+            Some(skip_reason) => PlannedTest {
+                test,
+                skip_reason: Some(skip_reason),
+                block_length: None,
+                estimated_memory: 0,
+                estimated_duration: Duration::ZERO,
+            },
+            None => PlannedTest {
+                test,
+                skip_reason: None,
+                block_length: block_length(test, len_bit, args),
+                estimated_memory: estimated_memory(test, len_bit, args),
+                estimated_duration: estimate_duration(test, len_bit, &args),
+            },
+        })
+        .collect();
+
+    Ok(plan)
+}
+
+/// The effective block length of the test, see [PlannedTest::block_length].
+fn block_length(test: Test, len_bit: usize, args: TestArgs) -> Option<usize> {
+    match test {
+        Test::FrequencyWithinABlock => Some(args.frequency_block.block_length(len_bit)),
+        Test::LongestRunOfOnes => Some(args.longest_run_of_ones.block_length_for(len_bit)),
+        Test::NonOverlappingTemplateMatching => {
+            Some(len_bit / args.non_overlapping_template.count_blocks())
+        }
+        Test::OverlappingTemplateMatching => Some(args.overlapping_template.block_length()),
+        Test::MaurersUniversalStatistical => {
+            maurers_universal_statistical::choose_block_length(len_bit)
+        }
+        Test::LinearComplexity => match args.linear_complexity {
+            LinearComplexityTestArg::ManualBlockLength(block_length) => Some(block_length.get()),
+            LinearComplexityTestArg::ChooseAutomatically => Some(512),
+        },
+        Test::Serial => Some(args.serial.block_length() as usize),
+        Test::ApproximateEntropy => Some(args.approximate_entropy.block_length() as usize),
+        Test::Frequency
+        | Test::Runs
+        | Test::BinaryMatrixRank
+        | Test::SpectralDft
+        | Test::CumulativeSums
+        | Test::RandomExcursions
+        | Test::RandomExcursionsVariant
+        | Test::LempelZiv
+        | Test::Autocorrelation => None,
+    }
+}
+
+/// The memory of the large buffers of the test, in bytes, see [PlannedTest::estimated_memory].
+fn estimated_memory(test: Test, len_bit: usize, args: TestArgs) -> usize {
+    match test {
+        // one complex f32 per 2 bits (real-input FFT), or per bit for odd lengths
+        Test::SpectralDft if len_bit % 2 == 0 => len_bit * 4,
+        Test::SpectralDft => len_bit * 8,
+        // the lookup table of the templates and the count of matches per template and block
+        Test::NonOverlappingTemplateMatching => {
+            let arg = args.non_overlapping_template;
+            let template_len = arg.templates().template_len();
+            let table = if template_len <= non_overlapping::MAX_TABLE_TEMPLATE_LENGTH {
+                (1 << template_len) * 4
+            } else {
+                0
+            };
+            table + arg.templates().templates().len() * arg.count_blocks() * 8
+        }
+        // the table with the last occurrence of each block, the table of log2 with 8 entries per
+        // block and the first and last occurrences of each block in each chunk
+        Test::MaurersUniversalStatistical => {
+            maurers_universal_statistical::choose_block_length(len_bit).map_or(0, |l| {
+                let test_blocks = (len_bit / l).saturating_sub(10 << l);
+                let chunks = test_blocks
+                    .div_ceil(maurers_universal_statistical::chunk_length(l, test_blocks));
+                (1 << l) * 8 * (1 + 8 + 2 * chunks)
+            })
+        }
+        // the frequency of each pattern of m bits, plus the folded frequencies of m - 1 and m - 2
+        // bits
+        Test::Serial => (1 << args.serial.block_length()) * 8 * 2,
+        Test::ApproximateEntropy => (1 << args.approximate_entropy.block_length()) * 8 * 2,
+        // the linear complexity of each block
+        Test::LinearComplexity => len_bit / block_length(test, len_bit, args).unwrap_or(512) * 8,
+        _ => 0,
+    }
+}
+
+/// Estimates how long the test takes on a single thread, on an input of the given length in bits
+/// with the given arguments.
+///
+/// The estimate is calculated from a simple model of the complexity of each test (e.g.
+/// `O(n log n)` for the spectral DFT test, `O(n * M)` for the linear complexity test with block
+/// length `M`), with the costs per bit measured on a current x86_64 CPU with inputs of 10^6 and
+/// 10^7 bits. On other machines, the real durations can differ by a large factor - use a
+/// [Calibration] to adapt the estimates to the current machine.
+///
+/// The estimate is calculated even if the test cannot run on the input, see
+/// [validate_tests](super::validate_tests).
+///
+/// ```
+/// use sts_lib::test_runner::plan::estimate_duration;
+/// use sts_lib::{Test, TestArgs};
+///
+/// let args = TestArgs::default();
+/// let short = estimate_duration(Test::SpectralDft, 1_000_000, &args);
+/// let long = estimate_duration(Test::SpectralDft, 10_000_000, &args);
+/// assert!(long > short * 10);
+/// ```
+pub fn estimate_duration(test: Test, len_bit: usize, args: &TestArgs) -> Duration {
+    let args = *args;
+    let n = len_bit as f64;
+
+    let nanos = match test {
+        Test::Frequency => 0.01 * n,
+        Test::FrequencyWithinABlock => 0.2 * n,
+        Test::Runs => 0.03 * n,
+        Test::LongestRunOfOnes => 7.0 * n,
+        Test::BinaryMatrixRank => 1.0 * n,
+        // O(n log n)
+        Test::SpectralDft => 0.4 * n * n.log2(),
+        // proportional to the count of templates, 148 for the default template length 9
+        Test::NonOverlappingTemplateMatching => {
+            let templates = args.non_overlapping_template.templates().templates().len();
+            0.06 * n * templates as f64
+        }
+        Test::OverlappingTemplateMatching => 2.2 * n,
+        Test::MaurersUniversalStatistical => 0.5 * n,
+        // O(n * M) with the Berlekamp-Massey algorithm for each block
+        Test::LinearComplexity => {
+            let block_length = block_length(test, len_bit, args).unwrap_or(512);
+            0.88 * n * block_length as f64
+        }
+        Test::Serial => 1.7 * n,
+        Test::ApproximateEntropy => 1.4 * n,
+        Test::CumulativeSums => 0.5 * n,
+        Test::RandomExcursions => 2.4 * n,
+        Test::RandomExcursionsVariant => 2.2 * n,
+        // only the first 10^6 bits are parsed
+        Test::LempelZiv => 5.6 * n.min(1e6),
+        Test::Autocorrelation => 0.06 * n,
+    };
+
+    Duration::from_nanos(nanos as u64)
+}
+
+/// The speed of the tests measured on the current machine, to scale the estimates of
+/// [estimate_duration]. Created with [Calibration::run].
+///
+/// Unlike the estimates of [estimate_duration], the measured speed includes the parallelization
+/// of the tests, with the thread pool configured when the calibration is run.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Calibration {
+    factors: HashMap<Test, f64>,
+}
+
+impl Calibration {
+    /// Runs each of the given tests with the given arguments on a pseudo-random input of 10^5 bits
+    /// (10^6 bits for tests that need longer inputs) and compares the time it takes with
+    /// [estimate_duration]. Each test is repeated for at least 20 ms, the whole calibration
+    /// usually takes less than a second.
+    ///
+    /// Tests that cannot run on either input length, or that return an error, are not calibrated.
+    /// For them, [Self::estimate_duration] falls back to [estimate_duration].
+    ///
+    /// ```
+    /// use sts_lib::test_runner::plan::Calibration;
+    /// use sts_lib::{Test, TestArgs};
+    ///
+    /// let args = TestArgs::default();
+    /// let calibration = Calibration::run([Test::Frequency, Test::Runs], args);
+    /// assert!(calibration.factor(Test::Frequency).is_some());
+    /// assert!(calibration.factor(Test::Serial).is_none());
+    ///
+    /// let estimate = calibration.estimate_duration(Test::Runs, 1_000_000, &args);
+    /// println!("Estimated duration of the runs test: {estimate:?}");
+    /// ```
+    pub fn run(tests: impl IntoIterator<Item = Test>, args: TestArgs) -> Self {
+        let mut inputs = HashMap::new();
+        let mut factors = HashMap::new();
+
+        for test in tests {
+            let Some(len_bit) = CALIBRATION_LENGTHS
+                .into_iter()
+                .find(|&len_bit| check_test(test, len_bit, args).is_none())
+            else {
+                continue;
+            };
+            let input = inputs
+                .entry(len_bit)
+                .or_insert_with(|| calibration_input(len_bit));
+
+            let begin = Instant::now();
+            let mut runs = 0;
+            while runs < MAX_CALIBRATION_RUNS && begin.elapsed() < MIN_CALIBRATION_TIME {
+                let result = run_tests(&*input, [test].into_iter(), args)
+                    .ok()
+                    .and_then(|mut runner| runner.next())
+                    .map(|(_, result)| result);
+                if !matches!(result, Some(Ok(_))) {
+                    break;
+                }
+                runs += 1;
+            }
+            if runs == 0 {
+                continue;
+            }
+
+            let measured = begin.elapsed().as_secs_f64() / runs as f64;
+            let estimated = estimate_duration(test, len_bit, &args).as_secs_f64();
+            if estimated > 0.0 {
+                factors.insert(test, measured / estimated);
+            }
+        }
+
+        Self { factors }
+    }
+
+    /// The factor between the measured duration of the test and [estimate_duration], e.g. `0.5`
+    /// if the test ran twice as fast as estimated. `None` if the test was not calibrated.
+    pub fn factor(&self, test: Test) -> Option<f64> {
+        self.factors.get(&test).copied()
+    }
+
+    /// Estimates how long the test takes on this machine, on an input of the given length in bits
+    /// with the given arguments: [estimate_duration] scaled by the measured [factor](Self::factor)
+    /// of the test, or unscaled if the test was not calibrated.
+    pub fn estimate_duration(&self, test: Test, len_bit: usize, args: &TestArgs) -> Duration {
+        let estimate = estimate_duration(test, len_bit, args);
+        match self.factor(test) {
+            Some(factor) => estimate.mul_f64(factor),
+            None => estimate,
+        }
+    }
+}
+
+/// A pseudo-random input of the given length in bits for the calibration, always the same. The
+/// length must be whole bytes, see [CALIBRATION_LENGTHS].
+fn calibration_input(len_bit: usize) -> BitVec<'static> {
+    let mut rng = SplitMix64(0);
+    let bytes = (0..len_bit / 8)
+        .map(|_| rng.next() as u8)
+        .collect::<Vec<_>>();
+    BitVec::from(bytes)
+}
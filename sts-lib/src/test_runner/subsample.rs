@@ -0,0 +1,234 @@
+//! Deterministic subsampling of long inputs: instead of only testing the start of the input, the
+//! tests are run on multiple windows placed at random, but reproducible, offsets.
+//!
+//! The offsets are generated from a seed, so a run can be reproduced exactly by passing the same
+//! seed, window length and window count for an input of the same length. The windows never
+//! overlap and are sorted by their offset.
+
+use crate::bitvec::BitVec;
+use crate::test_runner::cancellation::CancellationToken;
+use crate::test_runner::{
+    checked_tests, run_test, run_test_with_token, PartitionedResults, RunnerError,
+};
+use crate::{Error, Test, TestArgs};
+use std::num::NonZero;
+
+/// The placement of `count` disjoint windows of the same length in an input, generated from a
+/// seed. See [run_tests_subsampled].
+///
+/// ```
+/// use std::num::NonZero;
+/// use sts_lib::test_runner::Subsample;
+///
+/// let window_len = NonZero::new(1000).unwrap();
+/// let count = NonZero::new(5).unwrap();
+/// let subsample = Subsample::new(1_000_000, window_len, count, 42).unwrap();
+///
+/// assert_eq!(subsample.offsets().len(), 5);
+/// // the same seed always gives the same offsets
+/// assert_eq!(subsample, Subsample::new(1_000_000, window_len, count, 42).unwrap());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Subsample {
+    window_len: usize,
+    offsets: Vec<usize>,
+    seed: u64,
+}
+
+impl Subsample {
+    /// Places `count` windows with `window_len` bits each in an input of `len_bit` bits, with the
+    /// offsets generated from the given seed.
+    ///
+    /// Returns [Error::InvalidParameter] if the windows don't fit into the input.
+    pub fn new(
+        len_bit: usize,
+        window_len: NonZero<usize>,
+        count: NonZero<usize>,
+        seed: u64,
+    ) -> Result<Self, Error> {
+        let window_len = window_len.get();
+        let count = count.get();
+
+        let total = window_len
+            .checked_mul(count)
+            .filter(|&total| total <= len_bit)
+            .ok_or_else(|| {
+                Error::InvalidParameter(format!(
+                    "{count} windows of {window_len} bits don't fit into an input of {len_bit} bits"
+                ))
+            })?;
+
+        // The bits not covered by any window are distributed as gaps before the windows: draw a
+        // sorted position for each window in the free space, then shift each window by the
+        // length of the windows before it. This guarantees disjoint windows.
+        let free = (len_bit - total) as u64;
+        let mut rng = SplitMix64(seed);
+        let mut positions = (0..count)
+            .map(|_| rng.next_below_inclusive(free) as usize)
+            .collect::<Vec<_>>();
+        positions.sort_unstable();
+
+        let offsets = positions
+            .into_iter()
+            .enumerate()
+            .map(|(idx, position)| position + idx * window_len)
+            .collect();
+
+        Ok(Self {
+            window_len,
+            offsets,
+            seed,
+        })
+    }
+
+    /// The length of each window, in bits.
+    pub fn window_len(&self) -> usize {
+        self.window_len
+    }
+
+    /// The offsets of the windows in the input, in bits, sorted ascending.
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+
+    /// The seed the offsets were generated from.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// The windows of the given data, in the order of [Self::offsets].
+    ///
+    /// ## Panics
+    ///
+    /// If the data is shorter than the input length the subsample was created for.
+    pub fn windows(&self, data: &BitVec) -> Vec<BitVec<'static>> {
+        self.offsets
+            .iter()
+            .map(|&offset| data.slice(offset..offset + self.window_len))
+            .collect()
+    }
+}
+
+/// The SplitMix64 generator, used to generate the window offsets. It is fast, has a full period
+/// for every seed and its output is fixed, so that the offsets stay reproducible across versions.
+/// Also used for the input of the [Calibration](super::plan::Calibration).
+pub(crate) struct SplitMix64(pub(crate) u64);
+
+impl SplitMix64 {
+    pub(crate) fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A value in `0..=max`.
+    fn next_below_inclusive(&mut self, max: u64) -> u64 {
+        ((self.next() as u128 * (max as u128 + 1)) >> 64) as u64
+    }
+}
+
+/// Runs all given tests on each window of the [subsample](Subsample), with the used arguments
+/// taken from the passed [args](TestArgs). This allows testing extremely long inputs with a
+/// bounded effort, without only looking at the start of the input.
+///
+/// Only unique tests may be passed, and the windows must be long enough for each test: the
+/// tests are [validated](super::validate_tests) before any test is run, returning all problems at once.
+///
+/// Returns the results of each test, with one entry per window, in the order of
+/// [Subsample::offsets]. Each test is only run (on all windows) when `.next()` is called on the
+/// returned iterator.
+///
+/// ## Panics
+///
+/// If the data is shorter than the input length the subsample was created for.
+///
+/// ```
+/// use std::num::NonZero;
+/// use sts_lib::bitvec::BitVec;
+/// use sts_lib::test_runner::{run_tests_subsampled, Subsample};
+/// use sts_lib::{Test, TestArgs};
+///
+/// let data = BitVec::from(vec![0b1010_0110_u8; 1000]);
+/// let subsample = Subsample::new(
+///     data.len_bit(),
+///     NonZero::new(200).unwrap(),
+///     NonZero::new(4).unwrap(),
+///     7,
+/// )
+/// .unwrap();
+///
+/// let tests = [Test::Frequency].into_iter();
+/// let results = run_tests_subsampled(&data, tests, TestArgs::default(), &subsample)
+///     .unwrap()
+///     .collect::<Vec<_>>();
+///
+/// let (test, results) = &results[0];
+/// assert_eq!(*test, Test::Frequency);
+/// assert_eq!(results.len(), 4);
+/// ```
+pub fn run_tests_subsampled<'a>(
+    data: impl AsRef<BitVec<'a>>,
+    tests: impl Iterator<Item = Test>,
+    args: TestArgs,
+    subsample: &Subsample,
+) -> Result<impl Iterator<Item = (Test, PartitionedResults)>, RunnerError> {
+    let windows = subsample.windows(data.as_ref());
+
+    let tests = checked_tests(tests, subsample.window_len(), args)?;
+
+    let output = tests.into_iter().map(move |test| {
+        let results = windows
+            .iter()
+            .map(|window| run_test(test, window, args).1)
+            .collect();
+
+        (test, results)
+    });
+
+    Ok(output)
+}
+
+/// Runs all given tests on each window of the [subsample](Subsample), like
+/// [run_tests_subsampled], but allows to cancel the running tests with the given
+/// [token](CancellationToken).
+///
+/// Only unique tests may be passed, and the windows must be long enough for each test: the
+/// tests are [validated](super::validate_tests) before any test is run, returning all problems at once.
+///
+/// Once the token is cancelled, the currently running test aborts as soon as possible and all
+/// remaining tests and windows are skipped. All of these return [Error::Cancelled].
+///
+/// ## Panics
+///
+/// If the data is shorter than the input length the subsample was created for.
+pub fn run_tests_subsampled_with_cancel<'a>(
+    data: impl AsRef<BitVec<'a>>,
+    tests: impl Iterator<Item = Test>,
+    args: TestArgs,
+    subsample: &Subsample,
+    token: &CancellationToken,
+) -> Result<impl Iterator<Item = (Test, PartitionedResults)>, RunnerError> {
+    let windows = subsample.windows(data.as_ref());
+    let token = token.clone();
+
+    let tests = checked_tests(tests, subsample.window_len(), args)?;
+
+    let output = tests.into_iter().map(move |test| {
+        let results = windows
+            .iter()
+            .map(|window| {
+                if token.is_cancelled() {
+                    Err(Error::Cancelled)
+                } else {
+                    run_test_with_token(test, window, args, Some(&token)).1
+                }
+            })
+            .collect();
+
+        (test, results)
+    });
+
+    Ok(output)
+}
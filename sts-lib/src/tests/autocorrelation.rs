@@ -0,0 +1,148 @@
+//! Autocorrelation test.
+//!
+//! **This test is not part of NIST SP 800-22.** It is the autocorrelation test (T5) of the
+//! statistical tests of BSI AIS 31, in the form given in the Handbook of Applied Cryptography
+//! (Menezes et al., section 5.4.4), which results in a P-value like all other tests.
+//!
+//! This test compares the sequence with a copy of itself, shifted by *d* bits: the count of bits
+//! that differ from the bit *d* positions later should be about half of the compared bits. A
+//! sequence with too many or too few differences shows a correlation between bits at that
+//! distance, e.g. because of a periodic behaviour of the generator.
+//!
+//! This test needs a parameter [AutocorrelationTestArg]. Check the described constraints there.
+//!
+//! AIS 31 tests 10^4 bits, with shifts up to 5000. This is also the recommended minimum length,
+//! although only the constraint of [AutocorrelationTestArg] is enforced.
+
+use crate::bitvec::word;
+use crate::bitvec::BitVec;
+use crate::internals::{check_f64, erfc, trace_span};
+use crate::statistics::TestStatistics;
+use crate::{ArgError, Error, TestResult};
+use rayon::prelude::*;
+use std::f64::consts::FRAC_1_SQRT_2;
+use std::num::NonZero;
+use sts_lib_derive::use_thread_pool;
+
+/// The minimum input length, in bits, for this test, as used by BSI AIS 31.
+pub const MIN_INPUT_LENGTH: NonZero<usize> = const {
+    match NonZero::new(10_000) {
+        Some(v) => v,
+        None => panic!("Literal should be non-zero!"),
+    }
+};
+
+/// The argument for the autocorrelation test: the shift *d* in bits, i.e. the distance of the
+/// compared bits.
+///
+/// Argument constraints:
+/// 1. the shift must be >= 1.
+/// 2. the shift must be <= [BitVec::len_bit] / 2.
+///
+/// Constraint 1 is checked when creating the argument.
+///
+/// Constraint 2 is checked on executing the test, [autocorrelation_test]. If the constraint is
+/// violated, [Error::InvalidParameter] will be returned.
+///
+/// The default value for this argument is 1, i.e. each bit is compared with its successor.
+#[derive(Copy, Clone, Debug)]
+#[repr(transparent)]
+pub struct AutocorrelationTestArg(NonZero<usize>);
+
+impl AutocorrelationTestArg {
+    /// To create a new instance of [AutocorrelationTestArg]. Returns [ArgError::TooSmall] if the
+    /// shift is 0.
+    pub fn try_new(shift: usize) -> Result<Self, ArgError> {
+        NonZero::new(shift).map(Self).ok_or(ArgError::TooSmall {
+            name: "shift",
+            value: shift,
+            min: 1,
+        })
+    }
+
+    /// Like [Self::try_new], but returns `None` instead of the reason.
+    #[deprecated(note = "use `try_new`, which reports why the arguments are invalid")]
+    pub fn new(shift: usize) -> Option<Self> {
+        Self::try_new(shift).ok()
+    }
+
+    /// The shift in bits.
+    pub fn shift(&self) -> usize {
+        self.0.get()
+    }
+}
+
+impl Default for AutocorrelationTestArg {
+    fn default() -> Self {
+        Self(NonZero::<usize>::MIN)
+    }
+}
+
+/// Autocorrelation test - test T5 of BSI AIS 31, **not part of NIST SP 800-22**.
+///
+/// See the [module docs](crate::tests::autocorrelation).
+/// If the combination of the given data ([BitVec]) and [AutocorrelationTestArg] is invalid,
+/// [Error::InvalidParameter] is raised. For the exact constraints, see [AutocorrelationTestArg].
+#[use_thread_pool]
+pub fn autocorrelation_test(
+    data: &BitVec,
+    AutocorrelationTestArg(shift): AutocorrelationTestArg,
+) -> Result<TestResult, Error> {
+    trace_span!(INFO, "autocorrelation_test", len_bit = data.len_bit());
+
+    let shift = shift.get();
+    if shift > data.len_bit() / 2 {
+        return Err(Error::InvalidParameter(format!(
+            "Given shift must be <= len_bit / 2 (={}). Is: {shift}",
+            data.len_bit() / 2
+        )));
+    }
+
+    // Step 1: count the bits that differ from the bit shift positions later:
+    // A(d) = sum(b_i xor b_(i + d)) for i = 0 .. n - d - 1.
+    let compared = data.len_bit() - shift;
+    let differences = count_differences(data, shift);
+
+    // Step 2: compute the test statistic X = 2 * (A(d) - (n - d) / 2) / sqrt(n - d), which
+    // approximately follows the standard normal distribution.
+    let statistic = 2.0 * (differences as f64 - compared as f64 / 2.0) / f64::sqrt(compared as f64);
+    check_f64(statistic)?;
+
+    // Step 3: compute p-value = erfc(abs(X) / sqrt(2))
+    let p_value = erfc(f64::abs(statistic) * FRAC_1_SQRT_2);
+    check_f64(p_value)?;
+
+    Ok(
+        TestResult::new(p_value).with_statistics(TestStatistics::Autocorrelation {
+            shift,
+            differences,
+            statistic,
+        }),
+    )
+}
+
+/// Counts the bits that differ from the bit `shift` positions later, i.e. the Hamming weight of
+/// the sequence XOR the sequence shifted by `shift` bits.
+/// pub(crate) to allow for tests.
+pub(crate) fn count_differences(data: &BitVec, shift: usize) -> usize {
+    let compared = data.len_bit() - shift;
+    // word i of shifted contains the bits starting at (i * WORD_BITS + shift)
+    let shifted = data.bit_slice(shift..data.len_bit()).to_bit_vec();
+
+    // only the first (n - d) bits of data are compared, mask the rest of the last word.
+    let last_idx = shifted.words.len().saturating_sub(1);
+    let bits_last_word = word::bit_in_word(compared);
+
+    data.words
+        .par_iter()
+        .zip(shifted.words.par_iter())
+        .enumerate()
+        .map(|(i, (&word, &shifted_word))| {
+            let mut diff = word ^ shifted_word;
+            if i == last_idx && bits_last_word != 0 {
+                diff &= word::leading_mask(bits_last_word);
+            }
+            diff.count_ones() as usize
+        })
+        .sum()
+}
@@ -1,23 +1,32 @@
 //! Binary Matrix Rank Test
 //!
 //! This test checks for linear dependence among fixed length substrings of the sequence.
-//! These substrings are interpreted as matrices of size 32x32.
+//! These substrings are interpreted as matrices of size 32x32 by default. Other dimensions (up to
+//! 64x64) can be chosen with [BinaryMatrixRankTestArg], e.g. for research or to compare the results
+//! with other test suites.
 //!
-//! The sequence must consist of at least 38 912 bits = 4864 bytes.
+//! The sequence must consist of at least 38 matrices, i.e. 38 912 bits = 4864 bytes for the default
+//! dimensions. For other dimensions, the minimum count of matrices is chosen so that each of the 3
+//! rank classes is expected at least 5 times, see [BinaryMatrixRankTestArg::min_matrix_count].
 //!
-//! The probability constants were recalculated, using the generic formula for p_r given in 3.5.
+//! The probabilities of each rank are calculated with the generic formula for p_r given in 3.5.
+//! For 32x32 matrices, they match the values calculated with `binary_matrix_probabilities.py`.
 //! 
-//! Per feedback by Mikołaj Leonarski <m dot leonarski at uw dot edu dot pl>, the 3rd constant was
+//! Per feedback by Mikołaj Leonarski <m dot leonarski at uw dot edu dot pl>, the 3rd probability was
 //! again corrected from p_{m-2} to 1 - p_m - p_{m-1}.
 
+use crate::bitvec::word::{self, Word, WORD_BITS};
 use crate::bitvec::BitVec;
-use crate::internals::{check_f64, checked_add, igamc, BitPrimitive};
-use crate::{Error, TestResult};
+use crate::internals::{check_f64, checked_add, igamc, trace_span};
+use crate::statistics::TestStatistics;
+use crate::test_runner::cancellation::CancelCheck;
+use crate::{ArgError, Error, TestResult};
 use rayon::prelude::*;
 use std::num::NonZero;
 use sts_lib_derive::use_thread_pool;
 
-/// The minimum input length, in bits, for this test, as recommended by NIST.
+/// The minimum input length, in bits, for this test with the default 32x32 matrices, as
+/// recommended by NIST. See [BinaryMatrixRankTestArg::min_input_length] for other dimensions.
 pub const MIN_INPUT_LENGTH: NonZero<usize> = const {
     match NonZero::new(38_912) {
         Some(v) => v,
@@ -25,44 +34,152 @@ pub const MIN_INPUT_LENGTH: NonZero<usize> = const {
     }
 };
 
-/// Rows and columns
-const M: usize = u32::BITS as usize;
+/// The maximum count of rows and columns of a matrix.
+pub const MAX_DIMENSION: usize = u64::BITS as usize;
 
-// Probabilities, calculated with `binary_matrix_probabilities.py`
-const PROBABILITIES: [f64; 3] = {
-    let p1 = 0.2887880951538411;
-    let p2 = 0.5775761901732046;
-    [p1, p2, 1.0 - p1 - p2]
-};
+/// The argument for the Binary Matrix Rank Test: the dimensions of the matrices, *M* rows and
+/// *Q* columns.
+///
+/// Argument constraints:
+/// 1. the rows and columns must each be between 2 and [MAX_DIMENSION].
+/// 2. the input must contain at least [BinaryMatrixRankTestArg::min_matrix_count] matrices, see
+///    [BinaryMatrixRankTestArg::min_input_length].
+///
+/// Constraint 1 is checked when creating the argument. Constraint 2 is checked when running the
+/// test: if the input is too short, a result with a p-value of 0 and a comment is returned.
+///
+/// Matrices that are not square almost always have full rank, so that a very long input is needed
+/// to expect matrices of lower rank.
+///
+/// The default value for this argument is 32x32, as used by SP 800-22.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BinaryMatrixRankTestArg {
+    rows: usize,
+    cols: usize,
+}
+
+impl Default for BinaryMatrixRankTestArg {
+    fn default() -> Self {
+        Self { rows: 32, cols: 32 }
+    }
+}
+
+impl BinaryMatrixRankTestArg {
+    /// Creates a new argument with the given count of rows *M* and columns *Q*. Returns
+    /// [ArgError::OutOfRange] if one of them is not between 2 and [MAX_DIMENSION].
+    pub fn try_new(rows: usize, cols: usize) -> Result<Self, ArgError> {
+        ArgError::check_range("count of rows", rows, 2, MAX_DIMENSION)?;
+        ArgError::check_range("count of columns", cols, 2, MAX_DIMENSION)?;
+        Ok(Self { rows, cols })
+    }
+
+    /// Like [Self::try_new], but returns `None` instead of the reason.
+    #[deprecated(note = "use `try_new`, which reports why the arguments are invalid")]
+    pub fn new(rows: usize, cols: usize) -> Option<Self> {
+        Self::try_new(rows, cols).ok()
+    }
+
+    /// The count of rows *M* of each matrix.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// The count of columns *Q* of each matrix.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// The minimum count of matrices *N*: each of the 3 rank classes must be expected at least 5
+    /// times, as needed for the χ² approximation. For 32x32 matrices, this gives *N >= 38*, as
+    /// recommended by NIST.
+    pub fn min_matrix_count(&self) -> usize {
+        let min_probability = self
+            .probabilities()
+            .into_iter()
+            .fold(f64::INFINITY, f64::min);
+
+        // the conversion saturates if the count is not representable
+        (5.0 / min_probability).ceil() as usize
+    }
+
+    /// The minimum input length, in bits, for matrices of these dimensions, see
+    /// [Self::min_matrix_count].
+    pub fn min_input_length(&self) -> NonZero<usize> {
+        NonZero::new(
+            self.min_matrix_count()
+                .saturating_mul(self.rows * self.cols),
+        )
+        .unwrap()
+    }
+
+    /// The probabilities of a random matrix having full rank, full rank - 1, and any lower rank.
+    fn probabilities(&self) -> [f64; 3] {
+        let full_rank = self.rows.min(self.cols);
+        let p1 = rank_probability(self.rows, self.cols, full_rank);
+        let p2 = rank_probability(self.rows, self.cols, full_rank - 1);
+        [p1, p2, 1.0 - p1 - p2]
+    }
+}
+
+/// The probability of a random *M x Q* matrix having the rank *r*, according to section 3.5:
+///
+/// p_r = 2^(r(Q+M-r)-MQ) * prod_{i=0}^{r-1} ((1 - 2^(i-Q)) * (1 - 2^(i-M)) / (1 - 2^(i-r)))
+fn rank_probability(rows: usize, cols: usize, rank: usize) -> f64 {
+    // all values are <= 64, no overflow possible
+    let (m, q, r) = (rows as i32, cols as i32, rank as i32);
+
+    (0..r).fold(f64::powi(2.0, r * (q + m - r) - m * q), |p_r, i| {
+        let part1 = (1.0 - f64::powi(2.0, i - q)) * (1.0 - f64::powi(2.0, i - m));
+        let part2 = 1.0 - f64::powi(2.0, i - r);
+        p_r * part1 / part2
+    })
+}
 
 /// Binary matrix rank test - No. 5.
 ///
 /// See also the [module docs](crate::tests::binary_matrix_rank).
 #[use_thread_pool]
-pub fn binary_matrix_rank_test(data: &BitVec) -> Result<TestResult, Error> {
-    if data.len_bit() < MIN_INPUT_LENGTH.get() {
+pub fn binary_matrix_rank_test(
+    data: &BitVec,
+    test_arg: BinaryMatrixRankTestArg,
+) -> Result<TestResult, Error> {
+    trace_span!(INFO, "binary_matrix_rank_test", len_bit = data.len_bit());
+
+    let BinaryMatrixRankTestArg { rows, cols } = test_arg;
+
+    // Step 1: divide the sequence into blocks with length M * Q bits
+    let matrix_length = rows * cols;
+    let block_count = data.len_bit() / matrix_length;
+
+    if block_count < test_arg.min_matrix_count() {
         return Ok(TestResult::new_with_comment(
             0.0,
-            "Data is too short! Minimum is 38 912 Bits.",
+            format!(
+                "Data is too short ({} bits)! Each rank class must be expected at least 5 times, \
+                 i.e. the minimum is {} bits for {rows}x{cols} matrices.",
+                data.len_bit(),
+                test_arg.min_input_length(),
+            ),
         ));
     }
 
-    // Step 1: divide the sequence into blocks with length M * Q = 32 * 32 bits = 32 u32
-    let data = data.par_array_chunks_u32::<M>();
-    let block_count = data.len();
-
-    let categories = data
+    let cancel = CancelCheck::current();
+    let full_rank = rows.min(cols);
+    let categories = (0..block_count)
+        .into_par_iter()
         .try_fold(
             || [0_usize; 3],
-            |mut categories, chunk| {
-                let mut matrix = Matrix(chunk);
+            |mut categories, block_idx| {
+                cancel.check()?;
+
+                let mut matrix = Matrix::new(data, block_idx * matrix_length, rows, cols);
                 // Step 2: determine the binary rank of each matrix
                 let binary_rank = matrix.binary_rank();
 
                 // Step 3: categorise based on the binary rank
-                if binary_rank == M {
+                if binary_rank == full_rank {
                     categories[0] = checked_add!(categories[0], 1)?;
-                } else if binary_rank == M - 1 {
+                } else if binary_rank == full_rank - 1 {
                     categories[1] = checked_add!(categories[1], 1)?;
                 } else {
                     categories[2] = checked_add!(categories[2], 1)?;
@@ -85,7 +202,7 @@ pub fn binary_matrix_rank_test(data: &BitVec) -> Result<TestResult, Error> {
     // Step 4: compute chi
     let chi = categories
         .into_iter()
-        .zip(PROBABILITIES)
+        .zip(test_arg.probabilities())
         .map(|(f, p)| {
             let x = p * (block_count as f64);
             f64::powi((f as f64) - x, 2) / x
@@ -98,101 +215,97 @@ pub fn binary_matrix_rank_test(data: &BitVec) -> Result<TestResult, Error> {
     let p_value = igamc(1.0, chi / 2.0)?;
     check_f64(p_value)?;
 
-    Ok(TestResult::new(p_value))
+    let [full_rank_count, full_rank_minus_one_count, remaining_count] = categories;
+    Ok(
+        TestResult::new(p_value).with_statistics(TestStatistics::BinaryMatrixRank {
+            rows,
+            cols,
+            full_rank_count,
+            full_rank_minus_one_count,
+            remaining_count,
+            chi_square: chi,
+        }),
+    )
 }
 
-/// Matrix: each u32 is 1 row of 32 bits, 32 rows.
-#[repr(transparent)]
-struct Matrix([u32; M]);
+/// Matrix: each u64 is 1 row, the bits of the columns are stored in the lowest bits, column 0
+/// being the most significant one.
+struct Matrix {
+    rows: [u64; MAX_DIMENSION],
+    row_count: usize,
+    col_count: usize,
+}
 
 impl Matrix {
-    /// Swap 2 rows by their indices
-    fn swap_rows(&mut self, i: usize, j: usize) {
-        self.0.swap(i, j)
-    }
-
-    /// Get the bit in the given row and column
-    fn bit(&self, row_idx: usize, col_idx: usize) -> bool {
-        self.0[row_idx].get_bit(col_idx as u32)
-    }
+    /// Reads the matrix with the given dimensions from the data, starting at bit `start`. The
+    /// data must contain enough bits.
+    fn new(data: &BitVec, start: usize, row_count: usize, col_count: usize) -> Self {
+        let mut rows = [0; MAX_DIMENSION];
+        for (i, row) in rows.iter_mut().take(row_count).enumerate() {
+            *row = read_bits(&data.words, start + i * col_count, col_count);
+        }
 
-    /// xor 2 rows, save the result in row target
-    fn xor_rows(&mut self, target: usize, i: usize) {
-        self.0[target] ^= self.0[i];
+        Self {
+            rows,
+            row_count,
+            col_count,
+        }
     }
 
-    /// Calculate the binary rank of the given matrix according to Appendix F.1.
+    /// Calculate the binary rank of the given matrix according to Appendix F.1. Only the forward
+    /// row operations are necessary to determine the rank, they are done column by column, so
+    /// that matrices that are not square are handled correctly.
     fn binary_rank(&mut self) -> usize {
-        // Forward row operations
-        for i in 0..(M - 1) {
-            if !self.bit(i, i) {
-                // Step 2
-                // Search for a next row with a 1 in this column
-                let mut found_row = None;
-                for row in (i + 1)..M {
-                    if self.bit(row, i) {
-                        found_row = Some(row);
-                        break;
-                    }
-                }
+        // the count of rows that are already in echelon form = the rank found so far
+        let mut rank = 0;
 
-                // If found, swap the elements, else: look at next row
-                if let Some(row) = found_row {
-                    // each value is a row
-                    self.swap_rows(i, row);
-                } else {
-                    continue;
-                }
+        for col in 0..self.col_count {
+            if rank == self.row_count {
+                break;
             }
 
-            // Now, el_i,i contains a 1 in every case
+            // Step 2
+            // Search for a row with a 1 in this column, if none is found: look at next column
+            let shift = self.col_count - col - 1;
+            let Some(found_row) =
+                (rank..self.row_count).find(|&row| (self.rows[row] >> shift) & 1 == 1)
+            else {
+                continue;
+            };
+            self.rows.swap(rank, found_row);
+
             // Step 3
-            // For all rows with a 1 in the column i, replace each element e_r,j in the row with e_r,j ^ e_i,j
-            for row in (i + 1)..M {
-                // test if row is to be changed
-                if self.bit(row, i) {
-                    self.xor_rows(row, i);
-                }
+            // For all following rows with a 1 in this column, xor the pivot row into the row.
+            // Without branches: the mask is all ones if the row has a 1 in this column, else 0.
+            let pivot = self.rows[rank];
+            for row in &mut self.rows[(rank + 1)..self.row_count] {
+                let mask = 0_u64.wrapping_sub((*row >> shift) & 1);
+                *row ^= pivot & mask;
             }
 
-            // Step 4 is integrated in the loop
+            rank += 1;
         }
 
-        // Backward row operation
-        for i in (1..M).rev() {
-            if !self.bit(i, i) {
-                // Step 2
-                // Search for a next row with a 1 in this column
-                let mut found_row = None;
-                for row in (0..i).rev() {
-                    if self.bit(row, i) {
-                        found_row = Some(row);
-                        break;
-                    }
-                }
-                // If found, swap the elements, else: look at next row
-                if let Some(row) = found_row {
-                    // each value is a row
-                    self.swap_rows(i, row);
-                } else {
-                    continue;
-                }
-            }
-
-            // Now, el_i,i contains a 1 in every case
-            // Step 3
-            // For all rows with a 1 in the column i, replace each element e_r,j in the row with e_r,j ^ e_i,j
-            for row in (0..i).rev() {
-                // test if row is to be changed
-                if self.bit(row, i) {
-                    self.xor_rows(row, i);
-                }
-            }
+        rank
+    }
+}
 
-            // Step 4 is integrated in the loop
-        }
+/// Reads `count` bits (at most 64), starting at bit `start`, from the given words. The first bit
+/// read is the most significant one of the returned value.
+fn read_bits(words: &[Word], start: usize, count: usize) -> u64 {
+    let mut value = 0_u64;
+    let mut pos = start;
+    let mut remaining = count;
+    while remaining > 0 {
+        let offset = word::bit_in_word(pos);
+        let take = usize::min(WORD_BITS - offset, remaining);
+        let bits = (words[word::word_index(pos)] << offset) >> (WORD_BITS - take);
 
-        // rank of the matrix = the number of non-zero rows
-        self.0.iter().filter(|&row| row.count_ones() > 0).count()
+        // shifting by 64 bits only happens if value is still 0
+        value = value.checked_shl(take as u32).unwrap_or(0) | bits as u64;
+        pos += take;
+        remaining -= take;
     }
+
+    value
 }
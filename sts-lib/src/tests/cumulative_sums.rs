@@ -8,13 +8,18 @@
 //! The input sequence should be at least 100 bits in length, smaller sequences will raise
 //! [Error::InvalidParameter].
 
+use crate::bitvec::word::{self, Word};
 use crate::bitvec::BitVec;
-use crate::internals::{check_f64, BitPrimitive};
-use crate::{Error, TestResult};
-use statrs::distribution;
-use statrs::distribution::ContinuousCDF;
-use std::num::NonZero;
-use std::ops::Range;
+use crate::internals::par::*;
+#[cfg(not(feature = "std"))]
+use crate::internals::F64Ext;
+use crate::internals::{check_f64, trace_span};
+use crate::math::normal_cdf;
+use crate::statistics::TestStatistics;
+use crate::{Error, ResultLabel, TestResult};
+use alloc::format;
+use core::num::NonZero;
+use core::ops::Range;
 use sts_lib_derive::use_thread_pool;
 
 /// The minimum input length, in bits, for this test, as recommended by NIST.
@@ -25,12 +30,25 @@ pub const MIN_INPUT_LENGTH: NonZero<usize> = const {
     }
 };
 
+/// The mode of one result of the [cumulative_sums_test], see [ResultLabel::CumulativeSums].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CusumDirection {
+    /// The partial sums start with the first bit (mode 0).
+    Forward,
+    /// The partial sums start with the last bit (mode 1).
+    Backward,
+}
+
 /// Cumulative Sums Test - No. 13
 ///
 /// See also the [module docs](crate::tests::cumulative_sums).
+/// The first result is the forward mode, the second the backward mode. The maximum excursion
+/// *max |S_k|* of each mode is part of the [statistics](TestStatistics::CumulativeSums).
 /// If the bit length is less than 100 bits, [Error::InvalidParameter] is raised.
 #[use_thread_pool]
 pub fn cumulative_sums_test(data: &BitVec) -> Result<[TestResult; 2], Error> {
+    trace_span!(INFO, "cumulative_sums_test", len_bit = data.len_bit());
+
     if data.len_bit() < MIN_INPUT_LENGTH.get() {
         Err(Error::InvalidParameter(format!(
             "Sequence length must be >= 100. Is: {}",
@@ -38,8 +56,10 @@ pub fn cumulative_sums_test(data: &BitVec) -> Result<[TestResult; 2], Error> {
         )))
     } else {
         Ok([
-            cusum_test_internal(data, false)?,
-            cusum_test_internal(data, true)?,
+            cusum_test_internal(data, false)?
+                .with_label(ResultLabel::CumulativeSums(CusumDirection::Forward)),
+            cusum_test_internal(data, true)?
+                .with_label(ResultLabel::CumulativeSums(CusumDirection::Backward)),
         ])
     }
 }
@@ -47,37 +67,41 @@ pub fn cumulative_sums_test(data: &BitVec) -> Result<[TestResult; 2], Error> {
 /// Internal implementation of the cumulative sum test. Assumes that all constraints are met.
 /// pub(crate) to allow for tests.
 pub(crate) fn cusum_test_internal(data: &BitVec, mode: bool) -> Result<TestResult, Error> {
-    // create a range iterator if the last value needs to be handled differently (not a full word).
-    // range iterator goes from LSB to MSB by default (reverse order).
+    // the bits of the last word, if it is not a full word.
     let (full_words, last_word) = data.as_full_slice();
-    let last_word = last_word.map(|w| (w, 0..(data.bit_count_last_word as u32)));
+    let last_word = last_word.map(|w| bit_sums(w, 0..(data.bit_count_last_word as usize), mode));
 
     // Step 1: form a normalized sequence: 1 -> 1, 0 -> -1
     // Step 2: compute partial sums of subsequences of the original sequence, each starting with
     // [0] (if mode == false) or [^1] (if mode == true)
     // Step 3: compute the largest absolute value out of the partial sums
-    // This is all one big operation - we don't need to save the list, we can just compare with the prev maximum.
-    let max = if mode {
+    // This is all one big operation - we don't need to save the list, it is enough to know the
+    // maximum and minimum partial sum. The words are handled in parallel, see [PartialSums].
+    let sums = if mode {
         // Start with last bits, going in reverse
-        if let Some((last_word, shifts)) = last_word {
-            // if going backwards, the LSB is the first bit to watch
-            let (max, prev) = handle_value(0, 0, last_word, shifts, true);
+        let sums = full_words
+            .par_iter()
+            .rev()
+            .map(|&word| word_sums(word, true))
+            .reduce(PartialSums::default, PartialSums::then);
 
-            handle_slice(max, prev, full_words, true).0
-        } else {
-            handle_slice(0, 0, &data.words, true).0
+        match last_word {
+            Some(last_word) => last_word.then(sums),
+            None => sums,
         }
     } else {
         // Start with first bits, normal order
-        if let Some((last_word, shifts)) = last_word {
-            let (max, prev) = handle_slice(0, 0, full_words, false);
+        let sums = full_words
+            .par_iter()
+            .map(|&word| word_sums(word, false))
+            .reduce(PartialSums::default, PartialSums::then);
 
-            // if going forwards, the MSB is the first bit to watch
-            handle_value(max, prev, last_word, shifts, false).0
-        } else {
-            handle_slice(0, 0, &data.words, false).0
+        match last_word {
+            Some(last_word) => sums.then(last_word),
+            None => sums,
         }
     };
+    let max = sums.max_excursion();
 
     // Step 4: compute p_value = 1
     //  - sum_{k = (-n/z + 1) / 4}^{ (n/z - 1) / 4}(
@@ -91,8 +115,6 @@ pub(crate) fn cusum_test_internal(data: &BitVec, mode: bool) -> Result<TestResul
     let n = data.len_bit() as i64;
     let sqrt_n = f64::sqrt(n as f64);
 
-    let normal_distribution = distribution::Normal::standard();
-
     let sum_upper_bound = (n / z - 1) / 4 + 1;
 
     let sum_1 = {
@@ -102,8 +124,8 @@ pub(crate) fn cusum_test_internal(data: &BitVec, mode: bool) -> Result<TestResul
         (lower_bound..sum_upper_bound)
             .map(|k| {
                 let k = k as f64;
-                normal_distribution.cdf(((4.0 * k + 1.0) * z) / sqrt_n)
-                    - normal_distribution.cdf(((4.0 * k - 1.0) * z) / sqrt_n)
+                normal_cdf(((4.0 * k + 1.0) * z) / sqrt_n)
+                    - normal_cdf(((4.0 * k - 1.0) * z) / sqrt_n)
             })
             .sum::<f64>()
     };
@@ -116,8 +138,8 @@ pub(crate) fn cusum_test_internal(data: &BitVec, mode: bool) -> Result<TestResul
         (lower_bound..sum_upper_bound)
             .map(|k| {
                 let k = k as f64;
-                normal_distribution.cdf(((4.0 * k + 3.0) * z) / sqrt_n)
-                    - normal_distribution.cdf(((4.0 * k + 1.0) * z) / sqrt_n)
+                normal_cdf(((4.0 * k + 3.0) * z) / sqrt_n)
+                    - normal_cdf(((4.0 * k + 1.0) * z) / sqrt_n)
             })
             .sum::<f64>()
     };
@@ -126,62 +148,122 @@ pub(crate) fn cusum_test_internal(data: &BitVec, mode: bool) -> Result<TestResul
     let p_value = 1.0 - sum_1 + sum_2;
     check_f64(p_value)?;
 
-    Ok(TestResult::new(p_value))
+    Ok(
+        TestResult::new(p_value).with_statistics(TestStatistics::CumulativeSums {
+            max_excursion: max as usize,
+        }),
+    )
 }
 
-/// Add the increasing cumulative sums of the bytes to the state variables.
-/// Parameter rev: if the bit order should be reversed.
-/// Returns the new state variables.
-fn handle_slice(mut max: u64, mut prev: i64, data: &[usize], rev: bool) -> (u64, i64) {
-    if rev {
-        for &value in data.iter().rev() {
-            (max, prev) = handle_value(max, prev, value, 0..usize::BITS, rev);
-        }
-    } else {
-        for &value in data.iter() {
-            (max, prev) = handle_value(max, prev, value, 0..usize::BITS, rev);
+/// The partial sums of a part of the sequence, with 1 -> +1 and 0 -> -1, relative to the start of
+/// the part: the sum of the whole part and the maximum and minimum partial sum (including the empty
+/// sum 0).
+///
+/// Appending parts with [PartialSums::then] is associative, which allows calculating the partial
+/// sums of each word (or byte) independently.
+#[derive(Copy, Clone, Default, Debug)]
+struct PartialSums {
+    total: i64,
+    max: i64,
+    min: i64,
+}
+
+impl PartialSums {
+    /// The partial sums of this part, followed by the `next` part.
+    fn then(self, next: Self) -> Self {
+        Self {
+            total: self.total + next.total,
+            max: i64::max(self.max, self.total + next.max),
+            min: i64::min(self.min, self.total + next.min),
         }
     }
 
-    (max, prev)
+    /// The largest absolute partial sum.
+    fn max_excursion(&self) -> u64 {
+        u64::max(self.max.unsigned_abs(), self.min.unsigned_abs())
+    }
 }
 
-/// Handle an individual value:
-/// shifts denotes which bits to read
-#[inline]
-fn handle_value(
-    max: u64,
-    prev: i64,
-    value: usize,
-    bits_to_read: Range<u32>,
-    rev: bool,
-) -> (u64, i64) {
-    fn internal(
-        mut max: u64,
-        mut prev: i64,
-        value: usize,
-        indexes: impl Iterator<Item = u32>,
-    ) -> (u64, i64) {
-        indexes.for_each(|idx| {
-            if value.get_bit(idx) {
-                prev += 1
+/// The partial sums of all bytes, going from the MSB to the LSB.
+static FORWARD_TABLE: [PartialSums; 256] = byte_table(false);
+
+/// The partial sums of all bytes, going from the LSB to the MSB.
+static REVERSE_TABLE: [PartialSums; 256] = byte_table(true);
+
+/// Calculates the partial sums of all possible bytes. If `rev` is set, the LSB is the first bit.
+const fn byte_table(rev: bool) -> [PartialSums; 256] {
+    let mut table = [PartialSums {
+        total: 0,
+        max: 0,
+        min: 0,
+    }; 256];
+
+    let mut byte = 0;
+    while byte < 256 {
+        let sums = &mut table[byte];
+
+        let mut bit = 0;
+        while bit < 8 {
+            let shift = if rev { bit } else { 7 - bit };
+            if (byte >> shift) & 1 == 1 {
+                sums.total += 1;
             } else {
-                prev -= 1
+                sums.total -= 1;
             }
 
-            // set maximum if necessary
-            if max < i64::unsigned_abs(prev) {
-                max = i64::unsigned_abs(prev);
+            if sums.total > sums.max {
+                sums.max = sums.total;
+            }
+            if sums.total < sums.min {
+                sums.min = sums.total;
             }
-        });
-        (max, prev)
+            bit += 1;
+        }
+
+        byte += 1;
     }
 
+    table
+}
+
+/// The partial sums of a full word, byte by byte.
+/// Parameter rev: if the bit order should be reversed.
+#[inline]
+fn word_sums(value: Word, rev: bool) -> PartialSums {
+    let (bytes, table) = if rev {
+        // if going backwards, the LSB is the first bit to watch
+        (value.to_le_bytes(), &REVERSE_TABLE)
+    } else {
+        // if going forward, the MSB is the first bit to watch
+        (word::to_bytes(value), &FORWARD_TABLE)
+    };
+
+    bytes
+        .into_iter()
+        .fold(PartialSums::default(), |sums, byte| {
+            sums.then(table[byte as usize])
+        })
+}
+
+/// The partial sums of the given bits of a word, bit by bit.
+/// Parameter rev: if the bit order should be reversed.
+fn bit_sums(value: Word, bits_to_read: Range<usize>, rev: bool) -> PartialSums {
+    let bit_sum = |idx: usize| {
+        let total = if word::get_bit(value, idx) { 1 } else { -1 };
+        PartialSums {
+            total,
+            max: total.max(0),
+            min: total.min(0),
+        }
+    };
+
     if rev {
         // if going backwards, the LSB is the first bit to watch
-        internal(max, prev, value, bits_to_read.rev())
+        bits_to_read
+            .rev()
+            .fold(PartialSums::default(), |sums, idx| sums.then(bit_sum(idx)))
     } else {
         // if going forward, the MSB is the first bit to watch
-        internal(max, prev, value, bits_to_read)
+        bits_to_read.fold(PartialSums::default(), |sums, idx| sums.then(bit_sum(idx)))
     }
 }
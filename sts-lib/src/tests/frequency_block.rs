@@ -4,12 +4,18 @@
 //! It is recommended that each block  has a length of at least 100 bits.
 //! This test needs an argument, see [FrequencyBlockTestArg].
 
+use crate::bitvec::word::{self, WORD_BITS};
 use crate::bitvec::BitVec;
-use crate::internals::{check_f64, igamc, BitPrimitive};
+use crate::internals::par::*;
+#[cfg(not(feature = "std"))]
+use crate::internals::F64Ext;
+use crate::internals::{check_f64, igamc, trace_span, HIGH_PRECISION};
+use crate::statistics::TestStatistics;
 use crate::{Error, TestResult};
-use rayon::prelude::*;
-use std::num::NonZero;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::num::NonZero;
+use core::sync::atomic::{AtomicUsize, Ordering};
 use sts_lib_derive::use_thread_pool;
 
 /// The minimum input length, in bits, for this test, as recommended by NIST.
@@ -24,31 +30,100 @@ pub const MIN_INPUT_LENGTH: NonZero<usize> = const {
 ///
 /// The block length should be at least 20 bits, with the block length greater than 1% of the
 /// total bit length and fewer than 100 total blocks.
+///
+/// The block length used for an input can be queried with [FrequencyBlockTestArg::block_length],
+/// it is also part of the [statistics](TestStatistics::FrequencyWithinABlock) of the result.
 #[derive(Copy, Clone, Default, Debug)]
 pub enum FrequencyBlockTestArg {
     /// Manual block length
     Manual(NonZero<usize>),
     /// A suitable block length will be chosen automatically, based on the criteria outlined in
-    /// [FrequencyBlockTestArg].
+    /// [FrequencyBlockTestArg]. Same as [SelectionStrategy::NistRecommended].
     #[default]
     ChooseAutomatically,
+    /// The block length will be chosen automatically with the given strategy.
+    ChooseWithStrategy(SelectionStrategy),
+}
+
+impl FrequencyBlockTestArg {
+    /// The block length that is used for an input with the given length in bits.
+    pub fn block_length(&self, length: usize) -> usize {
+        match self {
+            FrequencyBlockTestArg::Manual(block_length) => block_length.get(),
+            FrequencyBlockTestArg::ChooseAutomatically => {
+                SelectionStrategy::NistRecommended.block_length(length)
+            }
+            FrequencyBlockTestArg::ChooseWithStrategy(strategy) => strategy.block_length(length),
+        }
+    }
+}
+
+/// The strategies to choose the block length automatically, see
+/// [FrequencyBlockTestArg::ChooseWithStrategy].
+///
+/// All strategies choose a block length of at least 20 bits, greater than 1% of the input length,
+/// so that there are fewer than 100 blocks (as recommended in SP 800-22, section 2.2.7) - if the
+/// input is long enough for at least 2 blocks.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub enum SelectionStrategy {
+    /// The smallest recommended block length, rounded up to a multiple of the word size
+    /// (64 bits on most platforms) if there are still at least 2 blocks. This is the strategy of
+    /// [FrequencyBlockTestArg::ChooseAutomatically].
+    #[default]
+    NistRecommended,
+    /// The smallest recommended block length, which gives the most blocks.
+    MaximizeBlocks,
+    /// The smallest recommended block length that is a power of two, if there are still at least
+    /// 2 blocks, else the same as [SelectionStrategy::MaximizeBlocks].
+    PowerOfTwo,
+}
+
+impl SelectionStrategy {
+    /// The block length chosen by this strategy for an input with the given length in bits.
+    pub fn block_length(&self, length: usize) -> usize {
+        const MIN_BLOCK_LENGTH: usize = 20;
+
+        // The recommended minimum block length based on the length of the data.
+        // This also satisfies that less than 100 block should exist.
+        let min_block_length = usize::max(length / 100 + 1, MIN_BLOCK_LENGTH);
+
+        let rounded_block_length = match self {
+            SelectionStrategy::NistRecommended if length / 100 + 1 >= MIN_BLOCK_LENGTH => {
+                // Round up to the next block length that is usize-aligned.
+                // This works by adding 63 and than truncating the lower bits.
+                (min_block_length + WORD_BITS - 1) & !(WORD_BITS - 1)
+            }
+            SelectionStrategy::NistRecommended | SelectionStrategy::MaximizeBlocks => {
+                return min_block_length
+            }
+            SelectionStrategy::PowerOfTwo => min_block_length.next_power_of_two(),
+        };
+
+        // the rounded block length is possible as long as there are at least 2 blocks.
+        // 1 block would just be the frequency test.
+        if rounded_block_length * 2 <= length {
+            rounded_block_length
+        } else {
+            min_block_length
+        }
+    }
 }
 
 /// Frequency test within a block - No. 2
 ///
 /// See the [module docs](crate::tests::frequency_block_test).
 /// If test_arg is [FrequencyBlockTestArg::ChooseAutomatically], a reasonable default, based on 2.2.7, is chosen.
+/// The chosen block length is part of the statistics of the result.
 /// If an error happens, it means either arithmetic underflow or overflow - beware.
 #[use_thread_pool]
 pub fn frequency_block_test(
     data: &BitVec,
     test_arg: FrequencyBlockTestArg,
 ) -> Result<TestResult, Error> {
+    trace_span!(INFO, "frequency_block_test", len_bit = data.len_bit());
+
     // Step 0 - get the block length or calculate one
-    let block_length = match test_arg {
-        FrequencyBlockTestArg::Manual(block_length) => block_length.get(),
-        FrequencyBlockTestArg::ChooseAutomatically => choose_block_length(data.len_bit()),
-    };
+    let block_length = test_arg.block_length(data.len_bit());
 
     // Step 1 - calculate the amount of blocks
     let block_count = data.len_bit() / block_length;
@@ -57,13 +132,7 @@ pub fn frequency_block_test(
     // We can't split in chunks here, because chunks would only catch whole words.
 
     // How many words are needed - there could be unused words at the end
-    let words_needed = if block_length * block_count % (usize::BITS as usize) == 0 {
-        // no remainder
-        block_length * block_count / (usize::BITS as usize)
-    } else {
-        // a remainder is left: 1 additional byte is needed for it.
-        block_length * block_count / (usize::BITS as usize) + 1
-    };
+    let words_needed = (block_length * block_count).div_ceil(WORD_BITS);
 
     let count_ones_per_block = {
         let mut vec = Vec::with_capacity(block_count);
@@ -76,43 +145,42 @@ pub fn frequency_block_test(
         .enumerate()
         .for_each(|(idx, value)| {
             // returns the block idx for the specified bit idx
-            let block_idx =
-                |bit_idx: u32| (idx * (usize::BITS as usize) + (bit_idx as usize)) / block_length;
+            let block_idx = |bit_idx: usize| (idx * WORD_BITS + bit_idx) / block_length;
 
             if block_idx(0) == block_count {
                 return;
             }
 
-            if block_idx(0) == block_idx(usize::BITS - 1) {
+            if block_idx(0) == block_idx(WORD_BITS - 1) {
                 // the whole word is the same block.
                 count_ones_per_block[block_idx(0)]
                     .fetch_add(value.count_ones() as usize, Ordering::Relaxed);
             } else {
                 // have to go bit by bit
-                for bit_idx in 0..usize::BITS {
+                for bit_idx in 0..WORD_BITS {
                     let block_idx = block_idx(bit_idx);
                     if block_idx == block_count {
                         break;
                     }
 
-                    if value.get_bit(bit_idx) {
+                    if word::get_bit(*value, bit_idx) {
                         count_ones_per_block[block_idx].fetch_add(1, Ordering::Relaxed);
                     }
                 }
             }
         });
 
-    let pis = Box::into_iter(count_ones_per_block).map(|count_ones| {
-        let count_ones = count_ones.into_inner();
-        (count_ones as f64) / (block_length as f64)
-    });
-
-    // Step 3 - compute the chi^2 statistics - calculate the values for each element in the sum
-    let chi_parts = pis.map(|pi| (pi - 0.5).powi(2));
+    let count_ones_per_block = Box::into_iter(count_ones_per_block)
+        .map(AtomicUsize::into_inner)
+        .collect::<Box<[usize]>>();
 
-    // Step 3 - compute the chi^2 statistics - build sum and multiply with 4 * block_length
-    // In Step 4, chi is again halved - do this now (replace 4 with 2)
-    let half_chi = chi_parts.sum::<f64>() * 2.0 * (block_length as f64);
+    // Step 3 - compute the chi^2 statistics
+    // In Step 4, chi is again halved - do this now
+    let half_chi = if HIGH_PRECISION {
+        half_chi_square_exact(&count_ones_per_block, block_length)
+    } else {
+        half_chi_square(&count_ones_per_block, block_length)
+    };
 
     check_f64(half_chi)?;
 
@@ -121,32 +189,40 @@ pub fn frequency_block_test(
 
     check_f64(p_value)?;
 
-    Ok(TestResult::new(p_value))
+    Ok(
+        TestResult::new(p_value).with_statistics(TestStatistics::FrequencyWithinABlock {
+            block_length,
+            block_count,
+            chi_square: 2.0 * half_chi,
+        }),
+    )
 }
 
-/// Choose a block length based on 2.2.7. Needs the amount of bits as the parameter. If possible,
-/// it chooses usize-aligned blocks.
-fn choose_block_length(length: usize) -> usize {
-    const BITS: usize = usize::BITS as usize;
-    const MIN_BLOCK_LENGTH: usize = 20;
+/// Step 3: chi^2 / 2 = 2 * M * sum((pi_i - 1/2)^2), with pi_i = (ones in block i) / M.
+fn half_chi_square(count_ones_per_block: &[usize], block_length: usize) -> f64 {
+    let pis = count_ones_per_block
+        .iter()
+        .map(|&count_ones| (count_ones as f64) / (block_length as f64));
 
-    // Start with the recommended minimum block length based on the length of the data.
-    // This also satisfies that less than 100 block should exist.
-    let block_length = length / 100 + 1;
+    // calculate the values for each element in the sum
+    let chi_parts = pis.map(|pi| (pi - 0.5).powi(2));
 
-    if block_length < MIN_BLOCK_LENGTH {
-        MIN_BLOCK_LENGTH
-    } else {
-        // Round up to the next block length that is usize-aligned.
-        // This works by adding 63 and than truncating the lower bits.
-        let ideal_block_length = (block_length + BITS - 1) & !(BITS - 1);
+    // build sum and multiply with 2 * block_length
+    chi_parts.sum::<f64>() * 2.0 * (block_length as f64)
+}
 
-        // the ideal block length is possible as long as there are at least 2 blocks.
-        // 1 block would just be the frequency test.
-        if ideal_block_length * 2 <= length {
-            ideal_block_length
-        } else {
-            block_length
-        }
-    }
+/// Step 3 with the feature `high-precision`: chi^2 / 2 = sum((2 * ones_i - M)^2) / (2 * M), which
+/// is the same as [half_chi_square]. The sum is calculated exactly with integers, so that the only
+/// rounding error is in the final division. pub(crate) to allow for tests.
+pub(crate) fn half_chi_square_exact(count_ones_per_block: &[usize], block_length: usize) -> f64 {
+    // each part is at most M^2, and there are at most n / M blocks: the sum is at most n * M
+    let sum = count_ones_per_block
+        .iter()
+        .map(|&count_ones| {
+            let deviation = (2 * count_ones).abs_diff(block_length) as u128;
+            deviation * deviation
+        })
+        .sum::<u128>();
+
+    sum as f64 / (2.0 * block_length as f64)
 }
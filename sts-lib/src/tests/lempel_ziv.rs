@@ -0,0 +1,97 @@
+//! Lempel-Ziv compression test.
+//!
+//! **This test is not part of the current revision of NIST SP 800-22.** It was included in the
+//! original version of SP 800-22 (2001) and removed in revision 1, because the reference
+//! distribution of the test statistic turned out to be inaccurate. It is only implemented for
+//! comparative studies that still require it, it should not be used to assess the randomness
+//! of a sequence. Therefore, it is not part of [Test::default_set](crate::Test::default_set) and
+//! is only run if it is selected explicitly.
+//!
+//! This test counts the cumulatively distinct words of the sequence, as found by the Lempel-Ziv
+//! (LZ78) parsing: the sequence is split into words, each word being the shortest word that was
+//! not seen before. A sequence that can be compressed significantly contains fewer distinct words
+//! than expected for a random sequence.
+//!
+//! The expected mean and variance of the word count are only known for sequences of exactly 10^6
+//! bits, see [MEAN] and [VARIANCE]. Longer sequences are accepted, but only the first 10^6 bits are
+//! tested.
+
+use crate::bitvec::word;
+use crate::bitvec::BitVec;
+use crate::internals::{check_f64, erfc, trace_span};
+use crate::statistics::TestStatistics;
+use crate::{Error, TestResult};
+use std::num::NonZero;
+use sts_lib_derive::use_thread_pool;
+
+/// The minimum input length, in bits, for this test. Also the count of bits that are tested.
+pub const MIN_INPUT_LENGTH: NonZero<usize> = const {
+    match NonZero::new(1_000_000) {
+        Some(v) => v,
+        None => panic!("Literal should be non-zero!"),
+    }
+};
+
+/// The expected count of distinct words *μ* in a random sequence of 10^6 bits, as given by the
+/// original SP 800-22.
+pub const MEAN: f64 = 69586.25;
+
+/// The expected variance *σ²* of the count of distinct words in a random sequence of 10^6 bits, as
+/// given by the original SP 800-22.
+pub const VARIANCE: f64 = 70.448718;
+
+/// Lempel-Ziv compression test - No. 10 of the original SP 800-22, **not part of the current
+/// revision**.
+///
+/// See the [module docs](crate::tests::lempel_ziv).
+/// If the input is shorter than [MIN_INPUT_LENGTH], [Error::InvalidParameter] is returned.
+#[use_thread_pool]
+pub fn lempel_ziv_test(data: &BitVec) -> Result<TestResult, Error> {
+    trace_span!(INFO, "lempel_ziv_test", len_bit = data.len_bit());
+
+    if data.len_bit() < MIN_INPUT_LENGTH.get() {
+        return Err(Error::InvalidParameter(format!(
+            "Length of input data must be >= 10^6. Is: {}",
+            data.len_bit()
+        )));
+    }
+
+    // Step 1: parse the sequence into cumulatively distinct words, W_obs is the count of words.
+    let words = count_distinct_words(data, MIN_INPUT_LENGTH.get());
+
+    // Step 2: compute P-value = 1/2 * erfc((mean - W_obs) / sqrt(2 * variance))
+    let p_value = 0.5 * erfc((MEAN - words as f64) / f64::sqrt(2.0 * VARIANCE));
+
+    check_f64(p_value)?;
+
+    Ok(TestResult::new(p_value).with_statistics(TestStatistics::LempelZiv { words }))
+}
+
+/// Counts the cumulatively distinct words in the first `bit_len` bits of the sequence, with the
+/// Lempel-Ziv parsing. An incomplete word at the end (a word that was already seen) is not
+/// counted.
+/// pub(crate) to allow for tests.
+pub(crate) fn count_distinct_words(data: &BitVec, bit_len: usize) -> usize {
+    // All words seen so far are stored in a binary trie, each node has the index of the child for
+    // the bit 0 and 1. The root (index 0) is never a child, so 0 denotes a missing child.
+    let mut nodes: Vec<[u32; 2]> = vec![[0, 0]];
+    let mut current = 0;
+
+    for bit_idx in 0..bit_len {
+        let bit = word::get_bit_at(&data.words, bit_idx) as usize;
+
+        match nodes[current][bit] {
+            0 => {
+                // the current word is new: store it and start a new word.
+                // Cannot overflow: there are at most as many words as bits.
+                nodes[current][bit] = nodes.len() as u32;
+                nodes.push([0, 0]);
+                current = 0;
+            }
+            child => current = child as usize,
+        }
+    }
+
+    // the root is not a word
+    nodes.len() - 1
+}
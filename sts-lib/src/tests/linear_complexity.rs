@@ -11,8 +11,11 @@
 //! 0.010417) means that results may deviate significantly from the NIST reference implementation.
 //! This is expected behaviour.
 
+use crate::bitvec::word::{self, Word, WORD_BITS};
 use crate::bitvec::BitVec;
-use crate::internals::{check_f64, checked_add, checked_mul, get_bit_from_sequence, igamc};
+use crate::internals::{check_f64, checked_add, checked_mul, igamc, trace_span};
+use crate::statistics::TestStatistics;
+use crate::test_runner::cancellation::CancelCheck;
 use crate::{Error, TestResult};
 use rayon::prelude::*;
 use std::num::NonZero;
@@ -57,14 +60,89 @@ pub enum LinearComplexityTestArg {
     ChooseAutomatically,
 }
 
+/// The linear complexity of each tested block, as calculated by [linear_complexity_profile].
+///
+/// This is diagnostic output, e.g. for the analysis of stream ciphers: besides the result of the
+/// [linear_complexity_test], it contains the linear complexity *L_i* of each block, from which the
+/// deviations *T_i* from the theoretical mean and their histogram are derived.
+#[derive(Clone, Debug)]
+pub struct LinearComplexityProfile {
+    block_length: usize,
+    mean: f64,
+    complexities: Box<[usize]>,
+    histogram: [usize; FREEDOM_DEGREES + 1],
+    result: TestResult,
+}
+
+impl LinearComplexityProfile {
+    /// The used block length *M*.
+    pub fn block_length(&self) -> usize {
+        self.block_length
+    }
+
+    /// The theoretical mean *μ* of the linear complexity of a random block with length *M*.
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// The linear complexity *L_i* of each block, in the order of the blocks.
+    pub fn complexities(&self) -> &[usize] {
+        &self.complexities
+    }
+
+    /// The deviation *T_i = (-1)^M * (L_i - μ) + 2/9* of each block, in the order of the blocks.
+    pub fn t_values(&self) -> Vec<f64> {
+        self.complexities
+            .iter()
+            .map(|&l_i| t_value(l_i, self.mean, self.block_length))
+            .collect()
+    }
+
+    /// The histogram of the deviations *T_i*: the count of blocks *ν_0* to *ν_6* with
+    /// `T_i <= -2.5`, `-2.5 < T_i <= -1.5`, ..., `1.5 < T_i <= 2.5` and `T_i > 2.5`.
+    pub fn histogram(&self) -> [usize; FREEDOM_DEGREES + 1] {
+        self.histogram
+    }
+
+    /// The result of the [linear_complexity_test].
+    pub fn result(&self) -> TestResult {
+        self.result.clone()
+    }
+}
+
 /// The linear complexity test - No. 10
 ///
 /// See also the [module docs](crate::tests::linear_complexity).
+/// The histogram of the deviations is part of the statistics of the result, for the linear
+/// complexity of each block, use [linear_complexity_profile].
 #[use_thread_pool]
 pub fn linear_complexity_test(
     data: &BitVec,
     arg: LinearComplexityTestArg,
 ) -> Result<TestResult, Error> {
+    trace_span!(INFO, "linear_complexity_test", len_bit = data.len_bit());
+
+    calculate_profile(data, arg).map(|profile| profile.result)
+}
+
+/// The linear complexity test, additionally returning the linear complexity of each block.
+///
+/// The result is the same as with [linear_complexity_test], see [LinearComplexityProfile].
+#[use_thread_pool]
+pub fn linear_complexity_profile(
+    data: &BitVec,
+    arg: LinearComplexityTestArg,
+) -> Result<LinearComplexityProfile, Error> {
+    trace_span!(INFO, "linear_complexity_profile", len_bit = data.len_bit());
+
+    calculate_profile(data, arg)
+}
+
+/// Implementation of [linear_complexity_test] and [linear_complexity_profile].
+fn calculate_profile(
+    data: &BitVec,
+    arg: LinearComplexityTestArg,
+) -> Result<LinearComplexityProfile, Error> {
     // Step 0: validate input arguments
     if data.len_bit() < MIN_INPUT_LENGTH.get() {
         return Err(Error::InvalidParameter(format!(
@@ -105,65 +183,54 @@ pub fn linear_complexity_test(
         - ((block_length as f64) / 3.0 + 2.0 / 9.0) / f64::powi(2.0, block_length as i32);
 
     // Step 2: for each block, calculate the linear complexity L_i according to berlekamp massey
-    // Step 4: for each block, calculate T_i = (-1)^block_length * (L_i - mean) + 2/9
-    // Step 5: sort the T_i value into an array depending on their value
-    let table = (0..count_blocks)
+    let cancel = CancelCheck::current();
+    trace_span!(DEBUG, "berlekamp_massey", block_length, count_blocks);
+    let complexities = (0..count_blocks)
         .into_par_iter()
-        .try_fold(
-            || [0_usize; FREEDOM_DEGREES + 1],
-            |mut sum, block_idx| {
-                // calculate the start byte and the bit position in the start byte for this block
-                let total_start_bit = checked_mul!(block_idx, block_length)?;
-
-                let start_idx = total_start_bit / (usize::BITS as usize);
-                let start_bit_idx = total_start_bit % (usize::BITS as usize);
-
-                let end_idx =
-                    (checked_mul!(block_idx + 1, block_length)? - 1) / (usize::BITS as usize);
-
-                // Step 2
-                let l_i = berlekamp_massey(
-                    &data.words[start_idx..=end_idx],
-                    block_length,
-                    start_bit_idx,
-                );
-
-                // Step 4
-                let t_i = f64::powi(-1.0, block_length as i32) * ((l_i as f64) - mean) + 2.0 / 9.0;
-                check_f64(t_i)?;
-
-                // Step 5
-                let idx_to_increment = if t_i <= -2.5 {
-                    0
-                } else if t_i <= -1.5 {
-                    1
-                } else if t_i <= -0.5 {
-                    2
-                } else if t_i <= 0.5 {
-                    3
-                } else if t_i <= 1.5 {
-                    4
-                } else if t_i <= 2.5 {
-                    5
-                } else {
-                    6
-                };
-
-                sum[idx_to_increment] = checked_add!(sum[idx_to_increment], 1)?;
-
-                Ok::<_, Error>(sum)
-            },
-        )
-        .try_reduce(
-            || [0_usize; FREEDOM_DEGREES + 1],
-            |mut a, b| {
-                for i in 0..(FREEDOM_DEGREES + 1) {
-                    a[i] = checked_add!(a[i], b[i])?;
-                }
+        .map(|block_idx| {
+            cancel.check()?;
+
+            // calculate the start byte and the bit position in the start byte for this block
+            let total_start_bit = checked_mul!(block_idx, block_length)?;
+
+            let start_idx = word::word_index(total_start_bit);
+            let start_bit_idx = word::bit_in_word(total_start_bit);
 
-                Ok(a)
-            },
-        )?;
+            let end_idx = word::word_index(checked_mul!(block_idx + 1, block_length)? - 1);
+
+            Ok(berlekamp_massey(
+                &data.words[start_idx..=end_idx],
+                block_length,
+                start_bit_idx,
+            ))
+        })
+        .collect::<Result<Box<[usize]>, Error>>()?;
+
+    // Step 4: for each block, calculate T_i = (-1)^block_length * (L_i - mean) + 2/9
+    // Step 5: sort the T_i value into an array depending on their value
+    let mut table = [0_usize; FREEDOM_DEGREES + 1];
+    for &l_i in complexities.iter() {
+        let t_i = t_value(l_i, mean, block_length);
+        check_f64(t_i)?;
+
+        let idx_to_increment = if t_i <= -2.5 {
+            0
+        } else if t_i <= -1.5 {
+            1
+        } else if t_i <= -0.5 {
+            2
+        } else if t_i <= 0.5 {
+            3
+        } else if t_i <= 1.5 {
+            4
+        } else if t_i <= 2.5 {
+            5
+        } else {
+            6
+        };
+
+        table[idx_to_increment] = checked_add!(table[idx_to_increment], 1)?;
+    }
 
     // Step 6: compute chi^2 = sum of ( (tables[i] - count_blocks * pi[i])^2 / (count_blocks * pi[i]) )
     let chi = table
@@ -179,7 +246,25 @@ pub fn linear_complexity_test(
     // Step 7: compute p-value = igamc(freedom_degrees / 2, chi^2 / 2)
     let p_value = igamc(FREEDOM_DEGREES as f64 / 2.0, chi / 2.0)?;
 
-    Ok(TestResult::new(p_value))
+    let result = TestResult::new(p_value).with_statistics(TestStatistics::LinearComplexity {
+        block_length,
+        block_count: count_blocks,
+        histogram: table,
+        chi_square: chi,
+    });
+
+    Ok(LinearComplexityProfile {
+        block_length,
+        mean,
+        complexities,
+        histogram: table,
+        result,
+    })
+}
+
+/// Step 4: calculate T_i = (-1)^block_length * (L_i - mean) + 2/9
+fn t_value(l_i: usize, mean: f64, block_length: usize) -> f64 {
+    f64::powi(-1.0, block_length as i32) * ((l_i as f64) - mean) + 2.0 / 9.0
 }
 
 /// An implementation of the Berlekamp-Massey algorithm for calculating the linear complexity of a
@@ -188,20 +273,27 @@ pub fn linear_complexity_test(
 /// Inputs: the sequence stored as packed binary (8 bits per byte) + 1 optional byte additional bits,
 /// the bit length of the sequence to calculate the linear complexity for, the start bit in the
 /// sequence.
-pub(crate) fn berlekamp_massey(
-    sequence: &[usize],
+pub(crate) fn berlekamp_massey(sequence: &[Word], total_bit_len: usize, start_bit: usize) -> usize {
+    berlekamp_massey_with_polynomial(sequence, total_bit_len, start_bit).0
+}
+
+/// The same as [berlekamp_massey], but additionally returns the connection polynomial *C(D)* of
+/// the shortest LFSR: the coefficient of *D^i* is bit *i* of the returned words, starting with the
+/// MSB of the first word.
+pub(crate) fn berlekamp_massey_with_polynomial(
+    sequence: &[Word],
     total_bit_len: usize,
     start_bit: usize,
-) -> usize {
+) -> (usize, Vec<Word>) {
     // Initialize C(D) - saves the values of a binary polynom
-    let mut c: Vec<usize> = vec![0; total_bit_len / (usize::BITS as usize) + 1];
-    c[0] = 1 << (usize::BITS - 1);
+    let mut c: Vec<Word> = vec![0; total_bit_len / WORD_BITS + 1];
+    c[0] = word::bit_mask(0);
     // the linear complexity
     let mut l = 0_u32;
     // the value m
     let mut m = -1_i32;
     // B(D) - binary polynom
-    let mut b: Vec<usize> = vec![1 << (usize::BITS - 1)];
+    let mut b: Vec<Word> = vec![word::bit_mask(0)];
 
     let total_bit_len = total_bit_len as u32;
     let start_bit = start_bit as u32;
@@ -213,11 +305,11 @@ pub(crate) fn berlekamp_massey(
         // compute discrepancy
         let mut sum = false;
         for i in 1..(l + 1) {
-            sum ^=
-                get_bit_from_sequence(&c, i) & get_bit_from_sequence(sequence, start_bit + n - i);
+            sum ^= word::get_bit_at(&c, i as usize)
+                & word::get_bit_at(sequence, (start_bit + n - i) as usize);
         }
 
-        let s_n = get_bit_from_sequence(sequence, start_bit + n);
+        let s_n = word::get_bit_at(sequence, (start_bit + n) as usize);
         let d = s_n ^ sum;
 
         if d {
@@ -225,8 +317,8 @@ pub(crate) fn berlekamp_massey(
 
             // addition of polynoms: shift is the power
             let shift = n.wrapping_add_signed(-m); // = n - m
-            let idx_forward = (shift / usize::BITS) as usize;
-            let shift = shift % usize::BITS;
+            let idx_forward = word::word_index(shift as usize);
+            let shift = word::bit_in_word(shift as usize);
 
             for (idx, bit) in b.iter().enumerate() {
                 if idx + idx_forward < c.len() {
@@ -234,7 +326,7 @@ pub(crate) fn berlekamp_massey(
                     c[idx + idx_forward] ^= shifted_value;
 
                     if idx + idx_forward + 1 < c.len() && shift > 0 {
-                        let carry_over = bit << (usize::BITS - shift);
+                        let carry_over = bit << (WORD_BITS - shift);
                         c[idx + idx_forward + 1] ^= carry_over;
                     }
                 }
@@ -248,5 +340,5 @@ pub(crate) fn berlekamp_massey(
         }
     }
 
-    l as usize
+    (l as usize, c)
 }
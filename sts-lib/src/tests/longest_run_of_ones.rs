@@ -8,13 +8,19 @@
 //!
 //! The data has to be at least 128 bits in length.
 //!
+//! SP 800-22 specifies three configurations with different block lengths *M* (8, 128 and 10^4 bits),
+//! which are chosen automatically based on the input length. To use a specific configuration, e.g.
+//! to compare a part of a sequence against the results of another tool, use
+//! [LongestRunOfOnesTestArg].
+//!
 //! The probability constants were recalculated, so you might see a deviation when comparing the
 //! output with the reference implementation. In testing, the deviations were not too big.
 
 use crate::bitvec::chunks::Chunk;
 use crate::bitvec::BitVec;
-use crate::internals::{check_f64, checked_add, igamc, BitPrimitive};
-use crate::{Error, TestResult};
+use crate::internals::{check_f64, checked_add, igamc, trace_span, BitPrimitive};
+use crate::statistics::TestStatistics;
+use crate::{ArgError, Error, TestResult};
 use rayon::prelude::*;
 use std::num::NonZero;
 use sts_lib_derive::use_thread_pool;
@@ -53,30 +59,123 @@ const PROBABILITIES_10_4: [f64; 7] = [
     0.07336609745614353,
 ];
 
+/// The argument for the Longest Run of Ones in a Block test: the configuration of SP 800-22,
+/// section 2.4.2, i.e. the block length *M* and with it the count of classes *K + 1* and their
+/// probabilities.
+///
+/// Each configuration has a minimum input length, as given in section 2.4.2. If the input is
+/// shorter, [Error::InvalidParameter] will be returned when executing the test,
+/// [longest_run_of_ones_test].
+///
+/// The default value for this argument is [LongestRunOfOnesTestArg::ChooseAutomatically].
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub enum LongestRunOfOnesTestArg {
+    /// The configuration is chosen based on the input length, as recommended in section 2.4.2.
+    #[default]
+    ChooseAutomatically,
+    /// *M = 8*, *K = 3*. Requires at least 128 bits.
+    Block8,
+    /// *M = 128*, *K = 5*. Requires at least 6272 bits.
+    Block128,
+    /// *M = 10^4*, *K = 6*. Requires at least 750 000 bits.
+    Block10000,
+}
+
+impl LongestRunOfOnesTestArg {
+    /// Creates the argument for the configuration with the given block length: 8, 128 or 10000.
+    /// Returns [ArgError::NotAllowed] for any other block length.
+    pub fn try_new(block_length: usize) -> Result<Self, ArgError> {
+        match block_length {
+            8 => Ok(Self::Block8),
+            128 => Ok(Self::Block128),
+            10_000 => Ok(Self::Block10000),
+            value => Err(ArgError::NotAllowed {
+                name: "block length",
+                value,
+                allowed: &[8, 128, 10_000],
+            }),
+        }
+    }
+
+    /// Like [Self::try_new], but returns `None` instead of the reason.
+    #[deprecated(note = "use `try_new`, which reports why the arguments are invalid")]
+    pub fn new(block_length: usize) -> Option<Self> {
+        Self::try_new(block_length).ok()
+    }
+
+    /// The block length *M* of the configuration, `None` if it is chosen automatically.
+    pub fn block_length(&self) -> Option<usize> {
+        match self {
+            Self::ChooseAutomatically => None,
+            Self::Block8 => Some(8),
+            Self::Block128 => Some(128),
+            Self::Block10000 => Some(10_000),
+        }
+    }
+
+    /// The block length *M* that is used for an input with the given length in bits: the block
+    /// length of the configuration or, if it is chosen automatically, the block length given in
+    /// section 2.4.2 for the input length.
+    pub fn block_length_for(&self, length: usize) -> usize {
+        self.block_length().unwrap_or(match length {
+            0..=6271 => 8,
+            6272..=749_999 => 128,
+            750_000.. => 10_000,
+        })
+    }
+
+    /// The minimum input length, in bits, of the configuration, as given in section 2.4.2.
+    pub fn min_input_length(&self) -> NonZero<usize> {
+        match self {
+            Self::ChooseAutomatically | Self::Block8 => MIN_INPUT_LENGTH,
+            Self::Block128 => NonZero::new(6272).unwrap(),
+            Self::Block10000 => NonZero::new(750_000).unwrap(),
+        }
+    }
+}
+
 /// Test for the longest run of ones in a block - No. 4
 ///
-/// See the [module docs](crate::tests::longest_run_of_ones)
+/// See the [module docs](crate::tests::longest_run_of_ones).
+/// If the data is shorter than the minimum length of the chosen configuration,
+/// [Error::InvalidParameter] is raised. See [LongestRunOfOnesTestArg].
 #[use_thread_pool]
-pub fn longest_run_of_ones_test(data: &BitVec) -> Result<TestResult, Error> {
+pub fn longest_run_of_ones_test(
+    data: &BitVec,
+    test_arg: LongestRunOfOnesTestArg,
+) -> Result<TestResult, Error> {
+    trace_span!(INFO, "longest_run_of_ones_test", len_bit = data.len_bit());
+
+    let min_input_length = test_arg.min_input_length().get();
+    if data.len_bit() < min_input_length {
+        return Err(Error::InvalidParameter(format!(
+            "Input length has to be at least {min_input_length} bits, is {}",
+            data.len_bit()
+        )));
+    }
+
     // Step 0: determine the block length and the block count, based on 2.4.2.
     // Also determine the values bucket_count (= K + 1) and n, as given 2.4.4
     // All possible values are whole bytes.
-    match data.len_bit() {
-        bit_len @ 0..=127 => Err(Error::InvalidParameter(format!(
-            "Input length has to be at least 128 bits, is {}",
-            bit_len
-        ))),
-        128..=6271 => {
+    let block_length = test_arg.block_length_for(data.len_bit());
+
+    match block_length {
+        8 => {
             let data = data.par_chunks_exact(8 / (u8::BITS as usize));
-            longest_run_of_ones_imp(data, TABLE_SORTING_CRITERIA_8, PROBABILITIES_8)
+            longest_run_of_ones_imp(data, 8, TABLE_SORTING_CRITERIA_8, PROBABILITIES_8)
         }
-        6272..=749_999 => {
+        128 => {
             let data = data.par_chunks_exact(128 / (u8::BITS as usize));
-            longest_run_of_ones_imp(data, TABLE_SORTING_CRITERIA_128, PROBABILITIES_128)
+            longest_run_of_ones_imp(data, 128, TABLE_SORTING_CRITERIA_128, PROBABILITIES_128)
         }
-        750_000.. => {
+        _ => {
             let data = data.par_chunks_exact(10_000 / (u8::BITS as usize));
-            longest_run_of_ones_imp(data, TABLE_SORTING_CRITERIA_10_4, PROBABILITIES_10_4)
+            longest_run_of_ones_imp(
+                data,
+                10_000,
+                TABLE_SORTING_CRITERIA_10_4,
+                PROBABILITIES_10_4,
+            )
         }
     }
 }
@@ -85,6 +184,7 @@ pub fn longest_run_of_ones_test(data: &BitVec) -> Result<TestResult, Error> {
 /// Each chunk must have exactly block_size bits.
 fn longest_run_of_ones_imp<'a, const BUCKET_COUNT: usize>(
     data: impl IndexedParallelIterator<Item = Chunk<'a>>,
+    block_length: usize,
     table_criteria: [usize; BUCKET_COUNT],
     probabilities: [f64; BUCKET_COUNT],
 ) -> Result<TestResult, Error> {
@@ -118,7 +218,7 @@ fn longest_run_of_ones_imp<'a, const BUCKET_COUNT: usize>(
             || [0_usize; BUCKET_COUNT],
             |mut a, b| -> Result<_, Error> {
                 a.iter_mut()
-                    .zip(b.into_iter())
+                    .zip(b)
                     .try_for_each(|(a, b)| -> Result<(), Error> {
                         *a = checked_add!(a, b)?;
                         Ok(())
@@ -149,7 +249,13 @@ fn longest_run_of_ones_imp<'a, const BUCKET_COUNT: usize>(
     check_f64(param2)?;
     let p_value = igamc(param1, param2)?;
     check_f64(p_value)?;
-    Ok(TestResult::new(p_value))
+    Ok(
+        TestResult::new(p_value).with_statistics(TestStatistics::LongestRunOfOnes {
+            block_length,
+            block_count,
+            chi_square: chi,
+        }),
+    )
 }
 
 /// Handles a part of the current Chunk: start, middle, or end. Calculates the current run length
@@ -7,10 +7,15 @@
 //! be used is 2020 bits, smaller inputs will raise an error.
 
 use crate::bitvec::BitVec;
-use crate::internals::{check_f64, checked_mul, erfc};
-use crate::{Error, TestResult};
+use crate::internals::{check_f64, erfc, trace_span, F64Sum};
+use crate::statistics::TestStatistics;
+use crate::test_runner::cancellation::CancelCheck;
+use crate::{Error, ResultStatus, TestResult};
+use rayon::prelude::*;
+use std::borrow::Cow;
 use std::f64::consts::SQRT_2;
 use std::num::NonZero;
+use std::ops::Range;
 use sts_lib_derive::use_thread_pool;
 
 /// The minimum input length, in bits, for this test.
@@ -21,6 +26,15 @@ pub const MIN_INPUT_LENGTH: NonZero<usize> = const {
     }
 };
 
+/// The input length, in bits, recommended by NIST: the minimum length for a block length *L* of 6.
+/// Shorter inputs are tested with a smaller block length.
+pub const RECOMMENDED_INPUT_LENGTH: NonZero<usize> = const {
+    match NonZero::new(387_840) {
+        Some(v) => v,
+        None => panic!("Literal should be non-zero!"),
+    }
+};
+
 /// The expected statistic values µ. The index is the block length *L* - 1, i.e. the array is
 /// defined for 1 <= *L* <= 16.
 ///
@@ -44,14 +58,15 @@ const VARIANCES: [f64; 16] = [
 /// See also the [module docs](crate::tests::maurers_universal_statistical).
 #[use_thread_pool]
 pub fn maurers_universal_statistical_test(data: &BitVec) -> Result<TestResult, Error> {
+    trace_span!(
+        INFO,
+        "maurers_universal_statistical_test",
+        len_bit = data.len_bit()
+    );
+
     // Step 0: calculate which block length L is fitting and the other inputs based on that
     let data_len = data.len_bit();
-    let block_length = (1..17).rev().find(|&l| {
-        let min_data_len = (1010 * usize::pow(2, l as u32)) * l;
-        data_len >= min_data_len
-    });
-
-    let Some(block_length) = block_length else {
+    let Some(block_length) = choose_block_length(data_len) else {
         return Err(Error::InvalidParameter(format!(
             "length of data ({data_len}) is too small!"
         )));
@@ -59,7 +74,9 @@ pub fn maurers_universal_statistical_test(data: &BitVec) -> Result<TestResult, E
 
     // result should contain a warning if input size is smaller than recommended
     let result_comment = if block_length < 6 {
-        Some("length of data is < 387 840!")
+        Some(Cow::Owned(format!(
+            "length of data ({data_len}) is < 387 840! Chose block length L = {block_length}."
+        )))
     } else {
         None
     };
@@ -70,48 +87,65 @@ pub fn maurers_universal_statistical_test(data: &BitVec) -> Result<TestResult, E
 
     // Step 2: create a table for each possible L-bit value
     let mut table = vec![0; 1 << block_length].into_boxed_slice();
+    let mut filled = 0;
 
     // Step 2: fill the table with the block number of the last occurrence of the pattern in the
     // init blocks.
-    for block_idx in (0..count_init_blocks).rev() {
-        // calculate the start byte and the bit position in the start byte for this block
-        let total_start_bit = checked_mul!(block_idx, block_length)?;
-
-        let current_block = extract_block(data, total_start_bit, block_length);
-
+    let init_blocks = data
+        .windows(block_length, block_length)
+        .range(0..count_init_blocks);
+    for (block_idx, current_block) in init_blocks.enumerate().rev() {
         // save the block idx if it no later block was already found
         if table[current_block] == 0 {
             table[current_block] = block_idx + 1;
-        }
+            filled += 1;
 
-        // if all table entries are filled, the loop can be stopped early
-        if table.iter().all(|&v| v != 0) {
-            break;
+            // if all table entries are filled, the loop can be stopped early
+            if filled == table.len() {
+                break;
+            }
         }
     }
 
     // Step 3: examine all test blocks, for each block determine the number of blocks (distance)
     // since the last occurrence of the same block (index of the last block is stored in the table).
     // Add log2(distance) to the sum.
-    // Because of the needed mutable (and ordered) access to the table, this operation cannot run parallel.
-    let mut sum = 0.0;
-
-    for block_idx in 0..count_test_blocks {
-        let block_idx = block_idx + count_init_blocks;
-
-        let total_start_bit = checked_mul!(block_idx, block_length)?;
-
-        let current_block = extract_block(data, total_start_bit, block_length);
-
-        let last_block_idx = table[current_block];
-        table[current_block] = block_idx + 1;
-        sum += f64::log2((block_idx + 1 - last_block_idx) as f64);
+    // The test blocks are scanned in chunks in parallel, each with its own table. The distances of
+    // the first occurrences in each chunk depend on the previous chunks, they are added when the
+    // chunks are merged in order. The chunks do not depend on the thread count, so that the
+    // result is always the same.
+    let chunk_length = chunk_length(block_length, count_test_blocks);
+    let log2 = Log2Table::new(block_length);
+    let cancel = CancelCheck::current();
+    let scans = (0..count_test_blocks.div_ceil(chunk_length))
+        .into_par_iter()
+        .map(|chunk| {
+            cancel.check()?;
+
+            let start = count_init_blocks + chunk * chunk_length;
+            let end = count_init_blocks + count_test_blocks.min((chunk + 1) * chunk_length);
+            Ok(ChunkScan::new(data, block_length, &log2, start..end))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let mut sum = F64Sum::default();
+    for scan in scans {
+        for (pattern, last_block_idx) in table.iter_mut().enumerate() {
+            let first = scan.first[pattern];
+            if first != 0 {
+                sum.add(log2.get(first - *last_block_idx));
+                *last_block_idx = scan.last[pattern];
+            }
+        }
+        sum = sum + scan.sum;
     }
+    let sum = sum.value();
 
     check_f64(sum)?;
 
     // Step 4: compute the test statistic: f_n = sum / K .
     // K denotes the count of test blocks.
+    let test_block_count = count_test_blocks;
     let count_test_blocks = count_test_blocks as f64;
     let f_n = sum / count_test_blocks;
     check_f64(f_n)?;
@@ -122,9 +156,10 @@ pub fn maurers_universal_statistical_test(data: &BitVec) -> Result<TestResult, E
     let variance = VARIANCES[block_length - 1];
     let expected_value = EXPECTED_VALUES[block_length - 1];
 
-    let block_length = block_length as f64;
-    let c = 0.7 - (0.8 / block_length)
-        + (4.0 + 32.0 / block_length) * (f64::powf(count_test_blocks, -3.0 / block_length) / 15.0);
+    let block_length_f64 = block_length as f64;
+    let c = 0.7 - (0.8 / block_length_f64)
+        + (4.0 + 32.0 / block_length_f64)
+            * (f64::powf(count_test_blocks, -3.0 / block_length_f64) / 15.0);
     let sigma = c * f64::sqrt(variance / count_test_blocks);
 
     let p_value = erfc(f64::abs((f_n - expected_value) / (SQRT_2 * sigma)));
@@ -133,36 +168,98 @@ pub fn maurers_universal_statistical_test(data: &BitVec) -> Result<TestResult, E
     Ok(TestResult {
         p_value,
         comment: result_comment,
+        label: None,
+        statistics: Some(TestStatistics::MaurersUniversalStatistical {
+            block_length,
+            init_block_count: count_init_blocks,
+            test_block_count,
+            f_n,
+            expected_value,
+            sigma,
+        }),
+        status: ResultStatus::Applicable,
     })
 }
 
-/// Extract a usize value with length block_length, starting from the start_bit_idx in the BitVec.
-/// The block length may not be more than `usize::BITS`, i.e. not more than 32.
-///
-/// The highest index of the block will be stored in the LSB.
-const fn extract_block(data: &BitVec, total_start_bit_idx: usize, block_size_bits: usize) -> usize {
-    const BITS: usize = usize::BITS as usize;
-
-    debug_assert!(block_size_bits < BITS);
-
-    // calculate necessary indices
-    let start_idx = total_start_bit_idx / BITS;
-    let start_bit_idx = total_start_bit_idx % BITS;
+/// The block length *L* used for an input with the given length in bits: the largest *L* with
+/// at least *1010 * 2^L * L* bits, `None` if the input is too short for any block length.
+pub(crate) fn choose_block_length(data_len: usize) -> Option<usize> {
+    (1..17).rev().find(|&l| {
+        let min_data_len = (1010 * usize::pow(2, l as u32)) * l;
+        data_len >= min_data_len
+    })
+}
 
-    let end_bit_idx = start_bit_idx + block_size_bits - 1;
-    let end_idx = start_idx + (end_bit_idx / BITS);
+/// The maximum count of chunks that the test blocks are split into, see [chunk_length]. Each
+/// chunk needs 2 tables with an entry for each possible block.
+const MAX_CHUNK_COUNT: usize = 32;
+
+/// The count of test blocks in each chunk that is scanned in parallel: at least 64 times the count
+/// of possible blocks, so that the tables of the chunks are small compared to the data, and large
+/// enough to need at most [MAX_CHUNK_COUNT] chunks.
+pub(crate) fn chunk_length(block_length: usize, count_test_blocks: usize) -> usize {
+    usize::max(
+        64 << block_length,
+        count_test_blocks.div_ceil(MAX_CHUNK_COUNT),
+    )
+}
 
-    // Calculate the shift
-    let shift = BITS - (end_bit_idx % BITS) - 1;
+/// The scan of a chunk of test blocks, see step 3.
+struct ChunkScan {
+    /// The sum of log2(distance) for all blocks whose previous occurrence is in the chunk.
+    sum: F64Sum,
+    /// The block number (block index + 1) of the first occurrence of each pattern in the chunk, 0
+    /// if the pattern does not occur.
+    first: Box<[usize]>,
+    /// The block number of the last occurrence of each pattern in the chunk, 0 if the pattern does
+    /// not occur.
+    last: Box<[usize]>,
+}
 
-    // create the mask
-    let mask = (1 << block_size_bits) - 1;
+impl ChunkScan {
+    /// Scans the blocks with the given indices.
+    fn new(data: &BitVec, block_length: usize, log2: &Log2Table, blocks: Range<usize>) -> Self {
+        let mut sum = F64Sum::default();
+        let mut first = vec![0; 1 << block_length].into_boxed_slice();
+        let mut last = vec![0; 1 << block_length].into_boxed_slice();
+
+        let mut block_number = blocks.start;
+        let windows = data.windows(block_length, block_length).range(blocks);
+        windows.for_each(|current_block| {
+            block_number += 1;
+            if last[current_block] == 0 {
+                first[current_block] = block_number;
+            } else {
+                sum.add(log2.get(block_number - last[current_block]));
+            }
+            last[current_block] = block_number;
+        });
+
+        Self { sum, first, last }
+    }
+}
 
-    let value = if start_idx == end_idx {
-        data.words[start_idx] >> shift
-    } else {
-        data.words[start_idx] << (BITS - shift) | data.words[end_idx] >> shift
-    };
+/// The values of [f64::log2] for the distances up to 8 times the count of possible blocks. The
+/// distances are geometrically distributed with a mean of about the count of possible blocks, so
+/// that almost all of them are looked up instead of calculated, with the same results.
+struct Log2Table(Box<[f64]>);
+
+impl Log2Table {
+    /// Calculates the table for the given block length.
+    fn new(block_length: usize) -> Self {
+        Self(
+            (0..(8 << block_length))
+                .map(|d| f64::log2(d as f64))
+                .collect(),
+        )
+    }
 
-    value & mask
+    /// log2 of the distance.
+    #[inline]
+    fn get(&self, distance: usize) -> f64 {
+        match self.0.get(distance) {
+            Some(&value) => value,
+            None => f64::log2(distance as f64),
+        }
+    }
 }
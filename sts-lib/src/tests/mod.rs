@@ -1,19 +1,39 @@
 //! All NIST STS tests. See the module documentation for details about each test.
+//!
+//! Without the feature `std`, only the frequency, frequency within a block, runs, serial,
+//! approximate entropy and cumulative sums tests are available.
 
+#[cfg(feature = "std")]
 pub mod binary_matrix_rank;
 pub mod frequency;
 pub mod frequency_block;
+#[cfg(feature = "std")]
 pub mod linear_complexity;
+#[cfg(feature = "std")]
 pub mod longest_run_of_ones;
+#[cfg(feature = "std")]
 pub mod maurers_universal_statistical;
 pub mod runs;
+#[cfg(feature = "std")]
 pub mod spectral_dft;
+#[cfg(feature = "std")]
 pub mod template_matching;
 // The approximate entropy test and the serial test share some code.
 // This module contains them both, for API consistency, both modules are re-exported as if they
 // were defined in this module.
-mod serial_and_approximate_entropy;
+pub(crate) mod serial_and_approximate_entropy;
 pub use serial_and_approximate_entropy::{approximate_entropy, serial};
 pub mod cumulative_sums;
+#[cfg(feature = "std")]
 pub mod random_excursions;
+#[cfg(feature = "std")]
 pub mod random_excursions_variant;
+// Not part of the current revision of SP 800-22.
+#[cfg(feature = "std")]
+pub mod lempel_ziv;
+// Not part of SP 800-22, but of BSI AIS 31.
+#[cfg(feature = "std")]
+pub mod autocorrelation;
+// Not a test, but a diagnostic without a P-value.
+#[cfg(feature = "std")]
+pub mod run_length_histogram;
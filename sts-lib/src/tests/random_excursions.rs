@@ -6,23 +6,28 @@
 //! a random sequence. A cycle consists of all cumulative sums between 2 "0"-values.
 //!
 //! Since the test needs at least 500 cycles to occur, bit sequences with fewer cycles will lead to an
-//! `Ok()` result, but with the values filled with "0.0".
+//! `Ok()` result, but with the values filled with "0.0" and the status set to
+//! [NotApplicable](crate::ResultStatus::NotApplicable), see [TestResult::status].
 //!
-//! If the computation finishes successfully, 8 [TestResult] are returned: one for each tested state,
-//! `x`. The results will contain a comment about the state they are calculated from (e.g. "x = 3"),
-//! the order is: `[-4, -3, -2, -1, +1, +2, +3, +4]`.
+//! The tested states *x* can be configured with [RandomExcursionsTestArg], NIST uses the states
+//! `-4..=+4`. If the computation finishes successfully, one [TestResult] is returned for each
+//! tested state. The results will contain a comment about the state they are calculated from
+//! (e.g. "x = +3"), the order is ascending: `[-4, -3, -2, -1, +1, +2, +3, +4]` with the defaults.
 //!
 //! The input length must be at least 10^6 bits, otherwise, an error is returned.
 //!
-//! Because the constants in this test are given as fractions for more precision, the results may
-//! deviate from the NIST reference implementation. In testing, the observed deviation was not too
-//! big.
+//! Because the probabilities in this test are calculated exactly instead of using the rounded
+//! constants of NIST, the results may deviate from the NIST reference implementation. In testing,
+//! the observed deviation was not too big.
 
+use crate::bitvec::word::{self, Word, WORD_BITS};
 use crate::bitvec::BitVec;
-use crate::internals::{check_f64, igamc, BitPrimitive};
-use crate::{Error, TestResult};
+use crate::internals::{check_f64, igamc, trace_span};
+use crate::statistics::TestStatistics;
+use crate::{ArgError, Error, ResultLabel, TestResult};
 use std::num::NonZero;
 use std::ops::Range;
+use std::sync::LazyLock;
 use sts_lib_derive::use_thread_pool;
 
 /// The minimum input length, in bits, for this test, as recommended by NIST.
@@ -33,24 +38,59 @@ pub const MIN_INPUT_LENGTH: NonZero<usize> = const {
     }
 };
 
-/// Constant probabilities, calculated with python script `random_excursions.py`, with the values
-/// reinterpreted as fractions.
-#[rustfmt::skip]
-const PROBABILITIES: [[f64; 8]; 6] = [
-    [    7.0 /      8.0,   5.0 /    6.0,  3.0 /    4.0, 1.0 /  2.0, 1.0 /  2.0,  3.0 /    4.0,   5.0 /    6.0,     7.0 /      8.0 ],
-    [    1.0 /     64.0,   1.0 /   36.0,  1.0 /   16.0, 1.0 /  4.0, 1.0 /  4.0,  1.0 /   16.0,   1.0 /   36.0,     1.0 /     64.0 ],
-    [    7.0 /    512.0,   5.0 /  216.0,  3.0 /   64.0, 1.0 /  8.0, 1.0 /  8.0,  3.0 /   64.0,   5.0 /  216.0,     7.0 /    512.0 ],
-    [   49.0 /   4096.0,  25.0 / 1296.0,  9.0 /  256.0, 1.0 / 16.0, 1.0 / 16.0,  9.0 /  256.0,  25.0 / 1296.0,    49.0 /   4096.0 ],
-    [  343.0 / 32_768.0, 125.0 / 7776.0, 27.0 / 1024.0, 1.0 / 32.0, 1.0 / 32.0, 27.0 / 1024.0, 125.0 / 7776.0,   343.0 / 32_768.0 ],
-    [ 2401.0 / 32_768.0, 625.0 / 7776.0, 81.0 / 1024.0, 1.0 / 32.0, 1.0 / 32.0, 81.0 / 1024.0, 625.0 / 7776.0,  2401.0 / 32_768.0 ],
-];
+/// The maximum state that can be tested, see [RandomExcursionsTestArg] and
+/// [RandomExcursionsVariantTestArg](crate::tests::random_excursions_variant::RandomExcursionsVariantTestArg).
+pub const MAX_STATE: u8 = 32;
+
+/// The argument for the random excursions test: the maximum state *x* to test. All states
+/// `-x..=-1` and `+1..=+x` are tested.
+///
+/// The maximum state must be between 1 and [MAX_STATE], this is checked when creating the argument.
+/// Larger states are visited less often, so the expected counts of the rare classes get small -
+/// for states above the default, more cycles than the required minimum are recommended.
+///
+/// The default value for this argument is 4, as used by NIST.
+#[derive(Copy, Clone, Debug)]
+#[repr(transparent)]
+pub struct RandomExcursionsTestArg(u8);
+
+impl RandomExcursionsTestArg {
+    /// To create a new instance of [RandomExcursionsTestArg]. Returns [ArgError::OutOfRange] if the
+    /// maximum state is not between 1 and [MAX_STATE].
+    pub fn try_new(max_state: u8) -> Result<Self, ArgError> {
+        ArgError::check_range("maximum state", max_state.into(), 1, MAX_STATE.into())?;
+        Ok(Self(max_state))
+    }
+
+    /// Like [Self::try_new], but returns `None` instead of the reason.
+    #[deprecated(note = "use `try_new`, which reports why the arguments are invalid")]
+    pub fn new(max_state: u8) -> Option<Self> {
+        Self::try_new(max_state).ok()
+    }
+
+    /// The maximum tested state.
+    pub fn max_state(&self) -> u8 {
+        self.0
+    }
+}
+
+impl Default for RandomExcursionsTestArg {
+    fn default() -> Self {
+        Self(4)
+    }
+}
 
 /// Random excursions test - No. 14
 ///
 /// See the [module docs](crate::tests::random_excursions).
 /// If the given [BitVec] contains fewer than 10^6 bits, [Error::InvalidParameter] is returned.
 #[use_thread_pool]
-pub fn random_excursions_test(data: &BitVec) -> Result<[TestResult; 8], Error> {
+pub fn random_excursions_test(
+    data: &BitVec,
+    RandomExcursionsTestArg(max_state): RandomExcursionsTestArg,
+) -> Result<Vec<TestResult>, Error> {
+    trace_span!(INFO, "random_excursions_test", len_bit = data.len_bit());
+
     #[cfg(not(test))]
     {
         if data.len_bit() < MIN_INPUT_LENGTH.get() {
@@ -62,36 +102,34 @@ pub fn random_excursions_test(data: &BitVec) -> Result<[TestResult; 8], Error> {
     }
 
     // Steps 1 to 5: calculate the cum sums (stored in prev), increment a counter per state
-    // per cycle, dynamically create a new entry per cycle. The count of cycles can be determined
-    // afterwards from the length of states_per_cycle.
-    let mut states_per_cycle = vec![[0_u8; 8]];
-    let mut last_index = 0;
+    // for the current cycle.
+    // Step 6: at the end of each cycle, compute v_k(x) = the total number of cycles in which state
+    // x occurred exactly k times, for k = 0, 1, 2, 3, 4, >= 5
+    let state_count = 2 * max_state as usize;
+    let mut cycles = Cycles {
+        max_state: max_state as i64,
+        current: vec![0; state_count],
+        v: vec![[0; 6]; state_count],
+        count: 0,
+    };
     let mut prev: i64 = 0;
 
     let (words, last_word) = data.as_full_slice();
 
     for &word in words {
-        handle_word(
-            word,
-            0..usize::BITS,
-            &mut prev,
-            &mut last_index,
-            &mut states_per_cycle,
-        );
+        handle_word(word, 0..WORD_BITS, &mut prev, &mut cycles);
     }
 
     if let Some(word) = last_word {
-        let bits = 0..(data.bit_count_last_word as u32);
-        handle_word(
-            word,
-            bits,
-            &mut prev,
-            &mut last_index,
-            &mut states_per_cycle,
-        );
+        let bits = 0..(data.bit_count_last_word as usize);
+        handle_word(word, bits, &mut prev, &mut cycles);
     }
 
-    let num_cycles = states_per_cycle.len();
+    // the last cycle ends with the sequence
+    cycles.end_cycle();
+    let Cycles {
+        v, count: cycles, ..
+    } = cycles;
 
     // only check this property when not running unit tests.
     #[cfg(not(test))]
@@ -99,99 +137,159 @@ pub fn random_excursions_test(data: &BitVec) -> Result<[TestResult; 8], Error> {
         // check the condition based on the last paragraph of 3-22, somehow, this information
         // is missing in 2.14?
         let min_cycles = f64::max(0.005 * f64::sqrt(data.len_bit() as f64), 500.0);
-        if (num_cycles as f64) < min_cycles {
-            return Ok([TestResult::new_with_comment(0.0, "Too few cycles"); 8]);
+        if (cycles as f64) < min_cycles {
+            let required = min_cycles.ceil() as usize;
+            let reason = crate::NotApplicableReason::TooFewCycles { cycles, required };
+            return Ok((0..state_count)
+                .map(|i| {
+                    TestResult::not_applicable(too_few_cycles_comment(cycles, required), reason)
+                        .with_statistics(TestStatistics::RandomExcursions {
+                            state: state_of_index(i, max_state),
+                            cycles,
+                            chi_square: None,
+                        })
+                        .with_label(ResultLabel::ExcursionState(state_of_index(i, max_state)))
+                })
+                .collect());
         }
     }
 
-    let num_cycles = num_cycles as f64;
-
-    // Step 6: based on states_per_cycle, compute v_k(x) = the total number of cycles in which state
-    // x occurred exactly k times, for k = 0, 1, 2, 3, 4, >= 5
-    let mut v = [[0_usize; 8]; 6];
-    states_per_cycle
-        .into_iter()
-        .flat_map(|cycle| cycle.into_iter().enumerate())
-        .for_each(|(state, occurrences)| {
-            let idx = occurrences.clamp(0, 5) as usize;
-            v[idx][state] += 1;
-        });
+    let num_cycles = cycles as f64;
 
     // Step 7: for each state, compute chi = sum_{k} ( v_k(x) - J * pi_k(x) )^2 / ( J * pi_k(x) ).
-    // pi_k(x) is the precalculated probability.
-    let mut chis = [0.0; 8];
+    // Step 8: for each state, compute p-value = igamc(5/2, chi^2 / 2)
     v.into_iter()
-        .zip(PROBABILITIES)
-        .flat_map(|(v_k, pi_k)| v_k.into_iter().zip(pi_k).enumerate())
-        .for_each(|(i, (v_k_x, pi_k_x))| {
-            chis[i] += f64::powi(v_k_x as f64 - num_cycles * pi_k_x, 2) / (num_cycles * pi_k_x);
-        });
-
-    let mut p_values = [
-        TestResult::new_with_comment(0.0, "x = -4"),
-        TestResult::new_with_comment(0.0, "x = -3"),
-        TestResult::new_with_comment(0.0, "x = -2"),
-        TestResult::new_with_comment(0.0, "x = -1"),
-        TestResult::new_with_comment(0.0, "x = +1"),
-        TestResult::new_with_comment(0.0, "x = +2"),
-        TestResult::new_with_comment(0.0, "x = +3"),
-        TestResult::new_with_comment(0.0, "x = +4"),
-    ];
-    chis.into_iter()
         .enumerate()
-        .try_for_each(|(i, chi)| -> Result<(), Error> {
+        .map(|(i, v_x)| {
+            let state = state_of_index(i, max_state);
+            let pi_x = probabilities(state);
+
+            let chi = v_x
+                .into_iter()
+                .zip(pi_x)
+                .map(|(v_k_x, pi_k_x)| {
+                    f64::powi(v_k_x as f64 - num_cycles * pi_k_x, 2) / (num_cycles * pi_k_x)
+                })
+                .sum::<f64>();
             check_f64(chi)?;
+
             let p_value = igamc(5.0 / 2.0, chi / 2.0)?;
             check_f64(p_value)?;
-            p_values[i].p_value = p_value;
-            Ok(())
-        })?;
 
-    Ok(p_values)
+            Ok(TestResult::new_with_comment(p_value, state_comment(state))
+                .with_statistics(TestStatistics::RandomExcursions {
+                    state,
+                    cycles,
+                    chi_square: Some(chi),
+                })
+                .with_label(ResultLabel::ExcursionState(state)))
+        })
+        .collect()
+}
+
+/// The probabilities *pi_k(x)* that the state *x* occurs exactly *k* times in a cycle, for
+/// k = 0, 1, 2, 3, 4, >= 5, as given in 3.14:
+/// * pi_0(x) = 1 - 1 / (2|x|)
+/// * pi_k(x) = 1 / (4x^2) * (1 - 1 / (2|x|))^(k - 1), for k = 1, 2, 3, 4
+/// * pi_5(x) = 1 / (2|x|) * (1 - 1 / (2|x|))^4
+fn probabilities(state: i8) -> [f64; 6] {
+    let x = f64::abs(state as f64);
+    let stay = 1.0 - 1.0 / (2.0 * x);
+
+    [
+        stay,
+        1.0 / (4.0 * x * x),
+        1.0 / (4.0 * x * x) * stay,
+        1.0 / (4.0 * x * x) * f64::powi(stay, 2),
+        1.0 / (4.0 * x * x) * f64::powi(stay, 3),
+        1.0 / (2.0 * x) * f64::powi(stay, 4),
+    ]
+}
+
+/// The state counters of the random excursions test.
+struct Cycles {
+    /// The maximum tested state.
+    max_state: i64,
+    /// The occurrences of each state in the current cycle.
+    current: Vec<u8>,
+    /// v_k(x) for each state x and k = 0, 1, 2, 3, 4, >= 5.
+    v: Vec<[usize; 6]>,
+    /// The count of cycles.
+    count: usize,
+}
+
+impl Cycles {
+    /// Adds the occurrences of the current cycle to v_k(x) and starts a new cycle.
+    fn end_cycle(&mut self) {
+        for (v_x, occurrences) in self.v.iter_mut().zip(&mut self.current) {
+            v_x[(*occurrences).min(5) as usize] += 1;
+            *occurrences = 0;
+        }
+        self.count += 1;
+    }
 }
 
 /// Handle step 1 to 5 for one word, with a specified bit range
-fn handle_word(
-    word: usize,
-    bits: Range<u32>,
-    prev: &mut i64,
-    last_index: &mut usize,
-    states: &mut Vec<[u8; 8]>,
-) {
+fn handle_word(word: Word, bits: Range<usize>, prev: &mut i64, cycles: &mut Cycles) {
     bits.for_each(|bit| {
-        if word.get_bit(bit) {
+        if word::get_bit(word, bit) {
             *prev += 1
         } else {
             *prev -= 1
         }
 
         // increment counter for state occurrences per cycle
-        if set_state(&mut states[*last_index], *prev) {
-            states.push(Default::default());
-            *last_index += 1;
+        if set_state(cycles, *prev) {
+            cycles.end_cycle();
         }
     });
 }
 
 /// Sets the state of the current cycle based on the current cumulative sum.
 /// If `true` is returned, a new cycle has begun.
-fn set_state(states: &mut [u8; 8], value: i64) -> bool {
+fn set_state(cycles: &mut Cycles, value: i64) -> bool {
+    let max_state = cycles.max_state;
     // since we're only interested in occurrences of 0, 1, 2, 3, 4, and >=5, saturating add is
     // completely fine.
-    match value {
-        -4 => states[0] = states[0].saturating_add(1),
-        -3 => states[1] = states[1].saturating_add(1),
-        -2 => states[2] = states[2].saturating_add(1),
-        -1 => states[3] = states[3].saturating_add(1),
-        1 => states[4] = states[4].saturating_add(1),
-        2 => states[5] = states[5].saturating_add(1),
-        3 => states[6] = states[6].saturating_add(1),
-        4 => states[7] = states[7].saturating_add(1),
-        0 => {
-            return true;
-        }
-        _ => (),
-    }
+    let idx = match value {
+        0 => return true,
+        _ if value.abs() > max_state => return false,
+        // -max_state -> 0
+        // -1 -> max_state - 1
+        ..0 => (value + max_state) as usize,
+        // 1 -> max_state
+        // max_state -> 2 * max_state - 1
+        1.. => (value + max_state - 1) as usize,
+    };
 
+    cycles.current[idx] = cycles.current[idx].saturating_add(1);
     false
 }
+
+/// Maps the index of a result to the state *x* it belongs to.
+pub(crate) fn state_of_index(idx: usize, max_state: u8) -> i8 {
+    // with max_state = 4: 0 -> -4, 3 -> -1, 4 -> +1, 7 -> +4
+    let max_state = max_state as i8;
+    if (idx as i8) < max_state {
+        idx as i8 - max_state
+    } else {
+        idx as i8 - max_state + 1
+    }
+}
+
+/// The comment of the results if the input has too few cycles.
+#[cfg(not(test))]
+pub(crate) fn too_few_cycles_comment(cycles: usize, required: usize) -> String {
+    format!("Too few cycles: {cycles}, required: {required}")
+}
+
+/// The comment of the result for the given state, e.g. "x = -4" or "x = +4".
+pub(crate) fn state_comment(state: i8) -> &'static str {
+    static COMMENTS: LazyLock<Box<[String]>> = LazyLock::new(|| {
+        (-(MAX_STATE as i8)..=MAX_STATE as i8)
+            .map(|state| format!("x = {state:+}"))
+            .collect()
+    });
+
+    &COMMENTS[(state + MAX_STATE as i8) as usize]
+}
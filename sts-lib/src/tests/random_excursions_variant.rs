@@ -4,17 +4,23 @@
 //! with the key difference being that the frequencies are calculated over all cycles, instead of per
 //! cycle.
 //!
-//! This test does not require a minimum number of cycles.
+//! Like the random excursions test, bit sequences with fewer than 500 cycles will lead to an `Ok()`
+//! result, but with the values filled with "0.0" and the status set to
+//! [NotApplicable](crate::ResultStatus::NotApplicable), see [TestResult::status].
 //!
-//! If the computation finishes successfully, 18 [TestResult] are returned: one for each tested state,
-//! `x`. The results will contain a comment about the state they are calculated from (e.g. "x = 3"),
-//! the order is: `[-9, -8, -7, -6, -5, -4, -3, -2, -1, +1, +2, +3, +4, +5, +6, +7, +8, +9]`.
+//! The tested states *x* can be configured with [RandomExcursionsVariantTestArg], NIST uses the
+//! states `-9..=+9`. If the computation finishes successfully, one [TestResult] is returned for each
+//! tested state. The results will contain a comment about the state they are calculated from
+//! (e.g. "x = +3"), the order is ascending: `[-9, -8, ..., -1, +1, ..., +8, +9]` with the defaults.
 //!
 //! The input length must be at least 10^6 bits, otherwise, an error is returned.
 
+use crate::bitvec::word::{self, Word, WORD_BITS};
 use crate::bitvec::BitVec;
-use crate::internals::{check_f64, checked_add, erfc, BitPrimitive};
-use crate::{Error, TestResult};
+use crate::internals::{check_f64, checked_add, erfc, trace_span};
+use crate::statistics::TestStatistics;
+use crate::tests::random_excursions::{state_comment, state_of_index, MAX_STATE};
+use crate::{ArgError, Error, ResultLabel, TestResult};
 use std::num::NonZero;
 use std::ops::Range;
 use sts_lib_derive::use_thread_pool;
@@ -27,12 +33,57 @@ pub const MIN_INPUT_LENGTH: NonZero<usize> = const {
     }
 };
 
+/// The argument for the random excursions variant test: the maximum state *x* to test. All states
+/// `-x..=-1` and `+1..=+x` are tested.
+///
+/// The maximum state must be between 1 and [MAX_STATE], this is checked when creating the argument.
+///
+/// The default value for this argument is 9, as used by NIST.
+#[derive(Copy, Clone, Debug)]
+#[repr(transparent)]
+pub struct RandomExcursionsVariantTestArg(u8);
+
+impl RandomExcursionsVariantTestArg {
+    /// To create a new instance of [RandomExcursionsVariantTestArg]. Returns [ArgError::OutOfRange] if the
+    /// maximum state is not between 1 and [MAX_STATE].
+    pub fn try_new(max_state: u8) -> Result<Self, ArgError> {
+        ArgError::check_range("maximum state", max_state.into(), 1, MAX_STATE.into())?;
+        Ok(Self(max_state))
+    }
+
+    /// Like [Self::try_new], but returns `None` instead of the reason.
+    #[deprecated(note = "use `try_new`, which reports why the arguments are invalid")]
+    pub fn new(max_state: u8) -> Option<Self> {
+        Self::try_new(max_state).ok()
+    }
+
+    /// The maximum tested state.
+    pub fn max_state(&self) -> u8 {
+        self.0
+    }
+}
+
+impl Default for RandomExcursionsVariantTestArg {
+    fn default() -> Self {
+        Self(9)
+    }
+}
+
 /// Random excursions variant test - No. 15.
 ///
 /// See the [module docs](crate::tests::random_excursions_variant).
 /// If the given [BitVec] contains fewer than 10^6 bits, [Error::InvalidParameter] is returned.
 #[use_thread_pool]
-pub fn random_excursions_variant_test(data: &BitVec) -> Result<[TestResult; 18], Error> {
+pub fn random_excursions_variant_test(
+    data: &BitVec,
+    RandomExcursionsVariantTestArg(max_state): RandomExcursionsVariantTestArg,
+) -> Result<Vec<TestResult>, Error> {
+    trace_span!(
+        INFO,
+        "random_excursions_variant_test",
+        len_bit = data.len_bit()
+    );
+
     #[cfg(not(test))]
     {
         if data.len_bit() < MIN_INPUT_LENGTH.get() {
@@ -44,7 +95,8 @@ pub fn random_excursions_variant_test(data: &BitVec) -> Result<[TestResult; 18],
     }
 
     // Step 1 to 4 - see also the random excursions test.
-    let mut frequencies = [0_usize; 18];
+    let mut frequencies = vec![0_usize; 2 * max_state as usize];
+    let max_state_value = max_state as i64;
     let mut prev: i64 = 0;
     let mut num_cycles = 1;
 
@@ -53,16 +105,24 @@ pub fn random_excursions_variant_test(data: &BitVec) -> Result<[TestResult; 18],
     for &word in words {
         handle_word(
             word,
-            0..usize::BITS,
+            0..WORD_BITS,
             &mut prev,
+            max_state_value,
             &mut num_cycles,
             &mut frequencies,
         )?;
     }
 
     if let Some(word) = last_word {
-        let bits = 0..(data.bit_count_last_word as u32);
-        handle_word(word, bits, &mut prev, &mut num_cycles, &mut frequencies)?;
+        let bits = 0..(data.bit_count_last_word as usize);
+        handle_word(
+            word,
+            bits,
+            &mut prev,
+            max_state_value,
+            &mut num_cycles,
+            &mut frequencies,
+        )?;
     }
 
     #[cfg(not(test))]
@@ -71,75 +131,79 @@ pub fn random_excursions_variant_test(data: &BitVec) -> Result<[TestResult; 18],
         // check is not mentioned in 2.15, it is mentioned in 3.15.
         let min_cycles = f64::max(0.005 * f64::sqrt(data.len_bit() as f64), 500.0);
         if (num_cycles as f64) < min_cycles {
-            return Ok([TestResult::new_with_comment(0.0, "Too few cycles"); 18]);
+            let required = min_cycles.ceil() as usize;
+            let reason = crate::NotApplicableReason::TooFewCycles {
+                cycles: num_cycles,
+                required,
+            };
+            return Ok(frequencies
+                .into_iter()
+                .enumerate()
+                .map(|(i, visits)| {
+                    TestResult::not_applicable(
+                        crate::tests::random_excursions::too_few_cycles_comment(
+                            num_cycles, required,
+                        ),
+                        reason,
+                    )
+                    .with_statistics(TestStatistics::RandomExcursionsVariant {
+                        state: state_of_index(i, max_state),
+                        cycles: num_cycles,
+                        visits,
+                    })
+                    .with_label(ResultLabel::ExcursionState(state_of_index(i, max_state)))
+                })
+                .collect());
         }
     }
 
     // Step 5: calculate p_values
-    let mut p_values = [
-        TestResult::new_with_comment(0.0, "x = -9"),
-        TestResult::new_with_comment(0.0, "x = -8"),
-        TestResult::new_with_comment(0.0, "x = -7"),
-        TestResult::new_with_comment(0.0, "x = -6"),
-        TestResult::new_with_comment(0.0, "x = -5"),
-        TestResult::new_with_comment(0.0, "x = -4"),
-        TestResult::new_with_comment(0.0, "x = -3"),
-        TestResult::new_with_comment(0.0, "x = -2"),
-        TestResult::new_with_comment(0.0, "x = -1"),
-        TestResult::new_with_comment(0.0, "x = +1"),
-        TestResult::new_with_comment(0.0, "x = +2"),
-        TestResult::new_with_comment(0.0, "x = +3"),
-        TestResult::new_with_comment(0.0, "x = +4"),
-        TestResult::new_with_comment(0.0, "x = +5"),
-        TestResult::new_with_comment(0.0, "x = +6"),
-        TestResult::new_with_comment(0.0, "x = +7"),
-        TestResult::new_with_comment(0.0, "x = +8"),
-        TestResult::new_with_comment(0.0, "x = +9"),
-    ];
-
+    let cycles = num_cycles;
     let num_cycles = num_cycles as f64;
 
-    for (i, frequency) in frequencies.into_iter().enumerate() {
-        let x = if i < 9 {
-            // 0 -> -9
-            // 8 -> -1
-            (i as f64) - 9.0
-        } else {
-            // 9 -> 1
-            // 17 -> 9
-            (i as f64) - 8.0
-        };
-
-        let p_value = erfc(
-            f64::abs(frequency as f64 - num_cycles)
-                / f64::sqrt(2.0 * num_cycles * (4.0 * f64::abs(x) - 2.0)),
-        );
-
-        check_f64(p_value)?;
-
-        p_values[i].p_value = p_value;
-    }
-
-    Ok(p_values)
+    frequencies
+        .into_iter()
+        .enumerate()
+        .map(|(i, frequency)| {
+            let state = state_of_index(i, max_state);
+            let x = state as f64;
+
+            let p_value = erfc(
+                f64::abs(frequency as f64 - num_cycles)
+                    / f64::sqrt(2.0 * num_cycles * (4.0 * f64::abs(x) - 2.0)),
+            );
+
+            check_f64(p_value)?;
+
+            Ok(TestResult::new_with_comment(p_value, state_comment(state))
+                .with_statistics(TestStatistics::RandomExcursionsVariant {
+                    state,
+                    cycles,
+                    visits: frequency,
+                })
+                .with_label(ResultLabel::ExcursionState(state)))
+        })
+        .collect()
 }
 
 /// Handle step 1 to 4 for one word, with a specified bit range
 fn handle_word(
-    word: usize,
-    mut bits: Range<u32>,
+    word: Word,
+    mut bits: Range<usize>,
     prev: &mut i64,
+    max_state: i64,
     num_cycles: &mut usize,
-    frequencies: &mut [usize; 18],
+    frequencies: &mut [usize],
 ) -> Result<(), Error> {
     bits.try_for_each(|bit| -> Result<(), Error> {
-        if word.get_bit(bit) {
+        if word::get_bit(word, bit) {
             *prev += 1
         } else {
             *prev -= 1
         }
 
         // increment counter for state occurrences per cycle
-        if inc_frequency(frequencies, *prev)? {
+        if inc_frequency(frequencies, max_state, *prev)? {
             *num_cycles += 1;
         }
 
@@ -149,16 +213,16 @@ fn handle_word(
 
 /// Increments the right frequency counter based on the current value, returns true if a new
 /// cycle started.
-fn inc_frequency(frequencies: &mut [usize; 18], value: i64) -> Result<bool, Error> {
+fn inc_frequency(frequencies: &mut [usize], max_state: i64, value: i64) -> Result<bool, Error> {
     let idx = match value {
-        ..-9 => return Ok(false),
-        // -9 -> 0
-        // -1 -> 8
-        -9..0 => (value + 9) as usize,
         0 => return Ok(true),
-        // 1 -> 9
-        1..=9 => (value + 8) as usize,
-        10.. => return Ok(false),
+        _ if value.abs() > max_state => return Ok(false),
+        // -max_state -> 0
+        // -1 -> max_state - 1
+        ..0 => (value + max_state) as usize,
+        // 1 -> max_state
+        // max_state -> 2 * max_state - 1
+        1.. => (value + max_state - 1) as usize,
     };
 
     frequencies[idx] = checked_add!(frequencies[idx], 1)?;
@@ -0,0 +1,195 @@
+//! Run-length histogram, a diagnostic.
+//!
+//! **This is not a test of NIST SP 800-22 and gives no P-value.** The [runs test](super::runs)
+//! only checks the total count of runs, and the [longest run test](super::longest_run_of_ones) only
+//! the longest run of ones within blocks. This diagnostic gives the full distribution of the run
+//! lengths of zeros and ones instead, compared against the expected counts of a random sequence,
+//! e.g. to see which run lengths a generator produces too often or too rarely.
+//!
+//! For a random sequence, the run lengths of each bit value are geometrically distributed: about
+//! `n / 2^(k + 2)` runs of zeros (and ones) have the length *k*, see
+//! [RunLengthHistogram::expected_count].
+
+use crate::bitvec::word::{self, WORD_BITS};
+use crate::bitvec::BitVec;
+use crate::internals::{check_f64, trace_span};
+use crate::Error;
+
+/// The minimum expected count of a run length to be a bin of its own in
+/// [RunLengthHistogram::chi_square], the usual rule for chi-square statistics.
+const MIN_EXPECTED_COUNT: f64 = 5.0;
+
+/// The distribution of the run lengths of a sequence, see the [module docs](self).
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunLengthHistogram {
+    len_bit: usize,
+    /// The count of runs with the length `index + 1`, for zeros and ones.
+    counts: [Box<[usize]>; 2],
+}
+
+impl RunLengthHistogram {
+    /// The length of the sequence in bits.
+    pub fn len_bit(&self) -> usize {
+        self.len_bit
+    }
+
+    /// The count of runs of the given bit with each length: the value at index `i` is the count of
+    /// runs with the length `i + 1`. The last value is the count of the longest runs.
+    pub fn counts(&self, bit: bool) -> &[usize] {
+        &self.counts[bit as usize]
+    }
+
+    /// The count of runs of the given bit with the given length.
+    pub fn count(&self, bit: bool, length: usize) -> usize {
+        length
+            .checked_sub(1)
+            .and_then(|idx| self.counts(bit).get(idx))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// The length of the longest run of the given bit, 0 if the bit does not occur.
+    pub fn longest_run(&self, bit: bool) -> usize {
+        self.counts(bit).len()
+    }
+
+    /// The total count of runs of the given bit.
+    pub fn run_count(&self, bit: bool) -> usize {
+        self.counts(bit).iter().sum()
+    }
+
+    /// The expected count of runs of zeros (or ones, it is the same) with the given length in a
+    /// random sequence with the same length *n*:
+    ///
+    /// - `(n - k - 1) / 2^(k + 2) + 1 / 2^k` for a length `k < n`: the runs inside the sequence
+    ///   are bounded by the other bit value on both sides, the runs at the start and the end only on
+    ///   one side.
+    /// - `1 / 2^n` for the length *n*, and 0 for the length 0 and longer lengths.
+    pub fn expected_count(&self, length: usize) -> f64 {
+        let n = self.len_bit;
+        let k = length as f64;
+        if length == 0 || length > n {
+            0.0
+        } else if length == n {
+            f64::exp2(-k)
+        } else {
+            (n - length - 1) as f64 * f64::exp2(-k - 2.0) + f64::exp2(-k)
+        }
+    }
+
+    /// The expected total count of runs of zeros (or ones) in a random sequence with the same
+    /// length *n*, `(n - 1) / 4 + 1 / 2`: a run starts at the first bit with probability `1 / 2`,
+    /// and at every other bit with probability `1 / 4`.
+    pub fn expected_run_count(&self) -> f64 {
+        if self.len_bit == 0 {
+            0.0
+        } else {
+            (self.len_bit - 1) as f64 / 4.0 + 0.5
+        }
+    }
+
+    /// The count of bins of [Self::chi_square]: each run length `1..bins` whose expected count is
+    /// at least 5 is a bin of its own, and the last bin holds all longer runs.
+    pub fn chi_square_bins(&self) -> usize {
+        (1..)
+            .take_while(|&length| self.expected_count(length) >= MIN_EXPECTED_COUNT)
+            .count()
+            + 1
+    }
+
+    /// The chi-square statistic `sum((observed - expected)^2 / expected)` of the counts of runs of
+    /// the given bit over the [bins](Self::chi_square_bins), as a measure how far the distribution
+    /// is from the expected one. Larger values mean larger deviations, with the count of bins minus
+    /// 1 as a rough mean for random sequences.
+    ///
+    /// The counts of the run lengths are not independent, so this is no exact chi-square
+    /// statistic, and no P-value is calculated.
+    pub fn chi_square(&self, bit: bool) -> Result<f64, Error> {
+        let bins = self.chi_square_bins();
+
+        let mut chi_square = 0.0;
+        let mut remaining_observed = self.run_count(bit);
+        let mut remaining_expected = self.expected_run_count();
+        for length in 1..bins {
+            let (observed, expected) = (self.count(bit, length), self.expected_count(length));
+            chi_square += (observed as f64 - expected).powi(2) / expected;
+            remaining_observed -= observed;
+            remaining_expected -= expected;
+        }
+
+        // the last bin: all longer runs
+        if remaining_expected > 0.0 {
+            chi_square +=
+                (remaining_observed as f64 - remaining_expected).powi(2) / remaining_expected;
+        }
+        check_f64(chi_square)?;
+
+        Ok(chi_square)
+    }
+}
+
+/// Counts the runs of zeros and ones of each length, see the [module docs](self).
+///
+/// The runs are found with the bit transitions of each word, so that the time is proportional to
+/// the count of runs, about half the length of the sequence.
+///
+/// If the data is empty, [Error::InvalidParameter] is raised.
+///
+/// ```
+/// use sts_lib::bitvec::BitVec;
+/// use sts_lib::tests::run_length_histogram::run_length_histogram;
+///
+/// let data = BitVec::from_ascii_str("0011101").unwrap();
+/// let histogram = run_length_histogram(&data).unwrap();
+/// assert_eq!(histogram.counts(false), [1, 1]);
+/// assert_eq!(histogram.counts(true), [1, 0, 1]);
+/// ```
+pub fn run_length_histogram(data: &BitVec) -> Result<RunLengthHistogram, Error> {
+    trace_span!(INFO, "run_length_histogram", len_bit = data.len_bit());
+
+    let len_bit = data.len_bit();
+    let Some(first_bit) = data.get(0) else {
+        return Err(Error::InvalidParameter(
+            "The run-length histogram needs at least 1 bit.".to_owned(),
+        ));
+    };
+
+    let mut counts = [Vec::new(), Vec::new()];
+    let mut add_run = |bit: bool, length: usize| {
+        let counts = &mut counts[bit as usize];
+        if counts.len() < length {
+            counts.resize(length, 0);
+        }
+        counts[length - 1] += 1;
+    };
+
+    // the current run: its bit and its start
+    let mut run_bit = first_bit;
+    let mut run_start = 0;
+    let mut prev_bit = first_bit as usize;
+    for (word_idx, &word) in data.words.iter().enumerate() {
+        // bit i of the transitions is set if bit i of the word differs from the bit before
+        let mut transitions = word ^ ((word >> 1) | (prev_bit << (WORD_BITS - 1)));
+        prev_bit = word & 1;
+
+        while transitions != 0 {
+            let bit = transitions.leading_zeros() as usize;
+            let position = word_idx * WORD_BITS + bit;
+            if position >= len_bit {
+                // the unused bits of the last word
+                break;
+            }
+
+            add_run(run_bit, position - run_start);
+            run_bit = !run_bit;
+            run_start = position;
+            transitions &= !word::bit_mask(bit);
+        }
+    }
+    add_run(run_bit, len_bit - run_start);
+
+    Ok(RunLengthHistogram {
+        len_bit,
+        counts: counts.map(Vec::into_boxed_slice),
+    })
+}
@@ -4,12 +4,16 @@
 //! identical bits.
 //! Each tested [BitVec] should have at least 100 bits length.
 
+use crate::bitvec::word::{self, Word, WORD_BITS};
 use crate::bitvec::BitVec;
-use crate::internals::{check_f64, checked_add, erfc, BitPrimitive};
+use crate::internals::par::*;
+#[cfg(not(feature = "std"))]
+use crate::internals::F64Ext;
+use crate::internals::{check_f64, checked_add, erfc, trace_span};
+use crate::statistics::TestStatistics;
 use crate::{Error, TestResult};
-use rayon::prelude::*;
-use std::num::NonZero;
-use std::ops::Range;
+use core::num::NonZero;
+use core::ops::Range;
 use sts_lib_derive::use_thread_pool;
 
 /// The minimum input length, in bits, for this test, as recommended by NIST.
@@ -26,27 +30,25 @@ pub const MIN_INPUT_LENGTH: NonZero<usize> = const {
 /// If an error happens, it means either arithmetic underflow or overflow - beware.
 #[use_thread_pool]
 pub fn runs_test(data: &BitVec) -> Result<TestResult, Error> {
+    trace_span!(INFO, "runs_test", len_bit = data.len_bit());
+
     // Step 1: calculate pi = count of ones / length of data
-    let count_ones = data
-        .words
-        .par_iter()
-        .try_fold(
-            || 0_usize,
-            |sum, value| checked_add!(sum, value.count_ones() as usize),
-        )
-        .try_reduce(|| 0_usize, |a, b| checked_add!(a, b))?;
-    // don't need to check if the last word was incomplete - we only care about 1, the empty bits
-    // in the last word are always zero.
+    // the count cannot overflow, it is at most the bit length.
+    let count_ones = data.count_ones();
     let pi = (count_ones as f64) / (data.len_bit() as f64);
 
     // Step 2: determine if the frequency test passed: abs(pi - 1/2) < 2 / sqrt(len_bit) has to uphold.
     // Otherwise, the test should not run because the frequency test would not pass.
     if f64::abs(pi - 0.5) >= 2.0 / f64::sqrt(data.len_bit() as f64) {
         // Frequency test would fail, don't run the test
-        return Ok(TestResult::new_with_comment(
-            0.0,
-            "Frequency test would not pass!",
-        ));
+        return Ok(
+            TestResult::new_with_comment(0.0, "Frequency test would not pass!").with_statistics(
+                TestStatistics::Runs {
+                    proportion: pi,
+                    runs: None,
+                },
+            ),
+        );
     }
 
     // Step 3: compute the statistic V = (sum of r(k) for data[1..] - index k) + 1
@@ -55,17 +57,17 @@ pub fn runs_test(data: &BitVec) -> Result<TestResult, Error> {
     let v = calc_v_data_for_slice(full_units)?;
 
     let v = if let Some(unit) = last_unit {
-        let bit_count = data.bit_count_last_word as u32;
+        let bit_count = data.bit_count_last_word as usize;
 
         let v_rem = if let Some(&full_unit) = full_units.last() {
             // if full_units contained data, take the last bit of it
-            let prev_value = full_unit & 0x01;
+            let prev_value = word::get_bit(full_unit, WORD_BITS - 1);
 
-            calc_v_data_for_unit(unit, 0..bit_count, prev_value == 1)?
+            calc_v_data_for_unit(unit, 0..bit_count, prev_value)
         } else {
             // need to take the first bit of the last word
-            let prev_value = (unit >> (usize::BITS - 1)) & 0x1;
-            calc_v_data_for_unit(unit, 1..bit_count, prev_value == 1)?
+            let prev_value = word::get_bit(unit, 0);
+            calc_v_data_for_unit(unit, 1..bit_count, prev_value)
         };
 
         v + v_rem
@@ -85,11 +87,14 @@ pub fn runs_test(data: &BitVec) -> Result<TestResult, Error> {
     let p_value = erfc(fraction);
     check_f64(p_value)?;
 
-    Ok(TestResult::new(p_value))
+    Ok(TestResult::new(p_value).with_statistics(TestStatistics::Runs {
+        proportion: pi,
+        runs: Some(v),
+    }))
 }
 
 /// Calculation of v statistic for the data array.
-fn calc_v_data_for_slice(data: &[usize]) -> Result<usize, Error> {
+fn calc_v_data_for_slice(data: &[Word]) -> Result<usize, Error> {
     // guard clause
     if data.is_empty() {
         return Ok(0);
@@ -98,47 +103,45 @@ fn calc_v_data_for_slice(data: &[usize]) -> Result<usize, Error> {
     // Special casing for the first byte
     let v_first_word = {
         // prev_value = first bit
-        let prev_value = data[0].get_bit(0);
+        let prev_value = word::get_bit(data[0], 0);
 
         // for the remaining bits, just get them and compare them to the previous bit to get the value
-        calc_v_data_for_unit(data[0], 1..usize::BITS, prev_value)?
+        calc_v_data_for_unit(data[0], 1..WORD_BITS, prev_value)
     };
 
     // remaining bytes (every byte except first)
+    // the count of bit changes cannot overflow, it is at most the bit length.
     let v_rem_words = data[1..]
         .par_iter()
-        .enumerate()
-        .try_fold(
-            || 0_usize,
-            |sum, (prev_idx, &word)| {
-                // start with last bit of previous byte
-                // prev_byte_idx is of the previous byte because we start with index 1 --> 0 in
-                // the iterator
-                let prev_value = data[prev_idx].get_bit(usize::BITS - 1);
-
-                let v_data = calc_v_data_for_unit(word, 0..usize::BITS, prev_value)?;
-                checked_add!(sum, v_data)
-            },
-        )
-        .try_reduce(|| 0_usize, |a, b| checked_add!(a, b))?;
+        .zip(data.par_iter())
+        .map(|(&word, &prev_word)| {
+            // start with last bit of previous word
+            let prev_value = word::get_bit(prev_word, WORD_BITS - 1);
+
+            calc_v_data_for_unit(word, 0..WORD_BITS, prev_value)
+        })
+        .sum::<usize>();
 
     checked_add!(v_first_word, v_rem_words)
 }
 
-/// Calculate v for a single byte
-fn calc_v_data_for_unit(
-    value: usize,
-    mut bits: Range<u32>,
-    mut prev_bit: bool,
-) -> Result<usize, Error> {
-    bits.try_fold(0_usize, |sum, bit_idx| {
-        let current_bit = value.get_bit(bit_idx);
-        let res = if current_bit == prev_bit {
-            Ok(sum)
-        } else {
-            checked_add!(sum, 1)
-        };
-        prev_bit = current_bit;
-        res
-    })
+/// Calculate v for a single word: the count of bit changes in the given bits (MSB first), with
+/// `prev_bit` being the bit before the first bit of the word.
+///
+/// Instead of comparing the bits one by one, the word is compared to itself shifted by one bit: each
+/// bit change is a '1' bit in the XOR of both, which are counted with a single popcount.
+#[inline]
+fn calc_v_data_for_unit(value: Word, bits: Range<usize>, prev_bit: bool) -> usize {
+    if bits.is_empty() {
+        return 0;
+    }
+
+    // bit i (MSB first) of the shifted value is bit i - 1 of the value, bit 0 is the previous bit
+    let shifted = (value >> 1) | ((prev_bit as Word) << (WORD_BITS - 1));
+    let changes = value ^ shifted;
+
+    // only count the changes in the given bits
+    let mask = word::leading_mask(bits.end) & !word::leading_mask(bits.start);
+
+    (changes & mask).count_ones() as usize
 }
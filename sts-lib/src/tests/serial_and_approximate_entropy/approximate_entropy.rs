@@ -11,13 +11,20 @@
 //! of constraint no. 3!
 
 use crate::bitvec::BitVec;
-use crate::internals::{check_f64, igamc};
-use crate::tests::serial_and_approximate_entropy::{access_bits, validate_test_arg};
-use crate::{Error, TestResult};
-use rayon::prelude::*;
-use std::f64::consts::LN_2;
-use std::num::NonZero;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use crate::internals::par::*;
+#[cfg(not(feature = "std"))]
+use crate::internals::F64Ext;
+use crate::internals::{check_f64, igamc, trace_span, F64Sum, HIGH_PRECISION};
+use crate::statistics::TestStatistics;
+use crate::tests::serial_and_approximate_entropy::{
+    count_patterns, fold_patterns, validate_sweep_range, validate_test_arg,
+};
+use crate::{ArgError, Error, TestResult};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::LN_2;
+use core::num::NonZero;
+use core::ops::RangeInclusive;
 use sts_lib_derive::use_thread_pool;
 
 // calculation: minimum block length = 2
@@ -37,7 +44,7 @@ pub const MIN_INPUT_LENGTH: NonZero<usize> = const {
 /// Argument constraints:
 /// 1. the given block length must be >= 2.
 /// 2. each value of with the bit length the given block length must be representable as usize,
-///     i.e. depending on the platform, 32 or 64 bits.
+///    i.e. depending on the platform, 32 or 64 bits.
 /// 3. the block length must be < (log2([BitVec::len_bit]) as int) - 5
 ///
 /// Constraints 1 and 2 are checked when creating the arguments.
@@ -52,11 +59,22 @@ pub const MIN_INPUT_LENGTH: NonZero<usize> = const {
 pub struct ApproximateEntropyTestArg(u8);
 
 impl ApproximateEntropyTestArg {
-    /// To create a new instance of [ApproximateEntropyTestArg]. This function checks some constraints,
-    /// for details, see [ApproximateEntropyTestArg].
-    pub fn new(block_length: u8) -> Option<Self> {
+    /// To create a new instance of [ApproximateEntropyTestArg]. This function checks some
+    /// constraints and returns [ArgError::OutOfRange] if one is violated, for details, see [ApproximateEntropyTestArg].
+    pub fn try_new(block_length: u8) -> Result<Self, ArgError> {
         validate_test_arg(block_length).map(Self)
     }
+
+    /// Like [Self::try_new], but returns `None` instead of the reason.
+    #[deprecated(note = "use `try_new`, which reports why the arguments are invalid")]
+    pub fn new(block_length: u8) -> Option<Self> {
+        Self::try_new(block_length).ok()
+    }
+
+    /// The block length in bits.
+    pub fn block_length(&self) -> u8 {
+        self.0
+    }
 }
 
 impl Default for ApproximateEntropyTestArg {
@@ -76,6 +94,8 @@ pub fn approximate_entropy_test(
     data: &BitVec,
     ApproximateEntropyTestArg(block_length): ApproximateEntropyTestArg,
 ) -> Result<TestResult, Error> {
+    trace_span!(INFO, "approximate_entropy_test", len_bit = data.len_bit());
+
     // only check the argument when not testing
     #[cfg(not(test))]
     {
@@ -83,72 +103,77 @@ pub fn approximate_entropy_test(
         let max_block_length = f64::log2(data.len_bit() as f64) as usize - 5;
 
         if (block_length as usize) >= max_block_length {
-            return Err(Error::InvalidParameter(format!(
+            return Err(Error::InvalidParameter(alloc::format!(
                 "Given block length must be lesser than log2(len_bit) - 5 (={max_block_length}). Is: {block_length}"
             )));
         }
     }
 
-    // Step 1 is skipped: we just read from the start again, see access_bits()
+    // Step 1 is skipped: the blocks wrap around to the start, see count_patterns()
     // Step 2: determine the frequency of all possible overlapping m bit blocks.
     // Step 5.2: determine the frequency of all possible overlapping (m+1) bit blocks.
-    // (m == block_length)
-    let frequencies = create_frequency_slices(block_length);
-    (0..data.len_bit()).into_par_iter().try_for_each(|idx| {
-        frequencies.iter().enumerate().try_for_each(|(i, freq)| {
-            let idx = access_bits(data, idx, block_length + i as u8)
-                .unwrap_or_else(|| panic!("serial_test: idx for (m + {i}) should be valid"));
-
-            let prev = freq[idx].fetch_add(1, Ordering::Relaxed);
-            if prev == usize::MAX {
-                return Err(Error::Overflow(format!("{prev} (frequency count) + 1")));
-            }
-            Ok(())
-        })
-    })?;
+    // (m == block_length). Only the (m+1) bit blocks are counted, the m bit blocks are derived
+    // from them.
+    let len_bit = data.len_bit();
+    let frequency = count_patterns(data, block_length + 1)?;
 
     // Step 3 / Step 5.3: for each frequency i, calculate i / len_bit
     // Step 4 / Step 5.4: calculate the sum of (i * ln(i)), where i denotes an entry in the frequency
     // array. Result is stored in phi
-    let len_bit = data.len_bit();
-    let phi = {
-        let [frequency_0, frequency_1] = frequencies;
-        [
-            execute_step_3_and_4(frequency_0, len_bit)?,
-            execute_step_3_and_4(frequency_1, len_bit)?,
-        ]
-    };
+    let phi = [
+        execute_step_3_and_4(&fold_patterns(&frequency), len_bit)?,
+        execute_step_3_and_4(&frequency, len_bit)?,
+    ];
+    let chi = HIGH_PRECISION.then(|| chi_square_exact(&frequency));
 
     // Step 5 is already finished (do step 1 to 4 for block_length + 1)
+    calculate_p_value(block_length, phi, chi, len_bit)
+}
 
-    // Step 6: compute the test statistic: chi^2 = 2 * n * [ln(2) - ( phi(m) - phi(m+1) )]
-    let chi = 2.0 * (len_bit as f64) * (LN_2 - (phi[0] - phi[1]));
-    check_f64(chi)?;
-
-    // Step 7: compute p-value = igamc(2^(m-1), chi^2 / 2)
-    let p_value = igamc(f64::powi(2.0, (block_length as i32) - 1), chi / 2.0)?;
-    check_f64(p_value)?;
+/// Approximate Entropy Test for multiple block lengths at once, e.g. to study the structure of the
+/// sequence at different scales.
+///
+/// The overlapping blocks are only counted once for the largest block length (+ 1), the
+/// frequencies for all smaller block lengths are derived from them. This is much faster than
+/// calling [approximate_entropy_test] for each block length. The results are the same, returned in
+/// ascending order with the used block length.
+///
+/// If the range is empty, or a block length in the range is invalid for the given data,
+/// [Error::InvalidParameter] is raised. For the exact constraints, see [ApproximateEntropyTestArg].
+#[use_thread_pool]
+pub fn approximate_entropy_sweep(
+    data: &BitVec,
+    range: RangeInclusive<u8>,
+) -> Result<Vec<(u8, TestResult)>, Error> {
+    trace_span!(INFO, "approximate_entropy_sweep", len_bit = data.len_bit());
 
-    Ok(TestResult::new(p_value))
-}
+    validate_sweep_range(&range, data.len_bit(), 5)?;
 
-/// Returns 2 boxed slices used for storing the measured frequency of a given pattern.
-/// \[0] is for patterns with bit length `block_length`, \[1] for `block_length + 1`.
-/// The pattern is used as the index for each boxed slice, the value itself stores the frequency.
-#[inline]
-fn create_frequency_slices(block_length: u8) -> [Box<[AtomicUsize]>; 2] {
-    #[inline]
-    fn create_frequency_slice(block_length: u8) -> Box<[AtomicUsize]> {
-        let len = 1 << block_length;
-        let mut vec = Vec::with_capacity(len);
-        vec.resize_with(len, || AtomicUsize::new(0));
-        vec.into_boxed_slice()
+    // phi for each block length from the largest one + 1 down to the smallest one.
+    let len_bit = data.len_bit();
+    let max_block_length = range.end() + 1;
+    let min_block_length = *range.start();
+    let mut phi = vec![0.0; max_block_length as usize + 1];
+    // chi^2 with the feature high-precision, calculated from the frequencies of block length m + 1
+    let mut chi = vec![None; max_block_length as usize + 1];
+    let mut frequency = count_patterns(data, max_block_length)?;
+    for block_length in (min_block_length..=max_block_length).rev() {
+        if block_length != max_block_length {
+            frequency = fold_patterns(&frequency);
+        }
+        phi[block_length as usize] = execute_step_3_and_4(&frequency, len_bit)?;
+        if HIGH_PRECISION && block_length > min_block_length {
+            chi[block_length as usize - 1] = Some(chi_square_exact(&frequency));
+        }
     }
 
-    [
-        create_frequency_slice(block_length),
-        create_frequency_slice(block_length + 1),
-    ]
+    range
+        .map(|block_length| {
+            let m = block_length as usize;
+            let result = calculate_p_value(block_length, [phi[m], phi[m + 1]], chi[m], len_bit)?;
+            Ok((block_length, result))
+        })
+        .collect()
 }
 
 /// Executes step 3 and 4:
@@ -157,13 +182,11 @@ fn create_frequency_slices(block_length: u8) -> [Box<[AtomicUsize]>; 2] {
 ///
 /// Since resulting values are checked to be valid normal f64s, an error may be returned.
 #[inline]
-fn execute_step_3_and_4(frequency: Box<[AtomicUsize]>, len_bit: usize) -> Result<f64, Error> {
+fn execute_step_3_and_4(frequency: &[usize], len_bit: usize) -> Result<f64, Error> {
     let phi = frequency
-        .into_vec()
-        .into_par_iter()
-        .map(|el| {
+        .par_iter()
+        .map(|&el| {
             // step 3
-            let el = el.into_inner();
             let pi = (el as f64) / (len_bit as f64);
 
             // step 4
@@ -178,3 +201,87 @@ fn execute_step_3_and_4(frequency: Box<[AtomicUsize]>, len_bit: usize) -> Result
     check_f64(phi)?;
     Ok(phi)
 }
+
+/// Executes step 6 and 7 with phi for the block lengths m and m + 1. With the feature
+/// `high-precision`, chi^2 is given, see [chi_square_exact].
+fn calculate_p_value(
+    block_length: u8,
+    phi: [f64; 2],
+    chi: Option<f64>,
+    len_bit: usize,
+) -> Result<TestResult, Error> {
+    // Step 6: compute the test statistic: chi^2 = 2 * n * [ln(2) - ( phi(m) - phi(m+1) )]
+    let (ap_en, chi) = match chi {
+        Some(chi) => (LN_2 - chi / (2.0 * len_bit as f64), chi),
+        None => {
+            let ap_en = phi[0] - phi[1];
+            (ap_en, 2.0 * (len_bit as f64) * (LN_2 - ap_en))
+        }
+    };
+    check_f64(chi)?;
+
+    // Step 7: compute p-value = igamc(2^(m-1), chi^2 / 2)
+    let p_value = igamc(f64::powi(2.0, (block_length as i32) - 1), chi / 2.0)?;
+    check_f64(p_value)?;
+
+    Ok(
+        TestResult::new(p_value).with_statistics(TestStatistics::ApproximateEntropy {
+            ap_en,
+            chi_square: chi,
+        }),
+    )
+}
+
+/// Step 6 with the feature `high-precision`: chi^2 from the frequencies of the m + 1 bit blocks.
+///
+/// In step 6, `ln(2) - ApEn` is about `2^m / n`, but phi(m) and phi(m + 1) are about `m * ln(2)`:
+/// the subtraction cancels most of the significant digits, e.g. for `n = 1e9` only about 7 are
+/// left. Since the blocks wrap around, the frequency `v` of each m bit block `x` is the sum of the
+/// frequencies `v_0` and `v_1` of the blocks `x0` and `x1`, so that
+/// `ln(2) - ApEn = sum over x of (v / n) * g((v_0 - v_1) / v)`, see [pair_divergence]. Each term is
+/// positive and calculated without cancellation. pub(crate) to allow for tests.
+pub(crate) fn chi_square_exact(frequency: &[usize]) -> f64 {
+    let sum = frequency
+        .par_chunks_exact(2)
+        .map(|pair| {
+            let count = pair[0] + pair[1];
+            if count == 0 {
+                return 0.0;
+            }
+
+            // the difference is exact, the quotient only rounded once
+            let t = pair[0].abs_diff(pair[1]) as f64 / count as f64;
+            count as f64 * pair_divergence(t)
+        })
+        .sum::<F64Sum>()
+        .value();
+
+    2.0 * sum
+}
+
+/// `g(t) = ((1 + t) * ln(1 + t) + (1 - t) * ln(1 - t)) / 2` for `0 <= t <= 1`, which is about
+/// `t^2 / 2` for small `t`. The direct formula would cancel the terms of first order in `t`, so the
+/// power series `sum over k >= 1 of t^(2k) / (2k * (2k - 1))` is used for small `t`.
+/// pub(crate) to allow for tests.
+pub(crate) fn pair_divergence(t: f64) -> f64 {
+    if t < 0.5 {
+        let t_squared = t * t;
+        let mut power = t_squared;
+        let mut sum = 0.0;
+        // the terms decrease by at least a factor of 4: 30 terms are enough for f64
+        for k in 1..=30 {
+            let k = k as f64;
+            sum += power / (2.0 * k * (2.0 * k - 1.0));
+            power *= t_squared;
+        }
+        sum
+    } else {
+        // 0 * ln(0) = 0 for t = 1
+        let low = if t < 1.0 {
+            (1.0 - t) * f64::ln_1p(-t)
+        } else {
+            0.0
+        };
+        ((1.0 + t) * f64::ln_1p(t) + low) / 2.0
+    }
+}
@@ -1,80 +1,165 @@
 //! The serial test and approximate entropy test. Since both share some code, this shared code
 //! is defined here. The submodules are reexported in [crate::tests] for API consistency.
 
+use crate::bitvec::word::WORD_BITS;
 use crate::bitvec::BitVec;
+use crate::internals::checked_add;
+use crate::internals::par::*;
+#[cfg(not(feature = "std"))]
+use crate::internals::{CancelCheck, F64Ext};
+#[cfg(feature = "std")]
+use crate::test_runner::cancellation::CancelCheck;
+use crate::{ArgError, Error};
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+use core::sync::atomic::{AtomicUsize, Ordering};
 
 pub mod approximate_entropy;
 pub mod serial;
 
 /// Since the constraints for both test args are large the same, this function takes care of the validation.
-fn validate_test_arg(block_length: u8) -> Option<u8> {
-    // block length > 1 (else this is just the frequency test) and maximum of usize bits (32 or 64)
-    if block_length > 1 && block_length as u32 <= usize::BITS {
-        Some(block_length)
-    } else {
-        None
-    }
+fn validate_test_arg(block_length: u8) -> Result<u8, ArgError> {
+    // block length > 1 (else this is just the frequency test) and maximum of the word bits (32 or 64)
+    ArgError::check_range("block length", block_length.into(), 2, WORD_BITS)?;
+    Ok(block_length)
 }
 
-/// Retrieves the bits at start_idx + block_length (e.g. for block_length = 3, 3 bits are retrieved)
-/// and returns them.
-///
-/// start_idx is measured in bits.
-///
-/// This function may wrap-around, meaning if start_idx + block_length >= data.len_bit(), bits from
-/// the start will be read.
-///
-/// Bounds: start_idx < [BitVec::len_bit], block_length <= [usize::BITS]
-fn access_bits(data: &BitVec, start_idx: usize, block_length: u8) -> Option<usize> {
-    let data_len = data.len_bit();
-
-    if start_idx >= data_len || block_length as u32 > usize::BITS {
-        return None;
+/// Validates the range of block lengths of a sweep: the range may not be empty, and each block
+/// length has to satisfy constraints 1 and 2 of the test arguments, as well as
+/// `block_length < (log2(len_bit) as int) - offset` (constraint 3).
+fn validate_sweep_range(
+    range: &RangeInclusive<u8>,
+    len_bit: usize,
+    offset: usize,
+) -> Result<(), Error> {
+    let (start, end) = (*range.start(), *range.end());
+
+    if start > end || validate_test_arg(start).is_err() || validate_test_arg(end).is_err() {
+        return Err(Error::InvalidParameter(format!(
+            "The block lengths must be between 2 and {} (non-empty range). Is: {start}..={end}",
+            WORD_BITS
+        )));
     }
 
-    let end_idx = start_idx + block_length as usize;
-
-    let res = if end_idx >= data_len {
-        // wrap-around
-        let end_idx = end_idx % data_len;
-        let end_bit_idx = end_idx % (usize::BITS as usize);
-
-        // read all bits from start_idx to the end (1 or 2 words)
-        let start_word_idx = start_idx / (usize::BITS as usize);
-        let start_bit_idx = start_idx % (usize::BITS as usize);
+    let max_block_length = (f64::log2(len_bit as f64) as usize).saturating_sub(offset);
+    if (end as usize) >= max_block_length {
+        return Err(Error::InvalidParameter(format!(
+            "Given block lengths must be lesser than log2(len_bit) - {offset} (={max_block_length}). Is: {end}"
+        )));
+    }
 
-        // first part is either last word or second-to-last word
-        let mut result: usize = data.words[start_word_idx] << start_bit_idx;
+    Ok(())
+}
 
-        if start_word_idx != data.words.len() - 1 {
-            // add last word, if necessary
-            result |= data.words[data.words.len() - 1] >> (usize::BITS as usize - start_bit_idx);
-        }
+/// The count of blocks that are counted in one chunk by [count_patterns].
+const CHUNK_LENGTH: usize = 1 << 16;
 
-        // add first word
-        let shift = (block_length as usize) - end_bit_idx;
-        let last_part = data.words[0] >> shift;
+/// The maximum block length whose frequencies are counted in a table per thread by
+/// [count_patterns]. For longer blocks, the tables would take too much memory, so all threads count
+/// in one shared table with atomic increments.
+const MAX_LOCAL_BLOCK_LENGTH: usize = 16;
 
-        result | last_part
+/// Determines the frequency of all possible overlapping blocks with the given block length, with
+/// wrap-around: the blocks starting in the last `block_length - 1` bits continue with the first
+/// bits. The pattern is used as the index, the value itself stores the frequency.
+///
+/// The blocks are read with [BitVec::windows], split into chunks which are counted in parallel.
+/// The blocks that wrap around are read from a copy of their bits, see [wrapped_bits].
+/// pub(crate) to allow for tests.
+pub(crate) fn count_patterns(data: &BitVec, block_length: u8) -> Result<Box<[usize]>, Error> {
+    let block_length = block_length as usize;
+    let windows = data.windows(block_length, 1);
+    let chunks = 0..windows.len().div_ceil(CHUNK_LENGTH);
+    let chunk = |idx: usize| {
+        let start = idx * CHUNK_LENGTH;
+        windows
+            .clone()
+            .range(start..windows.len().min(start + CHUNK_LENGTH))
+    };
+    let len = 1 << block_length;
+
+    let cancel = CancelCheck::current();
+    let mut frequency = if block_length <= MAX_LOCAL_BLOCK_LENGTH {
+        chunks
+            .into_par_iter()
+            .try_fold(
+                || vec![0_usize; len],
+                |mut frequency, chunk_idx| {
+                    cancel.check()?;
+
+                    // a frequency cannot be higher than the count of blocks
+                    chunk(chunk_idx).for_each(|pattern| frequency[pattern] += 1);
+                    Ok(frequency)
+                },
+            )
+            .try_reduce(
+                || vec![0_usize; len],
+                |mut a, b| -> Result<_, Error> {
+                    a.iter_mut()
+                        .zip(b)
+                        .try_for_each(|(a, b)| -> Result<(), Error> {
+                            *a = checked_add!(a, b)?;
+                            Ok(())
+                        })?;
+                    Ok(a)
+                },
+            )?
     } else {
-        // read "normally", maximum of 2 words possible
-        let start_word_idx = start_idx / (usize::BITS as usize);
-        let end_word_idx = end_idx / (usize::BITS as usize);
-
-        let start_bit_idx = start_idx % (usize::BITS as usize);
+        let mut frequency = Vec::with_capacity(len);
+        frequency.resize_with(len, || AtomicUsize::new(0));
+
+        chunks.into_par_iter().try_for_each(|chunk_idx| {
+            cancel.check()?;
+
+            let mut overflow = false;
+            chunk(chunk_idx).for_each(|pattern| {
+                overflow |= frequency[pattern].fetch_add(1, Ordering::Relaxed) == usize::MAX;
+            });
+            if overflow {
+                return Err(Error::Overflow(format!(
+                    "{} (frequency count) + 1",
+                    usize::MAX
+                )));
+            }
+            Ok(())
+        })?;
+
+        frequency.into_iter().map(AtomicUsize::into_inner).collect()
+    };
 
-        let first_part = data.words[start_word_idx] << start_bit_idx;
+    for pattern in wrapped_bits(data, block_length, windows.len()).windows(block_length, 1) {
+        frequency[pattern] = checked_add!(frequency[pattern], 1)?;
+    }
 
-        if start_word_idx == end_word_idx {
-            first_part
-        } else {
-            let second_part = data.words[end_word_idx] >> (usize::BITS as usize - start_bit_idx);
-            first_part | second_part
-        }
-    };
+    Ok(frequency.into_boxed_slice())
+}
 
-    // now the block_size high bits contain the value --> shift to low bits
-    let res = res >> (usize::BITS as u8 - block_length);
+/// The bits of the blocks that wrap around, i.e. the blocks starting at the bit `start` and after:
+/// the bits from `start` to the end of the sequence, followed by the first `block_length - 1` bits
+/// (repeated if the sequence is shorter than that).
+fn wrapped_bits(data: &BitVec, block_length: usize, start: usize) -> BitVec<'static> {
+    let bits = data
+        .iter_bits()
+        .skip(start)
+        .chain(data.iter_bits().cycle())
+        .take(data.len_bit() - start + block_length - 1)
+        .collect::<Vec<_>>();
+
+    BitVec::from(bits.as_slice())
+}
 
-    Some(res)
+/// Derives the frequencies of the blocks with length `m - 1` from the frequencies of the blocks
+/// with length `m`, without reading the data again.
+///
+/// Because of the wrap-around, each `(m - 1)` bit block is the prefix of exactly one `m` bit block
+/// at the same position, so its frequency is the sum of the frequencies of the `m` bit blocks
+/// `2 * pattern` and `2 * pattern + 1`.
+fn fold_patterns(frequency: &[usize]) -> Box<[usize]> {
+    frequency
+        .chunks_exact(2)
+        .map(|pair| pair[0] + pair[1])
+        .collect()
 }
@@ -15,12 +15,19 @@
 //! of constraint no. 3!
 
 use crate::bitvec::BitVec;
-use crate::internals::{check_f64, igamc};
-use crate::tests::serial_and_approximate_entropy::{access_bits, validate_test_arg};
-use crate::{Error, TestResult};
-use rayon::prelude::*;
-use std::num::NonZero;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use crate::internals::par::*;
+#[cfg(not(feature = "std"))]
+use crate::internals::F64Ext;
+use crate::internals::{check_f64, igamc, trace_span, HIGH_PRECISION};
+use crate::statistics::TestStatistics;
+use crate::tests::serial_and_approximate_entropy::{
+    count_patterns, fold_patterns, validate_sweep_range, validate_test_arg,
+};
+use crate::{ArgError, Error, ResultLabel, TestResult};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::num::NonZero;
+use core::ops::RangeInclusive;
 use sts_lib_derive::use_thread_pool;
 
 // calculation: minimum block length = 2
@@ -40,7 +47,7 @@ pub const MIN_INPUT_LENGTH: NonZero<usize> = const {
 /// Argument constraints:
 /// 1. the given block length must be >= 2.
 /// 2. each value of with the bit length the given block length must be representable as usize,
-///     i.e. depending on the platform, 32 or 64 bits.
+///    i.e. depending on the platform, 32 or 64 bits.
 /// 3. the block length must be < (log2([BitVec::len_bit]) as int) - 2
 ///
 /// Constraints 1 and 2 are checked when creating the arguments.
@@ -55,11 +62,22 @@ pub const MIN_INPUT_LENGTH: NonZero<usize> = const {
 pub struct SerialTestArg(u8);
 
 impl SerialTestArg {
-    /// To create a new instance of [SerialTestArg]. This function checks some constraints,
-    /// for details, see [SerialTestArg].
-    pub fn new(block_length: u8) -> Option<Self> {
+    /// To create a new instance of [SerialTestArg]. This function checks some
+    /// constraints and returns [ArgError::OutOfRange] if one is violated, for details, see [SerialTestArg].
+    pub fn try_new(block_length: u8) -> Result<Self, ArgError> {
         validate_test_arg(block_length).map(Self)
     }
+
+    /// Like [Self::try_new], but returns `None` instead of the reason.
+    #[deprecated(note = "use `try_new`, which reports why the arguments are invalid")]
+    pub fn new(block_length: u8) -> Option<Self> {
+        Self::try_new(block_length).ok()
+    }
+
+    /// The block length in bits.
+    pub fn block_length(&self) -> u8 {
+        self.0
+    }
 }
 
 impl Default for SerialTestArg {
@@ -68,6 +86,15 @@ impl Default for SerialTestArg {
     }
 }
 
+/// The test statistic of one result of the [serial_test], see [ResultLabel::Serial].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SerialKind {
+    /// The first result, calculated from *∇ψ²_m*.
+    Delta1,
+    /// The second result, calculated from *∇²ψ²_m*.
+    Delta2,
+}
+
 /// Serial Test  - No. 11
 ///
 /// See also the [module docs](crate::tests::serial).
@@ -79,6 +106,8 @@ pub fn serial_test(
     data: &BitVec,
     SerialTestArg(block_length): SerialTestArg,
 ) -> Result<[TestResult; 2], Error> {
+    trace_span!(INFO, "serial_test", len_bit = data.len_bit());
+
     // only check the argument when not testing
     #[cfg(not(test))]
     {
@@ -86,69 +115,163 @@ pub fn serial_test(
         let max_block_length = f64::log2(data.len_bit() as f64) as usize - 2;
 
         if (block_length as usize) >= max_block_length {
-            return Err(Error::InvalidParameter(format!(
+            return Err(Error::InvalidParameter(alloc::format!(
                 "Given block length must be lesser than log2(len_bit) - 2 (={max_block_length}). Is: {block_length}"
             )));
         }
     }
 
-    // Step 1 is skipped: we just read from the start again, see access_bits()
+    // Step 1 is skipped: the blocks wrap around to the start, see count_patterns()
     // Step 2: determine the frequency of all possible overlapping m, (m-1) and (m-2) bit blocks.
-    // (m == block_length)
-    let frequencies = create_frequency_slices(block_length);
-    (0..data.len_bit()).into_par_iter().try_for_each(|idx| {
-        for i in 0..3 {
-            // this can happen when block_length = 2
-            if block_length - i == 0 {
-                continue;
-            }
-
-            let idx = access_bits(data, idx, block_length - i)
-                .unwrap_or_else(|| panic!("serial_test: idx for (m - {i}) should be valid"));
-            let prev = frequencies[i as usize][idx].fetch_add(1, Ordering::Relaxed);
-            if prev == usize::MAX {
-                return Err(Error::Overflow(format!("{prev} (frequency count) + 1")));
-            }
+    // (m == block_length). Only the m bit blocks are counted, the others are derived from them.
+    let frequency = count_patterns(data, block_length)?;
+
+    // Step 3: for each tested block length m (3 in total), compute psi^2(m)
+    let mut psi = [PsiSquared::ZERO; 3];
+    psi[0] = psi_squared(&frequency, data.len_bit())?;
+    let frequency = fold_patterns(&frequency);
+    psi[1] = psi_squared(&frequency, data.len_bit())?;
+    // this is 0 when block_length = 2
+    if block_length > 2 {
+        psi[2] = psi_squared(&fold_patterns(&frequency), data.len_bit())?;
+    }
+
+    // Step 4 and 5
+    calculate_p_values(block_length, psi, data.len_bit())
+}
+
+/// Serial Test for multiple block lengths at once, e.g. to study the structure of the sequence at
+/// different scales.
+///
+/// The overlapping blocks are only counted once for the largest block length, the frequencies for
+/// all smaller block lengths are derived from them. This is much faster than calling [serial_test]
+/// for each block length. The results are the same, returned in ascending order with the used
+/// block length.
+///
+/// If the range is empty, or a block length in the range is invalid for the given data,
+/// [Error::InvalidParameter] is raised. For the exact constraints, see [SerialTestArg].
+#[use_thread_pool]
+pub fn serial_test_sweep(
+    data: &BitVec,
+    range: RangeInclusive<u8>,
+) -> Result<Vec<(u8, [TestResult; 2])>, Error> {
+    trace_span!(INFO, "serial_test_sweep", len_bit = data.len_bit());
+
+    validate_sweep_range(&range, data.len_bit(), 2)?;
+
+    // psi^2 for each block length from the largest one down to 1, psi^2(0) is always 0.
+    let max_block_length = *range.end();
+    let min_block_length = range.start() - 2;
+    let mut psi = vec![PsiSquared::ZERO; max_block_length as usize + 1];
+    let mut frequency = count_patterns(data, max_block_length)?;
+    for block_length in (min_block_length.max(1)..=max_block_length).rev() {
+        if block_length != max_block_length {
+            frequency = fold_patterns(&frequency);
         }
+        psi[block_length as usize] = psi_squared(&frequency, data.len_bit())?;
+    }
+
+    range
+        .map(|block_length| {
+            let m = block_length as usize;
+            let results = calculate_p_values(
+                block_length,
+                [psi[m], psi[m - 1], psi[m - 2]],
+                data.len_bit(),
+            )?;
+            Ok((block_length, results))
+        })
+        .collect()
+}
+
+/// psi^2(m) for one block length m, see [psi_squared].
+#[derive(Copy, Clone, Debug)]
+struct PsiSquared {
+    /// The value of psi^2(m).
+    value: f64,
+    /// `n * psi^2(m) = 2^m * sum(v_mi^2) - n^2`, calculated exactly with the feature
+    /// `high-precision`. `None` without the feature, or if the value does not fit in an [i128].
+    scaled: Option<i128>,
+}
+
+impl PsiSquared {
+    /// psi^2(0), which is always 0.
+    const ZERO: Self = Self {
+        value: 0.0,
+        scaled: Some(0),
+    };
+}
+
+/// Step 3: compute psi^2(m) = (2^m) / n * sum(v_mi^2) - n
+/// with n denoting the bit length of the sequence and v_mi denoting on element in the frequency
+/// list of the block length m.
+fn psi_squared(frequency: &[usize], len_bit: usize) -> Result<PsiSquared, Error> {
+    if HIGH_PRECISION {
+        if let Some(scaled) = scaled_psi_squared(frequency, len_bit) {
+            let value = scaled as f64 / len_bit as f64;
+            check_f64(value)?;
+            return Ok(PsiSquared {
+                value,
+                scaled: Some(scaled),
+            });
+        }
+    }
 
-        Ok(())
+    let sum = frequency.par_iter().map(|&v| (v * v) as f64).sum::<f64>();
+
+    check_f64(sum)?;
+
+    let psi = frequency.len() as f64 / (len_bit as f64) * sum - (len_bit as f64);
+    check_f64(psi)?;
+    Ok(PsiSquared {
+        value: psi,
+        scaled: None,
+    })
+}
+
+/// Calculates `n * psi^2(m) = 2^m * sum(v_mi^2) - n^2` exactly with integers, `None` if a value
+/// does not fit in an [i128] (inputs longer than about 2^42 bits). pub(crate) to allow for tests.
+pub(crate) fn scaled_psi_squared(frequency: &[usize], len_bit: usize) -> Option<i128> {
+    let sum = frequency.iter().try_fold(0_i128, |sum, &v| {
+        let v = v as i128;
+        sum.checked_add(v.checked_mul(v)?)
     })?;
 
-    // Step 3: for each tested block length m (3 in total), compute
-    // psi^2(m) = (2^m) / n * sum(v_mi^2) - n
-    // with n denoting the bit length of the sequence and v_mi denoting on element in the frequency list
-    // of the block length.
-    let mut psi = [0.0; 3];
-    psi.iter_mut()
-        .zip(frequencies)
-        .enumerate()
-        .try_for_each(|(i, (psi, frequency))| {
-            // this can happen when block_length = 2
-            if block_length - i as u8 == 0 {
-                *psi = 0.0;
-                return Ok(());
-            }
-
-            let sum = frequency
-                .into_vec()
-                .into_par_iter()
-                .map(|v| {
-                    let v = v.into_inner();
-                    (v * v) as f64
-                })
-                .sum::<f64>();
-
-            check_f64(sum)?;
-
-            *psi = f64::powi(2.0, block_length as i32 - i as i32) / (data.len_bit() as f64) * sum
-                - (data.len_bit() as f64);
-
-            check_f64(*psi)
-        })?;
+    let len_bit = len_bit as i128;
+    (frequency.len() as i128)
+        .checked_mul(sum)?
+        .checked_sub(len_bit.checked_mul(len_bit)?)
+}
 
+/// Steps 4 and 5: compute the P-values from psi^2 for the block lengths m, m - 1 and m - 2.
+fn calculate_p_values(
+    block_length: u8,
+    psi: [PsiSquared; 3],
+    len_bit: usize,
+) -> Result<[TestResult; 2], Error> {
     // Step 4: compute delta = psi[0] - psi[1] and delta^2 = psi[0] - 2 * psi[1] + psi[2]
-    let delta = psi[0] - psi[1];
-    let delta_squared = psi[0] - 2.0 * psi[1] + psi[2];
+    // With the feature high-precision, the differences are calculated without cancellation and
+    // only rounded in the division. psi^2 is never negative, so only the second difference may
+    // overflow.
+    let scaled = match psi.map(|psi| psi.scaled) {
+        [Some(psi_0), Some(psi_1), Some(psi_2)] => {
+            let delta = psi_0 - psi_1;
+            delta
+                .checked_sub(psi_1 - psi_2)
+                .map(|delta_squared| (delta, delta_squared))
+        }
+        _ => None,
+    };
+    let (delta, delta_squared) = match scaled {
+        Some((delta, delta_squared)) => (
+            delta as f64 / len_bit as f64,
+            delta_squared as f64 / len_bit as f64,
+        ),
+        None => {
+            let psi = psi.map(|psi| psi.value);
+            (psi[0] - psi[1], psi[0] - 2.0 * psi[1] + psi[2])
+        }
+    };
 
     // Step 5: compute p_value_1 = igamc(2^(block_length - 2), delta / 2)
     // and p_value_2 = igamc(2^(block_length - 3), delta^2 / 2).
@@ -157,26 +280,18 @@ pub fn serial_test(
     let p_value_1 = igamc(f64::powi(2.0, block_length as i32 - 2), delta / 2.0)?;
     let p_value_2 = igamc(f64::powi(2.0, block_length as i32 - 3), delta_squared / 2.0)?;
 
-    Ok([TestResult::new(p_value_1), TestResult::new(p_value_2)])
-}
-
-/// Returns 3 boxed slices used for storing the measured frequency of a given pattern.
-/// \[0] is for patterns with bit length `block_length`, \[1] for `block_length - 1` and
-/// \[2] for `block_length - 2`.
-/// The pattern is used as the index for each boxed slice, the value itself stores the frequency.
-#[inline]
-fn create_frequency_slices(block_length: u8) -> [Box<[AtomicUsize]>; 3] {
-    #[inline]
-    fn create_frequency_slice(block_length: u8) -> Box<[AtomicUsize]> {
-        let len = 1 << block_length;
-        let mut vec = Vec::with_capacity(len);
-        vec.resize_with(len, || AtomicUsize::new(0));
-        vec.into_boxed_slice()
-    }
+    let statistics = TestStatistics::Serial {
+        psi_squared: psi.map(|psi| psi.value),
+        delta,
+        delta_squared,
+    };
 
-    [
-        create_frequency_slice(block_length),
-        create_frequency_slice(block_length - 1),
-        create_frequency_slice(block_length - 2),
-    ]
+    Ok([
+        TestResult::new(p_value_1)
+            .with_statistics(statistics)
+            .with_label(ResultLabel::Serial(SerialKind::Delta1)),
+        TestResult::new(p_value_2)
+            .with_statistics(statistics)
+            .with_label(ResultLabel::Serial(SerialKind::Delta2)),
+    ])
 }
@@ -4,16 +4,19 @@
 //! periodic features that indicate a deviation from a random sequence.
 //!
 //! It is recommended (but not required) for the input to be of at least 1000 bits.
+//! The derivation of the peak height threshold can be chosen with [SpectralDftTestArg].
 
+use crate::bitvec::word::{self, Word};
 use crate::bitvec::BitVec;
-use crate::internals::{check_f64, checked_add, erfc, BitPrimitive};
+use crate::internals::{check_f64, checked_add, erfc, trace_span};
+use crate::statistics::TestStatistics;
+use crate::test_runner::cancellation::CancelCheck;
 use crate::{Error, TestResult};
 use rayon::prelude::*;
 use rustfft::num_complex::Complex;
 use rustfft::FftPlanner;
-use std::f64::consts::FRAC_1_SQRT_2;
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
 use std::num::NonZero;
-use std::ops::Range;
 use std::sync::{LazyLock, Mutex};
 use sts_lib_derive::use_thread_pool;
 
@@ -25,6 +28,72 @@ pub const MIN_INPUT_LENGTH: NonZero<usize> = const {
     }
 };
 
+/// The argument for the Spectral DFT test: how the peak height threshold is derived (see
+/// [PeakThreshold]).
+///
+/// The DFT is computed with a parallel FFT. For an even input length, the sequence is packed into
+/// a complex sequence of half the length (real-input FFT), and the peaks are counted while
+/// unpacking the transformed values. The only large buffer needs 4 bytes per input bit (10^8
+/// bits: 400 MB), or 8 bytes per bit for an odd input length.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct SpectralDftTestArg {
+    peak_threshold: PeakThreshold,
+}
+
+impl SpectralDftTestArg {
+    /// Create new arguments with the given peak height threshold.
+    pub const fn new(peak_threshold: PeakThreshold) -> Self {
+        Self { peak_threshold }
+    }
+
+    /// How the peak height threshold is derived.
+    pub fn peak_threshold(&self) -> PeakThreshold {
+        self.peak_threshold
+    }
+
+    /// Calculates the threshold *T* for an input of the given length in bits, see
+    /// [PeakThreshold::threshold].
+    pub fn threshold(&self, len_bit: usize) -> f64 {
+        self.peak_threshold.threshold(len_bit)
+    }
+}
+
+impl From<PeakThreshold> for SpectralDftTestArg {
+    fn from(value: PeakThreshold) -> Self {
+        Self::new(value)
+    }
+}
+
+/// How the 95 % peak height threshold *T* is derived, see [SpectralDftTestArg].
+///
+/// 95 % of the peaks in the DFT of a random sequence of length *n* are expected to be below *T*.
+/// The original SP 800-22 (2001) used the constant *T = sqrt(3n)*, which is known to be biased:
+/// only *1 - e^(-3) ≈ 95.02 %* of the peaks lie below it. Kim, Umeno and Hasegawa corrected
+/// this to *T = sqrt(ln(1/0.05) n)*, which is used by the current revision of SP 800-22 and its
+/// reference implementation.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub enum PeakThreshold {
+    /// *T = sqrt(ln(1/0.05) n)*, as corrected by Kim, Umeno and Hasegawa. This is the
+    /// threshold used by the current revision of SP 800-22.
+    #[default]
+    KimUmeno,
+    /// *T = sqrt(3n)*, the biased constant of the original SP 800-22. Only use this to compare
+    /// results with old implementations.
+    OriginalNist,
+}
+
+impl PeakThreshold {
+    /// Calculates the threshold *T* for an input of the given length in bits.
+    pub fn threshold(&self, len_bit: usize) -> f64 {
+        let factor = match self {
+            PeakThreshold::KimUmeno => f64::ln(1.0 / 0.05),
+            PeakThreshold::OriginalNist => 3.0,
+        };
+
+        f64::sqrt(factor * (len_bit as f64))
+    }
+}
+
 // Use a global planner to allow for caching if the test is run multiple times.
 static FFT_PLANNER: LazyLock<Mutex<FftPlanner<f32>>> =
     LazyLock::new(|| Mutex::new(FftPlanner::new()));
@@ -32,88 +101,319 @@ static FFT_PLANNER: LazyLock<Mutex<FftPlanner<f32>>> =
 /// Spectral DFT test - No. 6
 ///
 /// See the [module docs](crate::tests::spectral_dft).
+/// The threshold is derived as chosen by `test_arg`, see [SpectralDftTestArg].
 /// If an error happens, it means either arithmetic underflow or overflow.
 #[use_thread_pool]
-pub fn spectral_dft_test(data: &BitVec) -> Result<TestResult, Error> {
-    // Step 1: convert the input bit sequence to a sequence of -1 and +1 (x)
-    // This is done in parallel. f32 is used for better performance with such large lists.
-    // For use in the fourier transformation, the number is converted to a complex number.
-    let (words, last_word) = data.as_full_slice();
-
-    let mut x = words
-        .par_iter()
-        .flat_map_iter(|&word| {
-            // one number per bit
-            convert_word(word, 0..usize::BITS)
-        })
-        .collect::<Vec<_>>();
-    // add remaining bits
-    if let Some(last_word) = last_word {
-        let bits = 0..(data.bit_count_last_word as u32);
-        x.extend(convert_word(last_word, bits))
-    }
+pub fn spectral_dft_test(data: &BitVec, test_arg: SpectralDftTestArg) -> Result<TestResult, Error> {
+    trace_span!(INFO, "spectral_dft_test", len_bit = data.len_bit());
 
     // the bit length
     let n = data.len_bit();
 
-    debug_assert_eq!(x.len(), n);
+    // Step 4: compute T = sqrt(ln(1/0.05)*n) (or T = sqrt(3n) for the original NIST constant)
+    let t = test_arg.threshold(n);
+
+    // Step 5: compute n_0 = 0.95 * n / 2
+    let n_0 = 0.95 * (n as f64) / 2.0;
+
+    // Step 1 - 3 and step 6: compute n_1 = count of peaks in the first half of the DFT that are < t
+    let n_1 = count_peaks(data, t)? as f64;
+
+    // Step 7: compute d = (n_1 - n_0) / sqrt(data.len_bit() * 0.95 * 0.05 / 4.0)
+    let d = (n_1 - n_0) / f64::sqrt((data.len_bit() as f64) * 0.95 * 0.05 / 4.0);
+    check_f64(d)?;
+
+    // Step 8: compute p_value = erfc(|d| * 1 / sqrt(2))
+    let p_value = erfc(d.abs() * FRAC_1_SQRT_2);
+    check_f64(p_value)?;
+
+    Ok(
+        TestResult::new(p_value).with_statistics(TestStatistics::SpectralDft {
+            threshold: t,
+            expected_peaks: n_0,
+            observed_peaks: n_1 as usize,
+            d,
+        }),
+    )
+}
+
+/// The layout of the sequence *z* of length *m* for the FFT in [count_peaks]: a matrix with
+/// *r* rows and *c* columns, *m = r c*, with *z_j1+r*j2* in row *j1* and column *j2*.
+#[derive(Copy, Clone, Debug)]
+struct Layout {
+    rows: usize,
+    columns: usize,
+}
+
+impl Layout {
+    /// The layout for a sequence of length *m*: the count of rows is the largest divisor of *m*
+    /// up to *sqrt(m)*. For a prime *m*, this is a single row.
+    fn new(m: usize) -> Self {
+        let rows = (1..=(f64::sqrt(m as f64) as usize))
+            .rev()
+            .find(|rows| m % rows == 0)
+            .unwrap_or(1);
+
+        Self {
+            rows,
+            columns: m / rows,
+        }
+    }
+}
+
+/// Step 1 - 3 and step 6: returns the count of peaks in the first half of the DFT that are below
+/// the threshold `t`.
+///
+/// For an even bit length *n*, the sequence *x* is packed into the complex sequence *z* of length
+/// *m = n / 2*, with *z_j = x_2j + i x_2j+1* (real-input FFT), else *z = x* with *m = n*. The DFT
+/// *Z* of *z* is calculated with the six-step FFT (Bailey, 'FFTs in External or Hierarchical
+/// Memory', 1990), with *z* stored as a matrix, see [Layout], and *w_m = e^(-2 pi i / m)*:
+/// 1. transform each row (length *c*) and multiply the value in row *j1* and column *k2* by
+///    *w_m^(j1 k2)*,
+/// 2. transform each column (length *r*): *Z_k2+c*k1* is then in row *k1* and column *k2*.
+///
+/// Groups of rows and groups of columns are transformed in parallel. The columns are never
+/// written back, the peaks are counted right after transforming them. This way, *z* is the only
+/// large buffer, with 4 bytes per input bit (8 bytes for an odd length).
+///
+/// For an even bit length, the DFT *X* of *x* is then unpacked from *Z*, with
+/// *w = e^(-2 pi i / n)* and *Z_m = Z_0*:
+/// - *E_k = (Z_k + conj(Z_m-k)) / 2* and *O_k = -i (Z_k - conj(Z_m-k)) / 2*
+/// - *X_k = E_k + w^k O_k* and *X_m-k = conj(E_k - w^k O_k)*
+///
+/// *Z_k* and *Z_m-k* are in the columns *k2* and *c - k2* (or 0 for *k2 = 0*), so these columns
+/// are transformed together, yielding the values of *X* for both columns.
+fn count_peaks(data: &BitVec, t: f64) -> Result<usize, Error> {
+    // the count of rows transformed on one thread in the first step
+    const ROW_CHUNK: usize = 16;
+    // the count of columns transformed on one thread in the second step, with their partners
+    const COLUMN_GROUP: usize = 16;
+
+    let n = data.len_bit();
+    let real_input = n % 2 == 0;
+    let m = if real_input { n / 2 } else { n };
+    if m == 0 {
+        return Ok(0);
+    }
+    let Layout { rows, columns } = Layout::new(m);
+
+    let cancel = CancelCheck::current();
+    cancel.check()?;
 
-    // Step 2: apply a DFT to produce 's'
-    // A FFT is a DFT.
     // About the implementation: Panics from another thread should propagate here. The scope is used
     // to keep the Mutex lock as short as possible.
-    let fft = {
+    let (row_fft, column_fft) = {
         let mut fft_planner = FFT_PLANNER.lock().unwrap();
-        fft_planner.plan_fft_forward(x.len())
+        (
+            fft_planner.plan_fft_forward(columns),
+            fft_planner.plan_fft_forward(rows),
+        )
     };
-    // result is stored into the passed buffer
-    fft.process(&mut x);
 
-    // Step 4: compute T = sqrt(ln(1/0.05)*n)
-    let t = f64::sqrt(f64::ln(1.0 / 0.05) * (n as f64));
+    trace_span!(DEBUG, "dft", len = m);
 
-    // Step 5: compute n_0 = 0.95 * n / 2
-    let n_0 = 0.95 * (n as f64) / 2.0;
+    // Step 1: convert the input bit sequence to a sequence of -1 and +1 (x), which is stored as z
+    // in the layout of the matrix right away. f32 is used for better performance with such large
+    // lists.
+    // Step 2, first part: transform the rows of z and apply the twiddle factors.
+    let words = &*data.words;
+    let angle_m = -2.0 * PI / (m as f64);
+    let mut z = vec![Complex::<f32>::default(); m];
+    z.par_chunks_mut(ROW_CHUNK * columns)
+        .enumerate()
+        .try_for_each_init(
+            || vec![Complex::default(); row_fft.get_inplace_scratch_len()],
+            |scratch, (chunk_idx, chunk)| {
+                cancel.check()?;
 
-    // Step 3: calculate M = |S'|, with S' being the first half of S (=x)
+                let first_row = chunk_idx * ROW_CHUNK;
+                let chunk_rows = chunk.len() / columns;
+                // the values of a column of the chunk are consecutive in the sequence
+                for j2 in 0..columns {
+                    for r in 0..chunk_rows {
+                        let j = first_row + r + rows * j2;
+                        chunk[r * columns + j2] = if real_input {
+                            Complex::new(bit_to_f32(words, 2 * j), bit_to_f32(words, 2 * j + 1))
+                        } else {
+                            Complex::from(bit_to_f32(words, j))
+                        };
+                    }
+                }
+
+                row_fft.process_with_scratch(chunk, scratch);
+
+                for (r, row) in chunk.chunks_exact_mut(columns).enumerate() {
+                    let step = Complex::from_polar(1.0, angle_m * ((first_row + r) as f64));
+                    let mut twiddle = Complex::new(1.0, 0.0);
+                    for value in row {
+                        *value *= to_f32(twiddle);
+                        twiddle *= step;
+                    }
+                }
+
+                Ok::<_, Error>(())
+            },
+        )?;
+
+    // Step 2, second part: transform the columns, each column k2 <= c / 2 together with its
+    // partner column.
+    // Step 3: calculate M = |S'|, with S' being the first half of S (=X)
     // Step 6: compute n_1 = count of observed entries in M that are < t
-    let n_1 = x[0..(n / 2)]
-        .par_iter()
-        .try_fold(
-            || 0_usize,
-            |count, s| {
-                let no = Complex::<f64> {
-                    re: s.re as f64,
-                    im: s.im as f64,
-                };
-                let norm = no.norm();
-                check_f64(norm)?;
-
-                if norm < t {
-                    checked_add!(count, 1)
-                } else {
-                    Ok(count)
+    // comparing the squares saves the square roots of the magnitudes
+    let t_sqr = t * t;
+    let angle_n = -2.0 * PI / (n as f64);
+    let owned_columns = columns / 2 + 1;
+    let z = &z;
+
+    (0..owned_columns.div_ceil(COLUMN_GROUP))
+        .into_par_iter()
+        .map_init(
+            || {
+                (
+                    vec![Complex::<f32>::default(); COLUMN_GROUP * rows],
+                    vec![Complex::<f32>::default(); COLUMN_GROUP * rows],
+                    vec![Complex::default(); column_fft.get_inplace_scratch_len()],
+                )
+            },
+            |(own, partner, scratch), group| {
+                cancel.check()?;
+
+                let group_columns =
+                    (group * COLUMN_GROUP)..owned_columns.min((group + 1) * COLUMN_GROUP);
+                let len = group_columns.len() * rows;
+
+                // gather the columns, each one stored consecutively
+                for (j1, row) in z.chunks_exact(columns).enumerate() {
+                    for (i, k2) in group_columns.clone().enumerate() {
+                        own[i * rows + j1] = row[k2];
+                        partner[i * rows + j1] = row[(columns - k2) % columns];
+                    }
                 }
+                column_fft.process_with_scratch(&mut own[..len], scratch);
+                column_fft.process_with_scratch(&mut partner[..len], scratch);
+
+                let mut count = 0;
+                for (i, k2) in group_columns.enumerate() {
+                    let own = &own[i * rows..][..rows];
+                    let partner = &partner[i * rows..][..rows];
+                    // the columns 0 and c / 2 are their own partners
+                    let paired = k2 != (columns - k2) % columns;
+
+                    count += if real_input {
+                        count_unpacked_peaks(own, partner, k2, columns, paired, angle_n, t_sqr)?
+                    } else {
+                        let k2_partner = (columns - k2) % columns;
+                        count_column_peaks(own, k2, columns, n / 2, t_sqr)?
+                            + if paired {
+                                count_column_peaks(partner, k2_partner, columns, n / 2, t_sqr)?
+                            } else {
+                                0
+                            }
+                    };
+                }
+
+                Ok(count)
             },
         )
-        .try_reduce(|| 0_usize, |a, b| checked_add!(a, b))? as f64;
+        .try_reduce(|| 0_usize, |a, b| checked_add!(a, b))
+}
 
-    // Step 7: compute d = (n_1 - n_0) / sqrt(data.len_bit() * 0.95 * 0.05 / 4.0)
-    let d = (n_1 - n_0) / f64::sqrt((data.len_bit() as f64) * 0.95 * 0.05 / 4.0);
-    check_f64(d)?;
+/// Counts the values of the transformed column `k2` below the threshold, given as its square
+/// `t_sqr`, for a complex FFT: only the values *Z_k* with *k < `limit`* are counted.
+fn count_column_peaks(
+    column: &[Complex<f32>],
+    k2: usize,
+    columns: usize,
+    limit: usize,
+    t_sqr: f64,
+) -> Result<usize, Error> {
+    let mut count = 0;
+    for (k1, value) in column.iter().enumerate() {
+        if k2 + columns * k1 >= limit {
+            break;
+        }
 
-    // Step 8: compute p_value = erfc(|d| * 1 / sqrt(2))
-    let p_value = erfc(d.abs() * FRAC_1_SQRT_2);
-    check_f64(p_value)?;
+        let norm_sqr = to_f64(*value).norm_sqr();
+        check_f64(norm_sqr)?;
+        if norm_sqr < t_sqr {
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
+
+/// Unpacks the values of *X* from the transformed column `k2` and its partner column and counts
+/// the ones below the threshold, given as its square `t_sqr`, for a real-input FFT, see
+/// [count_peaks]. The values of the
+/// partner column are only counted if the column is `paired`, i.e. not its own partner.
+fn count_unpacked_peaks(
+    own: &[Complex<f32>],
+    partner: &[Complex<f32>],
+    k2: usize,
+    columns: usize,
+    paired: bool,
+    angle: f64,
+    t_sqr: f64,
+) -> Result<usize, Error> {
+    let rows = own.len();
+
+    // w^k for k = k2 + c * k1
+    let step = Complex::from_polar(1.0, angle * (columns as f64));
+    let mut twiddle = Complex::from_polar(1.0, angle * (k2 as f64));
+
+    let mut count = 0;
+    for (k1, z_k) in own.iter().enumerate() {
+        // Z_m-k is in the partner column, in reverse order
+        let k1_partner = if k2 == 0 {
+            (rows - k1) % rows
+        } else {
+            rows - 1 - k1
+        };
+
+        let z_k = to_f64(*z_k);
+        let z_m_k = to_f64(partner[k1_partner]).conj();
 
-    Ok(TestResult::new(p_value))
+        let even = (z_k + z_m_k) / 2.0;
+        let odd = (z_k - z_m_k) * Complex::new(0.0, -0.5) * twiddle;
+        twiddle *= step;
+
+        let norm_sqr = (even + odd).norm_sqr();
+        check_f64(norm_sqr)?;
+        if norm_sqr < t_sqr {
+            count += 1;
+        }
+
+        if paired {
+            let norm_sqr = (even - odd).norm_sqr();
+            check_f64(norm_sqr)?;
+            if norm_sqr < t_sqr {
+                count += 1;
+            }
+        }
+    }
+
+    Ok(count)
+}
+
+/// Convert the bit with the given index of the sequence, with bit 1 -> 1.0 and bit 0 -> -1.0
+#[inline]
+fn bit_to_f32(words: &[Word], bit_idx: usize) -> f32 {
+    if word::get_bit_at(words, bit_idx) {
+        1.0
+    } else {
+        -1.0
+    }
+}
+
+/// Convert a complex number to double precision for further calculations.
+#[inline]
+fn to_f64(value: Complex<f32>) -> Complex<f64> {
+    Complex::new(value.re as f64, value.im as f64)
 }
 
-/// Convert a word into a sequence of bit, with bit 1 -> 1.0 and bit 0 -> -1.0
+/// Convert a complex number back to single precision.
 #[inline]
-fn convert_word(word: usize, bits: Range<u32>) -> impl Iterator<Item = Complex<f32>> {
-    bits.map(move |bit| {
-        let bit = word.get_bit(bit);
-        Complex::from(if bit { 1.0 } else { -1.0 })
-    })
+fn to_f32(value: Complex<f64>) -> Complex<f32> {
+    Complex::new(value.re as f32, value.im as f32)
 }
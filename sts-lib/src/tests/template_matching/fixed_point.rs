@@ -0,0 +1,129 @@
+//! Unsigned fixed-point numbers with 256 bits, see [Fixed].
+
+use std::iter::Sum;
+use std::ops::{Add, Mul, Sub};
+
+/// The count of fractional bits.
+const FRACTIONAL_BITS: u32 = 192;
+
+/// An unsigned fixed-point number with 64 integer bits and 192 fractional bits, used to calculate
+/// the pi values of the [Overlapping Template Matching Test](super::overlapping).
+///
+/// The value is stored as 4 words, least significant first. The results of all operations are
+/// truncated to the 192 fractional bits, so each operation has an absolute error of at most
+/// 2^-192. Overflows and underflows are not checked in release mode, the calculations must keep
+/// all values between 0 and 2^64.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Fixed([u64; 4]);
+
+impl Fixed {
+    /// The value 0.
+    pub(crate) const ZERO: Self = Self([0; 4]);
+
+    /// The value 1.
+    pub(crate) const ONE: Self = Self([0, 0, 0, 1]);
+
+    /// Divides the value by 2^`exponent`, truncating the bits that are shifted out.
+    pub(crate) fn div_pow2(self, exponent: u32) -> Self {
+        let word_shift = (exponent / u64::BITS) as usize;
+        let bit_shift = exponent % u64::BITS;
+
+        let mut words = [0; 4];
+        for (i, word) in words.iter_mut().enumerate() {
+            let Some(&low) = self.0.get(i + word_shift) else {
+                break;
+            };
+            let high = self.0.get(i + word_shift + 1).copied().unwrap_or(0);
+
+            *word = if bit_shift == 0 {
+                low
+            } else {
+                (low >> bit_shift) | (high << (u64::BITS - bit_shift))
+            };
+        }
+
+        Self(words)
+    }
+
+    /// The nearest [f64] to the value, ignoring the bits after the 128 most significant set bits.
+    pub(crate) fn to_f64(self) -> f64 {
+        let Some(top) = self.0.iter().rposition(|&word| word != 0) else {
+            return 0.0;
+        };
+
+        // the two words starting with the top word, as one number
+        let (value, lowest_word) = match top {
+            0 => (self.0[0] as u128, 0),
+            _ => (
+                ((self.0[top] as u128) << u64::BITS) | self.0[top - 1] as u128,
+                top - 1,
+            ),
+        };
+        let exponent = (lowest_word as i32) * (u64::BITS as i32) - FRACTIONAL_BITS as i32;
+
+        value as f64 * f64::powi(2.0, exponent)
+    }
+}
+
+impl Add for Fixed {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let mut words = [0; 4];
+        let mut carry = false;
+        for (i, word) in words.iter_mut().enumerate() {
+            let (sum, carry_1) = self.0[i].overflowing_add(rhs.0[i]);
+            let (sum, carry_2) = sum.overflowing_add(u64::from(carry));
+            *word = sum;
+            carry = carry_1 || carry_2;
+        }
+        debug_assert!(!carry, "fixed-point overflow");
+
+        Self(words)
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        let mut words = [0; 4];
+        let mut borrow = false;
+        for (i, word) in words.iter_mut().enumerate() {
+            let (difference, borrow_1) = self.0[i].overflowing_sub(rhs.0[i]);
+            let (difference, borrow_2) = difference.overflowing_sub(u64::from(borrow));
+            *word = difference;
+            borrow = borrow_1 || borrow_2;
+        }
+        debug_assert!(!borrow, "fixed-point underflow");
+
+        Self(words)
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        // schoolbook multiplication into 8 words, the result are the words 3 to 6
+        let mut product = [0_u64; 8];
+        for (i, &left) in self.0.iter().enumerate() {
+            let mut carry = 0_u128;
+            for (j, &right) in rhs.0.iter().enumerate() {
+                let value = (left as u128) * (right as u128) + (product[i + j] as u128) + carry;
+                product[i + j] = value as u64;
+                carry = value >> u64::BITS;
+            }
+            product[i + 4] = carry as u64;
+        }
+        debug_assert!(product[7] == 0, "fixed-point overflow");
+
+        Self([product[3], product[4], product[5], product[6]])
+    }
+}
+
+impl Sum for Fixed {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
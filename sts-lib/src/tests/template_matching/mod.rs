@@ -1,21 +1,48 @@
 //! Everything necessary for the [Non-Overlapping](non_overlapping) and [Overlapping](overlapping)
 //! template matching tests. For the tests themselves, see the corresponding submodules.
 //!
-//! This module also contains the template argument used by both tests.
+//! This module also contains the template argument used by both tests, the parsing of custom
+//! template files, see [parse_template_file], and the generation of aperiodic templates, see
+//! [generate_aperiodic_templates].
+//!
+//! The templates up to a length of 21 bits are taken from the template files of the NIST reference
+//! implementation, which are embedded into the library. With the feature `external-templates`, the
+//! files are not embedded, but loaded from a directory at run time, see `set_template_dir`.
+//!
+//! With the feature `pi-cache`, the pi values of the overlapping test are cached on disk, see
+//! `pi_cache`.
 
+pub(crate) mod fixed_point;
 pub mod non_overlapping;
 pub mod overlapping;
+#[cfg(feature = "pi-cache")]
+pub mod pi_cache;
 
+use crate::bitvec::word::{self, Word, WORD_BITS};
+use crate::ArgError;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
 use std::io::BufReader;
+use std::path::Path;
+#[cfg(feature = "external-templates")]
+use std::path::PathBuf;
 use std::sync::LazyLock;
+#[cfg(feature = "external-templates")]
+use std::sync::{Mutex, PoisonError};
+use thiserror::Error;
 
 /// The default template length. For use in [TemplateArg].
 pub const DEFAULT_TEMPLATE_LENGTH: usize = 9;
 
+/// The maximum template length of a [TemplateArg]. The templates up to a length of 21 are embedded
+/// into the library, longer templates are generated on first use.
+pub const MAX_TEMPLATE_LENGTH: usize = 31;
+
 /// This argument contains the template to use.
 ///
-/// For the template length `m`, the following bounds must be met: 2 <= `m` <= 21 - recommended: 9.
-/// Templates are chosen automatically. Unit is bits.
+/// For the template length `m`, the following bounds must be met: 2 <= `m` <= 31 - recommended: 9.
+/// Templates are chosen automatically: all aperiodic templates of the length. Unit is bits.
 ///
 /// These bounds are checked by all creation functions.
 /// A default variant is available with [TemplateArg::default()].
@@ -29,12 +56,15 @@ pub struct TemplateArg<'a> {
 impl TemplateArg<'static> {
     /// Constructor with the template length as normal values, evaluated at run time.
     /// See [TemplateArg].
+    pub fn try_new(template_len: usize) -> Result<Self, ArgError> {
+        check_template_len(template_len)?;
+        Ok(Self::new_unchecked(template_len))
+    }
+
+    /// Like [Self::try_new], but returns `None` instead of the reason.
+    #[deprecated(note = "use `try_new`, which reports why the arguments are invalid")]
     pub fn new(template_len: usize) -> Option<Self> {
-        if (2..=21).contains(&template_len) {
-            Some(Self::new_unchecked(template_len))
-        } else {
-            None
-        }
+        Self::try_new(template_len).ok()
     }
 
     /// Constructor with the template length as const generic, which is asserted at compile time.
@@ -42,7 +72,7 @@ impl TemplateArg<'static> {
     pub fn new_const<const M: usize>() -> Self {
         const {
             assert!(2 <= M, "m must be >= 2");
-            assert!(M <= 21, "m must be <= 21");
+            assert!(M <= MAX_TEMPLATE_LENGTH, "m must be <= 31");
         }
 
         Self::new_unchecked(M)
@@ -50,82 +80,26 @@ impl TemplateArg<'static> {
 
     /// Internal constructor: does not check the arguments, just panics if one is wrong.
     fn new_unchecked(template_len: usize) -> Self {
-        // the template files are embedded into the program.
-        const UNCOMPRESSED_TEMPLATE_FILES: [&[u8]; 16] = [
-            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template2")),
-            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template3")),
-            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template4")),
-            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template5")),
-            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template6")),
-            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template7")),
-            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template8")),
-            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template9")),
-            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template10")),
-            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template11")),
-            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template12")),
-            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template13")),
-            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template14")),
-            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template15")),
-            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template16")),
-            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template17")),
+        // The templates longer than 21 bits are not embedded (the files would be too big), but
+        // generated on first access.
+        static GENERATED_TEMPLATES: [LazyLock<Box<[usize]>>; 10] = [
+            LazyLock::new(|| generate_aperiodic_templates(22).into_boxed_slice()),
+            LazyLock::new(|| generate_aperiodic_templates(23).into_boxed_slice()),
+            LazyLock::new(|| generate_aperiodic_templates(24).into_boxed_slice()),
+            LazyLock::new(|| generate_aperiodic_templates(25).into_boxed_slice()),
+            LazyLock::new(|| generate_aperiodic_templates(26).into_boxed_slice()),
+            LazyLock::new(|| generate_aperiodic_templates(27).into_boxed_slice()),
+            LazyLock::new(|| generate_aperiodic_templates(28).into_boxed_slice()),
+            LazyLock::new(|| generate_aperiodic_templates(29).into_boxed_slice()),
+            LazyLock::new(|| generate_aperiodic_templates(30).into_boxed_slice()),
+            LazyLock::new(|| generate_aperiodic_templates(31).into_boxed_slice()),
         ];
 
-        // Compressed files are decompressed at run-time.
-        const COMPRESSED_TEMPLATE_FILES: [&[u8]; 4] = [
-            include_bytes!(concat!(
-                env!("CARGO_MANIFEST_DIR"),
-                "/templates/template18.xz"
-            )),
-            include_bytes!(concat!(
-                env!("CARGO_MANIFEST_DIR"),
-                "/templates/template19.xz"
-            )),
-            include_bytes!(concat!(
-                env!("CARGO_MANIFEST_DIR"),
-                "/templates/template20.xz"
-            )),
-            include_bytes!(concat!(
-                env!("CARGO_MANIFEST_DIR"),
-                "/templates/template21.xz"
-            )),
-        ];
-
-        // decompression and splitting is lazily done on first access
-        static DECOMPRESSED_TEMPLATE_FILES: [LazyLock<Box<[u8]>>; 4] = [
-            LazyLock::new(|| decompress_template_file(COMPRESSED_TEMPLATE_FILES[0])),
-            LazyLock::new(|| decompress_template_file(COMPRESSED_TEMPLATE_FILES[1])),
-            LazyLock::new(|| decompress_template_file(COMPRESSED_TEMPLATE_FILES[2])),
-            LazyLock::new(|| decompress_template_file(COMPRESSED_TEMPLATE_FILES[3])),
-        ];
-
-        // The split references are stored for reuse later.
-        // Again: LazyLock creation so that this is not done on startup.
-        static TEMPLATES: [LazyLock<Box<[usize]>>; 20] = [
-            LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[0], 2)),
-            LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[1], 3)),
-            LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[2], 4)),
-            LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[3], 5)),
-            LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[4], 6)),
-            LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[5], 7)),
-            LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[6], 8)),
-            LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[7], 9)),
-            LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[8], 10)),
-            LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[9], 11)),
-            LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[10], 12)),
-            LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[11], 13)),
-            LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[12], 14)),
-            LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[13], 15)),
-            LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[14], 16)),
-            LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[15], 17)),
-            LazyLock::new(|| split_template_file(DECOMPRESSED_TEMPLATE_FILES[0].as_ref(), 18)),
-            LazyLock::new(|| split_template_file(DECOMPRESSED_TEMPLATE_FILES[1].as_ref(), 19)),
-            LazyLock::new(|| split_template_file(DECOMPRESSED_TEMPLATE_FILES[2].as_ref(), 20)),
-            LazyLock::new(|| split_template_file(DECOMPRESSED_TEMPLATE_FILES[3].as_ref(), 21)),
-        ];
-
-        // this call decompresses, if necessary, then splits the file into the individual templates
-        // and saves the references.
-        let templates = TEMPLATES[template_len - 2].as_ref();
+        // this call loads or generates the templates, if necessary, and saves the references.
+        let templates = match template_len {
+            2..=21 => template_file(template_len),
+            _ => GENERATED_TEMPLATES[template_len - 22].as_ref(),
+        };
 
         Self {
             templates,
@@ -134,18 +108,234 @@ impl TemplateArg<'static> {
     }
 }
 
+/// The templates of the given length (2 <= `template_len` <= 21) from the template files, which
+/// are embedded into the program.
+#[cfg(not(feature = "external-templates"))]
+fn template_file(template_len: usize) -> &'static [usize] {
+    // the template files are embedded into the program.
+    const UNCOMPRESSED_TEMPLATE_FILES: [&[u8]; 16] = [
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template2")),
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template3")),
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template4")),
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template5")),
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template6")),
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template7")),
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template8")),
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template9")),
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template10")),
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template11")),
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template12")),
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template13")),
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template14")),
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template15")),
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template16")),
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/template17")),
+    ];
+
+    // Compressed files are decompressed at run-time.
+    const COMPRESSED_TEMPLATE_FILES: [&[u8]; 4] = [
+        include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/templates/template18.xz"
+        )),
+        include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/templates/template19.xz"
+        )),
+        include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/templates/template20.xz"
+        )),
+        include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/templates/template21.xz"
+        )),
+    ];
+
+    // decompression and splitting is lazily done on first access
+    static DECOMPRESSED_TEMPLATE_FILES: [LazyLock<Box<[u8]>>; 4] = [
+        LazyLock::new(|| decompress_embedded_template_file(COMPRESSED_TEMPLATE_FILES[0])),
+        LazyLock::new(|| decompress_embedded_template_file(COMPRESSED_TEMPLATE_FILES[1])),
+        LazyLock::new(|| decompress_embedded_template_file(COMPRESSED_TEMPLATE_FILES[2])),
+        LazyLock::new(|| decompress_embedded_template_file(COMPRESSED_TEMPLATE_FILES[3])),
+    ];
+
+    // The split references are stored for reuse later.
+    // Again: LazyLock creation so that this is not done on startup.
+    static TEMPLATES: [LazyLock<Box<[usize]>>; 20] = [
+        LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[0], 2)),
+        LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[1], 3)),
+        LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[2], 4)),
+        LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[3], 5)),
+        LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[4], 6)),
+        LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[5], 7)),
+        LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[6], 8)),
+        LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[7], 9)),
+        LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[8], 10)),
+        LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[9], 11)),
+        LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[10], 12)),
+        LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[11], 13)),
+        LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[12], 14)),
+        LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[13], 15)),
+        LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[14], 16)),
+        LazyLock::new(|| split_template_file(UNCOMPRESSED_TEMPLATE_FILES[15], 17)),
+        LazyLock::new(|| split_template_file(DECOMPRESSED_TEMPLATE_FILES[0].as_ref(), 18)),
+        LazyLock::new(|| split_template_file(DECOMPRESSED_TEMPLATE_FILES[1].as_ref(), 19)),
+        LazyLock::new(|| split_template_file(DECOMPRESSED_TEMPLATE_FILES[2].as_ref(), 20)),
+        LazyLock::new(|| split_template_file(DECOMPRESSED_TEMPLATE_FILES[3].as_ref(), 21)),
+    ];
+
+    // this call decompresses, if necessary, then splits the file into the individual templates
+    // and saves the references.
+    TEMPLATES[template_len - 2].as_ref()
+}
+
+/// Decompress a compressed template file embedded into the program.
+#[cfg(not(feature = "external-templates"))]
+fn decompress_embedded_template_file(compressed: &[u8]) -> Box<[u8]> {
+    // if decompression does not work, something went seriously wrong with the files
+    // embedded into the program.
+    decompress_template_file(compressed).unwrap()
+}
+
+/// The templates of the given length (2 <= `template_len` <= 21) from the template files in the
+/// directory set with [set_template_dir], see [load_template_file].
+#[cfg(feature = "external-templates")]
+fn template_file(template_len: usize) -> &'static [usize] {
+    // loading is lazily done on first access
+    static TEMPLATES: [LazyLock<Box<[usize]>>; 20] = [
+        LazyLock::new(|| load_template_file(2)),
+        LazyLock::new(|| load_template_file(3)),
+        LazyLock::new(|| load_template_file(4)),
+        LazyLock::new(|| load_template_file(5)),
+        LazyLock::new(|| load_template_file(6)),
+        LazyLock::new(|| load_template_file(7)),
+        LazyLock::new(|| load_template_file(8)),
+        LazyLock::new(|| load_template_file(9)),
+        LazyLock::new(|| load_template_file(10)),
+        LazyLock::new(|| load_template_file(11)),
+        LazyLock::new(|| load_template_file(12)),
+        LazyLock::new(|| load_template_file(13)),
+        LazyLock::new(|| load_template_file(14)),
+        LazyLock::new(|| load_template_file(15)),
+        LazyLock::new(|| load_template_file(16)),
+        LazyLock::new(|| load_template_file(17)),
+        LazyLock::new(|| load_template_file(18)),
+        LazyLock::new(|| load_template_file(19)),
+        LazyLock::new(|| load_template_file(20)),
+        LazyLock::new(|| load_template_file(21)),
+    ];
+
+    TEMPLATES[template_len - 2].as_ref()
+}
+
+/// The environment variable with the directory of the template files, used with the feature
+/// `external-templates` if no directory was set with [set_template_dir].
+#[cfg(feature = "external-templates")]
+pub const TEMPLATE_DIR_ENV_VAR: &str = "STS_TEMPLATE_DIR";
+
+/// The directory set with [set_template_dir].
+#[cfg(feature = "external-templates")]
+static TEMPLATE_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Sets the directory of the template files, only available with the feature `external-templates`.
+/// Takes precedence over the environment variable [TEMPLATE_DIR_ENV_VAR].
+///
+/// The directory must contain the files of `sts-lib/templates`, e.g. `template9` and
+/// `template21.xz`. Each file is loaded on the first use of its template length, so the directory
+/// must be set before - changing the directory later does not affect the loaded templates.
+#[cfg(feature = "external-templates")]
+pub fn set_template_dir(path: impl Into<PathBuf>) {
+    *TEMPLATE_DIR.lock().unwrap_or_else(PoisonError::into_inner) = Some(path.into());
+}
+
+/// Loads the template file of the given length from the template directory, see
+/// [set_template_dir].
+///
+/// If no directory is set, or the file is missing or invalid, the templates are generated with
+/// [generate_aperiodic_templates] instead, which gives the same templates.
+#[cfg(feature = "external-templates")]
+fn load_template_file(template_len: usize) -> Box<[usize]> {
+    let dir = TEMPLATE_DIR
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+        .or_else(|| std::env::var_os(TEMPLATE_DIR_ENV_VAR).map(PathBuf::from));
+
+    dir.and_then(|dir| read_template_file(&dir, template_len))
+        .unwrap_or_else(|| generate_aperiodic_templates(template_len).into_boxed_slice())
+}
+
+/// Reads the template file of the given length from the given directory, the file may be
+/// xz-compressed, with the extension `.xz`. Returns `None` if the file is missing or invalid.
+/// pub(crate) to allow for tests.
+#[cfg(feature = "external-templates")]
+pub(crate) fn read_template_file(dir: &Path, template_len: usize) -> Option<Box<[usize]>> {
+    let path = dir.join(format!("template{template_len}"));
+    let template_raw = match fs::read(&path) {
+        Ok(template_raw) => template_raw.into_boxed_slice(),
+        Err(_) => {
+            let compressed = fs::read(path.with_extension("xz")).ok()?;
+            decompress_template_file(&compressed).ok()?
+        }
+    };
+
+    let template_len_bytes = template_len.div_ceil(u8::BITS as usize);
+    if template_raw.is_empty() || template_raw.len() % template_len_bytes != 0 {
+        return None;
+    }
+
+    Some(split_template_file(&template_raw, template_len))
+}
+
 impl<'a> TemplateArg<'a> {
     /// Constructor for custom templates - template length must be valid
+    pub fn try_new_with_custom_templates(
+        templates: &'a [usize],
+        template_len: usize,
+    ) -> Result<Self, ArgError> {
+        check_template_len(template_len)?;
+        Ok(Self {
+            templates,
+            template_len,
+        })
+    }
+
+    /// Like [Self::try_new_with_custom_templates], but returns `None` instead of the reason.
+    #[deprecated(
+        note = "use `try_new_with_custom_templates`, which reports why the arguments are invalid"
+    )]
     pub fn new_with_custom_templates(templates: &'a [usize], template_len: usize) -> Option<Self> {
-        // Basic bounds check
-        if !(2..=21).contains(&template_len) {
-            None
-        } else {
-            Some(Self {
-                templates,
-                template_len,
-            })
-        }
+        Self::try_new_with_custom_templates(templates, template_len).ok()
+    }
+
+    /// Loads custom templates from the given file, see [parse_template_file] for the format.
+    ///
+    /// The loaded templates are never freed, so that the argument can be used in
+    /// [TestArgs](crate::TestArgs), which needs a `'static` lifetime. Each file should only be
+    /// loaded once. To manage the memory yourself, use [parse_template_file] and
+    /// [TemplateArg::try_new_with_custom_templates].
+    pub fn from_template_file(
+        path: impl AsRef<Path>,
+        template_len: usize,
+    ) -> Result<TemplateArg<'static>, TemplateFileError> {
+        let content = fs::read_to_string(path)?;
+        let templates = parse_template_file(&content, template_len)?;
+
+        Ok(TemplateArg {
+            templates: Box::leak(templates.into_boxed_slice()),
+            template_len,
+        })
+    }
+
+    /// The templates to search for.
+    pub fn templates(&self) -> &'a [usize] {
+        self.templates
+    }
+
+    /// The length of each template in bits.
+    pub fn template_len(&self) -> usize {
+        self.template_len
     }
 }
 
@@ -156,15 +346,179 @@ impl Default for TemplateArg<'static> {
     }
 }
 
+/// Checks the bounds of the template length: 2 <= `template_len` <= [MAX_TEMPLATE_LENGTH].
+fn check_template_len(template_len: usize) -> Result<(), ArgError> {
+    ArgError::check_range("template length", template_len, 2, MAX_TEMPLATE_LENGTH)
+}
+
+/// The errors that can occur when loading a custom template file, see [parse_template_file].
+#[derive(Debug, Error)]
+pub enum TemplateFileError {
+    /// The file could not be read.
+    #[error("Could not read the template file: {0}")]
+    Io(#[from] io::Error),
+    /// The template length is not between 2 and [MAX_TEMPLATE_LENGTH].
+    #[error("The template length must be between 2 and 31, is: {0}")]
+    InvalidTemplateLength(usize),
+    /// A line contains a character other than '0', '1' and whitespace.
+    #[error(
+        "Line {line}: invalid character '{character}', only '0', '1' and whitespace are allowed."
+    )]
+    InvalidCharacter { line: usize, character: char },
+    /// A template does not have the given template length.
+    #[error("Line {line}: the template has {actual} bits, expected {expected}.")]
+    WrongLength {
+        line: usize,
+        expected: usize,
+        actual: usize,
+    },
+    /// A template is not aperiodic: shifted by `shift` bits, it overlaps with itself.
+    #[error(
+        "Line {line}: the template is not aperiodic, it overlaps with itself when shifted by {shift} bits."
+    )]
+    Periodic { line: usize, shift: usize },
+    /// A template is given twice.
+    #[error("Line {line}: the template is a duplicate of the template in line {first_line}.")]
+    Duplicate { line: usize, first_line: usize },
+    /// The file does not contain any template.
+    #[error("The template file contains no templates.")]
+    Empty,
+}
+
+/// Parses the content of a custom template file: one template per line, each bit written as
+/// '0' or '1'. Whitespace between the bits is ignored, so that both `000000001` and the format of
+/// the template files of the NIST reference implementation, `0 0 0 0 0 0 0 0 1`, can be used.
+/// Empty lines and lines starting with '#' are skipped.
+///
+/// Each template must have exactly `template_len` bits (2 <= `template_len` <= 31) and must be
+/// aperiodic, i.e. it must not overlap with itself when shifted by less than its length, as
+/// required by the [Non-overlapping Template Matching Test](non_overlapping). Templates may not be
+/// given twice. Line numbers in the errors start at 1.
+///
+/// The templates are returned in the format used by [TemplateArg::try_new_with_custom_templates].
+pub fn parse_template_file(
+    content: &str,
+    template_len: usize,
+) -> Result<Vec<usize>, TemplateFileError> {
+    if !(2..=MAX_TEMPLATE_LENGTH).contains(&template_len) {
+        return Err(TemplateFileError::InvalidTemplateLength(template_len));
+    }
+
+    let mut templates = Vec::new();
+    // template -> line, to report duplicates
+    let mut lines = HashMap::new();
+
+    for (idx, text) in content.lines().enumerate() {
+        let line = idx + 1;
+        let text = text.trim();
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+
+        let mut template = 0_usize;
+        let mut bit_count = 0;
+        for character in text.chars().filter(|c| !c.is_whitespace()) {
+            let bit = match character {
+                '0' => 0,
+                '1' => 1,
+                _ => return Err(TemplateFileError::InvalidCharacter { line, character }),
+            };
+            bit_count += 1;
+            if bit_count <= template_len {
+                template = (template << 1) | bit;
+            }
+        }
+
+        if bit_count != template_len {
+            return Err(TemplateFileError::WrongLength {
+                line,
+                expected: template_len,
+                actual: bit_count,
+            });
+        }
+
+        // aperiodic: for each shift, the first bits differ from the last bits
+        if let Some(shift) = (1..template_len).find(|&shift| {
+            let mask = (1 << (template_len - shift)) - 1;
+            (template >> shift) == (template & mask)
+        }) {
+            return Err(TemplateFileError::Periodic { line, shift });
+        }
+
+        if let Some(&first_line) = lines.get(&template) {
+            return Err(TemplateFileError::Duplicate { line, first_line });
+        }
+        lines.insert(template, line);
+
+        // the bits of the templates start at the MSB
+        templates.push(template << (WORD_BITS - template_len));
+    }
+
+    if templates.is_empty() {
+        Err(TemplateFileError::Empty)
+    } else {
+        Ok(templates)
+    }
+}
+
+/// Generates all aperiodic templates of the given length `m` (2 <= `m` <= 31), in ascending order
+/// and in the format used by [TemplateArg::try_new_with_custom_templates]. A template is aperiodic if
+/// it does not overlap with itself when shifted by less than its length, i.e. no prefix of the
+/// template equals the suffix of the same length.
+///
+/// For `m` <= 21, the result is the same as the templates embedded into the library, which are
+/// taken from the NIST reference implementation. The count of templates grows with about
+/// `0.27 * 2^m`, so generating all templates of length 31 takes a few GiB of memory.
+///
+/// ## Panics
+///
+/// If `m` is not between 2 and [MAX_TEMPLATE_LENGTH].
+pub fn generate_aperiodic_templates(m: usize) -> Vec<usize> {
+    assert!(
+        (2..=MAX_TEMPLATE_LENGTH).contains(&m),
+        "m must be between 2 and 31"
+    );
+
+    // The shortest overlap of a template is at most half its length. So removing the middle bit
+    // of a template of odd length keeps all possible overlaps, and the template is aperiodic iff
+    // the shorter one is. For an even length 2n, the overlap of exactly n bits has to be checked
+    // additionally. Each template is built by inserting a middle bit into an aperiodic template
+    // that is 1 bit shorter, starting from both templates of length 1.
+    let mut templates = vec![0b0_usize, 0b1];
+    for len in 2..=m {
+        // the count of bits after the inserted bit
+        let low_len = len - 1 - (len - 1) / 2;
+        let low_mask = (1 << low_len) - 1;
+
+        templates = templates
+            .iter()
+            .flat_map(|&shorter| {
+                let high = (shorter >> low_len) << (low_len + 1);
+                let low = shorter & low_mask;
+                [high | low, high | (1 << low_len) | low]
+            })
+            .filter(|&template| {
+                let half = len / 2;
+                len % 2 == 1 || (template >> half) != (template & ((1 << half) - 1))
+            })
+            .collect();
+    }
+
+    templates.sort_unstable();
+    // the bits of the templates start at the MSB
+    templates
+        .into_iter()
+        .map(|template| template << (WORD_BITS - m))
+        .collect()
+}
+
 /// Decompress a compressed template file.
-fn decompress_template_file(compressed: &[u8]) -> Box<[u8]> {
+fn decompress_template_file(compressed: &[u8]) -> Result<Box<[u8]>, lzma_rs::error::Error> {
     // bufreader is necessary for xz_decompress
     let mut bufreader = BufReader::new(compressed);
     let mut decompressed = Vec::new();
-    // if decompression does not work, something went seriously wrong with the files
-    // embedded into the program.
-    lzma_rs::xz_decompress(&mut bufreader, &mut decompressed).unwrap();
-    decompressed.into_boxed_slice()
+    lzma_rs::xz_decompress(&mut bufreader, &mut decompressed)?;
+    Ok(decompressed.into_boxed_slice())
 }
 
 /// Split a (decompressed) template file.
@@ -192,34 +546,14 @@ fn split_template_file(template_raw: &[u8], template_len: usize) -> Box<[usize]>
             };
 
             let value = u32::from_be_bytes(arr);
-            // if usize is u64, we need to shift the remaining 32 bits
-            (value as usize) << (usize::BITS - u32::BITS)
+            // if a word has 64 bits, we need to shift the remaining 32 bits
+            (value as Word) << (WORD_BITS - u32::BITS as usize)
         })
         .collect::<Box<_>>()
 }
 
 /// Take the template length and create a bitmask to compare if the template matches
 #[inline]
-fn create_mask(template_bit_len: usize) -> usize {
-    ((1 << template_bit_len) - 1) << (usize::BITS as usize - template_bit_len)
-}
-
-/// For bit masks and templates: right shift a base template or mask (whose bits are starting at the MSB),
-/// if overflowing, a second value is returned.
-///
-/// `shift < usize::BITS` must uphold.
-#[inline]
-fn overflowing_right_shift(
-    value: usize,
-    template_bit_len: usize,
-    shift: usize,
-) -> (usize, Option<usize>) {
-    let shifted = value >> shift;
-
-    if (usize::BITS as usize) < template_bit_len + shift {
-        let overflow_shift = (usize::BITS as usize) - shift;
-        (shifted, Some(value << overflow_shift))
-    } else {
-        (shifted, None)
-    }
+fn create_mask(template_bit_len: usize) -> Word {
+    word::leading_mask(template_bit_len)
 }
@@ -5,12 +5,17 @@
 //!
 //! This test allows for parameters, see [NonOverlappingTemplateTestArgs].
 
+use std::collections::HashMap;
 use std::num::NonZero;
+use std::path::Path;
 
-use super::{create_mask, overflowing_right_shift, TemplateArg};
+use super::{create_mask, TemplateArg, TemplateFileError};
+use crate::bitvec::word::WORD_BITS;
 use crate::bitvec::BitVec;
-use crate::internals::{check_f64, checked_mul, igamc};
-use crate::{Error, TestResult};
+use crate::internals::{check_f64, checked_mul, igamc, trace_span};
+use crate::statistics::TestStatistics;
+use crate::test_runner::cancellation::CancelCheck;
+use crate::{ArgError, Error, ResultLabel, TestResult};
 use rayon::prelude::*;
 use sts_lib_derive::use_thread_pool;
 
@@ -43,15 +48,14 @@ pub struct NonOverlappingTemplateTestArgs<'a> {
 impl NonOverlappingTemplateTestArgs<'static> {
     /// Constructor with all arguments as normal values, evaluated at run time.
     /// For the meaning of the arguments, see [NonOverlappingTemplateTestArgs].
+    pub fn try_new(template_len: usize, count_blocks: usize) -> Result<Self, ArgError> {
+        Self::try_new_with_custom_template(TemplateArg::try_new(template_len)?, count_blocks)
+    }
+
+    /// Like [Self::try_new], but returns `None` instead of the reason.
+    #[deprecated(note = "use `try_new`, which reports why the arguments are invalid")]
     pub fn new(template_len: usize, count_blocks: usize) -> Option<Self> {
-        if (1..100).contains(&count_blocks) {
-            Some(Self {
-                templates: TemplateArg::new(template_len)?,
-                count_blocks,
-            })
-        } else {
-            None
-        }
+        Self::try_new(template_len, count_blocks).ok()
     }
 
     /// Constructor with all arguments as const generics, which are asserted at compile time.
@@ -67,21 +71,55 @@ impl NonOverlappingTemplateTestArgs<'static> {
             count_blocks: N,
         }
     }
+
+    /// Constructor with custom templates loaded from the given file, e.g. a restricted or extended
+    /// set of aperiodic templates, and the default block count [DEFAULT_BLOCK_COUNT]. For the
+    /// format of the file and the leaked memory, see [TemplateArg::from_template_file].
+    pub fn from_template_file(
+        path: impl AsRef<Path>,
+        template_len: usize,
+    ) -> Result<Self, TemplateFileError> {
+        Ok(Self {
+            templates: TemplateArg::from_template_file(path, template_len)?,
+            count_blocks: DEFAULT_BLOCK_COUNT,
+        })
+    }
 }
 
 impl<'a> NonOverlappingTemplateTestArgs<'a> {
+    /// Constructor with custom templates, e.g. from
+    /// [TemplateArg::try_new_with_custom_templates]. For the meaning of the arguments, see
+    /// [NonOverlappingTemplateTestArgs].
+    pub fn try_new_with_custom_template(
+        templates: TemplateArg<'a>,
+        count_blocks: usize,
+    ) -> Result<Self, ArgError> {
+        ArgError::check_range("block count", count_blocks, 1, 99)?;
+        Ok(Self {
+            templates,
+            count_blocks,
+        })
+    }
+
+    /// Like [Self::try_new_with_custom_template], but returns `None` instead of the reason.
+    #[deprecated(
+        note = "use `try_new_with_custom_template`, which reports why the arguments are invalid"
+    )]
     pub fn new_with_custom_template(
         templates: TemplateArg<'a>,
         count_blocks: usize,
     ) -> Option<Self> {
-        if (1..100).contains(&count_blocks) {
-            Some(Self {
-                templates,
-                count_blocks,
-            })
-        } else {
-            None
-        }
+        Self::try_new_with_custom_template(templates, count_blocks).ok()
+    }
+
+    /// The templates to search for, see [TemplateArg].
+    pub fn templates(&self) -> TemplateArg<'a> {
+        self.templates
+    }
+
+    /// The number of independent blocks to test in the sequence.
+    pub fn count_blocks(&self) -> usize {
+        self.count_blocks
     }
 }
 
@@ -100,6 +138,12 @@ pub fn non_overlapping_template_matching_test(
     data: &BitVec,
     test_arg: NonOverlappingTemplateTestArgs,
 ) -> Result<Vec<TestResult>, Error> {
+    trace_span!(
+        INFO,
+        "non_overlapping_template_matching_test",
+        len_bit = data.len_bit()
+    );
+
     // Step 0: calculate block length M
     let NonOverlappingTemplateTestArgs {
         templates,
@@ -116,9 +160,13 @@ pub fn non_overlapping_template_matching_test(
     }
 
     // Step 2: for each template B, calculate the number of times the template matches
-    let count_matches_per_chunk_per_template =
-        count_matches_per_chunk_per_template(count_blocks, block_length_bit, data, templates)
-            .collect::<Result<Box<_>, Error>>()?;
+    let count_matches_per_chunk_per_template = count_matches_per_chunk_per_template(
+        count_blocks,
+        block_length_bit,
+        data,
+        templates,
+        CancelCheck::current(),
+    )?;
 
     // Step 3: compute the theoretical mean and variance
     let power_2_template_len = f64::powi(2.0, template_len as i32);
@@ -146,91 +194,152 @@ pub fn non_overlapping_template_matching_test(
             let p_value = igamc((count_blocks as f64) / 2.0, chi / 2.0)?;
             check_f64(p_value)?;
 
-            Ok(TestResult::new(p_value))
+            Ok(TestResult::new(p_value)
+                .with_statistics(TestStatistics::NonOverlappingTemplateMatching {
+                    mean,
+                    variance,
+                    chi_square: chi,
+                })
+                .with_label(ResultLabel::Template {
+                    index: template_idx,
+                    template: templates.templates[template_idx],
+                    template_len,
+                }))
         })
         .collect::<Result<Vec<_>, Error>>()?;
 
     Ok(p_values)
 }
 
+/// Marks window values in [TemplateTable::template_indexes] that are no template.
+const NO_TEMPLATE: u32 = u32::MAX;
+
+/// The maximum template length for which [TemplateIndexes::Table] is used (16 MiB), longer
+/// templates use [TemplateIndexes::Map].
+pub(crate) const MAX_TABLE_TEMPLATE_LENGTH: usize = 22;
+
+/// For each possible window value (the *m* bits at a position in the data, as the lowest bits of
+/// the value), the index of the first template with this value.
+enum TemplateIndexes {
+    /// A lookup table with an entry for each window value, [NO_TEMPLATE] if there is no template.
+    Table(Box<[u32]>),
+    /// Only the window values with a template, for long templates, where the table would be too big.
+    Map(HashMap<usize, u32>),
+}
+
+impl TemplateIndexes {
+    /// The index of the first template with the given window value, or [NO_TEMPLATE].
+    #[inline]
+    fn get(&self, window: usize) -> u32 {
+        match self {
+            TemplateIndexes::Table(table) => table[window],
+            TemplateIndexes::Map(map) => map.get(&window).copied().unwrap_or(NO_TEMPLATE),
+        }
+    }
+}
+
+/// The templates as a lookup table, to match all templates in a single pass over the data.
+struct TemplateTable {
+    /// For each possible window value, the index of the first template with this value.
+    template_indexes: TemplateIndexes,
+    /// For each template, the index of the template whose count is used, which differs for
+    /// duplicate templates. Is `None` for templates that can never match.
+    count_indexes: Box<[Option<usize>]>,
+}
+
+impl TemplateTable {
+    fn new(templates: &[usize], template_len: usize) -> Self {
+        let values = templates.iter().map(|&template| {
+            // templates with bits outside the mask can never match
+            (template & !create_mask(template_len) == 0)
+                .then(|| template >> (WORD_BITS - template_len))
+        });
+
+        let (template_indexes, count_indexes) = if template_len <= MAX_TABLE_TEMPLATE_LENGTH {
+            let mut table = vec![NO_TEMPLATE; 1 << template_len].into_boxed_slice();
+            let count_indexes = values
+                .enumerate()
+                .map(|(idx, value)| {
+                    let first_idx = &mut table[value?];
+                    if *first_idx == NO_TEMPLATE {
+                        *first_idx = idx as u32;
+                    }
+                    Some(*first_idx as usize)
+                })
+                .collect();
+            (TemplateIndexes::Table(table), count_indexes)
+        } else {
+            let mut map = HashMap::with_capacity(templates.len());
+            let count_indexes = values
+                .enumerate()
+                .map(|(idx, value)| Some(*map.entry(value?).or_insert(idx as u32) as usize))
+                .collect();
+            (TemplateIndexes::Map(map), count_indexes)
+        };
+
+        Self {
+            template_indexes,
+            count_indexes,
+        }
+    }
+}
+
 /// Count the matches per chunk and template.
-fn count_matches_per_chunk_per_template<'a>(
+///
+/// All templates are matched in a single pass over each block: the *m*-bit window at each position
+/// is looked up in a [TemplateTable]. To not count overlapping matches, each template stores the
+/// first position it may match again. This gives the same counts as matching each template
+/// separately and skipping *m* bits after each match, but the effort does not depend on the count
+/// of templates. The blocks are handled in parallel.
+/// pub(crate) to allow for tests.
+pub(crate) fn count_matches_per_chunk_per_template(
     block_count: usize,
     block_length_bit: usize,
-    data: &'a BitVec,
-    templates: TemplateArg<'a>,
-) -> impl Iterator<Item = Result<Box<[usize]>, Error>> + 'a {
+    data: &BitVec,
+    templates: TemplateArg,
+    cancel: CancelCheck,
+) -> Result<Vec<Box<[usize]>>, Error> {
     let TemplateArg {
         templates,
         template_len,
     } = templates;
 
+    let table = TemplateTable::new(templates, template_len);
+
     // For each block, calculate the times each template matches.
-    (0..block_count).map(move |block_idx| {
-        // calculate the start byte and the bit position in the start byte for this block
-        let total_start_bit = checked_mul!(block_idx, block_length_bit)?;
-
-        let start_bit_idx = total_start_bit % (usize::BITS as usize);
-
-        // calculate the max shifts
-        let max_shifts = block_length_bit - (template_len - 1);
-
-        // create the basic bitwise mask (allows only the bits that are the template)
-        let base_mask = create_mask(template_len);
-
-        // for each template, try to match
-        let matches_per_template = templates
-            .par_iter()
-            .map(|&base_template| {
-                // absolute current shift - but still based on word bit count
-                let mut absolute_shift = start_bit_idx;
-
-                // go over the current chunk
-                let mut count_matches: usize = 0;
-
-                let mut i = 0;
-                while i < max_shifts {
-                    // the working bitwise mask
-                    // This mask is bitwise shifted to the right position in the current stream.
-                    let (mask1, mask2) =
-                        overflowing_right_shift(base_mask, template_len, absolute_shift);
-
-                    // the working template
-                    // This template is bitwise shifted to the right position in the current stream.
-                    let (template1, template2) =
-                        overflowing_right_shift(base_template, template_len, absolute_shift);
-
-                    // a match is:
-                    // for every bit, apply bitwise AND with the current mask (which is shifted bitwise
-                    // for new position) - now only the bits the template tries to match, are there.
-                    let current_word_idx = (total_start_bit + i) / (usize::BITS as usize);
-
-                    let mut matched = data.words[current_word_idx] & mask1 == template1;
-                    // if the first word matched and the data for a second word is there
-                    if let (true, Some(mask2), Some(template2)) = (matched, mask2, template2) {
-                        matched = data.words[current_word_idx + 1] & mask2 == template2
-                    }
+    (0..block_count)
+        .into_par_iter()
+        .map(|block_idx| {
+            cancel.check()?;
+
+            // the windows starting in this block, which end in the block as well
+            let start_bit = checked_mul!(block_idx, block_length_bit)?;
+            let end_bit = start_bit + block_length_bit - (template_len - 1);
+            let windows = data.windows(template_len, 1).range(start_bit..end_bit);
+
+            let mut count_matches = vec![0_usize; templates.len()];
+            // for each template, the first position in the block it may match again
+            let mut next_positions = vec![0_usize; templates.len()];
+
+            windows.enumerate().for_each(|(position, window)| {
+                let template_idx = table.template_indexes.get(window);
+                if template_idx == NO_TEMPLATE {
+                    return;
+                }
 
-                    // set the next shift necessary (if the template matched, the shift is for
-                    // the template length), increment the counter if matched.
-                    let relative_shift = if matched {
-                        // There are not enough matches possible to warrant checked arithmetic
-                        count_matches += 1;
-                        template_len
-                    } else {
-                        1
-                    };
-
-                    // Calculate the absolute shift
-                    absolute_shift = (absolute_shift + relative_shift) % (usize::BITS as usize);
-
-                    // increment i - max_shifts cannot be big enough to warrant checked i
-                    i += relative_shift;
+                let template_idx = template_idx as usize;
+                if next_positions[template_idx] <= position {
+                    // There are not enough matches possible to warrant checked arithmetic
+                    count_matches[template_idx] += 1;
+                    next_positions[template_idx] = position + template_len;
                 }
+            });
 
-                count_matches
-            })
-            .collect::<Box<_>>();
-        Ok(matches_per_template)
-    })
+            Ok(table
+                .count_indexes
+                .iter()
+                .map(|&idx| idx.map_or(0, |idx| count_matches[idx]))
+                .collect())
+        })
+        .collect()
 }
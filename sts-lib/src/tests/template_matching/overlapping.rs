@@ -15,17 +15,24 @@
 //! * The (not working) fixed values according to Hamano and Kaneko only work for very specific cases.
 //!
 //! The PI values from NIST can still be used for testing purposes by using
-//! [OverlappingTemplateTestArgs::new_nist_behaviour].
+//! [OverlappingTemplateTestArgs::try_new_nist_behaviour].
+//!
+//! By default, the template consists of ones only. To study specific failure patterns, any other
+//! template can be used, see [OverlappingTemplateTestArgs::try_new_with_custom_template]. The
+//! formulas of Hamano and Kaneko only apply to the all-ones template, the pi values of custom
+//! templates are calculated exactly with the distribution of the matches in a block, see
+//! [calculate_custom_template_pis].
 //!
 //! This test needs arguments, see [OverlappingTemplateTestArgs].
 
+use crate::bitvec::word::{self, WORD_BITS};
 use crate::bitvec::BitVec;
-use crate::internals::{checked_mul, igamc};
-use crate::tests::template_matching::{create_mask, overflowing_right_shift};
-use crate::{Error, TestResult};
-use bigdecimal::num_bigint::BigInt;
-use bigdecimal::num_traits::ToPrimitive;
-use bigdecimal::BigDecimal;
+use crate::internals::{checked_mul, igamc, trace_span};
+use crate::tests::template_matching::fixed_point::Fixed;
+use crate::tests::template_matching::create_mask;
+use crate::statistics::TestStatistics;
+use crate::test_runner::cancellation::CancelCheck;
+use crate::{ArgError, Error, TestResult};
 use rayon::prelude::*;
 use std::collections::HashMap;
 use std::num::NonZero;
@@ -42,6 +49,14 @@ pub const MIN_INPUT_LENGTH: NonZero<usize> = const {
     }
 };
 
+/// The input length, in bits, recommended by NIST, for which the default arguments were chosen.
+pub const RECOMMENDED_INPUT_LENGTH: NonZero<usize> = const {
+    match NonZero::new(1_000_000) {
+        Some(v) => v,
+        None => panic!("Literal should be non-zero!"),
+    }
+};
+
 /// The default length of each block M, in bits.
 pub const DEFAULT_BLOCK_LENGTH: usize = 1032;
 
@@ -61,9 +76,12 @@ pub const DEFAULT_TEMPLATE_LENGTH: usize = 9;
 /// These bounds are checked by all creation functions.
 /// A default variant is available with [OverlappingTemplateTestArgs::default()].
 ///
-/// To replicate the exact NIST behaviour, use [OverlappingTemplateTestArgs::new_nist_behaviour]
+/// To replicate the exact NIST behaviour, use [OverlappingTemplateTestArgs::try_new_nist_behaviour].
+/// To search for a template other than all ones, use
+/// [OverlappingTemplateTestArgs::try_new_with_custom_template].
 #[derive(Copy, Clone, Debug)]
 pub struct OverlappingTemplateTestArgs {
+    custom_template: Option<usize>,
     template_length: usize,
     block_length: usize,
     freedom: usize,
@@ -72,41 +90,107 @@ pub struct OverlappingTemplateTestArgs {
 
 impl OverlappingTemplateTestArgs {
     /// Create new arguments. For the meanings and allowed value ranges, see [OverlappingTemplateTestArgs].
+    pub fn try_new(
+        template_length: usize,
+        block_length: usize,
+        freedom: usize,
+    ) -> Result<Self, ArgError> {
+        ArgError::check_range("template length", template_length, 2, 21)?;
+        Ok(Self {
+            custom_template: None,
+            template_length,
+            block_length,
+            freedom,
+            inaccurate_nist_calculation: false,
+        })
+    }
+
+    /// Like [Self::try_new], but returns `None` instead of the reason.
+    #[deprecated(note = "use `try_new`, which reports why the arguments are invalid")]
     pub fn new(template_length: usize, block_length: usize, freedom: usize) -> Option<Self> {
-        if (2..=21).contains(&template_length) {
-            Some(Self {
-                template_length,
-                block_length,
-                freedom,
-                inaccurate_nist_calculation: false,
-            })
-        } else {
-            None
-        }
+        Self::try_new(template_length, block_length, freedom).ok()
+    }
+
+    /// Create new arguments that search for the given template instead of all ones.
+    ///
+    /// The bits of the template start at the MSB, like the templates of
+    /// [TemplateArg::try_new_with_custom_templates](super::TemplateArg::try_new_with_custom_templates),
+    /// bits after the template length are ignored. Unlike in the non-overlapping test, the template
+    /// does not need to be aperiodic. For the other arguments, see [OverlappingTemplateTestArgs].
+    pub fn try_new_with_custom_template(
+        template: usize,
+        template_length: usize,
+        block_length: usize,
+        freedom: usize,
+    ) -> Result<Self, ArgError> {
+        let mut arg = Self::try_new(template_length, block_length, freedom)?;
+        arg.custom_template = Some(template & create_mask(template_length));
+        Ok(arg)
     }
 
     /// Force the inaccurate behaviour of the reference implementation.
     /// Template length may only be 9 or 10 here.
     ///
     /// The chosen variables are only accurate for bit lengths of 10^6.
-    pub fn new_nist_behaviour(template_length: usize) -> Option<Self> {
+    pub fn try_new_nist_behaviour(template_length: usize) -> Result<Self, ArgError> {
         if template_length == 9 || template_length == 10 {
-            Some(Self {
+            Ok(Self {
+                custom_template: None,
                 template_length,
                 block_length: 1032,
                 freedom: 6,
                 inaccurate_nist_calculation: true,
             })
         } else {
-            None
+            Err(ArgError::NotAllowed {
+                name: "template length",
+                value: template_length,
+                allowed: &[9, 10],
+            })
         }
     }
+
+    /// Like [Self::try_new_nist_behaviour], but returns `None` instead of the reason.
+    #[deprecated(
+        note = "use `try_new_nist_behaviour`, which reports why the arguments are invalid"
+    )]
+    pub fn new_nist_behaviour(template_length: usize) -> Option<Self> {
+        Self::try_new_nist_behaviour(template_length).ok()
+    }
+
+    /// The custom template, with the bits starting at the MSB, see
+    /// [Self::try_new_with_custom_template]. `None` if the template consists of ones only.
+    pub fn custom_template(&self) -> Option<usize> {
+        self.custom_template
+    }
+
+    /// The template length in bits.
+    pub fn template_length(&self) -> usize {
+        self.template_length
+    }
+
+    /// The length of each block in bits.
+    pub fn block_length(&self) -> usize {
+        self.block_length
+    }
+
+    /// The degrees of freedom.
+    pub fn freedom(&self) -> usize {
+        self.freedom
+    }
+
+    /// If the inaccurate behaviour of the reference implementation is used, see
+    /// [Self::try_new_nist_behaviour].
+    pub fn nist_behaviour(&self) -> bool {
+        self.inaccurate_nist_calculation
+    }
 }
 
 impl Default for OverlappingTemplateTestArgs {
     /// Default values, see the module constants
     fn default() -> Self {
         Self {
+            custom_template: None,
             template_length: DEFAULT_TEMPLATE_LENGTH,
             block_length: DEFAULT_BLOCK_LENGTH,
             freedom: DEFAULT_FREEDOM,
@@ -124,15 +208,24 @@ impl Default for OverlappingTemplateTestArgs {
 ///
 /// # About performance
 ///
-/// This test is quite slow in debug mode when using the more precise pi values, taking several
-/// seconds - it runs good when using release mode.
-/// For better performance, values that are calculated once are cached.
+/// The more precise pi values take time quadratic in the block length, e.g. 3 ms in release mode
+/// and 0.1 s in debug mode for a block length of 1032 - except for the default arguments, whose
+/// values are built in.
+/// For better performance, values that are calculated once are cached. With the feature
+/// `pi-cache`, the cache is kept on disk across processes, see `template_matching::pi_cache`.
 #[use_thread_pool]
 pub fn overlapping_template_matching_test(
     data: &BitVec,
     arg: OverlappingTemplateTestArgs,
 ) -> Result<TestResult, Error> {
+    trace_span!(
+        INFO,
+        "overlapping_template_matching_test",
+        len_bit = data.len_bit()
+    );
+
     let OverlappingTemplateTestArgs {
+        custom_template,
         template_length,
         block_length,
         freedom,
@@ -148,7 +241,9 @@ pub fn overlapping_template_matching_test(
     let block_count = data.len_bit() / block_length;
 
     // calculate the pi values
-    let pi_values = if inaccurate_nist_calculation && freedom == 6 {
+    let pi_values = if let Some(template) = custom_template {
+        calculate_custom_template_pis(template, block_length, template_length, freedom)
+    } else if inaccurate_nist_calculation && freedom == 6 {
         calculate_nist_pis(block_length, template_length)
     } else {
         // accurate calculation
@@ -163,20 +258,30 @@ pub fn overlapping_template_matching_test(
         vec.resize_with(freedom, || AtomicUsize::new(0));
         vec.into_boxed_slice()
     };
-    count_matches_per_chunk(block_count, DEFAULT_BLOCK_LENGTH, data, template_length)
-        .try_for_each(|matches_per_chunk| {
-            // short circuit; there is only one template
-            let matches = matches_per_chunk?;
-
-            // element to increment
-            let el_idx = matches.clamp(0, freedom - 1);
-            let prev = occurrences[el_idx].fetch_add(1, Ordering::Relaxed);
-            if prev == usize::MAX {
-                Err(Error::Overflow(format!("{prev} (occurrences) + 1")))
-            } else {
-                Ok(())
-            }
-        })?;
+    let cancel = CancelCheck::current();
+    let template = custom_template.unwrap_or_else(|| create_mask(template_length));
+    count_matches_per_chunk(
+        block_count,
+        DEFAULT_BLOCK_LENGTH,
+        data,
+        template,
+        template_length,
+    )
+    .try_for_each(|matches_per_chunk| {
+        cancel.check()?;
+
+        // short circuit; there is only one template
+        let matches = matches_per_chunk?;
+
+        // element to increment
+        let el_idx = matches.clamp(0, freedom - 1);
+        let prev = occurrences[el_idx].fetch_add(1, Ordering::Relaxed);
+        if prev == usize::MAX {
+            Err(Error::Overflow(format!("{prev} (occurrences) + 1")))
+        } else {
+            Ok(())
+        }
+    })?;
 
     // Step 3 makes no sense without the formulae for pi
 
@@ -194,7 +299,12 @@ pub fn overlapping_template_matching_test(
 
     // Step 5: compute p-value = igamc(5/2, chi^2 / 2).
     let p_value = igamc(5.0 / 2.0, chi / 2.0)?;
-    Ok(TestResult::new(p_value))
+    Ok(
+        TestResult::new(p_value).with_statistics(TestStatistics::OverlappingTemplateMatching {
+            block_count,
+            chi_square: chi,
+        }),
+    )
 }
 
 /// Calculate the PI values according to the NIST reference implementation.
@@ -223,6 +333,10 @@ fn calculate_nist_pis(block_length: usize, template_length: usize) -> Box<[f64]>
 /// Type for a pi caching hashmap
 type CacheHashMap = HashMap<(usize, usize, usize), Box<[f64]>>;
 
+/// The maximum relative error of the pi values calculated with [f64], see [hamano_kaneko_pis_f64].
+/// This is far more precise than the P-value needs.
+const PI_TOLERANCE: f64 = 1e-10;
+
 /// Calculate the PI values according to Hamano & Kaneko (as it should be according to the paper).
 ///
 /// Returns an array of count *freedom* with the pi values.
@@ -234,23 +348,18 @@ type CacheHashMap = HashMap<(usize, usize, usize), Box<[f64]>>;
 ///
 /// # About performance
 ///
-/// This method is quite slow in debug mode, taking several seconds - it runs okay (0.25s) when using
-/// release mode. For better performance when running multiple tests, once calculated results are
-/// cached.
+/// The values are calculated with [f64] arithmetic and a bound of the rounding errors, see
+/// [hamano_kaneko_pis_f64]. Only if the bound exceeds [PI_TOLERANCE], e.g. for very long blocks,
+/// they are calculated precisely with fixed-point numbers, see [hamano_kaneko_pis_fixed]. The time
+/// is quadratic in the block length: in release mode, about 3 ms for a block length of 1032 with
+/// [f64] and 25 ms with fixed-point numbers on a single thread, debug mode is 25 to 50 times
+/// slower. For better performance when running multiple tests, once calculated results are cached.
+/// With the feature `pi-cache`, they are also cached on disk, see [pi_cache](super::pi_cache).
 pub(crate) fn calculate_hamano_kaneko_pis(
     block_length: usize,
     template_length: usize,
     freedom: usize,
 ) -> Box<[f64]> {
-    // index transformation helper for the column indexes - rust does not support negative indexes.
-    #[inline]
-    fn idx(i: isize) -> usize {
-        (i + 1) as usize
-    }
-
-    // The type to use in the calculations - may be swapped out if e.g. f128 becomes stable in Rust.
-    type Decimal = BigDecimal;
-
     // static cache for already calculated values. Always contains the values for the default
     // argument for better performance.
     static CACHE: LazyLock<Mutex<CacheHashMap>> = LazyLock::new(|| {
@@ -279,143 +388,415 @@ pub(crate) fn calculate_hamano_kaneko_pis(
         }
     }
 
+    // check if calculated by an earlier process
+    #[cfg(feature = "pi-cache")]
+    if let Some(values) = super::pi_cache::load(block_length, template_length, freedom) {
+        let mut cache = CACHE.lock().unwrap();
+        cache.insert((block_length, template_length, freedom), values.clone());
+        return values;
+    }
+
+    trace_span!(
+        DEBUG,
+        "hamano_kaneko_pis",
+        block_length,
+        template_length,
+        freedom
+    );
+
+    let pis = hamano_kaneko_pis_f64(block_length, template_length, freedom, PI_TOLERANCE)
+        .unwrap_or_else(|| hamano_kaneko_pis_fixed(block_length, template_length, freedom));
+
+    // insert values into cache
+    {
+        let mut cache = CACHE.lock().unwrap();
+        cache.insert((block_length, template_length, freedom), pis.clone());
+    }
+    #[cfg(feature = "pi-cache")]
+    super::pi_cache::store(block_length, template_length, freedom, &pis);
+
+    pis
+}
+
+/// Index transformation helper for the column indexes of the tables of Hamano & Kaneko - rust
+/// does not support negative indexes.
+#[inline]
+fn idx(i: isize) -> usize {
+    (i + 1) as usize
+}
+
+/// Calculate the PI values according to Hamano & Kaneko with 256-bit fixed-point numbers, see
+/// [Fixed]. The values of each row of the tables are calculated in parallel.
+/// pub(crate) to allow for tests.
+pub(crate) fn hamano_kaneko_pis_fixed(
+    block_length: usize,
+    template_length: usize,
+    freedom: usize,
+) -> Box<[f64]> {
     // internally, this uses the identifiers used in the paper
     let m = template_length as isize;
     let n = block_length as isize;
 
-    // allocate the necessary tables
-    let mut tables: Vec<Vec<Decimal>> = vec![Vec::with_capacity(block_length + 2); freedom - 1];
-
-    // Step 1: compute T_0(n) according to formula (2) - use iterators to allow the Rust compiler
-    // to optimize as much as possible.
+    // The values T_a(n) are counts of sequences with up to 2^n, they are stored as
+    // t_a(n) = T_a(n) / 2^(n + 1) instead, which is between 0 and 1. This way, fixed-point numbers
+    // with a fixed precision are sufficient for any block length, and each value of a row only
+    // depends on the row before and the first row, so each row is calculated in parallel.
+    // Dividing by 2^(n + 1) is the same as dividing the sums by 2^(m + 1) and the first term of
+    // formula (4) by 2.
+    let sum_divisor = (m + 1) as u32;
+
+    // Step 1: compute t_0(n) according to formula (2). Each value depends on the previous ones,
+    // but there are only n of them.
+    let mut row_0 = Vec::with_capacity(block_length + 2);
     (-1..(n + 1)).for_each(|n| {
-        if n == -1 || n == 0 {
-            tables[0].push(Decimal::from(1u16));
+        if n == -1 {
+            row_0.push(Fixed::ONE);
+        } else if n == 0 {
+            row_0.push(Fixed::ONE.div_pow2(1));
         } else if n < m {
-            let value = 2 * &tables[0][idx(n - 1)];
-            tables[0].push(value);
+            let value = row_0[idx(n - 1)];
+            row_0.push(value);
         } else {
-            let value = 2 * &tables[0][idx(n - 1)] - &tables[0][idx(n - m - 1)];
-            tables[0].push(value);
+            let value = row_0[idx(n - 1)] - row_0[idx(n - m - 1)].div_pow2(sum_divisor);
+            row_0.push(value);
         }
     });
 
-    // Step 2: calculate T_1(n) according to formula (3)
-    (-1..(n + 1)).for_each(|n| {
-        if n < m {
-            tables[1].push(Decimal::from(0u16));
-        } else if n == m {
-            tables[1].push(Decimal::from(1u16));
-        } else if n == m + 1 {
-            tables[1].push(Decimal::from(2u16));
-        } else {
-            let sum = (-1..(n - m))
-                .map(|j| &tables[0][idx(j)] * &tables[0][idx(n - m - 2 - j)])
-                .sum::<Decimal>();
-            tables[1].push(sum);
-        }
-    });
+    // Step 2: calculate t_1(n) according to formula (3)
+    let row_1 = (-1..(n + 1))
+        .into_par_iter()
+        .map(|n| {
+            if n < m {
+                Fixed::ZERO
+            } else if n == m || n == m + 1 {
+                // T_1(m) = 1 and T_1(m + 1) = 2
+                Fixed::ONE.div_pow2(sum_divisor)
+            } else {
+                (-1..(n - m))
+                    .map(|j| row_0[idx(j)] * row_0[idx(n - m - 2 - j)])
+                    .sum::<Fixed>()
+                    .div_pow2(sum_divisor)
+            }
+        })
+        .collect::<Vec<_>>();
 
-    // Step 3: for each row with index 'a' left, calculate T_a(n) according to formula (4)
+    let mut tables = Vec::with_capacity(freedom - 1);
+    tables.push(row_0);
+    tables.push(row_1);
+
+    // Step 3: for each row with index 'a' left, calculate t_a(n) according to formula (4)
     (2..(freedom - 1)).for_each(|a| {
         // 'a' is the row index.
-        // Add a start element with value 0 to each row: this is necessary because else we would
+        let previous = &tables[a - 1];
+        let row_0 = &tables[0];
+
+        // The first element for index '-1' is 0: this is necessary because else we would
         // try to access the non-existent value at index '-2', we can avoid that by starting
         // with index 0 and setting the first value to 0 (which is the correct one)
-        tables[a].push(Decimal::from(0u16));
-
-        (0..(n + 1)).for_each(|n| {
-            let part_1 = &tables[a - 1][idx(n - 1)];
-            let sum = (-1..(n - 2 * m - (a as isize) + 1))
-                .map(|j| &tables[0][idx(j)] * &tables[a - 1][idx(n - m - 2 - j)])
-                .sum::<Decimal>();
-            let total = part_1 + sum;
-            tables[a].push(total);
-        })
+        let row = (-1..(n + 1))
+            .into_par_iter()
+            .map(|n| {
+                if n == -1 {
+                    return Fixed::ZERO;
+                }
+
+                let part_1 = previous[idx(n - 1)].div_pow2(1);
+                let sum = (-1..(n - 2 * m - (a as isize) + 1))
+                    .map(|j| row_0[idx(j)] * previous[idx(n - m - 2 - j)])
+                    .sum::<Fixed>();
+                part_1 + sum.div_pow2(sum_divisor)
+            })
+            .collect::<Vec<_>>();
+        tables.push(row);
     });
 
-    // Step 4: calculate each pi value using formula (1) and calculate the last value
-    // create the pi vector, the last element is the sum of all pi elements
-    let mut last_pi = Decimal::from(1u8);
+    // Step 4: calculate each pi value using formula (1), pi_a = T_a(n) / 2^n = 2 * t_a(n), and
+    // calculate the last value as the rest
+    let mut last_pi = Fixed::ONE;
     let mut pis = tables
         .iter()
         .map(|row| {
-            let divisor = BigInt::from(2u16).pow(block_length as u32).into();
-            let pi = &row[block_length + 1] / &divisor;
+            let pi = row[block_length + 1] + row[block_length + 1];
 
-            last_pi -= &pi;
-            pi.to_f64().unwrap()
+            last_pi = last_pi - pi;
+            pi.to_f64()
         })
         .collect::<Vec<_>>();
-    pis.push(last_pi.to_f64().unwrap());
+    pis.push(last_pi.to_f64());
 
-    let pis = pis.into_boxed_slice();
-    
-    // insert values into cache
-    {
-        let mut cache = CACHE.lock().unwrap();
-        cache.insert((block_length, template_length, freedom), pis.clone());
-    }
+    pis.into_boxed_slice()
+}
 
-    pis
+/// The unit roundoff of [f64]: the relative error of each rounded operation is at most this value.
+const UNIT_ROUNDOFF: f64 = f64::EPSILON / 2.0;
+
+/// The smallest positive (subnormal) [f64]: the absolute error of each operation whose result
+/// underflows is at most this value.
+const UNDERFLOW_ERROR: f64 = f64::MIN_POSITIVE * f64::EPSILON;
+
+/// An [f64] value with a bound of its absolute error, used by [hamano_kaneko_pis_f64].
+#[derive(Copy, Clone, Debug)]
+struct Bounded {
+    value: f64,
+    error: f64,
 }
 
-/// Count the matches per chunk
-fn count_matches_per_chunk(
-    block_count: usize,
-    block_length_bit: usize,
-    data: &BitVec,
-    template_len: usize,
-) -> impl ParallelIterator<Item = Result<usize, Error>> + '_ {
-    // For each block, calculate the times each template matches.
-    (0..block_count).into_par_iter().map(move |block_idx| {
-        // calculate the start byte and the bit position in the start byte for this block
-        let total_start_bit = checked_mul!(block_idx, block_length_bit)?;
+impl Bounded {
+    /// A value without error.
+    const fn exact(value: f64) -> Self {
+        Self { value, error: 0.0 }
+    }
 
-        // calculate the max shifts
-        let max_shifts = block_length_bit - (template_len - 1);
+    /// Adds the values. Both values must be non-negative.
+    fn add(self, rhs: Self) -> Self {
+        let value = self.value + rhs.value;
+        Self {
+            value,
+            error: self.error + rhs.error + UNIT_ROUNDOFF * value,
+        }
+    }
 
-        // create the base template stats
-        let base_template = create_mask(template_len);
+    /// Divides the value by 2^`exponent`, which is exact except for underflows.
+    fn div_pow2(self, exponent: i32) -> Self {
+        let factor = f64::powi(2.0, -exponent);
+        Self {
+            value: self.value * factor,
+            error: self.error * factor + UNDERFLOW_ERROR,
+        }
+    }
 
-        // absolute current shift - but still based on word bit count
-        let mut absolute_shift = total_start_bit % (usize::BITS as usize);
+    /// The sum of the products of the pairs of values. All values must be non-negative.
+    ///
+    /// The rounding errors of a sum of L products with recursive summation are at most
+    /// L * u times the exact sum, see Higham, Accuracy and Stability of Numerical Algorithms,
+    /// section 3.1.
+    fn sum_of_products(pairs: impl Iterator<Item = (Self, Self)>) -> Self {
+        let mut count = 0.0;
+        let mut value = 0.0;
+        let mut propagated_error = 0.0;
+        pairs.for_each(|(left, right)| {
+            count += 1.0;
+            value += left.value * right.value;
+            propagated_error +=
+                left.value * right.error + left.error * right.value + left.error * right.error;
+        });
 
-        // go over the current chunk
-        let mut count_matches: usize = 0;
+        Self {
+            value,
+            error: propagated_error + count * (UNIT_ROUNDOFF * value + 2.0 * UNDERFLOW_ERROR),
+        }
+    }
+}
 
-        let mut i = 0;
-        while i < max_shifts {
-            // the working template
-            // This template is bitwise shifted to the right position in the current stream.
-            let (template1, template2) =
-                overflowing_right_shift(base_template, template_len, absolute_shift);
+/// Calculate the same PI values as [hamano_kaneko_pis_fixed] with [f64] arithmetic, which is
+/// about 8 times faster, and a rigorous bound of the rounding errors. Returns `None` if the error
+/// bound of any pi value exceeds `tolerance` relative to the value, e.g. for very long blocks or
+/// pi values too small for [f64].
+/// pub(crate) to allow for tests.
+///
+/// Each value of the tables carries a bound of its absolute error: the errors of the operands are
+/// propagated, and each rounded operation adds the unit roundoff times its result, or the smallest
+/// subnormal number if the result underflows. All values of the tables are non-negative, so this
+/// is an upper bound of the error. The bounds are calculated with rounding as well, which is
+/// covered by checking twice the bound.
+pub(crate) fn hamano_kaneko_pis_f64(
+    block_length: usize,
+    template_length: usize,
+    freedom: usize,
+    tolerance: f64,
+) -> Option<Box<[f64]>> {
+    // the same tables and steps as in hamano_kaneko_pis_fixed
+    let m = template_length as isize;
+    let n = block_length as isize;
+    let sum_divisor = (m + 1) as i32;
 
-            // a match is:
-            // for every bit, apply bitwise AND with the current mask (which is shifted bitwise
-            // for new position) - now only the bits the template tries to match, are there.
-            let current_word_idx = (total_start_bit + i) / (usize::BITS as usize);
+    // Step 1: t_0(n) according to formula (2). The subtraction of formula (2) would let the error
+    // bound grow exponentially, so the equivalent sum T_0(n) = T_0(n - 1) + ... + T_0(n - m) is
+    // used, i.e. t_0(n) = t_0(n - 1) / 2 + ... + t_0(n - m) / 2^m, evaluated with Horner's method.
+    let mut row_0: Vec<Bounded> = Vec::with_capacity(block_length + 2);
+    (-1..(n + 1)).for_each(|n| {
+        let value = if n == -1 {
+            Bounded::exact(1.0)
+        } else if n == 0 {
+            Bounded::exact(0.5)
+        } else if n < m {
+            row_0[idx(n - 1)]
+        } else {
+            ((n - m + 1)..n)
+                .fold(row_0[idx(n - m)], |sum, i| {
+                    row_0[idx(i)].add(sum.div_pow2(1))
+                })
+                .div_pow2(1)
+        };
+        row_0.push(value);
+    });
 
-            let mut matched = data.words[current_word_idx] & template1 == template1;
-            // if the first word matched and the data for a second word is there
-            if let (true, Some(template2)) = (matched, template2) {
-                matched = data.words[current_word_idx + 1] & template2 == template2
+    // Step 2: t_1(n) according to formula (3)
+    let row_1 = (-1..(n + 1))
+        .into_par_iter()
+        .map(|n| {
+            if n < m {
+                Bounded::exact(0.0)
+            } else if n == m || n == m + 1 {
+                Bounded::exact(1.0).div_pow2(sum_divisor)
+            } else {
+                Bounded::sum_of_products(
+                    (-1..(n - m)).map(|j| (row_0[idx(j)], row_0[idx(n - m - 2 - j)])),
+                )
+                .div_pow2(sum_divisor)
             }
+        })
+        .collect::<Vec<_>>();
 
-            // set the next shift necessary (if the template matched, the shift is for
-            // the template length), increment the counter if matched.
-            if matched {
-                // There are not enough matches possible to warrant checked arithmetic
-                count_matches += 1;
-            }
+    let mut tables = Vec::with_capacity(freedom - 1);
+    tables.push(row_0);
+    tables.push(row_1);
+
+    // Step 3: t_a(n) according to formula (4)
+    (2..(freedom - 1)).for_each(|a| {
+        let previous = &tables[a - 1];
+        let row_0 = &tables[0];
+
+        let row = (-1..(n + 1))
+            .into_par_iter()
+            .map(|n| {
+                if n == -1 {
+                    return Bounded::exact(0.0);
+                }
+
+                let part_1 = previous[idx(n - 1)].div_pow2(1);
+                let sum = Bounded::sum_of_products(
+                    (-1..(n - 2 * m - (a as isize) + 1))
+                        .map(|j| (row_0[idx(j)], previous[idx(n - m - 2 - j)])),
+                )
+                .div_pow2(sum_divisor);
+                part_1.add(sum)
+            })
+            .collect::<Vec<_>>();
+        tables.push(row);
+    });
 
-            // Calculate the next mask and template.
-            absolute_shift = (absolute_shift + 1) % (usize::BITS as usize);
+    // Step 4: pi_a = 2 * t_a(n), which is exact, and the last value as the rest
+    let mut last_pi = Bounded::exact(1.0);
+    let mut pis = tables
+        .iter()
+        .map(|row| {
+            let pi = row[block_length + 1];
+            let pi = Bounded {
+                value: 2.0 * pi.value,
+                error: 2.0 * pi.error,
+            };
+
+            let value = last_pi.value - pi.value;
+            last_pi = Bounded {
+                value,
+                error: last_pi.error + pi.error + UNIT_ROUNDOFF * f64::abs(value),
+            };
+            pi
+        })
+        .collect::<Vec<_>>();
+    pis.push(last_pi);
+
+    pis.iter()
+        .all(|pi| 2.0 * pi.error <= tolerance * pi.value)
+        .then(|| pis.iter().map(|pi| pi.value).collect())
+}
+
+/// Calculate the PI values for a custom template: the probability of each count of (overlapping)
+/// matches of the template in a block of random bits. The last value is the probability of
+/// *freedom* - 1 or more matches.
+///
+/// The count of matches is tracked with the states of an automaton that searches for the
+/// template: state *s* means that the last *s* bits are the first *s* bits of the template. The
+/// distribution over all states and counts of matches is updated with each bit of the block, which
+/// gives the exact probabilities for any template. For the all-ones template, the values are the
+/// same as with [calculate_hamano_kaneko_pis].
+///
+/// Returns an array of count *freedom* with the pi values.
+pub(crate) fn calculate_custom_template_pis(
+    template: usize,
+    block_length: usize,
+    template_length: usize,
+    freedom: usize,
+) -> Box<[f64]> {
+    trace_span!(
+        DEBUG,
+        "custom_template_pis",
+        block_length,
+        template_length,
+        freedom
+    );
+
+    let bits = (0..template_length)
+        .map(|i| word::get_bit(template, i))
+        .collect::<Vec<_>>();
+
+    // the next state for each state and bit: the longest prefix of the template that is a suffix
+    // of the matched bits followed by the new bit. After a full match, the search continues with
+    // the overlap of the template with itself.
+    let transitions = (0..=template_length)
+        .map(|state| {
+            [false, true].map(|bit| {
+                // the last matched bits with the new bit, at most the template length
+                let mut matched = bits[..state].to_vec();
+                matched.push(bit);
+                let matched = &matched[matched.len().saturating_sub(template_length)..];
+
+                (0..=matched.len())
+                    .rev()
+                    .find(|&len| matched[matched.len() - len..] == bits[..len])
+                    .unwrap_or(0)
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // probabilities[state][matches], the last column stands for freedom - 1 or more matches
+    let mut probabilities = vec![vec![0.0; freedom]; template_length + 1];
+    probabilities[0][0] = 1.0;
 
-            // increment i - max_shifts cannot be big enough to warrant checked i
-            i += 1;
+    for _ in 0..block_length {
+        let mut next = vec![vec![0.0; freedom]; template_length + 1];
+
+        for (state, row) in probabilities.iter().enumerate() {
+            for next_state in transitions[state] {
+                let found = usize::from(next_state == template_length);
+
+                for (matches, &probability) in row.iter().enumerate() {
+                    let next_matches = (matches + found).min(freedom - 1);
+                    next[next_state][next_matches] += probability / 2.0;
+                }
+            }
         }
 
-        Ok(count_matches)
+        probabilities = next;
+    }
+
+    (0..freedom)
+        .map(|matches| probabilities.iter().map(|row| row[matches]).sum())
+        .collect()
+}
+
+/// Count the matches per chunk
+fn count_matches_per_chunk<'a>(
+    block_count: usize,
+    block_length_bit: usize,
+    data: &'a BitVec<'a>,
+    template: usize,
+    template_len: usize,
+) -> impl ParallelIterator<Item = Result<usize, Error>> + 'a {
+    // the template is stored starting at the MSB, the windows in the lowest bits
+    let template = template >> (WORD_BITS - template_len);
+
+    // For each block, calculate the times each template matches.
+    (0..block_count).into_par_iter().map(move |block_idx| {
+        let total_start_bit = checked_mul!(block_idx, block_length_bit)?;
+
+        // the windows starting in this block, which end in the block as well
+        let max_shifts = block_length_bit - (template_len - 1);
+        let windows = data
+            .windows(template_len, 1)
+            .range(total_start_bit..total_start_bit + max_shifts);
+
+        Ok(windows.filter(|&window| window == template).count())
     })
 }
@@ -0,0 +1,198 @@
+//! The on-disk cache of the pi values of the [Overlapping Template Matching Test](super::overlapping),
+//! only available with the feature `pi-cache`.
+//!
+//! The calculation of the accurate pi values according to Hamano and Kaneko takes time quadratic in
+//! the block length, e.g. seconds for long blocks or in debug mode. With this feature, the
+//! calculated values are saved as files in the cache directory, see [cache_dir], and are loaded by
+//! later processes instead of being calculated again. The cache can be filled in advance with
+//! [precompute_pis].
+//!
+//! The test itself ignores all errors of the cache: a missing or invalid file is calculated again,
+//! and a file that cannot be written is skipped.
+
+use crate::tests::template_matching::overlapping::{
+    calculate_hamano_kaneko_pis, OverlappingTemplateTestArgs,
+};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use sts_lib_derive::use_thread_pool;
+use thiserror::Error;
+
+/// The environment variable with the cache directory, used if no directory was set with
+/// [set_pi_cache_dir].
+pub const PI_CACHE_DIR_ENV_VAR: &str = "STS_PI_CACHE_DIR";
+
+/// The directory set with [set_pi_cache_dir].
+static PI_CACHE_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Sets the cache directory. Takes precedence over the environment variable
+/// [PI_CACHE_DIR_ENV_VAR] and the cache directory of the platform, see [cache_dir].
+pub fn set_pi_cache_dir(path: impl Into<PathBuf>) {
+    *PI_CACHE_DIR.lock().unwrap_or_else(PoisonError::into_inner) = Some(path.into());
+}
+
+/// The cache directory: the directory set with [set_pi_cache_dir], else the environment variable
+/// [PI_CACHE_DIR_ENV_VAR], else the directory `nist-sts` in the cache directory of the platform:
+/// `$XDG_CACHE_HOME` or `~/.cache` on Linux, `~/Library/Caches` on macOS and `%LOCALAPPDATA%` on
+/// Windows. `None` if none of them is known.
+pub fn cache_dir() -> Option<PathBuf> {
+    PI_CACHE_DIR
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+        .or_else(|| std::env::var_os(PI_CACHE_DIR_ENV_VAR).map(PathBuf::from))
+        .or_else(|| platform_cache_dir().map(|dir| dir.join("nist-sts")))
+}
+
+/// The errors of [precompute_pis].
+#[derive(Debug, Error)]
+pub enum PiCacheError {
+    /// No cache directory is known, see [cache_dir].
+    #[error("No cache directory is set and the cache directory of the platform is unknown.")]
+    NoCacheDir,
+    /// The pi values cannot be calculated for the arguments.
+    #[error("Invalid Parameter: {0}")]
+    InvalidParameter(String),
+    /// The file could not be written.
+    #[error("Could not write the pi cache file: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Calculates the pi values for the given arguments and saves them to the cache directory, see
+/// [cache_dir]. Returns `true` if the values were calculated, and `false` if they were already
+/// cached or the arguments do not use the values of Hamano and Kaneko, i.e. with a
+/// [custom template](OverlappingTemplateTestArgs::try_new_with_custom_template) or the
+/// [NIST behaviour](OverlappingTemplateTestArgs::try_new_nist_behaviour).
+///
+/// Like the test, this takes time quadratic in the block length, see
+/// [overlapping_template_matching_test](super::overlapping::overlapping_template_matching_test).
+#[use_thread_pool]
+pub fn precompute_pis(arg: OverlappingTemplateTestArgs) -> Result<bool, PiCacheError> {
+    if arg.custom_template().is_some() || arg.nist_behaviour() {
+        return Ok(false);
+    }
+
+    let (block_length, template_length, freedom) =
+        (arg.block_length(), arg.template_length(), arg.freedom());
+    if block_length < template_length {
+        return Err(PiCacheError::InvalidParameter(format!(
+            "the block length {block_length} is smaller than the template length {template_length}"
+        )));
+    }
+    if freedom < 3 {
+        return Err(PiCacheError::InvalidParameter(format!(
+            "the degrees of freedom must be at least 3, is: {freedom}"
+        )));
+    }
+
+    let dir = cache_dir().ok_or(PiCacheError::NoCacheDir)?;
+    if read_pi_file(&dir, block_length, template_length, freedom).is_some() {
+        return Ok(false);
+    }
+
+    let pis = calculate_hamano_kaneko_pis(block_length, template_length, freedom);
+    write_pi_file(&dir, block_length, template_length, freedom, &pis)?;
+    Ok(true)
+}
+
+/// Loads the pi values from the cache directory, `None` if they are not cached.
+pub(crate) fn load(
+    block_length: usize,
+    template_length: usize,
+    freedom: usize,
+) -> Option<Box<[f64]>> {
+    read_pi_file(&cache_dir()?, block_length, template_length, freedom)
+}
+
+/// Saves the pi values to the cache directory. Errors are ignored, the values are calculated
+/// again if they are needed later.
+pub(crate) fn store(block_length: usize, template_length: usize, freedom: usize, pis: &[f64]) {
+    if let Some(dir) = cache_dir() {
+        let _ = write_pi_file(&dir, block_length, template_length, freedom, pis);
+    }
+}
+
+/// Reads the pi values from their file in the given directory. Returns `None` if the file is
+/// missing or invalid. pub(crate) to allow for tests.
+pub(crate) fn read_pi_file(
+    dir: &Path,
+    block_length: usize,
+    template_length: usize,
+    freedom: usize,
+) -> Option<Box<[f64]>> {
+    let content =
+        fs::read_to_string(file_path(dir, block_length, template_length, freedom)).ok()?;
+
+    let pis = content
+        .lines()
+        .map(|line| {
+            line.trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|pi| (0.0..=1.0).contains(pi))
+        })
+        .collect::<Option<Box<[f64]>>>()?;
+
+    (pis.len() == freedom).then_some(pis)
+}
+
+/// Writes the pi values to their file in the given directory, one value per line. The file is
+/// written under a temporary name first, so that other processes never read a partial file.
+/// pub(crate) to allow for tests.
+pub(crate) fn write_pi_file(
+    dir: &Path,
+    block_length: usize,
+    template_length: usize,
+    freedom: usize,
+    pis: &[f64],
+) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+
+    // the shortest representation that parses to the same value
+    let content = pis.iter().map(|pi| format!("{pi:?}\n")).collect::<String>();
+    let path = file_path(dir, block_length, template_length, freedom);
+    let temp_path = path.with_extension(format!("tmp{}", std::process::id()));
+
+    fs::write(&temp_path, content)?;
+    fs::rename(&temp_path, &path).inspect_err(|_| {
+        let _ = fs::remove_file(&temp_path);
+    })
+}
+
+/// The path of the file with the pi values for the given arguments.
+fn file_path(dir: &Path, block_length: usize, template_length: usize, freedom: usize) -> PathBuf {
+    dir.join(format!(
+        "hamano-kaneko-{block_length}-{template_length}-{freedom}.txt"
+    ))
+}
+
+/// The cache directory of the platform on Windows.
+#[cfg(windows)]
+fn platform_cache_dir() -> Option<PathBuf> {
+    std::env::var_os("LOCALAPPDATA").map(PathBuf::from)
+}
+
+/// The cache directory of the platform on macOS.
+#[cfg(target_os = "macos")]
+fn platform_cache_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| Path::new(&home).join("Library/Caches"))
+}
+
+/// The cache directory of the platform on other Unix systems, according to the XDG Base Directory
+/// Specification.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn platform_cache_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        // relative paths are invalid and must be ignored
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+}
+
+/// There is no known cache directory on other platforms.
+#[cfg(not(any(windows, unix)))]
+fn platform_cache_dir() -> Option<PathBuf> {
+    None
+}
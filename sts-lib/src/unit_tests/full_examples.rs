@@ -4,14 +4,22 @@
 
 use super::{assert_f64_eq, round, TEST_FILE_PATH};
 use crate::bitvec::BitVec;
+use crate::internals::igamc;
 use crate::test_runner;
+use crate::test_runner::MultiSequenceRunner;
 use crate::tests::approximate_entropy::ApproximateEntropyTestArg;
+use crate::tests::autocorrelation::AutocorrelationTestArg;
+use crate::tests::binary_matrix_rank::BinaryMatrixRankTestArg;
 use crate::tests::frequency_block::FrequencyBlockTestArg;
 use crate::tests::linear_complexity::LinearComplexityTestArg;
+use crate::tests::longest_run_of_ones::LongestRunOfOnesTestArg;
+use crate::tests::random_excursions::RandomExcursionsTestArg;
+use crate::tests::random_excursions_variant::RandomExcursionsVariantTestArg;
 use crate::tests::serial::SerialTestArg;
+use crate::tests::spectral_dft::{PeakThreshold, SpectralDftTestArg};
 use crate::tests::template_matching::non_overlapping::NonOverlappingTemplateTestArgs;
 use crate::tests::template_matching::overlapping::OverlappingTemplateTestArgs;
-use crate::{Test, TestArgs};
+use crate::{Test, TestArgs, DEFAULT_THRESHOLD};
 use std::collections::HashMap;
 use std::fs;
 use std::num::NonZero;
@@ -21,11 +29,18 @@ use std::sync::LazyLock;
 /// Test args. These are used by all tests in this module.
 static TEST_ARGS: LazyLock<TestArgs> = LazyLock::new(|| TestArgs {
     frequency_block: FrequencyBlockTestArg::Manual(NonZero::new(128).unwrap()),
+    longest_run_of_ones: LongestRunOfOnesTestArg::ChooseAutomatically,
+    binary_matrix_rank: BinaryMatrixRankTestArg::try_new(32, 32).unwrap(),
+    spectral_dft: SpectralDftTestArg::new(PeakThreshold::KimUmeno),
     non_overlapping_template: NonOverlappingTemplateTestArgs::new_const::<9, 8>(),
-    overlapping_template: OverlappingTemplateTestArgs::new_nist_behaviour(9).unwrap(),
+    overlapping_template: OverlappingTemplateTestArgs::try_new_nist_behaviour(9).unwrap(),
     linear_complexity: LinearComplexityTestArg::ManualBlockLength(NonZero::new(500).unwrap()),
-    serial: SerialTestArg::new(16).unwrap(),
-    approximate_entropy: ApproximateEntropyTestArg::new(10).unwrap(),
+    serial: SerialTestArg::try_new(16).unwrap(),
+    approximate_entropy: ApproximateEntropyTestArg::try_new(10).unwrap(),
+    random_excursions: RandomExcursionsTestArg::default(),
+    random_excursions_variant: RandomExcursionsVariantTestArg::default(),
+    autocorrelation: AutocorrelationTestArg::default(),
+    timeout: None,
 });
 
 #[test]
@@ -40,6 +55,10 @@ fn pi_1e6() {
         (Test::CumulativeSums, vec![(0, 0.628308), (1, 0.663369)]),
         (Test::Serial, vec![(0, 0.143005)]),
         (Test::RandomExcursionsVariant, vec![(8, 0.760966)]),
+        // not part of SP 800-22r1a, no reference values
+        (Test::LempelZiv, vec![]),
+        // not part of SP 800-22r1a, no reference values
+        (Test::Autocorrelation, vec![]),
         (Test::NonOverlappingTemplateMatching, vec![(0, 0.165757)]),
         (Test::MaurersUniversalStatistical, vec![(0, 0.669012)]),
         (Test::BinaryMatrixRank, vec![(0, 0.083553)]),
@@ -59,6 +78,10 @@ fn e_1e6() {
         (Test::Runs, vec![(0, 0.561917)]),
         (Test::Frequency, vec![(0, 0.953749)]),
         (Test::RandomExcursionsVariant, vec![(8, 0.826009)]),
+        // not part of SP 800-22r1a, no reference values
+        (Test::LempelZiv, vec![]),
+        // not part of SP 800-22r1a, no reference values
+        (Test::Autocorrelation, vec![]),
         (Test::FrequencyWithinABlock, vec![(0, 0.211072)]),
         (Test::LongestRunOfOnes, vec![(0, 0.718366)]),
         (Test::OverlappingTemplateMatching, vec![(0, 0.110434)]),
@@ -98,6 +121,10 @@ fn sha1_1e6() {
         // returns 0.00 in release config - this bound check is turned off when testing, so this makes
         // no sense.
         (Test::RandomExcursionsVariant, vec![]),
+        // not part of SP 800-22r1a, no reference values
+        (Test::LempelZiv, vec![]),
+        // not part of SP 800-22r1a, no reference values
+        (Test::Autocorrelation, vec![]),
         (Test::Serial, vec![(0, 0.760793)]),
     ]
     .into();
@@ -110,6 +137,10 @@ fn sha1_1e6() {
 fn sqrt2_1e6() {
     let expected = [
         (Test::RandomExcursionsVariant, vec![(8, 0.566118)]),
+        // not part of SP 800-22r1a, no reference values
+        (Test::LempelZiv, vec![]),
+        // not part of SP 800-22r1a, no reference values
+        (Test::Autocorrelation, vec![]),
         (Test::LinearComplexity, vec![(0, 0.321866)]),
         (Test::NonOverlappingTemplateMatching, vec![(0, 0.569461)]),
         (Test::RandomExcursions, vec![(4, 0.216235)]),
@@ -149,6 +180,10 @@ fn sqrt3_1e6() {
         (Test::Runs, vec![(0, 0.261123)]),
         (Test::MaurersUniversalStatistical, vec![(0, 0.165981)]),
         (Test::RandomExcursionsVariant, vec![(8, 0.155066)]),
+        // not part of SP 800-22r1a, no reference values
+        (Test::LempelZiv, vec![]),
+        // not part of SP 800-22r1a, no reference values
+        (Test::Autocorrelation, vec![]),
     ]
     .into();
 
@@ -156,6 +191,33 @@ fn sqrt3_1e6() {
     common_parts(test_file, expected);
 }
 
+/// Test the final analysis over all test files, with the P-values of the frequency test.
+#[test]
+fn multi_sequence_frequency() {
+    let mut runner = MultiSequenceRunner::new([Test::Frequency].into_iter(), *TEST_ARGS).unwrap();
+
+    let sequences = ["e", "pi", "sha1", "sqrt2", "sqrt3"].map(|name| {
+        let test_file = Path::new(TEST_FILE_PATH).join(format!("{name}.1e6.bin"));
+        BitVec::from(fs::read(test_file).unwrap())
+    });
+    runner.run_sequences(&sequences);
+
+    let analysis = runner.final_analysis(DEFAULT_THRESHOLD).unwrap();
+    assert_eq!(analysis.len(), 1);
+
+    let analysis = &analysis[0];
+    assert_eq!(analysis.sample_size(), 5);
+    assert_eq!(analysis.passed_count(), 5);
+    assert!(analysis.proportion_passed());
+    // 0.953749, 0.578211, 0.604458, 0.811881, 0.610051
+    assert_eq!(analysis.histogram(), [0, 0, 0, 0, 0, 1, 2, 0, 1, 1]);
+    // chi-square = 9.0
+    assert_f64_eq!(
+        analysis.uniformity_p_value().unwrap(),
+        igamc(4.5, 4.5).unwrap()
+    );
+}
+
 /// Common parts of all tests
 fn common_parts(test_file: PathBuf, expected: HashMap<Test, Vec<(usize, f64)>>) {
     let data = fs::read(test_file).unwrap();
@@ -1,9 +1,29 @@
 //! All unit tests
-
-use crate::bitvec::BitVec;
+//!
+//! Without the feature `std`, only the tests of the code available without it run, e.g. with
+//! `cargo test -p sts-lib --no-default-features --lib`.
+
+use crate::bitvec::builder::{BitVecBuilder, ReaderFormat};
+use crate::bitvec::windows::Windows;
+use crate::bitvec::{BitOrder, BitVec};
+#[cfg(feature = "std")]
+use crate::test_runner::cancellation::{with_check, CancelCheck};
+#[cfg(feature = "std")]
+use crate::test_runner::{run_tests_with_cancel, CancellationToken};
+#[cfg(feature = "std")]
 use crate::tests::linear_complexity::berlekamp_massey;
-use crate::tests::template_matching::overlapping::calculate_hamano_kaneko_pis;
-
+#[cfg(feature = "std")]
+use crate::tests::template_matching::overlapping::{
+    calculate_custom_template_pis, calculate_hamano_kaneko_pis,
+};
+#[cfg(feature = "std")]
+use crate::TestArgs;
+use crate::{Error, Test};
+use std::collections::HashMap;
+#[cfg(not(feature = "std"))]
+use std::vec::Vec;
+
+#[cfg(feature = "std")]
 mod full_examples;
 mod nist_text_examples;
 
@@ -37,6 +57,17 @@ fn round(value: f64, digits: u8) -> f64 {
     (value * t).round() / t
 }
 
+/// A generator of deterministic pseudo-random bits (xorshift), only to get some test inputs.
+fn xorshift_bits() -> impl FnMut() -> bool {
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state & 1 == 1
+    }
+}
+
 /// Test the creation of a BitVec from a bool vec
 #[test]
 fn test_bitvec_from_bool() {
@@ -245,8 +276,546 @@ fn test_bitvec_crop_less_than_1_byte() {
     assert_eq!(bitvec.bit_count_last_word, length as u8);
 }
 
+/// Test the incremental creation of a BitVec over multiple words.
+#[test]
+fn test_bitvec_builder() {
+    let input_data = (0..150).map(|i| i % 3 == 0).collect::<Vec<_>>();
+
+    let mut builder = BitVecBuilder::new();
+    input_data[..7].iter().for_each(|&bit| builder.push_bit(bit));
+    builder.extend_from_ascii_lossy(b"0 0 1 0 0 1");
+    input_data[13..].iter().for_each(|&bit| builder.push_bit(bit));
+    let bitvec = builder.build();
+
+    let expected = BitVec::from(input_data);
+    assert_eq!(bitvec.len_bit(), expected.len_bit());
+    assert_eq!(bitvec.words, expected.words);
+    assert_eq!(bitvec.bit_count_last_word, expected.bit_count_last_word);
+}
+
+/// Test appending bits to an existing BitVec, with and without a partial last word.
+#[test]
+fn test_bitvec_push_and_extend() {
+    let input_data = (0..300).map(|i| i % 5 == 0 || i % 7 == 0).collect::<Vec<_>>();
+
+    let mut bitvec = BitVec::new();
+    input_data[..3].iter().for_each(|&bit| bitvec.push_bit(bit));
+    bitvec.extend_from_bits(&input_data[3..130]);
+    bitvec.push_bit(input_data[130]);
+    bitvec.extend_from_bits(&input_data[131..]);
+
+    let expected = BitVec::from(input_data);
+    assert_eq!(bitvec.len_bit(), expected.len_bit());
+    assert_eq!(bitvec.words, expected.words);
+    assert_eq!(bitvec.bit_count_last_word, expected.bit_count_last_word);
+}
+
+/// Test converting a BitVec to bytes, with and without a partial last word and remainder byte.
+#[test]
+fn test_bitvec_to_bytes() {
+    let bytes = (0..20).map(|i| (i * 37) as u8).collect::<Vec<_>>();
+    let bitvec = BitVec::from(bytes.as_slice());
+
+    for len_bit in [0, 8, 13, 64, 100, 128, 131, 160] {
+        let (full, rest) = bitvec.slice(0..len_bit).to_bytes();
+        assert_eq!(full, &bytes[..len_bit / 8], "length {len_bit}");
+
+        let expected_rest =
+            (len_bit % 8 != 0).then(|| bytes[len_bit / 8] & !(u8::MAX >> (len_bit % 8)));
+        assert_eq!(rest, expected_rest, "length {len_bit}");
+    }
+}
+
+/// Test concatenating BitVecs, with and without a partial last word on both sides.
+#[test]
+fn test_bitvec_concat() {
+    let input_data = (0..400).map(|i| i % 3 == 0 || i % 11 == 0).collect::<Vec<_>>();
+
+    for split in [0, 1, 64, 100, 128, 333, 400] {
+        let mut bitvec = BitVec::from(&input_data[..split]);
+        bitvec.concat(&BitVec::from(&input_data[split..]));
+
+        let expected = BitVec::from(input_data.as_slice());
+        assert_eq!(bitvec.len_bit(), expected.len_bit(), "split {split}");
+        assert_eq!(bitvec.words, expected.words, "split {split}");
+        assert_eq!(
+            bitvec.bit_count_last_word, expected.bit_count_last_word,
+            "split {split}"
+        );
+    }
+}
+
+/// Test slicing BitVecs and BitSlices, with ranges inside, across and at the edges of words.
+#[test]
+fn test_bitvec_slice() {
+    let input_data = (0..400).map(|i| i % 3 == 0 || i % 11 == 0).collect::<Vec<_>>();
+    let bitvec = BitVec::from(input_data.as_slice());
+
+    let ranges = [(0, 0), (0, 400), (1, 63), (3, 200), (64, 128), (100, 333), (399, 400)];
+    for (start, end) in ranges {
+        let slice = bitvec.slice(start..end);
+
+        let expected = BitVec::from(&input_data[start..end]);
+        assert_eq!(slice.len_bit(), expected.len_bit(), "range {start}..{end}");
+        assert_eq!(slice.words, expected.words, "range {start}..{end}");
+        assert_eq!(
+            slice.bit_count_last_word, expected.bit_count_last_word,
+            "range {start}..{end}"
+        );
+    }
+
+    // slice of a slice
+    let slice = bitvec.bit_slice(37..300).slice(30..101);
+    assert_eq!(slice.len_bit(), 71);
+    assert_eq!(
+        slice.to_bit_vec().words,
+        BitVec::from(&input_data[67..138]).words
+    );
+}
+
+/// Test that slicing panics if the range is out of bounds.
+#[test]
+#[should_panic(expected = "out of range")]
+fn test_bitvec_slice_out_of_range() {
+    let bitvec = BitVec::from_ascii_str("0110").unwrap();
+    _ = bitvec.bit_slice(1..5);
+}
+
+/// Test bit access and counting, including ranges inside, across and at the edges of words.
+#[test]
+fn test_bitvec_bit_queries() {
+    let input_data = (0..400).map(|i| i % 3 == 0 || i % 11 == 0).collect::<Vec<_>>();
+    let bitvec = BitVec::from(input_data.as_slice());
+
+    for (idx, &bit) in input_data.iter().enumerate() {
+        assert_eq!(bitvec.get(idx), Some(bit), "index {idx}");
+    }
+    assert_eq!(bitvec.get(400), None);
+
+    let expected_ones = input_data.iter().filter(|&&bit| bit).count();
+    assert_eq!(bitvec.count_ones(), expected_ones);
+    assert_eq!(bitvec.count_zeros(), 400 - expected_ones);
+
+    let ranges = [(0, 0), (5, 5), (0, 400), (1, 63), (3, 200), (64, 128), (100, 333), (399, 400)];
+    for (start, end) in ranges {
+        let expected = input_data[start..end].iter().filter(|&&bit| bit).count();
+        assert_eq!(
+            bitvec.hamming_weight_in(start..end),
+            expected,
+            "range {start}..{end}"
+        );
+    }
+
+    assert_eq!(BitVec::new().count_ones(), 0);
+    assert_eq!(BitVec::new().get(0), None);
+}
+
+/// Test iterating over the single bits of a BitVec, sequential and parallel.
+#[cfg(feature = "std")]
+#[test]
+fn test_bitvec_iter_bits() {
+    use rayon::prelude::*;
+
+    let input_data = (0..400).map(|i| i % 3 == 0 || i % 11 == 0).collect::<Vec<_>>();
+    let bitvec = BitVec::from(input_data.as_slice());
+
+    assert_eq!(bitvec.iter_bits().len(), 400);
+    assert_eq!(bitvec.iter_bits().collect::<Vec<_>>(), input_data);
+    assert_eq!(bitvec.par_iter_bits().collect::<Vec<_>>(), input_data);
+    assert!(bitvec
+        .iter_bits()
+        .rev()
+        .eq(input_data.iter().rev().copied()));
+    assert_eq!(bitvec.iter_bits().nth(130), Some(input_data[130]));
+    assert_eq!(
+        (&bitvec).into_iter().filter(|&bit| bit).count(),
+        bitvec.count_ones()
+    );
+    assert_eq!(bitvec.iter_bits().nth(400), None);
+    assert_eq!(BitVec::new().iter_bits().next(), None);
+}
+
+/// Test the windows of bits of a BitVec against the single bits, for all window lengths, different
+/// steps and with and without a partial last word, sequential and parallel.
+#[cfg(feature = "std")]
+#[test]
+fn test_bitvec_windows() {
+    use rayon::prelude::*;
+
+    let word_bits = usize::BITS as usize;
+    let value = |bits: &[bool]| {
+        bits.iter()
+            .fold(0, |value, &bit| (value << 1) | bit as usize)
+    };
+    // collects with Iterator::fold, which is implemented separately from Iterator::next
+    let fold = |windows: Windows| {
+        windows.fold(Vec::new(), |mut values, value| {
+            values.push(value);
+            values
+        })
+    };
+
+    for len_bit in [0, 1, 63, 64, 65, 130, 400] {
+        let input_data = (0..len_bit)
+            .map(|i| i % 3 == 0 || i % 11 == 0 || i % 17 == 5)
+            .collect::<Vec<_>>();
+        let bitvec = BitVec::from(input_data.as_slice());
+
+        for len in 1..=word_bits {
+            for idx in 0..=len_bit {
+                let expected = input_data.get(idx..(idx + len)).map(value);
+                assert_eq!(bitvec.window(idx, len), expected, "{len_bit}: {idx}, {len}");
+            }
+
+            for step in [1, 2, 3, len, 100] {
+                let expected = input_data
+                    .windows(len)
+                    .step_by(step)
+                    .map(value)
+                    .collect::<Vec<_>>();
+                let windows = bitvec.windows(len, step);
+
+                assert_eq!(windows.len(), expected.len(), "{len_bit}: {len}, {step}");
+                assert_eq!(windows.clone().collect::<Vec<_>>(), expected);
+                assert!(windows.clone().rev().eq(expected.iter().rev().copied()));
+                assert_eq!(fold(windows.clone()), expected);
+                assert_eq!(windows.clone().nth(5), expected.get(5).copied());
+                assert_eq!(
+                    windows.clone().nth_back(5),
+                    expected.iter().rev().nth(5).copied()
+                );
+
+                let ranges = [0..expected.len() / 2, expected.len().min(3)..expected.len()];
+                for range in ranges {
+                    let part = windows.clone().range(range.clone());
+                    assert_eq!(part.clone().collect::<Vec<_>>(), expected[range.clone()]);
+                    assert_eq!(fold(part), expected[range]);
+                }
+                assert_eq!(bitvec.par_windows(len, step).collect::<Vec<_>>(), expected);
+            }
+        }
+
+        assert_eq!(bitvec.window(0, 0), None);
+        assert_eq!(bitvec.window(0, word_bits + 1), None);
+        assert_eq!(bitvec.window(usize::MAX, 2), None);
+    }
+}
+
+/// Test the bitwise combinators XOR, NOT, interleave and the von Neumann extractor against a
+/// bit-by-bit calculation, with and without a partial last word and with different lengths.
+#[test]
+fn test_bitvec_combinators() {
+    let first = (0..400).map(|i| i % 3 == 0 || i % 11 == 0).collect::<Vec<_>>();
+    let second = (0..400).map(|i| i % 5 == 0 || i % 7 == 1).collect::<Vec<_>>();
+
+    let assert_bits = |bitvec: BitVec, expected: Vec<bool>, msg: &str| {
+        let expected = BitVec::from(expected.as_slice());
+        assert_eq!(bitvec.len_bit(), expected.len_bit(), "{msg}");
+        assert_eq!(bitvec.words, expected.words, "{msg}");
+        assert_eq!(
+            bitvec.bit_count_last_word, expected.bit_count_last_word,
+            "{msg}"
+        );
+    };
+
+    let lengths = [(0, 0), (1, 1), (64, 64), (100, 100), (400, 333), (128, 400), (7, 0)];
+    for (len_a, len_b) in lengths {
+        let a = BitVec::from(&first[..len_a]);
+        let b = BitVec::from(&second[..len_b]);
+        let msg = format!("lengths {len_a}, {len_b}");
+        let pairs = first.iter().zip(&second).take(usize::min(len_a, len_b));
+
+        let expected = pairs.clone().map(|(a, b)| a ^ b).collect();
+        assert_bits(a.xor(&b), expected, &msg);
+
+        let expected = pairs.flat_map(|(&a, &b)| [a, b]).collect();
+        assert_bits(a.interleave(&b), expected, &msg);
+
+        let expected = first[..len_a].iter().map(|bit| !bit).collect();
+        assert_bits(a.not(), expected, &msg);
+
+        let expected = first[..len_a]
+            .chunks_exact(2)
+            .filter(|pair| pair[0] != pair[1])
+            .map(|pair| pair[0])
+            .collect();
+        assert_bits(a.von_neumann_extract(), expected, &msg);
+    }
+
+    // a last single bit that would form the pair 10 with the padding is discarded.
+    let bitvec = BitVec::from_ascii_str("1001110").unwrap();
+    assert_bits(bitvec.von_neumann_extract(), vec![true, false], "odd length");
+}
+
+/// Test the creation of a BitVec from a reader, with and without a max length.
+#[cfg(feature = "std")]
+#[test]
+fn test_bitvec_from_reader() {
+    let input_data = (0..=u8::MAX).collect::<Vec<_>>();
+    let expected = BitVec::from(input_data.as_slice());
+
+    let bitvec = BitVec::from_reader(input_data.as_slice(), ReaderFormat::Binary).unwrap();
+    assert_eq!(bitvec.words, expected.words);
+    assert_eq!(bitvec.bit_count_last_word, expected.bit_count_last_word);
+
+    let length = 1001;
+    let mut expected = expected;
+    expected.crop(length);
+
+    let bitvec =
+        BitVec::from_reader_with_max_length(input_data.as_slice(), ReaderFormat::Binary, length)
+            .unwrap();
+    assert_eq!(bitvec.len_bit(), length);
+    assert_eq!(bitvec.words, expected.words);
+    assert_eq!(bitvec.bit_count_last_word, expected.bit_count_last_word);
+}
+
+/// Test the creation of a BitVec from a reader with ASCII input.
+#[cfg(feature = "std")]
+#[test]
+fn test_bitvec_from_reader_ascii() {
+    let input_data = "101a101100b101010o100";
+
+    let bitvec = BitVec::from_reader(input_data.as_bytes(), ReaderFormat::Ascii);
+    assert!(bitvec.is_err());
+
+    let bitvec =
+        BitVec::from_reader_with_max_length(input_data.as_bytes(), ReaderFormat::AsciiLossy, 13)
+            .unwrap();
+    let bits = 0b1_0110_1100_1010;
+    let bits = bits << (usize::BITS as usize - 13);
+    assert_eq!(&*bitvec.words, &[bits]);
+    assert_eq!(bitvec.bit_count_last_word, 13);
+
+    // reading in parts: the reader must only be consumed as far as necessary
+    let mut reader = input_data.as_bytes();
+    let mut first = BitVecBuilder::with_max_length(4);
+    first.extend_from_reader(&mut reader, ReaderFormat::AsciiLossy).unwrap();
+    let mut second = BitVecBuilder::new();
+    second.extend_from_reader(&mut reader, ReaderFormat::AsciiLossy).unwrap();
+    assert_eq!(first.len_bit(), 4);
+    assert_eq!(second.len_bit(), 14);
+}
+
+/// Test that a full builder ignores further input, also whole words of text input.
+#[test]
+fn test_bitvec_builder_full() {
+    for max_length in [0, 5, 64, 128] {
+        let mut builder = BitVecBuilder::with_max_length(max_length);
+        builder.extend_from_ascii_lossy(&vec![b'1'; max_length]);
+        assert!(builder.is_full());
+
+        builder.extend_from_ascii_lossy(&[b'0'; 64]);
+        builder.extend_from_hex_lossy(&[b'f'; 16]);
+        assert!(builder.extend_from_ascii(&[b'0'; 128]));
+        builder.extend_from_bytes(&[0; 8]);
+        builder.push_bit(false);
+
+        let bitvec = builder.build();
+        assert_eq!(bitvec.len_bit(), max_length);
+        assert_eq!(bitvec.count_ones(), max_length);
+    }
+}
+
+/// Test the creation of a BitVec from ASCII input grouped with whitespace, as in the data files of
+/// the NIST reference implementation.
+#[cfg(feature = "std")]
+#[test]
+fn test_bitvec_from_reader_ascii_whitespace() {
+    let input_data = "  1011 0110\n0101\t1100\r\n  011\n";
+    let expected = BitVec::from_ascii_str("1011011001011100011").unwrap();
+
+    let bitvec = BitVec::from_reader(input_data.as_bytes(), ReaderFormat::AsciiWhitespace).unwrap();
+    assert_eq!(bitvec.words, expected.words);
+    assert_eq!(bitvec.bit_count_last_word, expected.bit_count_last_word);
+
+    // the strict format rejects the whitespace
+    assert!(BitVec::from_reader(input_data.as_bytes(), ReaderFormat::Ascii).is_err());
+
+    // other characters are not skipped
+    let error =
+        BitVec::from_reader("0110 01a1".as_bytes(), ReaderFormat::AsciiWhitespace).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    let mut builder = BitVecBuilder::new();
+    assert!(!builder.extend_from_ascii_whitespace(b"01 1x"));
+    assert_eq!(builder.len_bit(), 0);
+    assert!(builder.extend_from_ascii_whitespace(b"01 1\n0"));
+    assert_eq!(builder.len_bit(), 4);
+}
+
+/// Test the creation of a BitVec from hexadecimal text, directly and from a reader.
+#[cfg(feature = "std")]
+#[test]
+fn test_bitvec_from_hex() {
+    let input_data = "0123 4567\n89ab\tCDEF\r\n0123456789abcdef f0";
+    let expected = BitVec::from(
+        [
+            0x01_u8, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0x01, 0x23, 0x45, 0x67, 0x89, 0xab,
+            0xcd, 0xef, 0xf0,
+        ]
+        .as_slice(),
+    );
+
+    let bitvec = BitVec::from_hex_str(input_data).unwrap();
+    assert_eq!(bitvec.words, expected.words);
+    assert_eq!(bitvec.bit_count_last_word, expected.bit_count_last_word);
+
+    assert!(BitVec::from_hex_str("0123 x").is_none());
+    assert!(BitVec::from_reader("0123 x".as_bytes(), ReaderFormat::Hex).is_err());
+
+    // odd count of digits, lossy
+    let bitvec = BitVec::from_hex_str_lossy("0x1-2:f");
+    assert_eq!(bitvec.len_bit(), 16);
+    assert_eq!(&*bitvec.words, &[0x012f << (usize::BITS as usize - 16)]);
+
+    // max length in the middle of a digit
+    let bitvec =
+        BitVec::from_reader_with_max_length(input_data.as_bytes(), ReaderFormat::Hex, 10).unwrap();
+    assert_eq!(bitvec.len_bit(), 10);
+    assert_eq!(&*bitvec.words, &[0b00_0000_0100 << (usize::BITS as usize - 10)]);
+}
+
+/// Test the creation of a BitVec from base64 text, with and without padding.
+#[cfg(feature = "std")]
+#[test]
+fn test_bitvec_from_base64() {
+    let input_data = (0..=u8::MAX).collect::<Vec<_>>();
+    let expected = BitVec::from(input_data.as_slice());
+
+    // 256 bytes: the last group is padded with "=="
+    let encoded = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4\n\
+                   OTo7PD0+P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5fYGFiY2RlZmdoaWprbG1ub3Bx\n\
+                   cnN0dXZ3eHl6e3x9fn+AgYKDhIWGh4iJiouMjY6PkJGSk5SVlpeYmZqbnJ2en6ChoqOkpaanqKmq\n\
+                   q6ytrq+wsbKztLW2t7i5uru8vb6/wMHCw8TFxsfIycrLzM3Oz9DR0tPU1dbX2Nna29zd3t/g4eLj\n\
+                   5OXm5+jp6uvs7e7v8PHy8/T19vf4+fr7/P3+/w==\n";
+
+    let bitvec = BitVec::from_base64(encoded).unwrap();
+    assert_eq!(bitvec.words, expected.words);
+    assert_eq!(bitvec.bit_count_last_word, expected.bit_count_last_word);
+
+    let bitvec = BitVec::from_base64(encoded.trim_end().trim_end_matches('=')).unwrap();
+    assert_eq!(bitvec.words, expected.words);
+    assert_eq!(bitvec.bit_count_last_word, expected.bit_count_last_word);
+
+    // concatenated with padding in between
+    let bitvec = BitVec::from_base64("/w==AQ").unwrap();
+    assert_eq!(bitvec.len_bit(), 16);
+    assert_eq!(&*bitvec.words, &[0xff01 << (usize::BITS as usize - 16)]);
+
+    assert!(BitVec::from_base64("AAEC*").is_none());
+    assert!(BitVec::from_base64("AAECA").is_none());
+}
+
+/// Test the creation of a BitVec from words, with and without a partial last word and unused
+/// words.
+#[test]
+fn test_bitvec_from_words() {
+    let input_data = (0..400).map(|i| i % 3 == 0 || i % 11 == 0).collect::<Vec<_>>();
+    let words = BitVec::from(input_data.as_slice()).words;
+
+    for length in [0, 1, 64, 100, 128, 333, 400] {
+        let bitvec = BitVec::from_words(&words, length).unwrap();
+        let expected = BitVec::from(&input_data[..length]);
+        assert_eq!(bitvec, expected, "length {length}");
+    }
+
+    assert!(BitVec::from_words(&words, words.len() * usize::BITS as usize + 1).is_none());
+    assert!(BitVec::from_words(&[], 1).is_none());
+}
+
+/// Test the creation of a BitVec that borrows its words: the words are only copied when the
+/// BitVec is modified, and are never written to.
+#[test]
+fn test_bitvec_from_words_borrowed() {
+    use std::borrow::Cow;
+
+    let input_data = (0..400)
+        .map(|i| i % 3 == 0 || i % 11 == 0)
+        .collect::<Vec<_>>();
+    let words = BitVec::from(input_data.as_slice()).words.into_owned();
+    let original_words = words.clone();
+
+    for length in [0, 64, 128, 320, 400] {
+        let bitvec = BitVec::from_words_borrowed(&words, length).unwrap();
+        assert!(matches!(bitvec.words, Cow::Borrowed(_)), "length {length}");
+        assert_eq!(
+            bitvec,
+            BitVec::from(&input_data[..length]),
+            "length {length}"
+        );
+    }
+
+    // cropping to full words does not copy
+    let mut bitvec = BitVec::from_words_borrowed(&words, 400).unwrap();
+    bitvec.crop(320);
+    assert!(matches!(bitvec.words, Cow::Borrowed(_)));
+    assert_eq!(bitvec, BitVec::from(&input_data[..320]));
+
+    // clearing bits and appending copies
+    bitvec.crop(100);
+    assert!(matches!(bitvec.words, Cow::Owned(_)));
+    assert_eq!(bitvec, BitVec::from(&input_data[..100]));
+
+    let mut bitvec = BitVec::from_words_borrowed(&words, 400).unwrap();
+    bitvec.push_bit(true);
+    assert!(matches!(bitvec.words, Cow::Owned(_)));
+    let mut expected = input_data.clone();
+    expected.push(true);
+    assert_eq!(bitvec, BitVec::from(expected.as_slice()));
+    assert_eq!(words, original_words);
+
+    // set bits after the length, too few words
+    assert!(BitVec::from_words_borrowed(&words, 100).is_none());
+    let too_long = words.len() * usize::BITS as usize + 1;
+    assert!(BitVec::from_words_borrowed(&words, too_long).is_none());
+    assert!(BitVec::from_words_borrowed(&[], 1).is_none());
+}
+
+/// Test the creation of a BitVec from bytes with the LSB first, directly, with the builder and
+/// from a reader, where only the byte formats are affected.
+#[cfg(feature = "std")]
+#[test]
+fn test_bitvec_bit_order() {
+    let input_data = (0..=u8::MAX).cycle().take(1001).collect::<Vec<_>>();
+    let bits = input_data
+        .iter()
+        .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+        .collect::<Vec<_>>();
+    let expected = BitVec::from(bits.as_slice());
+
+    let bitvec = BitVec::from_bytes(&input_data, BitOrder::LsbFirst);
+    assert_eq!(bitvec.words, expected.words);
+    assert_eq!(bitvec.bit_count_last_word, expected.bit_count_last_word);
+
+    let bitvec = BitVec::from_bytes(&input_data, BitOrder::MsbFirst);
+    assert_eq!(bitvec.words, BitVec::from(input_data.as_slice()).words);
+
+    let mut builder = BitVecBuilder::new();
+    builder.set_bit_order(BitOrder::LsbFirst);
+    builder.extend_from_bytes(&input_data[..3]);
+    builder
+        .extend_from_reader(&mut &input_data[3..], ReaderFormat::Binary)
+        .unwrap();
+    let bitvec = builder.build();
+    assert_eq!(bitvec.words, expected.words);
+    assert_eq!(bitvec.bit_count_last_word, expected.bit_count_last_word);
+
+    // base64 of the bytes 0x01 and 0x80
+    let mut builder = BitVecBuilder::new();
+    builder.set_bit_order(BitOrder::LsbFirst);
+    builder
+        .extend_from_reader(&mut "AYA=".as_bytes(), ReaderFormat::Base64)
+        .unwrap();
+    builder.extend_from_ascii_lossy(b"0011");
+    let bitvec = builder.build();
+    let expected = BitVec::from_ascii_str("10000000000000010011").unwrap();
+    assert_eq!(bitvec.words, expected.words);
+}
+
 /// Test the pi calculation according to Hamano and Kaneko. Used in the overlapping template matching
 /// test.
+#[cfg(feature = "std")]
 #[test]
 fn test_pi_calculation() {
     let block_length = 1032;
@@ -263,7 +832,125 @@ fn test_pi_calculation() {
     }
 }
 
+/// Test the pi calculation for custom templates, used in the overlapping template matching test:
+/// for the all-ones template, the values must be the same as according to Hamano and Kaneko, and
+/// for a short block, the same as counted over all possible blocks.
+#[cfg(feature = "std")]
+#[test]
+fn test_custom_template_pi_calculation() {
+    let all_ones = usize::MAX << (usize::BITS - 9);
+    let pis = calculate_custom_template_pis(all_ones, 1032, 9, 6);
+    let expected = calculate_hamano_kaneko_pis(1032, 9, 6);
+    for (pi, expected) in pis.iter().zip(expected.iter()) {
+        assert!(
+            f64::abs(pi - expected) < 1e-12,
+            "Expected {expected}, got {pi}"
+        );
+    }
+
+    // the periodic template 101 in all blocks of 12 bits, 3 or more matches are combined
+    let template = 0b101_usize;
+    let block_length = 12;
+    let mut counts = [0_usize; 4];
+    for block in 0..(1_usize << block_length) {
+        let matches = (0..=block_length - 3)
+            .filter(|shift| (block >> shift) & 0b111 == template)
+            .count();
+        counts[matches.min(3)] += 1;
+    }
+
+    let pis = calculate_custom_template_pis(template << (usize::BITS - 3), block_length, 3, 4);
+    for (pi, count) in pis.iter().zip(counts) {
+        assert_f64_eq!(*pi, count as f64 / (1 << block_length) as f64);
+    }
+}
+
+/// Test the pi calculation according to Hamano and Kaneko for other than the default arguments,
+/// against the pi values of the all-ones template calculated as a custom template.
+#[cfg(feature = "std")]
+#[test]
+fn test_pi_calculation_non_default() {
+    for (block_length, template_length, freedom) in [(100, 2, 4), (300, 5, 6), (1032, 10, 6)] {
+        let all_ones = usize::MAX << (usize::BITS as usize - template_length);
+        let pis = calculate_hamano_kaneko_pis(block_length, template_length, freedom);
+        let expected =
+            calculate_custom_template_pis(all_ones, block_length, template_length, freedom);
+
+        assert_eq!(pis.len(), freedom);
+        for (pi, expected) in pis.iter().zip(expected.iter()) {
+            assert!(
+                f64::abs(pi - expected) < 1e-12,
+                "M = {block_length}, m = {template_length}: expected {expected}, got {pi}"
+            );
+        }
+    }
+}
+
+/// Test the fast pi calculation according to Hamano and Kaneko with f64 against the precise one
+/// with fixed-point numbers, and that it fails if the error bound exceeds the tolerance.
+#[cfg(feature = "std")]
+#[test]
+fn test_pi_calculation_f64() {
+    use crate::tests::template_matching::overlapping::{
+        hamano_kaneko_pis_f64, hamano_kaneko_pis_fixed,
+    };
+
+    let tolerance = 1e-10;
+    for (block_length, template_length, freedom) in
+        [(100, 2, 4), (300, 5, 6), (1032, 9, 6), (1032, 10, 6)]
+    {
+        let pis = hamano_kaneko_pis_f64(block_length, template_length, freedom, tolerance)
+            .expect("the error bound must be within the tolerance");
+        let expected = hamano_kaneko_pis_fixed(block_length, template_length, freedom);
+
+        assert_eq!(pis.len(), freedom);
+        for (pi, expected) in pis.iter().zip(expected.iter()) {
+            assert!(
+                f64::abs(pi - expected) <= tolerance * expected,
+                "M = {block_length}, m = {template_length}: expected {expected}, got {pi}"
+            );
+        }
+    }
+
+    // rounding errors are always possible
+    assert!(hamano_kaneko_pis_f64(1032, 9, 6, 0.0).is_none());
+}
+
+/// Test the fixed-point numbers used in the pi calculation of the overlapping template matching
+/// test.
+#[cfg(feature = "std")]
+#[test]
+fn test_fixed_point() {
+    use crate::tests::template_matching::fixed_point::Fixed;
+
+    let half = Fixed::ONE.div_pow2(1);
+    assert_f64_eq!(half.to_f64(), 0.5);
+    assert_eq!(half + half, Fixed::ONE);
+    assert_eq!(Fixed::ONE - half, half);
+    assert_eq!(half * half, Fixed::ONE.div_pow2(2));
+    assert_eq!(Fixed::ONE.div_pow2(0), Fixed::ONE);
+    // across word boundaries and out of the precision
+    assert_f64_eq!(Fixed::ONE.div_pow2(100).to_f64(), f64::powi(2.0, -100));
+    assert_f64_eq!(Fixed::ONE.div_pow2(192).to_f64(), f64::powi(2.0, -192));
+    assert_eq!(Fixed::ONE.div_pow2(193), Fixed::ZERO);
+    assert_eq!(Fixed::ONE.div_pow2(300), Fixed::ZERO);
+
+    // 1/3 with the carries of all words
+    let third = (0..96)
+        .map(|i| Fixed::ONE.div_pow2(2 * i + 2))
+        .sum::<Fixed>();
+    assert!(f64::abs(third.to_f64() - 1.0 / 3.0) < f64::EPSILON);
+    let ninth = third * third;
+    assert!(f64::abs(ninth.to_f64() - 1.0 / 9.0) < f64::EPSILON);
+    assert!(f64::abs((Fixed::ONE - ninth).to_f64() - 8.0 / 9.0) < f64::EPSILON);
+    // products above 1
+    let three = Fixed::ONE + Fixed::ONE + Fixed::ONE;
+    assert_f64_eq!((three * three).to_f64(), 9.0);
+    assert_eq!(Fixed::ZERO.to_f64(), 0.0);
+}
+
 /// Test the Berlekamp-Massey algorithm used in the linear complexity test.
+#[cfg(feature = "std")]
 #[test]
 fn test_berlekamp_massey() {
     // start_bit is 0, everything in the sequence
@@ -282,3 +969,2372 @@ fn test_berlekamp_massey() {
 
     assert_eq!(berlekamp_massey(&sequence, bit_len, start_bit), 4);
 }
+
+/// Test that a cancelled token stops the test runner.
+#[cfg(feature = "std")]
+#[test]
+fn test_runner_cancellation() {
+    let data = BitVec::from_ascii_str("1100100100001111110110101010001000100001011010001100001000110100110001001100011001100010100010111000").unwrap();
+    let tests = [Test::Frequency, Test::Runs];
+
+    // not cancelled: the tests are run normally
+    let token = CancellationToken::new();
+    let results = run_tests_with_cancel(&data, tests.into_iter(), TestArgs::default(), &token)
+        .unwrap()
+        .collect::<Vec<_>>();
+    assert_eq!(results.len(), tests.len());
+    assert!(results.iter().all(|(_, res)| res.is_ok()));
+
+    // cancelled: no test is run
+    token.clone().cancel();
+    assert!(token.is_cancelled());
+    let results = run_tests_with_cancel(&data, tests.into_iter(), TestArgs::default(), &token)
+        .unwrap()
+        .collect::<Vec<_>>();
+    assert_eq!(results.len(), tests.len());
+    assert!(results
+        .iter()
+        .all(|(_, res)| matches!(res, Err(Error::Cancelled))));
+
+    // the check inside a test
+    with_check(Some(&token), None, || {
+        assert!(matches!(CancelCheck::current().check(), Err(Error::Cancelled)))
+    });
+    assert!(CancelCheck::current().check().is_ok());
+}
+
+/// Test that a test exceeding the timeout aborts, while the other tests are still run.
+#[cfg(feature = "std")]
+#[test]
+fn test_runner_timeout() {
+    use crate::test_runner::run_tests;
+    use std::fs;
+    use std::path::Path;
+    use std::time::Duration;
+
+    let input = fs::read(Path::new(TEST_FILE_PATH).join("e.1e6.bin")).unwrap();
+    let data = BitVec::from(input);
+    let tests = [Test::Frequency, Test::LinearComplexity];
+
+    // the linear complexity test checks the timeout, the frequency test is always completed
+    let args = TestArgs {
+        timeout: Some(Duration::ZERO),
+        ..Default::default()
+    };
+    let results = run_tests(&data, tests.into_iter(), args)
+        .unwrap()
+        .collect::<HashMap<_, _>>();
+    assert!(results[&Test::Frequency].is_ok());
+    assert!(matches!(
+        results[&Test::LinearComplexity],
+        Err(Error::TimedOut(Duration::ZERO))
+    ));
+
+    // a timeout that is not exceeded does not change anything
+    let args = TestArgs {
+        timeout: Some(Duration::from_secs(3600)),
+        ..Default::default()
+    };
+    let results = run_tests(&data, tests.into_iter(), args).unwrap();
+    assert!(results.into_iter().all(|(_, res)| res.is_ok()));
+
+    // the check inside a test
+    with_check(None, Some(Duration::ZERO), || {
+        assert!(matches!(
+            CancelCheck::current().check(),
+            Err(Error::TimedOut(_))
+        ))
+    });
+    assert!(CancelCheck::current().check().is_ok());
+}
+
+/// Test that the partitioned runner runs each test on each sub-sequence.
+#[cfg(feature = "std")]
+#[test]
+fn test_runner_partitioned() {
+    use crate::test_runner::{run_tests, run_tests_partitioned, run_tests_partitioned_with_cancel};
+    use std::fs;
+    use std::num::NonZero;
+    use std::path::Path;
+
+    let input = fs::read(Path::new(TEST_FILE_PATH).join("e.1e6.bin")).unwrap();
+    let data = BitVec::from(input);
+    let tests = [Test::Frequency, Test::Runs, Test::CumulativeSums];
+    let partitions = NonZero::new(3).unwrap();
+
+    let results = run_tests_partitioned(&data, tests.into_iter(), TestArgs::default(), partitions)
+        .unwrap()
+        .collect::<HashMap<_, _>>();
+    assert_eq!(results.len(), tests.len());
+
+    // the same results as running the tests on each sub-sequence, the last bit is not tested
+    let len = data.len_bit() / 3;
+    for (test, partition_results) in &results {
+        assert_eq!(partition_results.len(), 3);
+
+        for (idx, got) in partition_results.iter().enumerate() {
+            let partition = data.slice(idx * len..(idx + 1) * len);
+            let (_, expected) = run_tests(&partition, [*test].into_iter(), TestArgs::default())
+                .unwrap()
+                .next()
+                .unwrap();
+
+            let (got, expected) = (got.as_ref().unwrap(), expected.unwrap());
+            assert_eq!(got.len(), expected.len());
+            for (got, expected) in got.iter().zip(expected) {
+                assert_f64_eq!(got.p_value(), expected.p_value(), test);
+            }
+        }
+    }
+
+    // duplicate tests are rejected
+    let duplicates = [Test::Frequency, Test::Frequency].into_iter();
+    assert!(run_tests_partitioned(&data, duplicates, TestArgs::default(), partitions).is_err());
+
+    // cancelled: no sub-sequence is tested
+    let token = CancellationToken::new();
+    token.cancel();
+    let results = run_tests_partitioned_with_cancel(
+        &data,
+        tests.into_iter(),
+        TestArgs::default(),
+        partitions,
+        &token,
+    )
+    .unwrap()
+    .collect::<Vec<_>>();
+    for (_, results) in results {
+        assert_eq!(results.len(), 3);
+        assert!(results
+            .iter()
+            .all(|res| matches!(res, Err(Error::Cancelled))));
+    }
+}
+
+/// Test that the subsampled runner runs each test on reproducible, disjoint windows.
+#[cfg(feature = "std")]
+#[test]
+fn test_runner_subsampled() {
+    use crate::test_runner::{run_tests, run_tests_subsampled, Subsample};
+    use std::fs;
+    use std::num::NonZero;
+    use std::path::Path;
+
+    let input = fs::read(Path::new(TEST_FILE_PATH).join("e.1e6.bin")).unwrap();
+    let data = BitVec::from(input);
+    let window_len = NonZero::new(100_000).unwrap();
+    let count = NonZero::new(4).unwrap();
+
+    let subsample = Subsample::new(data.len_bit(), window_len, count, 1234).unwrap();
+    assert_eq!(
+        subsample,
+        Subsample::new(data.len_bit(), window_len, count, 1234).unwrap()
+    );
+    assert_ne!(
+        subsample.offsets(),
+        Subsample::new(data.len_bit(), window_len, count, 1235)
+            .unwrap()
+            .offsets()
+    );
+
+    // sorted, disjoint and inside the input
+    let offsets = subsample.offsets();
+    assert_eq!(offsets.len(), 4);
+    assert!(offsets.windows(2).all(|w| w[0] + window_len.get() <= w[1]));
+    assert!(offsets[3] + window_len.get() <= data.len_bit());
+
+    // the windows cover the whole input: only one possible placement
+    let exact = Subsample::new(400_000, window_len, count, 99).unwrap();
+    assert_eq!(exact.offsets(), [0, 100_000, 200_000, 300_000]);
+
+    // too many windows
+    assert!(Subsample::new(399_999, window_len, count, 0).is_err());
+    assert!(Subsample::new(usize::MAX, NonZero::<usize>::MAX, count, 0).is_err());
+
+    let tests = [Test::Frequency, Test::Runs];
+    let results = run_tests_subsampled(&data, tests.into_iter(), TestArgs::default(), &subsample)
+        .unwrap()
+        .collect::<HashMap<_, _>>();
+    assert_eq!(results.len(), tests.len());
+
+    for (test, window_results) in &results {
+        assert_eq!(window_results.len(), 4);
+
+        for (&offset, got) in offsets.iter().zip(window_results) {
+            let window = data.slice(offset..offset + window_len.get());
+            let (_, expected) = run_tests(&window, [*test].into_iter(), TestArgs::default())
+                .unwrap()
+                .next()
+                .unwrap();
+
+            let (got, expected) = (got.as_ref().unwrap(), expected.unwrap());
+            assert_f64_eq!(got[0].p_value(), expected[0].p_value(), test);
+        }
+    }
+}
+
+/// Test that the runner rejects invalid tests before running any test, returning all problems.
+#[cfg(feature = "std")]
+#[test]
+fn test_runner_validation() {
+    use crate::test_runner::{
+        run_tests, run_tests_partitioned, run_tests_subsampled, validate_tests, RunnerError,
+        Subsample,
+    };
+    use crate::tests::autocorrelation::AutocorrelationTestArg;
+    use std::num::NonZero;
+
+    let data = BitVec::from(vec![0b1011_0100_u8; 2500]);
+    let args = TestArgs {
+        autocorrelation: AutocorrelationTestArg::try_new(15_000).unwrap(),
+        ..TestArgs::default()
+    };
+
+    // valid tests
+    let tests = [Test::Frequency, Test::Runs, Test::CumulativeSums];
+    assert_eq!(validate_tests(data.len_bit(), tests, args), Ok(()));
+    assert!(run_tests(&data, tests.into_iter(), args).is_ok());
+
+    let tests = [
+        Test::Frequency,
+        Test::Autocorrelation,
+        Test::RandomExcursions,
+        Test::Frequency,
+        Test::Frequency,
+    ];
+    let expected = [
+        RunnerError::InvalidArgCombination {
+            test: Test::Autocorrelation,
+            reason: "the shift must be <= len_bit / 2 (=10000), is: 15000".to_owned(),
+        },
+        RunnerError::InputTooShort {
+            test: Test::RandomExcursions,
+            required: 1_000_000,
+            actual: 20_000,
+        },
+        RunnerError::DuplicateTest(Test::Frequency),
+        RunnerError::DuplicateTest(Test::Frequency),
+    ];
+
+    let error = validate_tests(data.len_bit(), tests, args).unwrap_err();
+    assert_eq!(error.problems(), expected);
+    assert!(matches!(error, RunnerError::Multiple(_)));
+
+    let Err(error) = run_tests(&data, tests.into_iter(), args) else {
+        panic!("invalid tests were run");
+    };
+    assert_eq!(error.problems(), expected);
+
+    // a single problem is not wrapped
+    let error = validate_tests(50, [Test::Frequency], args).unwrap_err();
+    assert_eq!(
+        error,
+        RunnerError::InputTooShort {
+            test: Test::Frequency,
+            required: 100,
+            actual: 50
+        }
+    );
+    assert_eq!(error.problems(), std::slice::from_ref(&error));
+
+    // the sub-sequences are checked: 80 bits each
+    let partitions = NonZero::new(250).unwrap();
+    let Err(error) = run_tests_partitioned(&data, [Test::Runs].into_iter(), args, partitions)
+    else {
+        panic!("the tests were run on too short sub-sequences");
+    };
+    assert!(matches!(error, RunnerError::InputTooShort { actual: 80, .. }));
+
+    let subsample = Subsample::new(
+        data.len_bit(),
+        NonZero::new(1000).unwrap(),
+        NonZero::new(2).unwrap(),
+        0,
+    )
+    .unwrap();
+    assert!(run_tests_subsampled(&data, [Test::Frequency].into_iter(), args, &subsample).is_ok());
+    assert!(
+        run_tests_subsampled(&data, [Test::Autocorrelation].into_iter(), args, &subsample).is_err()
+    );
+}
+
+/// Test that the plan of a test run matches the validation and the block lengths used by the tests.
+#[cfg(feature = "std")]
+#[test]
+fn test_plan_tests() {
+    use crate::test_runner::{plan_tests, validate_tests, RunnerError};
+    use std::time::Duration;
+
+    let tests = [
+        Test::LongestRunOfOnes,
+        Test::MaurersUniversalStatistical,
+        Test::ApproximateEntropy,
+        Test::RandomExcursions,
+        Test::Frequency,
+    ];
+    let plan = plan_tests(500_000, tests, TestArgs::default()).unwrap();
+
+    // the order of the tests is kept
+    let planned = plan.iter().map(|p| p.test()).collect::<Vec<_>>();
+    assert_eq!(planned, tests);
+
+    assert_eq!(plan[0].block_length(), Some(128));
+    assert_eq!(plan[1].block_length(), Some(6));
+    assert_eq!(plan[2].block_length(), Some(10));
+    assert!(plan[2].estimated_memory() > 0);
+    assert_eq!(plan[4].block_length(), None);
+
+    // the skipped tests are exactly the tests rejected by the validation
+    assert!(!plan[3].will_run());
+    assert_eq!(plan[3].estimated_duration(), Duration::ZERO);
+    let skipped = plan
+        .iter()
+        .filter_map(|p| p.skip_reason().cloned())
+        .collect::<Vec<_>>();
+    let error = validate_tests(500_000, tests, TestArgs::default()).unwrap_err();
+    assert_eq!(error.problems(), skipped);
+
+    // only duplicate tests are an error
+    assert_eq!(
+        plan_tests(10, [Test::Runs, Test::Runs], TestArgs::default()),
+        Err(RunnerError::DuplicateTest(Test::Runs))
+    );
+}
+
+/// Test the estimated durations of the tests, with and without a calibration.
+#[cfg(feature = "std")]
+#[test]
+fn test_estimate_duration() {
+    use crate::test_runner::{estimate_duration, Calibration};
+    use crate::tests::linear_complexity::LinearComplexityTestArg;
+    use crate::IntoEnumIterator;
+    use std::num::NonZero;
+    use std::time::Duration;
+
+    let args = TestArgs::default();
+    for test in Test::iter() {
+        let short = estimate_duration(test, 1_000_000, &args);
+        let long = estimate_duration(test, 10_000_000, &args);
+        assert!(short > Duration::ZERO, "{test}");
+        // only the first 10^6 bits are used by the Lempel-Ziv test
+        if test != Test::LempelZiv {
+            assert!(long >= short * 10, "{test}");
+        }
+    }
+
+    // the linear complexity test grows with the block length
+    let long_blocks = TestArgs {
+        linear_complexity: LinearComplexityTestArg::ManualBlockLength(NonZero::new(5000).unwrap()),
+        ..args
+    };
+    assert!(
+        estimate_duration(Test::LinearComplexity, 1_000_000, &long_blocks)
+            > estimate_duration(Test::LinearComplexity, 1_000_000, &args) * 5
+    );
+
+    // tests that were not calibrated keep the unscaled estimate
+    let calibration = Calibration::run([Test::Runs, Test::Frequency], args);
+    assert!(calibration
+        .factor(Test::Runs)
+        .is_some_and(|factor| factor > 0.0));
+    assert_eq!(calibration.factor(Test::SpectralDft), None);
+    assert_eq!(
+        calibration.estimate_duration(Test::SpectralDft, 1_000_000, &args),
+        estimate_duration(Test::SpectralDft, 1_000_000, &args)
+    );
+}
+
+/// Test the recommended input lengths and the warnings for inputs shorter than recommended.
+#[cfg(feature = "std")]
+#[test]
+fn test_length_warnings() {
+    use crate::test_runner::length_warnings;
+    use crate::{get_min_length_for_test, get_recommended_length_for_test};
+    use strum::IntoEnumIterator;
+
+    for test in Test::iter() {
+        assert!(get_recommended_length_for_test(test) >= get_min_length_for_test(test));
+    }
+    assert_eq!(
+        get_recommended_length_for_test(Test::MaurersUniversalStatistical).get(),
+        387_840
+    );
+    assert_eq!(
+        get_recommended_length_for_test(Test::OverlappingTemplateMatching).get(),
+        1_000_000
+    );
+
+    // long enough for all tests
+    assert!(length_warnings(1_000_000, Test::iter()).is_empty());
+
+    // too short for Maurer's test to run at all - no warning, since the test returns an error
+    let warnings = length_warnings(2_000, Test::iter());
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].test(), Test::OverlappingTemplateMatching);
+    assert_eq!(warnings[0].length(), 2_000);
+
+    let warnings = length_warnings(100_000, Test::iter());
+    let tests = warnings.iter().map(|w| w.test()).collect::<Vec<_>>();
+    assert_eq!(
+        tests,
+        [
+            Test::OverlappingTemplateMatching,
+            Test::MaurersUniversalStatistical
+        ]
+    );
+
+    // only the given tests are checked
+    assert!(length_warnings(100_000, [Test::Frequency]).is_empty());
+}
+
+/// Test that the fast and the slow tests are all tests, that the default tests are all tests
+/// except for the Lempel-Ziv test, and that the tests requiring 10^6 bits match their recommended
+/// input lengths.
+#[cfg(feature = "std")]
+#[test]
+fn test_test_sets() {
+    use crate::{get_recommended_length_for_test, IntoEnumIterator};
+    use std::collections::HashSet;
+
+    let fast = Test::fast_set().iter().collect::<HashSet<_>>();
+    let slow = Test::slow_set().iter().collect::<HashSet<_>>();
+    assert!(fast.is_disjoint(&slow));
+    assert_eq!(fast.len() + slow.len(), Test::iter().count());
+
+    assert_eq!(
+        Test::default_set().to_vec(),
+        Test::iter()
+            .filter(|&test| test != Test::LempelZiv)
+            .collect::<Vec<_>>()
+    );
+
+    let requires_1e6_bits = Test::iter()
+        .filter(|&test| get_recommended_length_for_test(test).get() >= 1_000_000)
+        .collect::<HashSet<_>>();
+    assert_eq!(
+        Test::requires_1e6_bits().iter().copied().collect::<HashSet<_>>(),
+        requires_1e6_bits
+    );
+}
+
+/// Test that the profiles can be selected by name and contain each test only once.
+#[cfg(feature = "std")]
+#[test]
+fn test_profiles() {
+    use crate::profile::Profile;
+    use crate::IntoEnumIterator;
+    use std::collections::HashSet;
+
+    for profile in Profile::iter() {
+        assert_eq!(profile.name().parse::<Profile>().unwrap(), profile);
+        assert_eq!(profile.to_string(), profile.name());
+
+        let tests = profile.tests().iter().collect::<HashSet<_>>();
+        assert_eq!(tests.len(), profile.tests().len());
+    }
+
+    assert!("unknown".parse::<Profile>().is_err());
+    // all tests of the current revision of SP 800-22
+    assert_eq!(
+        Profile::NistDefault.tests().len(),
+        Test::iter()
+            .filter(|&test| test != Test::LempelZiv && test != Test::Autocorrelation)
+            .count()
+    );
+
+    let args = Profile::FastScreening.test_args();
+    assert_eq!(args.serial.block_length(), 8);
+    assert_eq!(args.approximate_entropy.block_length(), 6);
+}
+
+/// Test that contexts use their own thread pool, with their own thread limit.
+#[cfg(feature = "std")]
+#[test]
+fn test_context() {
+    use crate::internals::IN_CUSTOM_THREAD_POOL;
+    use crate::test_runner::{run_tests, StsContext};
+    use crate::TestResult;
+    use std::num::NonZero;
+
+    let data = BitVec::from_ascii_str("1100100100001111110110101010001000100001011010001100001000110100110001001100011001100010100010111000").unwrap();
+    let tests = [Test::Frequency, Test::Runs, Test::CumulativeSums];
+    let p_values = |results: Vec<TestResult>| {
+        results
+            .iter()
+            .map(|res| res.p_value())
+            .collect::<Vec<_>>()
+    };
+    let expected = run_tests(&data, tests.into_iter(), TestArgs::default())
+        .unwrap()
+        .map(|(test, res)| (test, p_values(res.unwrap())))
+        .collect::<HashMap<_, _>>();
+
+    for max_threads in [1, 2] {
+        let context = StsContext::new(NonZero::new(max_threads).unwrap()).unwrap();
+        assert_eq!(context.max_threads(), max_threads);
+        assert_eq!(context.install(rayon::current_num_threads), max_threads);
+        assert!(context.install(|| IN_CUSTOM_THREAD_POOL.get()));
+
+        let results = context
+            .run_tests(&data, tests.into_iter(), TestArgs::default())
+            .unwrap()
+            .map(|(test, res)| (test, p_values(res.unwrap())))
+            .collect::<HashMap<_, _>>();
+        assert_eq!(results, expected);
+    }
+    assert!(!IN_CUSTOM_THREAD_POOL.get());
+
+    // shared contexts are only created once for each count of threads
+    let shared = StsContext::shared(NonZero::new(2).unwrap()).unwrap();
+    assert_eq!(shared.max_threads(), 2);
+    assert_eq!(StsContext::shared(NonZero::new(2).unwrap()).unwrap(), shared);
+    assert_ne!(StsContext::shared(NonZero::new(1).unwrap()).unwrap(), shared);
+    assert_ne!(StsContext::new(NonZero::new(2).unwrap()).unwrap(), shared);
+}
+
+/// Test the acceptance interval of the proportion of passed sequences, with the example of
+/// SP 800-22r1a, section 4.2.1, scaled to 100 sequences: the interval is 0.99 ± 0.0298.
+#[cfg(feature = "std")]
+#[test]
+fn test_analysis() {
+    use crate::analysis::analyse;
+    use crate::{NotApplicableReason, TestResult};
+
+    let sequences = |failed: usize| {
+        (0..100)
+            .map(|i| {
+                let p_value = if i < failed { 0.001 } else { 0.5 };
+                // the second result always passes
+                vec![TestResult::new(p_value), TestResult::new(0.5)]
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut not_applicable = sequences(3);
+    not_applicable.push(vec![TestResult::not_applicable(
+        "too few cycles",
+        NotApplicableReason::TooFewCycles {
+            cycles: 1,
+            required: 500,
+        },
+    )]);
+
+    let verdicts = analyse(
+        [
+            (Test::Frequency, sequences(3)),
+            (Test::Runs, sequences(4)),
+            (Test::RandomExcursions, not_applicable),
+            (Test::Serial, Vec::new()),
+        ],
+        0.01,
+    );
+    assert_eq!(verdicts.len(), 4);
+
+    // 97 of 100 passed: within the interval
+    assert_eq!(verdicts[0].test(), Test::Frequency);
+    assert_eq!(verdicts[0].results().len(), 2);
+    assert_eq!(verdicts[0].results()[0].passed_count(), 97);
+    let (min, max) = verdicts[0].results()[0].acceptance_interval();
+    assert_f64_eq!(round(min, 7), 0.9601504);
+    assert_f64_eq!(round(max, 7), 1.0198496);
+    assert!(verdicts[0].passed());
+
+    // 96 of 100 passed: below the interval
+    assert!(!verdicts[1].results()[0].passed());
+    assert!(verdicts[1].results()[1].passed());
+    assert!(!verdicts[1].passed());
+
+    // not applicable sequences are not part of the sample
+    assert_eq!(verdicts[2].not_applicable_count(), 1);
+    assert_eq!(verdicts[2].results()[0].sample_size(), 100);
+    assert!(verdicts[2].passed());
+
+    // no sample
+    assert!(verdicts[3].results().is_empty());
+    assert!(!verdicts[3].passed());
+}
+
+/// Test the uniformity of P-values: 5 P-values in the first and 5 in the last bin result in
+/// χ² = 2 * (5 - 1)² + 8 * (0 - 1)² = 40.
+#[cfg(feature = "std")]
+#[test]
+fn test_p_value_uniformity() {
+    use crate::analysis::p_value_uniformity;
+    use crate::statistics::TestStatistics;
+    use crate::test_runner::multi_sequence::UNIFORMITY_THRESHOLD;
+    use crate::{NotApplicableReason, ResultStatus};
+
+    let p_values = [0.05, 0.01, 0.0, 0.09, 0.05, 0.95, 0.99, 1.0, 0.9, 0.95];
+    let result = p_value_uniformity(&p_values);
+    assert_eq!(
+        result.statistics(),
+        Some(TestStatistics::Uniformity {
+            sample_size: 10,
+            chi_square: 40.0
+        })
+    );
+    assert_f64_eq!(round(result.p_value(), 10), 0.0000075985);
+    assert!(!result.passed(UNIFORMITY_THRESHOLD));
+
+    let p_values = (0..55).map(|i| i as f64 / 55.0).collect::<Vec<_>>();
+    assert!(p_value_uniformity(&p_values).passed(UNIFORMITY_THRESHOLD));
+
+    assert_eq!(
+        p_value_uniformity(&[]).status(),
+        ResultStatus::NotApplicable(NotApplicableReason::EmptySample)
+    );
+}
+
+/// Test the SHA-1 compression function with the known answer SHA-1("abc") - "abc" fits into one
+/// padded block.
+#[cfg(feature = "generators")]
+#[test]
+fn test_sha1_compress() {
+    use crate::generators::sha1_compress;
+
+    let mut block = [0; 64];
+    block[..3].copy_from_slice(b"abc");
+    block[3] = 0x80;
+    // message length in bits
+    block[63] = 24;
+
+    let state = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+    assert_eq!(
+        sha1_compress(state, &block),
+        [0xa9993e36, 0x4706816a, 0xba3e2571, 0x7850c26c, 0x9cd0d89d]
+    );
+}
+
+/// Test the reference generators: the start of each sequence, calculated independently, and the
+/// frequency test.
+#[cfg(feature = "generators")]
+#[test]
+fn test_generators() {
+    use crate::generators::Generator;
+    use crate::tests::frequency::frequency_test;
+    use crate::DEFAULT_THRESHOLD;
+    use strum::IntoEnumIterator;
+
+    let expected = [
+        (Generator::LinearCongruential, "10001000110100101011101100000110"),
+        (Generator::QuadraticCongruential1, "01111100010001011011110000101010"),
+        (Generator::QuadraticCongruential2, "10001111010001111000000101100000"),
+        (Generator::CubicCongruential, "11101101001010101010110010110110"),
+        (Generator::Xor, "11100100100100011110010100011100"),
+        (Generator::ModularExponentiation, "10000010000001110100110000110100"),
+        (Generator::GSha1, "11110001111010111100101011011100"),
+        (Generator::BlumBlumShub, "00011011001001100000110001111001"),
+        (Generator::MicaliSchnorr, "10011000111011011001100111101111"),
+    ];
+    assert_eq!(expected.len(), Generator::iter().count());
+
+    for (generator, start) in expected {
+        assert_eq!(generator.name().parse::<Generator>(), Ok(generator));
+
+        let data = generator.generate(5000);
+        assert_eq!(data.len_bit(), 5000, "{generator}");
+        assert_eq!(
+            data.slice(0..32).words,
+            BitVec::from_ascii_str(start).unwrap().words,
+            "{generator}"
+        );
+
+        let result = frequency_test(&data).unwrap();
+        assert!(result.passed(DEFAULT_THRESHOLD), "{generator}");
+    }
+}
+
+/// Test adding recorded results to the multi-sequence runner: the P-values of the frequency test
+/// on the test files, see full_examples::multi_sequence_frequency. The final analysis has to agree
+/// with the verdict of the test.
+#[cfg(feature = "std")]
+#[test]
+fn test_multi_sequence_recorded() {
+    use crate::internals::igamc;
+    use crate::test_runner::multi_sequence::RecordedResult;
+    use crate::test_runner::MultiSequenceRunner;
+    use crate::DEFAULT_THRESHOLD;
+
+    let mut runner =
+        MultiSequenceRunner::new([Test::Frequency].into_iter(), TestArgs::default()).unwrap();
+
+    for p_value in [0.953749, 0.578211, 0.604458, 0.811881, 0.610051] {
+        let result = RecordedResult::PValues(vec![p_value]);
+        assert!(runner.add_recorded(Test::Frequency, &result));
+    }
+    assert!(runner.add_recorded(Test::Frequency, &RecordedResult::NotApplicable));
+    assert!(runner.add_recorded(Test::Frequency, &RecordedResult::Error));
+    // not run by the runner
+    assert!(!runner.add_recorded(Test::Runs, &RecordedResult::PValues(vec![0.5])));
+
+    let analysis = runner.final_analysis(DEFAULT_THRESHOLD).unwrap();
+    assert_eq!(analysis.len(), 1);
+
+    let analysis = &analysis[0];
+    assert_eq!(analysis.sample_size(), 5);
+    assert_eq!(analysis.passed_count(), 5);
+    assert_eq!(analysis.not_applicable_count(), 1);
+    assert_eq!(analysis.error_count(), 1);
+    assert_eq!(analysis.histogram(), [0, 0, 0, 0, 0, 1, 2, 0, 1, 1]);
+    assert_f64_eq!(
+        analysis.uniformity_p_value().unwrap(),
+        igamc(4.5, 4.5).unwrap()
+    );
+
+    // the proportion is the one of the verdict
+    let verdicts = runner.verdicts(DEFAULT_THRESHOLD);
+    assert_eq!(verdicts.len(), 1);
+    let proportion = &verdicts[0].results()[0];
+    assert_eq!(
+        analysis.result_proportion().passed_count(),
+        proportion.passed_count()
+    );
+    assert_eq!(
+        analysis.proportion_range(),
+        proportion.acceptance_interval()
+    );
+    assert_eq!(analysis.proportion_passed(), verdicts[0].passed());
+}
+
+/// Test running custom tests, alone and with the multi-sequence runner: a custom test wrapping
+/// the frequency test must give the same results as the frequency test itself.
+#[cfg(feature = "std")]
+#[test]
+fn test_custom_test() {
+    use crate::test_runner::cancellation::check_current;
+    use crate::test_runner::custom::{
+        run_custom_tests, run_custom_tests_with_cancel, CustomRunnerError, CustomTest,
+    };
+    use crate::test_runner::MultiSequenceRunner;
+    use crate::tests::frequency::frequency_test;
+    use crate::{TestResult, DEFAULT_THRESHOLD};
+    use std::fs;
+    use std::num::NonZero;
+    use std::path::Path;
+    use std::sync::Arc;
+
+    struct CustomFrequency(&'static str);
+
+    impl CustomTest for CustomFrequency {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn min_length(&self) -> NonZero<usize> {
+            NonZero::new(100).unwrap()
+        }
+
+        fn run(&self, data: &BitVec, _: &TestArgs) -> Result<Vec<TestResult>, Error> {
+            check_current()?;
+            let result = frequency_test(data)?;
+            Ok(vec![TestResult::from_p_value(
+                result.p_value(),
+                Some("custom"),
+            )])
+        }
+    }
+
+    let first = CustomFrequency("first");
+    let second = CustomFrequency("second");
+    let duplicate = CustomFrequency("first");
+    let data = BitVec::from_ascii_str("1100100100001111110110101010001000100001011010001100001000110100110001001100011001100010100010111000").unwrap();
+
+    let results = run_custom_tests(
+        &data,
+        [&first as &dyn CustomTest, &second],
+        TestArgs::default(),
+    )
+    .unwrap()
+    .collect::<Vec<_>>();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].0, "first");
+    assert_eq!(results[1].0, "second");
+    let result = &results[0].1.as_ref().unwrap()[0];
+    assert_f64_eq!(result.p_value(), frequency_test(&data).unwrap().p_value());
+    assert_eq!(result.comment(), Some("custom"));
+
+    // duplicate names
+    assert!(matches!(
+        run_custom_tests(
+            &data,
+            [&first as &dyn CustomTest, &duplicate],
+            TestArgs::default()
+        ),
+        Err(CustomRunnerError("first"))
+    ));
+
+    // too short: the test is not run
+    let short = data.slice(0..99);
+    let mut results =
+        run_custom_tests(&short, [&first as &dyn CustomTest], TestArgs::default()).unwrap();
+    assert!(matches!(
+        results.next(),
+        Some((_, Err(Error::InvalidParameter(_))))
+    ));
+
+    // cancelled, the check inside the test
+    let token = CancellationToken::new();
+    token.cancel();
+    let mut results = run_custom_tests_with_cancel(
+        &data,
+        [&first as &dyn CustomTest],
+        TestArgs::default(),
+        &token,
+    )
+    .unwrap();
+    assert!(matches!(results.next(), Some((_, Err(Error::Cancelled)))));
+    with_check(Some(&token), None, || {
+        assert!(matches!(
+            first.run(&data, &TestArgs::default()),
+            Err(Error::Cancelled)
+        ))
+    });
+
+    // multiple sequences: the same analysis as the frequency test
+    let input = fs::read(Path::new(TEST_FILE_PATH).join("e.1e6.bin")).unwrap();
+    let input = BitVec::from(input);
+    let sequences = (0..10)
+        .map(|idx| input.slice(idx * 100_000..(idx + 1) * 100_000))
+        .collect::<Vec<_>>();
+
+    let mut runner = MultiSequenceRunner::new([Test::Frequency].into_iter(), TestArgs::default())
+        .unwrap()
+        .with_custom_tests([Arc::new(CustomFrequency("first")) as Arc<dyn CustomTest>])
+        .unwrap();
+    assert!(runner
+        .clone()
+        .with_custom_tests([Arc::new(CustomFrequency("first")) as Arc<dyn CustomTest>])
+        .is_err());
+    assert_eq!(runner.custom_tests().collect::<Vec<_>>(), ["first"]);
+
+    runner.run_sequences(&sequences);
+    let analysis = runner.final_analysis(DEFAULT_THRESHOLD).unwrap();
+    let custom_analysis = runner.custom_final_analysis(DEFAULT_THRESHOLD).unwrap();
+    assert_eq!(custom_analysis.len(), 1);
+    assert_eq!(custom_analysis[0].test(), "first");
+    assert_eq!(custom_analysis[0].comment(), Some("custom"));
+    assert_eq!(custom_analysis[0].sample_size(), 10);
+    assert_eq!(custom_analysis[0].histogram(), analysis[0].histogram());
+    assert_eq!(
+        custom_analysis[0].uniformity_p_value(),
+        analysis[0].uniformity_p_value()
+    );
+
+    let verdicts = runner.custom_verdicts(DEFAULT_THRESHOLD);
+    assert_eq!(verdicts[0].test(), "first");
+    assert_eq!(
+        verdicts[0].passed(),
+        runner.verdicts(DEFAULT_THRESHOLD)[0].passed()
+    );
+}
+
+/// Test the word-level calculations of the frequency, runs and cumulative sums tests against a
+/// bit-by-bit calculation, for lengths with and without a partial last word.
+#[test]
+fn test_word_level_statistics() {
+    use crate::statistics::TestStatistics;
+    use crate::tests::cumulative_sums::cumulative_sums_test;
+    use crate::tests::frequency::frequency_test;
+    use crate::tests::runs::runs_test;
+
+    let mut next_bit = xorshift_bits();
+
+    for len in [100, 128, 191, 1000, 4133] {
+        let bits = (0..len).map(|_| next_bit()).collect::<Vec<_>>();
+        let data = BitVec::from(bits.as_slice());
+
+        let sum = bits
+            .iter()
+            .map(|&bit| if bit { 1 } else { -1 })
+            .sum::<isize>();
+        let runs = 1 + bits.windows(2).filter(|pair| pair[0] != pair[1]).count();
+        let max_excursion = |bits: &mut dyn Iterator<Item = &bool>| {
+            bits.scan(0_isize, |sum, &bit| {
+                *sum += if bit { 1 } else { -1 };
+                Some(sum.unsigned_abs())
+            })
+            .max()
+            .unwrap()
+        };
+
+        let result = frequency_test(&data).unwrap();
+        assert!(
+            matches!(result.statistics(), Some(TestStatistics::Frequency { sum: s, .. }) if s == sum),
+            "{len}: {:?}",
+            result.statistics()
+        );
+
+        let result = runs_test(&data).unwrap();
+        assert!(
+            matches!(result.statistics(), Some(TestStatistics::Runs { runs: Some(v), .. }) if v == runs),
+            "{len}: {:?}",
+            result.statistics()
+        );
+
+        let [forward, backward] = cumulative_sums_test(&data).unwrap();
+        let expected = [
+            max_excursion(&mut bits.iter()),
+            max_excursion(&mut bits.iter().rev()),
+        ];
+        for (result, expected) in [forward, backward].into_iter().zip(expected) {
+            assert!(
+                matches!(result.statistics(), Some(TestStatistics::CumulativeSums { max_excursion }) if max_excursion == expected),
+                "{len}: {:?}",
+                result.statistics()
+            );
+        }
+    }
+}
+
+/// Test the parallel FFT of the spectral DFT test against a naive DFT, for even and odd lengths,
+/// and lengths whose FFT has a single row (a prime length, or twice a prime).
+#[cfg(feature = "std")]
+#[test]
+fn test_spectral_dft_peaks() {
+    use crate::statistics::TestStatistics;
+    use crate::tests::spectral_dft::{spectral_dft_test, SpectralDftTestArg};
+    use std::f64::consts::PI;
+
+    let mut next_bit = xorshift_bits();
+
+    for len in [1, 2, 7, 100, 1000, 1001, 1024, 1030, 1994, 1997, 2000] {
+        let bits = (0..len).map(|_| next_bit()).collect::<Vec<_>>();
+        let data = BitVec::from(bits.as_slice());
+        let arg = SpectralDftTestArg::default();
+        let threshold = arg.threshold(len);
+
+        // |X_k| for the first half of the DFT
+        let roots = (0..len)
+            .map(|j| {
+                let angle = 2.0 * PI * (j as f64) / (len as f64);
+                (f64::cos(angle), -f64::sin(angle))
+            })
+            .collect::<Vec<_>>();
+        let expected = (0..(len / 2))
+            .filter(|&k| {
+                let (re, im) = bits
+                    .iter()
+                    .enumerate()
+                    .fold((0.0, 0.0), |(re, im), (j, &bit)| {
+                        let x = if bit { 1.0 } else { -1.0 };
+                        let (cos, sin) = roots[(j * k) % len];
+                        (re + x * cos, im + x * sin)
+                    });
+                f64::sqrt(re * re + im * im) < threshold
+            })
+            .count();
+
+        let statistics = spectral_dft_test(&data, arg).map(|result| result.statistics());
+        assert!(
+            matches!(statistics, Ok(Some(TestStatistics::SpectralDft { observed_peaks, .. })) if observed_peaks == expected),
+            "{len}: expected {expected} peaks, got {statistics:?}"
+        );
+    }
+}
+
+/// Test the single pass matching of the non-overlapping template matching test against matching
+/// each template separately, including duplicate templates and templates that can never match.
+#[cfg(feature = "std")]
+#[test]
+fn test_non_overlapping_single_pass() {
+    use crate::tests::template_matching::non_overlapping::count_matches_per_chunk_per_template;
+    use crate::tests::template_matching::TemplateArg;
+
+    let mut next_bit = xorshift_bits();
+    let bits = (0..10_007).map(|_| next_bit()).collect::<Vec<_>>();
+    let data = BitVec::from(bits.as_slice());
+
+    let default_templates = TemplateArg::try_new(4).unwrap().templates().to_vec();
+    let mut custom_templates = default_templates.clone();
+    // duplicate, periodic template and a template with bits outside the template length
+    custom_templates.push(default_templates[0]);
+    custom_templates.push(0b0101 << (usize::BITS - 4));
+    custom_templates.push(0b11111 << (usize::BITS - 5));
+    // long templates, which are not looked up in a table, taken from the data to get matches
+    let long_templates = [100, 5000, 9000]
+        .map(|start| {
+            bits[start..(start + 23)]
+                .iter()
+                .fold(0_usize, |template, &bit| (template << 1) | (bit as usize))
+                << (usize::BITS - 23)
+        })
+        .to_vec();
+
+    for (templates, template_len, block_count) in [
+        (default_templates.as_slice(), 4, 8),
+        (custom_templates.as_slice(), 4, 7),
+        (long_templates.as_slice(), 23, 3),
+    ] {
+        let arg = TemplateArg::try_new_with_custom_templates(templates, template_len).unwrap();
+        let block_length = bits.len() / block_count;
+
+        let counts = count_matches_per_chunk_per_template(
+            block_count,
+            block_length,
+            &data,
+            arg,
+            CancelCheck::default(),
+        )
+        .unwrap();
+
+        for (block_idx, counts) in counts.iter().enumerate() {
+            let block = &bits[(block_idx * block_length)..((block_idx + 1) * block_length)];
+
+            for (&template, &count) in templates.iter().zip(counts.iter()) {
+                let template = (0..usize::BITS)
+                    .map(|bit| (template >> (usize::BITS - 1 - bit)) & 1 == 1)
+                    .collect::<Vec<_>>();
+                let (template, rest) = template.split_at(template_len);
+
+                let mut expected = 0;
+                let mut i = 0;
+                while i + template_len <= block.len() {
+                    if !rest.contains(&true) && &block[i..(i + template_len)] == template {
+                        expected += 1;
+                        i += template_len;
+                    } else {
+                        i += 1;
+                    }
+                }
+
+                assert_eq!(count, expected, "block {block_idx}, template {template:?}");
+            }
+        }
+    }
+}
+
+/// Test the automatic choice of the block length of the frequency test within a block with each
+/// strategy: the recommendations of SP 800-22 are satisfied and the chosen length is reported in
+/// the statistics.
+#[test]
+fn test_frequency_block_selection_strategy() {
+    use crate::statistics::TestStatistics;
+    use crate::tests::frequency_block::{
+        frequency_block_test, FrequencyBlockTestArg, SelectionStrategy,
+    };
+
+    let strategies = [
+        SelectionStrategy::NistRecommended,
+        SelectionStrategy::MaximizeBlocks,
+        SelectionStrategy::PowerOfTwo,
+    ];
+
+    for length in (100..20_000).step_by(7) {
+        for strategy in strategies {
+            let block_length = strategy.block_length(length);
+            assert!(block_length >= 20, "{strategy:?}, {length}");
+            assert!(block_length > length / 100, "{strategy:?}, {length}");
+            assert!(length / block_length >= 2, "{strategy:?}, {length}");
+        }
+
+        assert_eq!(
+            FrequencyBlockTestArg::ChooseAutomatically.block_length(length),
+            FrequencyBlockTestArg::ChooseWithStrategy(SelectionStrategy::NistRecommended)
+                .block_length(length)
+        );
+    }
+
+    for (length, expected) in [
+        (100, [20, 20, 32]),
+        (1950, [64, 20, 32]),
+        (1_000_000, [10048, 10001, 16384]),
+    ] {
+        let block_lengths = strategies.map(|strategy| strategy.block_length(length));
+        assert_eq!(block_lengths, expected, "{length}");
+    }
+
+    let data = BitVec::from(std::fs::read(format!("{TEST_FILE_PATH}/e.1e6.bin")).unwrap());
+    let arg = FrequencyBlockTestArg::ChooseWithStrategy(SelectionStrategy::PowerOfTwo);
+    let result = frequency_block_test(&data, arg).unwrap();
+    let Some(TestStatistics::FrequencyWithinABlock {
+        block_length,
+        block_count,
+        ..
+    }) = result.statistics()
+    else {
+        panic!("Wrong statistics: {:?}", result.statistics());
+    };
+    assert_eq!(block_length, arg.block_length(data.len_bit()));
+    assert_eq!(block_length, 16384);
+    assert_eq!(block_count, 61);
+}
+
+/// Test the generation of aperiodic templates: the same as the embedded templates, the same as a
+/// direct check of each template and the expected counts for the longer templates.
+#[cfg(feature = "std")]
+#[test]
+fn test_aperiodic_templates() {
+    use crate::tests::template_matching::{generate_aperiodic_templates, TemplateArg};
+
+    for m in 2..=21 {
+        assert_eq!(
+            generate_aperiodic_templates(m).as_slice(),
+            TemplateArg::try_new(m).unwrap().templates(),
+            "m = {m}"
+        );
+    }
+
+    for m in 2..=12 {
+        let expected = (0_usize..(1 << m))
+            .filter(|&template| {
+                (1..m).all(|shift| (template >> shift) != (template & ((1 << (m - shift)) - 1)))
+            })
+            .map(|template| template << (usize::BITS as usize - m))
+            .collect::<Vec<_>>();
+
+        assert_eq!(generate_aperiodic_templates(m), expected, "m = {m}");
+    }
+
+    // counts of unbordered binary words, OEIS A003000
+    let arg = TemplateArg::try_new(22).unwrap();
+    assert_eq!(arg.template_len(), 22);
+    assert_eq!(arg.templates().len(), 1_123_736);
+    assert_eq!(generate_aperiodic_templates(23).len(), 2_247_472);
+    assert!(TemplateArg::try_new(32).is_err());
+}
+
+/// Test loading the template files from a directory, with the feature `external-templates`: the
+/// same templates as generated, and no templates if the directory does not contain the files.
+#[cfg(feature = "external-templates")]
+#[test]
+fn test_external_templates() {
+    use crate::tests::template_matching::{
+        generate_aperiodic_templates, read_template_file, TemplateArg,
+    };
+    use std::path::Path;
+
+    let template_dir = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/templates"));
+    // uncompressed and compressed files
+    for m in [2, 9, 17, 18] {
+        assert_eq!(
+            read_template_file(template_dir, m).as_deref(),
+            Some(generate_aperiodic_templates(m).as_slice()),
+            "m = {m}"
+        );
+    }
+
+    assert!(read_template_file(Path::new(TEST_FILE_PATH), 10).is_none());
+    // without a template directory, the templates are generated
+    assert_eq!(
+        TemplateArg::try_new(10).unwrap().templates(),
+        generate_aperiodic_templates(10).as_slice()
+    );
+}
+
+/// Test the on-disk cache of the pi values, with the feature `pi-cache`: the values are read back
+/// exactly, invalid files are ignored, and precomputed values are not calculated again.
+#[cfg(feature = "pi-cache")]
+#[test]
+fn test_pi_cache() {
+    use crate::tests::template_matching::overlapping::OverlappingTemplateTestArgs;
+    use crate::tests::template_matching::pi_cache::{
+        cache_dir, precompute_pis, read_pi_file, set_pi_cache_dir, write_pi_file,
+    };
+    use std::fs;
+
+    let dir = std::env::temp_dir().join(format!("sts-lib-pi-cache-{}", std::process::id()));
+    let pis = calculate_hamano_kaneko_pis(1032, 9, 6);
+    write_pi_file(&dir, 1032, 9, 6, &pis).unwrap();
+    assert_eq!(read_pi_file(&dir, 1032, 9, 6), Some(pis));
+    // other arguments are not cached
+    assert_eq!(read_pi_file(&dir, 1032, 9, 5), None);
+
+    // wrong count of values and invalid values
+    let path = dir.join("hamano-kaneko-1032-9-6.txt");
+    fs::write(&path, "0.5\n0.5\n").unwrap();
+    assert_eq!(read_pi_file(&dir, 1032, 9, 6), None);
+    fs::write(&path, "0.5\n0.1\n0.1\n0.1\n0.1\nabc\n").unwrap();
+    assert_eq!(read_pi_file(&dir, 1032, 9, 6), None);
+
+    set_pi_cache_dir(&dir);
+    assert_eq!(cache_dir(), Some(dir.clone()));
+    let arg = OverlappingTemplateTestArgs::try_new(9, 100, 5).unwrap();
+    assert!(precompute_pis(arg).unwrap());
+    assert!(!precompute_pis(arg).unwrap());
+    assert_eq!(
+        read_pi_file(&dir, 100, 9, 5),
+        Some(calculate_hamano_kaneko_pis(100, 9, 5))
+    );
+    // nothing to cache for the NIST behaviour
+    let nist_arg = OverlappingTemplateTestArgs::try_new_nist_behaviour(9).unwrap();
+    assert!(!precompute_pis(nist_arg).unwrap());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Test loading custom template files: a file with the default templates in the format of the NIST
+/// reference implementation gives the same results as the default templates, and invalid files
+/// are rejected with the line of the problem.
+#[cfg(feature = "std")]
+#[test]
+fn test_template_file() {
+    use crate::tests::template_matching::non_overlapping::{
+        non_overlapping_template_matching_test, NonOverlappingTemplateTestArgs,
+    };
+    use crate::tests::template_matching::{parse_template_file, TemplateArg, TemplateFileError};
+
+    let default_args = NonOverlappingTemplateTestArgs::try_new(9, 8).unwrap();
+    let content = default_args
+        .templates()
+        .templates()
+        .iter()
+        .map(|template| {
+            (0..9)
+                .map(|bit| ((template >> (usize::BITS - 1 - bit)) & 1).to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let path = std::env::temp_dir().join(format!("sts-lib-templates-{}", std::process::id()));
+    std::fs::write(&path, format!("# the default templates\n{content}\n")).unwrap();
+    let file_args = NonOverlappingTemplateTestArgs::from_template_file(&path, 9);
+    std::fs::remove_file(&path).unwrap();
+    let file_args = file_args.unwrap();
+
+    assert_eq!(
+        file_args.templates().templates(),
+        default_args.templates().templates()
+    );
+    assert_eq!(file_args.count_blocks(), 8);
+
+    let data = BitVec::from(std::fs::read(format!("{TEST_FILE_PATH}/e.1e6.bin")).unwrap());
+    let default_results = non_overlapping_template_matching_test(&data, default_args).unwrap();
+    let file_results = non_overlapping_template_matching_test(&data, file_args).unwrap();
+    for (default_result, file_result) in default_results.iter().zip(file_results.iter()) {
+        assert_f64_eq!(default_result.p_value(), file_result.p_value());
+    }
+
+    // a restricted set, without whitespace
+    let templates = parse_template_file("0001\n\n1110\n", 4).unwrap();
+    assert_eq!(
+        templates,
+        [0b0001 << (usize::BITS - 4), 0b1110 << (usize::BITS - 4)]
+    );
+
+    assert!(matches!(
+        parse_template_file("0001\n0 1 2 1", 4),
+        Err(TemplateFileError::InvalidCharacter {
+            line: 2,
+            character: '2'
+        })
+    ));
+    assert!(matches!(
+        parse_template_file("00011", 4),
+        Err(TemplateFileError::WrongLength {
+            line: 1,
+            expected: 4,
+            actual: 5
+        })
+    ));
+    assert!(matches!(
+        parse_template_file("0001\n0101", 4),
+        Err(TemplateFileError::Periodic { line: 2, shift: 2 })
+    ));
+    assert!(matches!(
+        parse_template_file("0011\n1001", 4),
+        Err(TemplateFileError::Periodic { line: 2, shift: 3 })
+    ));
+    assert!(matches!(
+        parse_template_file("0001\n1000\n0 0 0 1", 4),
+        Err(TemplateFileError::Duplicate {
+            line: 3,
+            first_line: 1
+        })
+    ));
+    assert!(matches!(
+        parse_template_file("# nothing\n", 4),
+        Err(TemplateFileError::Empty)
+    ));
+    assert!(matches!(
+        parse_template_file("01", 32),
+        Err(TemplateFileError::InvalidTemplateLength(32))
+    ));
+    assert!(matches!(
+        TemplateArg::from_template_file(TEST_FILE_PATH, 9),
+        Err(TemplateFileError::Io(_))
+    ));
+}
+
+/// Test the public special functions against reference values, calculated with arbitrary
+/// precision (mpmath).
+#[test]
+fn test_math() {
+    use crate::math::{erfc, igamc, normal_cdf};
+
+    /// Checks the relative error guaranteed by the module docs.
+    fn assert_close(actual: f64, expected: f64, max_error: f64) {
+        assert!(
+            ((actual - expected) / expected).abs() < max_error,
+            "{actual} != {expected}"
+        );
+    }
+
+    for (a, x, expected) in [
+        (0.5, 0.5, 0.3173105078629141),
+        (1.5, 2.0, 0.2614641299491106),
+        (3.0, 2.5, 0.5438131158833295),
+        (4.0, 10.0, 0.010336050675925718),
+        (25.0, 20.0, 0.8432273781737623),
+        (74.0, 80.0, 0.23638303027425253),
+        (500.0, 600.0, 1.2255942330622905e-5),
+    ] {
+        assert_close(igamc(a, x).unwrap(), expected, 1e-12);
+    }
+    #[cfg(feature = "std")]
+    {
+        assert!(matches!(
+            igamc(-1.0, 1.0),
+            Err(Error::GammaFunctionFailed(_))
+        ));
+        assert!(matches!(
+            igamc(1.0, 0.0),
+            Err(Error::GammaFunctionFailed(_))
+        ));
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        assert!(matches!(igamc(-1.0, 1.0), Err(Error::InvalidParameter(_))));
+        assert!(matches!(igamc(1.0, 0.0), Err(Error::InvalidParameter(_))));
+    }
+
+    for (x, expected) in [
+        (-1.0, 1.842700792949715),
+        (0.0, 1.0),
+        (0.5, 0.4795001221869535),
+        (1.0, 0.15729920705028513),
+        (3.0, 2.209049699858544e-5),
+        (10.0, 2.088487583762545e-45),
+    ] {
+        assert_close(erfc(x), expected, 1e-9);
+    }
+
+    for (x, expected) in [
+        (-3.0, 0.0013498980316300946),
+        (-1.0, 0.15865525393145705),
+        (0.0, 0.5),
+        (1.0, 0.8413447460685429),
+        (1.96, 0.9750021048517795),
+        (3.0, 0.9986501019683699),
+    ] {
+        assert_close(normal_cdf(x), expected, 1e-9);
+    }
+}
+
+/// Test the special functions used without the feature `std`, for the arguments of the tests (the
+/// degrees of freedom of the chi-square statistics are between 1 and 2^(block length) for the
+/// serial test): the port of igamc against reference values (mpmath) and, with `std`, against the
+/// one of statrs, and erfc of libm against reference values.
+#[test]
+fn test_math_no_std() {
+    use crate::math::gamma::gamma_ur;
+
+    fn assert_close(actual: f64, expected: f64, max_error: f64) {
+        assert!(
+            actual == expected || ((actual - expected) / expected).abs() < max_error,
+            "{actual} != {expected}"
+        );
+    }
+
+    // reference values calculated with mpmath
+    for (a, x, expected) in [
+        (0.5, 0.005, 0.920344325445942),
+        (2.0, 1.8, 0.4628368870204423),
+        (8.0, 8.8, 0.34783440028489104),
+        (50.0, 25.0, 0.9999930466947524),
+        (128.0, 140.8, 0.1303058577240377),
+        (1000.0, 900.0, 0.9994500977342882),
+        (1000.0, 1100.0, 0.0010593232539299773),
+        (32768.0, 32768.0, 0.49926537802188137),
+        (32768.0, 36044.8, 3.991045375919826e-69),
+    ] {
+        // the error grows with a, see the module docs of math
+        let max_error = if a > 1000.0 { 1e-10 } else { 1e-12 };
+        assert_close(gamma_ur(a, x).unwrap(), expected, max_error);
+    }
+
+    #[cfg(feature = "std")]
+    for a in [
+        0.5, 1.0, 1.5, 2.0, 4.5, 8.0, 24.5, 50.0, 128.0, 1000.0, 32768.0,
+    ] {
+        for factor in [0.01, 0.1, 0.5, 0.9, 1.0, 1.1, 1.5, 2.0, 5.0] {
+            let x = a * factor;
+            let statrs = statrs::function::gamma::checked_gamma_ur(a, x).unwrap();
+            assert_close(gamma_ur(a, x).unwrap(), statrs, 1e-12);
+        }
+    }
+
+    assert!(matches!(
+        gamma_ur(-1.0, 1.0),
+        Err(Error::InvalidParameter(_))
+    ));
+    assert!(matches!(
+        gamma_ur(1.0, 0.0),
+        Err(Error::InvalidParameter(_))
+    ));
+    assert!(gamma_ur(f64::NAN, 1.0).unwrap().is_nan());
+
+    // reference values calculated with mpmath
+    for (x, expected) in [
+        (-5.0, 1.9999999999984626),
+        (-2.5, 1.999593047982555),
+        (-1.4, 1.9522851197626487),
+        (-0.5, 1.5204998778130465),
+        (0.0, 1.0),
+        (0.1, 0.887537083981715),
+        (0.5, 0.4795001221869535),
+        (1.0, 0.15729920705028513),
+        (1.4, 0.0477148802373512),
+        (2.5, 0.0004069520174449589),
+        (5.0, 1.537459794428035e-12),
+        (10.0, 2.088487583762545e-45),
+    ] {
+        assert_close(libm::erfc(x), expected, 1e-15);
+    }
+}
+
+/// Test the replacements of rayon and the methods of [f64] used without the feature `std`: the
+/// sequential iterators give the same results as the parallel ones, and the methods calculated
+/// with libm are exact for exactly representable results.
+#[cfg(not(feature = "std"))]
+#[test]
+fn test_no_std_replacements() {
+    use crate::internals::par::{IntoParallelIterator, IntoParallelRefIterator, ParallelSlice};
+    use crate::internals::{CancelCheck, F64Ext};
+
+    let values = (1..=100_u64).collect::<Vec<_>>();
+    assert_eq!(values.par_iter().map(|&v| 2 * v).sum::<u64>(), 10100);
+    assert_eq!(
+        values.par_chunks(30).map(<[u64]>::len).collect::<Vec<_>>(),
+        [30, 30, 30, 10]
+    );
+    assert_eq!(
+        values
+            .par_chunks_exact(30)
+            .map(<[u64]>::len)
+            .collect::<Vec<_>>(),
+        [30, 30, 30]
+    );
+    assert_eq!(values.par_iter().map(|&v| v).reduce(|| 0, u64::max), 100);
+
+    let sum_below = |limit: u64| {
+        values
+            .par_iter()
+            .try_fold(|| 0, |sum, &v| if v < limit { Ok(sum + v) } else { Err(v) })
+            .try_reduce(|| 0, |a, b| Ok(a + b))
+    };
+    assert_eq!(sum_below(101), Ok(5050));
+    assert_eq!(sum_below(20), Err(20));
+
+    assert_eq!(F64Ext::abs(-2.5), 2.5);
+    assert_eq!(F64Ext::sqrt(16.0), 4.0);
+    assert_eq!(F64Ext::ln(1.0), 0.0);
+    assert_eq!(F64Ext::ln_1p(0.0), 0.0);
+    assert_eq!(F64Ext::log2(8.0), 3.0);
+    assert_eq!(F64Ext::powi(2.0, 10), 1024.0);
+
+    assert!(CancelCheck::current().check().is_ok());
+}
+
+/// Test the FIPS 140-2 tests with the first 20000 bits of e, a constant sequence and a too short
+/// input.
+#[cfg(feature = "std")]
+#[test]
+fn test_fips140_2() {
+    use crate::fips140::{fips140_2_tests, Fips140Test, SAMPLE_LENGTH};
+    use crate::IntoEnumIterator;
+    use std::fs;
+    use std::path::Path;
+
+    let input = fs::read(Path::new(TEST_FILE_PATH).join("e.1e6.bin")).unwrap();
+    let data = BitVec::from(input.as_slice());
+
+    let result = fips140_2_tests(&data).unwrap();
+    assert_eq!(result.ones(), 10027);
+    assert_f64_eq!(round(result.poker_statistic(), 4), 7.6032);
+    assert_eq!(result.runs(false), [2502, 1190, 641, 312, 166, 152]);
+    assert_eq!(result.runs(true), [2429, 1266, 653, 300, 159, 157]);
+    assert_eq!(result.longest_run(), 16);
+    assert!(Fips140Test::iter().all(|test| result.passed(test)));
+    assert!(result.all_passed());
+
+    let data = BitVec::from([false; SAMPLE_LENGTH.get()].as_slice());
+    let result = fips140_2_tests(&data).unwrap();
+    assert_eq!(result.ones(), 0);
+    assert_eq!(result.runs(false), [0, 0, 0, 0, 0, 1]);
+    assert_eq!(result.longest_run(), SAMPLE_LENGTH.get());
+    assert!(Fips140Test::iter().all(|test| !result.passed(test)));
+    assert!(!result.all_passed());
+
+    let mut data = BitVec::from(input.as_slice());
+    data.crop(SAMPLE_LENGTH.get() - 1);
+    assert!(matches!(
+        fips140_2_tests(&data),
+        Err(Error::InvalidParameter(_))
+    ));
+}
+
+/// Test the entropy estimators of SP 800-90B on the binary expansion of e (reference values
+/// calculated with a separate script) and on degenerate inputs.
+#[cfg(feature = "std")]
+#[test]
+fn test_entropy_estimation() {
+    use crate::entropy_estimation::{
+        collision_estimate, estimate_entropy, most_common_value_estimate, MIN_LENGTH,
+    };
+    use std::fs;
+    use std::path::Path;
+
+    let input = fs::read(Path::new(TEST_FILE_PATH).join("e.1e6.bin")).unwrap();
+    let data = BitVec::from(input.as_slice());
+
+    let estimates = estimate_entropy(&data).unwrap();
+    assert_f64_eq!(round(estimates.shannon_entropy(), 9), 0.999999998);
+    let most_common_value = estimates.most_common_value();
+    assert_f64_eq!(round(most_common_value.probability_bound(), 6), 0.501317);
+    assert_f64_eq!(round(most_common_value.min_entropy(), 6), 0.996205);
+    let collision = estimates.collision();
+    assert_f64_eq!(round(collision.probability_bound(), 6), 0.528845);
+    assert_f64_eq!(round(collision.min_entropy(), 6), 0.919082);
+    assert_f64_eq!(estimates.min_entropy(), collision.min_entropy());
+
+    // constant: no entropy
+    let data = BitVec::from([true; 1000].as_slice());
+    let estimates = estimate_entropy(&data).unwrap();
+    assert_f64_eq!(estimates.shannon_entropy(), 0.0);
+    assert_f64_eq!(estimates.most_common_value().probability_bound(), 1.0);
+    assert_f64_eq!(estimates.collision().probability_bound(), 1.0);
+    assert_f64_eq!(estimates.min_entropy(), 0.0);
+
+    // alternating: balanced, but no collisions after 2 bits
+    let data = BitVec::from_ascii_str(&"01".repeat(500)).unwrap();
+    assert_f64_eq!(collision_estimate(&data).unwrap().min_entropy(), 1.0);
+    let most_common_value = most_common_value_estimate(&data).unwrap();
+    assert_f64_eq!(round(most_common_value.min_entropy(), 6), 0.886965);
+
+    let data = BitVec::from_ascii_str(&"1".repeat(MIN_LENGTH.get() - 1)).unwrap();
+    assert!(matches!(
+        estimate_entropy(&data),
+        Err(Error::InvalidParameter(_))
+    ));
+}
+
+/// Test that the sweeps of the serial test and the approximate entropy test give the same results
+/// as the single tests, and that invalid ranges are rejected.
+#[cfg(feature = "std")]
+#[test]
+fn test_serial_approximate_entropy_sweep() {
+    use crate::tests::approximate_entropy::{
+        approximate_entropy_sweep, approximate_entropy_test, ApproximateEntropyTestArg,
+    };
+    use crate::tests::serial::{serial_test, serial_test_sweep, SerialTestArg};
+    use std::fs;
+    use std::path::Path;
+
+    let input = fs::read(Path::new(TEST_FILE_PATH).join("e.1e6.bin")).unwrap();
+    let data = BitVec::from(input.as_slice());
+
+    let results = serial_test_sweep(&data, 2..=12).unwrap();
+    assert_eq!(results.len(), 11);
+    for (block_length, results) in results {
+        let expected = serial_test(&data, SerialTestArg::try_new(block_length).unwrap()).unwrap();
+        for (result, expected) in results.iter().zip(expected) {
+            assert!(
+                f64::abs(result.p_value() - expected.p_value()) < 1e-9,
+                "m = {block_length}: Expected {}, got {}",
+                expected.p_value(),
+                result.p_value()
+            );
+        }
+    }
+
+    let results = approximate_entropy_sweep(&data, 3..=10).unwrap();
+    assert_eq!(results.len(), 8);
+    for (block_length, result) in results {
+        let arg = ApproximateEntropyTestArg::try_new(block_length).unwrap();
+        let expected = approximate_entropy_test(&data, arg).unwrap();
+        assert!(
+            f64::abs(result.p_value() - expected.p_value()) < 1e-9,
+            "m = {block_length}: Expected {}, got {}",
+            expected.p_value(),
+            result.p_value()
+        );
+    }
+
+    // empty range, block length too small and too large for the input
+    #[allow(clippy::reversed_empty_ranges)]
+    let empty = 5..=4;
+    for range in [empty, 1..=4, 2..=17] {
+        assert!(matches!(
+            serial_test_sweep(&data, range),
+            Err(Error::InvalidParameter(_))
+        ));
+    }
+    assert!(matches!(
+        approximate_entropy_sweep(&data, 2..=14),
+        Err(Error::InvalidParameter(_))
+    ));
+}
+
+/// Test the linear complexity profile with the input of 2.10.8: the histogram is given there.
+#[cfg(feature = "std")]
+#[test]
+fn test_linear_complexity_profile() {
+    use crate::statistics::TestStatistics;
+    use crate::tests::linear_complexity::{
+        linear_complexity_profile, linear_complexity_test, LinearComplexityTestArg,
+    };
+    use std::fs;
+    use std::num::NonZero;
+    use std::path::Path;
+
+    let input = fs::read(Path::new(TEST_FILE_PATH).join("e.1e6.bin")).unwrap();
+    let data = BitVec::from(input.as_slice());
+    let arg = LinearComplexityTestArg::ManualBlockLength(NonZero::new(1000).unwrap());
+
+    let profile = linear_complexity_profile(&data, arg).unwrap();
+    assert_eq!(profile.block_length(), 1000);
+    assert_eq!(profile.complexities().len(), 1000);
+    assert_eq!(profile.histogram(), [11, 31, 116, 501, 258, 57, 26]);
+
+    // the first block, calculated independently of the test
+    assert_eq!(
+        profile.complexities()[0],
+        berlekamp_massey(&data.words, 1000, 0)
+    );
+
+    let t_values = profile.t_values();
+    assert_eq!(t_values.len(), 1000);
+    assert_eq!(t_values.iter().filter(|&&t_i| t_i > 2.5).count(), 26);
+
+    let result = linear_complexity_test(&data, arg).unwrap();
+    assert_f64_eq!(profile.result().p_value(), result.p_value());
+    assert!(matches!(
+        result.statistics(),
+        Some(TestStatistics::LinearComplexity {
+            histogram: [11, 31, 116, 501, 258, 57, 26],
+            ..
+        })
+    ));
+}
+
+/// Test the public LFSR API: the example of the Handbook of Applied Cryptography, p. 201, 6.32,
+/// and that the calculated LFSR generates a part of e.
+#[cfg(feature = "std")]
+#[test]
+fn test_shortest_lfsr() {
+    use crate::lfsr::{linear_complexity, shortest_lfsr};
+    use std::fs;
+    use std::path::Path;
+
+    let data = BitVec::from_ascii_str("001101110").unwrap();
+    let lfsr = shortest_lfsr(&data, 0..9);
+    assert_eq!(lfsr.length(), 5);
+    // C(D) = 1 + D^3 + D^5
+    assert_eq!(
+        lfsr.connection_polynomial(),
+        [true, false, false, true, false, true]
+    );
+    assert_eq!(linear_complexity(&data, 0..0), 0);
+    assert_eq!(shortest_lfsr(&data, 3..3).connection_polynomial(), [true]);
+
+    // a range that does not start at a word boundary and spans multiple words
+    let input = fs::read(Path::new(TEST_FILE_PATH).join("e.1e6.bin")).unwrap();
+    let data = BitVec::from(input.as_slice());
+    let range = 77..477;
+    let lfsr = shortest_lfsr(&data, range.clone());
+    assert_eq!(lfsr.length(), 200);
+
+    let bits = range.map(|idx| data.get(idx).unwrap()).collect::<Vec<_>>();
+    let c = lfsr.connection_polynomial();
+    for j in lfsr.length()..bits.len() {
+        let next = (1..=lfsr.length()).fold(false, |sum, i| sum ^ (c[i] & bits[j - i]));
+        assert_eq!(next, bits[j], "bit {j} is not generated by the LFSR");
+    }
+
+    assert_eq!(
+        linear_complexity(&data, 0..1000),
+        berlekamp_massey(&data.words, 1000, 0)
+    );
+}
+
+/// Test the random excursions tests with an extended state range: the results of the default
+/// states must not change, the additional states are appended at both ends.
+#[cfg(feature = "std")]
+#[test]
+fn test_random_excursions_state_range() {
+    use crate::tests::random_excursions::{
+        random_excursions_test, RandomExcursionsTestArg, MAX_STATE,
+    };
+    use crate::tests::random_excursions_variant::{
+        random_excursions_variant_test, RandomExcursionsVariantTestArg,
+    };
+    use crate::ResultStatus;
+    use std::fs;
+    use std::path::Path;
+
+    assert!(RandomExcursionsTestArg::try_new(0).is_err());
+    assert!(RandomExcursionsTestArg::try_new(MAX_STATE + 1).is_err());
+    assert!(RandomExcursionsVariantTestArg::try_new(0).is_err());
+    assert!(RandomExcursionsVariantTestArg::try_new(MAX_STATE + 1).is_err());
+
+    let input = fs::read(Path::new(TEST_FILE_PATH).join("e.1e6.bin")).unwrap();
+    let data = BitVec::from(input);
+
+    let default = random_excursions_test(&data, Default::default()).unwrap();
+    let arg = RandomExcursionsTestArg::try_new(16).unwrap();
+    let extended = random_excursions_test(&data, arg).unwrap();
+    assert_eq!(default.len(), 8);
+    assert_eq!(extended.len(), 32);
+    assert_eq!(extended[0].comment(), Some("x = -16"));
+    assert_eq!(extended[31].comment(), Some("x = +16"));
+    for (default, extended) in default.iter().zip(&extended[12..20]) {
+        assert_eq!(default.comment(), extended.comment());
+        assert_f64_eq!(default.p_value(), extended.p_value());
+    }
+    assert!(extended.iter().all(|result| {
+        result.status() == ResultStatus::Applicable && (0.0..=1.0).contains(&result.p_value())
+    }));
+
+    let default = random_excursions_variant_test(&data, Default::default()).unwrap();
+    let arg = RandomExcursionsVariantTestArg::try_new(16).unwrap();
+    let extended = random_excursions_variant_test(&data, arg).unwrap();
+    assert_eq!(default.len(), 18);
+    assert_eq!(extended.len(), 32);
+    for (default, extended) in default.iter().zip(&extended[7..25]) {
+        assert_eq!(default.comment(), extended.comment());
+        assert_f64_eq!(default.p_value(), extended.p_value());
+    }
+
+    // a single state
+    let arg = RandomExcursionsVariantTestArg::try_new(1).unwrap();
+    let single = random_excursions_variant_test(&data, arg).unwrap();
+    assert_eq!(single.len(), 2);
+    assert_f64_eq!(single[0].p_value(), default[8].p_value());
+    assert_f64_eq!(single[1].p_value(), default[9].p_value());
+}
+
+/// Test that each result of the tests with multiple results is labelled, and that tests with a
+/// single result have no label.
+#[cfg(feature = "std")]
+#[test]
+fn test_result_labels() {
+    use crate::tests::cumulative_sums::{cumulative_sums_test, CusumDirection};
+    use crate::tests::random_excursions::random_excursions_test;
+    use crate::tests::serial::{serial_test, SerialKind, SerialTestArg};
+    use crate::tests::template_matching::non_overlapping::{
+        non_overlapping_template_matching_test, NonOverlappingTemplateTestArgs,
+    };
+    use crate::tests::template_matching::TemplateArg;
+    use crate::ResultLabel;
+    use std::fs;
+    use std::path::Path;
+
+    let input = fs::read(Path::new(TEST_FILE_PATH).join("e.1e6.bin")).unwrap();
+    let data = BitVec::from(input);
+
+    assert_eq!(
+        crate::tests::frequency::frequency_test(&data)
+            .unwrap()
+            .label(),
+        None
+    );
+
+    let [forward, backward] = cumulative_sums_test(&data).unwrap();
+    assert_eq!(
+        forward.label(),
+        Some(ResultLabel::CumulativeSums(CusumDirection::Forward))
+    );
+    assert_eq!(
+        backward.label(),
+        Some(ResultLabel::CumulativeSums(CusumDirection::Backward))
+    );
+    assert_eq!(forward.label().unwrap().to_string(), "forward");
+
+    let [delta_1, delta_2] = serial_test(&data, SerialTestArg::try_new(5).unwrap()).unwrap();
+    assert_eq!(
+        delta_1.label(),
+        Some(ResultLabel::Serial(SerialKind::Delta1))
+    );
+    assert_eq!(
+        delta_2.label(),
+        Some(ResultLabel::Serial(SerialKind::Delta2))
+    );
+    assert_eq!(delta_2.label().unwrap().to_string(), "delta2");
+
+    let results = random_excursions_test(&data, Default::default()).unwrap();
+    let states = results
+        .iter()
+        .map(|result| match result.label() {
+            Some(ResultLabel::ExcursionState(state)) => state,
+            label => panic!("unexpected label {label:?}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(states, [-4, -3, -2, -1, 1, 2, 3, 4]);
+    // the label is the same as the comment
+    for result in &results {
+        assert_eq!(
+            result.label().map(|label| label.to_string()).as_deref(),
+            result.comment()
+        );
+    }
+
+    let templates = [0b001 << (usize::BITS - 3), 0b110 << (usize::BITS - 3)];
+    let arg = NonOverlappingTemplateTestArgs::try_new_with_custom_template(
+        TemplateArg::try_new_with_custom_templates(&templates, 3).unwrap(),
+        8,
+    )
+    .unwrap();
+    let results = non_overlapping_template_matching_test(&data, arg).unwrap();
+    assert_eq!(
+        results[1].label(),
+        Some(ResultLabel::Template {
+            index: 1,
+            template: templates[1],
+            template_len: 3
+        })
+    );
+    assert_eq!(results[0].label().unwrap().to_string(), "template 001");
+    assert_eq!(results[1].label().unwrap().to_string(), "template 110");
+}
+
+/// Test the overlapping template matching test with custom templates: the matches must be the
+/// same as counted bit by bit, and the all-ones template must give the same result as the default.
+#[cfg(feature = "std")]
+#[test]
+fn test_overlapping_custom_template() {
+    use crate::tests::template_matching::overlapping::{
+        overlapping_template_matching_test, OverlappingTemplateTestArgs,
+    };
+    use crate::TestStatistics;
+    use std::fs;
+    use std::path::Path;
+
+    let input = fs::read(Path::new(TEST_FILE_PATH).join("e.1e6.bin")).unwrap();
+    let data = BitVec::from(input);
+    let bits = data.iter_bits().collect::<Vec<_>>();
+
+    // an aperiodic and a periodic template, the latter spanning words in some blocks
+    for (template, template_length) in [(0b000000001, 9), (0b1010101010, 10)] {
+        let arg = OverlappingTemplateTestArgs::try_new_with_custom_template(
+            template << (usize::BITS as usize - template_length),
+            template_length,
+            1032,
+            6,
+        )
+        .unwrap();
+        let result = overlapping_template_matching_test(&data, arg).unwrap();
+
+        let template_bits = (0..template_length)
+            .map(|i| template & (1 << (template_length - 1 - i)) != 0)
+            .collect::<Vec<_>>();
+        let mut occurrences = [0_usize; 6];
+        for block in bits.chunks_exact(1032) {
+            let matches = block
+                .windows(template_length)
+                .filter(|window| *window == template_bits.as_slice())
+                .count();
+            occurrences[matches.min(5)] += 1;
+        }
+
+        let block_count = bits.len() / 1032;
+        let pis = calculate_custom_template_pis(
+            template << (usize::BITS as usize - template_length),
+            1032,
+            template_length,
+            6,
+        );
+        let expected_chi = occurrences
+            .iter()
+            .zip(pis.iter())
+            .map(|(&v_i, &pi_i)| {
+                let expected = block_count as f64 * pi_i;
+                f64::powi(v_i as f64 - expected, 2) / expected
+            })
+            .sum::<f64>();
+
+        match result.statistics() {
+            Some(TestStatistics::OverlappingTemplateMatching {
+                block_count: count,
+                chi_square,
+            }) => {
+                assert_eq!(count, block_count);
+                assert!(f64::abs(chi_square - expected_chi) < 1e-9);
+            }
+            statistics => panic!("unexpected statistics {statistics:?}"),
+        }
+    }
+
+    let all_ones =
+        OverlappingTemplateTestArgs::try_new_with_custom_template(usize::MAX, 9, 1032, 6).unwrap();
+    // the bits after the template length are ignored
+    assert_eq!(
+        all_ones.custom_template(),
+        Some(0b111111111 << (usize::BITS - 9))
+    );
+    let custom = overlapping_template_matching_test(&data, all_ones).unwrap();
+    let default = overlapping_template_matching_test(&data, Default::default()).unwrap();
+    assert!(f64::abs(custom.p_value() - default.p_value()) < 1e-9);
+}
+
+/// Test that invalid test arguments are rejected with an error stating the invalid value and the
+/// allowed values, and that the deprecated constructors still work.
+#[cfg(feature = "std")]
+#[test]
+#[allow(deprecated)]
+fn test_arg_errors() {
+    use crate::tests::approximate_entropy::ApproximateEntropyTestArg;
+    use crate::tests::autocorrelation::AutocorrelationTestArg;
+    use crate::tests::serial::SerialTestArg;
+    use crate::tests::template_matching::non_overlapping::NonOverlappingTemplateTestArgs;
+    use crate::tests::template_matching::overlapping::OverlappingTemplateTestArgs;
+    use crate::tests::template_matching::TemplateArg;
+    use crate::ArgError;
+
+    let message = |error: ArgError| error.to_string();
+
+    assert_eq!(
+        message(TemplateArg::try_new(1).unwrap_err()),
+        "The template length must be between 2 and 31, is: 1"
+    );
+    assert_eq!(
+        message(TemplateArg::try_new_with_custom_templates(&[], 32).unwrap_err()),
+        "The template length must be between 2 and 31, is: 32"
+    );
+    assert_eq!(
+        message(NonOverlappingTemplateTestArgs::try_new(9, 100).unwrap_err()),
+        "The block count must be between 1 and 99, is: 100"
+    );
+    assert_eq!(
+        message(OverlappingTemplateTestArgs::try_new(22, 1032, 6).unwrap_err()),
+        "The template length must be between 2 and 21, is: 22"
+    );
+    assert_eq!(
+        message(OverlappingTemplateTestArgs::try_new_nist_behaviour(8).unwrap_err()),
+        "The template length must be one of 9 or 10, is: 8"
+    );
+    assert_eq!(
+        message(AutocorrelationTestArg::try_new(0).unwrap_err()),
+        "The shift must be at least 1, is: 0"
+    );
+    assert_eq!(
+        SerialTestArg::try_new(u8::MAX).unwrap_err(),
+        ArgError::OutOfRange {
+            name: "block length",
+            value: 255,
+            min: 2,
+            max: usize::BITS as usize,
+        }
+    );
+    assert_eq!(
+        ApproximateEntropyTestArg::try_new(1).unwrap_err(),
+        ArgError::OutOfRange {
+            name: "block length",
+            value: 1,
+            min: 2,
+            max: usize::BITS as usize,
+        }
+    );
+
+    // the deprecated constructors only lose the reason
+    assert!(SerialTestArg::new(1).is_none());
+    assert_eq!(SerialTestArg::new(16).unwrap().block_length(), 16);
+    assert!(OverlappingTemplateTestArgs::new_nist_behaviour(10).is_some());
+    assert!(NonOverlappingTemplateTestArgs::new(9, 0).is_none());
+}
+
+/// Test the bit-index helpers of the words: the first bit of the sequence is the most significant
+/// bit of the first word, and bytes are in sequence order on all targets.
+#[test]
+fn test_word_order() {
+    use crate::bitvec::word::{self, WORD_BITS, WORD_BYTES};
+
+    assert_eq!(word::word_index(WORD_BITS + 3), 1);
+    assert_eq!(word::bit_in_word(WORD_BITS + 3), 3);
+    assert_eq!(word::bit_mask(0), 1 << (WORD_BITS - 1));
+    assert_eq!(word::bit_mask(WORD_BITS - 1), 1);
+    assert!(word::get_bit(word::bit_mask(5), 5));
+    assert!(!word::get_bit(word::bit_mask(5), 6));
+    assert_eq!(word::leading_mask(0), 0);
+    assert_eq!(word::leading_mask(3), 0b111 << (WORD_BITS - 3));
+    assert_eq!(word::leading_mask(WORD_BITS), usize::MAX);
+
+    let bytes: [u8; WORD_BYTES] = std::array::from_fn(|i| i as u8 + 1);
+    let value = word::from_bytes(bytes);
+    assert_eq!(value >> (WORD_BITS - 8), 1);
+    assert_eq!(word::to_bytes(value), bytes);
+
+    let bitvec = BitVec::from(bytes.as_slice());
+    assert_eq!(&*bitvec.words, &[value]);
+}
+
+/// Test that the runtime self-test passes.
+#[cfg(feature = "std")]
+#[test]
+fn test_self_test() {
+    crate::self_test().unwrap();
+}
+
+/// Test the sliding-window pattern count of the serial and approximate entropy tests against the
+/// pattern of each block read on its own, for both the per-thread and the shared frequency tables.
+#[test]
+fn test_count_patterns() {
+    use crate::tests::serial_and_approximate_entropy::count_patterns;
+
+    let mut next_bit = xorshift_bits();
+
+    // lengths shorter than, equal to and longer than a word and a chunk, and with partial words
+    for len in [10, 63, 64, 65, 1000, 65536, 65600, 140_001] {
+        let bits = (0..len).map(|_| next_bit()).collect::<Vec<_>>();
+        let data = BitVec::from(bits.as_slice());
+
+        for block_length in [2, 3, 9, 16, 17, 20] {
+            let mut expected = vec![0_usize; 1 << block_length];
+            for start in 0..len {
+                let pattern = (0..block_length).fold(0, |pattern, i| {
+                    (pattern << 1) | bits[(start + i) % len] as usize
+                });
+                expected[pattern] += 1;
+            }
+
+            assert_eq!(
+                count_patterns(&data, block_length as u8).unwrap().as_ref(),
+                expected.as_slice(),
+                "length {len}, block length {block_length}"
+            );
+        }
+    }
+}
+
+/// Test the rank categories of the binary matrix rank test against a Gaussian elimination on
+/// single bits, for square and non-square matrices.
+#[cfg(feature = "std")]
+#[test]
+fn test_binary_matrix_rank_categories() {
+    use crate::statistics::TestStatistics;
+    use crate::tests::binary_matrix_rank::{binary_matrix_rank_test, BinaryMatrixRankTestArg};
+
+    let mut next_bit = xorshift_bits();
+
+    for (rows, cols) in [(32, 32), (6, 6), (5, 6), (64, 64), (3, 2)] {
+        let arg = BinaryMatrixRankTestArg::try_new(rows, cols).unwrap();
+        let count = arg.min_matrix_count().max(200);
+        let bits = (0..(count * rows * cols))
+            .map(|_| next_bit())
+            .collect::<Vec<_>>();
+
+        let mut expected = [0_usize; 3];
+        for matrix in bits.chunks_exact(rows * cols) {
+            let mut matrix = matrix
+                .chunks_exact(cols)
+                .map(<[bool]>::to_vec)
+                .collect::<Vec<_>>();
+            let mut rank = 0;
+            for col in 0..cols {
+                let Some(pivot) = (rank..rows).find(|&row| matrix[row][col]) else {
+                    continue;
+                };
+                matrix.swap(rank, pivot);
+                let pivot = matrix[rank].clone();
+                for row in &mut matrix[(rank + 1)..] {
+                    if row[col] {
+                        row.iter_mut()
+                            .zip(&pivot)
+                            .for_each(|(bit, pivot)| *bit ^= pivot);
+                    }
+                }
+                rank += 1;
+            }
+
+            let full_rank = rows.min(cols);
+            expected[usize::min(full_rank - rank, 2)] += 1;
+        }
+
+        let statistics = binary_matrix_rank_test(&BitVec::from(bits.as_slice()), arg)
+            .map(|result| result.statistics());
+        assert!(
+            matches!(
+                statistics,
+                Ok(Some(TestStatistics::BinaryMatrixRank {
+                    full_rank_count,
+                    full_rank_minus_one_count,
+                    remaining_count,
+                    ..
+                })) if [full_rank_count, full_rank_minus_one_count, remaining_count] == expected
+            ),
+            "{rows}x{cols}: expected {expected:?}, got {statistics:?}"
+        );
+    }
+}
+
+/// Test the statistic of Maurer's universal statistical test, scanned in chunks in parallel,
+/// against the sequential scan of SP 800-22.
+#[cfg(feature = "std")]
+#[test]
+fn test_maurers_universal_statistical_chunks() {
+    use crate::statistics::TestStatistics;
+    use crate::tests::maurers_universal_statistical::maurers_universal_statistical_test;
+
+    let mut next_bit = xorshift_bits();
+
+    // block lengths 2, 5 and 7, with 20 to 32 chunks
+    for len in [10_000, 200_000, 2_000_000] {
+        let bits = (0..len).map(|_| next_bit()).collect::<Vec<_>>();
+        let statistics = maurers_universal_statistical_test(&BitVec::from(bits.as_slice()))
+            .unwrap()
+            .statistics();
+        let Some(TestStatistics::MaurersUniversalStatistical {
+            block_length,
+            init_block_count,
+            test_block_count,
+            f_n,
+            ..
+        }) = statistics
+        else {
+            panic!("{len}: no statistics: {statistics:?}");
+        };
+
+        let blocks = bits
+            .chunks_exact(block_length)
+            .map(|block| {
+                block
+                    .iter()
+                    .fold(0, |value, &bit| (value << 1) | bit as usize)
+            })
+            .collect::<Vec<_>>();
+        let mut table = vec![0; 1 << block_length];
+        for (block_idx, &block) in blocks[..init_block_count].iter().enumerate() {
+            table[block] = block_idx + 1;
+        }
+        let mut sum = 0.0;
+        for (block_idx, &block) in blocks.iter().enumerate().skip(init_block_count) {
+            sum += f64::log2((block_idx + 1 - table[block]) as f64);
+            table[block] = block_idx + 1;
+        }
+        let expected = sum / test_block_count as f64;
+
+        assert!(
+            f64::abs(f_n - expected) <= 1e-12 * expected,
+            "{len}: expected f_n = {expected}, got {f_n}"
+        );
+    }
+}
+
+/// Test the sum of f64 values: with the feature `high-precision`, the rounding errors are
+/// compensated, without it, the result is the plain sum in the same order.
+#[test]
+fn test_f64_sum() {
+    use crate::internals::{F64Sum, HIGH_PRECISION};
+
+    let values = [1.0, 1e100, 1.0, -1e100];
+    let expected = if HIGH_PRECISION { 2.0 } else { 0.0 };
+
+    assert_eq!(values.into_iter().sum::<F64Sum>().value(), expected);
+
+    // merging partial sums keeps their compensations
+    let first = values[..2].iter().copied().sum::<F64Sum>();
+    let merged = first + values[2..].iter().copied().sum::<F64Sum>();
+    assert_eq!(merged.value(), expected);
+
+    let values = (1..=1000).map(|i| 1.0 / i as f64).collect::<Vec<_>>();
+    let sum = values.iter().copied().sum::<F64Sum>().value();
+    if HIGH_PRECISION {
+        // the harmonic number H_1000, correctly rounded
+        assert_eq!(sum, 7.485470860550345);
+    } else {
+        assert_eq!(sum, values.iter().sum::<f64>());
+    }
+}
+
+/// Test the calculations of the feature `high-precision` against the examples of SP 800-22 and the
+/// calculations without the feature, and check that they do not lose precision for large counts.
+/// The functions are compiled without the feature as well, only the tests do not use them.
+#[test]
+fn test_high_precision_statistics() {
+    use crate::tests::frequency_block::half_chi_square_exact;
+    use crate::tests::serial_and_approximate_entropy::approximate_entropy::{
+        chi_square_exact, pair_divergence,
+    };
+    use crate::tests::serial_and_approximate_entropy::count_patterns;
+    use crate::tests::serial_and_approximate_entropy::serial::scaled_psi_squared;
+    use std::f64::consts::LN_2;
+
+    // frequency test within a block, 2.2.8: 0110011010 with M = 3 has chi^2 = 1
+    assert_eq!(half_chi_square_exact(&[2, 1, 2], 3), 0.5);
+    // a deviation of 1 in blocks of 2^40 bits
+    let counts = [(1 << 39) + 1, 1 << 39];
+    assert_eq!(half_chi_square_exact(&counts, 1 << 40), f64::powi(2.0, -39));
+
+    // serial test, 2.11.8: 0011011101 with m = 3 has psi^2 = 2.8
+    let data = BitVec::from_ascii_str("0011011101").unwrap();
+    let frequency = count_patterns(&data, 3).unwrap();
+    assert_eq!(scaled_psi_squared(&frequency, 10), Some(28));
+    // the float calculation rounds the squares: psi^2 would be 0
+    let frequency = [(1 << 40) + 1, (1 << 40) - 1, 1 << 40, 1 << 40];
+    assert_eq!(scaled_psi_squared(&frequency, 1 << 42), Some(8));
+    // too large for an i128
+    assert_eq!(scaled_psi_squared(&[usize::MAX, 0], usize::MAX), None);
+
+    // approximate entropy test, 2.12.8: 0100110101 with m = 3 has chi^2 = 20 * (ln(2) - 0.190954)
+    let data = BitVec::from_ascii_str("0100110101").unwrap();
+    let frequency = count_patterns(&data, 4).unwrap();
+    let chi = chi_square_exact(&frequency);
+    assert!(f64::abs(chi - 20.0 * (LN_2 - 0.190954)) < 1e-5, "{chi}");
+    // uniform frequencies: exactly 0, while phi(m) - phi(m + 1) is only ln(2) up to rounding
+    assert_eq!(chi_square_exact(&vec![1 << 40; 1 << 10]), 0.0);
+
+    // g(t) against the direct formula where it has no cancellation, and at the boundaries
+    let direct = |t: f64| ((1.0 + t) * f64::ln(1.0 + t) + (1.0 - t) * f64::ln(1.0 - t)) / 2.0;
+    for t in [0.2, 0.3, 0.4999, 0.5, 0.7, 0.99] {
+        let g = pair_divergence(t);
+        assert!(f64::abs(g - direct(t)) <= 1e-14 * g, "{t}: {g}");
+    }
+    assert_eq!(pair_divergence(0.0), 0.0);
+    assert_eq!(pair_divergence(1e-9), 1e-18 / 2.0);
+    assert_eq!(pair_divergence(1.0), LN_2);
+
+    // agrees with the calculation without the feature on a random sequence
+    let mut next_bit = xorshift_bits();
+    let bits = (0..100_000).map(|_| next_bit()).collect::<Vec<_>>();
+    let data = BitVec::from(bits.as_slice());
+    let phi = |frequency: &[usize]| {
+        frequency
+            .iter()
+            .filter(|&&v| v != 0)
+            .map(|&v| {
+                let pi = v as f64 / bits.len() as f64;
+                pi * f64::ln(pi)
+            })
+            .sum::<f64>()
+    };
+    for m in [2, 5, 10] {
+        let frequency = count_patterns(&data, m + 1).unwrap();
+        let folded = count_patterns(&data, m).unwrap();
+        let expected = 2.0 * bits.len() as f64 * (LN_2 - (phi(&folded) - phi(&frequency)));
+        let chi = chi_square_exact(&frequency);
+        assert!(
+            f64::abs(chi - expected) <= 1e-6 * expected,
+            "{m}: {chi} != {expected}"
+        );
+    }
+}
+
+/// Test the run-length histogram against counting the runs bit by bit, and the expected counts
+/// against the mean over all sequences of a short length.
+#[cfg(feature = "std")]
+#[test]
+fn test_run_length_histogram() {
+    use crate::tests::run_length_histogram::run_length_histogram;
+
+    let mut next_bit = xorshift_bits();
+
+    // with partial words, and with a long run over several words
+    let mut long_run = (0..300).map(|_| next_bit()).collect::<Vec<_>>();
+    long_run.splice(100..100, [true; 150]);
+    let inputs = [1, 2, 63, 64, 65, 1000, 100_001]
+        .map(|len| (0..len).map(|_| next_bit()).collect::<Vec<_>>())
+        .into_iter()
+        .chain([long_run, vec![false; 130], vec![true; 64]]);
+    for bits in inputs {
+        let mut expected = [vec![], vec![]];
+        for run in bits.chunk_by(|a, b| a == b) {
+            let counts = &mut expected[run[0] as usize];
+            counts.resize(counts.len().max(run.len()), 0);
+            counts[run.len() - 1] += 1;
+        }
+
+        let histogram = run_length_histogram(&BitVec::from(bits.as_slice())).unwrap();
+        for bit in [false, true] {
+            assert_eq!(
+                histogram.counts(bit),
+                expected[bit as usize],
+                "{}",
+                bits.len()
+            );
+            assert_eq!(histogram.longest_run(bit), expected[bit as usize].len());
+        }
+    }
+    assert!(run_length_histogram(&BitVec::new()).is_err());
+
+    // the expected counts are the mean over all sequences of the length
+    for len in 1..=10_usize {
+        let mut mean = vec![0.0; len + 2];
+        for value in 0..(1_u32 << len) {
+            let bits = (0..len).map(|i| value >> i & 1 == 1).collect::<Vec<_>>();
+            let histogram = run_length_histogram(&BitVec::from(bits.as_slice())).unwrap();
+            for (length, mean) in mean.iter_mut().enumerate() {
+                *mean += histogram.count(false, length) as f64 / (1 << len) as f64;
+            }
+        }
+
+        let histogram = run_length_histogram(&BitVec::from(vec![false; len].as_slice())).unwrap();
+        for (length, &mean) in mean.iter().enumerate() {
+            assert!(
+                f64::abs(histogram.expected_count(length) - mean) < 1e-12,
+                "{len}: {length}"
+            );
+        }
+        let sum = mean.iter().sum::<f64>();
+        assert!(
+            f64::abs(histogram.expected_run_count() - sum) < 1e-12,
+            "{len}"
+        );
+    }
+
+    // a random sequence is close to the expected distribution, alternating bits are not
+    let bits = (0..1_000_000).map(|_| next_bit()).collect::<Vec<_>>();
+    let histogram = run_length_histogram(&BitVec::from(bits.as_slice())).unwrap();
+    assert_eq!(histogram.chi_square_bins(), 16);
+    for bit in [false, true] {
+        assert!(histogram.chi_square(bit).unwrap() < 50.0);
+    }
+    let bits = (0..1_000_000).map(|i| i % 2 == 0).collect::<Vec<_>>();
+    let histogram = run_length_histogram(&BitVec::from(bits.as_slice())).unwrap();
+    assert!(histogram.chi_square(true).unwrap() > 1e5);
+}
+
+/// Test the count of ones at each bit position of the bytes against counting them bit by bit, and
+/// that only a stuck bit position is found as biased.
+#[cfg(feature = "std")]
+#[test]
+fn test_bit_position_bias() {
+    use crate::diagnostics::{bit_position_bias, BYTE_BITS};
+
+    let mut next_bit = xorshift_bits();
+
+    // with partial words and trailing bits that do not fill a byte
+    for len in [8, 15, 64, 71, 127, 128, 1003, 100_000] {
+        let bits = (0..len).map(|_| next_bit()).collect::<Vec<_>>();
+        let mut expected = [0; BYTE_BITS];
+        for byte in bits.chunks_exact(BYTE_BITS) {
+            for (ones, &bit) in expected.iter_mut().zip(byte) {
+                *ones += bit as usize;
+            }
+        }
+
+        let bias = bit_position_bias(&BitVec::from(bits.as_slice())).unwrap();
+        assert_eq!(bias.byte_count(), len / BYTE_BITS);
+        assert_eq!(bias.ones(), expected, "{len}");
+    }
+    assert!(bit_position_bias(&BitVec::from(vec![true; 7].as_slice())).is_err());
+    assert!(bit_position_bias(&BitVec::new()).is_err());
+
+    // a random sequence has no biased position, a stuck bit is found
+    let mut bits = (0..1_000_000).map(|_| next_bit()).collect::<Vec<_>>();
+    let bias = bit_position_bias(&BitVec::from(bits.as_slice())).unwrap();
+    for (frequency, chi_square) in bias.ones_frequencies().into_iter().zip(bias.chi_squares()) {
+        assert!(f64::abs(frequency - 0.5) < 0.01);
+        assert!(chi_square < 20.0);
+    }
+    assert!(bias.total_chi_square() < 40.0);
+
+    bits.iter_mut()
+        .skip(5)
+        .step_by(BYTE_BITS)
+        .for_each(|bit| *bit = true);
+    let bias = bit_position_bias(&BitVec::from(bits.as_slice())).unwrap();
+    assert_eq!(bias.most_biased_position(), 5);
+    assert_eq!(bias.ones_frequencies()[5], 1.0);
+    assert_eq!(bias.chi_squares()[5], 125_000.0);
+}
@@ -4,26 +4,43 @@
 use super::{assert_f64_eq, round, TEST_FILE_PATH};
 use crate::bitvec::BitVec;
 use crate::tests::approximate_entropy::{approximate_entropy_test, ApproximateEntropyTestArg};
-use crate::tests::binary_matrix_rank::binary_matrix_rank_test;
+#[cfg(feature = "std")]
+use crate::tests::autocorrelation::{
+    autocorrelation_test, count_differences, AutocorrelationTestArg,
+};
+#[cfg(feature = "std")]
+use crate::tests::binary_matrix_rank::{binary_matrix_rank_test, BinaryMatrixRankTestArg};
 use crate::tests::cumulative_sums::{cumulative_sums_test, cusum_test_internal};
 use crate::tests::frequency::frequency_test;
 use crate::tests::frequency_block::{frequency_block_test, FrequencyBlockTestArg};
+#[cfg(feature = "std")]
+use crate::tests::lempel_ziv::{count_distinct_words, lempel_ziv_test};
+#[cfg(feature = "std")]
 use crate::tests::linear_complexity::{linear_complexity_test, LinearComplexityTestArg};
-use crate::tests::longest_run_of_ones::longest_run_of_ones_test;
+#[cfg(feature = "std")]
+use crate::tests::longest_run_of_ones::{longest_run_of_ones_test, LongestRunOfOnesTestArg};
+#[cfg(feature = "std")]
 use crate::tests::maurers_universal_statistical::maurers_universal_statistical_test;
+#[cfg(feature = "std")]
 use crate::tests::random_excursions::random_excursions_test;
+#[cfg(feature = "std")]
 use crate::tests::random_excursions_variant::random_excursions_variant_test;
 use crate::tests::runs::runs_test;
 use crate::tests::serial::{serial_test, SerialTestArg};
-use crate::tests::spectral_dft::spectral_dft_test;
+#[cfg(feature = "std")]
+use crate::tests::spectral_dft::{spectral_dft_test, PeakThreshold, SpectralDftTestArg};
+#[cfg(feature = "std")]
 use crate::tests::template_matching::non_overlapping::{
     non_overlapping_template_matching_test, NonOverlappingTemplateTestArgs, DEFAULT_BLOCK_COUNT,
 };
+#[cfg(feature = "std")]
 use crate::tests::template_matching::overlapping::{
     overlapping_template_matching_test, OverlappingTemplateTestArgs,
 };
+#[cfg(feature = "std")]
 use crate::tests::template_matching::TemplateArg;
-use crate::Error;
+use crate::statistics::TestStatistics;
+use crate::{ArgError, Error};
 use crate::DEFAULT_THRESHOLD;
 use std::fs;
 use std::num::NonZero;
@@ -49,6 +66,12 @@ fn test_frequency_test_1() {
     assert!(output.passed(DEFAULT_THRESHOLD));
 
     assert_f64_eq!(round(output.p_value, 6), 0.527089);
+
+    let Some(TestStatistics::Frequency { sum, s_obs }) = output.statistics() else {
+        panic!("Wrong statistics: {:?}", output.statistics());
+    };
+    assert_eq!(sum, 2);
+    assert_f64_eq!(round(s_obs, 6), 0.632456);
 }
 
 /// Test the frequency test (no.1) - input and expected output from 2.1.8
@@ -79,6 +102,18 @@ fn test_frequency_block_test_1() {
     assert!(output.passed(DEFAULT_THRESHOLD));
 
     assert_f64_eq!(round(output.p_value, 6), 0.801252);
+
+    let Some(TestStatistics::FrequencyWithinABlock {
+        block_length,
+        block_count,
+        chi_square,
+    }) = output.statistics()
+    else {
+        panic!("Wrong statistics: {:?}", output.statistics());
+    };
+    assert_eq!(block_length, 3);
+    assert_eq!(block_count, 3);
+    assert_f64_eq!(round(chi_square, 6), 1.0);
 }
 
 /// Test the frequency within a block test (no. 2) - input and expected output from 2.2.8
@@ -127,12 +162,13 @@ fn test_runs_test_2() {
 }
 
 /// Test the longest run of ones in a block test (no. 4) - input and expected output from 2.4.8
+#[cfg(feature = "std")]
 #[test]
 fn test_longest_run_of_ones() {
     let input = BitVec::from_ascii_str("11001100000101010110110001001100111000000000001001001101010100010001001111010110100000001101011111001100111001101101100010110010")
         .unwrap();
 
-    let output = longest_run_of_ones_test(&input);
+    let output = longest_run_of_ones_test(&input, LongestRunOfOnesTestArg::default());
     result_checker(&output);
 
     let output = output.unwrap();
@@ -141,12 +177,64 @@ fn test_longest_run_of_ones() {
     // the expected value differs slightly from the textbook values because some constants
     // were recalculated with higher precision.
     assert_f64_eq!(round(output.p_value, 6), 0.180609);
+
+    // the configuration that is chosen automatically
+    let output = longest_run_of_ones_test(&input, LongestRunOfOnesTestArg::Block8).unwrap();
+    assert_f64_eq!(round(output.p_value, 6), 0.180609);
+
+    // too short for M = 128
+    assert!(matches!(
+        longest_run_of_ones_test(&input, LongestRunOfOnesTestArg::Block128),
+        Err(Error::InvalidParameter(_))
+    ));
+}
+
+/// Test the longest run of ones in a block test (no. 4) with each configuration on the same
+/// input. The automatic choice for 10^6 bits is M = 10^4, see 2.4.2.
+#[cfg(feature = "std")]
+#[test]
+fn test_longest_run_of_ones_configurations() {
+    let file_path = Path::new(TEST_FILE_PATH).join("e.1e6.bin");
+    let input = fs::read(file_path).unwrap();
+    let input = BitVec::from(input.as_slice());
+
+    for (test_arg, block_length, expected) in [
+        (
+            LongestRunOfOnesTestArg::ChooseAutomatically,
+            10_000,
+            0.718366,
+        ),
+        (LongestRunOfOnesTestArg::Block10000, 10_000, 0.718366),
+        (LongestRunOfOnesTestArg::Block128, 128, 0.152422),
+        (LongestRunOfOnesTestArg::Block8, 8, 0.393580),
+    ] {
+        let output = longest_run_of_ones_test(&input, test_arg);
+        result_checker(&output);
+
+        let output = output.unwrap();
+        assert_f64_eq!(round(output.p_value, 6), expected);
+        assert!(matches!(
+            output.statistics(),
+            Some(TestStatistics::LongestRunOfOnes { block_length: b, .. }) if b == block_length
+        ));
+    }
+
+    assert_eq!(
+        LongestRunOfOnesTestArg::try_new(128),
+        Ok(LongestRunOfOnesTestArg::Block128)
+    );
+    assert_eq!(
+        LongestRunOfOnesTestArg::try_new(64)
+            .unwrap_err()
+            .to_string(),
+        "The block length must be one of 8, 128 or 10000, is: 64"
+    );
 }
 
 /// Test the binary matrix rank test (no. 5) - input and expected output from 2.5.8.
-/// The values from 2.5.4 cannot be used here, because they would necessitate bitwise-matrices,
-/// while the implementation only supports byte-wise matrices (because the paper itself says that
-/// the implementation only gives usable values for 32x32 matrices)
+/// The values from 2.5.4 cannot be used here, because the 2 matrices of that example are far less
+/// than the minimum count of matrices.
+#[cfg(feature = "std")]
 #[test]
 fn test_binary_matrix_rank_test() {
     let file_path = Path::new(TEST_FILE_PATH).join("e.1e6.bin");
@@ -158,7 +246,7 @@ fn test_binary_matrix_rank_test() {
     bitvec.crop(length);
     assert_eq!(bitvec.len_bit(), length);
     // run the test
-    let output = binary_matrix_rank_test(&bitvec);
+    let output = binary_matrix_rank_test(&bitvec, BinaryMatrixRankTestArg::default());
     result_checker(&output);
 
     let output = output.unwrap();
@@ -167,12 +255,69 @@ fn test_binary_matrix_rank_test() {
     assert_f64_eq!(round(output.p_value, 6), 0.532069);
 }
 
+/// Test the binary matrix rank test (no. 5) with other matrix dimensions. The expected values were
+/// calculated with an independent implementation of the generic probability formula of 3.5.
+#[cfg(feature = "std")]
+#[test]
+fn test_binary_matrix_rank_dimensions() {
+    let file_path = Path::new(TEST_FILE_PATH).join("e.1e6.bin");
+    let input = fs::read(file_path).unwrap();
+    let input = BitVec::from(input.as_slice());
+
+    for (rows, cols, expected) in [(32, 32, 0.306156), (64, 64, 0.924576), (16, 16, 0.725083)] {
+        let test_arg = BinaryMatrixRankTestArg::try_new(rows, cols).unwrap();
+        let output = binary_matrix_rank_test(&input, test_arg);
+        result_checker(&output);
+
+        let output = output.unwrap();
+        assert_f64_eq!(round(output.p_value, 6), expected);
+        assert!(matches!(
+            output.statistics(),
+            Some(TestStatistics::BinaryMatrixRank { rows: r, cols: c, .. }) if r == rows && c == cols
+        ));
+    }
+
+    // NIST's recommendation for the default dimensions
+    assert_eq!(BinaryMatrixRankTestArg::default().min_matrix_count(), 38);
+    assert_eq!(
+        BinaryMatrixRankTestArg::default().min_input_length().get(),
+        38_912
+    );
+
+    // matrices that are not square almost always have full rank: too few matrices
+    let test_arg = BinaryMatrixRankTestArg::try_new(8, 16).unwrap();
+    assert!(test_arg.min_matrix_count() > 1_000_000);
+    let output = binary_matrix_rank_test(&input, test_arg).unwrap();
+    assert_eq!(output.p_value, 0.0);
+    // the comment contains the actual input length and dimensions
+    let comment = output.comment().unwrap();
+    assert!(comment.contains(&format!("({} bits)", input.len_bit())));
+    assert!(comment.contains("8x16 matrices"));
+
+    assert_eq!(
+        BinaryMatrixRankTestArg::try_new(1, 32),
+        Err(ArgError::OutOfRange {
+            name: "count of rows",
+            value: 1,
+            min: 2,
+            max: 64
+        })
+    );
+    assert_eq!(
+        BinaryMatrixRankTestArg::try_new(32, 65)
+            .unwrap_err()
+            .to_string(),
+        "The count of columns must be between 2 and 64, is: 65"
+    );
+}
+
 /// Test the spectral dft test (no 6.) - input and output taken from 2.6.4
+#[cfg(feature = "std")]
 #[test]
 fn test_spectral_dft_1() {
     let input = BitVec::from_ascii_str("1001010011").unwrap();
 
-    let output = spectral_dft_test(&input);
+    let output = spectral_dft_test(&input, SpectralDftTestArg::default());
 
     result_checker(&output);
 
@@ -185,12 +330,13 @@ fn test_spectral_dft_1() {
 }
 
 /// Test the spectral dft test (no 6.) - input and output taken from 2.6.8
+#[cfg(feature = "std")]
 #[test]
 fn test_spectral_dft_2() {
     let input = BitVec::from_ascii_str("1100100100001111110110101010001000100001011010001100001000110100110001001100011001100010100010111000")
         .unwrap();
 
-    let output = spectral_dft_test(&input);
+    let output = spectral_dft_test(&input, SpectralDftTestArg::default());
 
     result_checker(&output);
 
@@ -201,7 +347,31 @@ fn test_spectral_dft_2() {
     assert_f64_eq!(round(output.p_value, 6), 0.646355);
 }
 
+/// Test the spectral dft test (no 6.) with the threshold of the original SP 800-22 - same input as
+/// in 2.6.8
+#[cfg(feature = "std")]
+#[test]
+fn test_spectral_dft_original_nist() {
+    let input = BitVec::from_ascii_str("1100100100001111110110101010001000100001011010001100001000110100110001001100011001100010100010111000")
+        .unwrap();
+
+    let output = spectral_dft_test(&input, PeakThreshold::OriginalNist.into());
+
+    result_checker(&output);
+
+    let output = output.unwrap();
+    let Some(TestStatistics::SpectralDft { threshold, .. }) = output.statistics() else {
+        panic!("Wrong statistics: {:?}", output.statistics());
+    };
+    assert_f64_eq!(threshold, f64::sqrt(300.0));
+    assert_f64_eq!(
+        PeakThreshold::KimUmeno.threshold(100),
+        f64::sqrt(f64::ln(20.0) * 100.0)
+    );
+}
+
 /// Test the Non-Overlapping Template Matching test (no. 7) - input and output taken from 2.7.4
+#[cfg(feature = "std")]
 #[test]
 fn test_non_overlapping_template_matching_1() {
     let input = BitVec::from_ascii_str("10100100101110010110").unwrap();
@@ -212,16 +382,16 @@ fn test_non_overlapping_template_matching_1() {
 
     let count_blocks = 2;
     let template_arg =
-        TemplateArg::new_with_custom_templates(templates.as_slice(), template_len).unwrap();
+        TemplateArg::try_new_with_custom_templates(templates.as_slice(), template_len).unwrap();
     let test_arg =
-        NonOverlappingTemplateTestArgs::new_with_custom_template(template_arg, count_blocks)
+        NonOverlappingTemplateTestArgs::try_new_with_custom_template(template_arg, count_blocks)
             .unwrap();
 
     let output = non_overlapping_template_matching_test(&input, test_arg);
 
     result_checker(&output);
 
-    let output = output.unwrap()[0];
+    let output = output.unwrap().remove(0);
     assert!(output.passed(DEFAULT_THRESHOLD));
 
     assert_f64_eq!(round(output.p_value, 6), 0.344154);
@@ -236,6 +406,7 @@ fn test_non_overlapping_template_matching_1() {
 ///
 /// Because of this, the input pattern and the result value were chosen from the output of
 /// the NIST reference implementation.
+#[cfg(feature = "std")]
 #[test]
 fn test_non_overlapping_template_matching_2() {
     // This test depends on a test file: the first 2^20 bits of the G-SHA-1 generator
@@ -250,16 +421,18 @@ fn test_non_overlapping_template_matching_2() {
     let templates = [template << (usize::BITS as usize - template_len)];
 
     let template_arg =
-        TemplateArg::new_with_custom_templates(templates.as_slice(), template_len).unwrap();
-    let test_arg =
-        NonOverlappingTemplateTestArgs::new_with_custom_template(template_arg, DEFAULT_BLOCK_COUNT)
-            .unwrap();
+        TemplateArg::try_new_with_custom_templates(templates.as_slice(), template_len).unwrap();
+    let test_arg = NonOverlappingTemplateTestArgs::try_new_with_custom_template(
+        template_arg,
+        DEFAULT_BLOCK_COUNT,
+    )
+    .unwrap();
 
     let output = non_overlapping_template_matching_test(&input, test_arg);
 
     result_checker(&output);
 
-    let output = output.unwrap()[0];
+    let output = output.unwrap().remove(0);
     assert!(output.passed(DEFAULT_THRESHOLD));
 
     assert_f64_eq!(round(output.p_value, 6), 0.015021);
@@ -274,6 +447,7 @@ fn test_non_overlapping_template_matching_2() {
 /// The results shown in the paper use inaccurate values for the pis. This is mitigated by using
 /// the pi values from the reference implementation, which is entirely inaccurate for most
 /// bigger sequences (> 1e6).
+#[cfg(feature = "std")]
 #[test]
 fn test_overlapping_template_matching_test() {
     let file_path = Path::new(TEST_FILE_PATH).join("e.1e6.bin");
@@ -285,7 +459,7 @@ fn test_overlapping_template_matching_test() {
     assert_eq!(bitvec.len_bit(), length);
 
     // create the custom template
-    let arg = OverlappingTemplateTestArgs::new_nist_behaviour(9).unwrap();
+    let arg = OverlappingTemplateTestArgs::try_new_nist_behaviour(9).unwrap();
 
     // run the test
     let output = overlapping_template_matching_test(&bitvec, arg);
@@ -308,6 +482,7 @@ fn test_overlapping_template_matching_test() {
 ///    are not valid for real tests.
 /// 2. Because the parameters for the G-SHA-1 generator used in 2.9.8 are unknown, the exact sequence
 ///    used in the example is unknown and the values from 2.9.8 can also not be used.
+#[cfg(feature = "std")]
 #[test]
 fn test_maurers_universal_statistical_test() {
     let file_path = Path::new(TEST_FILE_PATH).join("e.1e6.bin");
@@ -340,6 +515,7 @@ fn test_maurers_universal_statistical_test() {
 ///
 /// Because of this, the output value is not exactly the one given in 2.10.8, but instead the one
 /// it would be if the correct constant was used.
+#[cfg(feature = "std")]
 #[test]
 fn test_linear_complexity_test() {
     let file_path = Path::new(TEST_FILE_PATH).join("e.1e6.bin");
@@ -373,7 +549,7 @@ fn test_linear_complexity_test() {
 #[test]
 fn test_serial_test_1() {
     let data = BitVec::from_ascii_str("0011011101").unwrap();
-    let test_arg = SerialTestArg::new(3).unwrap();
+    let test_arg = SerialTestArg::try_new(3).unwrap();
 
     let output = serial_test(&data, test_arg);
 
@@ -397,7 +573,7 @@ fn test_serial_test_2() {
     let data = BitVec::from(data);
     assert_eq!(data.len_bit(), length);
 
-    let test_arg = SerialTestArg::new(2).unwrap();
+    let test_arg = SerialTestArg::try_new(2).unwrap();
 
     let output = serial_test(&data, test_arg);
 
@@ -415,7 +591,7 @@ fn test_serial_test_2() {
 fn test_approximate_entropy_test_1() {
     let data = BitVec::from_ascii_str("0100110101").unwrap();
 
-    let test_arg = ApproximateEntropyTestArg::new(3).unwrap();
+    let test_arg = ApproximateEntropyTestArg::try_new(3).unwrap();
 
     let output = approximate_entropy_test(&data, test_arg);
 
@@ -434,7 +610,7 @@ fn test_approximate_entropy_test_2() {
     ).unwrap();
     assert_eq!(data.len_bit(), 100);
 
-    let test_arg = ApproximateEntropyTestArg::new(2).unwrap();
+    let test_arg = ApproximateEntropyTestArg::try_new(2).unwrap();
 
     let output = approximate_entropy_test(&data, test_arg);
 
@@ -487,11 +663,12 @@ fn test_cumulative_sums_test_2() {
 /// the recalculated constants used in this test being much better, the calculated result deviates
 /// from the result in the paper (beginning with step 7). This test passes because the result was
 /// manually recalculated with the new constants.
+#[cfg(feature = "std")]
 #[test]
 fn test_random_excursions_test_1() {
     let data = BitVec::from_ascii_str("0110110101").unwrap();
 
-    let output = random_excursions_test(&data);
+    let output = random_excursions_test(&data, Default::default());
 
     result_checker(&output);
 
@@ -507,6 +684,7 @@ fn test_random_excursions_test_1() {
 ///
 /// See [test_random_excursions_test_1]. Once again, the difference was verified manually to be a
 /// result of the changed constants.
+#[cfg(feature = "std")]
 #[test]
 fn test_random_excursions_test_2() {
     let file_path = Path::new(TEST_FILE_PATH).join("e.1e6.bin");
@@ -517,7 +695,7 @@ fn test_random_excursions_test_2() {
     let data = BitVec::from(data);
     assert_eq!(data.len_bit(), length);
 
-    let output = random_excursions_test(&data);
+    let output = random_excursions_test(&data, Default::default());
 
     result_checker(&output);
 
@@ -532,11 +710,12 @@ fn test_random_excursions_test_2() {
 }
 
 /// Test the random excursions variant test (no. 15) - input and output taken from 2.15.4.
+#[cfg(feature = "std")]
 #[test]
 fn test_random_excursions_variant_test_1() {
     let data = BitVec::from_ascii_str("0110110101").unwrap();
 
-    let output = random_excursions_variant_test(&data);
+    let output = random_excursions_variant_test(&data, Default::default());
 
     result_checker(&output);
 
@@ -547,6 +726,7 @@ fn test_random_excursions_variant_test_1() {
 }
 
 /// Test the random excursions variant test (no. 15) - input and output taken from 2.15.8
+#[cfg(feature = "std")]
 #[test]
 fn test_random_excursions_variant_test_2() {
     let file_path = Path::new(TEST_FILE_PATH).join("e.1e6.bin");
@@ -557,7 +737,7 @@ fn test_random_excursions_variant_test_2() {
     let data = BitVec::from(data);
     assert_eq!(data.len_bit(), length);
 
-    let output = random_excursions_variant_test(&data);
+    let output = random_excursions_variant_test(&data, Default::default());
 
     result_checker(&output);
 
@@ -571,3 +751,78 @@ fn test_random_excursions_variant_test_2() {
         assert_f64_eq!(round(result.p_value, 6), expected);
     }
 }
+
+/// Test the Lempel-Ziv compression test, which is only part of the original SP 800-22 (2001) -
+/// the input of the word parsing is taken from its section 2.10.4.
+#[cfg(feature = "std")]
+#[test]
+fn test_lempel_ziv_test() {
+    // parsed into the words 0, 1, 01, 10, 010
+    let data = BitVec::from_ascii_str("010110010").unwrap();
+    assert_eq!(count_distinct_words(&data, data.len_bit()), 5);
+
+    // the test itself is only defined for 10^6 bits
+    assert!(matches!(
+        lempel_ziv_test(&data),
+        Err(Error::InvalidParameter(_))
+    ));
+
+    let file_path = Path::new(TEST_FILE_PATH).join("e.1e6.bin");
+    let data = BitVec::from(fs::read(file_path).unwrap());
+
+    let output = lempel_ziv_test(&data);
+
+    result_checker(&output);
+
+    let output = output.unwrap();
+    let Some(TestStatistics::LempelZiv { words }) = output.statistics() else {
+        panic!("Lempel-Ziv statistics are missing");
+    };
+    assert_eq!(words, count_distinct_words(&data, data.len_bit()));
+}
+
+/// Test the autocorrelation test, which is not part of SP 800-22 - the example is taken from the
+/// Handbook of Applied Cryptography (Menezes et al.), example 5.31.
+#[cfg(feature = "std")]
+#[test]
+fn test_autocorrelation_test() {
+    let data =
+        BitVec::from_ascii_str(&"1110001100010001010011101111001001001001".repeat(4)).unwrap();
+    let arg = AutocorrelationTestArg::try_new(8).unwrap();
+
+    let output = autocorrelation_test(&data, arg);
+
+    result_checker(&output);
+
+    let output = output.unwrap();
+    let Some(TestStatistics::Autocorrelation {
+        shift,
+        differences,
+        statistic,
+    }) = output.statistics()
+    else {
+        panic!("autocorrelation statistics are missing");
+    };
+    assert_eq!(shift, 8);
+    assert_eq!(differences, 100);
+    assert_f64_eq!(round(statistic, 4), 3.8933);
+    assert!(!output.passed(DEFAULT_THRESHOLD));
+
+    // the shift may be at most half of the input length
+    assert!(matches!(
+        autocorrelation_test(&data, AutocorrelationTestArg::try_new(81).unwrap()),
+        Err(Error::InvalidParameter(_))
+    ));
+    assert!(AutocorrelationTestArg::try_new(0).is_err());
+
+    // compare with a naive count for shifts inside, across and at the edges of words
+    let file_path = Path::new(TEST_FILE_PATH).join("e.1e6.bin");
+    let data = BitVec::from(fs::read(file_path).unwrap()).slice(0..10_003);
+    let bits = data.iter_bits().collect::<Vec<_>>();
+    for shift in [1, 7, 31, 32, 63, 64, 65, 128, 1000, 5001] {
+        let expected = (0..bits.len() - shift)
+            .filter(|&i| bits[i] != bits[i + shift])
+            .count();
+        assert_eq!(count_differences(&data, shift), expected, "shift {shift}");
+    }
+}
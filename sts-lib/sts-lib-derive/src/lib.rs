@@ -42,6 +42,12 @@ fn threadpool_name() -> String {
     format!("{THREADPOOL_NAME}_{crate_name}")
 }
 
+/// Returns the export name of the function that checks if the current thread belongs to a custom
+/// thread pool.
+fn custom_pool_check_name() -> String {
+    format!("{}_IN_CUSTOM_POOL", threadpool_name())
+}
+
 /// Registers the specified static to be used as the thread pool for use in the [use_thread_pool]
 /// macro. This macro must be called exactly once if using [use_thread_pool].
 ///
@@ -52,6 +58,11 @@ fn threadpool_name() -> String {
 ///
 /// This macro may only be called once per crate.
 ///
+/// Additionally, this macro declares the thread local `IN_CUSTOM_THREAD_POOL: Cell<bool>` next to
+/// the static. It must be set to `true` for all worker threads of custom thread pools (e.g. with
+/// `ThreadPoolBuilder::start_handler`): functions using [use_thread_pool] that are called on such
+/// a thread run in the custom thread pool instead of the registered one.
+///
 /// Example:
 /// ```ignore
 /// use sts_lib_derive::register_thread_pool;
@@ -96,16 +107,15 @@ pub fn register_thread_pool(input: TokenStream) -> TokenStream {
                     "Attribute 'export_name' must be used by this macro!"
                 )
             }
-            Meta::Path(path) => {
-                if path.is_ident("used") {
-                    used_already_exists = true;
-                }
+            Meta::Path(path) if path.is_ident("used") => {
+                used_already_exists = true;
             }
             _ => (),
         }
     }
 
     let threadpool_name = threadpool_name();
+    let custom_pool_check_name = custom_pool_check_name();
 
     let used_attribute: IntoIter<Ident> = if !used_already_exists {
         Some(parse_quote!(used)).into_iter()
@@ -118,6 +128,18 @@ pub fn register_thread_pool(input: TokenStream) -> TokenStream {
         #(#[#used_attribute])*
         #[export_name = #threadpool_name]
         pub static #ident: ::std::sync::LazyLock<::rayon::ThreadPool> = #expr;
+
+        ::std::thread_local! {
+            /// Set for the worker threads of custom thread pools, see `use_thread_pool`.
+            pub static IN_CUSTOM_THREAD_POOL: ::std::cell::Cell<bool> =
+                const { ::std::cell::Cell::new(false) };
+        }
+
+        /// Checks if the current thread is a worker of a custom thread pool.
+        #[export_name = #custom_pool_check_name]
+        fn __sts_in_custom_thread_pool() -> bool {
+            IN_CUSTOM_THREAD_POOL.get()
+        }
     })
 }
 
@@ -129,6 +151,12 @@ pub fn register_thread_pool(input: TokenStream) -> TokenStream {
 ///
 /// This should be used for all statistical tests.
 ///
+/// If the function is called on a worker thread of a custom thread pool (see
+/// [register_thread_pool]), it runs in that thread pool instead.
+///
+/// If the feature `std` of the calling crate is disabled, there is no thread pool: the function is
+/// left unchanged.
+///
 /// ## Usage
 ///
 /// This macro takes no arguments. The threadpool to use is specified via the [register_thread_pool]
@@ -146,8 +174,14 @@ pub fn use_thread_pool(_: TokenStream, input: TokenStream) -> TokenStream {
     } = input;
 
     let threadpool_name: Ident = Ident::new(&threadpool_name(), Span::call_site());
+    let custom_pool_check_name: Ident = Ident::new(&custom_pool_check_name(), Span::call_site());
 
     TokenStream::from(quote! {
+        #[cfg(not(feature = "std"))]
+        #(#attrs)*
+        #visibility #signature #body
+
+        #[cfg(feature = "std")]
         #(#attrs)*
         #visibility #signature {
             let body = || #body;
@@ -155,8 +189,15 @@ pub fn use_thread_pool(_: TokenStream, input: TokenStream) -> TokenStream {
             unsafe {
                 extern "Rust" {
                     static #threadpool_name: ::std::sync::LazyLock<::rayon::ThreadPool>;
+                    fn #custom_pool_check_name() -> bool;
+                }
+
+                if #custom_pool_check_name() {
+                    // already running in the custom thread pool
+                    body()
+                } else {
+                    #threadpool_name.install(body)
                 }
-                #threadpool_name.install(body)
             }
         }
     })
@@ -0,0 +1,152 @@
+//! Analysis of the results of tests over multiple sequences.
+
+use crate::nist_sts::{Test, TestResult};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// The verdict for one test over multiple sequences, see [analyse].
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct TestVerdict(sts_lib::analysis::TestVerdict);
+
+#[pymethods]
+impl TestVerdict {
+    /// Returns the test this verdict is about.
+    pub fn test(&self) -> Test {
+        self.0.test().into()
+    }
+
+    /// Returns the proportion of passed sequences, for each result of the test. Is empty if no
+    /// sequence returned an applicable result.
+    pub fn results(&self) -> Vec<ResultProportion> {
+        self.0
+            .results()
+            .iter()
+            .copied()
+            .map(ResultProportion)
+            .collect()
+    }
+
+    /// Returns the count of sequences the test was not applicable to. These are not part of the
+    /// sample.
+    pub fn not_applicable_count(&self) -> usize {
+        self.0.not_applicable_count()
+    }
+
+    /// Returns True if the proportion of every result lies within the acceptance interval.
+    /// Is False if there are no results.
+    pub fn passed(&self) -> bool {
+        self.0.passed()
+    }
+
+    pub fn __repr__(&self) -> String {
+        let passed = if self.0.passed() { "True" } else { "False" };
+        format!("TestVerdict(test = {}, passed = {passed})", self.0.test())
+    }
+
+    pub fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
+
+/// The proportion of sequences that passed one result of a test, see TestVerdict.results().
+#[pyclass(frozen)]
+#[derive(Copy, Clone)]
+pub struct ResultProportion(sts_lib::analysis::ResultProportion);
+
+#[pymethods]
+impl ResultProportion {
+    /// Returns the index of the result, for tests that return multiple results.
+    pub fn result_idx(&self) -> usize {
+        self.0.result_idx()
+    }
+
+    /// Returns the count of sequences that returned an applicable result.
+    pub fn sample_size(&self) -> usize {
+        self.0.sample_size()
+    }
+
+    /// Returns the count of sequences that passed the test.
+    pub fn passed_count(&self) -> usize {
+        self.0.passed_count()
+    }
+
+    /// Returns the proportion of sequences that passed the test. Is 0 if the sample size is 0.
+    pub fn proportion(&self) -> f64 {
+        self.0.proportion()
+    }
+
+    /// Returns the acceptance interval (min, max) for the proportion:
+    /// p ± 3 * sqrt(p * (1 - p) / m), with p = 1 - threshold and m being the sample size.
+    pub fn acceptance_interval(&self) -> (f64, f64) {
+        self.0.acceptance_interval()
+    }
+
+    /// Returns True if the proportion lies within the acceptance interval.
+    pub fn passed(&self) -> bool {
+        self.0.passed()
+    }
+
+    pub fn __repr__(&self) -> String {
+        let (min, max) = self.0.acceptance_interval();
+        format!(
+            "ResultProportion(passed = {} / {}, acceptance_interval = ({min}, {max}))",
+            self.0.passed_count(),
+            self.0.sample_size(),
+        )
+    }
+
+    pub fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
+
+/// Analyses the results of tests over multiple sequences.
+///
+/// `results` is a dict mapping each test to the results of all sequences, each being the list of
+/// results the test returned for one sequence. For each test, the proportion of sequences that
+/// passed is compared to the acceptance interval of NIST SP 800-22r1a, section 4.2.1.
+/// Sequences with results that are not applicable are not part of the sample.
+///
+/// If no threshold is given, TestResult.DEFAULT_THRESHOLD is used.
+///
+/// Returns one TestVerdict per test, in the order of the dict.
+#[pyfunction]
+#[pyo3(signature = (results, threshold=None))]
+pub fn analyse(results: &Bound<'_, PyDict>, threshold: Option<f64>) -> PyResult<Vec<TestVerdict>> {
+    let results = results
+        .iter()
+        .map(|(test, sequences)| {
+            let test: Test = test.extract()?;
+            let sequences: Vec<Vec<TestResult>> = sequences.extract()?;
+
+            let sequences = sequences
+                .into_iter()
+                .map(|results| results.into_iter().map(|result| result.result).collect())
+                .collect::<Vec<Vec<_>>>();
+
+            Ok((test.into(), sequences))
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+
+    let threshold = threshold.unwrap_or(sts_lib::DEFAULT_THRESHOLD);
+
+    Ok(sts_lib::analysis::analyse(results, threshold)
+        .into_iter()
+        .map(TestVerdict)
+        .collect())
+}
+
+/// Checks the uniformity of the given P-values, e.g. the P-values of one test over multiple
+/// sequences, with the chi-square test of NIST SP 800-22r1a, section 4.2.2.
+///
+/// The P-value of the returned TestResult is the P-value of the P-values. NIST considers the
+/// P-values to be uniformly distributed if it is at least 0.0001, which is used as the threshold
+/// of the TestResult. If no P-values are given, the result is not applicable.
+#[pyfunction]
+pub fn p_value_uniformity(p_values: Vec<f64>) -> TestResult {
+    TestResult {
+        result: sts_lib::analysis::p_value_uniformity(&p_values),
+        threshold: sts_lib::test_runner::multi_sequence::UNIFORMITY_THRESHOLD,
+    }
+}
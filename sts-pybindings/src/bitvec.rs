@@ -1,14 +1,19 @@
-use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::exceptions::{PyIndexError, PyTypeError, PyValueError};
 use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyBytes, PySlice, PyType};
 use std::borrow::Cow;
 use std::sync::Arc;
 use sts_lib::bitvec;
 
 /// A list of bits, tightly packed - used as the data type for all tests.
 ///
-/// Note: `len(self)` returns the count of bits stored in the object.
-#[pyclass(frozen)]
-pub struct BitVec(pub(crate) Arc<bitvec::BitVec>);
+/// Behaves like an immutable sequence of bits: `len(self)` returns the count of bits stored in
+/// the object, `self[i]` returns a single bit as a `bool` and `self[start:end:step]` returns a new
+/// BitVec. BitVecs are equal if they contain the same bits, are hashable and can be pickled, e.g.
+/// to send them to `multiprocessing` workers.
+#[pyclass(eq, hash, frozen, sequence, module = "nist_sts")]
+#[derive(PartialEq, Eq, Hash)]
+pub struct BitVec(pub(crate) Arc<bitvec::BitVec<'static>>);
 
 #[pymethods]
 impl BitVec {
@@ -87,6 +92,69 @@ impl BitVec {
         self.0.len_bit()
     }
 
+    /// Returns the bit at the given index as a `bool`, or a new BitVec with the bits of the given
+    /// slice. Negative indices count from the end, like for lists.
+    ///
+    /// Raises an IndexError if the index is out of range.
+    pub fn __getitem__<'py>(&self, index: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        let py = index.py();
+        let len_bit = self.0.len_bit();
+
+        if let Ok(slice) = index.downcast::<PySlice>() {
+            let indices = slice.indices(len_bit as isize)?;
+
+            let bit_vec = if indices.slicelength == 0 {
+                bitvec::BitVec::new()
+            } else if indices.step == 1 {
+                let start = indices.start as usize;
+                self.0.slice(start..start + indices.slicelength)
+            } else {
+                let bits = (0..indices.slicelength)
+                    .map(|i| {
+                        let idx = indices.start + (i as isize) * indices.step;
+                        // all indices are valid for the length
+                        self.0.get(idx as usize).unwrap_or_default()
+                    })
+                    .collect::<Vec<_>>();
+                bitvec::BitVec::from(bits)
+            };
+
+            return Ok(Bound::new(py, Self(Arc::new(bit_vec)))?.into_any());
+        }
+
+        let Ok(idx) = index.extract::<isize>() else {
+            return Err(PyTypeError::new_err(
+                "BitVec indices must be integers or slices",
+            ));
+        };
+        let bit = if idx < 0 {
+            len_bit
+                .checked_sub(idx.unsigned_abs())
+                .and_then(|idx| self.0.get(idx))
+        } else {
+            self.0.get(idx as usize)
+        };
+
+        match bit {
+            Some(bit) => Ok(PyBool::new(py, bit).to_owned().into_any()),
+            None => Err(PyIndexError::new_err(format!(
+                "The index {idx} is out of range for a BitVec of length {len_bit}"
+            ))),
+        }
+    }
+
+    /// Support for pickling: the BitVec is recreated from its bytes, cropped to its length.
+    pub fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> (Bound<'py, PyType>, (Bound<'py, PyBytes>, bool, usize)) {
+        let this = slf.get();
+        let (mut bytes, rest) = this.0.to_bytes();
+        bytes.extend(rest);
+
+        let bytes = PyBytes::new(slf.py(), &bytes);
+        (slf.get_type(), (bytes, false, this.0.len_bit()))
+    }
+
     /// Returns a new object that contains the same data, but cropped to the given count of bits.
     /// If the given new length is greater than the old one, the data of the new object is
     /// unchanged.
@@ -96,6 +164,22 @@ impl BitVec {
         Self(Arc::new(this))
     }
 
+    /// Returns a new object that contains a copy of the bits from index `start` (inclusive) to
+    /// `end` (exclusive), e.g. to test only a part of the sequence.
+    ///
+    /// Raises a ValueError if `start > end` or `end` is greater than the length of this BitVec.
+    pub fn slice(&self, start: usize, end: usize) -> PyResult<Self> {
+        let len_bit = self.0.len_bit();
+
+        if start > end || end > len_bit {
+            return Err(PyValueError::new_err(format!(
+                "The range {start}..{end} is not valid for a BitVec of length {len_bit}"
+            )));
+        }
+
+        Ok(Self(Arc::new(self.0.slice(start..end))))
+    }
+
     // string representation.
     pub fn __str__(&self) -> String {
         format!("BitVec(length={})", self.0.len_bit())
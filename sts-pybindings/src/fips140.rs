@@ -0,0 +1,108 @@
+//! The statistical power-up tests of FIPS 140-2, as a quick check before running the tests of
+//! SP 800-22.
+
+use crate::bitvec::BitVec;
+use crate::thread_limit::install;
+use crate::TestError;
+use pyo3::prelude::*;
+use sts_lib::fips140::{
+    Fips140Result as InternalFips140Result, Fips140Test as InternalFips140Test,
+};
+
+/// The single tests of FIPS 140-2, see [fips140_2_tests].
+#[pyclass(eq, eq_int, frozen)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Fips140Test {
+    /// The count of ones must lie in (9725, 10275).
+    Monobit,
+    /// The statistic of the 4-bit segments must lie in (2.16, 46.17).
+    Poker,
+    /// The count of runs of each length must lie within the bounds of FIPS 140-2.
+    Runs,
+    /// There may be no run of 26 or more equal bits.
+    LongRun,
+}
+
+impl From<Fips140Test> for InternalFips140Test {
+    fn from(value: Fips140Test) -> Self {
+        match value {
+            Fips140Test::Monobit => InternalFips140Test::Monobit,
+            Fips140Test::Poker => InternalFips140Test::Poker,
+            Fips140Test::Runs => InternalFips140Test::Runs,
+            Fips140Test::LongRun => InternalFips140Test::LongRun,
+        }
+    }
+}
+
+#[pymethods]
+impl Fips140Test {
+    pub fn __repr__(&self) -> String {
+        format!("Fips140Test.{:?}", self)
+    }
+
+    pub fn __str__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+/// The result of the FIPS 140-2 tests, see [fips140_2_tests].
+#[pyclass(frozen)]
+#[derive(Copy, Clone)]
+pub struct Fips140Result(InternalFips140Result);
+
+#[pymethods]
+impl Fips140Result {
+    /// Returns the count of ones in the tested sample, used by the monobit test.
+    pub fn ones(&self) -> usize {
+        self.0.ones()
+    }
+
+    /// Returns the statistic of the poker test.
+    pub fn poker_statistic(&self) -> f64 {
+        self.0.poker_statistic()
+    }
+
+    /// Returns the count of runs of the given bit with length 1, 2, 3, 4, 5 and 6 or longer, used
+    /// by the runs test.
+    pub fn runs(&self, bit: bool) -> [usize; 6] {
+        self.0.runs(bit)
+    }
+
+    /// Returns the length of the longest run in the tested sample, used by the long run test.
+    pub fn longest_run(&self) -> usize {
+        self.0.longest_run()
+    }
+
+    /// Returns True if the given test passed.
+    pub fn passed(&self, test: Fips140Test) -> bool {
+        self.0.passed(test.into())
+    }
+
+    /// Returns True if all tests passed - the combined verdict.
+    pub fn all_passed(&self) -> bool {
+        self.0.all_passed()
+    }
+
+    pub fn __repr__(&self) -> String {
+        let passed = if self.0.all_passed() { "True" } else { "False" };
+        format!("Fips140Result(all_passed = {passed})")
+    }
+
+    pub fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
+
+/// Runs the 4 statistical power-up tests of FIPS 140-2 (monobit, poker, runs and long run) on the
+/// first 20000 bits of the given data. **These tests are not part of SP 800-22**, but they are much
+/// faster and can be used as a quick check before running the full tests.
+///
+/// ## Exceptions
+///
+/// A `TestError` is raised if the data is shorter than 20000 bits.
+#[pyfunction]
+pub fn fips140_2_tests(data: &BitVec) -> PyResult<Fips140Result> {
+    install(|| sts_lib::fips140::fips140_2_tests(&data.0))
+        .map(Fips140Result)
+        .map_err(|e| TestError::new_err(e.to_string()))
+}
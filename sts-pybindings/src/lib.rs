@@ -1,13 +1,16 @@
 #![doc = include_str!("../README.md")]
 
 use pyo3::create_exception;
-use pyo3::exceptions::PyException;
+use pyo3::exceptions::{PyException, PyUserWarning};
 use pyo3::prelude::*;
 
+pub mod analysis;
 pub mod bitvec;
+pub mod fips140;
 pub mod test_args;
 pub mod test_runner;
 pub mod tests;
+pub mod thread_limit;
 
 create_exception!(
     nist_sts,
@@ -15,6 +18,12 @@ create_exception!(
     PyException,
     "A statistical test failed."
 );
+create_exception!(
+    nist_sts,
+    TestTimeoutError,
+    TestError,
+    "A statistical test took longer than the timeout of the test runner."
+);
 create_exception!(
     nist_sts,
     RunnerError,
@@ -27,66 +36,171 @@ create_exception!(
     PyException,
     "The library was used very wrong."
 );
+create_exception!(
+    nist_sts,
+    InputLengthWarning,
+    PyUserWarning,
+    "A test is run on an input that is shorter than recommended by NIST, the result is less reliable."
+);
 
 #[pymodule]
 pub mod nist_sts {
-    use super::{RunnerError, StsError, TestError};
+    use super::{InputLengthWarning, RunnerError, StsError, TestError, TestTimeoutError};
     use pyo3::prelude::*;
+    use pyo3::types::PyDict;
     use pyo3::PyResult;
     use std::num::NonZero;
 
     // re-exports of the BitVec and TestRunner
     #[pymodule_export]
+    pub use crate::analysis::analyse;
+    #[pymodule_export]
+    pub use crate::analysis::p_value_uniformity;
+    #[pymodule_export]
+    pub use crate::analysis::ResultProportion;
+    #[pymodule_export]
+    pub use crate::analysis::TestVerdict;
+    #[pymodule_export]
     pub use crate::bitvec::BitVec;
     #[pymodule_export]
+    pub use crate::fips140::fips140_2_tests;
+    #[pymodule_export]
+    pub use crate::fips140::Fips140Result;
+    #[pymodule_export]
+    pub use crate::fips140::Fips140Test;
+    #[pymodule_export]
+    pub use crate::test_runner::iter_tests;
+    #[pymodule_export]
     pub use crate::test_runner::run_tests;
+    #[pymodule_export]
+    pub use crate::test_runner::run_tests_async;
+    #[pymodule_export]
+    pub use crate::test_runner::run_tests_partitioned;
+    #[pymodule_export]
+    pub use crate::test_runner::run_tests_subsampled;
+    #[pymodule_export]
+    pub use crate::test_runner::CancellationToken;
+    #[pymodule_export]
+    pub use crate::test_runner::Profile;
+    #[pymodule_export]
+    pub use crate::test_runner::RunResults;
+    #[pymodule_export]
+    pub use crate::test_runner::TestRunResult;
+    #[pymodule_export]
+    pub use crate::thread_limit::thread_limit;
+    #[pymodule_export]
+    pub use crate::thread_limit::ThreadLimit;
 
     /// Initialization function, takes care that the custom error types are in the module.
     #[pymodule_init]
     fn init(m: &Bound<'_, PyModule>) -> PyResult<()> {
         m.add("TestError", m.py().get_type::<TestError>())?;
+        m.add("TestTimeoutError", m.py().get_type::<TestTimeoutError>())?;
         m.add("RunnerError", m.py().get_type::<RunnerError>())?;
         m.add("LibError", m.py().get_type::<StsError>())?;
+        m.add(
+            "InputLengthWarning",
+            m.py().get_type::<InputLengthWarning>(),
+        )?;
         Ok(())
     }
 
     /// The common test result type, returned by all tests.
+    ///
+    /// Each result stores the threshold that is used by TestResult.passed() if no threshold is
+    /// given: either the threshold given to run_tests() or DEFAULT_THRESHOLD.
     #[pyclass(frozen)]
-    #[derive(Copy, Clone)]
-    pub struct TestResult(pub(crate) sts_lib::TestResult);
+    #[derive(Clone)]
+    pub struct TestResult {
+        pub(crate) result: sts_lib::TestResult,
+        pub(crate) threshold: f64,
+    }
+
+    impl From<sts_lib::TestResult> for TestResult {
+        fn from(value: sts_lib::TestResult) -> Self {
+            Self {
+                result: value,
+                threshold: sts_lib::DEFAULT_THRESHOLD,
+            }
+        }
+    }
 
     #[pymethods]
     impl TestResult {
         /// The default for threshold, to be used in TestResult.passed().
         #[classattr]
-        pub const DEFAULT_THRESHOLD: f64 = 0.01;
+        pub const DEFAULT_THRESHOLD: f64 = sts_lib::DEFAULT_THRESHOLD;
 
         /// Returns the stored P-Value of the result.
         pub fn p_value(&self) -> f64 {
-            self.0.p_value()
+            self.result.p_value()
+        }
+
+        /// Returns the threshold that is used by TestResult.passed() if no threshold is given.
+        pub fn threshold(&self) -> f64 {
+            self.threshold
         }
 
         /// Determines if the stored P-Value passed the test by comparing it to the given threshold.
         /// If the P-Value is greater than the threshold, the test passed.
-        pub fn passed(&self, threshold: f64) -> bool {
-            self.p_value() >= threshold
+        ///
+        /// If no threshold is given, the stored threshold is used, see TestResult.threshold().
+        #[pyo3(signature = (threshold=None))]
+        pub fn passed(&self, threshold: Option<f64>) -> bool {
+            self.p_value() >= threshold.unwrap_or(self.threshold)
+        }
+
+        /// Returns True if the test was applicable to the input. If not, the P-Value is set to 0.0,
+        /// but this does not mean that the input is not random. This is the case e.g. for the
+        /// random excursions test, if the input has too few cycles.
+        pub fn applicable(&self) -> bool {
+            self.result.is_applicable()
         }
 
         /// Returns the comment stored in the test result, or None if there is no comment.
         pub fn comment(&self) -> Option<&str> {
-            self.0.comment()
+            self.result.comment()
         }
 
-        // String representation
+        /// Returns the label that identifies the result for tests with multiple results, e.g.
+        /// "forward" and "backward" for the cumulative sums test, "delta1" and "delta2" for the
+        /// serial test or "x = +4" for the random excursions tests. Returns None for tests with a
+        /// single result.
+        pub fn label(&self) -> Option<String> {
+            self.result.label().map(|label| label.to_string())
+        }
+
+        /// Returns the statistics calculated by the test as a dict, mapping the name of each
+        /// value to the value. Returns None if the test calculated no statistics.
+        pub fn statistics<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDict>>> {
+            self.result
+                .statistics()
+                .map(|statistics| {
+                    let dict = PyDict::new(py);
+                    for (name, value) in statistics.values() {
+                        dict.set_item(name, value)?;
+                    }
+                    Ok(dict)
+                })
+                .transpose()
+        }
+
+        // String representation - the comment is quoted and escaped, since it may contain
+        // arbitrary details of the test run.
         pub fn __repr__(&self) -> String {
-            if let Some(comment) = self.0.comment() {
+            if !self.result.is_applicable() {
                 format!(
-                    "TestResult(p_value = {}, comment = \"{}\")",
-                    self.0.p_value(),
+                    "TestResult(not applicable, comment = {:?})",
+                    self.result.comment().unwrap_or_default()
+                )
+            } else if let Some(comment) = self.result.comment() {
+                format!(
+                    "TestResult(p_value = {}, comment = {:?})",
+                    self.result.p_value(),
                     comment
                 )
             } else {
-                format!("TestResult(p_value = {})", self.0.p_value())
+                format!("TestResult(p_value = {})", self.result.p_value())
             }
         }
 
@@ -99,7 +213,8 @@ pub mod nist_sts {
     /// Sets the maximum of threads to be used by the tests. These method can only be called ONCE and
     /// only BEFORE a test is started. If not used, a sane default will be chosen.
     ///
-    /// If called multiple times or after the first test, an error will be raised.
+    /// If called multiple times or after the first test, an error will be raised. To change the
+    /// thread limit at any time, use `with thread_limit(max_threads):` instead.
     #[pyfunction]
     pub fn set_max_threads(max_threads: usize) -> PyResult<()> {
         let max_threads =
@@ -113,8 +228,16 @@ pub mod nist_sts {
         sts_lib::get_min_length_for_test(test.into()).get()
     }
 
+    /// Returns the input length, in bits, recommended by NIST for the given test. Inputs that are
+    /// at least as long as the minimum length, but shorter than the recommended length, can be
+    /// tested, but the result is less reliable, see the argument `length_warnings` of `run_tests()`.
+    #[pyfunction]
+    pub fn get_recommended_length_for_test(test: Test) -> usize {
+        sts_lib::get_recommended_length_for_test(test.into()).get()
+    }
+
     /// List of all tests, used for the TestRunner to know which threads to run.
-    #[pyclass(eq, eq_int)]
+    #[pyclass(eq, eq_int, hash, frozen)]
     #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
     pub enum Test {
         /// See [tests::frequency_test].
@@ -147,6 +270,10 @@ pub mod nist_sts {
         RandomExcursions,
         /// See [tests::random_excursions_variant_test]
         RandomExcursionsVariant,
+        /// See [tests::lempel_ziv_test]. Not part of the current revision of SP 800-22.
+        LempelZiv,
+        /// See [tests::autocorrelation_test]. Not part of SP 800-22, but of BSI AIS 31.
+        Autocorrelation,
     }
 
     impl From<sts_lib::Test> for Test {
@@ -169,6 +296,8 @@ pub mod nist_sts {
                 sts_lib::Test::CumulativeSums => Test::CumulativeSums,
                 sts_lib::Test::RandomExcursions => Test::RandomExcursions,
                 sts_lib::Test::RandomExcursionsVariant => Test::RandomExcursionsVariant,
+                sts_lib::Test::LempelZiv => Test::LempelZiv,
+                sts_lib::Test::Autocorrelation => Test::Autocorrelation,
             }
         }
     }
@@ -193,12 +322,53 @@ pub mod nist_sts {
                 Test::CumulativeSums => sts_lib::Test::CumulativeSums,
                 Test::RandomExcursions => sts_lib::Test::RandomExcursions,
                 Test::RandomExcursionsVariant => sts_lib::Test::RandomExcursionsVariant,
+                Test::LempelZiv => sts_lib::Test::LempelZiv,
+                Test::Autocorrelation => sts_lib::Test::Autocorrelation,
             }
         }
     }
 
     #[pymethods]
     impl Test {
+        /// Returns the tests that are run if no tests are selected: all tests except for
+        /// Test.LempelZiv, which was removed from SP 800-22.
+        #[staticmethod]
+        pub fn default_set() -> Vec<Test> {
+            sts_lib::Test::default_set()
+                .iter()
+                .map(|&test| test.into())
+                .collect()
+        }
+
+        /// Returns the tests that are cheap to run: their runtime grows linearly with the input
+        /// length. Together with Test.slow_set(), these are all tests.
+        #[staticmethod]
+        pub fn fast_set() -> Vec<Test> {
+            sts_lib::Test::fast_set()
+                .iter()
+                .map(|&test| test.into())
+                .collect()
+        }
+
+        /// Returns the tests that take the most time, e.g. the spectral DFT test.
+        #[staticmethod]
+        pub fn slow_set() -> Vec<Test> {
+            sts_lib::Test::slow_set()
+                .iter()
+                .map(|&test| test.into())
+                .collect()
+        }
+
+        /// Returns the tests that require at least 10^6 bits, either as their minimum or as their
+        /// recommended input length.
+        #[staticmethod]
+        pub fn requires_1e6_bits() -> Vec<Test> {
+            sts_lib::Test::requires_1e6_bits()
+                .iter()
+                .map(|&test| test.into())
+                .collect()
+        }
+
         // String representations
         pub fn __repr__(&self) -> String {
             format!("Test.{}", sts_lib::Test::from(*self))
@@ -258,6 +428,12 @@ pub mod nist_sts {
         // Test 15
         #[pymodule_export]
         pub use crate::tests::random_excursions_variant_test;
+        // not part of the current revision of SP 800-22
+        #[pymodule_export]
+        pub use crate::tests::lempel_ziv_test;
+        // not part of SP 800-22, but of BSI AIS 31
+        #[pymodule_export]
+        pub use crate::tests::autocorrelation_test;
     }
 
     #[pymodule]
@@ -267,6 +443,18 @@ pub mod nist_sts {
         #[pymodule_export]
         pub use crate::test_args::FrequencyBlockTestArg;
 
+        #[pymodule_export]
+        pub use crate::test_args::SelectionStrategy;
+
+        #[pymodule_export]
+        pub use crate::test_args::LongestRunOfOnesTestArg;
+
+        #[pymodule_export]
+        pub use crate::test_args::BinaryMatrixRankTestArg;
+
+        #[pymodule_export]
+        pub use crate::test_args::SpectralDftTestArg;
+
         #[pymodule_export]
         pub use crate::test_args::NonOverlappingTemplateTestArgs;
 
@@ -281,5 +469,14 @@ pub mod nist_sts {
 
         #[pymodule_export]
         pub use crate::test_args::ApproximateEntropyTestArg;
+
+        #[pymodule_export]
+        pub use crate::test_args::RandomExcursionsTestArg;
+
+        #[pymodule_export]
+        pub use crate::test_args::RandomExcursionsVariantTestArg;
+
+        #[pymodule_export]
+        pub use crate::test_args::AutocorrelationTestArg;
     }
 }
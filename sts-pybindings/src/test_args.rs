@@ -19,29 +19,252 @@ impl FrequencyBlockTestArg {
     /// The block length should be at least 20 bits, with the block length greater than 1% of the
     /// total bit length and fewer than 100 total blocks.
     ///
-    /// If no block length is given, a suitable block length will be chosen when the test is run.
+    /// If no block length is given, a suitable block length will be chosen when the test is run,
+    /// with the given strategy or the NIST recommendation. A block length and a strategy cannot
+    /// both be given.
     #[new]
-    #[pyo3(signature = (block_length=None))]
-    pub fn new(block_length: Option<usize>) -> Self {
-        let arg = match block_length {
-            Some(0) | None => frequency_block::FrequencyBlockTestArg::ChooseAutomatically,
-            Some(block_length) => {
+    #[pyo3(signature = (block_length=None, strategy=None))]
+    pub fn new(block_length: Option<usize>, strategy: Option<SelectionStrategy>) -> PyResult<Self> {
+        let arg = match (block_length, strategy) {
+            (Some(0) | None, None) => frequency_block::FrequencyBlockTestArg::ChooseAutomatically,
+            (Some(0) | None, Some(strategy)) => {
+                frequency_block::FrequencyBlockTestArg::ChooseWithStrategy(strategy.into())
+            }
+            (Some(block_length), None) => {
                 // just checked: is not 0
                 let block_length = NonZero::new(block_length).unwrap();
                 frequency_block::FrequencyBlockTestArg::Manual(block_length)
             }
+            (Some(_), Some(_)) => {
+                return Err(PyValueError::new_err(
+                    "block_length and strategy cannot both be given.",
+                ))
+            }
         };
-        Self(arg)
+        Ok(Self(arg))
+    }
+
+    /// The block length that is used for an input with the given length in bits.
+    pub fn block_length(&self, length: usize) -> usize {
+        self.0.block_length(length)
     }
 
     pub fn __repr__(&self) -> String {
-        let len = match self.0 {
-            frequency_block::FrequencyBlockTestArg::Manual(len) => len.get(),
+        match self.0 {
+            frequency_block::FrequencyBlockTestArg::Manual(len) => {
+                format!("FrequencyBlockTestArg({})", len)
+            }
             frequency_block::FrequencyBlockTestArg::ChooseAutomatically => {
-                return String::from("FrequencyBlockTestArg()");
+                String::from("FrequencyBlockTestArg()")
             }
-        };
-        format!("FrequencyBlockTestArg({})", len)
+            frequency_block::FrequencyBlockTestArg::ChooseWithStrategy(strategy) => {
+                format!(
+                    "FrequencyBlockTestArg(strategy = {})",
+                    SelectionStrategy::from(strategy).__repr__()
+                )
+            }
+        }
+    }
+
+    pub fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
+
+/// The strategies to choose the block length of the Frequency test within a block automatically.
+///
+/// All strategies choose a block length of at least 20 bits, greater than 1% of the input length,
+/// so that there are fewer than 100 blocks - if the input is long enough for at least 2 blocks.
+#[pyclass(eq, eq_int, frozen)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SelectionStrategy {
+    /// The smallest recommended block length, rounded up to a multiple of the word size if there
+    /// are still at least 2 blocks. This is the default.
+    NistRecommended,
+    /// The smallest recommended block length, which gives the most blocks.
+    MaximizeBlocks,
+    /// The smallest recommended block length that is a power of two, if there are still at least
+    /// 2 blocks.
+    PowerOfTwo,
+}
+
+impl From<SelectionStrategy> for frequency_block::SelectionStrategy {
+    fn from(value: SelectionStrategy) -> Self {
+        match value {
+            SelectionStrategy::NistRecommended => {
+                frequency_block::SelectionStrategy::NistRecommended
+            }
+            SelectionStrategy::MaximizeBlocks => frequency_block::SelectionStrategy::MaximizeBlocks,
+            SelectionStrategy::PowerOfTwo => frequency_block::SelectionStrategy::PowerOfTwo,
+        }
+    }
+}
+
+impl From<frequency_block::SelectionStrategy> for SelectionStrategy {
+    fn from(value: frequency_block::SelectionStrategy) -> Self {
+        match value {
+            frequency_block::SelectionStrategy::NistRecommended => {
+                SelectionStrategy::NistRecommended
+            }
+            frequency_block::SelectionStrategy::MaximizeBlocks => SelectionStrategy::MaximizeBlocks,
+            frequency_block::SelectionStrategy::PowerOfTwo => SelectionStrategy::PowerOfTwo,
+        }
+    }
+}
+
+#[pymethods]
+impl SelectionStrategy {
+    pub fn __repr__(&self) -> String {
+        format!("SelectionStrategy.{:?}", self)
+    }
+
+    pub fn __str__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+/// The argument for the Test for the Longest Run of Ones in a Block: the configuration of
+/// SP 800-22, section 2.4.2, given by the block length *M*: 8, 128 or 10000 bits.
+///
+/// Each configuration has a minimum input length: 128, 6272 and 750000 bits. This constraint is
+/// checked on executing the test. If the constraint is violated, an exception will be raised.
+#[pyclass(frozen)]
+#[derive(Copy, Clone, Default)]
+#[repr(transparent)]
+pub struct LongestRunOfOnesTestArg(pub(crate) longest_run_of_ones::LongestRunOfOnesTestArg);
+
+#[pymethods]
+impl LongestRunOfOnesTestArg {
+    /// The argument for the Test for the Longest Run of Ones in a Block: the configuration of
+    /// SP 800-22, section 2.4.2, given by the block length *M*: 8, 128 or 10000 bits.
+    ///
+    /// Each configuration has a minimum input length: 128, 6272 and 750000 bits. This constraint
+    /// is checked on executing the test. If the constraint is violated, an exception will be
+    /// raised.
+    ///
+    /// If no block length is given, the configuration is chosen based on the input length, as
+    /// recommended by SP 800-22.
+    #[new]
+    #[pyo3(signature = (block_length=None))]
+    pub fn new(block_length: Option<usize>) -> PyResult<Self> {
+        match block_length {
+            Some(block_length) => {
+                match longest_run_of_ones::LongestRunOfOnesTestArg::try_new(block_length) {
+                    Ok(arg) => Ok(Self(arg)),
+                    Err(e) => Err(PyValueError::new_err(e.to_string())),
+                }
+            }
+            None => Ok(Self(Default::default())),
+        }
+    }
+
+    pub fn __repr__(&self) -> String {
+        match self.0.block_length() {
+            None => String::from("LongestRunOfOnesTestArg()"),
+            Some(block_length) => format!("LongestRunOfOnesTestArg({block_length})"),
+        }
+    }
+
+    pub fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
+
+/// The argument for the Binary Matrix Rank Test: the dimensions of the matrices, *M* rows and
+/// *Q* columns.
+///
+/// Argument constraints:
+/// 1. the rows and columns must each be between 2 and 64.
+/// 2. each of the 3 rank classes must be expected at least 5 times, for 32x32 matrices this means
+///    at least 38 matrices.
+///
+/// Constraint 1 is checked when creating the argument. Constraint 2 is checked on executing the
+/// test. If the constraint is violated, the result has a P-value of 0 and a comment.
+///
+/// The default value for this argument is 32x32, as used by SP 800-22.
+#[pyclass(frozen)]
+#[derive(Copy, Clone, Default)]
+#[repr(transparent)]
+pub struct BinaryMatrixRankTestArg(pub(crate) binary_matrix_rank::BinaryMatrixRankTestArg);
+
+#[pymethods]
+impl BinaryMatrixRankTestArg {
+    /// The argument for the Binary Matrix Rank Test: the dimensions of the matrices, *M* rows and
+    /// *Q* columns.
+    ///
+    /// Argument constraints:
+    /// 1. the rows and columns must each be between 2 and 64.
+    /// 2. each of the 3 rank classes must be expected at least 5 times, for 32x32 matrices this
+    ///    means at least 38 matrices.
+    ///
+    /// Constraint 1 is checked when creating the argument. Constraint 2 is checked on executing
+    /// the test. If the constraint is violated, the result has a P-value of 0 and a comment.
+    ///
+    /// ## Arguments
+    ///
+    /// - rows: may be left unspecified, default 32.
+    /// - cols: may be left unspecified, default 32.
+    #[new]
+    #[pyo3(signature = (rows=32, cols=32))]
+    pub fn new(rows: usize, cols: usize) -> PyResult<Self> {
+        match binary_matrix_rank::BinaryMatrixRankTestArg::try_new(rows, cols) {
+            Ok(arg) => Ok(Self(arg)),
+            Err(e) => Err(PyValueError::new_err(e.to_string())),
+        }
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "BinaryMatrixRankTestArg({}, {})",
+            self.0.rows(),
+            self.0.cols()
+        )
+    }
+
+    pub fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
+
+/// The argument for the Spectral DFT Test: how the 95 % peak height threshold *T* is derived.
+///
+/// The original SP 800-22 used the constant *T = sqrt(3n)*, which is known to be biased.
+/// The current revision of SP 800-22 uses *T = sqrt(ln(1/0.05) n)*, as corrected by Kim, Umeno
+/// and Hasegawa.
+#[pyclass(frozen)]
+#[derive(Copy, Clone, Default)]
+#[repr(transparent)]
+pub struct SpectralDftTestArg(pub(crate) spectral_dft::SpectralDftTestArg);
+
+#[pymethods]
+impl SpectralDftTestArg {
+    /// The argument for the Spectral DFT Test: how the 95 % peak height threshold *T* is derived.
+    ///
+    /// The original SP 800-22 used the constant *T = sqrt(3n)*, which is known to be biased.
+    /// The current revision of SP 800-22 uses *T = sqrt(ln(1/0.05) n)*, as corrected by Kim, Umeno
+    /// and Hasegawa.
+    ///
+    /// ## Arguments
+    ///
+    /// - original_nist = False: if `True`, the biased constant of the original SP 800-22 is used.
+    ///   Only use this to compare results with old implementations.
+    #[new]
+    #[pyo3(signature = (original_nist=false))]
+    pub fn new(original_nist: bool) -> Self {
+        if original_nist {
+            Self(spectral_dft::PeakThreshold::OriginalNist.into())
+        } else {
+            Self(spectral_dft::PeakThreshold::KimUmeno.into())
+        }
+    }
+
+    pub fn __repr__(&self) -> String {
+        match self.0.peak_threshold() {
+            spectral_dft::PeakThreshold::KimUmeno => "SpectralDftTestArg()".to_owned(),
+            spectral_dft::PeakThreshold::OriginalNist => {
+                "SpectralDftTestArg(original_nist=True)".to_owned()
+            }
+        }
     }
 
     pub fn __str__(&self) -> String {
@@ -52,7 +275,7 @@ impl FrequencyBlockTestArg {
 /// The arguments for the Non-overlapping Template Matching Test.
 ///
 /// 1. The template length `m` to use, in bits.
-///    2 <= `m` <= 21 - recommended: 9.
+///    2 <= `m` <= 31 - recommended: 9.
 /// 2. The number of independent blocks to test in the sequence: `N`
 ///    1 <= `N` < 100 - recommended: 8
 ///
@@ -69,7 +292,7 @@ impl NonOverlappingTemplateTestArgs {
     /// The arguments for the Non-overlapping Template Matching Test.
     ///
     /// 1. The template length `m` to use, in bits.
-    ///    2 <= `m` <= 21 - recommended: 9.
+    ///    2 <= `m` <= 31 - recommended: 9.
     /// 2. The number of independent blocks to test in the sequence: `N`
     ///    1 <= `N` < 100 - recommended: 8
     ///
@@ -87,15 +310,14 @@ impl NonOverlappingTemplateTestArgs {
         match (template_len, count_blocks) {
             (None, None) => Ok(Self(Default::default(), None)),
             (Some(template_len), Some(count_blocks)) => {
-                let arg = template_matching::non_overlapping::NonOverlappingTemplateTestArgs::new(
-                    template_len,
-                    count_blocks,
-                );
+                let arg =
+                    template_matching::non_overlapping::NonOverlappingTemplateTestArgs::try_new(
+                        template_len,
+                        count_blocks,
+                    );
                 match arg {
-                    Some(arg) => Ok(Self(arg, Some((template_len, count_blocks)))),
-                    None => Err(PyValueError::new_err(
-                        "One or both arguments are out of range",
-                    )),
+                    Ok(arg) => Ok(Self(arg, Some((template_len, count_blocks)))),
+                    Err(e) => Err(PyValueError::new_err(e.to_string())),
                 }
             }
             // use default value for missing argument
@@ -177,27 +399,23 @@ impl OverlappingTemplateTestArgs {
             (None, None, None, false) => Ok(Self(Default::default(), None)),
             (Some(template_len), None, None, true) => {
                 let arg =
-                    template_matching::overlapping::OverlappingTemplateTestArgs::new_nist_behaviour(
+                    template_matching::overlapping::OverlappingTemplateTestArgs::try_new_nist_behaviour(
                         template_len,
                     );
                 match arg {
-                    Some(arg) => Ok(Self(arg, Some((template_len, None)))),
-                    None => Err(PyValueError::new_err(
-                        "template_len was out of range for nist_behaviour = true",
-                    )),
+                    Ok(arg) => Ok(Self(arg, Some((template_len, None)))),
+                    Err(e) => Err(PyValueError::new_err(e.to_string())),
                 }
             }
             (Some(template_len), Some(block_len), Some(freedom), false) => {
-                let arg = template_matching::overlapping::OverlappingTemplateTestArgs::new(
+                let arg = template_matching::overlapping::OverlappingTemplateTestArgs::try_new(
                     template_len,
                     block_len,
                     freedom,
                 );
                 match arg {
-                    Some(arg) => Ok(Self(arg, Some((template_len, Some((block_len, freedom)))))),
-                    None => Err(PyValueError::new_err(
-                        "One or more arguments were out of range for nist_behaviour = false",
-                    )),
+                    Ok(arg) => Ok(Self(arg, Some((template_len, Some((block_len, freedom)))))),
+                    Err(e) => Err(PyValueError::new_err(e.to_string())),
                 }
             }
             // use default arguments
@@ -334,10 +552,10 @@ impl SerialTestArg {
     pub fn new(block_length: Option<u8>) -> PyResult<Self> {
         match block_length {
             Some(block_length) => {
-                let arg = serial::SerialTestArg::new(block_length);
+                let arg = serial::SerialTestArg::try_new(block_length);
                 match arg {
-                    Some(arg) => Ok(Self(arg, Some(block_length))),
-                    None => Err(PyValueError::new_err("block_length was out of range.")),
+                    Ok(arg) => Ok(Self(arg, Some(block_length))),
+                    Err(e) => Err(PyValueError::new_err(e.to_string())),
                 }
             }
             None => Ok(Self(Default::default(), None)),
@@ -406,10 +624,10 @@ impl ApproximateEntropyTestArg {
     pub fn new(block_length: Option<u8>) -> PyResult<Self> {
         match block_length {
             Some(block_length) => {
-                let arg = approximate_entropy::ApproximateEntropyTestArg::new(block_length);
+                let arg = approximate_entropy::ApproximateEntropyTestArg::try_new(block_length);
                 match arg {
-                    Some(arg) => Ok(Self(arg, Some(block_length))),
-                    None => Err(PyValueError::new_err("block_length was out of range.")),
+                    Ok(arg) => Ok(Self(arg, Some(block_length))),
+                    Err(e) => Err(PyValueError::new_err(e.to_string())),
                 }
             }
             None => Ok(Self(Default::default(), None)),
@@ -425,3 +643,168 @@ impl ApproximateEntropyTestArg {
         }
     }
 }
+
+/// The argument for the random excursions test: the maximum state *x* to test. All states `-x..=-1` and
+/// `+1..=+x` are tested, with one result per state.
+///
+/// The maximum state must be between 1 and 32, this is checked when creating the argument.
+///
+/// The default value for this argument is 4, as used by NIST.
+#[pyclass(frozen)]
+#[derive(Copy, Clone, Default)]
+pub struct RandomExcursionsTestArg(
+    pub(crate) random_excursions::RandomExcursionsTestArg,
+    Option<u8>,
+);
+
+#[pymethods]
+impl RandomExcursionsTestArg {
+    /// The argument for the random excursions test: the maximum state *x* to test. All states `-x..=-1` and
+    /// `+1..=+x` are tested, with one result per state.
+    ///
+    /// The maximum state must be between 1 and 32, this is checked when creating the argument.
+    ///
+    /// The default value for this argument is 4, as used by NIST.
+    ///
+    /// ## Arguments
+    ///
+    /// - max_state: may be left unspecified.
+    #[new]
+    #[pyo3(signature = (max_state=None))]
+    pub fn new(max_state: Option<u8>) -> PyResult<Self> {
+        match max_state {
+            Some(max_state) => match random_excursions::RandomExcursionsTestArg::try_new(max_state)
+            {
+                Ok(arg) => Ok(Self(arg, Some(max_state))),
+                Err(e) => Err(PyValueError::new_err(e.to_string())),
+            },
+            None => Ok(Self(Default::default(), None)),
+        }
+    }
+
+    pub fn __repr__(&self) -> String {
+        match self.1 {
+            None => String::from("RandomExcursionsTestArg()"),
+            Some(max_state) => format!("RandomExcursionsTestArg({max_state})"),
+        }
+    }
+
+    pub fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
+
+/// The argument for the random excursions variant test: the maximum state *x* to test. All states `-x..=-1` and
+/// `+1..=+x` are tested, with one result per state.
+///
+/// The maximum state must be between 1 and 32, this is checked when creating the argument.
+///
+/// The default value for this argument is 9, as used by NIST.
+#[pyclass(frozen)]
+#[derive(Copy, Clone, Default)]
+pub struct RandomExcursionsVariantTestArg(
+    pub(crate) random_excursions_variant::RandomExcursionsVariantTestArg,
+    Option<u8>,
+);
+
+#[pymethods]
+impl RandomExcursionsVariantTestArg {
+    /// The argument for the random excursions variant test: the maximum state *x* to test. All states `-x..=-1` and
+    /// `+1..=+x` are tested, with one result per state.
+    ///
+    /// The maximum state must be between 1 and 32, this is checked when creating the argument.
+    ///
+    /// The default value for this argument is 9, as used by NIST.
+    ///
+    /// ## Arguments
+    ///
+    /// - max_state: may be left unspecified.
+    #[new]
+    #[pyo3(signature = (max_state=None))]
+    pub fn new(max_state: Option<u8>) -> PyResult<Self> {
+        match max_state {
+            Some(max_state) => {
+                match random_excursions_variant::RandomExcursionsVariantTestArg::try_new(max_state)
+                {
+                    Ok(arg) => Ok(Self(arg, Some(max_state))),
+                    Err(e) => Err(PyValueError::new_err(e.to_string())),
+                }
+            }
+            None => Ok(Self(Default::default(), None)),
+        }
+    }
+
+    pub fn __repr__(&self) -> String {
+        match self.1 {
+            None => String::from("RandomExcursionsVariantTestArg()"),
+            Some(max_state) => format!("RandomExcursionsVariantTestArg({max_state})"),
+        }
+    }
+
+    pub fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
+
+/// The argument for the autocorrelation test: the shift *d* in bits, i.e. the distance of the
+/// compared bits.
+///
+/// Argument constraints:
+/// 1. the shift must be >= 1.
+/// 2. the shift must be <= bit_len of sequence / 2.
+///
+/// Constraint 1 is checked when creating the argument.
+///
+/// Constraint 2 is checked on executing the test. If the constraint is violated,
+/// an exception will be raised.
+///
+/// The default value for this argument is 1, i.e. each bit is compared with its successor.
+#[pyclass(frozen)]
+#[derive(Copy, Clone, Default)]
+pub struct AutocorrelationTestArg(
+    pub(crate) autocorrelation::AutocorrelationTestArg,
+    Option<usize>,
+);
+
+#[pymethods]
+impl AutocorrelationTestArg {
+    /// The argument for the autocorrelation test: the shift *d* in bits, i.e. the distance of the
+    /// compared bits.
+    ///
+    /// Argument constraints:
+    /// 1. the shift must be >= 1.
+    /// 2. the shift must be <= bit_len of sequence / 2.
+    ///
+    /// Constraint 1 is checked when creating the argument.
+    ///
+    /// Constraint 2 is checked on executing the test. If the constraint is violated,
+    /// an exception will be raised.
+    ///
+    /// The default value for this argument is 1, i.e. each bit is compared with its successor.
+    ///
+    /// ## Arguments
+    ///
+    /// - shift: may be left unspecified.
+    #[new]
+    #[pyo3(signature = (shift=None))]
+    pub fn new(shift: Option<usize>) -> PyResult<Self> {
+        match shift {
+            Some(shift) => match autocorrelation::AutocorrelationTestArg::try_new(shift) {
+                Ok(arg) => Ok(Self(arg, Some(shift))),
+                Err(e) => Err(PyValueError::new_err(e.to_string())),
+            },
+            None => Ok(Self(Default::default(), None)),
+        }
+    }
+
+    pub fn __repr__(&self) -> String {
+        match self.1 {
+            None => String::from("AutocorrelationTestArg()"),
+            Some(shift) => format!("AutocorrelationTestArg({shift})"),
+        }
+    }
+
+    pub fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
@@ -1,15 +1,31 @@
 use crate::nist_sts::{BitVec, Test, TestResult};
 use crate::test_args::*;
-use crate::{RunnerError, TestError};
+use crate::thread_limit::{current_context, install};
+use crate::{InputLengthWarning, RunnerError, StsError, TestError, TestTimeoutError};
+use pyo3::exceptions::{PyImportError, PyKeyError, PyValueError};
 use pyo3::prelude::*;
+use pyo3::types::{PyCFunction, PyDict, PyIterator, PyList};
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::num::NonZero;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use sts_lib::test_runner::{PartitionedResults, Subsample};
 use sts_lib::{test_runner, Error, TestArgs};
 
 type TestResultIteratorItem = (sts_lib::Test, Result<Vec<sts_lib::TestResult>, Error>);
 
-/// Iterator for the result of the [run_tests] function.
+/// The results of each test on each sub-sequence, see [run_tests_partitioned].
+type SubSequenceResults = HashMap<Test, Vec<Vec<TestResult>>>;
+
+/// Iterator for the result of the [iter_tests] function.
+///
+/// Each test is only run when the next result is requested. The GIL is released while the test
+/// runs, so that other Python threads can continue to work.
 #[pyclass]
 pub struct TestResultIterator {
     iter: Box<dyn Iterator<Item = TestResultIteratorItem> + Send + Sync + 'static>,
+    threshold: f64,
 }
 
 #[pymethods]
@@ -18,20 +34,31 @@ impl TestResultIterator {
         this
     }
 
-    pub fn __next__(mut this: PyRefMut<'_, Self>) -> PyResult<Option<(Test, Bound<PyAny>)>> {
-        if let Some((test, res)) = this.iter.next() {
+    pub fn __next__(mut this: PyRefMut<'_, Self>) -> PyResult<Option<(Test, Bound<'_, PyAny>)>> {
+        let py = this.py();
+        let iter = &mut this.iter;
+        // run the test without holding the GIL
+        let next = py.allow_threads(|| iter.next());
+
+        if let Some((test, res)) = next {
+            let threshold = this.threshold;
             let res = match res {
-                Ok(res) => {
+                Ok(mut res) => {
                     if res.len() == 1 {
-                        TestResult(res[0]).into_pyobject(this.py())?.into_any()
+                        TestResult {
+                            result: res.remove(0),
+                            threshold,
+                        }
+                        .into_pyobject(this.py())?
+                        .into_any()
                     } else {
                         res.into_iter()
-                            .map(TestResult)
+                            .map(|result| TestResult { result, threshold })
                             .collect::<Vec<_>>()
                             .into_pyobject(this.py())?
                     }
                 }
-                Err(e) => return Err(TestError::new_err(e.to_string())),
+                Err(e) => return Err(test_error(e.to_string(), e)),
             };
 
             Ok(Some((test.into(), res)))
@@ -41,39 +68,358 @@ impl TestResultIterator {
     }
 }
 
+/// The results of one test, as returned by [run_tests].
+///
+/// The fields `p_values`, `passed` and `comments` contain one entry per result of the test, in
+/// the same order as `results`.
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct TestRunResult {
+    test: Test,
+    results: Vec<TestResult>,
+    duration: Duration,
+}
+
+#[pymethods]
+impl TestRunResult {
+    /// The test that was run.
+    #[getter]
+    pub fn test(&self) -> Test {
+        self.test
+    }
+
+    /// The results of the test.
+    #[getter]
+    pub fn results(&self) -> Vec<TestResult> {
+        self.results.clone()
+    }
+
+    /// The P-value of each result.
+    #[getter]
+    pub fn p_values(&self) -> Vec<f64> {
+        self.results.iter().map(|r| r.p_value()).collect()
+    }
+
+    /// For each result, if it passed with its stored threshold, see TestResult.passed().
+    #[getter]
+    pub fn passed(&self) -> Vec<bool> {
+        self.results.iter().map(|r| r.passed(None)).collect()
+    }
+
+    /// The comment of each result, or None if a result has no comment.
+    #[getter]
+    pub fn comments(&self) -> Vec<Option<&str>> {
+        self.results.iter().map(|r| r.comment()).collect()
+    }
+
+    /// The label of each result, or None if a result has no label, see TestResult.label().
+    #[getter]
+    pub fn labels(&self) -> Vec<Option<String>> {
+        self.results.iter().map(|r| r.label()).collect()
+    }
+
+    /// The time it took to run the test, in seconds.
+    #[getter]
+    pub fn duration(&self) -> f64 {
+        self.duration.as_secs_f64()
+    }
+
+    pub fn __repr__(&self) -> String {
+        let passed = self
+            .passed()
+            .into_iter()
+            .map(|passed| if passed { "True" } else { "False" })
+            .collect::<Vec<_>>();
+        format!(
+            "TestRunResult(test = {}, p_values = {:?}, passed = [{}], duration = {})",
+            sts_lib::Test::from(self.test),
+            self.p_values(),
+            passed.join(", "),
+            self.duration(),
+        )
+    }
+
+    pub fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
+
+/// The results of all tests of [run_tests]: a read-only mapping from each `Test` to its
+/// `TestRunResult`, in the order the tests were run.
+#[pyclass(frozen, mapping)]
+pub struct RunResults(Vec<TestRunResult>);
+
+#[pymethods]
+impl RunResults {
+    pub fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn __getitem__(&self, test: Test) -> PyResult<TestRunResult> {
+        self.get(test)
+            .ok_or_else(|| PyKeyError::new_err(test.__repr__()))
+    }
+
+    pub fn __contains__(&self, test: Test) -> bool {
+        self.get(test).is_some()
+    }
+
+    pub fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyIterator>> {
+        PyList::new(py, self.keys())?.try_iter()
+    }
+
+    /// Returns the tests that were run.
+    pub fn keys(&self) -> Vec<Test> {
+        self.0.iter().map(|result| result.test).collect()
+    }
+
+    /// Returns the results of all tests.
+    pub fn values(&self) -> Vec<TestRunResult> {
+        self.0.clone()
+    }
+
+    /// Returns tuples of each test and its results.
+    pub fn items(&self) -> Vec<(Test, TestRunResult)> {
+        self.0
+            .iter()
+            .map(|result| (result.test, result.clone()))
+            .collect()
+    }
+
+    /// Converts the results to a pandas DataFrame with one row per result and the columns
+    /// "test" (the name of the test), "result_idx", "p_value", "passed", "applicable", "comment"
+    /// and "duration" (of the whole test, in seconds).
+    ///
+    /// Raises an ImportError if pandas is not installed.
+    pub fn to_pandas<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let pandas = py.import("pandas").map_err(|e| {
+            PyImportError::new_err(format!("RunResults.to_pandas() requires pandas: {e}"))
+        })?;
+
+        let mut test = Vec::new();
+        let mut result_idx = Vec::new();
+        let mut label = Vec::new();
+        let mut p_value = Vec::new();
+        let mut passed = Vec::new();
+        let mut applicable = Vec::new();
+        let mut comment = Vec::new();
+        let mut duration = Vec::new();
+
+        for run_result in &self.0 {
+            for (idx, result) in run_result.results.iter().enumerate() {
+                test.push(sts_lib::Test::from(run_result.test).to_string());
+                result_idx.push(idx);
+                label.push(result.label());
+                p_value.push(result.p_value());
+                passed.push(result.passed(None));
+                applicable.push(result.applicable());
+                comment.push(result.comment());
+                duration.push(run_result.duration());
+            }
+        }
+
+        let columns = PyDict::new(py);
+        columns.set_item("test", test)?;
+        columns.set_item("result_idx", result_idx)?;
+        columns.set_item("label", label)?;
+        columns.set_item("p_value", p_value)?;
+        columns.set_item("passed", passed)?;
+        columns.set_item("applicable", applicable)?;
+        columns.set_item("comment", comment)?;
+        columns.set_item("duration", duration)?;
+
+        pandas.call_method1("DataFrame", (columns,))
+    }
+
+    pub fn __repr__(&self) -> String {
+        let tests = self
+            .0
+            .iter()
+            .map(|result| sts_lib::Test::from(result.test).to_string())
+            .collect::<Vec<_>>();
+        format!("RunResults(tests = [{}])", tests.join(", "))
+    }
+
+    pub fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
+
+impl RunResults {
+    fn get(&self, test: Test) -> Option<TestRunResult> {
+        self.0.iter().find(|result| result.test == test).cloned()
+    }
+}
+
+/// A token to abort a running analysis, see the argument `cancel_token` of [run_tests].
+///
+/// All copies of a token share the same state. Cancellation cannot be undone.
+#[pyclass(frozen)]
+#[derive(Clone, Default)]
+pub struct CancellationToken(test_runner::CancellationToken);
+
+#[pymethods]
+impl CancellationToken {
+    /// Creates a new token that is not cancelled.
+    #[new]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels all tests using this token.
+    pub fn cancel(&self) {
+        self.0.cancel()
+    }
+
+    /// Checks if the token was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!("CancellationToken(cancelled={})", self.0.is_cancelled())
+    }
+}
+
+/// A profile bundles a selection of tests with the test arguments to use for them, see the
+/// argument `profile` of [run_tests].
+#[pyclass(eq, eq_int, frozen)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Profile {
+    /// Name: "nist-default". All tests of the current revision of SP 800-22, with its parameters
+    /// and the ones of its reference implementation.
+    NistDefault,
+    /// Name: "bsi-ais31". The tests of SP 800-22 that correspond to the statistical tests of
+    /// BSI AIS 31, and its autocorrelation test. This does not replace an AIS 31 evaluation.
+    BsiAis31,
+    /// Name: "fast-screening". Only the fast tests with low input length requirements.
+    FastScreening,
+}
+
+impl From<Profile> for sts_lib::profile::Profile {
+    fn from(value: Profile) -> Self {
+        match value {
+            Profile::NistDefault => sts_lib::profile::Profile::NistDefault,
+            Profile::BsiAis31 => sts_lib::profile::Profile::BsiAis31,
+            Profile::FastScreening => sts_lib::profile::Profile::FastScreening,
+        }
+    }
+}
+
+impl From<sts_lib::profile::Profile> for Profile {
+    fn from(value: sts_lib::profile::Profile) -> Self {
+        match value {
+            sts_lib::profile::Profile::NistDefault => Profile::NistDefault,
+            sts_lib::profile::Profile::BsiAis31 => Profile::BsiAis31,
+            sts_lib::profile::Profile::FastScreening => Profile::FastScreening,
+        }
+    }
+}
+
+#[pymethods]
+impl Profile {
+    /// Returns the profile with the given name, e.g. "nist-default". Raises a ValueError if no
+    /// profile has this name.
+    #[staticmethod]
+    pub fn from_name(name: &str) -> PyResult<Self> {
+        name.parse::<sts_lib::profile::Profile>()
+            .map(Self::from)
+            .map_err(|_| PyValueError::new_err(format!("\"{name}\" is not a valid profile name")))
+    }
+
+    /// Returns the name of the profile.
+    pub fn name(&self) -> &'static str {
+        sts_lib::profile::Profile::from(*self).name()
+    }
+
+    /// Returns the tests that are run in this profile.
+    pub fn tests(&self) -> Vec<Test> {
+        sts_lib::profile::Profile::from(*self)
+            .tests()
+            .iter()
+            .map(|&test| test.into())
+            .collect()
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!("Profile.{:?}", self)
+    }
+
+    pub fn __str__(&self) -> String {
+        self.name().to_owned()
+    }
+}
+
 /// Runs the tests.
 ///
 /// ## Arguments
 ///
 /// Main arguments:
 /// - data: `BitVec` - the test data to run the tests on.
-/// - tests: `[Test]` - the tests to run. If unspecified, runs all tests.
+/// - tests: `[Test]` - the tests to run. If unspecified, runs the tests of the profile, or
+///   `Test.default_set()` (all tests except for the Lempel-Ziv test) without a profile.
 ///
 /// Test arguments: optionally, arguments for tests that need them can be specified. If
 /// left unspecified, default values will be used.
 /// - frequency_block_arg: `FrequencyBlockTestArg`
+/// - longest_run_of_ones_arg: `LongestRunOfOnesTestArg`
+/// - binary_matrix_rank_arg: `BinaryMatrixRankTestArg`
+/// - spectral_dft_arg: `SpectralDftTestArg`
 /// - non_overlapping_template_args: `NonOverlappingTemplateTestArgs`
 /// - overlapping_template_args: `OverlappingTemplateTestArgs`
 /// - linear_complexity_arg: `LinearComplexityTestArg`
 /// - serial_arg: `SerialTestArg`
 /// - approximate_entropy_arg: `ApproximateEntropyTestArg`
+/// - autocorrelation_arg: `AutocorrelationTestArg`
+/// - random_excursions_arg: `RandomExcursionsTestArg`
+/// - random_excursions_variant_arg: `RandomExcursionsVariantTestArg`
+///
+/// The threshold to decide if a test passed can optionally be specified, it must be between 0 and 1
+/// (exclusive). It is stored in each returned `TestResult` and used by `TestResult.passed()`. If
+/// left unspecified, `TestResult.DEFAULT_THRESHOLD` is used.
+/// - threshold: `float`
+///
+/// To abort the tests, a `CancellationToken` can optionally be specified. Once it is cancelled, the
+/// running test aborts and all remaining tests are not run, each of them raises a TestError.
+/// - cancel_token: `CancellationToken`
+///
+/// A `Profile` can optionally be specified. Its tests are run if `tests` is unspecified, and its
+/// test arguments are used for all test arguments that are unspecified.
+/// - profile: `Profile`
+///
+/// A timeout in seconds can optionally be specified, it must be greater than 0. A test that runs
+/// longer is aborted and raises a TestTimeoutError (a subclass of TestError). If left unspecified,
+/// the tests run without a timeout.
+/// - timeout: `float`
+///
+/// If `length_warnings` is set to True, an `InputLengthWarning` is issued for each test that is run
+/// on data that is long enough for the test, but shorter than recommended by NIST (see
+/// `get_recommended_length_for_test()`). The warnings are issued before the tests are run and can
+/// be handled with the `warnings` module. Default: False.
+/// - length_warnings: `bool`
 ///
 /// ## Return value
 ///
-/// An iterator of tuples. Each tuple contains the `Test` that was run as the first element, and
-/// the second element is either of:
-/// * 1 TestResult
-/// * a list of TestResults
+/// A `RunResults`, mapping each `Test` that was run to a `TestRunResult` with the fields
+/// `results`, `p_values`, `passed`, `comments` and `duration`. `RunResults.to_pandas()` converts
+/// the results to a pandas DataFrame.
+///
+/// All tests are run before this function returns, the GIL is released in the meantime. To get
+/// each result as soon as its test completes, use `iter_tests()`.
 ///
 /// ## Errors
 ///
-/// RunnerError if a test is specified more than 1 time.
+/// RunnerError if a test is specified more than 1 time, if the data is too short for a test or
+/// its arguments, or if the threshold or the timeout is invalid. All problems with the tests are
+/// listed in the message at once, before any test is run.
 ///
-/// If an error occurs while evaluating a test, TestError is thrown.
+/// If an error occurs while evaluating a test, TestError is thrown. If a test takes longer than
+/// the timeout, TestTimeoutError is thrown.
 #[allow(clippy::too_many_arguments)]
 #[pyfunction]
-#[pyo3(signature = (data, tests=None, frequency_block_arg=None, non_overlapping_template_args=None, overlapping_template_args=None, linear_complexity_arg=None, serial_arg=None, approximate_entropy_arg=None))]
+#[pyo3(signature = (data, tests=None, frequency_block_arg=None, non_overlapping_template_args=None, overlapping_template_args=None, linear_complexity_arg=None, serial_arg=None, approximate_entropy_arg=None, threshold=None, cancel_token=None, profile=None, spectral_dft_arg=None, autocorrelation_arg=None, longest_run_of_ones_arg=None, binary_matrix_rank_arg=None, random_excursions_arg=None, random_excursions_variant_arg=None, timeout=None, length_warnings=false))]
 pub fn run_tests(
+    py: Python<'_>,
     data: &BitVec,
     tests: Option<Vec<Test>>,
     frequency_block_arg: Option<FrequencyBlockTestArg>,
@@ -82,33 +428,702 @@ pub fn run_tests(
     linear_complexity_arg: Option<LinearComplexityTestArg>,
     serial_arg: Option<SerialTestArg>,
     approximate_entropy_arg: Option<ApproximateEntropyTestArg>,
-) -> PyResult<TestResultIterator> {
-    // assemble args (or use defaults if not there)
-    let args = TestArgs {
-        frequency_block: frequency_block_arg.unwrap_or_default().0,
-        non_overlapping_template: non_overlapping_template_args.unwrap_or_default().0,
-        overlapping_template: overlapping_template_args.unwrap_or_default().0,
-        linear_complexity: linear_complexity_arg.unwrap_or_default().0,
-        serial: serial_arg.unwrap_or_default().0,
-        approximate_entropy: approximate_entropy_arg.unwrap_or_default().0,
-    };
+    threshold: Option<f64>,
+    cancel_token: Option<&CancellationToken>,
+    profile: Option<Profile>,
+    spectral_dft_arg: Option<SpectralDftTestArg>,
+    autocorrelation_arg: Option<AutocorrelationTestArg>,
+    longest_run_of_ones_arg: Option<LongestRunOfOnesTestArg>,
+    binary_matrix_rank_arg: Option<BinaryMatrixRankTestArg>,
+    random_excursions_arg: Option<RandomExcursionsTestArg>,
+    random_excursions_variant_arg: Option<RandomExcursionsVariantTestArg>,
+    timeout: Option<f64>,
+    length_warnings: bool,
+) -> PyResult<RunResults> {
+    let TestResultIterator {
+        mut iter,
+        threshold,
+    } = create_iterator(
+        py,
+        data,
+        tests,
+        frequency_block_arg,
+        non_overlapping_template_args,
+        overlapping_template_args,
+        linear_complexity_arg,
+        serial_arg,
+        approximate_entropy_arg,
+        threshold,
+        cancel_token,
+        profile,
+        spectral_dft_arg,
+        autocorrelation_arg,
+        longest_run_of_ones_arg,
+        binary_matrix_rank_arg,
+        random_excursions_arg,
+        random_excursions_variant_arg,
+        timeout,
+        length_warnings,
+    )?;
 
-    match tests {
-        Some(tests) => {
-            let tests = tests.into_iter().map(|t| t.into());
+    // run all tests without holding the GIL, stopping at the first error
+    let results = py.allow_threads(|| run_all(&mut iter));
+    into_run_results(results, threshold)
+}
 
-            let iter = test_runner::run_tests(data.0.clone(), tests, args)
-                .map_err(|e| RunnerError::new_err(format!("Duplicate test: {}", e.0)))?;
-            Ok(TestResultIterator {
-                iter: Box::new(iter),
-            })
+/// Runs the tests like `run_tests()`, but on a thread of the default executor of the running event
+/// loop: returns an `asyncio.Future` that is completed with the `RunResults` once all tests ran,
+/// so the event loop keeps running in the meantime. The GIL is released while the tests run.
+///
+/// This function takes the same arguments as `run_tests()` and must be called while an event
+/// loop is running, e.g. from a coroutine:
+///
+/// ```python
+/// async def check(data):
+///     results = await nist_sts.run_tests_async(data, profile=nist_sts.Profile.FastScreening)
+///     return all(all(result.passed) for result in results.values())
+/// ```
+///
+/// If the awaiting task is cancelled, the running test is aborted and no further test is run: the
+/// `CancellationToken` given as `cancel_token` is cancelled, or an internal one if none is given.
+/// The thread limit of `thread_limit()` that is active when this function is called applies.
+///
+/// ## Errors
+///
+/// RunnerError is raised immediately for the same invalid arguments as in `run_tests()`, and
+/// RuntimeError if no event loop is running.
+///
+/// The future raises a TestError if an error occurs while evaluating a test, and a
+/// TestTimeoutError if a test takes longer than the timeout.
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(signature = (data, tests=None, frequency_block_arg=None, non_overlapping_template_args=None, overlapping_template_args=None, linear_complexity_arg=None, serial_arg=None, approximate_entropy_arg=None, threshold=None, cancel_token=None, profile=None, spectral_dft_arg=None, autocorrelation_arg=None, longest_run_of_ones_arg=None, binary_matrix_rank_arg=None, random_excursions_arg=None, random_excursions_variant_arg=None, timeout=None, length_warnings=false))]
+pub fn run_tests_async<'py>(
+    py: Python<'py>,
+    data: &BitVec,
+    tests: Option<Vec<Test>>,
+    frequency_block_arg: Option<FrequencyBlockTestArg>,
+    non_overlapping_template_args: Option<NonOverlappingTemplateTestArgs>,
+    overlapping_template_args: Option<OverlappingTemplateTestArgs>,
+    linear_complexity_arg: Option<LinearComplexityTestArg>,
+    serial_arg: Option<SerialTestArg>,
+    approximate_entropy_arg: Option<ApproximateEntropyTestArg>,
+    threshold: Option<f64>,
+    cancel_token: Option<&CancellationToken>,
+    profile: Option<Profile>,
+    spectral_dft_arg: Option<SpectralDftTestArg>,
+    autocorrelation_arg: Option<AutocorrelationTestArg>,
+    longest_run_of_ones_arg: Option<LongestRunOfOnesTestArg>,
+    binary_matrix_rank_arg: Option<BinaryMatrixRankTestArg>,
+    random_excursions_arg: Option<RandomExcursionsTestArg>,
+    random_excursions_variant_arg: Option<RandomExcursionsVariantTestArg>,
+    timeout: Option<f64>,
+    length_warnings: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    let event_loop = py.import("asyncio")?.call_method0("get_running_loop")?;
+    let token = cancel_token.cloned().unwrap_or_default();
+
+    let TestResultIterator { iter, threshold } = create_iterator(
+        py,
+        data,
+        tests,
+        frequency_block_arg,
+        non_overlapping_template_args,
+        overlapping_template_args,
+        linear_complexity_arg,
+        serial_arg,
+        approximate_entropy_arg,
+        threshold,
+        Some(&token),
+        profile,
+        spectral_dft_arg,
+        autocorrelation_arg,
+        longest_run_of_ones_arg,
+        binary_matrix_rank_arg,
+        random_excursions_arg,
+        random_excursions_variant_arg,
+        timeout,
+        length_warnings,
+    )?;
+
+    // the tests run on a thread of the default executor of the event loop, without the GIL
+    let iter = Mutex::new(Some(iter));
+    let run = PyCFunction::new_closure(py, None, None, move |args, _| {
+        let mut iter = iter
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+            .ok_or_else(|| StsError::new_err("The tests were already run"))?;
+        let results = args.py().allow_threads(|| run_all(&mut iter));
+        into_run_results(results, threshold)
+    })?;
+    let future = event_loop.call_method1("run_in_executor", (py.None(), run))?;
+
+    // abort the tests if the awaiting task is cancelled
+    let on_done = PyCFunction::new_closure(py, None, None, move |args, _| {
+        if args.get_item(0)?.call_method0("cancelled")?.is_truthy()? {
+            token.0.cancel();
         }
-        None => {
-            let iter = test_runner::run_all_tests(data.0.clone(), args)
-                .map_err(|e| RunnerError::new_err(format!("Duplicate test: {}", e.0)))?;
-            Ok(TestResultIterator {
-                iter: Box::new(iter),
+        PyResult::Ok(())
+    })?;
+    future.call_method1("add_done_callback", (on_done,))?;
+
+    Ok(future)
+}
+
+/// The tests that ran successfully, with their results and the time they took.
+type CompletedTests = Vec<(sts_lib::Test, Vec<sts_lib::TestResult>, Duration)>;
+
+/// Runs all tests of the iterator, stopping at the first error. Used by [run_tests] and
+/// [run_tests_async].
+fn run_all(
+    iter: &mut (dyn Iterator<Item = TestResultIteratorItem> + Send + Sync),
+) -> Result<CompletedTests, (sts_lib::Test, Error)> {
+    let mut results = Vec::new();
+    loop {
+        let start = Instant::now();
+        let Some((test, res)) = iter.next() else {
+            break;
+        };
+        let res = res.map_err(|e| (test, e))?;
+        results.push((test, res, start.elapsed()));
+    }
+    Ok(results)
+}
+
+/// Converts the results of [run_all] into the `RunResults`, or the error into the matching Python
+/// exception.
+fn into_run_results(
+    results: Result<CompletedTests, (sts_lib::Test, Error)>,
+    threshold: f64,
+) -> PyResult<RunResults> {
+    let results = results.map_err(|(test, e)| test_error(format!("Test {test}: {e}"), e))?;
+    Ok(RunResults(
+        results
+            .into_iter()
+            .map(|(test, results, duration)| TestRunResult {
+                test: test.into(),
+                results: results
+                    .into_iter()
+                    .map(|result| TestResult { result, threshold })
+                    .collect(),
+                duration,
             })
+            .collect(),
+    ))
+}
+
+/// Runs the tests one after another, yielding each result as soon as its test completes.
+///
+/// This function takes the same arguments as `run_tests()`. It returns an iterator of tuples, each
+/// containing the `Test` that was run as the first element, and either 1 TestResult or a list of
+/// TestResults as the second element.
+///
+/// It is meant for long runs: the GIL is released while each test runs, so the results can be
+/// monitored (e.g. to show the progress) while other Python threads continue to work. Such a
+/// thread can also abort the run with the `CancellationToken` given as `cancel_token`.
+///
+/// ```python
+/// for test, result in nist_sts.iter_tests(data, [nist_sts.Test.Frequency, nist_sts.Test.Runs]):
+///     print(f"Test {test}: {result}")
+/// ```
+///
+/// ## Errors
+///
+/// RunnerError if a test is specified more than 1 time, if the data is too short for a test or
+/// its arguments, or if the threshold or the timeout is invalid. All problems with the tests are
+/// listed in the message at once, before any test is run.
+///
+/// If an error occurs while evaluating a test, TestError is thrown. If a test takes longer than
+/// the timeout, TestTimeoutError is thrown.
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(signature = (data, tests=None, frequency_block_arg=None, non_overlapping_template_args=None, overlapping_template_args=None, linear_complexity_arg=None, serial_arg=None, approximate_entropy_arg=None, threshold=None, cancel_token=None, profile=None, spectral_dft_arg=None, autocorrelation_arg=None, longest_run_of_ones_arg=None, binary_matrix_rank_arg=None, random_excursions_arg=None, random_excursions_variant_arg=None, timeout=None, length_warnings=false))]
+pub fn iter_tests(
+    py: Python<'_>,
+    data: &BitVec,
+    tests: Option<Vec<Test>>,
+    frequency_block_arg: Option<FrequencyBlockTestArg>,
+    non_overlapping_template_args: Option<NonOverlappingTemplateTestArgs>,
+    overlapping_template_args: Option<OverlappingTemplateTestArgs>,
+    linear_complexity_arg: Option<LinearComplexityTestArg>,
+    serial_arg: Option<SerialTestArg>,
+    approximate_entropy_arg: Option<ApproximateEntropyTestArg>,
+    threshold: Option<f64>,
+    cancel_token: Option<&CancellationToken>,
+    profile: Option<Profile>,
+    spectral_dft_arg: Option<SpectralDftTestArg>,
+    autocorrelation_arg: Option<AutocorrelationTestArg>,
+    longest_run_of_ones_arg: Option<LongestRunOfOnesTestArg>,
+    binary_matrix_rank_arg: Option<BinaryMatrixRankTestArg>,
+    random_excursions_arg: Option<RandomExcursionsTestArg>,
+    random_excursions_variant_arg: Option<RandomExcursionsVariantTestArg>,
+    timeout: Option<f64>,
+    length_warnings: bool,
+) -> PyResult<TestResultIterator> {
+    create_iterator(
+        py,
+        data,
+        tests,
+        frequency_block_arg,
+        non_overlapping_template_args,
+        overlapping_template_args,
+        linear_complexity_arg,
+        serial_arg,
+        approximate_entropy_arg,
+        threshold,
+        cancel_token,
+        profile,
+        spectral_dft_arg,
+        autocorrelation_arg,
+        longest_run_of_ones_arg,
+        binary_matrix_rank_arg,
+        random_excursions_arg,
+        random_excursions_variant_arg,
+        timeout,
+        length_warnings,
+    )
+}
+
+/// Converts an error of a test into the matching Python exception.
+fn test_error(message: String, error: Error) -> PyErr {
+    match error {
+        Error::TimedOut(_) => TestTimeoutError::new_err(message),
+        _ => TestError::new_err(message),
+    }
+}
+
+/// Runs the tests on each of `partitions` disjoint sub-sequences of the data. All sub-sequences
+/// have the same length: the length of the data divided by `partitions`, the remaining bits at
+/// the end are not tested.
+///
+/// Apart from `partitions`, which must be at least 1, this function takes the same arguments as
+/// `run_tests()`. With `length_warnings`, the length of the sub-sequences is checked.
+///
+/// ## Return value
+///
+/// A dict mapping each `Test` that was run to a list with one entry per sub-sequence, in the
+/// order of the sub-sequences in the data. Each entry is the list of TestResults of the test on
+/// that sub-sequence. The dict can be passed to `analyse()` to check the proportion of passed
+/// sub-sequences.
+///
+/// All tests are run before this function returns, the GIL is released in the meantime.
+///
+/// ## Errors
+///
+/// RunnerError if a test is specified more than 1 time, if the sub-sequences are too short for a
+/// test or its arguments, or if the count of partitions, the threshold or the timeout is invalid.
+///
+/// If an error occurs while evaluating a test on any sub-sequence, TestError is thrown. If a test
+/// takes longer than the timeout, TestTimeoutError is thrown.
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(signature = (data, partitions, tests=None, frequency_block_arg=None, non_overlapping_template_args=None, overlapping_template_args=None, linear_complexity_arg=None, serial_arg=None, approximate_entropy_arg=None, threshold=None, cancel_token=None, profile=None, spectral_dft_arg=None, autocorrelation_arg=None, longest_run_of_ones_arg=None, binary_matrix_rank_arg=None, random_excursions_arg=None, random_excursions_variant_arg=None, timeout=None, length_warnings=false))]
+pub fn run_tests_partitioned(
+    py: Python<'_>,
+    data: &BitVec,
+    partitions: usize,
+    tests: Option<Vec<Test>>,
+    frequency_block_arg: Option<FrequencyBlockTestArg>,
+    non_overlapping_template_args: Option<NonOverlappingTemplateTestArgs>,
+    overlapping_template_args: Option<OverlappingTemplateTestArgs>,
+    linear_complexity_arg: Option<LinearComplexityTestArg>,
+    serial_arg: Option<SerialTestArg>,
+    approximate_entropy_arg: Option<ApproximateEntropyTestArg>,
+    threshold: Option<f64>,
+    cancel_token: Option<&CancellationToken>,
+    profile: Option<Profile>,
+    spectral_dft_arg: Option<SpectralDftTestArg>,
+    autocorrelation_arg: Option<AutocorrelationTestArg>,
+    longest_run_of_ones_arg: Option<LongestRunOfOnesTestArg>,
+    binary_matrix_rank_arg: Option<BinaryMatrixRankTestArg>,
+    random_excursions_arg: Option<RandomExcursionsTestArg>,
+    random_excursions_variant_arg: Option<RandomExcursionsVariantTestArg>,
+    timeout: Option<f64>,
+    length_warnings: bool,
+) -> PyResult<SubSequenceResults> {
+    let Some(partitions) = NonZero::new(partitions) else {
+        return Err(RunnerError::new_err(
+            "The count of partitions must be at least 1, is: 0",
+        ));
+    };
+
+    let (tests, args, threshold) = validate_args(
+        tests,
+        frequency_block_arg,
+        non_overlapping_template_args,
+        overlapping_template_args,
+        linear_complexity_arg,
+        serial_arg,
+        approximate_entropy_arg,
+        threshold,
+        profile,
+        spectral_dft_arg,
+        autocorrelation_arg,
+        longest_run_of_ones_arg,
+        binary_matrix_rank_arg,
+        random_excursions_arg,
+        random_excursions_variant_arg,
+        timeout,
+    )?;
+    let tests = tests.collect::<Vec<_>>();
+
+    if length_warnings {
+        warn_short_input(py, data.0.len_bit() / partitions, &tests)?;
+    }
+
+    run_sub_sequences(py, threshold, "partition", || {
+        let iter: Box<dyn Iterator<Item = (sts_lib::Test, PartitionedResults)>> = match cancel_token
+        {
+            Some(token) => Box::new(test_runner::run_tests_partitioned_with_cancel(
+                &data.0,
+                tests.into_iter(),
+                args,
+                partitions,
+                &token.0,
+            )?),
+            None => Box::new(test_runner::run_tests_partitioned(
+                &data.0,
+                tests.into_iter(),
+                args,
+                partitions,
+            )?),
+        };
+        Ok(iter)
+    })
+}
+
+/// Runs the tests on each of `count` windows of `window_length` bits, placed at random offsets
+/// in the data. The offsets are generated from `seed`, so that running with the same seed, window
+/// length and count on data of the same length tests the same windows. The windows don't overlap.
+/// This allows testing extremely long captures without only testing their start.
+///
+/// Apart from `window_length`, `count` and `seed`, this function takes the same arguments as
+/// `run_tests()`. With `length_warnings`, the window length is checked.
+///
+/// ## Return value
+///
+/// A tuple of the window offsets in bits, sorted ascending, and a dict mapping each `Test` that
+/// was run to a list with one entry per window, in the order of the offsets. Each entry is the
+/// list of TestResults of the test on that window. The dict can be passed to `analyse()`.
+///
+/// All tests are run before this function returns, the GIL is released in the meantime.
+///
+/// ## Errors
+///
+/// RunnerError if a test is specified more than 1 time, if the windows don't fit into the data or
+/// are too short for a test or its arguments, or if the threshold or the timeout is invalid.
+///
+/// If an error occurs while evaluating a test on any window, TestError is thrown. If a test
+/// takes longer than the timeout, TestTimeoutError is thrown.
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(signature = (data, window_length, count, seed, tests=None, frequency_block_arg=None, non_overlapping_template_args=None, overlapping_template_args=None, linear_complexity_arg=None, serial_arg=None, approximate_entropy_arg=None, threshold=None, cancel_token=None, profile=None, spectral_dft_arg=None, autocorrelation_arg=None, longest_run_of_ones_arg=None, binary_matrix_rank_arg=None, random_excursions_arg=None, random_excursions_variant_arg=None, timeout=None, length_warnings=false))]
+pub fn run_tests_subsampled(
+    py: Python<'_>,
+    data: &BitVec,
+    window_length: usize,
+    count: usize,
+    seed: u64,
+    tests: Option<Vec<Test>>,
+    frequency_block_arg: Option<FrequencyBlockTestArg>,
+    non_overlapping_template_args: Option<NonOverlappingTemplateTestArgs>,
+    overlapping_template_args: Option<OverlappingTemplateTestArgs>,
+    linear_complexity_arg: Option<LinearComplexityTestArg>,
+    serial_arg: Option<SerialTestArg>,
+    approximate_entropy_arg: Option<ApproximateEntropyTestArg>,
+    threshold: Option<f64>,
+    cancel_token: Option<&CancellationToken>,
+    profile: Option<Profile>,
+    spectral_dft_arg: Option<SpectralDftTestArg>,
+    autocorrelation_arg: Option<AutocorrelationTestArg>,
+    longest_run_of_ones_arg: Option<LongestRunOfOnesTestArg>,
+    binary_matrix_rank_arg: Option<BinaryMatrixRankTestArg>,
+    random_excursions_arg: Option<RandomExcursionsTestArg>,
+    random_excursions_variant_arg: Option<RandomExcursionsVariantTestArg>,
+    timeout: Option<f64>,
+    length_warnings: bool,
+) -> PyResult<(Vec<usize>, SubSequenceResults)> {
+    let (Some(window_length), Some(count)) = (NonZero::new(window_length), NonZero::new(count))
+    else {
+        return Err(RunnerError::new_err(
+            "The window length and the count of windows must be at least 1",
+        ));
+    };
+    let subsample = Subsample::new(data.0.len_bit(), window_length, count, seed)
+        .map_err(|e| RunnerError::new_err(e.to_string()))?;
+
+    let (tests, args, threshold) = validate_args(
+        tests,
+        frequency_block_arg,
+        non_overlapping_template_args,
+        overlapping_template_args,
+        linear_complexity_arg,
+        serial_arg,
+        approximate_entropy_arg,
+        threshold,
+        profile,
+        spectral_dft_arg,
+        autocorrelation_arg,
+        longest_run_of_ones_arg,
+        binary_matrix_rank_arg,
+        random_excursions_arg,
+        random_excursions_variant_arg,
+        timeout,
+    )?;
+    let tests = tests.collect::<Vec<_>>();
+
+    if length_warnings {
+        warn_short_input(py, window_length.get(), &tests)?;
+    }
+
+    let results = run_sub_sequences(py, threshold, "window", || {
+        let iter: Box<dyn Iterator<Item = (sts_lib::Test, PartitionedResults)>> = match cancel_token
+        {
+            Some(token) => Box::new(test_runner::run_tests_subsampled_with_cancel(
+                &data.0,
+                tests.into_iter(),
+                args,
+                &subsample,
+                &token.0,
+            )?),
+            None => Box::new(test_runner::run_tests_subsampled(
+                &data.0,
+                tests.into_iter(),
+                args,
+                &subsample,
+            )?),
+        };
+        Ok(iter)
+    })?;
+
+    Ok((subsample.offsets().to_vec(), results))
+}
+
+/// Runs the tests created by `create_iter` without holding the GIL, stopping at the first error,
+/// and converts the results. `part_name` names the sub-sequences in error messages. Used by
+/// [run_tests_partitioned] and [run_tests_subsampled].
+fn run_sub_sequences<'a>(
+    py: Python<'_>,
+    threshold: f64,
+    part_name: &str,
+    create_iter: impl FnOnce() -> Result<
+            Box<dyn Iterator<Item = (sts_lib::Test, PartitionedResults)> + 'a>,
+            test_runner::RunnerError,
+        > + Send,
+) -> PyResult<SubSequenceResults> {
+    let results = py.allow_threads(|| {
+        install(|| {
+            let mut results = HashMap::new();
+            for (test, partition_results) in create_iter()? {
+                let partition_results = partition_results
+                    .into_iter()
+                    .enumerate()
+                    .map(|(idx, res)| res.map_err(|e| PartitionError::Test(test, idx, e)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                results.insert(test, partition_results);
+            }
+            Ok::<_, PartitionError>(results)
+        })
+    });
+
+    let results = results.map_err(|e| match e {
+        PartitionError::Runner(e) => RunnerError::new_err(e.to_string()),
+        PartitionError::Test(test, idx, e) => {
+            test_error(format!("Test {test}, {part_name} {idx}: {e}"), e)
         }
+    })?;
+
+    Ok(results
+        .into_iter()
+        .map(|(test, partition_results)| {
+            let partition_results = partition_results
+                .into_iter()
+                .map(|results| {
+                    results
+                        .into_iter()
+                        .map(|result| TestResult { result, threshold })
+                        .collect()
+                })
+                .collect();
+
+            (test.into(), partition_results)
+        })
+        .collect())
+}
+
+/// Issues an `InputLengthWarning` for each test that is run on an input shorter than recommended
+/// by NIST, see [test_runner::length_warnings]. Raises the warning instead, if warnings are
+/// configured to be errors.
+fn warn_short_input(py: Python<'_>, len_bit: usize, tests: &[sts_lib::Test]) -> PyResult<()> {
+    let category = py.get_type::<InputLengthWarning>();
+    for warning in test_runner::length_warnings(len_bit, tests.iter().copied()) {
+        let message = CString::new(warning.to_string())?;
+        PyErr::warn(py, &category, &message, 1)?;
+    }
+    Ok(())
+}
+
+/// The errors of [run_sub_sequences], converted into Python exceptions while holding the GIL.
+enum PartitionError {
+    /// The tests were rejected before running them.
+    Runner(test_runner::RunnerError),
+    /// A test failed on the sub-sequence (partition or window) with the given index.
+    Test(sts_lib::Test, usize, Error),
+}
+
+impl From<test_runner::RunnerError> for PartitionError {
+    fn from(value: test_runner::RunnerError) -> Self {
+        Self::Runner(value)
     }
 }
+
+/// Validates the arguments and creates the iterator, used by [run_tests], [run_tests_async] and
+/// [iter_tests].
+#[allow(clippy::too_many_arguments)]
+fn create_iterator(
+    py: Python<'_>,
+    data: &BitVec,
+    tests: Option<Vec<Test>>,
+    frequency_block_arg: Option<FrequencyBlockTestArg>,
+    non_overlapping_template_args: Option<NonOverlappingTemplateTestArgs>,
+    overlapping_template_args: Option<OverlappingTemplateTestArgs>,
+    linear_complexity_arg: Option<LinearComplexityTestArg>,
+    serial_arg: Option<SerialTestArg>,
+    approximate_entropy_arg: Option<ApproximateEntropyTestArg>,
+    threshold: Option<f64>,
+    cancel_token: Option<&CancellationToken>,
+    profile: Option<Profile>,
+    spectral_dft_arg: Option<SpectralDftTestArg>,
+    autocorrelation_arg: Option<AutocorrelationTestArg>,
+    longest_run_of_ones_arg: Option<LongestRunOfOnesTestArg>,
+    binary_matrix_rank_arg: Option<BinaryMatrixRankTestArg>,
+    random_excursions_arg: Option<RandomExcursionsTestArg>,
+    random_excursions_variant_arg: Option<RandomExcursionsVariantTestArg>,
+    timeout: Option<f64>,
+    length_warnings: bool,
+) -> PyResult<TestResultIterator> {
+    let (tests, args, threshold) = validate_args(
+        tests,
+        frequency_block_arg,
+        non_overlapping_template_args,
+        overlapping_template_args,
+        linear_complexity_arg,
+        serial_arg,
+        approximate_entropy_arg,
+        threshold,
+        profile,
+        spectral_dft_arg,
+        autocorrelation_arg,
+        longest_run_of_ones_arg,
+        binary_matrix_rank_arg,
+        random_excursions_arg,
+        random_excursions_variant_arg,
+        timeout,
+    )?;
+    let tests = tests.collect::<Vec<_>>();
+
+    if length_warnings {
+        warn_short_input(py, data.0.len_bit(), &tests)?;
+    }
+
+    // the thread limit active now applies to all tests of the iterator
+    let data = data.0.clone();
+    let tests = tests.into_iter();
+    let iter: Box<dyn Iterator<Item = TestResultIteratorItem> + Send + Sync> =
+        match (current_context(), cancel_token) {
+            (Some(context), Some(token)) => Box::new(
+                context
+                    .run_tests_with_cancel(data, tests, args, &token.0)
+                    .map_err(|e| RunnerError::new_err(e.to_string()))?,
+            ),
+            (Some(context), None) => Box::new(
+                context
+                    .run_tests(data, tests, args)
+                    .map_err(|e| RunnerError::new_err(e.to_string()))?,
+            ),
+            (None, Some(token)) => Box::new(
+                test_runner::run_tests_with_cancel(data, tests, args, &token.0)
+                    .map_err(|e| RunnerError::new_err(e.to_string()))?,
+            ),
+            (None, None) => Box::new(
+                test_runner::run_tests(data, tests, args)
+                    .map_err(|e| RunnerError::new_err(e.to_string()))?,
+            ),
+        };
+
+    Ok(TestResultIterator { iter, threshold })
+}
+
+/// Validates the arguments, returns the tests to run, the test arguments and the threshold. Used
+/// by [create_iterator] and [run_tests_partitioned].
+#[allow(clippy::too_many_arguments)]
+fn validate_args(
+    tests: Option<Vec<Test>>,
+    frequency_block_arg: Option<FrequencyBlockTestArg>,
+    non_overlapping_template_args: Option<NonOverlappingTemplateTestArgs>,
+    overlapping_template_args: Option<OverlappingTemplateTestArgs>,
+    linear_complexity_arg: Option<LinearComplexityTestArg>,
+    serial_arg: Option<SerialTestArg>,
+    approximate_entropy_arg: Option<ApproximateEntropyTestArg>,
+    threshold: Option<f64>,
+    profile: Option<Profile>,
+    spectral_dft_arg: Option<SpectralDftTestArg>,
+    autocorrelation_arg: Option<AutocorrelationTestArg>,
+    longest_run_of_ones_arg: Option<LongestRunOfOnesTestArg>,
+    binary_matrix_rank_arg: Option<BinaryMatrixRankTestArg>,
+    random_excursions_arg: Option<RandomExcursionsTestArg>,
+    random_excursions_variant_arg: Option<RandomExcursionsVariantTestArg>,
+    timeout: Option<f64>,
+) -> PyResult<(Box<dyn Iterator<Item = sts_lib::Test>>, TestArgs, f64)> {
+    let threshold = threshold.unwrap_or(sts_lib::DEFAULT_THRESHOLD);
+    if !(threshold > 0.0 && threshold < 1.0) {
+        return Err(RunnerError::new_err(format!(
+            "The threshold must be between 0 and 1 (exclusive), is: {threshold}"
+        )));
+    }
+
+    let timeout = match timeout {
+        None => None,
+        Some(timeout) if timeout > 0.0 => {
+            Some(Duration::try_from_secs_f64(timeout).map_err(|_| {
+                RunnerError::new_err(format!("The timeout is too large, is: {timeout}"))
+            })?)
+        }
+        Some(timeout) => {
+            return Err(RunnerError::new_err(format!(
+                "The timeout must be greater than 0, is: {timeout}"
+            )))
+        }
+    };
+
+    // assemble args (or use the defaults of the profile or the library if not there)
+    let profile = profile.map(sts_lib::profile::Profile::from);
+    let base = profile.map(|p| p.test_args()).unwrap_or_default();
+    let args = TestArgs {
+        frequency_block: frequency_block_arg.map_or(base.frequency_block, |arg| arg.0),
+        longest_run_of_ones: longest_run_of_ones_arg.map_or(base.longest_run_of_ones, |arg| arg.0),
+        binary_matrix_rank: binary_matrix_rank_arg.map_or(base.binary_matrix_rank, |arg| arg.0),
+        spectral_dft: spectral_dft_arg.map_or(base.spectral_dft, |arg| arg.0),
+        non_overlapping_template: non_overlapping_template_args
+            .map_or(base.non_overlapping_template, |arg| arg.0),
+        overlapping_template: overlapping_template_args
+            .map_or(base.overlapping_template, |arg| arg.0),
+        linear_complexity: linear_complexity_arg.map_or(base.linear_complexity, |arg| arg.0),
+        serial: serial_arg.map_or(base.serial, |arg| arg.0),
+        approximate_entropy: approximate_entropy_arg.map_or(base.approximate_entropy, |arg| arg.0),
+        random_excursions: random_excursions_arg.map_or(base.random_excursions, |arg| arg.0),
+        random_excursions_variant: random_excursions_variant_arg
+            .map_or(base.random_excursions_variant, |arg| arg.0),
+        autocorrelation: autocorrelation_arg.map_or(base.autocorrelation, |arg| arg.0),
+        timeout,
+    };
+
+    let tests: Box<dyn Iterator<Item = sts_lib::Test>> = match (tests, profile) {
+        (Some(tests), _) => Box::new(tests.into_iter().map(|t| t.into())),
+        (None, Some(profile)) => Box::new(profile.tests().iter().copied()),
+        (None, None) => Box::new(sts_lib::Test::default_set().iter().copied()),
+    };
+
+    Ok((tests, args, threshold))
+}
@@ -1,6 +1,7 @@
 use crate::bitvec::BitVec;
 use crate::nist_sts::TestResult;
 use crate::test_args::*;
+use crate::thread_limit::install;
 use crate::TestError;
 use pyo3::prelude::*;
 use pyo3::types::PyTuple;
@@ -20,8 +21,8 @@ use sts_lib::tests::*;
 /// Exceptions of type `TestError` may happen.
 #[pyfunction]
 pub fn frequency_test(data: &BitVec) -> PyResult<TestResult> {
-    frequency::frequency_test(&data.0)
-        .map(TestResult)
+    install(|| frequency::frequency_test(&data.0))
+        .map(TestResult::from)
         .map_err(|e| TestError::new_err(e.to_string()))
 }
 
@@ -46,8 +47,8 @@ pub fn frequency_block_test(
 ) -> PyResult<TestResult> {
     let arg = test_arg.map(|a| a.0).unwrap_or_default();
 
-    frequency_block::frequency_block_test(&data.0, arg)
-        .map(TestResult)
+    install(|| frequency_block::frequency_block_test(&data.0, arg))
+        .map(TestResult::from)
         .map_err(|e| TestError::new_err(e.to_string()))
 }
 
@@ -65,8 +66,8 @@ pub fn frequency_block_test(
 /// Exceptions of type `TestError` may happen
 #[pyfunction]
 pub fn runs_test(data: &BitVec) -> PyResult<TestResult> {
-    runs::runs_test(&data.0)
-        .map(TestResult)
+    install(|| runs::runs_test(&data.0))
+        .map(TestResult::from)
         .map_err(|e| TestError::new_err(e.to_string()))
 }
 
@@ -81,33 +82,48 @@ pub fn runs_test(data: &BitVec) -> PyResult<TestResult> {
 /// ## Arguments
 ///
 /// - data: `BitVec` to test. Has to be at least 128 bits in length.
+/// - test_arg: `LongestRunOfOnesTestArg` - the configuration, may be left unspecified.
 ///
 /// ## Exceptions
 ///
 /// Exceptions of type `TestError` may happen
 #[pyfunction]
-pub fn longest_runs_of_ones_test(data: &BitVec) -> PyResult<TestResult> {
-    longest_run_of_ones::longest_run_of_ones_test(&data.0)
-        .map(TestResult)
+#[pyo3(signature = (data, test_arg=None))]
+pub fn longest_runs_of_ones_test(
+    data: &BitVec,
+    test_arg: Option<LongestRunOfOnesTestArg>,
+) -> PyResult<TestResult> {
+    let arg = test_arg.map(|a| a.0).unwrap_or_default();
+
+    install(|| longest_run_of_ones::longest_run_of_ones_test(&data.0, arg))
+        .map(TestResult::from)
         .map_err(|e| TestError::new_err(e.to_string()))
 }
 
 /// Binary Matrix Rank Test -  No. 5
 ///
 /// This test checks for linear dependence among fixed length substrings of the sequence.
-/// These substrings are interpreted as matrices of size 32x32.
+/// These substrings are interpreted as matrices of size 32x32 by default.
 ///
 /// ## Arguments
 ///
-/// - data: `BitVec` to test. Has to consist of at least 38 912 bits = 4864 bytes
+/// - data: `BitVec` to test. Has to consist of at least 38 912 bits = 4864 bytes for 32x32
+///   matrices.
+/// - test_arg: `BinaryMatrixRankTestArg` - the matrix dimensions, may be left unspecified.
 ///
 /// ## Exceptions
 ///
 /// Exceptions of type `TestError` may happen
 #[pyfunction]
-pub fn binary_matrix_rank_test(data: &BitVec) -> PyResult<TestResult> {
-    binary_matrix_rank::binary_matrix_rank_test(&data.0)
-        .map(TestResult)
+#[pyo3(signature = (data, test_arg=None))]
+pub fn binary_matrix_rank_test(
+    data: &BitVec,
+    test_arg: Option<BinaryMatrixRankTestArg>,
+) -> PyResult<TestResult> {
+    let arg = test_arg.map(|a| a.0).unwrap_or_default();
+
+    install(|| binary_matrix_rank::binary_matrix_rank_test(&data.0, arg))
+        .map(TestResult::from)
         .map_err(|e| TestError::new_err(e.to_string()))
 }
 
@@ -119,14 +135,21 @@ pub fn binary_matrix_rank_test(data: &BitVec) -> PyResult<TestResult> {
 /// ## Arguments
 ///
 /// - data: `BitVec` to test. It is recommended (but not required) for the input to be of at least 1000 bits.
+/// - test_arg: `SpectralDftTestArg`. May be left unspecified.
 ///
 /// ## Exceptions
 ///
 /// Exceptions of type `TestError` may happen
 #[pyfunction]
-pub fn spectral_dft_test(data: &BitVec) -> PyResult<TestResult> {
-    spectral_dft::spectral_dft_test(&data.0)
-        .map(TestResult)
+#[pyo3(signature = (data, test_arg=None))]
+pub fn spectral_dft_test(
+    data: &BitVec,
+    test_arg: Option<SpectralDftTestArg>,
+) -> PyResult<TestResult> {
+    let arg = test_arg.map(|a| a.0).unwrap_or_default();
+
+    install(|| spectral_dft::spectral_dft_test(&data.0, arg))
+        .map(TestResult::from)
         .map_err(|e| TestError::new_err(e.to_string()))
 }
 
@@ -151,9 +174,11 @@ pub fn non_overlapping_template_matching_test(
 ) -> PyResult<Vec<TestResult>> {
     let arg = test_arg.map(|a| a.0).unwrap_or_default();
 
-    template_matching::non_overlapping::non_overlapping_template_matching_test(&data.0, arg)
-        .map(|results| results.into_iter().map(TestResult).collect())
-        .map_err(|e| TestError::new_err(e.to_string()))
+    install(|| {
+        template_matching::non_overlapping::non_overlapping_template_matching_test(&data.0, arg)
+    })
+    .map(|results| results.into_iter().map(TestResult::from).collect())
+    .map_err(|e| TestError::new_err(e.to_string()))
 }
 
 /// Overlapping Template Matching test - No. 8
@@ -176,8 +201,8 @@ pub fn non_overlapping_template_matching_test(
 ///
 /// # About performance
 ///
-/// This test is quite slow in debug mode when using the more precise pi values (non-NIST behaviour),
-/// taking several seconds - it runs good when using release mode.
+/// The more precise pi values (non-NIST behaviour) take time quadratic in the block length, e.g.
+/// 3 ms for a block length of 1032 - except for the default arguments, whose values are built in.
 /// For better performance, values that are calculated once are cached.
 ///
 /// ## Arguments
@@ -196,8 +221,8 @@ pub fn overlapping_template_matching_test(
 ) -> PyResult<TestResult> {
     let arg = test_arg.map(|a| a.0).unwrap_or_default();
 
-    template_matching::overlapping::overlapping_template_matching_test(&data.0, arg)
-        .map(TestResult)
+    install(|| template_matching::overlapping::overlapping_template_matching_test(&data.0, arg))
+        .map(TestResult::from)
         .map_err(|e| TestError::new_err(e.to_string()))
 }
 
@@ -216,8 +241,8 @@ pub fn overlapping_template_matching_test(
 /// Exceptions of type `TestError` may happen
 #[pyfunction]
 pub fn maurers_universal_statistical_test(data: &BitVec) -> PyResult<TestResult> {
-    maurers_universal_statistical::maurers_universal_statistical_test(&data.0)
-        .map(TestResult)
+    install(|| maurers_universal_statistical::maurers_universal_statistical_test(&data.0))
+        .map(TestResult::from)
         .map_err(|e| TestError::new_err(e.to_string()))
 }
 
@@ -242,8 +267,8 @@ pub fn linear_complexity_test(
 ) -> PyResult<TestResult> {
     let arg = test_arg.map(|a| a.0).unwrap_or_default();
 
-    linear_complexity::linear_complexity_test(&data.0, arg)
-        .map(TestResult)
+    install(|| linear_complexity::linear_complexity_test(&data.0, arg))
+        .map(TestResult::from)
         .map_err(|e| TestError::new_err(e.to_string()))
 }
 
@@ -276,8 +301,8 @@ pub fn serial_test(
 ) -> PyResult<(TestResult, TestResult)> {
     let arg = test_arg.map(|a| a.0).unwrap_or_default();
 
-    serial::serial_test(&data.0, arg)
-        .map(|[res1, res2]| (TestResult(res1), TestResult(res2)))
+    install(|| serial::serial_test(&data.0, arg))
+        .map(|[res1, res2]| (TestResult::from(res1), TestResult::from(res2)))
         .map_err(|e| TestError::new_err(e.to_string()))
 }
 
@@ -306,8 +331,8 @@ pub fn approximate_entropy_test(
 ) -> PyResult<TestResult> {
     let arg = test_arg.map(|a| a.0).unwrap_or_default();
 
-    approximate_entropy::approximate_entropy_test(&data.0, arg)
-        .map(TestResult)
+    install(|| approximate_entropy::approximate_entropy_test(&data.0, arg))
+        .map(TestResult::from)
         .map_err(|e| TestError::new_err(e.to_string()))
 }
 
@@ -329,8 +354,8 @@ pub fn approximate_entropy_test(
 /// Exceptions of type `TestError` may happen.
 #[pyfunction]
 pub fn cumulative_sums_test(data: &BitVec) -> PyResult<(TestResult, TestResult)> {
-    cumulative_sums::cumulative_sums_test(&data.0)
-        .map(|[res1, res2]| (TestResult(res1), TestResult(res2)))
+    install(|| cumulative_sums::cumulative_sums_test(&data.0))
+        .map(|[res1, res2]| (TestResult::from(res1), TestResult::from(res2)))
         .map_err(|e| TestError::new_err(e.to_string()))
 }
 
@@ -344,21 +369,30 @@ pub fn cumulative_sums_test(data: &BitVec) -> PyResult<(TestResult, TestResult)>
 /// Since the test needs at least 500 cycles to occur, bit sequences with fewer cycles will not
 /// raise and error, but all values will be filled with "0.0".
 ///
-/// If the computation finishes successfully, 8 `TestResult`s are returned: one for each tested state,
-/// `x`. The results will contain a comment about the state they are calculated from (e.g. "x = 3"),
-/// the order is: `[-4, -3, -2, -1, +1, +2, +3, +4]`.
+/// The tested states *x* can be configured with `RandomExcursionsTestArg`, NIST uses the states
+/// `-4..=+4`. If the computation finishes successfully, a tuple with one `TestResult` for each
+/// tested state is returned. The results will contain a comment about the state they are
+/// calculated from (e.g. "x = +3"), the order is ascending: `[-4, -3, -2, -1, +1, +2, +3, +4]`
+/// with the defaults.
 ///
 /// ## Arguments
 ///
 /// - data: `BitVec` to test. Minimum length of 10^6 bits.
+/// - test_arg: `RandomExcursionsTestArg`. May be left unspecified.
 ///
 /// ## Exceptions
 ///
 /// Exceptions of type `TestError` may happen.
 #[pyfunction]
-pub fn random_excursions_test(data: &BitVec) -> PyResult<TestResultLen8> {
-    random_excursions::random_excursions_test(&data.0)
-        .map(|res| TestResultLen8 { data: res })
+#[pyo3(signature = (data, test_arg=None))]
+pub fn random_excursions_test(
+    data: &BitVec,
+    test_arg: Option<RandomExcursionsTestArg>,
+) -> PyResult<TestResultTuple> {
+    let arg = test_arg.map(|a| a.0).unwrap_or_default();
+
+    install(|| random_excursions::random_excursions_test(&data.0, arg))
+        .map(|res| TestResultTuple { data: res })
         .map_err(|e| TestError::new_err(e.to_string()))
 }
 
@@ -370,50 +404,91 @@ pub fn random_excursions_test(data: &BitVec) -> PyResult<TestResultLen8> {
 ///
 /// This test does not require a minimum number of cycles.
 ///
-/// If the computation finishes successfully, 18 `TestResult`s are returned: one for each tested state,
-/// `x`. The results will contain a comment about the state they are calculated from (e.g. "x = 3"),
-/// the order is: `[-9, -8, -7, -6, -5, -4, -3, -2, -1, +1, +2, +3, +4, +5, +6, +7, +8, +9]`.
+/// The tested states *x* can be configured with `RandomExcursionsVariantTestArg`, NIST uses the
+/// states `-9..=+9`. If the computation finishes successfully, a tuple with one `TestResult` for
+/// each tested state is returned. The results will contain a comment about the state they are
+/// calculated from (e.g. "x = +3"), the order is ascending: `[-9, -8, ..., -1, +1, ..., +8, +9]`
+/// with the defaults.
 ///
 /// ## Arguments
 ///
 /// - data: `BitVec` to test. Minimum length of 10^6 bits.
+/// - test_arg: `RandomExcursionsVariantTestArg`. May be left unspecified.
 ///
 /// ## Exceptions
 ///
 /// Exceptions of type `TestError` may happen.
 #[pyfunction]
-pub fn random_excursions_variant_test(data: &BitVec) -> PyResult<TestResultLen18> {
-    random_excursions_variant::random_excursions_variant_test(&data.0)
-        .map(|res| TestResultLen18 { data: res })
+#[pyo3(signature = (data, test_arg=None))]
+pub fn random_excursions_variant_test(
+    data: &BitVec,
+    test_arg: Option<RandomExcursionsVariantTestArg>,
+) -> PyResult<TestResultTuple> {
+    let arg = test_arg.map(|a| a.0).unwrap_or_default();
+
+    install(|| random_excursions_variant::random_excursions_variant_test(&data.0, arg))
+        .map(|res| TestResultTuple { data: res })
         .map_err(|e| TestError::new_err(e.to_string()))
 }
 
-/// Struct to convert a test result with length 8 into a tuple
-pub struct TestResultLen8 {
-    data: [sts_lib::TestResult; 8],
+/// Lempel-Ziv Compression Test - not part of the current revision of SP 800-22.
+///
+/// This test was part of the original SP 800-22 (2001) and was removed in revision 1, because the
+/// reference distribution of its test statistic is inaccurate. It is only provided for
+/// comparative studies.
+///
+/// This test counts the cumulatively distinct words of the Lempel-Ziv parsing of the sequence.
+/// A sequence that can be compressed significantly is considered non-random.
+///
+/// ## Arguments
+///
+/// - data: `BitVec` to test. Minimum length of 10^6 bits, only the first 10^6 bits are tested.
+///
+/// ## Exceptions
+///
+/// Exceptions of type `TestError` may happen.
+#[pyfunction]
+pub fn lempel_ziv_test(data: &BitVec) -> PyResult<TestResult> {
+    install(|| lempel_ziv::lempel_ziv_test(&data.0))
+        .map(TestResult::from)
+        .map_err(|e| TestError::new_err(e.to_string()))
 }
 
-impl<'py> IntoPyObject<'py> for TestResultLen8 {
-    type Target = PyTuple;
-    type Output = Bound<'py, Self::Target>;
-    type Error = PyErr;
+/// Autocorrelation Test - not part of SP 800-22, but test T5 of BSI AIS 31.
+///
+/// This test compares the sequence with a copy of itself, shifted by *d* bits: the count of bits
+/// that differ from the bit *d* positions later should be about half of the compared bits.
+///
+/// ## Arguments
+///
+/// - data: `BitVec` to test. Minimum recommended length of 10^4 bits.
+/// - test_arg: `AutocorrelationTestArg`. May be left unspecified.
+///
+/// If the combination of the given data and `test_arg` is invalid,
+/// an error is raised. For the exact constraints, see `AutocorrelationTestArg`.
+///
+/// ## Exceptions
+///
+/// Exceptions of type `TestError` may happen.
+#[pyfunction]
+#[pyo3(signature = (data, test_arg=None))]
+pub fn autocorrelation_test(
+    data: &BitVec,
+    test_arg: Option<AutocorrelationTestArg>,
+) -> PyResult<TestResult> {
+    let arg = test_arg.map(|a| a.0).unwrap_or_default();
 
-    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
-        let data: Vec<_> = self
-            .data
-            .into_iter()
-            .map(|res| TestResult(res).into_pyobject(py))
-            .collect::<Result<_, _>>()?;
-        PyTuple::new(py, data)
-    }
+    install(|| autocorrelation::autocorrelation_test(&data.0, arg))
+        .map(TestResult::from)
+        .map_err(|e| TestError::new_err(e.to_string()))
 }
 
-/// Struct to convert a test result array with length 18 into a tuple
-pub struct TestResultLen18 {
-    data: [sts_lib::TestResult; 18],
+/// Struct to convert a list of test results into a tuple
+pub struct TestResultTuple {
+    data: Vec<sts_lib::TestResult>,
 }
 
-impl<'py> IntoPyObject<'py> for TestResultLen18 {
+impl<'py> IntoPyObject<'py> for TestResultTuple {
     type Target = PyTuple;
     type Output = Bound<'py, Self::Target>;
     type Error = PyErr;
@@ -422,8 +497,8 @@ impl<'py> IntoPyObject<'py> for TestResultLen18 {
         let data: Vec<_> = self
             .data
             .into_iter()
-            .map(|res| TestResult(res).into_pyobject(py))
+            .map(|res| TestResult::from(res).into_pyobject(py))
             .collect::<Result<_, _>>()?;
         PyTuple::new(py, data)
     }
-}
\ No newline at end of file
+}
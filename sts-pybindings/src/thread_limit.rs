@@ -0,0 +1,98 @@
+//! The thread limit of the tests, see [thread_limit].
+
+use crate::StsError;
+use pyo3::prelude::*;
+use pyo3::types::PyAny;
+use std::cell::RefCell;
+use std::num::NonZero;
+use sts_lib::test_runner::StsContext;
+
+thread_local! {
+    /// The contexts of the active `with thread_limit(...)` blocks of this thread, the innermost
+    /// block last.
+    static ACTIVE_CONTEXTS: RefCell<Vec<StsContext>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Returns the context of the innermost active `with thread_limit(...)` block of the current
+/// thread, or `None` if the global thread pool is used.
+pub(crate) fn current_context() -> Option<StsContext> {
+    ACTIVE_CONTEXTS.with_borrow(|contexts| contexts.last().cloned())
+}
+
+/// Runs `f` in the thread pool of the innermost active `with thread_limit(...)` block, or directly
+/// (using the global thread pool) if there is none.
+pub(crate) fn install<R: Send>(f: impl FnOnce() -> R + Send) -> R {
+    match current_context() {
+        Some(context) => context.install(f),
+        None => f(),
+    }
+}
+
+/// A thread limit for the tests, to be used as a context manager, see `thread_limit()`.
+#[pyclass(frozen)]
+pub struct ThreadLimit(StsContext);
+
+#[pymethods]
+impl ThreadLimit {
+    /// The maximum count of threads used by the tests.
+    #[getter]
+    pub fn max_threads(&self) -> usize {
+        self.0.max_threads()
+    }
+
+    pub fn __enter__(this: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        ACTIVE_CONTEXTS.with_borrow_mut(|contexts| contexts.push(this.0.clone()));
+        this
+    }
+
+    #[pyo3(signature = (_exc_type=None, _exc_value=None, _traceback=None))]
+    pub fn __exit__(
+        &self,
+        _exc_type: Option<&Bound<'_, PyAny>>,
+        _exc_value: Option<&Bound<'_, PyAny>>,
+        _traceback: Option<&Bound<'_, PyAny>>,
+    ) -> bool {
+        ACTIVE_CONTEXTS.with_borrow_mut(|contexts| {
+            // blocks are left in the reverse order they were entered
+            if let Some(idx) = contexts.iter().rposition(|context| *context == self.0) {
+                contexts.remove(idx);
+            }
+        });
+        // exceptions are not suppressed
+        false
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!("ThreadLimit(max_threads = {})", self.0.max_threads())
+    }
+
+    pub fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
+
+/// Limits the count of threads used by the tests, for all tests that are started in the current
+/// thread within the `with` block:
+///
+/// ```python
+/// with nist_sts.thread_limit(4):
+///     results = nist_sts.run_tests(data)
+/// ```
+///
+/// Unlike `set_max_threads()`, this can be used at any time and as often as needed, e.g. in a
+/// notebook after the first tests already ran. Blocks can be nested, the innermost limit applies.
+/// Iterators returned by `iter_tests()` keep the limit that was active when they were created.
+///
+/// The thread pool for each count of threads is only created once and then reused.
+///
+/// ## Errors
+///
+/// StsError if `max_threads` is 0 or the thread pool cannot be created.
+#[pyfunction]
+pub fn thread_limit(max_threads: usize) -> PyResult<ThreadLimit> {
+    let max_threads =
+        NonZero::new(max_threads).ok_or(StsError::new_err("0 is not a valid thread count"))?;
+    StsContext::shared(max_threads)
+        .map(ThreadLimit)
+        .map_err(|e| StsError::new_err(e.to_string()))
+}
@@ -0,0 +1,128 @@
+#![doc = include_str!("../README.md")]
+
+use std::str::FromStr;
+use sts_lib::bitvec::BitVec;
+use sts_lib::profile::Profile;
+use sts_lib::{test_runner, Test, DEFAULT_THRESHOLD};
+use wasm_bindgen::prelude::*;
+
+/// A single result of a test, see [sts_lib::TestResult].
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct TestResult(sts_lib::TestResult);
+
+#[wasm_bindgen]
+impl TestResult {
+    /// The P-value of the result.
+    #[wasm_bindgen(getter, js_name = pValue)]
+    pub fn p_value(&self) -> f64 {
+        self.0.p_value()
+    }
+
+    /// If the result passed with the given threshold, default: 0.01.
+    pub fn passed(&self, threshold: Option<f64>) -> bool {
+        self.0.passed(threshold.unwrap_or(DEFAULT_THRESHOLD))
+    }
+
+    /// If the test was applicable to the input, see [sts_lib::ResultStatus].
+    #[wasm_bindgen(getter)]
+    pub fn applicable(&self) -> bool {
+        self.0.is_applicable()
+    }
+
+    /// Identifies the result for tests with multiple results, see [sts_lib::ResultLabel].
+    #[wasm_bindgen(getter)]
+    pub fn label(&self) -> Option<String> {
+        self.0.label().map(|label| label.to_string())
+    }
+
+    /// The comment of the result, if there is one.
+    #[wasm_bindgen(getter)]
+    pub fn comment(&self) -> Option<String> {
+        self.0.comment().map(str::to_owned)
+    }
+}
+
+/// The outcome of a single test: its results, or the error if the test failed to run.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct TestRun {
+    test: Test,
+    results: Result<Vec<TestResult>, String>,
+}
+
+#[wasm_bindgen]
+impl TestRun {
+    /// The name of the test, e.g. "Frequency".
+    #[wasm_bindgen(getter)]
+    pub fn test(&self) -> String {
+        self.test.to_string()
+    }
+
+    /// The results of the test, empty if the test returned an error.
+    #[wasm_bindgen(getter)]
+    pub fn results(&self) -> Vec<TestResult> {
+        self.results.clone().unwrap_or_default()
+    }
+
+    /// The error message, if the test returned an error.
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.results.as_ref().err().cloned()
+    }
+}
+
+/// Runs the tests of the given profile (default: "nist-default", see [Profile]) with the arguments
+/// of the profile on the given bytes, skipping the tests whose minimum input length is not
+/// satisfied. The bits of each byte are read starting with the most significant bit.
+///
+/// The tests run on the calling thread, see the README of sts-lib: call this function from a web
+/// worker to keep the page responsive.
+#[wasm_bindgen(js_name = runTests)]
+pub fn run_tests(data: &[u8], profile: Option<String>) -> Result<Vec<TestRun>, JsError> {
+    let profile = match profile {
+        Some(name) => Profile::from_str(&name)
+            .map_err(|_| JsError::new(&format!("Unknown profile \"{name}\"")))?,
+        None => Profile::NistDefault,
+    };
+
+    let data = BitVec::from(data);
+    let tests = profile
+        .tests()
+        .iter()
+        .copied()
+        .filter(|&test| sts_lib::get_min_length_for_test(test).get() <= data.len_bit());
+    let runs = test_runner::run_tests(&data, tests, profile.test_args())
+        .map_err(|e| JsError::new(&e.to_string()))?
+        .map(|(test, results)| TestRun {
+            test,
+            results: results
+                .map(|results| results.into_iter().map(TestResult).collect())
+                .map_err(|e| e.to_string()),
+        })
+        .collect();
+
+    Ok(runs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs the profile "fast-screening" natively, to check the conversion of the results.
+    #[test]
+    fn test_run_tests() {
+        let data = include_bytes!("../../sts-lib/test-files/e.1e6.bin");
+        let runs = run_tests(&data[..20_000], Some("fast-screening".to_owned())).unwrap();
+
+        assert_eq!(runs.len(), Profile::FastScreening.tests().len());
+        for run in runs {
+            assert_eq!(run.error(), None, "{}", run.test());
+            assert!(!run.results().is_empty());
+            for result in run.results() {
+                assert!(result.applicable());
+                assert!((0.0..=1.0).contains(&result.p_value()));
+            }
+        }
+    }
+}